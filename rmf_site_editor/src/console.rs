@@ -0,0 +1,197 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use bevy::prelude::*;
+use std::{collections::VecDeque, sync::Mutex};
+
+/// How many entries the console keeps before dropping the oldest ones
+pub const CONSOLE_CAPACITY: usize = 500;
+/// How many seconds a toast notification is shown for
+pub const TOAST_DURATION: f64 = 5.0;
+/// The most toast notifications that are shown at once
+pub const MAX_TOASTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Self::Info, Self::Warning, Self::Error];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Info => "Info",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConsoleEntry {
+    pub severity: Severity,
+    pub message: String,
+    /// The element that the message is about, if there is one
+    pub entity: Option<Entity>,
+    /// How many more times the same message was reported in a row
+    pub repeats: usize,
+    /// Whether the message is also shown as a toast notification
+    pub toast: bool,
+}
+
+/// Messages that were reported since the console last collected them. Many of
+/// the places that report problems are helper functions without access to
+/// the world, so they report into this instead of a resource.
+static REPORTED: Mutex<Vec<ConsoleEntry>> = Mutex::new(Vec::new());
+
+fn report(severity: Severity, entity: Option<Entity>, message: String, toast: bool) {
+    println!("{message}");
+    if let Ok(mut reported) = REPORTED.lock() {
+        reported.push(ConsoleEntry {
+            severity,
+            message,
+            entity,
+            repeats: 0,
+            toast,
+        });
+    }
+}
+
+/// Print a message and show it in the console of the editor
+pub fn report_info(entity: Option<Entity>, message: impl Into<String>) {
+    report(Severity::Info, entity, message.into(), false);
+}
+
+/// Print a warning and show it in the console of the editor
+pub fn report_warning(entity: Option<Entity>, message: impl Into<String>) {
+    report(Severity::Warning, entity, message.into(), false);
+}
+
+/// Print an error and show it in the console of the editor
+pub fn report_error(entity: Option<Entity>, message: impl Into<String>) {
+    report(Severity::Error, entity, message.into(), false);
+}
+
+/// Report the result of an operation that the user is waiting on. The message
+/// is shown in the console and as a toast notification.
+pub fn notify_info(entity: Option<Entity>, message: impl Into<String>) {
+    report(Severity::Info, entity, message.into(), true);
+}
+
+/// Report a warning as a toast notification as well as in the console
+pub fn notify_warning(entity: Option<Entity>, message: impl Into<String>) {
+    report(Severity::Warning, entity, message.into(), true);
+}
+
+/// Report an error as a toast notification as well as in the console
+pub fn notify_error(entity: Option<Entity>, message: impl Into<String>) {
+    report(Severity::Error, entity, message.into(), true);
+}
+
+/// The messages shown in the console of the editor
+#[derive(Resource, Default)]
+pub struct ConsoleLog {
+    pub entries: VecDeque<ConsoleEntry>,
+}
+
+impl ConsoleLog {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.severity == severity)
+            .count()
+    }
+}
+
+/// A message that is shown as a toast notification
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub entry: ConsoleEntry,
+    /// When the toast was first shown, in seconds since the app started
+    pub shown_at: f64,
+}
+
+/// The toast notifications that are currently shown
+#[derive(Resource, Default)]
+pub struct Toasts {
+    pub toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    /// Remove the toasts that have been shown for long enough
+    pub fn expire(&mut self, now: f64) {
+        self.toasts
+            .retain(|toast| now - toast.shown_at < TOAST_DURATION);
+    }
+}
+
+pub fn collect_console_entries(
+    mut log: ResMut<ConsoleLog>,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time>,
+) {
+    let reported = match REPORTED.lock() {
+        Ok(mut reported) => std::mem::take(&mut *reported),
+        Err(_) => return,
+    };
+    if reported.is_empty() {
+        return;
+    }
+
+    let now = time.elapsed_seconds_f64();
+    for entry in reported {
+        if entry.toast {
+            // Show a repeated message again instead of stacking copies of it
+            toasts
+                .toasts
+                .retain(|toast| toast.entry.message != entry.message);
+            toasts.toasts.push_back(Toast {
+                entry: entry.clone(),
+                shown_at: now,
+            });
+            while toasts.toasts.len() > MAX_TOASTS {
+                toasts.toasts.pop_front();
+            }
+        }
+
+        // Systems that hit a problem every frame would otherwise flood the
+        // console with the same message
+        if let Some(last) = log.entries.back_mut() {
+            if last.message == entry.message && last.entity == entry.entity {
+                last.repeats += 1;
+                continue;
+            }
+        }
+        log.entries.push_back(entry);
+    }
+    while log.entries.len() > CONSOLE_CAPACITY {
+        log.entries.pop_front();
+    }
+}
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConsoleLog>()
+            .init_resource::<Toasts>()
+            .add_system_to_stage(CoreStage::First, collect_console_entries);
+    }
+}
@@ -0,0 +1,153 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{notify_error, notify_warning},
+    interaction::Cursor,
+    site::{CurrentLevel, SiteState},
+    widgets::view_asset_library::MODEL_FILE_EXTENSIONS,
+    AppState, LoadWorkspace,
+};
+use bevy::prelude::*;
+use rmf_site_format::{
+    AssetSource, Drawing, DrawingMarker, Model, NameInSite, PixelsPerMeter, Pose, SiteFileFormat,
+};
+use std::path::Path;
+
+/// File extensions of images that can be dropped in to become drawings
+pub const DRAWING_FILE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Handles files that are dragged from the operating system and dropped onto
+/// the window. Site and workcell files are opened, while model files and
+/// images are added to the current level of the site at the cursor location.
+pub struct DragAndDropPlugin;
+
+impl Plugin for DragAndDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(handle_dropped_files);
+    }
+}
+
+/// What should be done with a file that was dropped onto the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DroppedFileKind {
+    Workspace,
+    Model,
+    Drawing,
+}
+
+impl DroppedFileKind {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let filename = path.file_name()?.to_str()?.to_lowercase();
+        if filename.ends_with(".building.yaml")
+            || SiteFileFormat::from_path(path).is_some()
+            || filename.ends_with("workcell.json")
+            || filename.ends_with(".urdf")
+            || filename.ends_with(".xacro")
+        {
+            return Some(Self::Workspace);
+        }
+
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        if extension == "sdf" || MODEL_FILE_EXTENSIONS.contains(&extension.as_str()) {
+            Some(Self::Model)
+        } else if DRAWING_FILE_EXTENSIONS.contains(&extension.as_str()) {
+            Some(Self::Drawing)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn handle_dropped_files(
+    mut commands: Commands,
+    mut dropped_files: EventReader<FileDragAndDrop>,
+    mut load_workspace: EventWriter<LoadWorkspace>,
+    app_state: Res<State<AppState>>,
+    site_state: Res<State<SiteState>>,
+    current_level: Res<CurrentLevel>,
+    cursor: Option<Res<Cursor>>,
+    transforms: Query<&GlobalTransform>,
+) {
+    for event in dropped_files.iter() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+
+        let Some(kind) = DroppedFileKind::from_path(path_buf) else {
+            notify_error(
+                None,
+                format!("Unable to open dropped file {path_buf:?}: unsupported file type"),
+            );
+            continue;
+        };
+
+        if kind == DroppedFileKind::Workspace {
+            load_workspace.send(LoadWorkspace::Path(path_buf.clone()));
+            continue;
+        }
+
+        let in_site = *app_state.current() == AppState::SiteEditor
+            && *site_state.current() == SiteState::Display;
+        let Some(level) = current_level.0.filter(|_| in_site) else {
+            notify_warning(
+                None,
+                format!("Open a site before dropping {path_buf:?} into it"),
+            );
+            continue;
+        };
+
+        // Place the new element where the cursor was last seen, relative to
+        // the level that it will be added to.
+        let mut pose = Pose::default();
+        if let (Some(cursor), Ok(level_tf)) = (&cursor, transforms.get(level)) {
+            if let Ok(cursor_tf) = transforms.get(cursor.frame) {
+                let p = level_tf
+                    .affine()
+                    .inverse()
+                    .transform_point3(cursor_tf.translation());
+                pose.trans = [p.x, p.y, 0.0];
+            }
+        }
+
+        let source = AssetSource::Local(path_buf.to_string_lossy().into_owned());
+        let name = path_buf
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "<Unnamed>".to_owned());
+        // Elements without a parent are assigned to the current level
+        match kind {
+            DroppedFileKind::Model => {
+                commands.spawn(Model {
+                    name: NameInSite(name),
+                    source,
+                    pose,
+                    ..default()
+                });
+            }
+            DroppedFileKind::Drawing => {
+                commands.spawn(Drawing {
+                    source,
+                    pose,
+                    pixels_per_meter: PixelsPerMeter::default(),
+                    marker: DrawingMarker,
+                });
+            }
+            DroppedFileKind::Workspace => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,530 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::notify_error,
+    localization::{tr, tr_args, Translations},
+    site::{generate_site, DefaultFile},
+    workcell::{diff_workcells, generate_workcell},
+    CloseWorkspace, CurrentWorkspace, LoadWorkspace, WorkspaceData,
+};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+use rmf_site_format::{legacy::building_map::BuildingMap, Site, SiteFileFormat, Workcell};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Seconds between checks of whether the file of the current workspace has
+/// been changed by another program
+pub const FILE_WATCH_INTERVAL: f32 = 2.0;
+
+/// Keeps track of the file of the current workspace so that changes made to
+/// it outside of the editor are noticed before they get overwritten
+#[derive(Resource, Default, Debug)]
+pub struct WorkspaceFileWatch {
+    /// The workspace whose file is watched, its file, and when the file was
+    /// last modified by the editor
+    pub watching: Option<(Entity, PathBuf, Option<SystemTime>)>,
+    /// Set when the file has been modified since the editor opened or saved it
+    pub changed_on_disk: bool,
+    /// A workspace that is being replaced by a fresh load of its file
+    pub reloading: Option<(Entity, PathBuf)>,
+    /// Set when the user asked to see how the file differs from the editor
+    pub summary_requested: bool,
+    /// What reloading the file would change in the current workspace
+    pub summary: Option<Vec<String>>,
+}
+
+impl WorkspaceFileWatch {
+    /// Check if the file has been changed by another program and the user has
+    /// not yet decided what to do about it
+    pub fn is_changed_on_disk(&self, path: &Path) -> bool {
+        self.changed_on_disk
+            && self
+                .watching
+                .as_ref()
+                .filter(|(_, file, _)| file == path)
+                .is_some()
+    }
+
+    /// Remember that the editor itself just wrote to a file, so the change
+    /// should not be reported
+    pub fn saved(&mut self, path: &Path) {
+        if let Some((_, file, modified)) = &mut self.watching {
+            if file == path {
+                *modified = last_modified(path);
+                self.changed_on_disk = false;
+                self.summary = None;
+            }
+        }
+    }
+
+    /// Stop reporting the change that was found, so the next save overwrites
+    /// the file
+    pub fn dismiss(&mut self) {
+        if let Some((_, file, modified)) = &mut self.watching {
+            *modified = last_modified(file);
+        }
+        self.changed_on_disk = false;
+        self.summary = None;
+    }
+
+    /// Give up on reloading a file that could not be loaded, so the user can
+    /// decide again what to do about the change
+    pub fn load_failed(&mut self, path: &Path) {
+        if self
+            .reloading
+            .as_ref()
+            .filter(|(_, file)| file == path)
+            .is_some()
+        {
+            notify_error(None, format!("Unable to reload {}", path.display()));
+            self.reloading = None;
+        }
+    }
+}
+
+fn last_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+pub struct FileWatchPlugin;
+
+impl Plugin for FileWatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorkspaceFileWatch>();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_system(watch_workspace_file)
+            .add_system(finish_workspace_reload)
+            .add_system(show_file_changed_prompt)
+            .add_system(summarize_file_changes);
+    }
+}
+
+pub fn watch_workspace_file(
+    mut elapsed: Local<f32>,
+    time: Res<Time>,
+    current_workspace: Res<CurrentWorkspace>,
+    default_files: Query<&DefaultFile>,
+    mut watch: ResMut<WorkspaceFileWatch>,
+) {
+    let current = current_workspace
+        .root
+        .and_then(|root| default_files.get(root).ok().map(|file| (root, &file.0)));
+    let Some((root, file)) = current else {
+        if watch.watching.is_some() {
+            watch.watching = None;
+            watch.changed_on_disk = false;
+            watch.summary = None;
+        }
+        return;
+    };
+
+    let same_file = watch
+        .watching
+        .as_ref()
+        .filter(|(e, f, _)| *e == root && f == file)
+        .is_some();
+    if !same_file {
+        watch.watching = Some((root, file.clone(), last_modified(file)));
+        watch.changed_on_disk = false;
+        watch.summary = None;
+        *elapsed = 0.0;
+        return;
+    }
+
+    *elapsed += time.delta_seconds();
+    if *elapsed < FILE_WATCH_INTERVAL || watch.changed_on_disk {
+        return;
+    }
+    *elapsed = 0.0;
+
+    let modified = last_modified(file);
+    let changed = match (&watch.watching, modified) {
+        (Some((_, _, Some(known))), Some(modified)) => modified > *known,
+        (Some((_, _, None)), Some(_)) => true,
+        _ => false,
+    };
+    if changed {
+        watch.changed_on_disk = true;
+    }
+}
+
+/// Close the old copy of a reloaded workspace once its fresh copy has taken
+/// its place as the current workspace
+pub fn finish_workspace_reload(
+    current_workspace: Res<CurrentWorkspace>,
+    default_files: Query<&DefaultFile>,
+    mut watch: ResMut<WorkspaceFileWatch>,
+    mut close_workspace: EventWriter<CloseWorkspace>,
+) {
+    let Some((old, path)) = &watch.reloading else {
+        return;
+    };
+    let Some(current) = current_workspace.root.filter(|root| root != old) else {
+        return;
+    };
+    // Some other workspace may become current before the reload finishes
+    if default_files
+        .get(current)
+        .ok()
+        .filter(|f| &f.0 == path)
+        .is_none()
+    {
+        return;
+    }
+    close_workspace.send(CloseWorkspace { root: *old });
+    watch.reloading = None;
+}
+
+pub fn show_file_changed_prompt(
+    mut egui_context: ResMut<EguiContext>,
+    mut watch: ResMut<WorkspaceFileWatch>,
+    mut load_workspace: EventWriter<LoadWorkspace>,
+) {
+    if !watch.changed_on_disk || watch.reloading.is_some() {
+        return;
+    }
+    let Some((root, file, _)) = watch.watching.clone() else {
+        return;
+    };
+
+    let ctx = egui_context.ctx_mut();
+    let title = Translations::of(ctx).get("File Changed on Disk");
+    let mut reload = false;
+    let mut keep = false;
+    let mut show_changes = false;
+    egui::Window::new(title)
+        .id(egui::Id::new("file_changed_on_disk"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0., 80.))
+        .show(ctx, |ui| {
+            ui.label(tr_args(
+                ui,
+                "{file} was changed by another program since it was opened or saved.",
+                &[("file", file.display().to_string())],
+            ));
+            ui.horizontal(|ui| {
+                if ui
+                    .button(tr(ui, "Reload"))
+                    .on_hover_text(tr(
+                        ui,
+                        "Open the file as it is now. Changes in the editor that have not \
+                        been saved will be lost.",
+                    ))
+                    .clicked()
+                {
+                    reload = true;
+                }
+                if ui
+                    .button(tr(ui, "Keep My Version"))
+                    .on_hover_text(tr(
+                        ui,
+                        "Keep working on the version in the editor. Saving will overwrite \
+                        the changes that were made to the file.",
+                    ))
+                    .clicked()
+                {
+                    keep = true;
+                }
+                if watch.summary.is_none()
+                    && ui
+                        .button(tr(ui, "Show Changes"))
+                        .on_hover_text(tr(
+                            ui,
+                            "List what reloading the file would change in the editor.",
+                        ))
+                        .clicked()
+                {
+                    show_changes = true;
+                }
+            });
+
+            if let Some(summary) = &watch.summary {
+                ui.separator();
+                if summary.is_empty() {
+                    ui.label(tr(ui, "The file has the same content as the editor."));
+                } else {
+                    ui.label(tr(ui, "Reloading would make these changes:"));
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for change in summary {
+                                ui.label(change);
+                            }
+                        });
+                }
+            }
+        });
+
+    if reload {
+        watch.reloading = Some((root, file.clone()));
+        watch.summary = None;
+        load_workspace.send(LoadWorkspace::Path(file));
+    } else if keep {
+        watch.dismiss();
+    } else if show_changes {
+        watch.summary_requested = true;
+    }
+}
+
+/// Compare the file of the current workspace with what is in the editor when
+/// the user asks to see the changes
+pub fn summarize_file_changes(world: &mut World) {
+    let mut watch = world.resource_mut::<WorkspaceFileWatch>();
+    if !watch.summary_requested {
+        return;
+    }
+    watch.summary_requested = false;
+    let Some((root, file, _)) = watch.watching.clone() else {
+        return;
+    };
+
+    match changes_from_file(world, root, &file) {
+        Ok(summary) => {
+            world.resource_mut::<WorkspaceFileWatch>().summary = Some(summary);
+        }
+        Err(err) => {
+            notify_error(
+                Some(root),
+                format!("Unable to compare with {}: {err}", file.display()),
+            );
+        }
+    }
+}
+
+fn changes_from_file(
+    world: &mut World,
+    root: Entity,
+    file: &PathBuf,
+) -> Result<Vec<String>, String> {
+    let data = std::fs::read(file).map_err(|err| err.to_string())?;
+    let Some(data) = WorkspaceData::new(file, data) else {
+        return Err("unrecognized file type".to_owned());
+    };
+    let mut other = match data {
+        WorkspaceData::LegacyBuilding(data) => BuildingMap::from_bytes(&data)
+            .map_err(|err| err.to_string())?
+            .to_site()
+            .map_err(|err| err.to_string())?,
+        WorkspaceData::Site(data) => {
+            let format = SiteFileFormat::from_path(file).unwrap_or_default();
+            Site::from_bytes_as(format, &data).map_err(|err| err.to_string())?
+        }
+        WorkspaceData::Workcell(data) => {
+            let other = Workcell::from_bytes(&data).map_err(|err| err.to_string())?;
+            let current = generate_workcell(world, root).map_err(|err| err.to_string())?;
+            return Ok(diff_workcells(&current, &other)
+                .into_iter()
+                .map(|change| change.description)
+                .collect());
+        }
+        WorkspaceData::Urdf(_) => {
+            return Err("urdf files cannot be compared".to_owned());
+        }
+    };
+    // Match how the file would be loaded so drawing paths compare equal
+    if let Some(folder) = file.parent() {
+        other.resolve_asset_paths(folder);
+    }
+    let current = generate_site(world, root).map_err(|err| err.to_string())?;
+    Ok(diff_sites(&current, &other))
+}
+
+/// Describe how each kind of element differs between the current site and
+/// another one. Elements are matched by their site IDs.
+pub fn diff_sites(current: &Site, other: &Site) -> Vec<String> {
+    let mut changes = Vec::new();
+    if differs(&current.properties, &other.properties) {
+        changes.push("Site properties changed".to_owned());
+    }
+    diff_elements(
+        "Site anchors",
+        &current.anchors,
+        &other.anchors,
+        &mut changes,
+    );
+    diff_elements("Lifts", &current.lifts, &other.lifts, &mut changes);
+    diff_elements("Site lights", &current.lights, &other.lights, &mut changes);
+    let (guided, other_guided) = (&current.navigation.guided, &other.navigation.guided);
+    diff_elements(
+        "Nav graphs",
+        &guided.graphs,
+        &other_guided.graphs,
+        &mut changes,
+    );
+    diff_elements("Lanes", &guided.lanes, &other_guided.lanes, &mut changes);
+    diff_elements(
+        "Locations",
+        &guided.locations,
+        &other_guided.locations,
+        &mut changes,
+    );
+    diff_elements("Agents", &current.agents, &other.agents, &mut changes);
+    if differs(&current.environment, &other.environment) {
+        changes.push("Environment changed".to_owned());
+    }
+
+    for (id, level) in &current.levels {
+        let name = &level.properties.name;
+        let Some(other_level) = other.levels.get(id) else {
+            changes.push(format!("Level {name} was removed"));
+            continue;
+        };
+        if differs(&level.properties, &other_level.properties) {
+            changes.push(format!("Level {name} properties changed"));
+        }
+        let on_level = |category: &str| format!("{category} on level {name}");
+        let (l, o) = (level, other_level);
+        let c = &mut changes;
+        diff_elements(&on_level("Anchors"), &l.anchors, &o.anchors, c);
+        diff_elements(&on_level("Doors"), &l.doors, &o.doors, c);
+        diff_elements(&on_level("Drawings"), &l.drawings, &o.drawings, c);
+        diff_elements(&on_level("Fiducials"), &l.fiducials, &o.fiducials, c);
+        diff_elements(&on_level("Floors"), &l.floors, &o.floors, c);
+        diff_elements(&on_level("Lights"), &l.lights, &o.lights, c);
+        diff_elements(
+            &on_level("Measurements"),
+            &l.measurements,
+            &o.measurements,
+            c,
+        );
+        diff_elements(&on_level("Models"), &l.models, &o.models, c);
+        diff_elements(
+            &on_level("Cameras"),
+            &l.physical_cameras,
+            &o.physical_cameras,
+            c,
+        );
+        diff_elements(
+            &on_level("Shapes"),
+            &l.primitive_shapes,
+            &o.primitive_shapes,
+            c,
+        );
+        diff_elements(&on_level("Sensors"), &l.sensors, &o.sensors, c);
+        diff_elements(&on_level("Walls"), &l.walls, &o.walls, c);
+    }
+    for (id, level) in &other.levels {
+        if !current.levels.contains_key(id) {
+            changes.push(format!("Level {} was added", level.properties.name));
+        }
+    }
+    changes
+}
+
+/// Count the elements of one kind that were added, removed, or modified
+fn diff_elements<T: Serialize>(
+    category: &str,
+    current: &BTreeMap<u32, T>,
+    other: &BTreeMap<u32, T>,
+    changes: &mut Vec<String>,
+) {
+    let added = other.keys().filter(|id| !current.contains_key(id)).count();
+    let removed = current.keys().filter(|id| !other.contains_key(id)).count();
+    let modified = current
+        .iter()
+        .filter(|(id, element)| {
+            other
+                .get(id)
+                .filter(|other| differs(*element, *other))
+                .is_some()
+        })
+        .count();
+
+    let mut counts = Vec::new();
+    for (count, what) in [
+        (added, "added"),
+        (removed, "removed"),
+        (modified, "modified"),
+    ] {
+        if count > 0 {
+            counts.push(format!("{count} {what}"));
+        }
+    }
+    if !counts.is_empty() {
+        changes.push(format!("{category}: {}", counts.join(", ")));
+    }
+}
+
+/// Not every element type implements PartialEq, so compare what would be
+/// saved for them instead
+fn differs<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn office() -> Site {
+        let data = std::fs::read("../assets/demo_maps/office.building.yaml").unwrap();
+        BuildingMap::from_bytes(&data).unwrap().to_site().unwrap()
+    }
+
+    #[test]
+    fn identical_sites_have_no_changes() {
+        assert!(diff_sites(&office(), &office()).is_empty());
+    }
+
+    #[test]
+    fn site_changes_are_counted_by_kind() {
+        let current = office();
+        let mut other = office();
+        let (level_id, level) = other.levels.iter_mut().next().unwrap();
+        let level_id = *level_id;
+        level.properties.elevation += 1.0;
+        let model = *level.models.keys().next().unwrap();
+        level.models.remove(&model);
+
+        let guided = &mut other.navigation.guided;
+        let lane = *guided.lanes.keys().next().unwrap();
+        let copy = guided.lanes[&lane].clone();
+        guided.lanes.insert(u32::MAX, copy);
+        guided.locations.values_mut().next().unwrap().name.0 = "renamed".to_owned();
+
+        let name = &current.levels[&level_id].properties.name;
+        assert_eq!(
+            diff_sites(&current, &other),
+            vec![
+                "Lanes: 1 added".to_owned(),
+                "Locations: 1 modified".to_owned(),
+                format!("Level {name} properties changed"),
+                format!("Models on level {name}: 1 removed"),
+            ],
+        );
+    }
+
+    #[test]
+    fn added_and_removed_levels_are_named() {
+        let current = office();
+        let mut other = office();
+        let (_, level) = other.levels.pop_first().unwrap();
+        let name = level.properties.name.clone();
+        other.levels.insert(u32::MAX, level);
+
+        let changes = diff_sites(&current, &other);
+        assert!(changes.contains(&format!("Level {name} was removed")));
+        assert!(changes.contains(&format!("Level {name} was added")));
+    }
+}
@@ -15,7 +15,7 @@
  *
 */
 
-use crate::interaction::PickingBlockers;
+use crate::{interaction::PickingBlockers, Preferences};
 use bevy::{
     core_pipeline::clear_color::ClearColorConfig,
     core_pipeline::core_3d::Camera3dBundle,
@@ -91,6 +91,11 @@ pub struct CameraControls {
     pub was_oribiting: bool,
 }
 
+/// Send this event to move the camera so that it is centered on a point
+/// without changing how far away or how rotated the view is
+#[derive(Debug, Clone, Copy)]
+pub struct FocusCamera(pub Vec3);
+
 /// True/false for whether the headlight should be on or off
 #[derive(Clone, Copy, PartialEq, Eq, Deref, DerefMut, Resource)]
 pub struct HeadlightToggle(pub bool);
@@ -332,7 +337,9 @@ fn camera_controls(
     mut visibility: Query<&mut Visibility>,
     headlight_toggle: Res<HeadlightToggle>,
     picking_blockers: Res<PickingBlockers>,
+    preferences: Res<Preferences>,
 ) {
+    let speeds = &preferences.camera;
     if headlight_toggle.is_changed() {
         controls.toggle_lights(headlight_toggle.0, &mut visibility);
     }
@@ -392,12 +399,13 @@ fn camera_controls(
                 if cursor_motion.length_squared() > 0.0 {
                     cursor_motion *= 2. / window_size
                         * Vec2::new(ortho_proj.scale * aspect_ratio, ortho_proj.scale);
+                    cursor_motion *= speeds.pan_speed;
                     let right = -cursor_motion.x * Vec3::X;
                     let up = -cursor_motion.y * Vec3::Y;
                     ortho_transform.translation += right + up;
                 }
                 if scroll.abs() > 0.0 {
-                    ortho_proj.scale -= scroll * ortho_proj.scale * 0.1;
+                    ortho_proj.scale -= scroll * ortho_proj.scale * 0.1 * speeds.zoom_speed;
                     ortho_proj.scale = f32::max(ortho_proj.scale, 0.02);
                 }
             }
@@ -430,14 +438,19 @@ fn camera_controls(
                 if let Some(window) = windows.get_primary() {
                     let window_size = Vec2::new(window.width() as f32, window.height() as f32);
                     let delta_x = {
-                        let delta = cursor_motion.x / window_size.x * std::f32::consts::PI * 2.0;
+                        let delta = cursor_motion.x / window_size.x
+                            * std::f32::consts::PI
+                            * 2.0
+                            * speeds.orbit_speed;
                         if controls.orbit_upside_down {
                             -delta
                         } else {
                             delta
                         }
                     };
-                    let delta_y = -cursor_motion.y / window_size.y * std::f32::consts::PI;
+                    let delta_y = -cursor_motion.y / window_size.y
+                        * std::f32::consts::PI
+                        * speeds.orbit_speed;
                     let yaw = Quat::from_rotation_z(-delta_x);
                     let pitch = Quat::from_rotation_x(-delta_y);
                     persp_transform.rotation = yaw * persp_transform.rotation; // global y
@@ -452,7 +465,8 @@ fn camera_controls(
 
                     cursor_motion *=
                         Vec2::new(persp_proj.fov * persp_proj.aspect_ratio, persp_proj.fov)
-                            / window_size;
+                            / window_size
+                            * speeds.pan_speed;
                     // translate by local axes
                     let right = persp_transform.rotation * Vec3::X * -cursor_motion.x;
                     let up = persp_transform.rotation * Vec3::Y * -cursor_motion.y;
@@ -464,7 +478,7 @@ fn camera_controls(
 
             if scroll.abs() > 0.0 {
                 changed = true;
-                controls.orbit_radius -= scroll * controls.orbit_radius * 0.2;
+                controls.orbit_radius -= scroll * controls.orbit_radius * 0.2 * speeds.zoom_speed;
                 // dont allow zoom to reach zero or you get stuck
                 controls.orbit_radius = f32::max(controls.orbit_radius, 0.05);
             }
@@ -481,6 +495,28 @@ fn camera_controls(
     }
 }
 
+fn focus_camera(
+    mut focus: EventReader<FocusCamera>,
+    mut controls: ResMut<CameraControls>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let Some(FocusCamera(point)) = focus.iter().last() else {
+        return;
+    };
+
+    if let Ok(mut tf) = transforms.get_mut(controls.orthographic_camera_entities[0]) {
+        tf.translation.x = point.x;
+        tf.translation.y = point.y;
+    }
+
+    controls.orbit_center = *point;
+    if let Ok(mut tf) = transforms.get_mut(controls.perspective_camera_entities[0]) {
+        let rot_matrix = Mat3::from_quat(tf.rotation);
+        tf.translation =
+            controls.orbit_center + rot_matrix.mul_vec3(Vec3::new(0.0, 0.0, controls.orbit_radius));
+    }
+}
+
 pub struct CameraControlsPlugin;
 
 impl Plugin for CameraControlsPlugin {
@@ -488,6 +524,8 @@ impl Plugin for CameraControlsPlugin {
         app.insert_resource(MouseLocation::default())
             .init_resource::<CameraControls>()
             .init_resource::<HeadlightToggle>()
-            .add_system(camera_controls);
+            .add_event::<FocusCamera>()
+            .add_system(camera_controls)
+            .add_system(focus_camera);
     }
 }
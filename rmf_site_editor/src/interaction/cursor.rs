@@ -18,12 +18,13 @@
 use crate::{
     animate::*,
     interaction::*,
-    site::{AnchorBundle, Pending, SiteAssets},
+    site::{primitive_base_offset, AnchorBundle, Pending, SiteAssets},
+    Preferences,
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_mod_picking::PickingRaycastSet;
 use bevy_mod_raycast::{Intersection, Ray3d};
-use rmf_site_format::{FloorMarker, Model, ModelMarker, WallMarker, WorkcellModel};
+use rmf_site_format::{FloorMarker, Model, ModelMarker, PrimitiveShape, WallMarker, WorkcellModel};
 use std::collections::HashSet;
 
 /// A resource that keeps track of the unique entities that play a role in
@@ -123,6 +124,23 @@ impl Cursor {
         }
     }
 
+    pub fn set_primitive_shape_preview(
+        &mut self,
+        commands: &mut Commands,
+        shape: Option<PrimitiveShape>,
+    ) {
+        self.remove_preview(commands);
+        self.preview_model = if let Some(mut shape) = shape {
+            // Preview the shape resting on the surface it will be placed on
+            shape.pose.trans[2] = primitive_base_offset(&shape.shape);
+            let e = commands.spawn(shape).insert(Pending).id();
+            commands.entity(self.frame).push_children(&[e]);
+            Some(e)
+        } else {
+            None
+        }
+    }
+
     pub fn set_workcell_model_preview(
         &mut self,
         commands: &mut Commands,
@@ -278,13 +296,18 @@ pub struct IntersectGroundPlaneParams<'w, 's> {
 }
 
 impl<'w, 's> IntersectGroundPlaneParams<'w, 's> {
-    pub fn ground_plane_intersection(&self) -> Option<Vec3> {
+    /// The ray that goes from the active camera through the cursor
+    pub fn cursor_ray(&self) -> Option<Ray3d> {
         let window = self.windows.get_primary()?;
         let cursor_position = window.cursor_position()?;
         let e_active_camera = self.camera_controls.active_camera();
         let active_camera = self.cameras.get(e_active_camera).ok()?;
         let camera_tf = self.global_transforms.get(e_active_camera).ok()?;
-        let ray = Ray3d::from_screenspace(cursor_position, active_camera, camera_tf)?;
+        Ray3d::from_screenspace(cursor_position, active_camera, camera_tf)
+    }
+
+    pub fn ground_plane_intersection(&self) -> Option<Vec3> {
+        let ray = self.cursor_ray()?;
         let n_p = Vec3::Z;
         let n_r = ray.direction();
         let denom = n_p.dot(n_r);
@@ -305,8 +328,11 @@ pub fn update_cursor_transform(
     mut transforms: Query<&mut Transform>,
     hovering: Res<Hovering>,
     intersect_ground_params: IntersectGroundPlaneParams,
+    snapping: SurfaceSnappingParams,
+    preferences: Res<Preferences>,
     mut visibility: Query<&mut Visibility>,
 ) {
+    let grid = &preferences.grid;
     match &*mode {
         InteractionMode::Inspect => {
             let intersection = match intersections.iter().last() {
@@ -332,7 +358,7 @@ pub fn update_cursor_transform(
 
             *transform = Transform::from_matrix(ray.to_aligned_transform([0., 0., 1.].into()));
         }
-        InteractionMode::SelectAnchor(_) => {
+        InteractionMode::SelectAnchor(_) | InteractionMode::SplitLane(_) => {
             let intersection = match intersect_ground_params.ground_plane_intersection() {
                 Some(intersection) => intersection,
                 None => {
@@ -347,7 +373,7 @@ pub fn update_cursor_transform(
                 }
             };
 
-            *transform = Transform::from_translation(intersection);
+            *transform = Transform::from_translation(grid.snap(intersection));
         }
         // TODO(luca) snap to features of meshes
         InteractionMode::SelectAnchor3D(_mode) => {
@@ -395,7 +421,17 @@ pub fn update_cursor_transform(
                             set_visibility(cursor.frame, &mut visibility, false);
                         }
                     }
-                    Some(_) => {
+                    Some(preview) => {
+                        if snapping.snapping.enabled {
+                            if let Some(hit) = intersect_ground_params
+                                .cursor_ray()
+                                .and_then(|ray| snapping.surface_hit(&ray, Some(preview)))
+                            {
+                                set_visibility(cursor.frame, &mut visibility, true);
+                                *transform = Transform::from_translation(hit);
+                                return;
+                            }
+                        }
                         // If we are placing a model avoid snapping to faced and just project to
                         // ground plane
                         let intersection = match intersect_ground_params.ground_plane_intersection()
@@ -406,7 +442,7 @@ pub fn update_cursor_transform(
                             }
                         };
                         set_visibility(cursor.frame, &mut visibility, true);
-                        *transform = Transform::from_translation(intersection);
+                        *transform = Transform::from_translation(grid.snap(intersection));
                     }
                 }
             } else {
@@ -417,7 +453,7 @@ pub fn update_cursor_transform(
                     }
                 };
                 set_visibility(cursor.frame, &mut visibility, true);
-                *transform = Transform::from_translation(intersection);
+                *transform = Transform::from_translation(grid.snap(intersection));
             }
         }
     }
@@ -15,7 +15,7 @@
  *
 */
 
-use crate::interaction::*;
+use crate::{interaction::*, Preferences};
 use bevy::{math::Affine3A, prelude::*};
 use bevy_mod_picking::{PickableBundle, PickableMesh, PickingRaycastSet};
 use bevy_mod_raycast::{Intersection, Ray3d};
@@ -246,6 +246,7 @@ pub fn update_gizmo_click_start(
     intersections: Query<&Intersection<PickingRaycastSet>>,
     mut cursor: ResMut<Cursor>,
     mut gizmo_state: ResMut<GizmoState>,
+    tool: Res<Tool>,
     mut picks: EventReader<ChangePick>,
     mut click: EventWriter<GizmoClicked>,
     removed_gizmos: RemovedComponents<Gizmo>,
@@ -288,6 +289,11 @@ pub fn update_gizmo_click_start(
     if clicked {
         if let GizmoState::Hovering(e) = *gizmo_state {
             click.send(GizmoClicked(e));
+            // Gizmos can still be clicked with other tools, but only the move
+            // tool drags elements with them
+            if !tool.allows_dragging() {
+                return;
+            }
             if let Ok(Some(intersection)) = intersections.get_single().map(|i| i.position()) {
                 if let Ok((gizmo, Some(mut draggable), mut material)) = gizmos.get_mut(e) {
                     if let Ok((local_tf, global_tf)) = transforms.get(draggable.for_entity) {
@@ -357,7 +363,10 @@ pub fn update_drag_motions(
     drag_state: Res<GizmoState>,
     mut cursor_motion: EventReader<CursorMoved>,
     mut move_to: EventWriter<MoveTo>,
+    snapping: SurfaceSnappingParams,
+    preferences: Res<Preferences>,
 ) {
+    let grid = &preferences.grid;
     if let GizmoState::Dragging(dragging) = *drag_state {
         let cursor_position = match cursor_motion.iter().last() {
             Some(m) => m.position,
@@ -409,9 +418,9 @@ pub fn update_drag_motions(
 
                 let t = (a * b - c) / denom;
                 let delta = t * n;
-                let tf_goal = initial
-                    .tf_for_entity_global
-                    .with_translation(initial.tf_for_entity_global.translation + delta);
+                let translation =
+                    grid.snap_along(initial.tf_for_entity_global.translation + delta, n);
+                let tf_goal = initial.tf_for_entity_global.with_translation(translation);
                 move_to.send(MoveTo {
                     entity: draggable.for_entity,
                     transform: Transform::from_matrix(
@@ -423,6 +432,27 @@ pub fn update_drag_motions(
 
         if let Ok((plane, draggable, drag_tf)) = drag_plane.get(dragging) {
             if let Some(initial) = &draggable.drag {
+                if snapping.snaps(draggable.for_entity) {
+                    if let Some(hit) = snapping.surface_hit(&ray, Some(draggable.for_entity)) {
+                        // Keep the horizontal offset between the entity and
+                        // the point where it was grabbed, but rest the entity
+                        // on the surface.
+                        let grab_offset =
+                            initial.tf_for_entity_global.translation - initial.click_point;
+                        let mut translation = grid.snap(hit + grab_offset);
+                        translation.z = hit.z + snapping.base_offset(draggable.for_entity);
+                        let tf_goal = initial.tf_for_entity_global.with_translation(translation);
+                        move_to.send(MoveTo {
+                            entity: draggable.for_entity,
+                            transform: Transform::from_matrix(
+                                (initial.tf_for_entity_parent_inv * tf_goal.compute_affine())
+                                    .into(),
+                            ),
+                        });
+                        return;
+                    }
+                }
+
                 let n_p = if plane.frame.is_local() {
                     drag_tf
                         .affine()
@@ -442,9 +472,9 @@ pub fn update_drag_motions(
 
                 let t = (initial.click_point - ray.origin()).dot(n_p) / denom;
                 let delta = ray.position(t) - initial.click_point;
-                let tf_goal = initial
-                    .tf_for_entity_global
-                    .with_translation(initial.tf_for_entity_global.translation + delta);
+                let translation =
+                    grid.snap_in_plane(initial.tf_for_entity_global.translation + delta, n_p);
+                let tf_goal = initial.tf_for_entity_global.with_translation(translation);
                 move_to.send(MoveTo {
                     entity: draggable.for_entity,
                     transform: Transform::from_matrix(
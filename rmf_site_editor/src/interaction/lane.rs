@@ -19,6 +19,8 @@ use crate::{interaction::*, site::*};
 use bevy::prelude::*;
 use rmf_site_format::{Edge, LaneMarker};
 
+pub const SPLIT_LANE_MODE_LABEL: &'static str = "split_lane";
+
 pub fn add_lane_visual_cues(
     mut commands: Commands,
     new_lane_segments: Query<(Entity, &LaneSegments), Added<LaneSegments>>,
@@ -70,3 +72,44 @@ pub fn update_lane_visual_cues(
         tf.translation.z = h;
     }
 }
+
+pub fn handle_split_lane_mode(
+    mode: Res<InteractionMode>,
+    mut change_mode: EventWriter<ChangeMode>,
+    mut split_lane: EventWriter<SplitLane>,
+    mut cursor: ResMut<Cursor>,
+    mut visibility: Query<&mut Visibility>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    touch_input: Res<Touches>,
+    blockers: Option<Res<PickingBlockers>>,
+    intersect_ground_params: IntersectGroundPlaneParams,
+) {
+    let lane = match &*mode {
+        InteractionMode::SplitLane(lane) => *lane,
+        _ => {
+            if mode.is_changed() {
+                cursor.remove_mode(SPLIT_LANE_MODE_LABEL, &mut visibility);
+            }
+            return;
+        }
+    };
+
+    if mode.is_changed() {
+        cursor.add_mode(SPLIT_LANE_MODE_LABEL, &mut visibility);
+    }
+
+    let clicked = mouse_button_input.just_pressed(MouseButton::Left)
+        || touch_input.iter_just_pressed().next().is_some();
+    let blocked = blockers.filter(|x| x.blocking()).is_some();
+    if !clicked || blocked {
+        return;
+    }
+
+    if let Some(point) = intersect_ground_params.ground_plane_intersection() {
+        split_lane.send(SplitLane {
+            lane,
+            point: point.truncate(),
+        });
+    }
+    change_mode.send(ChangeMode::To(InteractionMode::Inspect));
+}
@@ -16,6 +16,7 @@
 */
 
 use crate::{
+    console::report_error,
     interaction::{DragPlaneBundle, HeadlightToggle, InteractionAssets, Selectable},
     site::LightKind,
 };
@@ -171,7 +172,7 @@ pub fn update_physical_light_visual_cues(
         if let Some(m) = material_assets.get_mut(material) {
             m.base_color = kind.color().into();
         } else {
-            println!("DEV ERROR: Unable to get material asset for light");
+            report_error(None, "DEV ERROR: Unable to get material asset for light");
         }
 
         if kind.is_directional() {
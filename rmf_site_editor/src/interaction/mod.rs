@@ -68,9 +68,21 @@ pub use select::*;
 pub mod select_anchor;
 pub use select_anchor::*;
 
+pub mod reference_grid;
+pub use reference_grid::*;
+
+pub mod surface_snapping;
+pub use surface_snapping::*;
+
+pub mod tool;
+pub use tool::*;
+
 pub mod visual_cue;
 pub use visual_cue::*;
 
+pub mod xray_selection;
+pub use xray_selection::*;
+
 use bevy::prelude::*;
 use bevy_mod_outline::OutlinePlugin;
 use bevy_mod_picking::{PickingPlugin, PickingSystem};
@@ -124,11 +136,16 @@ impl Plugin for InteractionPlugin {
             .init_resource::<PickingBlockers>()
             .init_resource::<SelectionBlockers>()
             .init_resource::<Selection>()
+            .init_resource::<MultiSelection>()
             .init_resource::<Hovering>()
             .init_resource::<GizmoState>()
+            .init_resource::<SurfaceSnapping>()
+            .init_resource::<XRaySelection>()
             .init_resource::<InteractionMode>()
+            .init_resource::<Tool>()
             .add_event::<ChangePick>()
             .add_event::<Select>()
+            .add_event::<ToggleSelect>()
             .add_event::<Hover>()
             .add_event::<MoveTo>()
             .add_event::<ChangeMode>()
@@ -142,6 +159,7 @@ impl Plugin for InteractionPlugin {
                     .with_system(make_lift_doormat_gizmo)
                     .with_system(update_doormats_for_level_change)
                     .with_system(update_cursor_transform)
+                    .with_system(update_reference_grid)
                     .with_system(update_picking_cam)
                     .with_system(update_physical_light_visual_cues)
                     .with_system(make_selectable_entities_pickable)
@@ -157,15 +175,18 @@ impl Plugin for InteractionPlugin {
                     .with_system(remove_deleted_supports_from_visual_cues)
                     .with_system(make_model_previews_not_selectable)
                     .with_system(update_lane_visual_cues.after(maintain_selected_entities))
+                    .with_system(handle_split_lane_mode.after(maintain_selected_entities))
                     .with_system(update_edge_visual_cues.after(maintain_selected_entities))
                     .with_system(update_point_visual_cues.after(maintain_selected_entities))
                     .with_system(update_path_visual_cues.after(maintain_selected_entities))
                     .with_system(update_outline_visualization.after(maintain_selected_entities))
+                    .with_system(update_xray_selection.after(maintain_selected_entities))
                     .with_system(
                         update_cursor_hover_visualization.after(maintain_selected_entities),
                     )
                     .with_system(update_gizmo_click_start.after(maintain_selected_entities))
                     .with_system(update_gizmo_release)
+                    .with_system(update_tool_for_mode)
                     .with_system(
                         update_drag_motions
                             .after(update_gizmo_click_start)
@@ -30,6 +30,8 @@ pub enum InteractionMode {
     /// 3D version of SelectAnchor
     // TODO(anyone) rename above SelectAnchor2D and this SelectAnchor
     SelectAnchor3D(SelectAnchor3D),
+    /// The user must click a point along a lane to split it into two lanes
+    SplitLane(Entity),
 }
 
 impl Default for InteractionMode {
@@ -44,7 +46,7 @@ impl InteractionMode {
             Self::Inspect => true,
             Self::SelectAnchor(_) => true,
             Self::SelectAnchor3D(_) => true,
-            // _ => false,
+            Self::SplitLane(_) => false,
         }
     }
 
@@ -59,6 +61,16 @@ impl InteractionMode {
         matches!(self, InteractionMode::Inspect)
     }
 
+    /// A short name for the mode that can be shown to the user
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Inspect => "Inspect",
+            Self::SelectAnchor(_) => "Select Anchor",
+            Self::SelectAnchor3D(_) => "Place",
+            Self::SplitLane(_) => "Split Lane",
+        }
+    }
+
     fn backout<'w, 's>(&mut self, params: &mut BackoutParams<'w, 's>) {
         let change_mode = match self {
             Self::Inspect => {
@@ -72,6 +84,7 @@ impl InteractionMode {
             Self::SelectAnchor3D(select_anchor) => {
                 Some(select_anchor.backout(&mut params.select_anchor))
             }
+            Self::SplitLane(_) => Some(Self::Inspect),
         };
 
         if let Some(change_mode) = change_mode {
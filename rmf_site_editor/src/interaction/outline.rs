@@ -20,7 +20,7 @@ use bevy::render::view::RenderLayers;
 use bevy_mod_outline::{OutlineBundle, OutlineRenderLayers, OutlineVolume, SetOutlineDepth};
 use rmf_site_format::{
     DoorType, FloorMarker, LiftCabin, LightKind, LocationTags, MeasurementMarker, ModelMarker,
-    PhysicalCameraProperties, WallMarker,
+    PhysicalCameraProperties, PrimitiveShapeMarker, WallMarker,
 };
 use smallvec::SmallVec;
 
@@ -117,6 +117,7 @@ pub fn add_outline_visualization(
             Added<FloorMarker>,
             Added<ModelMarker>,
             Added<PhysicalCameraProperties>,
+            Added<PrimitiveShapeMarker>,
             Added<LightKind>,
             Added<LocationTags>,
         )>,
@@ -125,6 +125,7 @@ pub fn update_picked(
     blockers: Option<Res<PickingBlockers>>,
     pick_source_query: Query<&PickingCamera>,
     visual_cues: Query<&ComputedVisualCue>,
+    xray: Res<XRaySelection>,
     mut picked: ResMut<Picked>,
     mut change_pick: EventWriter<ChangePick>,
     current_workspace: Res<CurrentWorkspace>,
@@ -172,6 +173,23 @@ pub fn update_picked(
                 break 'current_picked Some(topmost);
             }
 
+            // Surfaces that x-ray selection made transparent should not
+            // block the visual cues that can be seen through them
+            if xray.0 {
+                if let Some(topmost) = pick_topmost(
+                    picks
+                        .iter()
+                        .filter(|(e, _)| visual_cues.contains(*e))
+                        .map(|(e, _)| *e),
+                    &selectable,
+                    &anchors,
+                    &mode,
+                    current_site,
+                ) {
+                    break 'current_picked Some(topmost);
+                }
+            }
+
             // Now look at all possible pickables
             if let Some(topmost) = pick_topmost(
                 picks.iter().map(|(e, _)| *e),
@@ -24,7 +24,10 @@ use bevy::{
     window::{CreateWindow, PresentMode, WindowClosed, WindowId, Windows},
 };
 
-use rmf_site_format::{NameInSite, PhysicalCameraProperties, PreviewableMarker};
+use rmf_site_format::{
+    Angle, NameInSite, NameInWorkcell, PhysicalCameraProperties, PreviewableMarker,
+    SensorProperties,
+};
 
 /// Instruction to spawn a preview for the given entity
 /// TODO None to encode "Clear all"
@@ -43,19 +46,54 @@ impl SpawnPreview {
 #[derive(Component)]
 pub struct CameraPreviewWindow(pub WindowId);
 
+/// The image of a camera that is being previewed. Both physical cameras and
+/// camera sensors can be previewed.
+#[derive(Debug, Clone, Copy)]
+struct PreviewImage {
+    width: u32,
+    height: u32,
+    horizontal_fov: Angle,
+}
+
+impl PreviewImage {
+    fn new(
+        physical_camera: Option<&PhysicalCameraProperties>,
+        sensor: Option<&SensorProperties>,
+    ) -> Option<Self> {
+        if let Some(camera) = physical_camera {
+            return Some(Self {
+                width: camera.width,
+                height: camera.height,
+                horizontal_fov: camera.horizontal_fov,
+            });
+        }
+        let camera = sensor?.camera()?;
+        Some(Self {
+            width: camera.width,
+            height: camera.height,
+            horizontal_fov: camera.horizontal_fov,
+        })
+    }
+
+    fn fov(&self) -> f32 {
+        let aspect_ratio = (self.width as f32) / (self.height as f32);
+        self.horizontal_fov.radians() / aspect_ratio
+    }
+}
+
 fn create_camera_window(
     commands: &mut Commands,
     entity: Entity,
     camera_name: &String,
-    camera_properties: &PhysicalCameraProperties,
+    image: &PreviewImage,
     create_window_events: &mut EventWriter<CreateWindow>,
 ) -> WindowId {
     let window_id = WindowId::new();
     create_window_events.send(CreateWindow {
         id: window_id,
         descriptor: WindowDescriptor {
-            width: camera_properties.width as f32,
-            height: camera_properties.height as f32,
+            width: image.width as f32,
+            height: image.height as f32,
             present_mode: PresentMode::AutoNoVsync,
             title: "Camera preview: ".to_string() + camera_name,
             ..default()
@@ -79,7 +117,13 @@ pub fn manage_previews(
     mut commands: Commands,
     mut preview_events: EventReader<SpawnPreview>,
     previewable: Query<
-        (&Children, &NameInSite, Option<&PhysicalCameraProperties>),
+        (
+            &Children,
+            Option<&NameInSite>,
+            Option<&NameInWorkcell>,
+            Option<&PhysicalCameraProperties>,
+            Option<&SensorProperties>,
+        ),
         With<PreviewableMarker>,
     >,
     preview_windows: Query<&CameraPreviewWindow>,
@@ -91,8 +135,14 @@ pub fn manage_previews(
             None => { // TODO clear all previews
             }
             Some(e) => {
-                if let Ok((children, camera_name, camera_option)) = previewable.get(e) {
-                    if let Some(camera_properties) = camera_option {
+                if let Ok((children, site_name, workcell_name, camera_option, sensor_option)) =
+                    previewable.get(e)
+                {
+                    let camera_name = site_name
+                        .map(|name| name.0.clone())
+                        .or_else(|| workcell_name.map(|name| name.0.clone()))
+                        .unwrap_or_default();
+                    if let Some(image) = PreviewImage::new(camera_option, sensor_option) {
                         if preview_windows.get(e).is_ok() {
                             // Preview window already exists, skip creating it
                             continue;
@@ -106,16 +156,13 @@ pub fn manage_previews(
                             if let Projection::Perspective(perspective_projection) =
                                 &mut (*projection)
                             {
-                                let aspect_ratio = (camera_properties.width as f32)
-                                    / (camera_properties.height as f32);
-                                perspective_projection.fov =
-                                    camera_properties.horizontal_fov.radians() / aspect_ratio;
+                                perspective_projection.fov = image.fov();
                             }
                             let window_id = create_camera_window(
                                 &mut commands,
                                 child_entity,
                                 &camera_name,
-                                &camera_properties,
+                                &image,
                                 &mut create_window_events,
                             );
                             commands.entity(e).insert(CameraPreviewWindow(window_id));
@@ -129,27 +176,29 @@ pub fn manage_previews(
 
 pub fn update_physical_camera_preview(
     updated_cameras: Query<
-        (&Children, &PhysicalCameraProperties, &CameraPreviewWindow),
-        Changed<PhysicalCameraProperties>,
+        (
+            &Children,
+            Option<&PhysicalCameraProperties>,
+            Option<&SensorProperties>,
+            &CameraPreviewWindow,
+        ),
+        Or<(Changed<PhysicalCameraProperties>, Changed<SensorProperties>)>,
     >,
     mut camera_children: Query<&mut Projection, With<Camera>>,
     mut windows: ResMut<Windows>,
 ) {
-    for (children, camera_properties, preview_window) in updated_cameras.iter() {
+    for (children, camera_properties, sensor_properties, preview_window) in updated_cameras.iter() {
+        let Some(image) = PreviewImage::new(camera_properties, sensor_properties) else {
+            continue;
+        };
         if let Some(window) = windows.get_mut(preview_window.0) {
             // Update fov first
             if let Ok(mut projection) = camera_children.get_mut(children[0]) {
                 if let Projection::Perspective(perspective_projection) = &mut (*projection) {
-                    let aspect_ratio =
-                        (camera_properties.width as f32) / (camera_properties.height as f32);
-                    perspective_projection.fov =
-                        camera_properties.horizontal_fov.radians() / aspect_ratio;
+                    perspective_projection.fov = image.fov();
                 }
             }
-            window.set_resolution(
-                camera_properties.width as f32,
-                camera_properties.height as f32,
-            );
+            window.set_resolution(image.width as f32, image.height as f32);
         }
     }
 }
@@ -0,0 +1,223 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{shapes::make_finite_grid, site::CurrentLevel, Preferences};
+use bevy::prelude::*;
+use bevy_polyline::{
+    material::PolylineMaterial,
+    polyline::{Polyline, PolylineBundle},
+};
+use serde::{Deserialize, Serialize};
+
+/// Height of the reference grid above the floor of the level, which keeps it
+/// above the drawings and floors
+pub const REFERENCE_GRID_HEIGHT: f32 = 0.005;
+
+/// A grid that is drawn on the floor of the current level to help judge
+/// distances while laying out a site
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct ReferenceGrid {
+    pub visible: bool,
+    /// Distance in meters between the major lines of the grid
+    pub spacing: f32,
+    /// How many cells each major cell is divided into
+    pub subdivisions: u32,
+    /// How many major cells the grid extends in each direction from the
+    /// origin of the level
+    pub extent: u32,
+    pub color: [f32; 4],
+    /// Move new anchors and elements that are dragged by their gizmos to the
+    /// nearest crossing of the grid lines while the grid is visible
+    pub snap: bool,
+}
+
+impl Default for ReferenceGrid {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            spacing: 1.0,
+            subdivisions: 4,
+            extent: 50,
+            color: [0.5, 0.5, 0.5, 1.0],
+            snap: false,
+        }
+    }
+}
+
+impl ReferenceGrid {
+    /// Distance in meters between adjacent lines of the grid
+    pub fn cell_size(&self) -> f32 {
+        self.spacing / self.subdivisions.max(1) as f32
+    }
+
+    /// Move a point on the floor to the nearest crossing of the grid lines,
+    /// if the grid is visible and snapping is turned on
+    pub fn snap(&self, point: Vec3) -> Vec3 {
+        let cell = self.cell_size();
+        if !self.visible || !self.snap || cell <= 0.0 {
+            return point;
+        }
+        Vec3::new(
+            (point.x / cell).round() * cell,
+            (point.y / cell).round() * cell,
+            point.z,
+        )
+    }
+
+    /// Snap a point that is being dragged along an axis. Only the motion
+    /// along the axis is snapped, so the point does not jump off the axis.
+    pub fn snap_along(&self, point: Vec3, axis: Vec3) -> Vec3 {
+        let n = axis.normalize_or_zero();
+        point + n * n.dot(self.snap(point) - point)
+    }
+
+    /// Snap a point that is being dragged within a plane. Any snapping that
+    /// would move the point out of the plane is left out.
+    pub fn snap_in_plane(&self, point: Vec3, normal: Vec3) -> Vec3 {
+        let n = normal.normalize_or_zero();
+        let snapped = self.snap(point);
+        snapped - n * n.dot(snapped - point)
+    }
+}
+
+#[derive(Component)]
+pub struct ReferenceGridMarker;
+
+/// Rebuild the reference grid when its settings or the current level change
+pub fn update_reference_grid(
+    mut commands: Commands,
+    preferences: Res<Preferences>,
+    current_level: Option<Res<CurrentLevel>>,
+    existing: Query<Entity, With<ReferenceGridMarker>>,
+    mut polylines: ResMut<Assets<Polyline>>,
+    mut materials: ResMut<Assets<PolylineMaterial>>,
+    mut shown: Local<Option<(ReferenceGrid, Option<Entity>)>>,
+) {
+    let grid = preferences.grid;
+    let level = current_level.and_then(|level| level.0);
+    if *shown == Some((grid, level)) {
+        return;
+    }
+    *shown = Some((grid, level));
+
+    for e in &existing {
+        commands.entity(e).despawn_recursive();
+    }
+
+    let Some(level) = level else {
+        return;
+    };
+    if !grid.visible || grid.cell_size() <= 0.0 {
+        return;
+    }
+
+    let [r, g, b, a] = grid.color;
+    let subdivisions = grid.subdivisions.max(1);
+    let lines = make_finite_grid(
+        grid.cell_size(),
+        grid.extent * subdivisions,
+        Color::rgba(r, g, b, a),
+        [(1, 0.5), (subdivisions, 1.5)].into(),
+    );
+    let grid_entity = commands
+        .spawn(SpatialBundle {
+            transform: Transform::from_xyz(0.0, 0.0, REFERENCE_GRID_HEIGHT),
+            ..default()
+        })
+        .insert(ReferenceGridMarker)
+        .with_children(|parent| {
+            for (polyline, material) in lines {
+                parent.spawn(PolylineBundle {
+                    polyline: polylines.add(polyline),
+                    material: materials.add(material),
+                    ..default()
+                });
+            }
+        })
+        .id();
+    commands.entity(level).add_child(grid_entity);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapping_grid() -> ReferenceGrid {
+        ReferenceGrid {
+            visible: true,
+            snap: true,
+            spacing: 2.0,
+            subdivisions: 4,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn points_snap_to_the_nearest_crossing() {
+        let grid = snapping_grid();
+        let snapped = grid.snap(Vec3::new(0.74, -1.26, 0.3));
+        assert!((snapped - Vec3::new(0.5, -1.5, 0.3)).length() < 1e-6);
+        let snapped = grid.snap(Vec3::new(0.76, 2.01, 0.0));
+        assert!((snapped - Vec3::new(1.0, 2.0, 0.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn points_do_not_snap_unless_the_grid_is_visible_and_snapping() {
+        let point = Vec3::new(0.74, -1.26, 0.3);
+        let mut grid = snapping_grid();
+        grid.snap = false;
+        assert_eq!(grid.snap(point), point);
+
+        let mut grid = snapping_grid();
+        grid.visible = false;
+        assert_eq!(grid.snap(point), point);
+
+        let mut grid = snapping_grid();
+        grid.spacing = 0.0;
+        assert_eq!(grid.snap(point), point);
+    }
+
+    #[test]
+    fn zero_subdivisions_use_the_major_lines() {
+        let mut grid = snapping_grid();
+        grid.subdivisions = 0;
+        let snapped = grid.snap(Vec3::new(2.9, 3.1, 0.0));
+        assert!((snapped - Vec3::new(2.0, 4.0, 0.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn drags_along_an_axis_only_snap_along_that_axis() {
+        let grid = snapping_grid();
+        let point = Vec3::new(0.74, 0.1, 0.3);
+        let snapped = grid.snap_along(point, Vec3::X);
+        assert!((snapped - Vec3::new(0.5, 0.1, 0.3)).length() < 1e-6);
+        // The grid does not snap heights
+        assert_eq!(grid.snap_along(point, Vec3::Z), point);
+    }
+
+    #[test]
+    fn drags_in_a_plane_stay_in_the_plane() {
+        let grid = snapping_grid();
+        let point = Vec3::new(0.74, 0.1, 0.3);
+        let snapped = grid.snap_in_plane(point, Vec3::Z);
+        assert!((snapped - Vec3::new(0.5, 0.0, 0.3)).length() < 1e-6);
+        // A vertical plane facing along y only snaps along x
+        let snapped = grid.snap_in_plane(point, Vec3::Y);
+        assert!((snapped - Vec3::new(0.5, 0.1, 0.3)).length() < 1e-6);
+    }
+}
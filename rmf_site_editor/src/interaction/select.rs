@@ -91,6 +91,11 @@ impl Default for Hovered {
 #[derive(Default, Debug, Clone, Copy, Deref, DerefMut, Resource)]
 pub struct Selection(pub Option<Entity>);
 
+/// Used as a resource to keep track of the entities that are selected along
+/// with the entity in [`Selection`]. These are added by shift-clicking.
+#[derive(Default, Debug, Clone, Deref, DerefMut, Resource)]
+pub struct MultiSelection(pub Vec<Entity>);
+
 /// Used as a resource to keep track of which entity is currently hovered.
 #[derive(Default, Debug, Clone, Copy, Deref, DerefMut, Resource)]
 pub struct Hovering(pub Option<Entity>);
@@ -99,6 +104,11 @@ pub struct Hovering(pub Option<Entity>);
 #[derive(Default, Debug, Clone, Copy, Deref, DerefMut)]
 pub struct Select(pub Option<Entity>);
 
+/// Used as an event to add an entity to the selection, or remove it if it is
+/// already selected, without changing the rest of the selection.
+#[derive(Debug, Clone, Copy, Deref, DerefMut)]
+pub struct ToggleSelect(pub Entity);
+
 /// Used as an event to command a change in the hovered entity.
 #[derive(Default, Debug, Clone, Copy, Deref, DerefMut)]
 pub struct Hover(pub Option<Entity>);
@@ -202,7 +212,9 @@ pub fn maintain_hovered_entities(
     mut hover: EventReader<Hover>,
     mouse_button_input: Res<Input<MouseButton>>,
     touch_input: Res<Touches>,
+    keyboard_input: Res<Input<KeyCode>>,
     mut select: EventWriter<Select>,
+    mut toggle_select: EventWriter<ToggleSelect>,
     mode: Res<InteractionMode>,
     blockers: Option<Res<PickingBlockers>>,
 ) {
@@ -237,7 +249,12 @@ pub fn maintain_hovered_entities(
                     return;
                 }
             }
-            select.send(Select(Some(current_hovered)));
+            let shift = keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+            if shift && mode.is_inspecting() {
+                toggle_select.send(ToggleSelect(current_hovered));
+            } else {
+                select.send(Select(Some(current_hovered)));
+            }
         }
     }
 }
@@ -246,7 +263,9 @@ pub fn maintain_selected_entities(
     mode: Res<InteractionMode>,
     mut selected: Query<&mut Selected>,
     mut selection: ResMut<Selection>,
+    mut multi_selection: ResMut<MultiSelection>,
     mut select: EventReader<Select>,
+    mut toggle_select: EventReader<ToggleSelect>,
 ) {
     if !mode.is_inspecting() {
         // We only maintain the "selected" entity when we are in Inspect mode.
@@ -257,6 +276,13 @@ pub fn maintain_selected_entities(
     }
 
     if let Some(new_selection) = select.iter().last() {
+        // Selecting a single entity drops the rest of the selection
+        for previous_selection in multi_selection.drain(..) {
+            if let Ok(mut selected) = selected.get_mut(previous_selection) {
+                selected.is_selected = false;
+            }
+        }
+
         if selection.0 != new_selection.0 {
             if let Some(previous_selection) = selection.0 {
                 if let Ok(mut selected) = selected.get_mut(previous_selection) {
@@ -273,4 +299,28 @@ pub fn maintain_selected_entities(
             selection.0 = new_selection.0;
         }
     }
+    for ToggleSelect(e) in toggle_select.iter().copied() {
+        let now_selected = if selection.0.is_none() {
+            selection.0 = Some(e);
+            true
+        } else if selection.0 == Some(e) {
+            // Hand the inspector over to the next entity in the selection
+            selection.0 = if multi_selection.is_empty() {
+                None
+            } else {
+                Some(multi_selection.remove(0))
+            };
+            false
+        } else if let Some(i) = multi_selection.iter().position(|s| *s == e) {
+            multi_selection.remove(i);
+            false
+        } else {
+            multi_selection.push(e);
+            true
+        };
+
+        if let Ok(mut selected) = selected.get_mut(e) {
+            selected.is_selected = now_selected;
+        }
+    }
 }
@@ -16,15 +16,20 @@
 */
 
 use crate::{
+    console::report_error,
     interaction::*,
-    site::{Anchor, AnchorBundle, Category, Dependents, Original, PathBehavior, Pending},
+    site::{
+        primitive_base_offset, Anchor, AnchorBundle, Category, CurrentLevel, Dependents, Original,
+        PathBehavior, Pending, RecentModels,
+    },
     CurrentWorkspace,
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
 use rmf_site_format::{
     ConstraintDependents, Door, Edge, Floor, Lane, LiftProperties, Location, Measurement,
-    MeshConstraint, MeshElement, Model, ModelMarker, NameInWorkcell, Path, Point, Pose, Side,
-    SiteProperties, Wall, WorkcellCollisionMarker, WorkcellModel, WorkcellVisualMarker,
+    MeshConstraint, MeshElement, Model, ModelMarker, NameInWorkcell, Path, Point, Pose,
+    PrimitiveShape, Side, SiteProperties, Wall, WorkcellCollisionMarker, WorkcellModel,
+    WorkcellVisualMarker,
 };
 use std::sync::Arc;
 
@@ -164,9 +169,10 @@ impl TargetTransition {
         match e {
             Some(e) => {
                 if self.created.is_some() {
-                    println!(
+                    report_error(
+                        None,
                         "DEV ERROR: Created a superfluous target while in \
-                        SelectAnchor mode"
+                        SelectAnchor mode",
                     );
                 }
                 Some(e)
@@ -174,9 +180,10 @@ impl TargetTransition {
             None => match self.created {
                 Some(e) => Some(e),
                 None => {
-                    println!(
+                    report_error(
+                        None,
                         "DEV ERROR: Failed to create an entity while in \
-                            SelectAnchor mode"
+                            SelectAnchor mode",
                     );
                     None
                 }
@@ -276,7 +283,7 @@ impl AnchorSelection {
                     Ok(dep) => dep,
                     Err(_) => {
                         // The entity was not a proper anchor
-                        println!("DEV ERROR: Invalid anchor selected {:?}", e);
+                        report_error(None, format!("DEV ERROR: Invalid anchor selected {:?}", e));
                         return Err(());
                     }
                 };
@@ -301,7 +308,7 @@ impl AnchorSelection {
                 let mut deps = match params.dependents.get_mut(*e).map_err(|_| ()) {
                     Ok(dep) => dep,
                     Err(_) => {
-                        println!("DEV ERROR: Invalid anchor selected {:?}", e);
+                        report_error(None, format!("DEV ERROR: Invalid anchor selected {:?}", e));
                         return Err(());
                     }
                 };
@@ -394,10 +401,13 @@ impl EdgePlacement {
                         // Do nothing
                     }
                     Err(_) => {
-                        println!(
-                            "DEV ERROR: No AnchorDependents component found for \
-                            {:?} while in SelectAnchor mode.",
-                            old_anchor
+                        report_error(
+                            None,
+                            format!(
+                                "DEV ERROR: No AnchorDependents component found for \
+                                {:?} while in SelectAnchor mode.",
+                                old_anchor
+                            ),
                         );
                     }
                 }
@@ -435,10 +445,13 @@ impl Placement for EdgePlacement {
                 match params.edges.get_mut(target) {
                     Ok((edge, original)) => (target, edge, original),
                     Err(_) => {
-                        println!(
-                            "DEV ERROR: Entity {:?} is not the right kind of \
-                            element",
-                            target,
+                        report_error(
+                            None,
+                            format!(
+                                "DEV ERROR: Entity {:?} is not the right kind of \
+                                element",
+                                target
+                            ),
                         );
                         return Err(());
                     }
@@ -592,16 +605,22 @@ impl Placement for EdgePlacement {
                     Self::update_dependencies(None, target, old_edge, *edge, params)?;
                     return Ok((TargetTransition::finished(), self.to_start()).into());
                 } else {
-                    println!(
-                        "DEV ERROR: Unable to find original for {target:?} \
-                        while backing out of edge replacement"
+                    report_error(
+                        None,
+                        format!(
+                            "DEV ERROR: Unable to find original for {target:?} \
+                            while backing out of edge replacement"
+                        ),
                     );
                     return Err(());
                 }
             } else {
-                println!(
-                    "DEV ERROR: Unable to find edge for {target:?} while \
-                    backing out of edge replacement"
+                report_error(
+                    None,
+                    format!(
+                        "DEV ERROR: Unable to find edge for {target:?} while \
+                        backing out of edge replacement"
+                    ),
                 );
                 return Err(());
             }
@@ -678,10 +697,13 @@ impl Placement for PointPlacement {
                 let mut point = match params.points.get_mut(target) {
                     Ok(l) => l,
                     Err(_) => {
-                        println!(
-                            "DEV ERROR: Unable to get location {:?} while in \
-                            SelectAnchor mode.",
-                            target
+                        report_error(
+                            None,
+                            format!(
+                                "DEV ERROR: Unable to get location {:?} while in \
+                                SelectAnchor mode.",
+                                target
+                            ),
                         );
                         return Err(());
                     }
@@ -764,9 +786,12 @@ impl Placement for PointPlacement {
                 return Ok((TargetTransition::discontinued(), self.transition()).into());
             }
         } else {
-            println!(
-                "DEV ERROR: Cannot find point for location {target:?} while \
-                trying to back out of SelectAnchor mode"
+            report_error(
+                None,
+                format!(
+                    "DEV ERROR: Cannot find point for location {target:?} while \
+                    trying to back out of SelectAnchor mode"
+                ),
             );
             return Err(());
         }
@@ -862,9 +887,12 @@ impl Placement for PathPlacement {
         let (mut path, behavior) = match params.paths.get_mut(target) {
             Ok(q) => q,
             Err(_) => {
-                println!(
-                    "DEV ERROR: Unable to find path info for {target:?} while \
-                    in SelectAnchor mode."
+                report_error(
+                    None,
+                    format!(
+                        "DEV ERROR: Unable to find path info for {target:?} while \
+                        in SelectAnchor mode."
+                    ),
                 );
                 return Err(());
             }
@@ -932,10 +960,13 @@ impl Placement for PathPlacement {
         let path = match params.paths.get(target) {
             Ok(p) => p.0,
             Err(_) => {
-                println!(
-                    "DEV ERROR: Unable to find path for {:?} while in \
-                    SelectAnchor mode",
-                    target,
+                report_error(
+                    None,
+                    format!(
+                        "DEV ERROR: Unable to find path for {:?} while in \
+                        SelectAnchor mode",
+                        target
+                    ),
                 );
                 return None;
             }
@@ -952,10 +983,13 @@ impl Placement for PathPlacement {
         let path = match params.paths.get(target) {
             Ok(p) => p.0.clone(),
             Err(_) => {
-                println!(
-                    "DEV ERROR: Unable to find path for {:?} while in \
-                    SelectAnchor mode",
-                    target,
+                report_error(
+                    None,
+                    format!(
+                        "DEV ERROR: Unable to find path for {:?} while in \
+                        SelectAnchor mode",
+                        target
+                    ),
                 );
                 return None;
             }
@@ -988,18 +1022,22 @@ impl Placement for PathPlacement {
                     return Ok((TargetTransition::finished(), self.restart()).into());
                 }
 
-                println!(
-                    "DEV ERROR: Path of length {} is missing the index {} \
-                    that was supposed to be replaced.",
-                    path.len(),
-                    index
+                report_error(
+                    None,
+                    format!(
+                        "DEV ERROR: Path of length {} is missing the index {} \
+                        that was supposed to be replaced.",
+                        path.len(),
+                        index
+                    ),
                 );
                 return Err(());
             }
 
-            println!(
+            report_error(
+                None,
                 "DEV ERROR: Unable to find the placement of a path anchor \
-                that is being replaced."
+                that is being replaced.",
             );
             return Err(());
         }
@@ -1081,9 +1119,12 @@ impl<'w, 's> SelectAnchorPlacementParams<'w, 's> {
         let mut deps = match self.dependents.get_mut(to_anchor).map_err(|_| ()) {
             Ok(dep) => dep,
             Err(_) => {
-                println!(
-                    "DEV ERROR: Trying to insert invalid anchor \
-                    {to_anchor:?} into entity {dependent:?}"
+                report_error(
+                    None,
+                    format!(
+                        "DEV ERROR: Trying to insert invalid anchor \
+                        {to_anchor:?} into entity {dependent:?}"
+                    ),
                 );
                 return Err(());
             }
@@ -1107,9 +1148,12 @@ impl<'w, 's> SelectAnchorPlacementParams<'w, 's> {
         let mut deps = match self.dependents.get_mut(from_anchor).map_err(|_| ()) {
             Ok(dep) => dep,
             Err(_) => {
-                println!(
-                    "DEV ERROR: Removing invalid anchor {from_anchor:?} \
-                    from entity {dependent:?}"
+                report_error(
+                    None,
+                    format!(
+                        "DEV ERROR: Removing invalid anchor {from_anchor:?} \
+                        from entity {dependent:?}"
+                    ),
                 );
                 return Err(());
             }
@@ -1225,6 +1269,15 @@ impl SelectAnchorPointBuilder {
         }
     }
 
+    pub fn for_primitive_shape(self, shape: PrimitiveShape) -> SelectAnchor3D {
+        SelectAnchor3D {
+            bundle: PlaceableObject::PrimitiveShape(shape),
+            parent: None,
+            target: self.for_element,
+            continuity: self.continuity,
+        }
+    }
+
     pub fn for_visual(self, model: WorkcellModel) -> SelectAnchor3D {
         SelectAnchor3D {
             bundle: PlaceableObject::WorkcellVisual(model),
@@ -1432,9 +1485,10 @@ impl SelectAnchor {
                 params.commands.entity(finished_target).remove::<Pending>();
                 self.placement.finalize(finished_target, params);
             } else {
-                println!(
+                report_error(
+                    None,
                     "DEV ERROR: An element was supposed to be finished by \
-                    SelectAnchor, but we could not find it"
+                    SelectAnchor, but we could not find it",
                 );
             }
         }
@@ -1598,6 +1652,7 @@ impl SelectAnchor {
 #[derive(Clone)]
 enum PlaceableObject {
     Model(Model),
+    PrimitiveShape(PrimitiveShape),
     Anchor,
     WorkcellVisual(WorkcellModel),
     WorkcellCollision(WorkcellModel),
@@ -1665,10 +1720,13 @@ impl SelectAnchor3D {
             let (e, anchor) = match params.anchors.get_mut(target) {
                 Ok(l) => l,
                 Err(_) => {
-                    println!(
-                        "DEV ERROR: Unable to get anchor {:?} while \
-                        replacing 3D Anchor.",
-                        target
+                    report_error(
+                        None,
+                        format!(
+                            "DEV ERROR: Unable to get anchor {:?} while \
+                            replacing 3D Anchor.",
+                            target
+                        ),
                     );
                     return Err(());
                 }
@@ -1722,14 +1780,20 @@ impl SelectAnchor3D {
                         return Ok(());
                     }
                     None => {
-                        println!("DEV ERROR: Reassigning parent for entity without a parent");
+                        report_error(
+                            None,
+                            "DEV ERROR: Reassigning parent for entity without a parent",
+                        );
                         return Err(());
                     }
                 }
             }
             return Err(());
         } else {
-            println!("DEV error replacing anchor without original");
+            report_error(
+                Some(anchor_selection.entity()),
+                "DEV ERROR: Replacing anchor without original",
+            );
             return Err(());
         }
     }
@@ -1790,9 +1854,12 @@ impl SelectAnchor3D {
                         return Err(());
                     }
                 } else {
-                    println!(
-                        "DEV ERROR: Cannot find point for location {target:?} while \
-                        trying to back out of SelectAnchor mode"
+                    report_error(
+                        None,
+                        format!(
+                            "DEV ERROR: Cannot find point for location {target:?} while \
+                            trying to back out of SelectAnchor mode"
+                        ),
                     );
                     return Err(());
                 }
@@ -1902,9 +1969,10 @@ pub fn handle_select_anchor_mode(
             let for_element = match request.target {
                 Some(for_element) => for_element,
                 None => {
-                    println!(
+                    report_error(
+                        None,
                         "DEV ERROR: for_element must be Some for ReplaceAnchor. \
-                        Reverting to Inspect Mode."
+                        Reverting to Inspect Mode.",
                     );
                     params.cleanup();
                     *mode = InteractionMode::Inspect;
@@ -1915,10 +1983,13 @@ pub fn handle_select_anchor_mode(
             let original = match request.placement.save_original(for_element, &mut params) {
                 Some(original) => original,
                 None => {
-                    println!(
-                        "DEV ERROR: cannot locate an original anchor for \
-                        entity {:?}. Reverting to Inspect Mode.",
-                        for_element,
+                    report_error(
+                        None,
+                        format!(
+                            "DEV ERROR: cannot locate an original anchor for \
+                            entity {:?}. Reverting to Inspect Mode.",
+                            for_element
+                        ),
                     );
                     params.cleanup();
                     *mode = InteractionMode::Inspect;
@@ -1960,10 +2031,13 @@ pub fn handle_select_anchor_mode(
             let tf = match transforms.get(params.cursor.frame) {
                 Ok(tf) => tf,
                 Err(_) => {
-                    println!(
-                        "DEV ERROR: Could not get transform for cursor frame \
-                        {:?} in SelectAnchor mode.",
-                        params.cursor.frame,
+                    report_error(
+                        None,
+                        format!(
+                            "DEV ERROR: Could not get transform for cursor frame \
+                            {:?} in SelectAnchor mode.",
+                            params.cursor.frame
+                        ),
                     );
                     // TODO(MXG): Put in backout behavior here.
                     return;
@@ -2079,6 +2153,8 @@ pub fn handle_select_anchor_3d_mode(
     mut hover: EventWriter<Hover>,
     blockers: Option<Res<PickingBlockers>>,
     workspace: Res<CurrentWorkspace>,
+    current_level: Res<CurrentLevel>,
+    mut recent_models: ResMut<RecentModels>,
 ) {
     let mut request = match &*mode {
         InteractionMode::SelectAnchor3D(request) => request.clone(),
@@ -2119,6 +2195,12 @@ pub fn handle_select_anchor_3d_mode(
                     .cursor
                     .set_model_preview(&mut params.commands, Some(m.clone()));
             }
+            PlaceableObject::PrimitiveShape(ref s) => {
+                // Spawn the shape as a child of the cursor
+                params
+                    .cursor
+                    .set_primitive_shape_preview(&mut params.commands, Some(s.clone()));
+            }
             PlaceableObject::WorkcellVisual(ref m) | PlaceableObject::WorkcellCollision(ref m) => {
                 // Spawn the model as a child of the cursor
                 params
@@ -2195,9 +2277,23 @@ pub fn handle_select_anchor_3d_mode(
                         let mut model = a.clone();
                         let parent = workspace.root.expect("No workspace");
                         model.pose = compute_parent_inverse_pose(&cursor_tf, &transforms, parent);
+                        recent_models.push(model.source.clone(), model.scale.clone());
                         params.commands.entity(id).insert(model);
                         parent
                     }
+                    PlaceableObject::PrimitiveShape(ref a) => {
+                        println!("Creating primitive shape for entity {:?}", id);
+                        let mut shape = a.clone();
+                        // Shapes are saved as part of a level, so they need to
+                        // be placed relative to the current one.
+                        let parent = current_level
+                            .0
+                            .unwrap_or(workspace.root.expect("No workspace"));
+                        shape.pose = compute_parent_inverse_pose(&cursor_tf, &transforms, parent);
+                        shape.pose.trans[2] += primitive_base_offset(&shape.shape);
+                        params.commands.entity(id).insert(shape);
+                        parent
+                    }
                     PlaceableObject::WorkcellVisual(ref a) => {
                         println!("Creating visual for entity {:?}", id);
                         let mut model = a.clone();
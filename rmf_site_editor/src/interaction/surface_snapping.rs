@@ -0,0 +1,104 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{interaction::Selectable, site::primitive_base_offset};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_mod_raycast::{ray_intersection_over_mesh, Backfaces, Ray3d};
+use rmf_site_format::{FloorMarker, MeshPrimitive, ModelMarker, PrimitiveShapeMarker};
+
+/// Surfaces whose normal has less than this upward component are treated as
+/// walls or undersides of a model, which nothing can be placed on.
+pub const SURFACE_SNAPPING_MIN_NORMAL_Z: f32 = 0.7;
+
+/// When enabled, models and primitive shapes that are being placed or dragged
+/// will rest on the floor or on the top of the model beneath the cursor.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct SurfaceSnapping {
+    pub enabled: bool,
+}
+
+#[derive(SystemParam)]
+pub struct SurfaceSnappingParams<'w, 's> {
+    pub snapping: Res<'w, SurfaceSnapping>,
+    surfaces: Query<
+        'w,
+        's,
+        (
+            &'static Handle<Mesh>,
+            &'static GlobalTransform,
+            &'static Selectable,
+            &'static ComputedVisibility,
+        ),
+    >,
+    supports: Query<
+        'w,
+        's,
+        (),
+        Or<(
+            With<ModelMarker>,
+            With<PrimitiveShapeMarker>,
+            With<FloorMarker>,
+        )>,
+    >,
+    snappable: Query<'w, 's, (), Or<(With<ModelMarker>, With<PrimitiveShapeMarker>)>>,
+    shapes: Query<'w, 's, &'static MeshPrimitive, With<PrimitiveShapeMarker>>,
+    meshes: Res<'w, Assets<Mesh>>,
+}
+
+impl<'w, 's> SurfaceSnappingParams<'w, 's> {
+    /// Check if an element should be snapped to surfaces while it is moved
+    pub fn snaps(&self, e: Entity) -> bool {
+        self.snapping.enabled && self.snappable.contains(e)
+    }
+
+    /// Find where a ray first hits the top of a floor, model, or primitive
+    /// shape, ignoring the meshes that belong to the `ignore` element.
+    pub fn surface_hit(&self, ray: &Ray3d, ignore: Option<Entity>) -> Option<Vec3> {
+        let mut nearest: Option<(f32, Vec3)> = None;
+        for (mesh, tf, selectable, visibility) in &self.surfaces {
+            if Some(selectable.element) == ignore
+                || !visibility.is_visible()
+                || !self.supports.contains(selectable.element)
+            {
+                continue;
+            }
+            let Some(mesh) = self.meshes.get(mesh) else {
+                continue;
+            };
+            let Some(hit) =
+                ray_intersection_over_mesh(mesh, &tf.compute_matrix(), ray, Backfaces::Include)
+            else {
+                continue;
+            };
+            // Only surfaces that face upwards can hold something up. The
+            // underside of a shelf has a normal pointing down.
+            if hit.normal().z < SURFACE_SNAPPING_MIN_NORMAL_Z {
+                continue;
+            }
+            if nearest.map_or(true, |(d, _)| hit.distance() < d) {
+                nearest = Some((hit.distance(), hit.position()));
+            }
+        }
+        nearest.map(|(_, p)| p)
+    }
+
+    /// How far the origin of an element needs to be above a surface for the
+    /// element to rest on it
+    pub fn base_offset(&self, e: Entity) -> f32 {
+        self.shapes.get(e).map(primitive_base_offset).unwrap_or(0.0)
+    }
+}
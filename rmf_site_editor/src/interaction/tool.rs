@@ -0,0 +1,116 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{
+    interaction::{InteractionMode, SelectAnchor},
+    KeyAction,
+};
+use bevy::prelude::*;
+
+/// Used as a resource to keep track of the tool that was chosen from the
+/// toolbar. Each tool puts the editor into an interaction mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Resource)]
+pub enum Tool {
+    /// Click on elements to select them without dragging them
+    Select,
+    /// Select elements and drag them with their gizmos. This is the default
+    /// tool so elements can be dragged the same way as before the toolbar.
+    #[default]
+    Move,
+    /// Draw a sequence of lanes
+    Lane,
+    /// Draw a sequence of walls
+    Wall,
+    /// Draw a measurement between two anchors
+    Measure,
+}
+
+impl Tool {
+    pub const SITE: [Tool; 5] = [
+        Tool::Select,
+        Tool::Move,
+        Tool::Lane,
+        Tool::Wall,
+        Tool::Measure,
+    ];
+
+    pub const WORKCELL: [Tool; 2] = [Tool::Select, Tool::Move];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Tool::Select => "Select",
+            Tool::Move => "Move",
+            Tool::Lane => "Lane",
+            Tool::Wall => "Wall",
+            Tool::Measure => "Measure",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Tool::Select => "☝",
+            Tool::Move => "✥",
+            Tool::Lane => "↔",
+            Tool::Wall => "▬",
+            Tool::Measure => "📏",
+        }
+    }
+
+    /// The keyboard shortcut that chooses this tool
+    pub fn key_action(&self) -> KeyAction {
+        match self {
+            Tool::Select => KeyAction::SelectTool,
+            Tool::Move => KeyAction::MoveTool,
+            Tool::Lane => KeyAction::LaneTool,
+            Tool::Wall => KeyAction::WallTool,
+            Tool::Measure => KeyAction::MeasureTool,
+        }
+    }
+
+    /// The interaction mode that this tool starts
+    pub fn mode(&self) -> InteractionMode {
+        match self {
+            Tool::Select | Tool::Move => InteractionMode::Inspect,
+            Tool::Lane => SelectAnchor::create_new_edge_sequence().for_lane().into(),
+            Tool::Wall => SelectAnchor::create_new_edge_sequence().for_wall().into(),
+            Tool::Measure => SelectAnchor::create_one_new_edge().for_measurement().into(),
+        }
+    }
+
+    /// Whether this tool is still active while the editor is in an
+    /// interaction mode
+    pub fn is_active_in(&self, mode: &InteractionMode) -> bool {
+        match self {
+            Tool::Select | Tool::Move => mode.is_inspecting(),
+            Tool::Lane | Tool::Wall | Tool::Measure => mode.is_selecting_anchor(),
+        }
+    }
+
+    pub fn allows_dragging(&self) -> bool {
+        matches!(self, Tool::Move)
+    }
+}
+
+/// Go back to the default tool when the mode of a drawing tool is finished
+pub fn update_tool_for_mode(mode: Res<InteractionMode>, mut tool: ResMut<Tool>) {
+    if !mode.is_changed() {
+        return;
+    }
+
+    if mode.is_inspecting() && !tool.is_active_in(&mode) {
+        *tool = Tool::default();
+    }
+}
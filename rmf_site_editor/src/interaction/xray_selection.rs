@@ -0,0 +1,128 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::{ComputedVisualCue, Hovering, Selectable, Selection},
+    site::HiddenSurface,
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// How opaque the surfaces that are not being interacted with are drawn while
+/// x-ray selection is on
+pub const XRAY_SELECTION_ALPHA: f32 = 0.2;
+
+/// When enabled, every surface except the selected and hovered elements is
+/// drawn semi-transparent, and picking prefers lanes, anchors, and other
+/// visual cues over surfaces. This lets lanes and anchors that are hidden
+/// behind walls or inside of models be selected without hiding any layers.
+#[derive(Resource, Default, Debug, Clone, Copy, Deref, DerefMut)]
+pub struct XRaySelection(pub bool);
+
+/// Holds the original material of a mesh while x-ray selection draws it with
+/// a transparent copy
+#[derive(Component, Clone, Debug)]
+pub struct XRaySurface(pub Handle<StandardMaterial>);
+
+pub fn update_xray_selection(
+    mut commands: Commands,
+    xray: Res<XRaySelection>,
+    selection: Res<Selection>,
+    hovering: Res<Hovering>,
+    all_meshes: Query<Entity, With<Handle<Mesh>>>,
+    new_meshes: Query<Entity, Added<Handle<Mesh>>>,
+    new_materials: Query<Entity, (With<Handle<Mesh>>, Changed<Handle<StandardMaterial>>)>,
+    materials: Query<&Handle<StandardMaterial>>,
+    surfaces: Query<&XRaySurface>,
+    hidden: Query<(), With<HiddenSurface>>,
+    visual_cues: Query<(), With<ComputedVisualCue>>,
+    selectables: Query<&Selectable>,
+    parents: Query<&Parent>,
+    mut material_assets: ResMut<Assets<StandardMaterial>>,
+    // Transparent copies of the materials, keyed by the original material
+    mut copies: Local<HashMap<Handle<StandardMaterial>, Handle<StandardMaterial>>>,
+) {
+    let entities: Vec<Entity> =
+        if xray.is_changed() || (xray.0 && (selection.is_changed() || hovering.is_changed())) {
+            all_meshes.iter().collect()
+        } else if xray.0 {
+            new_meshes.iter().chain(new_materials.iter()).collect()
+        } else {
+            return;
+        };
+
+    if !xray.0 {
+        copies.clear();
+    }
+
+    let focused = [selection.0, hovering.0];
+    for e in entities {
+        // Visual cues like lanes and anchors are what x-ray selection is
+        // meant to reveal, so those stay opaque
+        if visual_cues.contains(e) {
+            continue;
+        }
+
+        let mut selectable = false;
+        let mut is_focused = false;
+        for a in std::iter::once(e).chain(AncestorIter::new(&parents, e)) {
+            if let Ok(s) = selectables.get(a) {
+                selectable = true;
+                is_focused |= focused.contains(&Some(s.element));
+            }
+            is_focused |= focused.contains(&Some(a));
+        }
+        if !selectable {
+            continue;
+        }
+
+        let transparent = xray.0 && !is_focused;
+        let material = materials.get(e).ok();
+        if transparent {
+            let Some(material) = material else {
+                continue;
+            };
+            if copies.values().any(|copy| copy == material) {
+                continue;
+            }
+
+            let copy = copies
+                .entry(material.clone())
+                .or_insert_with(|| {
+                    let mut copy = material_assets.get(material).cloned().unwrap_or_default();
+                    copy.base_color
+                        .set_a(copy.base_color.a().min(XRAY_SELECTION_ALPHA));
+                    copy.alpha_mode = AlphaMode::Blend;
+                    material_assets.add(copy)
+                })
+                .clone();
+            commands
+                .entity(e)
+                .insert(XRaySurface(material.clone()))
+                .insert(copy);
+        } else if let Ok(surface) = surfaces.get(e) {
+            if material.is_some() {
+                commands.entity(e).insert(surface.0.clone());
+            } else if hidden.contains(e) {
+                // The wireframe shading mode is holding on to the transparent
+                // copy, so give it the original material instead
+                commands.entity(e).insert(HiddenSurface(surface.0.clone()));
+            }
+            commands.entity(e).remove::<XRaySurface>();
+        }
+    }
+}
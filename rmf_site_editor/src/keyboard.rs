@@ -20,11 +20,18 @@ use crate::{
         camera_controls::{CameraControls, HeadlightToggle},
         ChangeMode, InteractionMode, Selection,
     },
+    localization::tr,
+    settings::{config_file, load_config, save_config},
     site::Delete,
     CreateNewWorkspace, LoadWorkspace, SaveWorkspace,
 };
 use bevy::prelude::*;
-use bevy_egui::EguiContext;
+use bevy_egui::{
+    egui::{Button, Context, Id, Response, Ui},
+    EguiContext,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
 
 #[derive(Debug, Clone, Copy, Resource)]
 pub struct DebugMode(pub bool);
@@ -35,17 +42,478 @@ impl FromWorld for DebugMode {
     }
 }
 
+/// Everything in the editor that can be triggered by a keyboard shortcut
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    NewWorkspace,
+    OpenWorkspace,
+    Save,
+    SaveAs,
+    ExportUrdf,
+    Backout,
+    DeleteSelection,
+    OrthographicCamera,
+    PerspectiveCamera,
+    ToggleVisuals,
+    ToggleCollisions,
+    ToggleDebugMode,
+    SelectTool,
+    MoveTool,
+    LaneTool,
+    WallTool,
+    MeasureTool,
+    Undo,
+    Redo,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 19] = [
+        KeyAction::NewWorkspace,
+        KeyAction::OpenWorkspace,
+        KeyAction::Save,
+        KeyAction::SaveAs,
+        KeyAction::ExportUrdf,
+        KeyAction::Backout,
+        KeyAction::DeleteSelection,
+        KeyAction::OrthographicCamera,
+        KeyAction::PerspectiveCamera,
+        KeyAction::ToggleVisuals,
+        KeyAction::ToggleCollisions,
+        KeyAction::ToggleDebugMode,
+        KeyAction::SelectTool,
+        KeyAction::MoveTool,
+        KeyAction::LaneTool,
+        KeyAction::WallTool,
+        KeyAction::MeasureTool,
+        KeyAction::Undo,
+        KeyAction::Redo,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            KeyAction::NewWorkspace => "New",
+            KeyAction::OpenWorkspace => "Open",
+            KeyAction::Save => "Save",
+            KeyAction::SaveAs => "Save As",
+            KeyAction::ExportUrdf => "Export urdf",
+            KeyAction::Backout => "Cancel / Back Out",
+            KeyAction::DeleteSelection => "Delete Selection",
+            KeyAction::OrthographicCamera => "Orthographic Camera",
+            KeyAction::PerspectiveCamera => "Perspective Camera",
+            KeyAction::ToggleVisuals => "Toggle Visuals (Workcell)",
+            KeyAction::ToggleCollisions => "Toggle Collisions (Workcell)",
+            KeyAction::ToggleDebugMode => "Toggle Debug Mode",
+            KeyAction::SelectTool => "Select Tool",
+            KeyAction::MoveTool => "Move Tool",
+            KeyAction::LaneTool => "Lane Tool (Site)",
+            KeyAction::WallTool => "Wall Tool (Site)",
+            KeyAction::MeasureTool => "Measure Tool (Site)",
+            KeyAction::Undo => "Undo",
+            KeyAction::Redo => "Redo",
+        }
+    }
+
+    pub fn default_bindings(&self) -> Vec<KeyBinding> {
+        match self {
+            KeyAction::NewWorkspace => vec![KeyBinding::new(KeyCode::N).ctrl()],
+            KeyAction::OpenWorkspace => vec![KeyBinding::new(KeyCode::O).ctrl()],
+            KeyAction::Save => vec![KeyBinding::new(KeyCode::S).ctrl()],
+            KeyAction::SaveAs => vec![KeyBinding::new(KeyCode::S).ctrl().shift()],
+            KeyAction::ExportUrdf => vec![KeyBinding::new(KeyCode::E).ctrl()],
+            KeyAction::Backout => vec![KeyBinding::new(KeyCode::Escape)],
+            KeyAction::DeleteSelection => vec![
+                KeyBinding::new(KeyCode::Delete),
+                KeyBinding::new(KeyCode::Back),
+            ],
+            KeyAction::OrthographicCamera => vec![KeyBinding::new(KeyCode::F2)],
+            KeyAction::PerspectiveCamera => vec![KeyBinding::new(KeyCode::F3)],
+            KeyAction::ToggleVisuals => vec![KeyBinding::new(KeyCode::V).shift()],
+            KeyAction::ToggleCollisions => vec![KeyBinding::new(KeyCode::C).shift()],
+            KeyAction::ToggleDebugMode => vec![KeyBinding::new(KeyCode::D)],
+            KeyAction::SelectTool => vec![KeyBinding::new(KeyCode::Q)],
+            KeyAction::MoveTool => vec![KeyBinding::new(KeyCode::G)],
+            KeyAction::LaneTool => vec![KeyBinding::new(KeyCode::L)],
+            KeyAction::WallTool => vec![KeyBinding::new(KeyCode::W)],
+            KeyAction::MeasureTool => vec![KeyBinding::new(KeyCode::M)],
+            KeyAction::Undo => vec![KeyBinding::new(KeyCode::Z).ctrl()],
+            KeyAction::Redo => vec![
+                KeyBinding::new(KeyCode::Z).ctrl().shift(),
+                KeyBinding::new(KeyCode::Y).ctrl(),
+            ],
+        }
+    }
+}
+
+/// Names of the keys that can be used in a key binding. These names are
+/// shown in the editor and used when saving the keymap.
+pub const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::Key0, "0"),
+    (KeyCode::Key1, "1"),
+    (KeyCode::Key2, "2"),
+    (KeyCode::Key3, "3"),
+    (KeyCode::Key4, "4"),
+    (KeyCode::Key5, "5"),
+    (KeyCode::Key6, "6"),
+    (KeyCode::Key7, "7"),
+    (KeyCode::Key8, "8"),
+    (KeyCode::Key9, "9"),
+    (KeyCode::A, "A"),
+    (KeyCode::B, "B"),
+    (KeyCode::C, "C"),
+    (KeyCode::D, "D"),
+    (KeyCode::E, "E"),
+    (KeyCode::F, "F"),
+    (KeyCode::G, "G"),
+    (KeyCode::H, "H"),
+    (KeyCode::I, "I"),
+    (KeyCode::J, "J"),
+    (KeyCode::K, "K"),
+    (KeyCode::L, "L"),
+    (KeyCode::M, "M"),
+    (KeyCode::N, "N"),
+    (KeyCode::O, "O"),
+    (KeyCode::P, "P"),
+    (KeyCode::Q, "Q"),
+    (KeyCode::R, "R"),
+    (KeyCode::S, "S"),
+    (KeyCode::T, "T"),
+    (KeyCode::U, "U"),
+    (KeyCode::V, "V"),
+    (KeyCode::W, "W"),
+    (KeyCode::X, "X"),
+    (KeyCode::Y, "Y"),
+    (KeyCode::Z, "Z"),
+    (KeyCode::F1, "F1"),
+    (KeyCode::F2, "F2"),
+    (KeyCode::F3, "F3"),
+    (KeyCode::F4, "F4"),
+    (KeyCode::F5, "F5"),
+    (KeyCode::F6, "F6"),
+    (KeyCode::F7, "F7"),
+    (KeyCode::F8, "F8"),
+    (KeyCode::F9, "F9"),
+    (KeyCode::F10, "F10"),
+    (KeyCode::F11, "F11"),
+    (KeyCode::F12, "F12"),
+    (KeyCode::Escape, "Escape"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Back, "Backspace"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Space, "Space"),
+    (KeyCode::Return, "Enter"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Minus, "-"),
+    (KeyCode::Equals, "="),
+    (KeyCode::Comma, ","),
+    (KeyCode::Period, "."),
+    (KeyCode::Slash, "/"),
+];
+
+/// A key along with the modifiers that need to be held while pressing it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(into = "String", try_from = "String")]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub const fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub const fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Make a binding out of a key that was just pressed and the modifiers
+    /// that are currently held. Returns None if the key cannot be bound.
+    pub fn from_input(key: KeyCode, input: &Input<KeyCode>) -> Option<Self> {
+        key_name(key)?;
+        let (ctrl, shift, alt) = modifiers(input);
+        Some(Self {
+            key,
+            ctrl,
+            shift,
+            alt,
+        })
+    }
+
+    /// Check if the key was just pressed while exactly the modifiers of this
+    /// binding are held
+    pub fn just_pressed(&self, input: &Input<KeyCode>) -> bool {
+        input.just_pressed(self.key) && modifiers(input) == (self.ctrl, self.shift, self.alt)
+    }
+
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.ctrl {
+            label += "Ctrl+";
+        }
+        if self.shift {
+            label += "Shift+";
+        }
+        if self.alt {
+            label += "Alt+";
+        }
+        label += key_name(self.key).unwrap_or("?");
+        label
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = text.split('+').collect();
+        // The + key itself is not bindable, so the last part is always the key
+        let key = parts.pop()?;
+        let key = KEY_NAMES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(key))?
+            .0;
+        let mut binding = Self::new(key);
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" => binding.ctrl = true,
+                "shift" => binding.shift = true,
+                "alt" => binding.alt = true,
+                _ => return None,
+            }
+        }
+        Some(binding)
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(binding: KeyBinding) -> Self {
+        binding.label()
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        KeyBinding::parse(&text).ok_or_else(|| format!("invalid key binding [{text}]"))
+    }
+}
+
+pub fn key_name(key: KeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(code, _)| *code == key)
+        .map(|(_, name)| *name)
+}
+
+fn modifiers(input: &Input<KeyCode>) -> (bool, bool, bool) {
+    (
+        input.any_pressed([KeyCode::LControl, KeyCode::RControl]),
+        input.any_pressed([KeyCode::LShift, KeyCode::RShift]),
+        input.any_pressed([KeyCode::LAlt, KeyCode::RAlt]),
+    )
+}
+
+/// The keyboard shortcuts of every action, which users can remap
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Keymap {
+    pub bindings: BTreeMap<KeyAction, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: KeyAction::ALL
+                .into_iter()
+                .map(|action| (action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn bindings_of(&self, action: KeyAction) -> &[KeyBinding] {
+        self.bindings
+            .get(&action)
+            .map(|b| b.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn just_pressed(&self, action: KeyAction, input: &Input<KeyCode>) -> bool {
+        self.bindings_of(action)
+            .iter()
+            .any(|binding| binding.just_pressed(input))
+    }
+
+    /// The text that menus show for the shortcut of an action
+    pub fn shortcut_text(&self, action: KeyAction) -> String {
+        self.bindings_of(action)
+            .first()
+            .map(|binding| binding.label())
+            .unwrap_or_default()
+    }
+
+    /// Hover text for a widget that does the same thing as an action, which
+    /// ends with the current shortcut of the action
+    pub fn hover_text(&self, action: KeyAction, text: &str) -> String {
+        let shortcut = self.shortcut_text(action);
+        if shortcut.is_empty() {
+            text.to_owned()
+        } else {
+            format!("{text} ({shortcut})")
+        }
+    }
+
+    fn id() -> Id {
+        Id::new("rmf_site_editor_keymap")
+    }
+
+    /// Keep the keymap in the egui memory so that any widget can name the
+    /// shortcut of its action without the keymap being passed down to it.
+    pub fn share(&self, ctx: &Context) {
+        ctx.data().insert_temp(Self::id(), self.clone());
+    }
+
+    /// The keymap that was shared with egui, or the default keymap if none
+    /// has been shared
+    pub fn of(ctx: &Context) -> Self {
+        ctx.data().get_temp(Self::id()).unwrap_or_default()
+    }
+
+    /// Find another action that uses the same binding
+    pub fn conflict(&self, action: KeyAction, binding: &KeyBinding) -> Option<KeyAction> {
+        self.bindings
+            .iter()
+            .find(|(other, bindings)| **other != action && bindings.contains(binding))
+            .map(|(other, _)| *other)
+    }
+
+    pub fn reset(&mut self, action: KeyAction) {
+        self.bindings.insert(action, action.default_bindings());
+    }
+
+    /// The file that the keymap is saved in
+    pub fn file() -> Option<PathBuf> {
+        config_file("keymap.json")
+    }
+
+    /// Load the keymap from the last session, if it was saved. Actions that
+    /// are missing from the saved keymap keep their default bindings.
+    pub fn load() -> Option<Self> {
+        let mut keymap: Self = load_config("keymap.json", "keymap")?;
+        for action in KeyAction::ALL {
+            keymap
+                .bindings
+                .entry(action)
+                .or_insert_with(|| action.default_bindings());
+        }
+        Some(keymap)
+    }
+
+    pub fn save(&self) {
+        save_config("keymap.json", "keymap", self);
+    }
+}
+
+/// The binding that is waiting for the user to press a key. The index may be
+/// one past the last binding of the action to add a new binding.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct KeyCapture(pub Option<(KeyAction, usize)>);
+
 pub struct KeyboardInputPlugin;
 
 impl Plugin for KeyboardInputPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DebugMode>()
-            .add_system(handle_keyboard_input);
+            .insert_resource(Keymap::load().unwrap_or_default())
+            .init_resource::<KeyCapture>()
+            .add_system(handle_keyboard_input)
+            .add_system(capture_key_binding.after(handle_keyboard_input))
+            .add_system(save_keymap)
+            .add_system(share_keymap);
+    }
+}
+
+fn share_keymap(mut egui_context: ResMut<EguiContext>, keymap: Res<Keymap>) {
+    if keymap.is_changed() {
+        keymap.share(egui_context.ctx_mut());
+    }
+}
+
+/// A menu button for an action, which shows the shortcut of the action next
+/// to its label and in its hover text
+pub fn action_button(ui: &mut Ui, action: KeyAction, label: &str, hover: &str) -> Response {
+    let shortcut = Keymap::of(ui.ctx()).shortcut_text(action);
+    let response = ui.add(Button::new(tr(ui, label)).shortcut_text(shortcut));
+    on_hover_shortcut(response, action, hover)
+}
+
+/// Show hover text on a widget that does the same thing as an action. The
+/// shortcut that is bound to the action is looked up from the shared keymap
+/// when the widget is hovered, so it always matches the actual bindings.
+pub fn on_hover_shortcut(response: Response, action: KeyAction, text: &str) -> Response {
+    response.on_hover_ui(|ui| {
+        let text = tr(ui, text);
+        ui.label(Keymap::of(ui.ctx()).hover_text(action, &text));
+    })
+}
+
+pub fn capture_key_binding(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut capture: ResMut<KeyCapture>,
+    mut keymap: ResMut<Keymap>,
+) {
+    let Some((action, index)) = capture.0 else {
+        return;
+    };
+    let binding = keyboard_input
+        .get_just_pressed()
+        .find_map(|key| KeyBinding::from_input(*key, &keyboard_input));
+    let Some(binding) = binding else {
+        return;
+    };
+
+    let bindings = keymap.bindings.entry(action).or_default();
+    if index < bindings.len() {
+        bindings[index] = binding;
+    } else if !bindings.contains(&binding) {
+        bindings.push(binding);
+    }
+    capture.0 = None;
+}
+
+pub fn save_keymap(keymap: Res<Keymap>) {
+    if keymap.is_changed() && !keymap.is_added() {
+        keymap.save();
     }
 }
 
 fn handle_keyboard_input(
     keyboard_input: Res<Input<KeyCode>>,
+    keymap: Res<Keymap>,
+    capture: Res<KeyCapture>,
     selection: Res<Selection>,
     current_mode: Res<InteractionMode>,
     mut camera_controls: ResMut<CameraControls>,
@@ -65,23 +533,27 @@ fn handle_keyboard_input(
         || egui_context.wants_keyboard_input()
         || egui_context.is_pointer_over_area();
 
-    if ui_has_focus {
+    // Keys that are pressed while a binding is being captured only change
+    // the keymap
+    if ui_has_focus || capture.0.is_some() {
         return;
     }
 
-    if keyboard_input.just_pressed(KeyCode::F2) {
+    let pressed = |action| keymap.just_pressed(action, &keyboard_input);
+
+    if pressed(KeyAction::OrthographicCamera) {
         camera_controls.use_orthographic(true, &mut cameras, &mut visibilities, headlight_toggle.0);
     }
 
-    if keyboard_input.just_pressed(KeyCode::F3) {
+    if pressed(KeyAction::PerspectiveCamera) {
         camera_controls.use_perspective(true, &mut cameras, &mut visibilities, headlight_toggle.0);
     }
 
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if pressed(KeyAction::Backout) {
         change_mode.send(ChangeMode::Backout);
     }
 
-    if keyboard_input.just_pressed(KeyCode::Delete) || keyboard_input.just_pressed(KeyCode::Back) {
+    if pressed(KeyAction::DeleteSelection) {
         if current_mode.is_inspecting() {
             if let Some(selection) = selection.0 {
                 delete.send(Delete::new(selection));
@@ -91,29 +563,26 @@ fn handle_keyboard_input(
         }
     }
 
-    if keyboard_input.just_pressed(KeyCode::D) {
+    if pressed(KeyAction::ToggleDebugMode) {
         debug_mode.0 = !debug_mode.0;
         println!("Toggling debug mode: {debug_mode:?}");
     }
 
-    // Ctrl keybindings
-    if keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
-        if keyboard_input.just_pressed(KeyCode::S) {
-            if keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]) {
-                save_workspace.send(SaveWorkspace::new().to_dialog());
-            } else {
-                save_workspace.send(SaveWorkspace::new().to_default_file());
-            }
-        }
+    if pressed(KeyAction::Save) {
+        save_workspace.send(SaveWorkspace::new().to_default_file());
+    }
 
-        // TODO(luca) pop up a confirmation prompt if the current file is not saved, or create a
-        // gui to switch between open workspaces
-        if keyboard_input.just_pressed(KeyCode::N) {
-            new_workspace.send(CreateNewWorkspace);
-        }
+    if pressed(KeyAction::SaveAs) {
+        save_workspace.send(SaveWorkspace::new().to_dialog());
+    }
 
-        if keyboard_input.just_pressed(KeyCode::O) {
-            load_workspace.send(LoadWorkspace::Dialog);
-        }
+    // TODO(luca) pop up a confirmation prompt if the current file is not saved, or create a
+    // gui to switch between open workspaces
+    if pressed(KeyAction::NewWorkspace) {
+        new_workspace.send(CreateNewWorkspace);
+    }
+
+    if pressed(KeyAction::OpenWorkspace) {
+        load_workspace.send(LoadWorkspace::Dialog);
     }
 }
@@ -1,6 +1,4 @@
-use bevy::{
-    log::LogPlugin, pbr::DirectionalLightShadowMap, prelude::*, render::renderer::RenderAdapterInfo,
-};
+use bevy::{log::LogPlugin, prelude::*, render::renderer::RenderAdapterInfo};
 use bevy_egui::EguiPlugin;
 use main_menu::MainMenuPlugin;
 // use warehouse_generator::WarehouseGeneratorPlugin;
@@ -22,8 +20,23 @@ use keyboard::*;
 
 mod settings;
 use settings::*;
+
+mod preferences;
+use preferences::*;
+
+mod units;
+use units::*;
+
+mod localization;
+use localization::*;
+
+pub mod console;
+use console::ConsolePlugin;
+
 mod save;
 use save::*;
+mod file_watch;
+use file_watch::*;
 mod widgets;
 use widgets::*;
 
@@ -31,6 +44,8 @@ pub mod occupancy;
 use occupancy::OccupancyPlugin;
 
 mod demo_world;
+mod drag_and_drop;
+use drag_and_drop::DragAndDropPlugin;
 mod recency;
 mod shapes;
 
@@ -46,10 +61,12 @@ mod workspace;
 use workspace::*;
 
 mod sdf_loader;
+mod workcell_loader;
 
 mod site_asset_io;
 mod urdf_loader;
 use sdf_loader::*;
+use workcell_loader::*;
 
 use aabb::AabbUpdatePlugin;
 use animate::AnimationPlugin;
@@ -65,6 +82,18 @@ struct CommandLineArgs {
     /// Name of a Site (.site.ron) file to import on top of the base FILENAME.
     #[cfg_attr(not(target_arch = "wasm32"), arg(short, long))]
     import: Option<String>,
+    /// Export the door and lift supervisor config of FILENAME to this file
+    /// and exit without opening the editor.
+    #[cfg_attr(not(target_arch = "wasm32"), arg(long))]
+    export_supervisors: Option<String>,
+    /// Start in offline mode, which only loads assets that are on this
+    /// computer or in the remote asset cache.
+    #[cfg_attr(not(target_arch = "wasm32"), arg(long))]
+    offline: bool,
+    /// Start with the low, medium, or high graphics preset instead of the
+    /// render settings from the last session.
+    #[cfg_attr(not(target_arch = "wasm32"), arg(long))]
+    graphics_preset: Option<String>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
@@ -92,7 +121,11 @@ fn check_browser_window_size(mut windows: ResMut<Windows>) {
     }
 }
 
-fn init_settings(mut settings: ResMut<Settings>, adapter_info: Res<RenderAdapterInfo>) {
+fn init_settings(
+    mut settings: ResMut<Settings>,
+    mut render_settings: ResMut<RenderSettings>,
+    adapter_info: Res<RenderAdapterInfo>,
+) {
     // todo: be more sophisticated
     let is_elite = adapter_info.name.contains("NVIDIA");
     if is_elite {
@@ -100,6 +133,11 @@ fn init_settings(mut settings: ResMut<Settings>, adapter_info: Res<RenderAdapter
     } else {
         settings.graphics_quality = GraphicsQuality::Low;
     }
+    *render_settings = match settings.graphics_preset {
+        Some(preset) => RenderSettings::preset(preset),
+        None => RenderSettings::load()
+            .unwrap_or_else(|| RenderSettings::preset((&settings.graphics_quality).into())),
+    };
 }
 
 #[wasm_bindgen]
@@ -113,6 +151,32 @@ pub fn run(command_line_args: Vec<String>) {
     #[cfg(not(target_arch = "wasm32"))]
     {
         let command_line_args = CommandLineArgs::parse_from(command_line_args);
+        if command_line_args.offline {
+            site_asset_io::set_offline_mode(true);
+        }
+        if let Some(name) = &command_line_args.graphics_preset {
+            match GraphicsPreset::from_name(name) {
+                Some(preset) => {
+                    app.insert_resource(Settings {
+                        graphics_preset: Some(preset),
+                        ..default()
+                    });
+                }
+                None => println!("Unknown graphics preset [{name}], use low, medium, or high"),
+            }
+        }
+        if let Some(export_file) = command_line_args.export_supervisors {
+            let Some(path) = command_line_args.filename else {
+                println!("A FILENAME is required to export a supervisor config");
+                return;
+            };
+            match export_supervisor_config_headless(&path.into(), &export_file.into()) {
+                Ok(()) => println!("Export successful"),
+                Err(err) => println!("Failed to export supervisor config: {err}"),
+            }
+            return;
+        }
+
         if let Some(path) = command_line_args.filename {
             app.insert_resource(Autoload::file(
                 path.into(),
@@ -164,13 +228,22 @@ pub fn run(command_line_args: Vec<String>) {
     }
 
     app.init_resource::<Settings>()
+        .init_resource::<RenderSettings>()
         .add_startup_system(init_settings)
-        .insert_resource(DirectionalLightShadowMap { size: 2048 })
+        .add_system(apply_render_settings)
+        .add_system_to_stage(CoreStage::PreUpdate, limit_texture_sizes)
+        .add_system(update_shadow_distance)
+        .add_system(apply_post_processing)
+        .add_plugin(ConsolePlugin)
         .add_plugin(AabbUpdatePlugin)
         .add_plugin(EguiPlugin)
         .add_plugin(KeyboardInputPlugin)
+        .add_plugin(PreferencesPlugin)
+        .add_plugin(LocalizationPlugin)
         .add_plugin(SavePlugin)
+        .add_plugin(FileWatchPlugin)
         .add_plugin(SdfPlugin)
+        .add_plugin(WorkcellAssetPlugin)
         .add_state(AppState::MainMenu)
         .add_plugin(MainMenuPlugin)
         // .add_plugin(WarehouseGeneratorPlugin)
@@ -181,5 +254,6 @@ pub fn run(command_line_args: Vec<String>) {
         .add_plugin(AnimationPlugin)
         .add_plugin(OccupancyPlugin)
         .add_plugin(WorkspacePlugin)
+        .add_plugin(DragAndDropPlugin)
         .run();
 }
@@ -0,0 +1,293 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{console::notify_error, settings::config_file, Preferences};
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{Context, FontData, FontDefinitions, FontFamily, Id, Ui},
+    EguiContext,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// Extensions of the font files that can be provided alongside a translation
+/// bundle, for languages whose characters are missing from the default fonts
+pub const TRANSLATION_FONT_EXTENSIONS: [&str; 2] = ["ttf", "otf"];
+
+/// The folder that translation bundles are loaded from. Each bundle is a JSON
+/// object named after its language, e.g. zh-CN.json, that maps the English
+/// text of the editor to its translation. A font with the same name, e.g.
+/// zh-CN.ttf, will be used for any characters that the default fonts lack.
+pub fn translations_dir() -> Option<PathBuf> {
+    config_file("translations")
+}
+
+/// The languages that have a bundle in the translations folder
+pub fn available_languages() -> Vec<String> {
+    let Some(entries) = translations_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut languages: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let language = name.strip_suffix(".json")?;
+            // Skip the files that untranslated text is exported to
+            (!language.contains('.')).then(|| language.to_owned())
+        })
+        .collect();
+    languages.sort();
+    languages
+}
+
+/// The translation of the editor into one language. Text without a
+/// translation is shown in English.
+#[derive(Clone, Default)]
+pub struct Translations {
+    pub language: Option<String>,
+    strings: Arc<HashMap<String, String>>,
+    /// Text that was shown without a translation, so it can be exported for
+    /// translators
+    missing: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl Translations {
+    /// Load the bundle of a language from the translations folder
+    pub fn load(language: &str) -> Result<Self, String> {
+        let dir = translations_dir().ok_or("Unable to find the translations folder")?;
+        let path = dir.join(format!("{language}.json"));
+        let bytes = std::fs::read(&path).map_err(|err| format!("{path:?}: {err}"))?;
+        let strings: HashMap<String, String> =
+            serde_json::from_slice(&bytes).map_err(|err| format!("{path:?}: {err}"))?;
+        Ok(Self {
+            language: Some(language.to_owned()),
+            strings: Arc::new(strings),
+            missing: Default::default(),
+        })
+    }
+
+    /// The font provided with the bundle of this language, if there is one
+    pub fn font(&self) -> Option<Vec<u8>> {
+        let language = self.language.as_ref()?;
+        let dir = translations_dir()?;
+        TRANSLATION_FONT_EXTENSIONS
+            .iter()
+            .find_map(|ext| std::fs::read(dir.join(format!("{language}.{ext}"))).ok())
+    }
+
+    pub fn get(&self, text: &str) -> String {
+        if self.language.is_none() {
+            return text.to_owned();
+        }
+        match self.strings.get(text) {
+            Some(translated) if !translated.is_empty() => translated.clone(),
+            _ => {
+                if let Ok(mut missing) = self.missing.lock() {
+                    if !missing.contains(text) {
+                        missing.insert(text.to_owned());
+                    }
+                }
+                text.to_owned()
+            }
+        }
+    }
+
+    /// Translate text that has {name} placeholders and then fill them in
+    pub fn get_args(&self, text: &str, args: &[(&str, String)]) -> String {
+        let mut translated = self.get(text);
+        for (name, value) in args {
+            translated = translated.replace(&format!("{{{name}}}"), value);
+        }
+        translated
+    }
+
+    /// Save the text that has been shown without a translation this session
+    /// next to the bundle, e.g. as zh-CN.untranslated.json, with empty
+    /// translations for translators to fill in
+    pub fn export_missing(&self) -> Result<PathBuf, String> {
+        let language = self.language.as_ref().ok_or("No language is chosen")?;
+        let dir = translations_dir().ok_or("Unable to find the translations folder")?;
+        let missing: BTreeMap<String, String> = self
+            .missing
+            .lock()
+            .map_err(|err| err.to_string())?
+            .iter()
+            .map(|text| (text.clone(), String::new()))
+            .collect();
+        let path = dir.join(format!("{language}.untranslated.json"));
+        let bytes = serde_json::to_vec_pretty(&missing).map_err(|err| err.to_string())?;
+        std::fs::write(&path, bytes).map_err(|err| format!("{path:?}: {err}"))?;
+        Ok(path)
+    }
+
+    fn id() -> Id {
+        Id::new("rmf_site_editor_translations")
+    }
+
+    /// Keep the translations in the egui memory so that any widget can find
+    /// them without them being passed down through every widget.
+    pub fn share(self, ctx: &Context) {
+        ctx.data().insert_temp(Self::id(), self);
+    }
+
+    /// The translations that were shared with egui, or English if none have
+    /// been shared
+    pub fn of(ctx: &Context) -> Self {
+        ctx.data().get_temp(Self::id()).unwrap_or_default()
+    }
+}
+
+/// Translate text into the language that was chosen in the preferences
+pub fn tr(ui: &Ui, text: &str) -> String {
+    Translations::of(ui.ctx()).get(text)
+}
+
+/// Translate text that has {name} placeholders and then fill them in. The
+/// placeholders are part of the text that gets translated, so translators
+/// can move them to wherever the language needs them.
+pub fn tr_args(ui: &Ui, text: &str, args: &[(&str, String)]) -> String {
+    Translations::of(ui.ctx()).get_args(text, args)
+}
+
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(apply_language);
+    }
+}
+
+fn apply_language(
+    mut egui_context: ResMut<EguiContext>,
+    preferences: Res<Preferences>,
+    mut applied: Local<Option<Option<String>>>,
+) {
+    if applied.as_ref() == Some(&preferences.language) {
+        return;
+    }
+    *applied = Some(preferences.language.clone());
+
+    let translations = match &preferences.language {
+        Some(language) => match Translations::load(language) {
+            Ok(translations) => translations,
+            Err(err) => {
+                notify_error(
+                    None,
+                    format!("Unable to load the translation for {language}: {err}"),
+                );
+                Translations::default()
+            }
+        },
+        None => Translations::default(),
+    };
+
+    let mut fonts = FontDefinitions::default();
+    if let Some(font) = translations.font() {
+        let name = "translation".to_owned();
+        fonts
+            .font_data
+            .insert(name.clone(), FontData::from_owned(font));
+        for family in [FontFamily::Proportional, FontFamily::Monospace] {
+            if let Some(fallbacks) = fonts.families.get_mut(&family) {
+                fallbacks.push(name.clone());
+            }
+        }
+    }
+
+    let ctx = egui_context.ctx_mut();
+    ctx.set_fonts(fonts);
+    translations.share(ctx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn german() -> Translations {
+        let strings = [
+            ("Save", "Speichern"),
+            ("Untranslated", ""),
+            ("{count} of {max} remembered", "{count} von {max} gemerkt"),
+            ("Move {name} to {level}", "{name} nach {level} verschieben"),
+        ]
+        .into_iter()
+        .map(|(text, translated)| (text.to_owned(), translated.to_owned()))
+        .collect();
+        Translations {
+            language: Some("de".to_owned()),
+            strings: Arc::new(strings),
+            missing: Default::default(),
+        }
+    }
+
+    fn missing(translations: &Translations) -> Vec<String> {
+        translations
+            .missing
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn english_is_shown_as_it_is() {
+        let english = Translations::default();
+        assert_eq!(english.get("Save"), "Save");
+        assert!(missing(&english).is_empty());
+    }
+
+    #[test]
+    fn text_is_translated() {
+        let german = german();
+        assert_eq!(german.get("Save"), "Speichern");
+        assert!(missing(&german).is_empty());
+    }
+
+    #[test]
+    fn missing_translations_fall_back_to_english() {
+        let german = german();
+        assert_eq!(german.get("Open"), "Open");
+        assert_eq!(german.get("Open"), "Open");
+        // An empty translation has not been filled in by a translator yet
+        assert_eq!(german.get("Untranslated"), "Untranslated");
+        assert_eq!(missing(&german), ["Open", "Untranslated"]);
+    }
+
+    #[test]
+    fn placeholders_are_filled_in_after_translating() {
+        let german = german();
+        let args = [("count", "3".to_owned()), ("max", "10".to_owned())];
+        assert_eq!(
+            german.get_args("{count} of {max} remembered", &args),
+            "3 von 10 gemerkt"
+        );
+        // Translators can move the placeholders
+        let args = [("name", "L1".to_owned()), ("level", "L2".to_owned())];
+        assert_eq!(
+            german.get_args("Move {name} to {level}", &args),
+            "L1 nach L2 verschieben"
+        );
+        assert_eq!(
+            Translations::default().get_args("{count} of {max} remembered", &[]),
+            "{count} of {max} remembered"
+        );
+    }
+}
@@ -16,11 +16,21 @@
 */
 
 use super::demo_world::*;
-use crate::{AppState, LoadWorkspace, WorkspaceData};
+use crate::{
+    console::notify_error, localization::tr, workcell::WorkcellTemplateLibrary, AppState,
+    LoadWorkspace, Preferences, WorkspaceData,
+};
 use bevy::{app::AppExit, prelude::*, tasks::Task};
 use bevy_egui::{egui, EguiContext};
+use rmf_site_format::Site;
 use std::path::PathBuf;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    autosave_path, console::notify_warning, has_newer_autosave, localization::Translations,
+    widgets::view_preferences::show_recent_files,
+};
+
 #[derive(Resource)]
 pub struct Autoload {
     pub filename: Option<PathBuf>,
@@ -44,6 +54,8 @@ fn egui_ui(
     mut _load_workspace: EventWriter<LoadWorkspace>,
     mut _app_state: ResMut<State<AppState>>,
     autoload: Option<ResMut<Autoload>>,
+    mut _preferences: ResMut<Preferences>,
+    _templates: Res<WorkcellTemplateLibrary>,
 ) {
     if let Some(mut autoload) = autoload {
         #[cfg(not(target_arch = "wasm32"))]
@@ -62,34 +74,99 @@ fn egui_ui(
         .title_bar(false)
         .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0., 0.))
         .show(egui_context.ctx_mut(), |ui| {
-            ui.heading("Welcome to The RMF Site Editor!");
+            ui.heading(tr(ui, "Welcome to The RMF Site Editor!"));
             ui.add_space(10.);
 
-            ui.horizontal(|ui| {
-                if ui.button("View demo map").clicked() {
-                    _load_workspace.send(LoadWorkspace::Data(WorkspaceData::LegacyBuilding(
-                        demo_office(),
-                    )));
-                }
+            ui.horizontal_top(|ui| {
+                ui.vertical(|ui| {
+                    ui.strong(tr(ui, "Start"));
+                    if ui
+                        .button(tr(ui, "New blank site"))
+                        .on_hover_text(tr(ui, "Start a site with a single empty level"))
+                        .clicked()
+                    {
+                        match Site::default().to_string() {
+                            Ok(site) => {
+                                _load_workspace.send(LoadWorkspace::Data(WorkspaceData::Site(
+                                    site.into_bytes(),
+                                )));
+                            }
+                            Err(err) => {
+                                notify_error(None, format!("Unable to create a blank site: {err}"));
+                            }
+                        }
+                    }
 
-                if ui.button("Open a file").clicked() {
-                    _load_workspace.send(LoadWorkspace::Dialog);
-                }
+                    if ui.button(tr(ui, "Open a file")).clicked() {
+                        _load_workspace.send(LoadWorkspace::Dialog);
+                    }
+
+                    // TODO(@mxgrey): Bring this back when we have time to fix the
+                    // warehouse generator.
+                    // if ui.button("Warehouse generator").clicked() {
+                    //     println!("Entering warehouse generator");
+                    //     _app_state.set(AppState::WarehouseGenerator).unwrap();
+                    // }
+                });
+
+                ui.separator();
+                ui.vertical(|ui| {
+                    ui.strong(tr(ui, "Templates"));
+                    if ui
+                        .button(tr(ui, "Office demo"))
+                        .on_hover_text(tr(ui, "A small office with lanes, doors, and lifts"))
+                        .clicked()
+                    {
+                        _load_workspace.send(LoadWorkspace::Data(WorkspaceData::LegacyBuilding(
+                            demo_office(),
+                        )));
+                    }
+
+                    // TODO(@mxgrey): Bring this back when we have finished developing
+                    // the key features for workcell editing.
+                    // if ui.button("Workcell Editor").clicked() {
+                    //     _load_workspace.send(LoadWorkspace::Data(WorkspaceData::Workcell(
+                    //         demo_workcell(),
+                    //     )));
+                    // }
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    for (name, path) in &_templates.templates {
+                        if ui
+                            .button(name)
+                            .on_hover_text(path.to_string_lossy())
+                            .clicked()
+                        {
+                            // Templates are loaded as data so that saving the
+                            // new workcell does not overwrite the template
+                            match std::fs::read(path) {
+                                Ok(data) => {
+                                    _load_workspace
+                                        .send(LoadWorkspace::Data(WorkspaceData::Workcell(data)));
+                                }
+                                Err(err) => {
+                                    notify_error(
+                                        None,
+                                        format!("Unable to open template {path:?}: {err}"),
+                                    );
+                                }
+                            }
+                        }
+                    }
+                });
 
-                // TODO(@mxgrey): Bring this back when we have finished developing
-                // the key features for workcell editing.
-                // if ui.button("Workcell Editor").clicked() {
-                //     _load_workspace.send(LoadWorkspace::Data(WorkspaceData::Workcell(
-                //         demo_workcell(),
-                //     )));
-                // }
-
-                // TODO(@mxgrey): Bring this back when we have time to fix the
-                // warehouse generator.
-                // if ui.button("Warehouse generator").clicked() {
-                //     println!("Entering warehouse generator");
-                //     _app_state.set(AppState::WarehouseGenerator).unwrap();
-                // }
+                // Files cannot be reopened from their paths in a browser
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        ui.strong(tr(ui, "Recent files"));
+                        let action = show_recent_files(ui, &_preferences);
+                        if let Some(file) = action.and_then(|a| a.apply(&mut _preferences)) {
+                            _load_workspace.send(LoadWorkspace::Path(file));
+                        }
+                    });
+                }
             });
 
             #[cfg(not(target_arch = "wasm32"))]
@@ -97,7 +174,7 @@ fn egui_ui(
                 ui.add_space(20.);
                 ui.horizontal(|ui| {
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.button("Exit").clicked() {
+                        if ui.button(tr(ui, "Exit")).clicked() {
                             _exit.send(AppExit);
                         }
                     });
@@ -106,10 +183,110 @@ fn egui_ui(
         });
 }
 
+/// Recent files whose autosave is newer than their last save. The user is
+/// asked what to do with each of them when the editor starts.
+#[derive(Resource, Default)]
+pub struct AutosaveRecovery {
+    pub files: Vec<PathBuf>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn find_recoverable_autosaves(mut commands: Commands, preferences: Res<Preferences>) {
+    let files = preferences
+        .recent_files
+        .iter()
+        .filter(|file| has_newer_autosave(file))
+        .cloned()
+        .collect();
+    commands.insert_resource(AutosaveRecovery { files });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn autosave_recovery_prompt(
+    mut egui_context: ResMut<EguiContext>,
+    mut recovery: ResMut<AutosaveRecovery>,
+    mut load_workspace: EventWriter<LoadWorkspace>,
+    autoload: Option<Res<Autoload>>,
+) {
+    if recovery.files.is_empty() || autoload.is_some() {
+        return;
+    }
+
+    let mut restore = None;
+    let mut dismissed = None;
+    let title = Translations::of(egui_context.ctx_mut()).get("Recover Unsaved Work");
+    egui::Window::new(title)
+        .id(egui::Id::new("autosave_recovery"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0., 40.))
+        .show(egui_context.ctx_mut(), |ui| {
+            ui.label(tr(
+                ui,
+                "The editor closed before these files were saved. \
+                Their autosaves have changes that are newer than the files.",
+            ));
+            ui.add_space(10.);
+            for (i, file) in recovery.files.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(file.to_string_lossy());
+                    if ui
+                        .button(tr(ui, "Restore"))
+                        .on_hover_text(tr(ui, "Open the file with the changes from its autosave"))
+                        .clicked()
+                    {
+                        restore = Some(i);
+                    }
+                    if ui
+                        .button(tr(ui, "Discard"))
+                        .on_hover_text(tr(ui, "Delete the autosave of this file"))
+                        .clicked()
+                    {
+                        let autosave = autosave_path(file);
+                        if let Err(err) = std::fs::remove_file(&autosave) {
+                            notify_warning(
+                                None,
+                                format!("Unable to remove autosave {autosave:?}: {err}"),
+                            );
+                        }
+                        dismissed = Some(i);
+                    }
+                    if ui
+                        .button(tr(ui, "Keep"))
+                        .on_hover_text(tr(
+                            ui,
+                            "Leave the autosave alone and decide the next time the editor starts",
+                        ))
+                        .clicked()
+                    {
+                        dismissed = Some(i);
+                    }
+                });
+            }
+        });
+
+    if let Some(i) = dismissed {
+        recovery.files.remove(i);
+    }
+    if let Some(i) = restore {
+        // The other files are offered again when the main menu comes back
+        let file = recovery.files.remove(i);
+        load_workspace.send(LoadWorkspace::Autosave(file));
+    }
+}
+
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_update(AppState::MainMenu).with_system(egui_ui));
+        app.init_resource::<AutosaveRecovery>()
+            .add_system_set(SystemSet::on_update(AppState::MainMenu).with_system(egui_ui));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_startup_system(find_recoverable_autosaves)
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu)
+                    .with_system(autosave_recovery_prompt.after(egui_ui)),
+            );
     }
 }
@@ -16,9 +16,16 @@
 */
 
 use crate::{
-    interaction::ComputedVisualCue,
+    console::{notify_error, notify_info, notify_warning, report_warning},
+    interaction::{ComputedVisualCue, MoveTo},
     shapes::*,
-    site::{Category, LevelProperties, SiteAssets, SiteProperties, LANE_LAYER_START},
+    site::{
+        Anchor, AnchorBundle, AssociatedGraphs, Category, DisplayColor, DoorOccupancy,
+        DoorSegments, DoorType, Edge, Issue, Lane, LaneMarker, LevelProperties, LocationTags,
+        NameInSite, NavGraph, NavGraphMarker, NavGraphPalette, Pending, Point, ReverseLane,
+        SiteAssets, SiteDiagnostics, SiteProperties, LANE_LAYER_START, PLACEMENT_ISSUES,
+    },
+    CurrentWorkspace,
 };
 use bevy::{
     math::{swizzles::*, Affine3A, Mat3A, Vec2, Vec3A},
@@ -30,7 +37,7 @@ use bevy::{
 };
 use itertools::Itertools;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     time::Instant,
 };
 
@@ -38,7 +45,28 @@ pub struct OccupancyPlugin;
 
 impl Plugin for OccupancyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<CalculateGrid>().add_system(calculate_grid);
+        app.add_event::<CalculateGrid>()
+            .add_event::<GenerateNavGraph>()
+            .add_event::<SnapLaneToCenterline>()
+            .add_event::<CompareGrids>()
+            .add_event::<CheckReachability>()
+            .init_resource::<DisplayedGrid>()
+            .init_resource::<LiveOccupancy>()
+            .init_resource::<GridComparison>()
+            .init_resource::<Reachability>()
+            .init_resource::<PlacementWarnings>()
+            .add_system(calculate_grid)
+            .add_system(update_displayed_grid.after(calculate_grid))
+            .add_system(update_grids_incrementally.after(calculate_grid))
+            .add_system(compare_grids.after(calculate_grid))
+            .add_system(check_reachability.after(calculate_grid))
+            .add_system(
+                check_placement_warnings
+                    .after(calculate_grid)
+                    .after(update_grids_incrementally),
+            )
+            .add_system(generate_nav_graph)
+            .add_system(snap_lanes_to_centerline);
     }
 }
 
@@ -84,11 +112,65 @@ impl Cell {
 
 #[derive(Component)]
 pub struct Grid {
+    /// Name of the resolution that this grid was calculated for. Grids for
+    /// several resolutions can be kept at the same time.
+    pub name: String,
     pub occupied: HashSet<Cell>,
+    /// Cells that are free of obstacles but are within the inflation radius
+    /// of an occupied cell, so the center of a robot cannot enter them.
+    pub inflated: HashSet<Cell>,
     pub cell_size: f32,
     pub floor: f32,
     pub ceiling: f32,
+    pub inflation: f32,
     pub range: GridRange,
+    /// The heights of the obstacles in each cell, if the grid was calculated
+    /// in voxel mode
+    pub voxels: Option<Voxels>,
+    /// The physical bodies that were found inside the grid's slice, and the
+    /// cells that each of them may overlap
+    pub bodies: HashMap<Entity, GridRange>,
+    /// Whether doors with the default [`DoorOccupancy`] were treated as open
+    pub open_doors: bool,
+}
+
+/// Marks the mesh that shows the inflated cells of a grid
+#[derive(Component)]
+pub struct InflatedCells;
+
+/// Occupancy of a grid that is split into layers between its floor and ceiling
+#[derive(Debug, Clone, Default)]
+pub struct Voxels {
+    /// How tall each layer is
+    pub layer_height: f32,
+    /// The occupied layers of each cell, counted up from the floor of the grid
+    pub columns: HashMap<Cell, BTreeSet<usize>>,
+}
+
+impl Grid {
+    /// Check whether the center of a robot cannot be inside this cell, either
+    /// because the cell is occupied or because it is too close to an obstacle.
+    pub fn is_blocked(&self, cell: &Cell) -> bool {
+        self.occupied.contains(cell) || self.inflated.contains(cell)
+    }
+
+    /// Find the inflated cells again after the occupied cells have changed
+    pub fn inflate(&mut self) {
+        self.inflated = inflate_cells(&self.occupied, self.cell_size, self.inflation);
+    }
+
+    /// Get every cell that the center of a robot with the given radius cannot
+    /// be inside. The inflated cells of the grid are used when the radius
+    /// matches its inflation radius.
+    pub fn blocked_cells(&self, robot_radius: f32) -> HashSet<Cell> {
+        let mut blocked = if robot_radius == self.inflation {
+            self.inflated.clone()
+        } else {
+            inflate_cells(&self.occupied, self.cell_size, robot_radius)
+        };
+        blocked.extend(self.occupied.iter().copied());
+        blocked
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -126,30 +208,264 @@ impl GridRange {
         self
     }
 
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.min[0] <= cell.x
+            && cell.x <= self.max[0]
+            && self.min[1] <= cell.y
+            && cell.y <= self.max[1]
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (i64, i64)> {
         (self.min[0]..=self.max[0]).cartesian_product(self.min[1]..=self.max[1])
     }
 }
 
 pub struct CalculateGrid {
+    /// Name of the resolution being calculated. A previous grid with the same
+    /// name will be replaced, while grids with other names are kept.
+    pub name: String,
     /// How large is each cell
     pub cell_size: f32,
     /// Ignore meshes below this height
     pub floor: f32,
     /// Ignore meshes above this height
     pub ceiling: f32,
+    /// Radius of the robot that the grid is being calculated for. Free cells
+    /// within this distance of an obstacle are marked as inflated so that the
+    /// grid shows the configuration space of the robot.
+    pub inflation: f32,
+    /// Split the space between the floor and ceiling into layers that are
+    /// checked separately, so the grid records the height of each obstacle.
+    pub voxels: bool,
+    /// Treat doors as open, leaving them out of the grid, unless their
+    /// [`DoorOccupancy`] says otherwise
+    pub open_doors: bool,
+}
+
+/// The name of the occupancy grid resolution that is currently being displayed.
+/// Tools that use the occupancy grid will use the displayed resolution.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct DisplayedGrid(pub Option<String>);
+
+/// When this is turned on, occupancy grids will be updated as the site is
+/// edited, only checking the cells around the bodies that have changed.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiveOccupancy(pub bool);
+
+impl DisplayedGrid {
+    pub fn shows(&self, grid: &Grid) -> bool {
+        self.0.as_ref() == Some(&grid.name)
+    }
+}
+
+/// Show which cells differ between two occupancy grids. The grids can belong
+/// to different levels, e.g. to check that corridors line up on either side of
+/// a lift, or they can be two named resolutions of the same level that were
+/// calculated before and after an edit.
+#[derive(Clone, Copy, Debug)]
+pub enum CompareGrids {
+    Start { base: Entity, other: Entity },
+    Clear,
+}
+
+/// The result of the most recent [`CompareGrids`] request
+#[derive(Resource, Default)]
+pub struct GridComparison {
+    /// The grids being compared, if a comparison is being displayed
+    pub grids: Option<[Entity; 2]>,
+    /// How many cells are occupied in the base grid but free in the other
+    pub only_base: usize,
+    /// How many cells are occupied in the other grid but free in the base
+    pub only_other: usize,
+    overlays: Vec<Entity>,
+}
+
+/// Height of the overlays that are drawn just above the occupancy grid
+const OVERLAY_HEIGHT: f32 = 0.75 * LANE_LAYER_START;
+
+/// Color all of the free space that a robot could reach from a location,
+/// according to the displayed occupancy grid of the location's level.
+#[derive(Clone, Copy, Debug)]
+pub enum CheckReachability {
+    Start { location: Entity, robot_radius: f32 },
+    Clear,
+}
+
+/// The result of the most recent [`CheckReachability`] request
+#[derive(Resource, Default)]
+pub struct Reachability {
+    /// The location that reachability was checked from
+    pub location: Option<Entity>,
+    /// How many cells can be reached from the location
+    pub reachable: usize,
+    /// How many cells are free of obstacles but cannot be reached from the
+    /// location
+    pub unreachable: usize,
+    overlay: Option<Entity>,
 }
 
+/// Settings and markers for the warnings about lanes that are blocked or
+/// squeezed by obstacles in the displayed occupancy grid. The lanes are checked
+/// whenever a grid changes, so turning on [`LiveOccupancy`] will produce
+/// warnings as walls and models are moved around.
+#[derive(Resource)]
+pub struct PlacementWarnings {
+    /// Warn about lanes that run through corridors narrower than this
+    pub min_corridor_width: f32,
+    markers: Vec<Entity>,
+}
+
+impl Default for PlacementWarnings {
+    fn default() -> Self {
+        Self {
+            min_corridor_width: 1.0,
+            markers: Vec::new(),
+        }
+    }
+}
+
+/// Height above the level where placement warning markers are drawn
+pub const PLACEMENT_WARNING_HEIGHT: f32 = 1.0;
+
+/// Generate a candidate nav graph from the skeleton of the free space in the
+/// displayed occupancy grid.
+pub struct GenerateNavGraph {
+    /// The radius of the robot that will use the graph. Cells that are closer
+    /// than this to an obstacle will not be used for lanes.
+    pub robot_radius: f32,
+}
+
+/// Approximate distance between the anchors of a generated lane network
+pub const GENERATED_LANE_LENGTH: f32 = 2.0;
+
+/// Move the anchors of a lane sideways so that the lane runs down the middle
+/// of the free space around it in the displayed occupancy grid.
+pub struct SnapLaneToCenterline {
+    pub lane: Entity,
+}
+
+/// How far to either side of a lane to look for obstacles when snapping it to
+/// the centerline of the free space
+pub const CENTERLINE_SEARCH_DISTANCE: f32 = 5.0;
+
+const NEIGHBORS: [(i64, i64); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
 enum Group {
     Level(Entity),
     Site(Entity),
     None,
 }
 
+/// The space that an occupancy calculation checks for obstacles. The slice
+/// between the floor and ceiling is split into layers which are checked
+/// separately. Grids that are not calculated in voxel mode have one layer.
+#[derive(Clone, Copy, Debug)]
+struct Slice {
+    cell_size: f32,
+    floor: f32,
+    ceiling: f32,
+    layer_count: usize,
+    layer_height: f32,
+}
+
+impl Slice {
+    fn new(cell_size: f32, floor: f32, ceiling: f32, voxels: bool) -> Self {
+        let layer_count = if voxels {
+            ((ceiling - floor) / cell_size).ceil().max(1.0) as usize
+        } else {
+            1
+        };
+        Self {
+            cell_size,
+            floor,
+            ceiling,
+            layer_count,
+            layer_height: (ceiling - floor) / layer_count as f32,
+        }
+    }
+
+    fn of_grid(grid: &Grid) -> Self {
+        Self::new(
+            grid.cell_size,
+            grid.floor,
+            grid.ceiling,
+            grid.voxels.is_some(),
+        )
+    }
+
+    fn layer_box(&self, cell: Cell, layer: usize) -> Aabb {
+        let half_cell_size = self.cell_size / 2.0;
+        Aabb {
+            center: cell
+                .to_center_point(self.cell_size)
+                .extend(self.floor + (layer as f32 + 0.5) * self.layer_height)
+                .into(),
+            half_extents: Vec3A::new(half_cell_size, half_cell_size, self.layer_height / 2.0),
+        }
+    }
+
+    /// Find which layers of a cell are occupied by a mesh, skipping the
+    /// layers that are already known to be occupied.
+    fn occupied_layers(
+        &self,
+        cell: Cell,
+        known: &BTreeSet<usize>,
+        positions: &Vec<[f32; 3]>,
+        indices: &Vec<u32>,
+        tf: &GlobalTransform,
+    ) -> BTreeSet<usize> {
+        (0..self.layer_count)
+            .filter(|layer| {
+                !known.contains(layer)
+                    && mesh_intersects_box(&self.layer_box(cell, *layer), positions, indices, tf)
+            })
+            .collect()
+    }
+}
+
+fn mesh_triangles(mesh: &Mesh, e: Entity) -> Option<(&Vec<[f32; 3]>, &Vec<u32>)> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+
+    let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions,
+        _ => return None,
+    };
+
+    let indices = match mesh.indices() {
+        Some(Indices::U32(indices)) => indices,
+        _ => {
+            report_warning(
+                Some(e),
+                format!(
+                    "Unexpected index set for mesh of {e:?}:\n{:?}",
+                    mesh.indices()
+                ),
+            );
+            return None;
+        }
+    };
+
+    Some((positions, indices))
+}
+
 fn calculate_grid(
     mut commands: Commands,
     mut request: EventReader<CalculateGrid>,
-    bodies: Query<(Entity, &Handle<Mesh>, &Aabb, &GlobalTransform)>,
+    bodies: Query<
+        (Entity, &Handle<Mesh>, &Aabb, &GlobalTransform),
+        (Without<Grid>, Without<InflatedCells>),
+    >,
     meta: Query<(
         Option<&Parent>,
         Option<&Category>,
@@ -160,19 +476,23 @@ fn calculate_grid(
     sites: Query<(), With<SiteProperties>>,
     mut meshes: ResMut<Assets<Mesh>>,
     assets: Res<SiteAssets>,
-    grids: Query<Entity, With<Grid>>,
+    grids: Query<(Entity, &Grid)>,
+    doors: Query<Option<&DoorOccupancy>, With<DoorType>>,
+    mut displayed: ResMut<DisplayedGrid>,
 ) {
     if let Some(request) = request.iter().last() {
         let start_time = Instant::now();
-        // let mut occupied: HashSet<Cell> = HashSet::new();
-        let mut occupied: HashMap<Entity, HashSet<Cell>> = HashMap::new();
+        let mut columns: HashMap<Entity, HashMap<Cell, BTreeSet<usize>>> = HashMap::new();
+        let mut group_bodies: HashMap<Entity, HashMap<Entity, GridRange>> = HashMap::new();
         let mut range = GridRange::new();
-        let cell_size = request.cell_size as f32;
-        let half_cell_size = cell_size / 2.0;
-        let floor = request.floor;
-        let ceiling = request.ceiling;
-        let mid = (floor + ceiling) / 2.0;
-        let half_height = (ceiling - floor) / 2.0;
+        let slice = Slice::new(
+            request.cell_size,
+            request.floor,
+            request.ceiling,
+            request.voxels,
+        );
+        let cell_size = slice.cell_size;
+        let inflation = request.inflation.max(0.0);
         let levels_of_sites = get_levels_of_sites(&levels, &parents);
 
         let physical_entities = collect_physical_entities(&bodies, &meta);
@@ -188,55 +508,40 @@ fn calculate_grid(
                 Group::None => continue,
             };
 
-            let group_occupied = occupied.entry(e_group).or_default();
+            if is_open_door_body(*e, &parents, &doors, request.open_doors) {
+                continue;
+            }
 
-            let body_range = match grid_range_of_aabb(aabb, tf, cell_size, floor, ceiling) {
-                Some(range) => range,
-                None => continue,
-            };
+            let body_range =
+                match grid_range_of_aabb(aabb, tf, cell_size, slice.floor, slice.ceiling) {
+                    Some(range) => range,
+                    None => continue,
+                };
 
             range = range.union_with(body_range);
+            group_bodies
+                .entry(e_group)
+                .or_default()
+                .insert(*e, body_range);
 
-            if let Some(mesh) = meshes.get(mesh) {
-                if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            let Some((positions, indices)) = meshes.get(mesh).and_then(|m| mesh_triangles(m, *e))
+            else {
+                continue;
+            };
+
+            let group_columns = columns.entry(e_group).or_default();
+            for (x, y) in body_range.iter() {
+                let cell = Cell::new(x, y);
+                let known = group_columns.get(&cell).cloned().unwrap_or_default();
+                if known.len() == slice.layer_count {
+                    // No reason to check this cell since we already know
+                    // that it is fully occupied.
                     continue;
                 }
 
-                let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
-                    Some(VertexAttributeValues::Float32x3(positions)) => positions,
-                    _ => continue,
-                };
-
-                let indices = match mesh.indices() {
-                    Some(Indices::U32(indices)) => indices,
-                    _ => {
-                        println!(
-                            "Unexpected index set for mesh of {e:?}:\n{:?}",
-                            mesh.indices()
-                        );
-                        continue;
-                    }
-                };
-
-                for (x, y) in body_range.iter() {
-                    let cell = Cell::new(x, y);
-                    if group_occupied.contains(&cell) {
-                        // No reason to check this cell since we already know
-                        // that it is occupied.
-                        continue;
-                    }
-
-                    let b = Aabb {
-                        center: Cell::new(x, y)
-                            .to_center_point(cell_size)
-                            .extend(mid)
-                            .into(),
-                        half_extents: Vec3A::new(half_cell_size, half_cell_size, half_height),
-                    };
-
-                    if mesh_intersects_box(&b, positions, indices, tf) {
-                        group_occupied.insert(cell);
-                    }
+                let layers = slice.occupied_layers(cell, &known, positions, indices, tf);
+                if !layers.is_empty() {
+                    group_columns.entry(cell).or_default().extend(layers);
                 }
             }
         }
@@ -245,36 +550,61 @@ fn calculate_grid(
         let delta = finish_time - start_time;
         println!("Occupancy calculation time: {}", delta.as_secs_f32());
 
-        for grid in &grids {
-            commands.entity(grid).despawn_recursive();
+        for (e, grid) in &grids {
+            if grid.name == request.name {
+                commands.entity(e).despawn_recursive();
+            }
         }
 
         for (site, levels) in levels_of_sites {
-            let site_occupancy = occupied.get(&site).cloned().unwrap_or_default();
+            let site_columns = columns.get(&site).cloned().unwrap_or_default();
+            let site_bodies = group_bodies.get(&site).cloned().unwrap_or_default();
             for level in levels {
-                let level_occupied = occupied.entry(level).or_default();
-                for cell in &site_occupancy {
-                    level_occupied.insert(*cell);
+                let level_columns = columns.entry(level).or_default();
+                for (cell, column) in &site_columns {
+                    level_columns
+                        .entry(*cell)
+                        .or_default()
+                        .extend(column.iter().copied());
                 }
+
+                group_bodies
+                    .entry(level)
+                    .or_default()
+                    .extend(site_bodies.iter().map(|(e, r)| (*e, *r)));
             }
         }
 
         for level in &levels {
-            let mut mesh = MeshBuffer::empty();
-            let level_occupied = match occupied.remove(&level) {
-                Some(o) => o,
+            let level_columns = match columns.remove(&level) {
+                Some(c) => c,
                 None => continue,
             };
-            for cell in &level_occupied {
-                let p = Vec3::new(
-                    cell_size * (cell.x as f32 + 0.5),
-                    cell_size * (cell.y as f32 + 0.5),
-                    LANE_LAYER_START / 2.0,
-                );
-                mesh = mesh.merge_with(
-                    make_flat_square_mesh(cell_size).transform_by(Affine3A::from_translation(p)),
-                );
-            }
+            let level_occupied: HashSet<Cell> = level_columns.keys().copied().collect();
+            let level_voxels = if request.voxels {
+                Some(Voxels {
+                    layer_height: slice.layer_height,
+                    columns: level_columns,
+                })
+            } else {
+                None
+            };
+
+            let mut grid = Grid {
+                name: request.name.clone(),
+                occupied: level_occupied,
+                inflated: HashSet::new(),
+                cell_size,
+                floor: slice.floor,
+                ceiling: slice.ceiling,
+                inflation,
+                range,
+                voxels: level_voxels,
+                bodies: group_bodies.remove(&level).unwrap_or_default(),
+                open_doors: request.open_doors,
+            };
+            grid.inflate();
+            let (mesh, inflated_mesh) = make_grid_meshes(&grid);
 
             commands.entity(level).add_children(|level| {
                 level
@@ -283,15 +613,476 @@ fn calculate_grid(
                         material: assets.occupied_material.clone(),
                         ..default()
                     })
-                    .insert(Grid {
-                        occupied: level_occupied,
-                        cell_size,
-                        floor,
-                        ceiling,
-                        range,
+                    .insert(grid)
+                    .with_children(|grid| {
+                        grid.spawn(PbrBundle {
+                            mesh: meshes.add(inflated_mesh.into()),
+                            material: assets.inflated_material.clone(),
+                            ..default()
+                        })
+                        .insert(InflatedCells);
                     });
             });
         }
+
+        displayed.0 = Some(request.name.clone());
+    }
+}
+
+/// Make the meshes that show the occupied cells and the inflated cells of a
+/// grid.
+fn make_grid_meshes(grid: &Grid) -> (MeshBuffer, MeshBuffer) {
+    let slice = Slice::of_grid(grid);
+    let mut mesh = MeshBuffer::empty();
+    if let Some(voxels) = &grid.voxels {
+        for (cell, column) in &voxels.columns {
+            for layer in column {
+                let b = slice.layer_box(*cell, *layer);
+                mesh = mesh.merge_with(
+                    make_box(grid.cell_size, grid.cell_size, slice.layer_height)
+                        .transform_by(Affine3A::from_translation(b.center.into())),
+                );
+            }
+        }
+    } else {
+        for cell in &grid.occupied {
+            mesh = mesh.merge_with(make_cell_mesh(*cell, grid.cell_size));
+        }
+    }
+
+    let mut inflated_mesh = MeshBuffer::empty();
+    for cell in &grid.inflated {
+        inflated_mesh = inflated_mesh.merge_with(make_cell_mesh(*cell, grid.cell_size));
+    }
+
+    (mesh, inflated_mesh)
+}
+
+/// Keep the occupancy grids up to date while the site is being edited by only
+/// checking the cells around the bodies that have moved, changed their mesh,
+/// or been removed.
+fn update_grids_incrementally(
+    live: Res<LiveOccupancy>,
+    changed_bodies: Query<
+        Entity,
+        (
+            Or<(Changed<GlobalTransform>, Changed<Handle<Mesh>>)>,
+            With<Aabb>,
+            Without<Grid>,
+            Without<InflatedCells>,
+        ),
+    >,
+    removed_bodies: RemovedComponents<Handle<Mesh>>,
+    changed_doors: Query<&DoorSegments, Changed<DoorOccupancy>>,
+    doors: Query<Option<&DoorOccupancy>, With<DoorType>>,
+    bodies: Query<
+        (Entity, &Handle<Mesh>, &Aabb, &GlobalTransform),
+        (Without<Grid>, Without<InflatedCells>),
+    >,
+    meta: Query<(
+        Option<&Parent>,
+        Option<&Category>,
+        Option<&ComputedVisualCue>,
+    )>,
+    parents: Query<&Parent>,
+    levels: Query<Entity, With<LevelProperties>>,
+    sites: Query<(), With<SiteProperties>>,
+    mut grids: Query<(&mut Grid, &Parent, &mut Handle<Mesh>, &Children)>,
+    mut inflated_cells: Query<&mut Handle<Mesh>, (With<InflatedCells>, Without<Grid>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    if !live.0 || grids.is_empty() {
+        return;
+    }
+
+    let mut changed: HashSet<Entity> = changed_bodies.iter().chain(removed_bodies.iter()).collect();
+    // Doors that switched between open and closed need all of their bodies to
+    // be checked again.
+    for segments in &changed_doors {
+        changed.extend([segments.body, segments.cue_inner, segments.cue_outline]);
+    }
+    if changed.is_empty() {
+        return;
+    }
+
+    let levels_of_sites = get_levels_of_sites(&levels, &parents);
+    // Figure out which levels each changed body can affect. Bodies that are
+    // no longer physical or have been removed will affect no levels.
+    let mut affected_levels: HashMap<Entity, Vec<Entity>> = HashMap::new();
+    for e in &changed {
+        if !bodies.contains(*e) || !is_physical_entity(*e, &meta) {
+            continue;
+        }
+
+        let e_levels = match get_group(*e, &parents, &levels, &sites) {
+            Group::Level(level) => vec![level],
+            Group::Site(site) => levels_of_sites.get(&site).cloned().unwrap_or_default(),
+            Group::None => continue,
+        };
+        affected_levels.insert(*e, e_levels);
+    }
+
+    for (mut grid, level, mut grid_mesh, children) in &mut grids {
+        // Keeping track of the bodies in the grid does not count as a change
+        // to the grid. It is only marked as changed when its cells change, so
+        // Changed<Grid> does not fire for bodies that move within their cells.
+        let occupancy_changed = {
+            let grid = grid.bypass_change_detection();
+            update_dirty_cells(
+                grid,
+                level.get(),
+                &changed,
+                &affected_levels,
+                &bodies,
+                &parents,
+                &doors,
+                &meshes,
+            )
+        };
+        if !occupancy_changed {
+            continue;
+        }
+        grid.set_changed();
+
+        grid.inflate();
+        let (mesh, inflated_mesh) = make_grid_meshes(&grid);
+        *grid_mesh = meshes.add(mesh.into());
+        for child in children {
+            if let Ok(mut child_mesh) = inflated_cells.get_mut(*child) {
+                *child_mesh = meshes.add(inflated_mesh.clone().into());
+            }
+        }
+    }
+}
+
+/// Check the cells of a grid again around bodies that have changed. Returns
+/// true if the occupancy of any cell changed.
+fn update_dirty_cells(
+    grid: &mut Grid,
+    level: Entity,
+    changed: &HashSet<Entity>,
+    affected_levels: &HashMap<Entity, Vec<Entity>>,
+    bodies: &Query<
+        (Entity, &Handle<Mesh>, &Aabb, &GlobalTransform),
+        (Without<Grid>, Without<InflatedCells>),
+    >,
+    parents: &Query<&Parent>,
+    doors: &Query<Option<&DoorOccupancy>, With<DoorType>>,
+    meshes: &Assets<Mesh>,
+) -> bool {
+    let slice = Slice::of_grid(grid);
+    let mut dirty: Vec<GridRange> = Vec::new();
+    for e in changed {
+        if let Some(old_range) = grid.bodies.remove(e) {
+            dirty.push(old_range);
+        }
+
+        let affects_level = match affected_levels.get(e) {
+            Some(e_levels) => e_levels.contains(&level),
+            None => false,
+        };
+        if !affects_level || is_open_door_body(*e, parents, doors, grid.open_doors) {
+            continue;
+        }
+
+        let Ok((_, _, aabb, tf)) = bodies.get(*e) else {
+            continue;
+        };
+        if let Some(new_range) =
+            grid_range_of_aabb(aabb, tf, slice.cell_size, slice.floor, slice.ceiling)
+        {
+            grid.bodies.insert(*e, new_range);
+            grid.range = grid.range.union_with(new_range);
+            dirty.push(new_range);
+        }
+    }
+
+    let dirty_cells: HashSet<Cell> = dirty
+        .iter()
+        .flat_map(|r| r.iter())
+        .map(|(x, y)| Cell::new(x, y))
+        .collect();
+    let mut occupancy_changed = false;
+    for cell in dirty_cells {
+        let mut column = BTreeSet::new();
+        for (body, body_range) in &grid.bodies {
+            if column.len() == slice.layer_count {
+                break;
+            }
+
+            if !body_range.contains(cell) {
+                continue;
+            }
+
+            let Ok((_, mesh, _, tf)) = bodies.get(*body) else {
+                continue;
+            };
+            let Some((positions, indices)) =
+                meshes.get(mesh).and_then(|m| mesh_triangles(m, *body))
+            else {
+                continue;
+            };
+            let layers = slice.occupied_layers(cell, &column, positions, indices, tf);
+            column.extend(layers);
+        }
+
+        if column.is_empty() {
+            occupancy_changed |= grid.occupied.remove(&cell);
+        } else {
+            occupancy_changed |= grid.occupied.insert(cell);
+        }
+
+        if let Some(voxels) = &mut grid.voxels {
+            if column.is_empty() {
+                occupancy_changed |= voxels.columns.remove(&cell).is_some();
+            } else if voxels.columns.get(&cell) != Some(&column) {
+                voxels.columns.insert(cell, column);
+                occupancy_changed = true;
+            }
+        }
+    }
+
+    occupancy_changed
+}
+
+fn make_cell_mesh(cell: Cell, cell_size: f32) -> MeshBuffer {
+    make_cell_mesh_at(cell, cell_size, LANE_LAYER_START / 2.0)
+}
+
+fn make_cell_mesh_at(cell: Cell, cell_size: f32, height: f32) -> MeshBuffer {
+    let p = cell.to_center_point(cell_size).extend(height);
+    make_flat_square_mesh(cell_size).transform_by(Affine3A::from_translation(p))
+}
+
+fn compare_grids(
+    mut commands: Commands,
+    mut requests: EventReader<CompareGrids>,
+    mut comparison: ResMut<GridComparison>,
+    grids: Query<(&Grid, &Parent)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<SiteAssets>,
+) {
+    let Some(request) = requests.iter().last() else {
+        return;
+    };
+
+    for e in comparison.overlays.drain(..) {
+        commands.entity(e).despawn_recursive();
+    }
+    comparison.grids = None;
+    comparison.only_base = 0;
+    comparison.only_other = 0;
+
+    let (base, other) = match request {
+        CompareGrids::Start { base, other } => (*base, *other),
+        CompareGrids::Clear => return,
+    };
+
+    let (Ok((base_grid, base_level)), Ok((other_grid, other_level))) =
+        (grids.get(base), grids.get(other))
+    else {
+        notify_error(None, "Unable to find the occupancy grids to compare");
+        return;
+    };
+
+    if base_grid.cell_size != other_grid.cell_size {
+        notify_warning(
+            None,
+            format!(
+                "Cannot compare occupancy grids with different cell sizes: {} and {}",
+                base_grid.cell_size, other_grid.cell_size
+            ),
+        );
+        return;
+    }
+
+    let cell_size = base_grid.cell_size;
+    let mut only_base_mesh = MeshBuffer::empty();
+    for cell in base_grid.occupied.difference(&other_grid.occupied) {
+        only_base_mesh =
+            only_base_mesh.merge_with(make_cell_mesh_at(*cell, cell_size, OVERLAY_HEIGHT));
+        comparison.only_base += 1;
+    }
+
+    let mut only_other_mesh = MeshBuffer::empty();
+    for cell in other_grid.occupied.difference(&base_grid.occupied) {
+        only_other_mesh =
+            only_other_mesh.merge_with(make_cell_mesh_at(*cell, cell_size, OVERLAY_HEIGHT));
+        comparison.only_other += 1;
+    }
+
+    let only_base_mesh = meshes.add(only_base_mesh.into());
+    let only_other_mesh = meshes.add(only_other_mesh.into());
+    let mut levels = vec![base_level.get()];
+    if other_level.get() != base_level.get() {
+        levels.push(other_level.get());
+    }
+
+    // Put the overlay on both levels so it can be seen from either of them
+    for level in levels {
+        for (mesh, material) in [
+            (&only_base_mesh, &assets.occupancy_only_base_material),
+            (&only_other_mesh, &assets.occupancy_only_other_material),
+        ] {
+            let overlay = commands
+                .spawn(PbrBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    ..default()
+                })
+                .id();
+            commands.entity(level).add_child(overlay);
+            comparison.overlays.push(overlay);
+        }
+    }
+
+    comparison.grids = Some([base, other]);
+}
+
+fn check_reachability(
+    mut commands: Commands,
+    mut requests: EventReader<CheckReachability>,
+    mut reachability: ResMut<Reachability>,
+    locations: Query<&Point<Entity>, With<LocationTags>>,
+    anchors: Query<(&Anchor, &Parent)>,
+    grids: Query<(&Grid, &Parent)>,
+    displayed: Res<DisplayedGrid>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<SiteAssets>,
+) {
+    let Some(request) = requests.iter().last() else {
+        return;
+    };
+
+    if let Some(overlay) = reachability.overlay.take() {
+        commands.entity(overlay).despawn_recursive();
+    }
+    reachability.location = None;
+    reachability.reachable = 0;
+    reachability.unreachable = 0;
+
+    let (location, robot_radius) = match request {
+        CheckReachability::Start {
+            location,
+            robot_radius,
+        } => (*location, *robot_radius),
+        CheckReachability::Clear => return,
+    };
+
+    let Some((anchor, level)) = locations
+        .get(location)
+        .ok()
+        .and_then(|point| anchors.get(point.0).ok())
+    else {
+        notify_error(
+            Some(location),
+            format!("Unable to find the anchor of location {location:?}"),
+        );
+        return;
+    };
+    let Some((grid, _)) = grids
+        .iter()
+        .find(|(grid, parent)| displayed.shows(grid) && parent.get() == level.get())
+    else {
+        notify_warning(
+            None,
+            "Calculate the occupancy grid before checking reachability",
+        );
+        return;
+    };
+
+    let blocked = grid.blocked_cells(robot_radius);
+
+    let p = Vec2::from(*anchor.translation_for_category(Category::General));
+    let start = Cell::from_point(p, grid.cell_size);
+    if blocked.contains(&start) {
+        notify_warning(
+            Some(location),
+            format!(
+                "Location {location:?} is too close to an obstacle for a robot \
+            with a radius of {robot_radius}"
+            ),
+        );
+        return;
+    }
+
+    // Flood fill the free cells within the range of the grid. Robots are
+    // only allowed to move between cells that share an edge so that they
+    // cannot squeeze diagonally between two obstacles.
+    let mut reached: HashSet<Cell> = HashSet::new();
+    let mut frontier = vec![start];
+    reached.insert(start);
+    while let Some(cell) = frontier.pop() {
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let n = cell.shifted(dx, dy);
+            if !grid.range.contains(n) || blocked.contains(&n) || reached.contains(&n) {
+                continue;
+            }
+            reached.insert(n);
+            frontier.push(n);
+        }
+    }
+
+    let mut mesh = MeshBuffer::empty();
+    for cell in &reached {
+        mesh = mesh.merge_with(make_cell_mesh_at(*cell, grid.cell_size, OVERLAY_HEIGHT));
+    }
+
+    let free = grid
+        .range
+        .iter()
+        .filter(|(x, y)| !blocked.contains(&Cell::new(*x, *y)))
+        .count();
+
+    let overlay = commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(mesh.into()),
+            material: assets.reachable_material.clone(),
+            ..default()
+        })
+        .id();
+    commands.entity(level.get()).add_child(overlay);
+
+    reachability.location = Some(location);
+    reachability.reachable = reached.len();
+    reachability.unreachable = free.saturating_sub(reached.len());
+    reachability.overlay = Some(overlay);
+}
+
+/// Find the free cells whose centers are within the inflation radius of the
+/// center of an occupied cell.
+fn inflate_cells(occupied: &HashSet<Cell>, cell_size: f32, inflation: f32) -> HashSet<Cell> {
+    let reach = (inflation / cell_size).ceil() as i64;
+    let offsets: Vec<(i64, i64)> = (-reach..=reach)
+        .cartesian_product(-reach..=reach)
+        .filter(|(dx, dy)| {
+            (*dx, *dy) != (0, 0)
+                && Vec2::new(*dx as f32, *dy as f32).length() * cell_size <= inflation
+        })
+        .collect();
+
+    let mut inflated = HashSet::new();
+    for cell in occupied {
+        for (dx, dy) in &offsets {
+            let n = cell.shifted(*dx, *dy);
+            if !occupied.contains(&n) {
+                inflated.insert(n);
+            }
+        }
+    }
+
+    inflated
+}
+
+fn update_displayed_grid(
+    displayed: Res<DisplayedGrid>,
+    mut grids: Query<(&Grid, &mut Visibility)>,
+) {
+    for (grid, mut visibility) in &mut grids {
+        let is_visible = displayed.shows(grid);
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
     }
 }
 
@@ -309,6 +1100,27 @@ fn get_levels_of_sites(
     levels_of_sites
 }
 
+/// Check whether a body belongs to a door that should be treated as open.
+fn is_open_door_body(
+    e: Entity,
+    parents: &Query<&Parent>,
+    doors: &Query<Option<&DoorOccupancy>, With<DoorType>>,
+    open_doors: bool,
+) -> bool {
+    let mut e_meta = e;
+    loop {
+        if let Ok(state) = doors.get(e_meta) {
+            return state.copied().unwrap_or_default().is_open(open_doors);
+        }
+
+        if let Ok(parent) = parents.get(e_meta) {
+            e_meta = parent.get();
+        } else {
+            return false;
+        }
+    }
+}
+
 fn get_group(
     e: Entity,
     parents: &Query<&Parent>,
@@ -334,7 +1146,10 @@ fn get_group(
 }
 
 fn collect_physical_entities(
-    meshes: &Query<(Entity, &Handle<Mesh>, &Aabb, &GlobalTransform)>,
+    meshes: &Query<
+        (Entity, &Handle<Mesh>, &Aabb, &GlobalTransform),
+        (Without<Grid>, Without<InflatedCells>),
+    >,
     meta: &Query<(
         Option<&Parent>,
         Option<&Category>,
@@ -343,38 +1158,47 @@ fn collect_physical_entities(
 ) -> Vec<Entity> {
     let mut physical_entities = Vec::new();
     for (e, _, _, _) in meshes {
-        let mut e_meta = e;
-        let is_physical = loop {
-            if let Ok((parent, category, cue)) = meta.get(e_meta) {
-                if cue.is_some() {
-                    // This is a visual cue, making it non-physical
-                    break false;
-                }
+        if is_physical_entity(e, meta) {
+            physical_entities.push(e);
+        }
+    }
 
-                if let Some(category) = category {
-                    break category.is_physical();
-                }
+    physical_entities
+}
 
-                if let Some(parent) = parent {
-                    e_meta = parent.get();
-                } else {
-                    // There is no parent and we have not determined a
-                    // category for this mesh, so let's assume it is not
-                    // physical
-                    break false;
-                }
-            } else {
-                // Should this ever happen?
-                break false;
+fn is_physical_entity(
+    e: Entity,
+    meta: &Query<(
+        Option<&Parent>,
+        Option<&Category>,
+        Option<&ComputedVisualCue>,
+    )>,
+) -> bool {
+    let mut e_meta = e;
+    loop {
+        if let Ok((parent, category, cue)) = meta.get(e_meta) {
+            if cue.is_some() {
+                // This is a visual cue, making it non-physical
+                return false;
             }
-        };
 
-        if is_physical {
-            physical_entities.push(e);
+            if let Some(category) = category {
+                return category.is_physical();
+            }
+
+            if let Some(parent) = parent {
+                e_meta = parent.get();
+            } else {
+                // There is no parent and we have not determined a
+                // category for this mesh, so let's assume it is not
+                // physical
+                return false;
+            }
+        } else {
+            // Should this ever happen?
+            return false;
         }
     }
-
-    physical_entities
 }
 
 fn grid_range_of_aabb(
@@ -504,3 +1328,435 @@ fn unit_vec(axis: usize) -> Vec3A {
     v[axis] = 1.0;
     v
 }
+
+fn generate_nav_graph(
+    mut commands: Commands,
+    mut request: EventReader<GenerateNavGraph>,
+    grids: Query<(&Grid, &Parent)>,
+    graphs: Query<(), With<NavGraphMarker>>,
+    displayed: Res<DisplayedGrid>,
+    current_workspace: Res<CurrentWorkspace>,
+    palettes: Query<&NavGraphPalette>,
+) {
+    let Some(request) = request.iter().last() else {
+        return;
+    };
+
+    if !grids.iter().any(|(grid, _)| displayed.shows(grid)) {
+        notify_warning(
+            None,
+            "Calculate the occupancy grid before generating a nav graph",
+        );
+        return;
+    }
+
+    let start_time = Instant::now();
+    let palette = current_workspace
+        .root
+        .and_then(|root| palettes.get(root).ok())
+        .copied()
+        .unwrap_or_default();
+    let graph = commands
+        .spawn(SpatialBundle::default())
+        .insert(NavGraph {
+            name: NameInSite("generated".to_owned()),
+            color: DisplayColor(palette.color(graphs.iter().count())),
+            manual_color: Default::default(),
+            lane_defaults: Default::default(),
+            marker: Default::default(),
+        })
+        .id();
+
+    let mut lane_count = 0;
+    for (grid, parent) in &grids {
+        if !displayed.shows(grid) {
+            continue;
+        }
+        let level = parent.get();
+        let skeleton = compute_skeleton(grid, request.robot_radius);
+        let stride = ((GENERATED_LANE_LENGTH / grid.cell_size).round() as usize).max(1);
+        let mut anchors: HashMap<Cell, Entity> = HashMap::new();
+        let mut get_anchor = |cell: Cell, commands: &mut Commands| -> Entity {
+            *anchors.entry(cell).or_insert_with(|| {
+                let p = cell.to_center_point(grid.cell_size);
+                let anchor = commands.spawn(AnchorBundle::new([p.x, p.y].into())).id();
+                commands.entity(level).add_child(anchor);
+                anchor
+            })
+        };
+
+        for chain in trace_skeleton(&skeleton) {
+            let mut keypoints: Vec<Cell> = chain.iter().step_by(stride).copied().collect();
+            if keypoints.last() != chain.last() {
+                keypoints.extend(chain.last());
+            }
+
+            for pair in keypoints.windows(2) {
+                let start = get_anchor(pair[0], &mut commands);
+                let end = get_anchor(pair[1], &mut commands);
+                commands.spawn(Lane {
+                    anchors: Edge::new(start, end),
+                    forward: Default::default(),
+                    reverse: ReverseLane::Same,
+                    graphs: AssociatedGraphs::Only([graph].into()),
+                    cost: Default::default(),
+                    curve: Default::default(),
+                    shared_zone: Default::default(),
+                    marker: LaneMarker,
+                });
+                lane_count += 1;
+            }
+        }
+    }
+
+    let delta = Instant::now() - start_time;
+    notify_info(
+        None,
+        format!(
+            "Generated {lane_count} lanes in {} seconds",
+            delta.as_secs_f32()
+        ),
+    );
+}
+
+fn snap_lanes_to_centerline(
+    mut requests: EventReader<SnapLaneToCenterline>,
+    lanes: Query<&Edge<Entity>, With<LaneMarker>>,
+    anchors: Query<(&Anchor, &Parent)>,
+    grids: Query<(&Grid, &Parent)>,
+    displayed: Res<DisplayedGrid>,
+    mut move_to: EventWriter<MoveTo>,
+) {
+    for request in requests.iter() {
+        let Ok(edge) = lanes.get(request.lane) else {
+            continue;
+        };
+        let (Ok((start, start_parent)), Ok((end, end_parent))) =
+            (anchors.get(edge.start()), anchors.get(edge.end()))
+        else {
+            continue;
+        };
+        if start_parent.get() != end_parent.get() {
+            notify_warning(
+                Some(request.lane),
+                "Cannot snap a lane whose anchors are on different levels",
+            );
+            continue;
+        }
+        let Some((grid, _)) = grids
+            .iter()
+            .find(|(grid, parent)| displayed.shows(grid) && parent.get() == start_parent.get())
+        else {
+            notify_warning(
+                None,
+                "Calculate the occupancy grid before snapping lanes to centerlines",
+            );
+            continue;
+        };
+
+        let p0 = Vec2::from(*start.translation_for_category(Category::General));
+        let p1 = Vec2::from(*end.translation_for_category(Category::General));
+        let Some(left) = (p1 - p0).perp().try_normalize() else {
+            continue;
+        };
+
+        for (anchor, p) in [(edge.start(), p0), (edge.end(), p1)] {
+            let Some(offset) = centerline_offset(grid, p, left) else {
+                notify_warning(
+                    Some(anchor),
+                    format!("Unable to find the walls on both sides of anchor {anchor:?}"),
+                );
+                continue;
+            };
+            if offset.abs() < grid.cell_size / 2.0 {
+                // The anchor is already as close to the centerline as the
+                // grid can tell.
+                continue;
+            }
+
+            let p = p + offset * left;
+            move_to.send(MoveTo {
+                entity: anchor,
+                transform: Transform::from_translation(p.extend(0.0)),
+            });
+        }
+    }
+}
+
+/// Find how far a point needs to move along the `left` direction to be halfway
+/// between the nearest obstacles on either side of it.
+fn centerline_offset(grid: &Grid, p: Vec2, left: Vec2) -> Option<f32> {
+    let (to_left, to_right) = wall_distances(grid, p, left)?;
+    Some((to_left - to_right) / 2.0)
+}
+
+/// Find how far the nearest obstacles are from a point on its left and right
+/// sides, searching up to [`CENTERLINE_SEARCH_DISTANCE`] in each direction.
+fn wall_distances(grid: &Grid, p: Vec2, left: Vec2) -> Option<(f32, f32)> {
+    if grid.occupied.contains(&Cell::from_point(p, grid.cell_size)) {
+        return None;
+    }
+
+    let step = grid.cell_size / 2.0;
+    let steps = (CENTERLINE_SEARCH_DISTANCE / step).ceil() as usize;
+    let distance_to_wall = |dir: Vec2| {
+        (1..=steps).map(|i| i as f32 * step).find(|s| {
+            grid.occupied
+                .contains(&Cell::from_point(p + *s * dir, grid.cell_size))
+        })
+    };
+
+    Some((distance_to_wall(left)?, distance_to_wall(-left)?))
+}
+
+fn check_placement_warnings(
+    mut commands: Commands,
+    mut warnings: ResMut<PlacementWarnings>,
+    mut diagnostics: ResMut<SiteDiagnostics>,
+    changed_grids: Query<(), Changed<Grid>>,
+    grids: Query<(&Grid, &Parent)>,
+    displayed: Res<DisplayedGrid>,
+    lanes: Query<(Entity, &Edge<Entity>), (With<LaneMarker>, Without<Pending>)>,
+    anchors: Query<(&Anchor, &Parent)>,
+    assets: Res<SiteAssets>,
+) {
+    if changed_grids.is_empty() && !displayed.is_changed() && !warnings.is_changed() {
+        return;
+    }
+
+    for marker in warnings.markers.drain(..) {
+        commands.entity(marker).despawn_recursive();
+    }
+
+    let mut issues = Vec::new();
+    let mut markers = Vec::new();
+    for (e, edge) in &lanes {
+        let (Ok((start, start_parent)), Ok((end, end_parent))) =
+            (anchors.get(edge.start()), anchors.get(edge.end()))
+        else {
+            continue;
+        };
+        if start_parent.get() != end_parent.get() {
+            continue;
+        }
+        let level = start_parent.get();
+        let Some((grid, _)) = grids
+            .iter()
+            .find(|(grid, parent)| displayed.shows(grid) && parent.get() == level)
+        else {
+            continue;
+        };
+
+        let p0 = Vec2::from(*start.translation_for_category(Category::General));
+        let p1 = Vec2::from(*end.translation_for_category(Category::General));
+        let Some(left) = (p1 - p0).perp().try_normalize() else {
+            continue;
+        };
+
+        // Check points along the lane, leaving out the anchors themselves
+        // since lanes are often meant to end right next to walls.
+        let step = grid.cell_size / 2.0;
+        let samples = ((p1 - p0).length() / step).floor() as usize;
+        let mut blocked_at = None;
+        let mut narrowest: Option<(f32, Vec2)> = None;
+        for i in 1..samples {
+            let p = p0.lerp(p1, i as f32 / samples as f32);
+            if grid.occupied.contains(&Cell::from_point(p, grid.cell_size)) {
+                blocked_at = Some(p);
+                break;
+            }
+
+            if let Some((to_left, to_right)) = wall_distances(grid, p, left) {
+                let width = to_left + to_right;
+                if narrowest.filter(|(w, _)| *w <= width).is_none() {
+                    narrowest = Some((width, p));
+                }
+            }
+        }
+
+        let warning = if let Some(p) = blocked_at {
+            Some((p, "Lane is blocked by an obstacle".to_owned()))
+        } else if let Some((width, p)) = narrowest {
+            if width < warnings.min_corridor_width {
+                Some((
+                    p,
+                    format!("Lane runs through a corridor that is only {width:.2} m wide"),
+                ))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        if let Some((p, message)) = warning {
+            issues.push(Issue::new(e, message));
+            let marker = commands
+                .spawn(PbrBundle {
+                    mesh: assets.placement_warning_mesh.clone(),
+                    material: assets.placement_warning_material.clone(),
+                    transform: Transform::from_translation(p.extend(PLACEMENT_WARNING_HEIGHT)),
+                    ..default()
+                })
+                .id();
+            commands.entity(level).add_child(marker);
+            markers.push(marker);
+        }
+    }
+
+    warnings.markers = markers;
+    diagnostics.issues.insert(PLACEMENT_ISSUES, issues);
+}
+
+/// Find the cells that a robot of the given radius can occupy and thin them
+/// down to a skeleton that is one cell wide, approximating the medial axis of
+/// the free space.
+fn compute_skeleton(grid: &Grid, robot_radius: f32) -> HashSet<Cell> {
+    // Measure how many cells away from an obstacle each free cell is using a
+    // brushfire expansion. Anything outside of the grid range is treated as
+    // an obstacle.
+    let mut clearance: HashMap<Cell, usize> = HashMap::new();
+    let mut frontier: Vec<Cell> = Vec::new();
+    let (min, max) = (grid.range.min_cell(), grid.range.max_cell());
+    let in_range = |c: &Cell| min.x <= c.x && c.x <= max.x && min.y <= c.y && c.y <= max.y;
+    for (x, y) in grid.range.iter() {
+        let cell = Cell::new(x, y);
+        if grid.occupied.contains(&cell) {
+            continue;
+        }
+
+        let touches_obstacle = NEIGHBORS.iter().any(|(dx, dy)| {
+            let n = cell.shifted(*dx, *dy);
+            !in_range(&n) || grid.occupied.contains(&n)
+        });
+        if touches_obstacle {
+            clearance.insert(cell, 1);
+            frontier.push(cell);
+        }
+    }
+
+    let mut distance = 1;
+    while !frontier.is_empty() {
+        distance += 1;
+        let mut next_frontier = Vec::new();
+        for cell in frontier {
+            for (dx, dy) in &NEIGHBORS {
+                let n = cell.shifted(*dx, *dy);
+                if !in_range(&n) || grid.occupied.contains(&n) || clearance.contains_key(&n) {
+                    continue;
+                }
+                clearance.insert(n, distance);
+                next_frontier.push(n);
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    let mut free: HashSet<Cell> = clearance
+        .into_iter()
+        .filter(|(_, d)| (*d as f32 - 0.5) * grid.cell_size >= robot_radius)
+        .map(|(cell, _)| cell)
+        .collect();
+
+    // Zhang-Suen thinning
+    loop {
+        let mut changed = false;
+        for step in 0..2 {
+            let remove: Vec<Cell> = free
+                .iter()
+                .filter(|cell| {
+                    let p: Vec<bool> = NEIGHBORS
+                        .iter()
+                        .map(|(dx, dy)| free.contains(&cell.shifted(*dx, *dy)))
+                        .collect();
+                    let count = p.iter().filter(|b| **b).count();
+                    let transitions = (0..8).filter(|i| !p[*i] && p[(*i + 1) % 8]).count();
+                    let (n, e, s, w) = (p[0], p[2], p[4], p[6]);
+                    let erodes = if step == 0 {
+                        !(n && e && s) && !(e && s && w)
+                    } else {
+                        !(n && e && w) && !(n && s && w)
+                    };
+                    (2..=6).contains(&count) && transitions == 1 && erodes
+                })
+                .copied()
+                .collect();
+
+            changed |= !remove.is_empty();
+            for cell in remove {
+                free.remove(&cell);
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    free
+}
+
+fn skeleton_neighbors(skeleton: &HashSet<Cell>, cell: Cell) -> Vec<Cell> {
+    NEIGHBORS
+        .iter()
+        .map(|(dx, dy)| cell.shifted(*dx, *dy))
+        .filter(|n| skeleton.contains(n))
+        .collect()
+}
+
+/// Walk along the skeleton from one cell until a junction or dead end is
+/// reached, marking each step as visited.
+fn walk_skeleton(
+    skeleton: &HashSet<Cell>,
+    start: Cell,
+    first: Cell,
+    visited: &mut HashSet<(Cell, Cell)>,
+) -> Vec<Cell> {
+    let mut chain = vec![start];
+    let mut prev = start;
+    let mut current = first;
+    loop {
+        visited.insert((prev, current));
+        visited.insert((current, prev));
+        chain.push(current);
+        if current == start {
+            break;
+        }
+
+        let neighbors = skeleton_neighbors(skeleton, current);
+        if neighbors.len() != 2 {
+            break;
+        }
+
+        let Some(next) = neighbors.into_iter().find(|n| *n != prev) else {
+            break;
+        };
+        if visited.contains(&(current, next)) {
+            break;
+        }
+        prev = current;
+        current = next;
+    }
+
+    chain
+}
+
+/// Break a skeleton into chains of cells that connect its junctions and dead
+/// ends to each other.
+fn trace_skeleton(skeleton: &HashSet<Cell>) -> Vec<Vec<Cell>> {
+    let mut visited = HashSet::new();
+    let mut chains = Vec::new();
+    // Start from the junctions and dead ends first so that chains end at
+    // them, then pick up any loops that have no junctions.
+    let is_node = |cell: &&Cell| skeleton_neighbors(skeleton, **cell).len() != 2;
+    let nodes = skeleton.iter().filter(is_node);
+    for cell in nodes.chain(skeleton.iter()) {
+        for n in skeleton_neighbors(skeleton, *cell) {
+            if !visited.contains(&(*cell, n)) {
+                chains.push(walk_skeleton(skeleton, *cell, n, &mut visited));
+            }
+        }
+    }
+
+    chains
+}
@@ -0,0 +1,234 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::{CameraControls, HeadlightToggle, ReferenceGrid},
+    settings::{config_file, load_config, save_config},
+    site::DefaultFile,
+    CurrentWorkspace, DisplayUnits, SaveWorkspace, UnsavedChanges,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How many files are remembered in the list of recent files
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// The colors of the editor panels and menus
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UiTheme {
+    /// Follow the light or dark mode of the operating system
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl UiTheme {
+    pub const ALL: [UiTheme; 3] = [UiTheme::System, UiTheme::Dark, UiTheme::Light];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UiTheme::System => "System",
+            UiTheme::Dark => "Dark",
+            UiTheme::Light => "Light",
+        }
+    }
+
+    pub fn is_dark(&self) -> bool {
+        match self {
+            UiTheme::System => system_prefers_dark(),
+            UiTheme::Dark => true,
+            UiTheme::Light => false,
+        }
+    }
+}
+
+/// Ask the operating system whether it is using a dark theme. Dark is assumed
+/// when the operating system has no preference.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn system_prefers_dark() -> bool {
+    !matches!(dark_light::detect(), dark_light::Mode::Light)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn system_prefers_dark() -> bool {
+    true
+}
+
+/// How the camera starts out and responds to the mouse
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CameraPreferences {
+    /// Start each session looking straight down with the orthographic camera
+    pub start_orthographic: bool,
+    /// Multiplier for how far each step of the scroll wheel zooms
+    pub zoom_speed: f32,
+    /// Multiplier for how far the camera pans when the mouse is dragged
+    pub pan_speed: f32,
+    /// Multiplier for how far the camera turns when orbiting
+    pub orbit_speed: f32,
+}
+
+impl Default for CameraPreferences {
+    fn default() -> Self {
+        Self {
+            start_orthographic: false,
+            zoom_speed: 1.0,
+            pan_speed: 1.0,
+            orbit_speed: 1.0,
+        }
+    }
+}
+
+/// Choices that the user makes about the editor itself, which carry over
+/// from one session to the next
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: UiTheme,
+    pub units: DisplayUnits,
+    /// The language of the editor, named after its bundle in the
+    /// translations folder. The editor is shown in English when this is None.
+    pub language: Option<String>,
+    pub camera: CameraPreferences,
+    pub grid: ReferenceGrid,
+    /// Minutes between autosaves of the open workspace to a sidecar file next
+    /// to its file. Autosave is turned off when this is zero.
+    pub autosave_minutes: u32,
+    /// Files that were recently opened or saved, starting with the most
+    /// recent one
+    pub recent_files: Vec<PathBuf>,
+    /// Files that are always listed above the recent files, no matter how
+    /// long ago they were opened
+    pub pinned_files: Vec<PathBuf>,
+    /// Folders that are added to the asset library at the start of each
+    /// session
+    pub asset_directories: Vec<PathBuf>,
+}
+
+impl Preferences {
+    /// Move a file to the front of the recent files
+    pub fn add_recent_file(&mut self, path: &Path) {
+        self.recent_files.retain(|p| p != path);
+        self.recent_files.insert(0, path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    /// Pin a file above the recent files, or unpin it
+    pub fn pin_file(&mut self, path: &Path, pinned: bool) {
+        self.pinned_files.retain(|p| p != path);
+        if pinned {
+            self.pinned_files.push(path.to_path_buf());
+        }
+    }
+
+    pub fn is_pinned(&self, path: &Path) -> bool {
+        self.pinned_files.iter().any(|p| p == path)
+    }
+
+    /// The file that preferences are saved in
+    pub fn file() -> Option<PathBuf> {
+        config_file("preferences.json")
+    }
+
+    /// Load the preferences from the last session, if they were saved
+    pub fn load() -> Option<Self> {
+        load_config("preferences.json", "preferences")
+    }
+
+    pub fn save(&self) {
+        save_config("preferences.json", "preferences", self);
+    }
+}
+
+pub struct PreferencesPlugin;
+
+impl Plugin for PreferencesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Preferences::load().unwrap_or_default())
+            .add_startup_system(apply_camera_preferences)
+            .add_system(record_recent_files)
+            .add_system(save_preferences);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_system(autosave_workspace);
+    }
+}
+
+pub fn save_preferences(preferences: Res<Preferences>) {
+    if preferences.is_changed() && !preferences.is_added() {
+        preferences.save();
+    }
+}
+
+fn apply_camera_preferences(
+    preferences: Res<Preferences>,
+    mut camera_controls: ResMut<CameraControls>,
+    mut cameras: Query<&mut Camera>,
+    mut visibilities: Query<&mut Visibility>,
+    headlight_toggle: Res<HeadlightToggle>,
+) {
+    if preferences.camera.start_orthographic {
+        camera_controls.use_orthographic(true, &mut cameras, &mut visibilities, headlight_toggle.0);
+    }
+}
+
+pub fn record_recent_files(
+    mut preferences: ResMut<Preferences>,
+    changed_files: Query<&DefaultFile, Changed<DefaultFile>>,
+) {
+    for file in &changed_files {
+        if preferences.recent_files.first() != Some(&file.0) {
+            preferences.add_recent_file(&file.0);
+        }
+    }
+}
+
+/// Save the current workspace to its autosave file each time the autosave
+/// interval passes, if it has changed since it was last saved. Workspaces
+/// that have never been saved to a file are skipped so that autosave never
+/// opens a file dialog.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn autosave_workspace(
+    mut elapsed: Local<f32>,
+    time: Res<Time>,
+    preferences: Res<Preferences>,
+    current_workspace: Res<CurrentWorkspace>,
+    unsaved: Res<UnsavedChanges>,
+    default_files: Query<&DefaultFile>,
+    mut save_workspace: EventWriter<SaveWorkspace>,
+) {
+    if preferences.autosave_minutes == 0 {
+        *elapsed = 0.0;
+        return;
+    }
+
+    *elapsed += time.delta_seconds();
+    if *elapsed < preferences.autosave_minutes as f32 * 60.0 {
+        return;
+    }
+    *elapsed = 0.0;
+
+    let Some(root) = current_workspace.root else {
+        return;
+    };
+    if unsaved.0 && default_files.get(root).is_ok() {
+        save_workspace.send(SaveWorkspace::new().to_autosave());
+    }
+}
@@ -15,21 +15,27 @@
  *
 */
 
-use crate::site::{DefaultFile, SaveSite};
+use crate::site::{DefaultFile, ExportSupervisorConfig, SaveSite, SiteSaveOptions};
+use crate::widgets::SaveAsDisplay;
 use crate::workcell::SaveWorkcell;
-use crate::{AppState, CurrentWorkspace};
-use bevy::prelude::*;
-
+use crate::{
+    console::notify_warning, AppState, CurrentWorkspace, UnsavedChanges, WorkspaceFileWatch,
+};
+use bevy::{prelude::*, utils::HashMap};
 #[cfg(not(target_arch = "wasm32"))]
 use rfd::FileDialog;
+use rmf_site_format::SiteFileFormat;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct SaveWorkspace {
     /// If specified workspace will be saved to requested file, otherwise the default file
     pub destination: SaveWorkspaceDestination,
     /// If specified the workspace will be exported to a specific format
     pub format: ExportFormat,
+    /// How a site should be written. When a site is saved through a file
+    /// dialog without these, the user is asked to choose them first.
+    pub site_options: Option<SiteSaveOptions>,
 }
 
 impl SaveWorkspace {
@@ -37,6 +43,7 @@ impl SaveWorkspace {
         Self {
             destination: SaveWorkspaceDestination::default(),
             format: ExportFormat::default(),
+            site_options: None,
         }
     }
 
@@ -55,10 +62,32 @@ impl SaveWorkspace {
         self
     }
 
+    /// Save to the autosave file next to the default file of the workspace.
+    /// Workspaces that have never been saved to a file are skipped.
+    pub fn to_autosave(mut self) -> Self {
+        self.destination = SaveWorkspaceDestination::Autosave;
+        self
+    }
+
+    pub fn with_site_options(mut self, options: SiteSaveOptions) -> Self {
+        self.site_options = Some(options);
+        self
+    }
+
     pub fn to_urdf(mut self) -> Self {
         self.format = ExportFormat::Urdf;
         self
     }
+
+    pub fn to_sdf(mut self) -> Self {
+        self.format = ExportFormat::Sdf;
+        self
+    }
+
+    pub fn to_supervisor_config(mut self) -> Self {
+        self.format = ExportFormat::SupervisorConfig;
+        self
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -67,6 +96,99 @@ pub enum SaveWorkspaceDestination {
     DefaultFile,
     Dialog,
     Path(PathBuf),
+    Autosave,
+}
+
+/// The last autosave of one workspace
+#[derive(Debug, Clone)]
+pub struct AutosaveRecord {
+    /// The file that was autosaved to
+    pub file: PathBuf,
+    /// Seconds since startup when the autosave finished
+    pub time: f64,
+}
+
+/// Keeps track of the autosaves that have happened during this session,
+/// keyed by the root entity of each workspace
+#[derive(Resource, Default, Debug)]
+pub struct AutosaveStatus(pub HashMap<Entity, AutosaveRecord>);
+
+/// Extensions of workspace files that contain dots of their own. They are
+/// kept whole when naming autosaves.
+const WORKSPACE_EXTENSIONS: [&str; 3] = ["building.yaml", "workcell.json", "urdf"];
+
+/// The editor cannot write legacy building files, so sites that were opened
+/// from one are saved in the site format
+const LEGACY_BUILDING_EXTENSION: &str = "building.yaml";
+
+/// The sidecar file that autosaves of a file are written to. It sits next to
+/// the file as a hidden file and keeps the extension of the file so that it
+/// can be opened like any other workspace, e.g. `office.site.ron` is
+/// autosaved to `.office.autosave.site.ron`. Legacy buildings are autosaved
+/// as RON site files, e.g. `.office.autosave.site.ron` for
+/// `office.building.yaml`.
+pub fn autosave_path(file: &Path) -> PathBuf {
+    let name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let known = SiteFileFormat::ALL
+        .iter()
+        .map(|format| format.extension())
+        .chain(WORKSPACE_EXTENSIONS)
+        .find_map(|extension| {
+            let stem = name.strip_suffix(extension)?.strip_suffix('.')?;
+            (!stem.is_empty()).then(|| (stem, extension))
+        });
+    let name = match known.or_else(|| name.rsplit_once('.')) {
+        Some((stem, extension)) if !stem.is_empty() => {
+            let extension = match extension {
+                LEGACY_BUILDING_EXTENSION => SiteFileFormat::Ron.extension(),
+                extension => extension,
+            };
+            format!(".{stem}.autosave.{extension}")
+        }
+        _ => format!(".{name}.autosave"),
+    };
+    file.with_file_name(name)
+}
+
+/// Check if a file has an autosave that is newer than the file itself, which
+/// means the editor was closed before its latest changes were saved.
+pub fn has_newer_autosave(file: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(&autosave_path(file)), modified(file)) {
+        (Some(autosave), Some(saved)) => autosave > saved,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+/// Keep track of a save that has finished. Autosaves leave the unsaved
+/// changes marker alone, while saving to the file itself makes its autosave
+/// obsolete.
+pub fn record_finished_save(world: &mut World, root: Entity, path: &Path, autosave: bool) {
+    if autosave {
+        let time = world.resource::<Time>().elapsed_seconds_f64();
+        let file = path.to_path_buf();
+        world
+            .resource_mut::<AutosaveStatus>()
+            .0
+            .insert(root, AutosaveRecord { file, time });
+        return;
+    }
+
+    world.resource_mut::<UnsavedChanges>().0 = false;
+    world.resource_mut::<WorkspaceFileWatch>().saved(path);
+    let autosave = autosave_path(path);
+    if autosave.exists() {
+        if let Err(err) = std::fs::remove_file(&autosave) {
+            notify_warning(
+                None,
+                format!("Unable to remove autosave file {autosave:?}: {err}"),
+            );
+        }
+    }
 }
 
 #[derive(Clone, Default, Debug)]
@@ -74,13 +196,16 @@ pub enum ExportFormat {
     #[default]
     Default,
     Urdf,
+    Sdf,
+    SupervisorConfig,
 }
 
 pub struct SavePlugin;
 
 impl Plugin for SavePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SaveWorkspace>();
+        app.add_event::<SaveWorkspace>()
+            .init_resource::<AutosaveStatus>();
         #[cfg(not(target_arch = "wasm32"))]
         app.add_system(dispatch_save_events);
     }
@@ -91,23 +216,47 @@ pub fn dispatch_save_events(
     mut save_events: EventReader<SaveWorkspace>,
     mut save_site: EventWriter<SaveSite>,
     mut save_workcell: EventWriter<SaveWorkcell>,
+    mut export_supervisor_config: EventWriter<ExportSupervisorConfig>,
     app_state: Res<State<AppState>>,
     workspace: Res<CurrentWorkspace>,
     default_files: Query<&DefaultFile>,
+    mut save_as_display: ResMut<SaveAsDisplay>,
+    file_watch: Res<WorkspaceFileWatch>,
 ) {
     for event in save_events.iter() {
+        let choosing_site_file = matches!(event.destination, SaveWorkspaceDestination::Dialog)
+            && matches!(event.format, ExportFormat::Default)
+            && *app_state.current() == AppState::SiteEditor;
+        if choosing_site_file && event.site_options.is_none() {
+            save_as_display.open = true;
+            continue;
+        }
+        let site_options = event.site_options.clone().unwrap_or_default();
+
         if let Some(ws_root) = workspace.root {
             let path = match &event.destination {
                 SaveWorkspaceDestination::DefaultFile => {
                     if let Some(file) = default_files.get(ws_root).ok().map(|f| f.0.clone()) {
                         file
                     } else {
-                        let Some(file) =  FileDialog::new().save_file() else {
+                        let Some(file) = FileDialog::new().save_file() else {
                             continue;
                         };
                         file
                     }
                 }
+                SaveWorkspaceDestination::Dialog if choosing_site_file => {
+                    let format = site_options.format;
+                    // File dialogs only filter by the last part of the name
+                    let filter = format.extension().rsplit('.').next().unwrap_or_default();
+                    let Some(file) = FileDialog::new()
+                        .add_filter(format.label(), &[filter])
+                        .save_file()
+                    else {
+                        continue;
+                    };
+                    with_site_extension(file, format)
+                }
                 SaveWorkspaceDestination::Dialog => {
                     // TODO(luca) async impl?
                     let Some(file) = FileDialog::new().save_file() else {
@@ -116,21 +265,52 @@ pub fn dispatch_save_events(
                     file
                 }
                 SaveWorkspaceDestination::Path(path) => path.clone(),
+                SaveWorkspaceDestination::Autosave => {
+                    let Ok(file) = default_files.get(ws_root) else {
+                        continue;
+                    };
+                    autosave_path(&file.0)
+                }
             };
+            // Autosaves go to a sidecar file, but every other destination,
+            // including a file chosen in a dialog, may be the watched file
+            let autosave = matches!(event.destination, SaveWorkspaceDestination::Autosave);
+            if !autosave && file_watch.is_changed_on_disk(&path) {
+                notify_warning(
+                    None,
+                    format!(
+                        "{} was changed by another program. Choose whether to \
+                        reload it or keep your version before saving.",
+                        path.display()
+                    ),
+                );
+                continue;
+            }
             match app_state.current() {
                 AppState::WorkcellEditor => {
                     save_workcell.send(SaveWorkcell {
                         root: ws_root,
                         to_file: path,
                         format: event.format.clone(),
+                        autosave,
                     });
                 }
-                AppState::SiteEditor => {
-                    save_site.send(SaveSite {
-                        site: ws_root,
-                        to_file: path,
-                    });
-                }
+                AppState::SiteEditor => match event.format {
+                    ExportFormat::SupervisorConfig => {
+                        export_supervisor_config.send(ExportSupervisorConfig {
+                            site: ws_root,
+                            to_file: path,
+                        });
+                    }
+                    _ => {
+                        save_site.send(SaveSite {
+                            site: ws_root,
+                            to_file: path,
+                            autosave,
+                            options: site_options.clone(),
+                        });
+                    }
+                },
                 AppState::MainMenu => { /* Noop */ }
             }
         } else {
@@ -139,3 +319,171 @@ pub fn dispatch_save_events(
         }
     }
 }
+
+/// Make sure the name of a site file ends the way its format expects, e.g. a
+/// file named `office`, `office.json` or `office.site.ron` is saved as
+/// `office.site.json` when JSON is chosen. Other dots in the name are kept.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_site_extension(file: PathBuf, format: SiteFileFormat) -> PathBuf {
+    let Some(name) = file.file_name().and_then(|name| name.to_str()) else {
+        return file;
+    };
+    if name.ends_with(format.extension()) {
+        return file;
+    }
+    let extensions = SiteFileFormat::ALL.map(|format| format.extension());
+    let stem = extensions
+        .iter()
+        .copied()
+        .chain(extensions.iter().filter_map(|ext| ext.rsplit('.').next()))
+        .find_map(|ext| name.strip_suffix(ext)?.strip_suffix('.'))
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(name);
+    let name = format!("{stem}.{}", format.extension());
+    file.with_file_name(name)
+}
+
+/// Export the door and lift supervisor config of a site file without opening
+/// the editor.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_supervisor_config_headless(
+    site_file: &PathBuf,
+    to_file: &PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::WorkspaceData;
+    use rmf_site_format::legacy::{building_map::BuildingMap, supervisor::SupervisorConfig};
+    use rmf_site_format::Site;
+
+    let data = std::fs::read(site_file)?;
+    let site = match WorkspaceData::new(site_file, data) {
+        Some(WorkspaceData::LegacyBuilding(data)) => BuildingMap::from_bytes(&data)?.to_site()?,
+        Some(WorkspaceData::Site(data)) => {
+            let format = SiteFileFormat::from_path(site_file).unwrap_or_default();
+            Site::from_bytes_as(format, &data)?
+        }
+        _ => {
+            return Err(format!("{site_file:?} is not a site or building file").into());
+        }
+    };
+
+    let config = SupervisorConfig::from_site(&site)?;
+    let f = std::fs::File::create(to_file)?;
+    serde_yaml::to_writer(f, &config)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_autosave(file: &str, autosave: &str) {
+        let dir = Path::new("maps");
+        assert_eq!(autosave_path(&dir.join(file)), dir.join(autosave));
+    }
+
+    #[test]
+    fn autosaves_keep_the_extension_of_site_files() {
+        assert_autosave("office.site.ron", ".office.autosave.site.ron");
+        assert_autosave("office.site.json", ".office.autosave.site.json");
+        assert_autosave("office.site.yaml", ".office.autosave.site.yaml");
+    }
+
+    #[test]
+    fn autosaves_keep_dots_in_the_name_of_the_file() {
+        assert_autosave("my.office.site.ron", ".my.office.autosave.site.ron");
+        assert_autosave("v1.2.workcell.json", ".v1.2.autosave.workcell.json");
+        assert_autosave("arm.v2.urdf", ".arm.v2.autosave.urdf");
+    }
+
+    #[test]
+    fn legacy_buildings_are_autosaved_as_site_files() {
+        use crate::WorkspaceData;
+        use rmf_site_format::{legacy::building_map::BuildingMap, Site};
+
+        assert_autosave("hq.building.yaml", ".hq.autosave.site.ron");
+        assert_autosave("my.hq.building.yaml", ".my.hq.autosave.site.ron");
+
+        let dir = tempfile::tempdir().unwrap();
+        let building = dir.path().join("office.building.yaml");
+        std::fs::copy("../assets/demo_maps/office.building.yaml", &building).unwrap();
+        let data = std::fs::read(&building).unwrap();
+        let site = BuildingMap::from_bytes(&data).unwrap().to_site().unwrap();
+
+        // Saving picks the format from the name of the autosave file
+        let autosave = autosave_path(&building);
+        let format = SiteFileFormat::from_path(&autosave).unwrap();
+        let f = std::fs::File::create(&autosave).unwrap();
+        site.to_writer_as(format, f).unwrap();
+
+        // Restoring opens the autosave as a site, with the format that is
+        // used for the file of the workspace
+        let data = std::fs::read(&autosave).unwrap();
+        let Some(WorkspaceData::Site(data)) = WorkspaceData::new(&autosave, data) else {
+            panic!("the autosave of a building should be opened as a site");
+        };
+        let format = SiteFileFormat::from_path(&building).unwrap_or_default();
+        let restored = Site::from_bytes_as(format, &data).unwrap();
+        assert_eq!(restored.levels.len(), site.levels.len());
+        assert_eq!(restored.lifts.len(), site.lifts.len());
+    }
+
+    #[test]
+    fn newer_autosaves_are_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("office.site.ron");
+        let autosave = autosave_path(&file);
+        let set_modified = |path: &Path, seconds: u64| {
+            let time = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(seconds);
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        };
+
+        // Nothing has been saved yet
+        assert!(!has_newer_autosave(&file));
+
+        std::fs::write(&file, "saved").unwrap();
+        assert!(!has_newer_autosave(&file));
+
+        std::fs::write(&autosave, "autosaved").unwrap();
+        set_modified(&file, 1000);
+        set_modified(&autosave, 2000);
+        assert!(has_newer_autosave(&file));
+
+        // Saving the file after the autosave makes the autosave obsolete
+        set_modified(&file, 3000);
+        assert!(!has_newer_autosave(&file));
+
+        // An autosave of a file that has since been removed is still offered
+        std::fs::remove_file(&file).unwrap();
+        assert!(has_newer_autosave(&file));
+    }
+
+    #[test]
+    fn site_files_get_the_extension_of_their_format() {
+        let json = |name: &str| {
+            with_site_extension(PathBuf::from("maps").join(name), SiteFileFormat::Json)
+        };
+        let expected = |name: &str| PathBuf::from("maps").join(name);
+        assert_eq!(json("office"), expected("office.site.json"));
+        assert_eq!(json("office.site.json"), expected("office.site.json"));
+        assert_eq!(json("office.json"), expected("office.site.json"));
+        assert_eq!(json("office.site.ron"), expected("office.site.json"));
+        assert_eq!(json("my.office"), expected("my.office.site.json"));
+        assert_eq!(json("my.office.site.yaml"), expected("my.office.site.json"));
+        assert_eq!(
+            with_site_extension(PathBuf::from("office.ron"), SiteFileFormat::MessagePack),
+            PathBuf::from("office.site.msgpack")
+        );
+    }
+
+    #[test]
+    fn autosaves_of_other_files_use_their_last_extension() {
+        assert_autosave("notes.v2.txt", ".notes.v2.autosave.txt");
+        assert_autosave("office", ".office.autosave");
+        assert_autosave(".hidden", "..hidden.autosave");
+    }
+}
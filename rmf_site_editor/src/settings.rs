@@ -1,8 +1,23 @@
-use bevy::prelude::Resource;
+use crate::{
+    console::report_error,
+    interaction::CameraControls,
+    site::{LoadingDrawings, LodSettings},
+};
+use bevy::{
+    core_pipeline::{bloom::BloomSettings, tonemapping::Tonemapping},
+    pbr::{DirectionalLightShadowMap, PointLightShadowMap},
+    prelude::*,
+};
+use image::imageops::FilterType;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Resource)]
 pub struct Settings {
     pub graphics_quality: GraphicsQuality,
+    /// A preset that was chosen on the command line. This takes priority
+    /// over the render settings that were saved in the last session.
+    pub graphics_preset: Option<GraphicsPreset>,
 }
 
 impl Default for Settings {
@@ -10,6 +25,7 @@ impl Default for Settings {
         Self {
             // todo: select based on WASM and GPU (or not)
             graphics_quality: GraphicsQuality::Low,
+            graphics_preset: None,
         }
     }
 }
@@ -19,3 +35,343 @@ pub enum GraphicsQuality {
     Low,
     Ultra,
 }
+
+/// Combinations of render settings that suit different kinds of computers,
+/// so the same site can be edited on a laptop and on a workstation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsPreset {
+    Low,
+    Medium,
+    High,
+}
+
+impl GraphicsPreset {
+    pub const ALL: [GraphicsPreset; 3] = [Self::Low, Self::Medium, Self::High];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Low => "No shadows or anti-aliasing, smaller textures, and simpler models",
+            Self::Medium => "Shadows and anti-aliasing with limited texture sizes",
+            Self::High => "Detailed shadows and full size textures for a dedicated graphics card",
+        }
+    }
+
+    /// Parse the name of a preset, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.label().eq_ignore_ascii_case(name))
+    }
+}
+
+impl From<&GraphicsQuality> for GraphicsPreset {
+    fn from(quality: &GraphicsQuality) -> Self {
+        match quality {
+            GraphicsQuality::Low => Self::Low,
+            GraphicsQuality::Ultra => Self::High,
+        }
+    }
+}
+
+/// Sizes that can be chosen for the shadow maps of lights
+pub const SHADOW_MAP_SIZES: [usize; 4] = [512, 1024, 2048, 4096];
+/// Sample counts that can be chosen for multisample anti-aliasing. wgpu only
+/// guarantees support for 1 and 4 samples.
+pub const MSAA_SAMPLES: [u32; 2] = [1, 4];
+/// Limits that can be chosen for the width and height of textures
+pub const TEXTURE_SIZE_LIMITS: [u32; 3] = [1024, 2048, 4096];
+
+/// Settings that trade the quality of the rendering for performance. These
+/// are saved on this computer so they persist across sessions.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RenderSettings {
+    /// Let lights cast shadows. Lights that have shadows turned off in the
+    /// site never cast them.
+    pub shadows: bool,
+    /// Width and height in pixels of the shadow map of each light
+    pub shadow_map_size: usize,
+    /// How far in meters from the center of the scene directional lights
+    /// cast shadows
+    pub shadow_distance: f32,
+    pub msaa_samples: u32,
+    /// Make bright surfaces glow. This renders the viewport in HDR, which
+    /// costs more memory and bandwidth.
+    pub bloom: bool,
+    pub bloom_intensity: f32,
+    /// Map the colors of the scene into the range of the display, which
+    /// keeps bright lights from washing out surfaces
+    pub tonemapping: bool,
+    /// Textures that are larger than this are scaled down when they are
+    /// loaded, which saves graphics memory. Drawings are never scaled down
+    /// because their size determines their scale in the site.
+    pub max_texture_size: Option<u32>,
+    /// Models with more triangles than this get simplified levels of detail.
+    /// Every copy of a model shares the same meshes, so this is what limits
+    /// how much geometry crowded sites send to the graphics card.
+    pub model_triangle_budget: usize,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self::preset(GraphicsPreset::High)
+    }
+}
+
+impl RenderSettings {
+    pub fn preset(preset: GraphicsPreset) -> Self {
+        match preset {
+            GraphicsPreset::Low => Self {
+                shadows: false,
+                shadow_map_size: 1024,
+                shadow_distance: 50.0,
+                msaa_samples: 1,
+                bloom: false,
+                bloom_intensity: 0.3,
+                tonemapping: true,
+                max_texture_size: Some(1024),
+                model_triangle_budget: 20_000,
+            },
+            GraphicsPreset::Medium => Self {
+                shadows: true,
+                shadow_map_size: 1024,
+                shadow_distance: 50.0,
+                msaa_samples: 4,
+                bloom: false,
+                bloom_intensity: 0.3,
+                tonemapping: true,
+                max_texture_size: Some(2048),
+                model_triangle_budget: 50_000,
+            },
+            GraphicsPreset::High => Self {
+                shadows: true,
+                shadow_map_size: 2048,
+                shadow_distance: 100.0,
+                msaa_samples: 4,
+                bloom: false,
+                bloom_intensity: 0.3,
+                tonemapping: true,
+                max_texture_size: None,
+                model_triangle_budget: 200_000,
+            },
+        }
+    }
+
+    /// The preset that these settings came from, if they have not been
+    /// customized since
+    pub fn current_preset(&self) -> Option<GraphicsPreset> {
+        GraphicsPreset::ALL
+            .into_iter()
+            .find(|preset| *self == Self::preset(*preset))
+    }
+
+    /// The file that render settings are saved in
+    pub fn file() -> Option<PathBuf> {
+        config_file("render_settings.json")
+    }
+
+    /// Load the settings from the last session, if they were saved
+    pub fn load() -> Option<Self> {
+        load_config("render_settings.json", "render settings")
+    }
+
+    pub fn save(&self) {
+        save_config("render_settings.json", "render settings", self);
+    }
+
+    pub fn shadow_projection(&self) -> OrthographicProjection {
+        let d = self.shadow_distance;
+        OrthographicProjection {
+            left: -d,
+            right: d,
+            bottom: -d,
+            top: d,
+            near: -d,
+            far: d,
+            ..default()
+        }
+    }
+}
+
+/// The file in the configuration folder of this computer where the editor
+/// keeps one kind of setting between sessions
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    let mut p = dirs::config_dir()?;
+    p.push("open-rmf");
+    p.push("rmf_site_editor");
+    p.push(name);
+    Some(p)
+}
+
+/// Load a setting that was saved by [`save_config`] in an earlier session.
+/// The description is used to report a file that could not be parsed.
+pub fn load_config<T: DeserializeOwned>(name: &str, description: &str) -> Option<T> {
+    let bytes = std::fs::read(config_file(name)?).ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            report_error(None, format!("Unable to parse saved {description}: {err}"));
+            None
+        }
+    }
+}
+
+pub fn save_config<T: Serialize>(name: &str, description: &str, value: &T) {
+    let Some(path) = config_file(name) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        if let Err(err) = std::fs::create_dir_all(dir) {
+            report_error(
+                None,
+                format!("Unable to create settings folder {dir:?}: {err}"),
+            );
+            return;
+        }
+    }
+    let result = serde_json::to_vec_pretty(value)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| std::fs::write(&path, bytes).map_err(|err| err.to_string()));
+    if let Err(err) = result {
+        report_error(
+            None,
+            format!("Unable to save {description} to {path:?}: {err}"),
+        );
+    }
+}
+
+pub fn apply_render_settings(
+    settings: Res<RenderSettings>,
+    mut directional_shadow_map: ResMut<DirectionalLightShadowMap>,
+    mut point_shadow_map: ResMut<PointLightShadowMap>,
+    mut msaa: ResMut<Msaa>,
+    mut lod_settings: ResMut<LodSettings>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    directional_shadow_map.size = settings.shadow_map_size;
+    point_shadow_map.size = settings.shadow_map_size;
+    msaa.samples = settings.msaa_samples;
+    if lod_settings.triangle_budget != settings.model_triangle_budget {
+        lod_settings.triangle_budget = settings.model_triangle_budget;
+    }
+    if !settings.is_added() {
+        settings.save();
+    }
+}
+
+/// Directional lights are created in several places, so keep their shadow
+/// projections in line with the settings after they are created or replaced.
+pub fn update_shadow_distance(
+    settings: Res<RenderSettings>,
+    mut lights: Query<&mut DirectionalLight>,
+) {
+    let projection = settings.shadow_projection();
+    let bounds = |p: &OrthographicProjection| [p.left, p.right, p.bottom, p.top, p.near, p.far];
+    for mut light in &mut lights {
+        if bounds(&light.shadow_projection) != bounds(&projection) {
+            light.shadow_projection = projection.clone();
+        }
+    }
+}
+
+pub fn apply_post_processing(
+    mut commands: Commands,
+    settings: Res<RenderSettings>,
+    camera_controls: Res<CameraControls>,
+    mut cameras: Query<(&mut Camera, &mut Tonemapping)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let base_cameras = [
+        camera_controls.perspective_camera_entities[0],
+        camera_controls.orthographic_camera_entities[0],
+    ];
+    let all_cameras = camera_controls
+        .perspective_camera_entities
+        .iter()
+        .chain(camera_controls.orthographic_camera_entities.iter());
+    for e in all_cameras {
+        let Ok((mut camera, mut tonemapping)) = cameras.get_mut(*e) else {
+            continue;
+        };
+        // Every camera that renders into the window needs to agree on HDR,
+        // otherwise the outline and x-ray layers are drawn into a different
+        // texture than the scene.
+        if camera.hdr != settings.bloom {
+            camera.hdr = settings.bloom;
+        }
+        if tonemapping.is_enabled != settings.tonemapping {
+            tonemapping.is_enabled = settings.tonemapping;
+        }
+    }
+
+    for e in base_cameras {
+        if settings.bloom {
+            commands.entity(e).insert(BloomSettings {
+                intensity: settings.bloom_intensity,
+                ..default()
+            });
+        } else {
+            commands.entity(e).remove::<BloomSettings>();
+        }
+    }
+}
+
+/// Scale down textures that are larger than the limit in the settings as they
+/// get loaded. Changing the limit only affects textures that are loaded
+/// afterwards.
+///
+/// This runs in PreUpdate so that drawings are still waiting in
+/// [`LoadingDrawings`] when their images arrive.
+pub fn limit_texture_sizes(
+    settings: Res<RenderSettings>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut images: ResMut<Assets<Image>>,
+    loading_drawings: Res<LoadingDrawings>,
+) {
+    for event in image_events.iter() {
+        let AssetEvent::Created { handle } = event else {
+            continue;
+        };
+        let Some(limit) = settings.max_texture_size else {
+            continue;
+        };
+        if loading_drawings.0.contains_key(handle) {
+            continue;
+        }
+        let Some(image) = images.get(handle) else {
+            continue;
+        };
+        let size = image.texture_descriptor.size;
+        if size.width <= limit && size.height <= limit {
+            continue;
+        }
+
+        let is_srgb = image.texture_descriptor.format.describe().srgb;
+        let sampler = image.sampler_descriptor.clone();
+        // Compressed and floating point formats cannot be converted, so
+        // those are left at their original size.
+        let Ok(dynamic) = image.clone().try_into_dynamic() else {
+            continue;
+        };
+        let mut resized =
+            Image::from_dynamic(dynamic.resize(limit, limit, FilterType::Triangle), is_srgb);
+        resized.sampler_descriptor = sampler;
+        if let Some(image) = images.get_mut(handle) {
+            *image = resized;
+        }
+    }
+}
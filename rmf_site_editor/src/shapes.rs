@@ -953,6 +953,71 @@ pub(crate) fn make_flat_rect_mesh(x_size: f32, y_size: f32) -> MeshBuffer {
         .with_outline(outline);
 }
 
+/// Make a flat triangle that points along the +X axis with its center at the
+/// origin.
+pub(crate) fn make_flat_arrowhead(length: f32, width: f32) -> MeshBuffer {
+    let x = length / 2.0;
+    let y = width / 2.0;
+    let positions: Vec<[f32; 3]> = [[x, 0., 0.], [-x, y, 0.], [-x, -y, 0.]]
+        .into_iter()
+        .cycle()
+        .take(6)
+        .collect();
+
+    let indices = [0, 1, 2, 3, 5, 4].into_iter().collect();
+
+    let normals: Vec<[f32; 3]> = [[0., 0., 1.]]
+        .into_iter()
+        .cycle()
+        .take(3)
+        .chain([[0., 0., -1.]].into_iter().cycle().take(3))
+        .collect();
+
+    let outline = [0, 1, 1, 2, 2, 0].into_iter().collect();
+
+    MeshBuffer::new(positions, normals, indices).with_outline(outline)
+}
+
+/// Make a flat strip of constant width that follows a path in the XY plane.
+pub(crate) fn make_flat_strip(path: &[Vec3], width: f32) -> MeshBuffer {
+    let w = width / 2.0;
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    for (i, p) in path.iter().enumerate() {
+        // Use the average direction of the pieces on either side of each
+        // point so that the strip bends smoothly around the corners.
+        let before = path[i.saturating_sub(1)];
+        let after = path[(i + 1).min(path.len() - 1)];
+        let left = (after - before).truncate().perp().normalize_or_zero();
+        positions.push((*p + w * left.extend(0.0)).into());
+        positions.push((*p - w * left.extend(0.0)).into());
+    }
+
+    let n = positions.len() as u32;
+    let mut indices = Vec::new();
+    for i in (0..n.saturating_sub(2)).step_by(2) {
+        indices.extend([i, i + 1, i + 3, i, i + 3, i + 2]);
+        indices.extend([n + i, n + i + 3, n + i + 1, n + i, n + i + 2, n + i + 3]);
+    }
+    positions.extend(positions.clone());
+
+    let normals: Vec<[f32; 3]> = [[0., 0., 1.]]
+        .into_iter()
+        .cycle()
+        .take(n as usize)
+        .chain([[0., 0., -1.]].into_iter().cycle().take(n as usize))
+        .collect();
+
+    let mut outline = Vec::new();
+    for i in (0..n.saturating_sub(2)).step_by(2) {
+        outline.extend([i, i + 2, i + 1, i + 3]);
+    }
+    if n >= 2 {
+        outline.extend([0, 1, n - 2, n - 1]);
+    }
+
+    MeshBuffer::new(positions, normals, indices).with_outline(outline)
+}
+
 pub(crate) fn make_flat_mesh_for_aabb(aabb: Aabb) -> MeshBuffer {
     make_flat_rect_mesh(2.0 * aabb.half_extents.x, 2.0 * aabb.half_extents.y)
         .transform_by(Affine3A::from_translation(aabb.center.into()))
@@ -25,9 +25,12 @@ pub struct SiteAssets {
     pub lane_mid_outline: Handle<Mesh>,
     pub lane_end_mesh: Handle<Mesh>,
     pub lane_end_outline: Handle<Mesh>,
+    pub lane_arrow_mesh: Handle<Mesh>,
     pub box_mesh: Handle<Mesh>,
     pub location_mesh: Handle<Mesh>,
     pub physical_camera_mesh: Handle<Mesh>,
+    pub virtual_robot_mesh: Handle<Mesh>,
+    pub placement_warning_mesh: Handle<Mesh>,
     pub unassigned_lane_material: Handle<StandardMaterial>,
     pub passive_anchor_material: Handle<StandardMaterial>,
     pub unassigned_anchor_material: Handle<StandardMaterial>,
@@ -44,12 +47,25 @@ pub struct SiteAssets {
     pub site_anchor_mesh: Handle<Mesh>,
     pub wall_material: Handle<StandardMaterial>,
     pub lift_wall_material: Handle<StandardMaterial>,
+    pub lift_shaft_material: Handle<StandardMaterial>,
+    pub lane_arrow_material: Handle<StandardMaterial>,
+    pub shared_zone_material: Handle<StandardMaterial>,
+    pub path_preview_material: Handle<StandardMaterial>,
+    pub virtual_robot_material: Handle<StandardMaterial>,
+    pub blocked_robot_material: Handle<StandardMaterial>,
     pub door_body_material: Handle<StandardMaterial>,
     pub translucent_black: Handle<StandardMaterial>,
     pub translucent_white: Handle<StandardMaterial>,
     pub physical_camera_material: Handle<StandardMaterial>,
     pub occupied_material: Handle<StandardMaterial>,
+    pub inflated_material: Handle<StandardMaterial>,
+    pub occupancy_only_base_material: Handle<StandardMaterial>,
+    pub occupancy_only_other_material: Handle<StandardMaterial>,
+    pub reachable_material: Handle<StandardMaterial>,
+    pub placement_warning_material: Handle<StandardMaterial>,
     pub default_mesh_grey_material: Handle<StandardMaterial>,
+    pub missing_asset_material: Handle<StandardMaterial>,
+    pub collision_geometry_material: Handle<StandardMaterial>,
 }
 
 impl FromWorld for SiteAssets {
@@ -120,6 +136,38 @@ impl FromWorld for SiteAssets {
             perceptual_roughness: 0.3,
             ..default()
         });
+        let lift_shaft_material = materials.add(StandardMaterial {
+            base_color: Color::rgba(0.3, 0.6, 0.9, 0.15),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        let lane_arrow_material = materials.add(StandardMaterial {
+            base_color: Color::rgb(0.95, 0.95, 0.95),
+            unlit: true,
+            ..default()
+        });
+        let shared_zone_material = materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 0.75, 0.0, 0.6),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        let path_preview_material = materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.5, 0.0),
+            unlit: true,
+            ..default()
+        });
+        let virtual_robot_material = materials.add(StandardMaterial {
+            base_color: Color::rgb(0.1, 0.6, 0.9),
+            unlit: true,
+            ..default()
+        });
+        let blocked_robot_material = materials.add(StandardMaterial {
+            base_color: Color::rgb(0.9, 0.1, 0.1),
+            unlit: true,
+            ..default()
+        });
         let lift_floor_material = materials.add(StandardMaterial {
             base_color: Color::rgb(0.3, 0.3, 0.3).into(),
             perceptual_roughness: 0.5,
@@ -142,7 +190,41 @@ impl FromWorld for SiteAssets {
         });
         let physical_camera_material = materials.add(Color::rgb(0.6, 0.7, 0.8).into());
         let occupied_material = materials.add(Color::rgba(0.8, 0.1, 0.1, 0.2).into());
+        let inflated_material = materials.add(Color::rgba(0.9, 0.6, 0.1, 0.15).into());
+        let occupancy_only_base_material = materials.add(StandardMaterial {
+            base_color: Color::rgba(0.1, 0.3, 0.9, 0.6),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        let occupancy_only_other_material = materials.add(StandardMaterial {
+            base_color: Color::rgba(0.1, 0.8, 0.2, 0.6),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        let placement_warning_material = materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.8, 0.0),
+            unlit: true,
+            ..default()
+        });
+        let reachable_material = materials.add(StandardMaterial {
+            base_color: Color::rgba(0.2, 0.9, 0.9, 0.4),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
         let default_mesh_grey_material = materials.add(Color::rgb(0.7, 0.7, 0.7).into());
+        let missing_asset_material = materials.add(StandardMaterial {
+            base_color: Color::rgba(0.9, 0.2, 0.9, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        });
+        let collision_geometry_material = materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 0.4, 0.1, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        });
 
         let mut meshes = world.get_resource_mut::<Assets<Mesh>>().unwrap();
         let level_anchor_mesh = meshes.add(
@@ -183,6 +265,7 @@ impl FromWorld for SiteAssets {
             )
             .into(),
         );
+        let lane_arrow_mesh = meshes.add(make_flat_arrowhead(0.5, 0.6).into());
         let box_mesh = meshes.add(
             Mesh::from(shape::Box::new(1., 1., 1.))
                 .with_generated_outline_normals()
@@ -201,6 +284,14 @@ impl FromWorld for SiteAssets {
                 .with_generated_outline_normals()
                 .unwrap(),
         );
+        let virtual_robot_mesh = meshes.add(
+            make_cylinder(VIRTUAL_ROBOT_HEIGHT, VIRTUAL_ROBOT_RADIUS)
+                .transform_by(Affine3A::from_translation(
+                    [0.0, 0.0, VIRTUAL_ROBOT_HEIGHT / 2.0].into(),
+                ))
+                .into(),
+        );
+        let placement_warning_mesh = meshes.add(make_diamond(0.2, 0.15).into());
 
         Self {
             level_anchor_mesh,
@@ -211,9 +302,12 @@ impl FromWorld for SiteAssets {
             lane_mid_outline,
             lane_end_mesh,
             lane_end_outline,
+            lane_arrow_mesh,
             box_mesh,
             location_mesh,
             physical_camera_mesh,
+            virtual_robot_mesh,
+            placement_warning_mesh,
             unassigned_lane_material,
             hover_anchor_material,
             select_anchor_material,
@@ -227,12 +321,25 @@ impl FromWorld for SiteAssets {
             preview_anchor_material,
             wall_material,
             lift_wall_material,
+            lift_shaft_material,
+            lane_arrow_material,
+            shared_zone_material,
+            path_preview_material,
+            virtual_robot_material,
+            blocked_robot_material,
             door_body_material,
             translucent_black,
             translucent_white,
             physical_camera_material,
             occupied_material,
+            inflated_material,
+            occupancy_only_base_material,
+            occupancy_only_other_material,
+            reachable_material,
+            placement_warning_material,
             default_mesh_grey_material,
+            missing_asset_material,
+            collision_geometry_material,
         }
     }
 }
@@ -15,8 +15,9 @@
  *
 */
 
-use crate::site::{SiteState, SiteUpdateLabel};
-use crate::AppState;
+use crate::console::report_error;
+use crate::site::{SiteState, SiteUpdateLabel, UndoEntry, UndoHistory};
+use crate::{AppState, UnsavedChanges};
 use bevy::prelude::*;
 use std::fmt::Debug;
 
@@ -80,9 +81,20 @@ fn update_changed_values<T: Component + Clone + Debug>(
     mut commands: Commands,
     mut values: Query<&mut T>,
     mut changes: EventReader<Change<T>>,
+    mut unsaved: ResMut<UnsavedChanges>,
+    mut history: ResMut<UndoHistory>,
 ) {
+    // Changes that arrive together come from one edit, such as a batch edit
+    // of every selected element, so they are undone together
+    let mut applied = Vec::new();
     for change in changes.iter() {
+        unsaved.0 = true;
         if let Ok(mut new_value) = values.get_mut(change.for_element) {
+            applied.push(UndoEntry::new(
+                change.for_element,
+                new_value.clone(),
+                change.to_value.clone(),
+            ));
             *new_value = change.to_value.clone();
         } else {
             if change.allow_insert {
@@ -90,14 +102,21 @@ fn update_changed_values<T: Component + Clone + Debug>(
                     .entity(change.for_element)
                     .insert(change.to_value.clone());
             } else {
-                println!(
-                    "DEV ERROR: Unable to change {} data to {:?} for entity {:?} \
-                    because the entity does not have that type",
-                    std::any::type_name::<T>(),
-                    change.to_value,
-                    change.for_element,
+                report_error(
+                    Some(change.for_element),
+                    format!(
+                        "DEV ERROR: Unable to change {} data to {:?} for entity {:?} \
+                        because the entity does not have that type",
+                        std::any::type_name::<T>(),
+                        change.to_value,
+                        change.for_element
+                    ),
                 );
             }
         }
     }
+
+    if let Some(entry) = UndoEntry::combine(applied) {
+        history.push(entry);
+    }
 }
@@ -0,0 +1,124 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::site::SiteAssets;
+use bevy::prelude::*;
+use rmf_site_format::{ModelMarker, WorkcellCollisionMarker};
+
+/// Which geometry of a model should be rendered. This only makes a difference
+/// for models that describe separate collision geometry, such as SDF models.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CollisionDisplay {
+    #[default]
+    Visuals,
+    Collisions,
+    Both,
+}
+
+impl CollisionDisplay {
+    pub const ALL: [CollisionDisplay; 3] = [
+        CollisionDisplay::Visuals,
+        CollisionDisplay::Collisions,
+        CollisionDisplay::Both,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Visuals => "Visuals",
+            Self::Collisions => "Collisions",
+            Self::Both => "Visuals and Collisions",
+        }
+    }
+
+    pub fn shows_visuals(&self) -> bool {
+        matches!(self, Self::Visuals | Self::Both)
+    }
+
+    pub fn shows_collisions(&self) -> bool {
+        matches!(self, Self::Collisions | Self::Both)
+    }
+}
+
+/// The geometry that is shown for models which do not have their own
+/// [`CollisionDisplay`] component.
+#[derive(Resource, Clone, Copy, Debug, Default, Deref, DerefMut)]
+pub struct GlobalCollisionDisplay(pub CollisionDisplay);
+
+/// Marks an entity whose children are the visual geometry of a model link
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ModelVisualGeometry;
+
+/// Marks an entity whose children are the collision geometry of a model link
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ModelCollisionGeometry;
+
+pub fn update_collision_display(
+    global: Res<GlobalCollisionDisplay>,
+    changed_models: Query<(), (Changed<CollisionDisplay>, With<ModelMarker>)>,
+    removed_displays: RemovedComponents<CollisionDisplay>,
+    new_geometry: Query<(), Or<(Added<ModelVisualGeometry>, Added<ModelCollisionGeometry>)>>,
+    mut geometry: Query<
+        (
+            Entity,
+            &mut Visibility,
+            Option<&ModelVisualGeometry>,
+            Option<&ModelCollisionGeometry>,
+        ),
+        Or<(With<ModelVisualGeometry>, With<ModelCollisionGeometry>)>,
+    >,
+    models: Query<Option<&CollisionDisplay>, With<ModelMarker>>,
+    parents: Query<&Parent>,
+) {
+    if !global.is_changed()
+        && changed_models.is_empty()
+        && removed_displays.iter().next().is_none()
+        && new_geometry.is_empty()
+    {
+        return;
+    }
+
+    for (e, mut visibility, visual, collision) in &mut geometry {
+        // Use the setting of the outermost model, since the meshes of an SDF
+        // model are spawned as models of their own.
+        let display = AncestorIter::new(&parents, e)
+            .filter_map(|p| models.get(p).ok())
+            .last()
+            .flatten()
+            .copied()
+            .unwrap_or(global.0);
+        let is_visible = (visual.is_some() && display.shows_visuals())
+            || (collision.is_some() && display.shows_collisions());
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+    }
+}
+
+/// Get the material that a primitive shape of a model should be rendered
+/// with, which depends on whether it is collision geometry.
+pub fn model_primitive_material(
+    e: Entity,
+    parents: &Query<&Parent>,
+    collisions: &Query<(), Or<(With<ModelCollisionGeometry>, With<WorkcellCollisionMarker>)>>,
+    site_assets: &SiteAssets,
+) -> Handle<StandardMaterial> {
+    if collisions.contains(e) || AncestorIter::new(parents, e).any(|p| collisions.contains(p)) {
+        site_assets.collision_geometry_material.clone()
+    } else {
+        site_assets.default_mesh_grey_material.clone()
+    }
+}
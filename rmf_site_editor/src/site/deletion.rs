@@ -16,8 +16,10 @@
 */
 
 use crate::{
+    console::report_warning,
     interaction::{Select, Selection},
     site::{Category, CurrentLevel, Dependents, LevelProperties, SiteUpdateStage},
+    UnsavedChanges,
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
 use rmf_site_format::{ConstraintDependents, Edge, MeshConstraint, Path, Point};
@@ -102,8 +104,13 @@ impl Plugin for DeletionPlugin {
     }
 }
 
-fn handle_deletion_requests(mut deletions: EventReader<Delete>, mut params: DeletionParams) {
+fn handle_deletion_requests(
+    mut deletions: EventReader<Delete>,
+    mut params: DeletionParams,
+    mut unsaved: ResMut<UnsavedChanges>,
+) {
     for delete in deletions.iter() {
+        unsaved.0 = true;
         if delete.and_dependents {
             recursive_dependent_delete(delete.element, &mut params);
         } else {
@@ -128,24 +135,30 @@ fn cautious_delete(element: Entity, params: &mut DeletionParams) {
     for descendent in &all_descendents {
         if let Ok(prevent) = params.preventions.get(*descendent) {
             if *descendent == element {
-                println!(
-                    "Element {:?} cannot be deleted because: {}",
-                    element,
-                    prevent
-                        .reason
-                        .as_ref()
-                        .unwrap_or(&"<.. no reason given>".to_string()),
+                report_warning(
+                    Some(element),
+                    format!(
+                        "Element {:?} cannot be deleted because: {}",
+                        element,
+                        prevent
+                            .reason
+                            .as_ref()
+                            .unwrap_or(&"<.. no reason given>".to_string()),
+                    ),
                 );
             } else {
-                println!(
-                    "Element {:?} is an ancestor of {:?} which cannot be \
-                    deleted because: {}",
-                    element,
-                    descendent,
-                    prevent
-                        .reason
-                        .as_ref()
-                        .unwrap_or(&"<.. no reason given>".to_string()),
+                report_warning(
+                    Some(*descendent),
+                    format!(
+                        "Element {:?} is an ancestor of {:?} which cannot be \
+                        deleted because: {}",
+                        element,
+                        descendent,
+                        prevent
+                            .reason
+                            .as_ref()
+                            .unwrap_or(&"<.. no reason given>".to_string()),
+                    ),
                 );
             }
             return;
@@ -155,19 +168,25 @@ fn cautious_delete(element: Entity, params: &mut DeletionParams) {
             for dep in dependents.iter() {
                 if !all_descendents.contains(dep) {
                     if *descendent == element {
-                        println!(
-                            "Cannot delete {:?} because it has {} dependents. \
-                            Only elements with no outside dependents can be \
-                            deleted.",
-                            element,
-                            dependents.len(),
+                        report_warning(
+                            Some(element),
+                            format!(
+                                "Cannot delete {:?} because it has {} dependents. \
+                                Only elements with no outside dependents can be \
+                                deleted.",
+                                element,
+                                dependents.len(),
+                            ),
                         );
                     } else {
-                        println!(
-                            "Element {:?} is an ancestor of {:?} \
-                            which cannot be deleted because {:?} depends \
-                            on it.",
-                            element, descendent, dep,
+                        report_warning(
+                            Some(*dep),
+                            format!(
+                                "Element {:?} is an ancestor of {:?} \
+                                which cannot be deleted because {:?} depends \
+                                on it.",
+                                element, descendent, dep,
+                            ),
                         );
                     }
                     return;
@@ -238,25 +257,31 @@ fn recursive_dependent_delete(element: Entity, params: &mut DeletionParams) {
     while let Some(top) = queue.pop() {
         if let Ok(prevent) = params.preventions.get(top) {
             if top == element {
-                println!(
-                    "Cannot delete {:?} because: {}",
-                    element,
-                    prevent
-                        .reason
-                        .as_ref()
-                        .unwrap_or(&"<.. no reason given>".to_string()),
+                report_warning(
+                    Some(element),
+                    format!(
+                        "Cannot delete {:?} because: {}",
+                        element,
+                        prevent
+                            .reason
+                            .as_ref()
+                            .unwrap_or(&"<.. no reason given>".to_string()),
+                    ),
                 );
             } else {
-                println!(
-                    "Cannot delete {:?} because we would need to also delete \
-                    {:?} which cannot be deleted because: {}",
-                    element,
-                    top,
-                    prevent
-                        .reason
-                        .as_ref()
-                        .unwrap_or(&"<.. no reason given>".to_string()),
-                )
+                report_warning(
+                    Some(top),
+                    format!(
+                        "Cannot delete {:?} because we would need to also delete \
+                        {:?} which cannot be deleted because: {}",
+                        element,
+                        top,
+                        prevent
+                            .reason
+                            .as_ref()
+                            .unwrap_or(&"<.. no reason given>".to_string()),
+                    ),
+                );
             }
             return;
         }
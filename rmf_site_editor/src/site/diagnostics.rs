@@ -0,0 +1,365 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::console::notify_info;
+use crate::site::*;
+use bevy::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Request to check a site for problems that would cause trouble for RMF
+/// once the site is exported.
+#[derive(Clone, Copy, Debug)]
+pub struct ValidateSite(pub Entity);
+
+/// A problem that was found while validating a site
+#[derive(Clone, Debug)]
+pub struct Issue {
+    /// The entity that is responsible for the issue, if there is one
+    pub entity: Option<Entity>,
+    /// A description of the problem
+    pub message: String,
+}
+
+impl Issue {
+    pub fn new(entity: Entity, message: String) -> Self {
+        Self {
+            entity: Some(entity),
+            message,
+        }
+    }
+}
+
+/// The issues that were found the last time the site was validated, grouped
+/// by the topic of the check that found them. Each check replaces the issues
+/// of its own topic whenever it runs.
+#[derive(Resource, Default, Debug)]
+pub struct SiteDiagnostics {
+    pub issues: BTreeMap<&'static str, Vec<Issue>>,
+}
+
+impl SiteDiagnostics {
+    pub fn count(&self) -> usize {
+        self.issues.values().map(|issues| issues.len()).sum()
+    }
+}
+
+pub const LIFT_ISSUES: &'static str = "Lifts";
+pub const NAV_GRAPH_ISSUES: &'static str = "Navigation Graphs";
+/// Issues found by comparing lanes against the occupancy grid
+pub const PLACEMENT_ISSUES: &'static str = "Placement";
+
+/// Lanes shorter than this are considered to have zero length
+pub const MIN_LANE_LENGTH: f32 = 1e-3;
+
+pub fn check_lifts_for_issues(
+    mut validate: EventReader<ValidateSite>,
+    mut diagnostics: ResMut<SiteDiagnostics>,
+    lifts: Query<
+        (
+            Entity,
+            &NameInSite,
+            &Edge<Entity>,
+            &InitialLevel<Entity>,
+            &ChildCabinAnchorGroup,
+            &Children,
+            &Parent,
+        ),
+        (With<LiftCabin<Entity>>, Without<Pending>),
+    >,
+    doors: Query<
+        (&Edge<Entity>, &LevelVisits<Entity>),
+        (With<LiftCabinDoorMarker>, Without<Pending>),
+    >,
+    lanes: Query<(&Edge<Entity>, &AssociatedGraphs<Entity>), (With<LaneMarker>, Without<Pending>)>,
+    graphs: Query<(Entity, &Parent), (With<NavGraphMarker>, Without<Pending>)>,
+    levels: Query<(&LevelProperties, &Parent), Without<Pending>>,
+    anchors: Query<&Parent, (With<Anchor>, Without<Pending>)>,
+) {
+    for ValidateSite(site) in validate.iter() {
+        let mut issues = Vec::new();
+        let site_graphs: Vec<Entity> = graphs
+            .iter()
+            .filter(|(_, parent)| parent.get() == *site)
+            .map(|(e, _)| e)
+            .collect();
+        let is_valid_level = |level: Entity| {
+            levels
+                .get(level)
+                .ok()
+                .filter(|(_, parent)| parent.get() == *site)
+                .is_some()
+        };
+        let level_name = |level: Entity| {
+            levels
+                .get(level)
+                .map(|(props, _)| props.name.clone())
+                .unwrap_or_else(|_| format!("{level:?}"))
+        };
+
+        for (e, name, edge, initial_level, anchor_group, children, parent) in &lifts {
+            if parent.get() != *site {
+                continue;
+            }
+
+            for anchor in edge.array() {
+                if anchors
+                    .get(anchor)
+                    .ok()
+                    .filter(|p| p.get() == *site)
+                    .is_none()
+                {
+                    issues.push(Issue::new(
+                        e,
+                        format!(
+                            "Lift [{}] has a reference anchor that is not a site anchor",
+                            name.0
+                        ),
+                    ));
+                }
+            }
+
+            if let Some(level) = initial_level.0 {
+                if !is_valid_level(level) {
+                    issues.push(Issue::new(
+                        e,
+                        format!("Lift [{}] has an initial level that does not exist", name.0),
+                    ));
+                }
+            }
+
+            let mut served_levels = BTreeSet::new();
+            for child in children {
+                let Ok((door_edge, visits)) = doors.get(*child) else {
+                    continue;
+                };
+
+                for anchor in door_edge.array() {
+                    if anchors
+                        .get(anchor)
+                        .ok()
+                        .filter(|p| p.get() == **anchor_group)
+                        .is_none()
+                    {
+                        issues.push(Issue::new(
+                            *child,
+                            format!(
+                                "A cabin door of lift [{}] has an anchor that is not \
+                                inside the lift cabin",
+                                name.0
+                            ),
+                        ));
+                    }
+                }
+
+                for level in visits.iter() {
+                    if is_valid_level(*level) {
+                        served_levels.insert(*level);
+                    } else {
+                        issues.push(Issue::new(
+                            *child,
+                            format!(
+                                "A cabin door of lift [{}] visits a level that does not exist",
+                                name.0
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            // Find which levels have a lane that connects them to the inside
+            // of the cabin on at least one nav graph.
+            let mut reachable_levels = BTreeSet::new();
+            for (lane_edge, associated) in &lanes {
+                if !site_graphs.iter().any(|g| associated.includes(*g)) {
+                    continue;
+                }
+
+                let parents = lane_edge
+                    .array()
+                    .map(|a| anchors.get(a).ok().map(|p| p.get()));
+                for (inside, outside) in [(parents[0], parents[1]), (parents[1], parents[0])] {
+                    if inside == Some(**anchor_group) {
+                        if let Some(outside) = outside {
+                            reachable_levels.insert(outside);
+                        }
+                    }
+                }
+            }
+
+            for level in served_levels.difference(&reachable_levels) {
+                issues.push(Issue::new(
+                    e,
+                    format!(
+                        "Lift [{}] serves level [{}] but no nav graph connects that \
+                        level to the lift cabin",
+                        name.0,
+                        level_name(*level),
+                    ),
+                ));
+            }
+        }
+
+        if !issues.is_empty() {
+            notify_info(
+                None,
+                format!("Found {} issue(s) with the lifts of the site", issues.len()),
+            );
+        }
+        diagnostics.issues.insert(LIFT_ISSUES, issues);
+    }
+}
+
+/// Follow the links of a union-find forest to the root of its tree
+fn find_root(component: &HashMap<Entity, Entity>, mut e: Entity) -> Entity {
+    while let Some(parent) = component.get(&e).copied() {
+        if parent == e {
+            break;
+        }
+        e = parent;
+    }
+    e
+}
+
+pub fn check_nav_graphs_for_issues(
+    mut validate: EventReader<ValidateSite>,
+    mut diagnostics: ResMut<SiteDiagnostics>,
+    graphs: Query<(Entity, &NameInSite, &Parent), (With<NavGraphMarker>, Without<Pending>)>,
+    lanes: Query<
+        (Entity, &Edge<Entity>, &AssociatedGraphs<Entity>, &Parent),
+        (With<LaneMarker>, Without<Pending>),
+    >,
+    locations: Query<
+        (
+            Entity,
+            &NameInSite,
+            &Point<Entity>,
+            &AssociatedGraphs<Entity>,
+            &Parent,
+        ),
+        (With<LocationTags>, Without<Pending>),
+    >,
+    anchors: Query<&GlobalTransform, With<Anchor>>,
+) {
+    for ValidateSite(site) in validate.iter() {
+        let mut issues = Vec::new();
+        let site_graphs: Vec<(Entity, &NameInSite)> = graphs
+            .iter()
+            .filter(|(_, _, parent)| parent.get() == *site)
+            .map(|(e, name, _)| (e, name))
+            .collect();
+        let site_lanes = lanes.iter().filter(|(_, _, _, p)| p.get() == *site);
+
+        // Lanes that can be used for connectivity, keyed by graph
+        let mut graph_lanes: HashMap<Entity, Vec<(Entity, [Entity; 2])>> = HashMap::new();
+        for (e, edge, associated, _) in site_lanes {
+            let positions = edge.array().map(|a| anchors.get(a).ok());
+            let [Some(p0), Some(p1)] = positions else {
+                issues.push(Issue::new(
+                    e,
+                    "Lane is connected to an anchor that no longer exists".to_owned(),
+                ));
+                continue;
+            };
+
+            if p0.translation().distance(p1.translation()) < MIN_LANE_LENGTH {
+                issues.push(Issue::new(e, "Lane has zero length".to_owned()));
+            }
+
+            for (graph, _) in &site_graphs {
+                if associated.includes(*graph) {
+                    graph_lanes
+                        .entry(*graph)
+                        .or_default()
+                        .push((e, edge.array()));
+                }
+            }
+        }
+
+        for (graph, name) in &site_graphs {
+            let Some(lanes) = graph_lanes.get(graph) else {
+                continue;
+            };
+
+            // Group the anchors of the graph into connected components
+            let mut component: HashMap<Entity, Entity> = HashMap::new();
+            for (_, [a0, a1]) in lanes {
+                component.entry(*a0).or_insert(*a0);
+                component.entry(*a1).or_insert(*a1);
+                let r0 = find_root(&component, *a0);
+                let r1 = find_root(&component, *a1);
+                if r0 != r1 {
+                    component.insert(r0, r1);
+                }
+            }
+
+            let mut subgraphs: HashMap<Entity, Vec<Entity>> = HashMap::new();
+            for (lane, [a0, _]) in lanes {
+                let r = find_root(&component, *a0);
+                subgraphs.entry(r).or_default().push(*lane);
+            }
+
+            if subgraphs.len() > 1 {
+                let mut subgraphs: Vec<Vec<Entity>> = subgraphs.into_values().collect();
+                subgraphs.sort_by_key(|lanes| std::cmp::Reverse(lanes.len()));
+                for subgraph in subgraphs.iter().skip(1) {
+                    issues.push(Issue::new(
+                        subgraph[0],
+                        format!(
+                            "Graph [{}] has a disconnected subgraph of {} lane(s)",
+                            name.0,
+                            subgraph.len(),
+                        ),
+                    ));
+                }
+            }
+        }
+
+        for (e, name, point, associated, parent) in &locations {
+            if parent.get() != *site {
+                continue;
+            }
+
+            let attached = site_graphs.iter().any(|(graph, _)| {
+                associated.includes(*graph)
+                    && graph_lanes
+                        .get(graph)
+                        .filter(|lanes| lanes.iter().any(|(_, ends)| ends.contains(&**point)))
+                        .is_some()
+            });
+            if !attached {
+                issues.push(Issue::new(
+                    e,
+                    format!(
+                        "Location [{}] is not on a lane of any of its graphs",
+                        name.0
+                    ),
+                ));
+            }
+        }
+
+        if !issues.is_empty() {
+            notify_info(
+                None,
+                format!(
+                    "Found {} issue(s) with the nav graphs of the site",
+                    issues.len()
+                ),
+            );
+        }
+        diagnostics.issues.insert(NAV_GRAPH_ISSUES, issues);
+    }
+}
@@ -16,6 +16,7 @@
 */
 
 use crate::{
+    console::report_error,
     interaction::Selectable,
     shapes::make_flat_rect_mesh,
     site::{
@@ -31,7 +32,7 @@ pub const DRAWING_LAYER_START: f32 = 0.0;
 
 #[derive(Debug, Clone, Copy, Component)]
 pub struct DrawingSegments {
-    leaf: Entity,
+    pub leaf: Entity,
 }
 
 // We need to keep track of the drawing data until the image is loaded
@@ -112,7 +113,10 @@ pub fn handle_loaded_drawing(
                     if let Ok(mut mesh_handle) = mesh_handles.get_mut(segment.leaf) {
                         *mesh_handle = mesh;
                     } else {
-                        println!("DEV ERROR: Partially-constructed Drawing entity detected");
+                        report_error(
+                            None,
+                            "DEV ERROR: Partially-constructed Drawing entity detected",
+                        );
                     }
                     // We can ignore the layer height here since that update
                     // will be handled by another system.
@@ -0,0 +1,207 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{interaction::CameraControls, AppState, CurrentWorkspace, RenderSettings};
+use bevy::{
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::mesh::VertexAttributeValues,
+};
+use rmf_site_format::{Environment, SiteProperties, Sky};
+use std::f32::consts::FRAC_PI_2;
+
+/// Radius of the sphere that the sky is drawn on. The sphere follows the
+/// camera around, so anything farther than this from the camera will be
+/// hidden behind the sky.
+pub const SKY_RADIUS: f32 = 500.0;
+
+#[derive(Component)]
+pub struct SunMarker;
+
+#[derive(Component)]
+pub struct SkyMarker;
+
+/// Rebuild the sun and sky whenever the current site, its environment, or the
+/// render settings change. These are not children of the site because the sky needs to
+/// follow the camera instead of the site.
+pub fn update_environment(
+    mut commands: Commands,
+    current_workspace: Res<CurrentWorkspace>,
+    open_sites: Query<Entity, With<SiteProperties>>,
+    environments: Query<&Environment>,
+    changed_environments: Query<(), Changed<Environment>>,
+    existing: Query<Entity, Or<(With<SunMarker>, With<SkyMarker>)>>,
+    mut clear_color: ResMut<ClearColor>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    render_settings: Res<RenderSettings>,
+    app_state: Res<State<AppState>>,
+) {
+    let site = current_workspace.to_site(&open_sites);
+    let changed = site
+        .map(|site| changed_environments.contains(site))
+        .unwrap_or(false);
+    if !current_workspace.is_changed()
+        && !changed
+        && !render_settings.is_changed()
+        && !app_state.is_changed()
+    {
+        return;
+    }
+
+    for e in &existing {
+        commands.entity(e).despawn_recursive();
+    }
+    clear_color.0 = Color::BLACK;
+
+    let Some(environment) = site.and_then(|site| environments.get(site).ok()) else {
+        return;
+    };
+
+    if let Some(sun) = &environment.sun {
+        let [x, y, z] = sun.direction();
+        let direction = Vec3::new(x, y, z);
+        // looking_at cannot use the z axis as up when the sun is overhead
+        let up = if direction.z.abs() > 0.999 {
+            Vec3::X
+        } else {
+            Vec3::Z
+        };
+        let [r, g, b] = sun.color;
+        commands
+            .spawn(DirectionalLightBundle {
+                directional_light: DirectionalLight {
+                    illuminance: sun.illuminance,
+                    color: Color::rgb(r, g, b),
+                    shadows_enabled: sun.shadows && render_settings.shadows,
+                    shadow_projection: render_settings.shadow_projection(),
+                    ..default()
+                },
+                transform: Transform::from_translation(direction).looking_at(Vec3::ZERO, up),
+                ..default()
+            })
+            .insert(SunMarker);
+    }
+
+    let texture = match &environment.sky {
+        Sky::Solid { color } => {
+            let [r, g, b] = *color;
+            clear_color.0 = Color::rgb(r, g, b);
+            return;
+        }
+        Sky::Procedural { .. } => None,
+        Sky::Hdri { source } => Some(asset_server.load(&String::from(source))),
+    };
+
+    commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(make_sky_mesh(&environment.sky)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                base_color_texture: texture,
+                unlit: true,
+                cull_mode: None,
+                ..default()
+            }),
+            // The sphere mesh is generated with y pointing up
+            transform: Transform::from_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+            ..default()
+        })
+        .insert(SkyMarker)
+        .insert(NotShadowCaster)
+        .insert(NotShadowReceiver);
+}
+
+/// Remove the sun and sky when leaving the site editor so they do not light up
+/// other kinds of workspaces
+pub fn clear_environment(
+    mut commands: Commands,
+    existing: Query<Entity, Or<(With<SunMarker>, With<SkyMarker>)>>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    for e in &existing {
+        commands.entity(e).despawn_recursive();
+    }
+    clear_color.0 = Color::BLACK;
+}
+
+fn make_sky_mesh(sky: &Sky) -> Mesh {
+    let mut mesh: Mesh = shape::UVSphere {
+        radius: SKY_RADIUS,
+        sectors: 64,
+        stacks: 32,
+    }
+    .into();
+
+    match sky {
+        Sky::Procedural {
+            zenith,
+            horizon,
+            ground,
+        } => {
+            let Some(VertexAttributeValues::Float32x3(positions)) =
+                mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                return mesh;
+            };
+            let colors: Vec<[f32; 4]> = positions
+                .iter()
+                .map(|p| {
+                    let height = p[1] / SKY_RADIUS;
+                    let [r, g, b] = if height < 0.0 {
+                        *ground
+                    } else {
+                        // Keep the horizon color close to the horizon
+                        let t = height.sqrt();
+                        [0, 1, 2].map(|i| horizon[i] + (zenith[i] - horizon[i]) * t)
+                    };
+                    Color::rgb(r, g, b).as_linear_rgba_f32()
+                })
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+        Sky::Hdri { .. } => {
+            // The panorama is seen from inside the sphere, so it needs to be
+            // mirrored to keep it from appearing backwards.
+            if let Some(VertexAttributeValues::Float32x2(uvs)) =
+                mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
+            {
+                for uv in uvs {
+                    uv[0] = 1.0 - uv[0];
+                }
+            }
+        }
+        Sky::Solid { .. } => {}
+    }
+
+    mesh
+}
+
+/// Keep the sky centered on the camera so it always appears infinitely far
+/// away
+pub fn follow_camera_with_sky(
+    camera_controls: Res<CameraControls>,
+    cameras: Query<&GlobalTransform>,
+    mut skies: Query<&mut Transform, With<SkyMarker>>,
+) {
+    let Ok(camera_tf) = cameras.get(camera_controls.active_camera()) else {
+        return;
+    };
+    for mut tf in &mut skies {
+        tf.translation = camera_tf.translation();
+    }
+}
@@ -15,7 +15,7 @@
  *
 */
 
-use crate::{interaction::Selectable, shapes::*, site::*};
+use crate::{console::report_error, interaction::Selectable, shapes::*, site::*};
 use bevy::{
     math::Affine3A,
     prelude::*,
@@ -165,7 +165,10 @@ fn make_floor_mesh(entity: Entity, anchor_path: &Path<Entity>, anchors: &AnchorP
             if let Ok(p) = anchors.point_in_parent_frame_of(*anchor, Category::Floor, entity) {
                 positions.push(p);
             } else {
-                println!("DEV ERROR: Failed to find anchor {anchor:?} used by a path");
+                report_error(
+                    Some(entity),
+                    format!("DEV ERROR: Failed to find anchor {anchor:?} used by a path"),
+                );
                 valid = false;
             }
         }
@@ -187,7 +190,10 @@ fn make_floor_mesh(entity: Entity, anchor_path: &Path<Entity>, anchors: &AnchorP
         let p = match anchors.point_in_parent_frame_of(*anchor, Category::Floor, entity) {
             Ok(a) => a,
             Err(_) => {
-                println!("DEV ERROR: Failed to find anchor {anchor:?} used by a path");
+                report_error(
+                    Some(entity),
+                    format!("DEV ERROR: Failed to find anchor {anchor:?} used by a path"),
+                );
                 valid = false;
                 continue;
             }
@@ -222,7 +228,7 @@ fn make_floor_mesh(entity: Entity, anchor_path: &Path<Entity>, anchors: &AnchorP
 
         match result {
             Err(err) => {
-                println!("Failed to render floor: {err}");
+                report_error(Some(entity), format!("Failed to render floor: {err}"));
                 return make_fallback_floor_mesh_near_path(entity, anchor_path, anchors);
             }
             _ => {}
@@ -0,0 +1,359 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{
+    console::notify_warning,
+    occupancy::{Cell, GridRange},
+    shapes::*,
+    site::{Anchor, Category, CurrentLevel, LevelProperties, LightKind, LANE_LAYER_START},
+};
+use bevy::{math::Affine3A, prelude::*};
+use std::f32::consts::PI;
+
+/// The lower bound in lux of each band of the illuminance heatmap, along
+/// with the color that the band is drawn with
+pub const ILLUMINANCE_BANDS: [(f32, [f32; 3]); 6] = [
+    (0.0, [0.1, 0.1, 0.5]),
+    (50.0, [0.1, 0.4, 0.9]),
+    (100.0, [0.1, 0.8, 0.8]),
+    (200.0, [0.2, 0.9, 0.2]),
+    (500.0, [0.95, 0.9, 0.1]),
+    (1000.0, [0.95, 0.3, 0.1]),
+];
+/// The heatmap will not be calculated for more cells than this, to keep a
+/// small cell size on a large level from freezing the editor
+pub const ILLUMINANCE_CELL_LIMIT: usize = 250_000;
+const ILLUMINANCE_OVERLAY_HEIGHT: f32 = 0.75 * LANE_LAYER_START;
+
+/// Settings for the illuminance heatmap of the current level
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct IlluminancePreview {
+    pub show: bool,
+    pub cell_size: f32,
+    /// Height above the floor of the plane where illuminance is measured.
+    /// Use the height of camera lenses or robot sensors to check what they
+    /// will see.
+    pub height: f32,
+    /// The illuminance in lux that each cell is expected to reach
+    pub required: f32,
+}
+
+impl Default for IlluminancePreview {
+    fn default() -> Self {
+        Self {
+            show: false,
+            cell_size: 0.5,
+            height: 0.0,
+            required: 300.0,
+        }
+    }
+}
+
+/// The heatmap that is currently being displayed and a summary of its values
+#[derive(Resource)]
+pub struct IlluminanceMap {
+    pub overlay: Option<Entity>,
+    pub cells: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    /// How many cells reach the required illuminance
+    pub meeting_requirement: usize,
+    band_materials: Vec<Handle<StandardMaterial>>,
+}
+
+impl FromWorld for IlluminanceMap {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let band_materials = ILLUMINANCE_BANDS
+            .iter()
+            .map(|(_, [r, g, b])| {
+                materials.add(StandardMaterial {
+                    base_color: Color::rgba(*r, *g, *b, 0.5),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                })
+            })
+            .collect();
+
+        Self {
+            overlay: None,
+            cells: 0,
+            min: 0.0,
+            max: 0.0,
+            mean: 0.0,
+            meeting_requirement: 0,
+            band_materials,
+        }
+    }
+}
+
+/// Approximate the illuminance in lux that a light casts onto an upward
+/// facing surface at a point. This does not account for anything that might
+/// block the light, so the real illuminance will never be higher than this.
+pub fn illuminance_at(p: Vec3, kind: &LightKind, tf: &Transform) -> f32 {
+    illuminance_on(p, Vec3::Z, kind, tf)
+}
+
+/// Approximate the illuminance in lux that a light casts onto a surface at a
+/// point, where the surface faces in the direction of the normal.
+pub fn illuminance_on(p: Vec3, normal: Vec3, kind: &LightKind, tf: &Transform) -> f32 {
+    match kind {
+        LightKind::Point(point) => {
+            point_illuminance(p, normal, tf.translation, point.intensity, point.range)
+        }
+        LightKind::Spot(spot) => {
+            let spot = spot.to_bevy();
+            let cos_angle = tf.forward().dot((p - tf.translation).normalize_or_zero());
+            let (cos_outer, cos_inner) = (spot.outer_angle.cos(), spot.inner_angle.cos());
+            // Fade out towards the edge of the cone the same way bevy does
+            let cone = ((cos_angle - cos_outer) / (cos_inner - cos_outer)).clamp(0.0, 1.0);
+            cone * cone * point_illuminance(p, normal, tf.translation, spot.intensity, spot.range)
+        }
+        LightKind::Directional(dir) => dir.illuminance * (-tf.forward()).dot(normal).max(0.0),
+    }
+}
+
+fn point_illuminance(p: Vec3, normal: Vec3, light: Vec3, lumens: f32, range: f32) -> f32 {
+    let offset = light - p;
+    let distance_squared = offset.length_squared();
+    if distance_squared > range * range || distance_squared < 1e-6 {
+        return 0.0;
+    }
+    // The luminous power of the light is spread evenly in every direction
+    let candela = lumens / (4.0 * PI);
+    let cos_incidence = offset.normalize().dot(normal).max(0.0);
+    candela * cos_incidence / distance_squared
+}
+
+fn band_of(lux: f32) -> usize {
+    ILLUMINANCE_BANDS
+        .iter()
+        .rposition(|(lower, _)| lux >= *lower)
+        .unwrap_or(0)
+}
+
+pub fn update_illuminance_map(
+    mut commands: Commands,
+    preview: Res<IlluminancePreview>,
+    mut map: ResMut<IlluminanceMap>,
+    current_level: Res<CurrentLevel>,
+    lights: Query<(&LightKind, &GlobalTransform, &Parent)>,
+    changed_lights: Query<
+        (),
+        (
+            With<LightKind>,
+            Or<(Changed<LightKind>, Changed<GlobalTransform>)>,
+        ),
+    >,
+    levels: Query<&GlobalTransform, With<LevelProperties>>,
+    removed_lights: RemovedComponents<LightKind>,
+    anchors: Query<(&Anchor, &Parent)>,
+    changed_anchors: Query<(), Changed<Anchor>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let changed = preview.is_changed()
+        || current_level.is_changed()
+        || !changed_lights.is_empty()
+        || !changed_anchors.is_empty()
+        || removed_lights.iter().next().is_some();
+    if !changed {
+        return;
+    }
+
+    if let Some(overlay) = map.overlay.take() {
+        commands.entity(overlay).despawn_recursive();
+    }
+    map.cells = 0;
+    map.meeting_requirement = 0;
+
+    if !preview.show {
+        return;
+    }
+    let Some(level) = **current_level else {
+        return;
+    };
+
+    let Ok(level_tf) = levels.get(level) else {
+        return;
+    };
+    let floor = level_tf.translation().z;
+
+    let cell_size = preview.cell_size.max(0.05);
+    // Include the lights that are shared by every level
+    let level_lights: Vec<_> = lights
+        .iter()
+        .filter(|(_, _, parent)| parent.get() == level || !levels.contains(parent.get()))
+        .map(|(kind, tf, _)| (kind, tf.compute_transform()))
+        .collect();
+
+    // Cover every anchor and light on the level
+    let mut range = GridRange::new();
+    for (anchor, parent) in &anchors {
+        if parent.get() == level {
+            let p = anchor.translation_for_category(Category::General);
+            range.include(Cell::from_point(Vec2::from(*p), cell_size));
+        }
+    }
+    for (_, tf) in &level_lights {
+        range.include(Cell::from_point(tf.translation.truncate(), cell_size));
+    }
+
+    let (min, max) = (range.min_cell(), range.max_cell());
+    if min.x > max.x || min.y > max.y {
+        return;
+    }
+    let cell_count = ((max.x - min.x + 1) * (max.y - min.y + 1)) as usize;
+    if cell_count > ILLUMINANCE_CELL_LIMIT {
+        notify_warning(None, format!("Unable to preview illuminance for {cell_count} cells. Try a cell size larger than \
+            {cell_size}m."));
+        return;
+    }
+
+    let mut band_meshes: Vec<MeshBuffer> = ILLUMINANCE_BANDS
+        .iter()
+        .map(|_| MeshBuffer::empty())
+        .collect();
+    let (mut lowest, mut highest, mut total) = (f32::INFINITY, 0_f32, 0_f32);
+    for (x, y) in range.iter() {
+        let cell = Cell::new(x, y);
+        let center = cell.to_center_point(cell_size);
+        let p = center.extend(floor + preview.height);
+        let lux: f32 = level_lights
+            .iter()
+            .map(|(kind, tf)| illuminance_at(p, kind, tf))
+            .sum();
+
+        lowest = lowest.min(lux);
+        highest = highest.max(lux);
+        total += lux;
+        if lux >= preview.required {
+            map.meeting_requirement += 1;
+        }
+
+        let band = band_of(lux);
+        let mesh = std::mem::replace(&mut band_meshes[band], MeshBuffer::empty());
+        band_meshes[band] = mesh.merge_with(make_flat_square_mesh(cell_size).transform_by(
+            Affine3A::from_translation(center.extend(ILLUMINANCE_OVERLAY_HEIGHT)),
+        ));
+    }
+    map.cells = cell_count;
+    map.min = lowest;
+    map.max = highest;
+    map.mean = total / cell_count as f32;
+
+    let overlay = commands
+        .spawn(SpatialBundle::default())
+        .with_children(|parent| {
+            for (mesh, material) in band_meshes.into_iter().zip(&map.band_materials) {
+                parent.spawn(PbrBundle {
+                    mesh: meshes.add(mesh.into()),
+                    material: material.clone(),
+                    ..default()
+                });
+            }
+        })
+        .id();
+    commands.entity(level).add_child(overlay);
+    map.overlay = Some(overlay);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmf_site_format::{DirectionalLight, PointLight, SpotLight};
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "expected {expected} but got {actual}"
+        );
+    }
+
+    /// A point light that emits 100 candela in every direction
+    fn point_light() -> LightKind {
+        LightKind::Point(PointLight {
+            intensity: 400.0 * PI,
+            range: 10.0,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn point_light_falls_off_with_distance_and_angle() {
+        let light = point_light();
+        let above = Transform::from_xyz(0.0, 0.0, 2.0);
+        assert_near(illuminance_at(Vec3::ZERO, &light, &above), 25.0);
+
+        let diagonal = Transform::from_xyz(1.0, 0.0, 1.0);
+        assert_near(
+            illuminance_at(Vec3::ZERO, &light, &diagonal),
+            100.0 * 0.5_f32.sqrt() / 2.0,
+        );
+
+        // Light from beneath the surface or beyond the range does not count
+        let below = Transform::from_xyz(0.0, 0.0, -2.0);
+        assert_eq!(illuminance_at(Vec3::ZERO, &light, &below), 0.0);
+        let far = Transform::from_xyz(0.0, 0.0, 11.0);
+        assert_eq!(illuminance_at(Vec3::ZERO, &light, &far), 0.0);
+    }
+
+    #[test]
+    fn spot_light_only_reaches_inside_its_cone() {
+        let light = LightKind::Spot(SpotLight {
+            intensity: 400.0 * PI,
+            range: 10.0,
+            ..Default::default()
+        });
+        // The forward direction of an unrotated transform points down
+        let tf = Transform::from_xyz(0.0, 0.0, 2.0);
+        assert_near(illuminance_at(Vec3::ZERO, &light, &tf), 25.0);
+        assert_eq!(illuminance_at(Vec3::new(5.0, 0.0, 0.0), &light, &tf), 0.0);
+    }
+
+    #[test]
+    fn directional_light_depends_on_its_angle() {
+        let light = LightKind::Directional(DirectionalLight {
+            illuminance: 1000.0,
+            ..Default::default()
+        });
+        let down = Transform::default();
+        assert_near(illuminance_at(Vec3::ZERO, &light, &down), 1000.0);
+        assert_near(
+            illuminance_at(Vec3::new(50.0, 0.0, 0.0), &light, &down),
+            1000.0,
+        );
+
+        let slanted = Transform::from_rotation(Quat::from_rotation_y(PI / 3.0));
+        assert_near(illuminance_at(Vec3::ZERO, &light, &slanted), 500.0);
+
+        let up = Transform::from_rotation(Quat::from_rotation_x(PI));
+        assert_near(illuminance_at(Vec3::ZERO, &light, &up), 0.0);
+    }
+
+    #[test]
+    fn lux_is_sorted_into_bands() {
+        assert_eq!(band_of(-1.0), 0);
+        assert_eq!(band_of(0.0), 0);
+        assert_eq!(band_of(49.9), 0);
+        assert_eq!(band_of(50.0), 1);
+        assert_eq!(band_of(300.0), 3);
+        assert_eq!(band_of(999.0), 4);
+        assert_eq!(band_of(1000.0), 5);
+        assert_eq!(band_of(1e6), 5);
+    }
+}
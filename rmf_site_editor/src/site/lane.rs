@@ -15,10 +15,12 @@
  *
 */
 
+use crate::console::{notify_info, notify_warning};
 use crate::site::*;
-use crate::CurrentWorkspace;
+use crate::{shapes::make_flat_strip, CurrentWorkspace};
 use bevy::prelude::*;
 use rmf_site_format::{Edge, LaneMarker};
+use std::collections::HashMap;
 
 pub const SELECTED_LANE_OFFSET: f32 = 0.001;
 pub const HOVERED_LANE_OFFSET: f32 = 0.002;
@@ -29,6 +31,53 @@ pub const LANE_LAYER_LIMIT: f32 = LANE_LAYER_START + SELECTED_LANE_OFFSET;
 // so users can customize the lane width per lane.
 pub const LANE_WIDTH: f32 = 0.5;
 
+/// How many straight pieces are used to draw each span between the control
+/// points of a curved lane
+pub const LANE_CURVE_RESOLUTION: usize = 16;
+
+/// Lanes will not be split at points closer than this to either of their ends
+pub const MIN_SPLIT_DISTANCE: f32 = 0.05;
+
+/// Split a lane into two lanes at the point along the lane that is closest to
+/// the given point. Both halves keep the properties of the original lane.
+#[derive(Clone, Copy, Debug)]
+pub struct SplitLane {
+    pub lane: Entity,
+    pub point: Vec2,
+}
+
+/// Lanes will be considered collinear when the sine of the angle between them
+/// is smaller than this.
+pub const COLLINEAR_LANE_TOLERANCE: f32 = 1e-3;
+
+/// Merge chains of collinear lanes in a site that pass through anchors which
+/// nothing else depends on. Each chain becomes a single lane.
+#[derive(Clone, Copy, Debug)]
+pub struct MergeCollinearLanes {
+    pub site: Entity,
+}
+
+/// The lane end meshes are made for LANE_WIDTH so they get scaled to match the
+/// width of the lane.
+fn lane_end_transform(p: Vec3, width: f32) -> Transform {
+    Transform::from_translation(p).with_scale(Vec3::new(
+        width / LANE_WIDTH,
+        width / LANE_WIDTH,
+        1.0,
+    ))
+}
+
+fn set_lane_width(segments: &LaneSegments, width: f32, transforms: &mut Query<&mut Transform>) {
+    for e in [segments.start, segments.end] {
+        if let Ok(mut tf) = transforms.get_mut(e) {
+            tf.scale = Vec3::new(width / LANE_WIDTH, width / LANE_WIDTH, 1.0);
+        }
+    }
+    if let Ok(mut tf) = transforms.get_mut(segments.mid) {
+        tf.scale.y = width;
+    }
+}
+
 #[derive(Component, Debug, Clone, Copy)]
 pub struct LaneSegments {
     pub layer: Entity,
@@ -44,6 +93,70 @@ impl LaneSegments {
     }
 }
 
+/// How fast the direction arrow of a one-way lane travels along the lane, in
+/// meters per second
+pub const LANE_ARROW_SPEED: f32 = 0.5;
+/// Height of the direction arrows above the lane that they belong to
+pub const LANE_ARROW_OFFSET: f32 = 0.000_5;
+
+/// Arrowheads that show which way a lane can be traveled. One-way lanes have a
+/// single arrow that moves from the start of the lane to its end. Two-way lanes
+/// show both arrows back to back in the middle of the lane.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LaneArrows {
+    pub forward: Entity,
+    pub reverse: Entity,
+}
+
+impl LaneArrows {
+    pub fn iter(&self) -> [Entity; 2] {
+        [self.forward, self.reverse]
+    }
+}
+
+/// Height of the shared zone overlay above the middle segment of its lane
+pub const SHARED_ZONE_OVERLAY_OFFSET: f32 = 0.000_3;
+
+/// The overlay that highlights a lane when it belongs to a shared zone
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SharedZoneOverlay(pub Entity);
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SharedZoneOverlayMarker;
+
+/// Get the path that a lane follows in the frame of its parent, going from
+/// `p0` at its start anchor to `p1` at its end anchor.
+pub fn lane_path(p0: Vec3, p1: Vec3, curve: &LaneCurve) -> Vec<Vec3> {
+    let path = curve.path(p0.truncate(), p1.truncate(), LANE_CURVE_RESOLUTION);
+    let pieces = (path.len() - 1) as f32;
+    path.into_iter()
+        .enumerate()
+        .map(|(i, p)| p.extend(p0.z + (p1.z - p0.z) * i as f32 / pieces))
+        .collect()
+}
+
+/// Find the point that is `distance` along a path, as well as the direction
+/// of the path at that point.
+fn point_along_path(path: &[Vec3], mut distance: f32) -> (Vec2, Vec2) {
+    for piece in path.windows(2) {
+        let dp = (piece[1] - piece[0]).truncate();
+        let length = dp.length();
+        if distance <= length || piece[1] == *path.last().unwrap() {
+            let dir = dp.normalize_or_zero();
+            return (piece[0].truncate() + dir * distance.min(length), dir);
+        }
+        distance -= length;
+    }
+
+    (path[0].truncate(), Vec2::ZERO)
+}
+
+fn path_length(path: &[Vec3]) -> f32 {
+    path.windows(2)
+        .map(|piece| (piece[1] - piece[0]).truncate().length())
+        .sum()
+}
+
 // TODO(MXG): Refactor these function arguments into a SystemParam
 fn should_display_lane(
     edge: &Edge<Entity>,
@@ -102,6 +215,7 @@ pub fn add_lane_visuals(
         }
 
         let (lane_material, height) = graphs.display_style(associated_graphs);
+        let width = graphs.lane_width(associated_graphs);
         let is_visible = should_display_lane(
             edge,
             associated_graphs,
@@ -130,7 +244,7 @@ pub fn add_lane_visuals(
                 let mut start = parent.spawn(PbrBundle {
                     mesh: assets.lane_end_mesh.clone(),
                     material: lane_material.clone(),
-                    transform: Transform::from_translation(start_anchor),
+                    transform: lane_end_transform(start_anchor, width),
                     ..default()
                 });
                 let start_outline = start.add_children(|start| {
@@ -148,7 +262,7 @@ pub fn add_lane_visuals(
                 let mut mid = parent.spawn(PbrBundle {
                     mesh: assets.lane_mid_mesh.clone(),
                     material: lane_material.clone(),
-                    transform: line_stroke_transform(&start_anchor, &end_anchor, LANE_WIDTH),
+                    transform: line_stroke_transform(&start_anchor, &end_anchor, width),
                     ..default()
                 });
                 let mid_outline = mid.add_children(|mid| {
@@ -165,7 +279,7 @@ pub fn add_lane_visuals(
                 let mut end = parent.spawn(PbrBundle {
                     mesh: assets.lane_end_mesh.clone(),
                     material: lane_material.clone(),
-                    transform: Transform::from_translation(end_anchor),
+                    transform: lane_end_transform(end_anchor, width),
                     ..default()
                 });
                 let end_outline = end.add_children(|end| {
@@ -203,11 +317,171 @@ pub fn add_lane_visuals(
     }
 }
 
+pub fn add_lane_arrows(
+    mut commands: Commands,
+    lanes: Query<(Entity, &LaneSegments), Added<LaneSegments>>,
+    assets: Res<SiteAssets>,
+) {
+    for (e, segments) in &lanes {
+        let mut spawn_arrow = || {
+            commands
+                .spawn(PbrBundle {
+                    mesh: assets.lane_arrow_mesh.clone(),
+                    material: assets.lane_arrow_material.clone(),
+                    ..default()
+                })
+                .id()
+        };
+        let arrows = LaneArrows {
+            forward: spawn_arrow(),
+            reverse: spawn_arrow(),
+        };
+        commands
+            .entity(segments.layer)
+            .push_children(&arrows.iter());
+        commands.entity(e).insert(arrows);
+    }
+}
+
+pub fn add_shared_zone_overlays(
+    mut commands: Commands,
+    lanes: Query<(Entity, &LaneSegments), Added<LaneSegments>>,
+    assets: Res<SiteAssets>,
+) {
+    for (e, segments) in &lanes {
+        // The overlay is a child of the middle segment so that it can reuse
+        // the mesh and transform of the segment, whether it is straight or
+        // curved.
+        let overlay = commands
+            .spawn(PbrBundle {
+                mesh: assets.lane_mid_mesh.clone(),
+                material: assets.shared_zone_material.clone(),
+                transform: Transform::from_translation(SHARED_ZONE_OVERLAY_OFFSET * Vec3::Z),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(SharedZoneOverlayMarker)
+            .id();
+        commands.entity(segments.mid).add_child(overlay);
+        commands.entity(e).insert(SharedZoneOverlay(overlay));
+    }
+}
+
+pub fn update_shared_zone_overlays(
+    lanes: Query<(Entity, &SharedZone, &LaneSegments, &SharedZoneOverlay)>,
+    changed_zones: Query<(), Changed<SharedZone>>,
+    new_overlays: Query<(), Added<SharedZoneOverlay>>,
+    mid_meshes: Query<
+        (&Handle<Mesh>, ChangeTrackers<Handle<Mesh>>),
+        Without<SharedZoneOverlayMarker>,
+    >,
+    mut overlays: Query<(&mut Handle<Mesh>, &mut Visibility), With<SharedZoneOverlayMarker>>,
+) {
+    for (e, zone, segments, overlay) in &lanes {
+        let Ok((mut overlay_mesh, mut visibility)) = overlays.get_mut(overlay.0) else {
+            continue;
+        };
+        let is_new = new_overlays.contains(e);
+
+        if is_new || changed_zones.contains(e) {
+            let is_visible = zone.0.is_some();
+            if visibility.is_visible != is_visible {
+                visibility.is_visible = is_visible;
+            }
+        }
+
+        if let Ok((mid_mesh, tracker)) = mid_meshes.get(segments.mid) {
+            if is_new || tracker.is_changed() {
+                *overlay_mesh = mid_mesh.clone();
+            }
+        }
+    }
+}
+
+pub fn update_lane_arrows(
+    lanes: Query<(
+        Entity,
+        &Edge<Entity>,
+        &LaneCurve,
+        &ReverseLane,
+        &AssociatedGraphs<Entity>,
+        &LaneArrows,
+        &ComputedVisibility,
+    )>,
+    anchors: AnchorParams,
+    graphs: GraphSelect,
+    mut arrows: Query<(&mut Transform, &mut Visibility), Without<NavGraphMarker>>,
+    now: Res<Time>,
+) {
+    for (e, edge, curve, reverse, associated, lane_arrows, visibility) in &lanes {
+        if !visibility.is_visible_in_hierarchy() {
+            continue;
+        }
+
+        let (Ok(p0), Ok(p1)) = (
+            anchors.point_in_parent_frame_of(edge.start(), Category::Lane, e),
+            anchors.point_in_parent_frame_of(edge.end(), Category::Lane, e),
+        ) else {
+            continue;
+        };
+        let width = graphs.lane_width(associated);
+        let path = lane_path(p0, p1, curve);
+        let length = path_length(&path);
+        let scale = Vec3::new(width, width, 1.0);
+        // Arrows would cover up lanes that are too short to fit them
+        let fits = length > 2.0 * width;
+
+        let one_way = matches!(reverse, ReverseLane::Disable);
+        let forward_distance = if one_way {
+            // Cycle the arrow from one end of the lane to the other
+            let travel = length - width;
+            let s = (now.elapsed_seconds() * LANE_ARROW_SPEED).rem_euclid(travel.max(1e-3));
+            width / 2.0 + s
+        } else {
+            length / 2.0 + width / 4.0
+        };
+        let (forward_position, forward_dir) = point_along_path(&path, forward_distance);
+        let (reverse_position, reverse_dir) = point_along_path(&path, length / 2.0 - width / 4.0);
+
+        for (arrow, position, yaw, is_visible) in [
+            (
+                lane_arrows.forward,
+                forward_position,
+                forward_dir.y.atan2(forward_dir.x),
+                fits,
+            ),
+            (
+                lane_arrows.reverse,
+                reverse_position,
+                reverse_dir.y.atan2(reverse_dir.x) + std::f32::consts::PI,
+                fits && !one_way,
+            ),
+        ] {
+            let Ok((mut tf, mut vis)) = arrows.get_mut(arrow) else {
+                continue;
+            };
+            let new_tf = Transform {
+                translation: position.extend(LANE_ARROW_OFFSET),
+                rotation: Quat::from_rotation_z(yaw),
+                scale,
+            };
+            // Only moving arrows should trigger transform propagation
+            if *tf != new_tf {
+                *tf = new_tf;
+            }
+            if vis.is_visible != is_visible {
+                vis.is_visible = is_visible;
+            }
+        }
+    }
+}
+
 fn update_lane_visuals(
     entity: Entity,
     edge: &Edge<Entity>,
     segments: &LaneSegments,
     anchors: &AnchorParams,
+    width: f32,
     transforms: &mut Query<&mut Transform>,
 ) {
     let start_anchor = anchors
@@ -218,13 +492,13 @@ fn update_lane_visuals(
         .unwrap();
 
     if let Some(mut tf) = transforms.get_mut(segments.start).ok() {
-        *tf = Transform::from_translation(start_anchor);
+        *tf = lane_end_transform(start_anchor, width);
     }
     if let Some(mut tf) = transforms.get_mut(segments.mid).ok() {
-        *tf = line_stroke_transform(&start_anchor, &end_anchor, LANE_WIDTH);
+        *tf = line_stroke_transform(&start_anchor, &end_anchor, width);
     }
     if let Some(mut tf) = transforms.get_mut(segments.end).ok() {
-        *tf = Transform::from_translation(end_anchor);
+        *tf = lane_end_transform(end_anchor, width);
     }
 }
 
@@ -247,7 +521,8 @@ pub fn update_changed_lane(
     current_level: Res<CurrentLevel>,
 ) {
     for (e, edge, associated, segments, mut visibility) in &mut lanes {
-        update_lane_visuals(e, edge, segments, &anchors, &mut transforms);
+        let width = graphs.lane_width(associated);
+        update_lane_visuals(e, edge, segments, &anchors, width, &mut transforms);
 
         let is_visible =
             should_display_lane(edge, associated, &parents, &levels, &current_level, &graphs);
@@ -258,7 +533,12 @@ pub fn update_changed_lane(
 }
 
 pub fn update_lane_for_moved_anchor(
-    lanes: Query<(Entity, &Edge<Entity>, &LaneSegments)>,
+    lanes: Query<(
+        Entity,
+        &Edge<Entity>,
+        &AssociatedGraphs<Entity>,
+        &LaneSegments,
+    )>,
     anchors: AnchorParams,
     changed_anchors: Query<
         &Dependents,
@@ -267,12 +547,79 @@ pub fn update_lane_for_moved_anchor(
             Or<(Changed<Anchor>, Changed<GlobalTransform>)>,
         ),
     >,
+    graphs: GraphSelect,
     mut transforms: Query<&mut Transform>,
 ) {
     for dependents in &changed_anchors {
         for dependent in dependents.iter() {
-            if let Ok((e, edge, segments)) = lanes.get(*dependent) {
-                update_lane_visuals(e, edge, segments, &anchors, &mut transforms);
+            if let Ok((e, edge, associated, segments)) = lanes.get(*dependent) {
+                let width = graphs.lane_width(associated);
+                update_lane_visuals(e, edge, segments, &anchors, width, &mut transforms);
+            }
+        }
+    }
+}
+
+/// Curved lanes replace the straight middle segment of the lane with a mesh
+/// that follows the curve. This needs to run after the other systems that
+/// update lane visuals so it can redraw the curve any time they move the
+/// middle segment.
+pub fn update_lane_curves(
+    lanes: Query<(
+        Entity,
+        &Edge<Entity>,
+        &LaneCurve,
+        &AssociatedGraphs<Entity>,
+        &LaneSegments,
+    )>,
+    changed_curves: Query<(), Changed<LaneCurve>>,
+    anchors: AnchorParams,
+    graphs: GraphSelect,
+    mut transforms: Query<&mut Transform>,
+    mut mesh_handles: Query<&mut Handle<Mesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<SiteAssets>,
+) {
+    for (e, edge, curve, associated, segments) in &lanes {
+        let Ok(mut mid_tf) = transforms.get_mut(segments.mid) else {
+            continue;
+        };
+        let curve_changed = changed_curves.contains(e);
+        if !curve_changed && !mid_tf.is_changed() {
+            continue;
+        }
+
+        let (Ok(p0), Ok(p1)) = (
+            anchors.point_in_parent_frame_of(edge.start(), Category::Lane, e),
+            anchors.point_in_parent_frame_of(edge.end(), Category::Lane, e),
+        ) else {
+            continue;
+        };
+        let width = graphs.lane_width(associated);
+        let (mid_mesh, outline_mesh) = if curve.is_straight() {
+            if !curve_changed {
+                continue;
+            }
+            *mid_tf = line_stroke_transform(&p0, &p1, width);
+            (
+                assets.lane_mid_mesh.clone(),
+                assets.lane_mid_outline.clone(),
+            )
+        } else {
+            *mid_tf = Transform::IDENTITY;
+            let path = lane_path(p0, p1, curve);
+            (
+                meshes.add(make_flat_strip(&path, width).into()),
+                meshes.add(make_flat_strip(&path, 1.125 * width).into()),
+            )
+        };
+
+        for (segment, mesh) in [
+            (segments.mid, mid_mesh),
+            (segments.outlines[1], outline_mesh),
+        ] {
+            if let Ok(mut handle) = mesh_handles.get_mut(segment) {
+                *handle = mesh;
             }
         }
     }
@@ -322,7 +669,11 @@ pub fn update_visibility_for_lanes(
         (),
         (
             With<NavGraphMarker>,
-            Or<(Changed<Visibility>, Changed<RecencyRank<NavGraphMarker>>)>,
+            Or<(
+                Changed<Visibility>,
+                Changed<RecencyRank<NavGraphMarker>>,
+                Changed<LaneDefaults>,
+            )>,
         ),
     >,
     removed: RemovedComponents<NavGraphMarker>,
@@ -367,6 +718,12 @@ pub fn update_visibility_for_lanes(
             if let Ok(mut tf) = transforms.get_mut(segments.layer) {
                 tf.translation.z = height;
             }
+
+            set_lane_width(
+                segments,
+                graphs.lane_width(associated_graphs),
+                &mut transforms,
+            );
         }
     } else {
         for (_, associated_graphs, segments) in &lanes_with_changed_association {
@@ -380,6 +737,12 @@ pub fn update_visibility_for_lanes(
             if let Ok(mut tf) = transforms.get_mut(segments.layer) {
                 tf.translation.z = height;
             }
+
+            set_lane_width(
+                segments,
+                graphs.lane_width(associated_graphs),
+                &mut transforms,
+            );
         }
     }
 }
@@ -406,3 +769,236 @@ pub fn handle_consider_associated_graph(
         }
     }
 }
+
+pub fn split_lanes(
+    mut commands: Commands,
+    mut requests: EventReader<SplitLane>,
+    mut lanes: Query<
+        (
+            &mut Edge<Entity>,
+            &Motion,
+            &ReverseLane,
+            &AssociatedGraphs<Entity>,
+            &LaneCost,
+            &LaneCurve,
+            &SharedZone,
+        ),
+        With<LaneMarker>,
+    >,
+    anchors: Query<(&GlobalTransform, &Parent), With<Anchor>>,
+    levels: Query<(), With<LevelProperties>>,
+    transforms: Query<&GlobalTransform>,
+    mut dependents: Query<&mut Dependents>,
+) {
+    for request in requests.iter() {
+        let Ok((mut edge, motion, reverse, graphs, cost, curve, shared_zone)) =
+            lanes.get_mut(request.lane)
+        else {
+            continue;
+        };
+        if !curve.is_straight() {
+            notify_warning(
+                Some(request.lane),
+                format!("Cannot split lane {:?} because it is curved", request.lane),
+            );
+            continue;
+        }
+        let (Ok((start_tf, start_parent)), Ok((end_tf, end_parent))) =
+            (anchors.get(edge.start()), anchors.get(edge.end()))
+        else {
+            continue;
+        };
+
+        let p0 = start_tf.translation();
+        let p1 = end_tf.translation();
+        let dp = p1 - p0;
+        let length = dp.truncate().length();
+        if length < 2.0 * MIN_SPLIT_DISTANCE {
+            notify_warning(
+                Some(request.lane),
+                format!("Lane {:?} is too short to split", request.lane),
+            );
+            continue;
+        }
+
+        let s = (request.point - p0.truncate()).dot(dp.truncate()) / length;
+        if s < MIN_SPLIT_DISTANCE || length - MIN_SPLIT_DISTANCE < s {
+            notify_warning(
+                Some(request.lane),
+                format!("Cannot split lane {:?} so close to its end", request.lane),
+            );
+            continue;
+        }
+        let p_split = p0 + dp * s / length;
+
+        // Prefer to put the new anchor on the level of the lane in case one
+        // of its anchors is inside of a lift.
+        let parent = [start_parent.get(), end_parent.get()]
+            .into_iter()
+            .find(|p| levels.contains(*p))
+            .unwrap_or(start_parent.get());
+        let Ok(parent_tf) = transforms.get(parent) else {
+            continue;
+        };
+        let p = parent_tf.affine().inverse().transform_point3(p_split);
+        let anchor = commands
+            .spawn(
+                AnchorBundle::new([p.x, p.y].into())
+                    .parent_transform(parent_tf)
+                    .dependents(Dependents::single(request.lane)),
+            )
+            .id();
+        commands.entity(parent).add_child(anchor);
+
+        let original_end = edge.end();
+        if let Ok(mut deps) = dependents.get_mut(original_end) {
+            deps.remove(&request.lane);
+        }
+        *edge.end_mut() = anchor;
+
+        commands.spawn(Lane {
+            anchors: Edge::new(anchor, original_end),
+            forward: motion.clone(),
+            reverse: reverse.clone(),
+            graphs: graphs.clone(),
+            cost: *cost,
+            curve: Default::default(),
+            shared_zone: shared_zone.clone(),
+            marker: LaneMarker,
+        });
+    }
+}
+
+pub fn merge_collinear_lanes(
+    mut requests: EventReader<MergeCollinearLanes>,
+    mut lanes: Query<
+        (
+            Entity,
+            &mut Edge<Entity>,
+            &Motion,
+            &ReverseLane,
+            &AssociatedGraphs<Entity>,
+            &LaneCost,
+            &LaneCurve,
+            &SharedZone,
+            &Parent,
+        ),
+        (With<LaneMarker>, Without<Pending>),
+    >,
+    anchors: Query<&GlobalTransform, With<Anchor>>,
+    mut dependents: Query<&mut Dependents>,
+    mut delete: EventWriter<Delete>,
+) {
+    for request in requests.iter() {
+        let mut edges: HashMap<Entity, [Entity; 2]> = HashMap::new();
+        let mut lanes_at: HashMap<Entity, Vec<Entity>> = HashMap::new();
+        for (e, edge, _, _, _, _, _, _, parent) in &lanes {
+            if parent.get() != request.site {
+                continue;
+            }
+
+            edges.insert(e, edge.array());
+            for anchor in edge.array() {
+                lanes_at.entry(anchor).or_default().push(e);
+            }
+        }
+
+        let candidates: Vec<Entity> = lanes_at
+            .iter()
+            .filter(|(_, lanes)| lanes.len() == 2)
+            .map(|(anchor, _)| *anchor)
+            .collect();
+
+        let mut merged = 0;
+        for anchor in candidates {
+            let [a, b] = match lanes_at.get(&anchor).map(|l| l.as_slice()) {
+                Some([a, b]) => [*a, *b],
+                _ => continue,
+            };
+
+            // The anchor must not be used by anything besides these two lanes
+            let only_lanes = dependents
+                .get(anchor)
+                .ok()
+                .filter(|deps| deps.len() == 2 && deps.contains(&a) && deps.contains(&b))
+                .is_some();
+            if !only_lanes {
+                continue;
+            }
+
+            // Put the lanes in order so that `first` leads into `second`
+            let (first, second) = if edges[&a][1] == anchor && edges[&b][0] == anchor {
+                (a, b)
+            } else if edges[&b][1] == anchor && edges[&a][0] == anchor {
+                (b, a)
+            } else {
+                continue;
+            };
+
+            let start = edges[&first][0];
+            let end = edges[&second][1];
+            if start == end {
+                continue;
+            }
+
+            let same_properties = match (lanes.get(first), lanes.get(second)) {
+                (Ok((_, _, m0, r0, g0, c0, k0, z0, _)), Ok((_, _, m1, r1, g1, c1, k1, z1, _))) => {
+                    m0 == m1
+                        && r0 == r1
+                        && g0 == g1
+                        && c0 == c1
+                        && z0 == z1
+                        && k0.is_straight()
+                        && k1.is_straight()
+                }
+                _ => false,
+            };
+            if !same_properties {
+                continue;
+            }
+
+            let (Ok(p0), Ok(p1), Ok(p2)) =
+                (anchors.get(start), anchors.get(anchor), anchors.get(end))
+            else {
+                continue;
+            };
+            let d0 = (p1.translation() - p0.translation())
+                .truncate()
+                .normalize_or_zero();
+            let d1 = (p2.translation() - p1.translation())
+                .truncate()
+                .normalize_or_zero();
+            if d0.perp_dot(d1).abs() > COLLINEAR_LANE_TOLERANCE || d0.dot(d1) <= 0.0 {
+                continue;
+            }
+
+            // Extend the first lane over the second one and delete the second
+            // lane along with the anchor between them.
+            if let Ok((_, mut edge, _, _, _, _, _, _, _)) = lanes.get_mut(first) {
+                *edge.end_mut() = end;
+            }
+            if let Ok(mut deps) = dependents.get_mut(end) {
+                deps.remove(&second);
+                deps.insert(first);
+            }
+            if let Ok(mut deps) = dependents.get_mut(anchor) {
+                deps.remove(&first);
+            }
+            delete.send(Delete::new(anchor).and_dependents());
+
+            edges.get_mut(&first).unwrap()[1] = end;
+            edges.remove(&second);
+            lanes_at.remove(&anchor);
+            if let Some(lanes) = lanes_at.get_mut(&end) {
+                for lane in lanes.iter_mut() {
+                    if *lane == second {
+                        *lane = first;
+                    }
+                }
+            }
+            merged += 1;
+        }
+
+        notify_info(None, format!("Merged {merged} collinear lane(s)"));
+    }
+}
@@ -15,10 +15,50 @@
  *
 */
 
+use crate::console::notify_error;
 use crate::site::*;
 use crate::CurrentWorkspace;
 use bevy::prelude::*;
 
+/// Used as an event to move an element that is placed directly on a level,
+/// such as a model or a light, onto another level of the same site
+#[derive(Clone, Copy, Debug)]
+pub struct ChangeLevel {
+    pub element: Entity,
+    pub level: Entity,
+}
+
+pub fn handle_change_level(
+    mut commands: Commands,
+    mut requests: EventReader<ChangeLevel>,
+    levels: Query<&Parent, With<LevelProperties>>,
+    parents: Query<&Parent>,
+) {
+    for ChangeLevel { element, level } in requests.iter().copied() {
+        let Ok(site) = levels.get(level).map(|p| p.get()) else {
+            notify_error(
+                Some(element),
+                format!("Unable to move {element:?} onto {level:?} because it is not a level"),
+            );
+            continue;
+        };
+        let Ok(current) = parents.get(element).map(|p| p.get()) else {
+            continue;
+        };
+        if current == level {
+            continue;
+        }
+        if levels.get(current).map(|p| p.get()) != Ok(site) {
+            notify_error(
+                Some(element),
+                format!("Unable to move {element:?} because it is not on a level of the same site"),
+            );
+            continue;
+        }
+        commands.entity(level).add_child(element);
+    }
+}
+
 pub fn update_level_visibility(
     mut levels: Query<(Entity, &mut Visibility), With<LevelProperties>>,
     current_level: Res<CurrentLevel>,
@@ -15,7 +15,7 @@
  *
 */
 
-use crate::{interaction::Selectable, shapes::*, site::*, CurrentWorkspace};
+use crate::{console::report_error, interaction::Selectable, shapes::*, site::*, CurrentWorkspace};
 use bevy::{prelude::*, render::primitives::Aabb};
 use rmf_site_format::{Edge, LiftCabin};
 use std::collections::BTreeSet;
@@ -26,6 +26,9 @@ pub struct ChildLiftCabinGroup(pub Entity);
 #[derive(Clone, Copy, Debug, Component, Deref, DerefMut)]
 pub struct ChildCabinAnchorGroup(pub Entity);
 
+#[derive(Clone, Copy, Debug, Component, Deref, DerefMut)]
+pub struct ChildLiftShaft(pub Entity);
+
 #[derive(Clone, Copy, Debug, Component, Default)]
 pub struct CabinAnchorGroup;
 
@@ -78,6 +81,23 @@ pub struct ToggleLiftDoorAvailability {
     pub door_available: bool,
 }
 
+/// Request to generate a lobby waypoint in front of each cabin door of a lift
+/// on every level that the door visits. Each lobby waypoint will be connected
+/// by a lane to a waypoint in the center of the lift cabin.
+#[derive(Clone, Copy, Debug)]
+pub struct GenerateLiftLobbies {
+    pub for_lift: Entity,
+}
+
+/// How far in front of a lift door its lobby waypoint will be placed
+pub const LIFT_LOBBY_DISTANCE: f32 = 1.0;
+
+/// Tags given to the waypoints that are generated for a lift. Robots wait at
+/// these waypoints for the lift, so they are holding points.
+pub fn lift_lobby_tags() -> LocationTags {
+    LocationTags(vec![LocationTag::HoldingPoint])
+}
+
 fn make_lift_transform(
     entity: Entity,
     reference_anchors: &Edge<Entity>,
@@ -305,6 +325,204 @@ pub fn update_lift_cabin(
     }
 }
 
+/// Render a translucent shaft for each lift that spans every level that the
+/// lift visits. The shaft is drawn relative to the elevation of the current
+/// level, so it reaches up and down to the other floors that the lift serves.
+pub fn update_lift_shafts(
+    mut commands: Commands,
+    lifts: Query<(
+        Entity,
+        &LiftCabin<Entity>,
+        Option<&ChildLiftShaft>,
+        Option<&Children>,
+    )>,
+    changed_lifts: Query<(), Changed<LiftCabin<Entity>>>,
+    changed_visits: Query<(), Changed<LevelVisits<Entity>>>,
+    changed_levels: Query<(), Changed<LevelProperties>>,
+    removed_levels: RemovedComponents<LevelProperties>,
+    level_visits: Query<&LevelVisits<Entity>, Without<Pending>>,
+    levels: Query<&LevelProperties>,
+    current_level: Res<CurrentLevel>,
+    assets: Res<SiteAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    if changed_lifts.is_empty()
+        && changed_visits.is_empty()
+        && changed_levels.is_empty()
+        && removed_levels.iter().next().is_none()
+        && !current_level.is_changed()
+    {
+        return;
+    }
+
+    let current_elevation = current_level
+        .0
+        .and_then(|l| levels.get(l).ok())
+        .map(|l| l.elevation)
+        .unwrap_or(0.0);
+
+    for (e, cabin, child_shaft, children) in &lifts {
+        if let Some(shaft) = child_shaft {
+            commands.entity(**shaft).despawn_recursive();
+            commands.entity(e).remove::<ChildLiftShaft>();
+        }
+
+        let elevations: Vec<f32> = children
+            .into_iter()
+            .flat_map(|children| children.iter())
+            .filter_map(|child| level_visits.get(*child).ok())
+            .flat_map(|visits| visits.iter())
+            .filter_map(|level| levels.get(*level).ok())
+            .map(|level| level.elevation)
+            .collect();
+
+        let (Some(bottom), Some(top)) = (
+            elevations.iter().copied().reduce(f32::min),
+            elevations.iter().copied().reduce(f32::max),
+        ) else {
+            continue;
+        };
+
+        let bottom = bottom - current_elevation;
+        let top = top + DEFAULT_LEVEL_HEIGHT - current_elevation;
+
+        match cabin {
+            LiftCabin::Rect(params) => {
+                let Aabb { center, .. } = params.aabb();
+                let mesh = make_box(
+                    params.depth + 2.0 * params.thickness(),
+                    params.width + 2.0 * params.thickness(),
+                    top - bottom,
+                );
+                let shaft = commands
+                    .spawn(PbrBundle {
+                        mesh: meshes.add(mesh.into()),
+                        material: assets.lift_shaft_material.clone(),
+                        transform: Transform::from_translation(Vec3::new(
+                            center.x,
+                            center.y,
+                            (top + bottom) / 2.0,
+                        )),
+                        ..default()
+                    })
+                    .insert(Selectable::new(e))
+                    .id();
+
+                commands
+                    .entity(e)
+                    .insert(ChildLiftShaft(shaft))
+                    .add_child(shaft);
+            }
+        }
+    }
+}
+
+pub fn generate_lift_lobbies(
+    mut commands: Commands,
+    mut requests: EventReader<GenerateLiftLobbies>,
+    lifts: Query<(&NameInSite, &LiftCabin<Entity>, &ChildCabinAnchorGroup)>,
+    anchor_groups: Query<(&GlobalTransform, Option<&Children>), With<CabinAnchorGroup>>,
+    doors: Query<&LevelVisits<Entity>, (With<LiftCabinDoorMarker>, Without<Pending>)>,
+    levels: Query<(&LevelProperties, &GlobalTransform)>,
+    locations: Query<(&NameInSite, &Point<Entity>), (With<LocationTags>, Without<Pending>)>,
+) {
+    for request in requests.iter() {
+        let Ok((lift_name, cabin, anchor_group)) = lifts.get(request.for_lift) else {
+            continue;
+        };
+        let Ok((group_tf, group_children)) = anchor_groups.get(**anchor_group) else {
+            continue;
+        };
+
+        // Use the existing cabin waypoint if there is one, otherwise create a
+        // new one in the center of the cabin.
+        let cabin_anchor = group_children
+            .into_iter()
+            .flat_map(|children| children.iter())
+            .find_map(|child| {
+                locations
+                    .iter()
+                    .find(|(_, point)| point.0 == *child)
+                    .map(|_| *child)
+            })
+            .unwrap_or_else(|| {
+                let anchor = commands
+                    .spawn(AnchorBundle::new([0.0, 0.0].into()).parent_transform(group_tf))
+                    .id();
+                commands.entity(**anchor_group).add_child(anchor);
+                commands.spawn(Location {
+                    anchor: Point(anchor),
+                    tags: lift_lobby_tags(),
+                    name: lift_name.clone(),
+                    graphs: AssociatedGraphs::All,
+                    parameters: Default::default(),
+                });
+                anchor
+            });
+
+        let existing_names: BTreeSet<&str> =
+            locations.iter().map(|(name, _)| name.0.as_str()).collect();
+
+        match cabin {
+            LiftCabin::Rect(params) => {
+                for face in RectFace::iter_all() {
+                    let (Some(placement), Some(door_anchors)) =
+                        (params.door(face), params.level_door_anchors(face))
+                    else {
+                        continue;
+                    };
+                    let Ok(visits) = doors.get(placement.door) else {
+                        continue;
+                    };
+
+                    let (u, _) = face.uv2();
+                    let [left, right] = door_anchors
+                        .map(|a| Vec2::from(*a.translation_for_category(Category::General)));
+                    let p_lobby = (left + right) / 2.0 + LIFT_LOBBY_DISTANCE * u;
+                    let p_lobby = group_tf.transform_point(p_lobby.extend(0.0));
+
+                    for level in visits.iter() {
+                        let Ok((level_props, level_tf)) = levels.get(*level) else {
+                            continue;
+                        };
+
+                        let name = format!(
+                            "{}_{}_{}_lobby",
+                            lift_name.0,
+                            level_props.name,
+                            face.label().to_lowercase(),
+                        );
+                        if existing_names.contains(name.as_str()) {
+                            continue;
+                        }
+
+                        let p = level_tf.affine().inverse().transform_point3(p_lobby);
+                        let anchor = commands.spawn(AnchorBundle::new([p.x, p.y].into())).id();
+                        commands.entity(*level).add_child(anchor);
+                        commands.spawn(Location {
+                            anchor: Point(anchor),
+                            tags: lift_lobby_tags(),
+                            name: NameInSite(name),
+                            graphs: AssociatedGraphs::All,
+                            parameters: Default::default(),
+                        });
+                        commands.spawn(Lane {
+                            anchors: Edge::new(anchor, cabin_anchor),
+                            forward: Default::default(),
+                            reverse: ReverseLane::Same,
+                            graphs: AssociatedGraphs::All,
+                            cost: Default::default(),
+                            curve: Default::default(),
+                            shared_zone: Default::default(),
+                            marker: LaneMarker,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub fn update_lift_edge(
     mut lifts: Query<
         (Entity, &Edge<Entity>, &mut Transform),
@@ -363,10 +581,13 @@ pub fn update_lift_door_availability(
             if !all_levels.contains(toggle.on_level) {
                 // If we're being asked to toggle availability on for something
                 // that isn't a level, then ignore this request.
-                println!(
-                    "DEV ERROR: Asking to turn on lift {:?} door {:?} availability \
-                    for a level {:?} that does not exist.",
-                    toggle.for_lift, toggle.cabin_door, toggle.on_level,
+                report_error(
+                    Some(toggle.for_lift),
+                    format!(
+                        "DEV ERROR: Asking to turn on lift {:?} door {:?} availability \
+                        for a level {:?} that does not exist.",
+                        toggle.for_lift, toggle.cabin_door, toggle.on_level
+                    ),
                 );
                 continue;
             }
@@ -531,9 +752,12 @@ pub fn update_lift_door_availability(
             let e_lift = match parents.get(e_door) {
                 Ok(e_lift) => e_lift,
                 Err(_) => {
-                    println!(
-                        "DEV ERROR: Unable to find parent for lift door \
-                        {e_door:?} while handling a removed level"
+                    report_error(
+                        Some(e_door),
+                        format!(
+                            "DEV ERROR: Unable to find parent for lift door \
+                            {e_door:?} while handling a removed level"
+                        ),
                     );
                     continue;
                 }
@@ -541,7 +765,10 @@ pub fn update_lift_door_availability(
             let (mut cabin, _, _) = match lifts.get_mut(e_lift.get()) {
                 Ok(cabin) => cabin,
                 Err(_) => {
-                    println!("DEV ERROR: Unable to find cabin for lift {e_lift:?}");
+                    report_error(
+                        Some(e_lift.get()),
+                        format!("DEV ERROR: Unable to find cabin for lift {e_lift:?}"),
+                    );
                     continue;
                 }
             };
@@ -15,7 +15,11 @@
  *
 */
 
-use crate::site::CurrentLevel;
+use crate::{
+    console::{notify_error, notify_info, report_error},
+    site::CurrentLevel,
+    RenderSettings,
+};
 use bevy::{
     pbr::CubemapVisibleEntities,
     prelude::{
@@ -27,7 +31,7 @@ use bevy::{
         view::VisibleEntities,
     },
 };
-use rmf_site_format::{Category, LevelProperties, Light, LightKind, Pose};
+use rmf_site_format::{Category, LevelProperties, Light, LightGroup, LightKind, Pose};
 use std::collections::{BTreeMap, HashMap};
 
 /// True/false for whether the physical lights of an environment should be
@@ -67,46 +71,61 @@ pub fn add_physical_lights(
             if let Some(current_level) = **current_level {
                 commands.entity(current_level).add_child(e);
             } else {
-                println!("DEV ERROR: No current level to assign light {e:?}");
+                report_error(
+                    Some(e),
+                    format!("DEV ERROR: No current level to assign light {e:?}"),
+                );
             }
         }
     }
 }
 
+/// Lights are also refreshed when the render settings change, because the
+/// settings can turn off shadows for every light.
 pub fn update_physical_lights(
     mut commands: Commands,
-    mut changed: Query<
-        (
-            Entity,
-            &LightKind,
-            Option<&mut BevyPointLight>,
-            Option<&mut BevySpotLight>,
-            Option<&mut BevyDirectionalLight>,
-        ),
-        Changed<LightKind>,
-    >,
+    mut lights: Query<(
+        Entity,
+        &LightKind,
+        ChangeTrackers<LightKind>,
+        Option<&mut BevyPointLight>,
+        Option<&mut BevySpotLight>,
+        Option<&mut BevyDirectionalLight>,
+    )>,
+    render_settings: Res<RenderSettings>,
 ) {
-    for (e, kind, mut b_point, mut b_spot, mut b_dir) in &mut changed {
+    let shadows = render_settings.shadows;
+    for (e, kind, tracker, mut b_point, mut b_spot, mut b_dir) in &mut lights {
+        if !tracker.is_changed() && !render_settings.is_changed() {
+            continue;
+        }
+
         match kind {
             LightKind::Point(point) => {
+                let mut light = point.to_bevy();
+                light.shadows_enabled &= shadows;
                 if let Some(b_point) = &mut b_point {
-                    **b_point = point.to_bevy();
+                    **b_point = light;
                 } else {
-                    commands.entity(e).insert(point.to_bevy());
+                    commands.entity(e).insert(light);
                 }
             }
             LightKind::Spot(spot) => {
+                let mut light = spot.to_bevy();
+                light.shadows_enabled &= shadows;
                 if let Some(b_spot) = &mut b_spot {
-                    **b_spot = spot.to_bevy();
+                    **b_spot = light;
                 } else {
-                    commands.entity(e).insert(spot.to_bevy());
+                    commands.entity(e).insert(light);
                 }
             }
             LightKind::Directional(dir) => {
+                let mut light = dir.to_bevy();
+                light.shadows_enabled &= shadows;
                 if let Some(b_dir) = &mut b_dir {
-                    **b_dir = dir.to_bevy();
+                    **b_dir = light;
                 } else {
-                    commands.entity(e).insert(dir.to_bevy());
+                    commands.entity(e).insert(light);
                 }
             }
         }
@@ -125,14 +144,24 @@ pub fn update_physical_lights(
     }
 }
 
-pub fn toggle_physical_lights(
-    mut physical_lights: Query<&mut Visibility, With<LightKind>>,
+/// Lights that belong to a level only shine while that level is being shown.
+/// Lights that belong to the site itself are shared by every level.
+pub fn update_physical_light_visibility(
+    mut physical_lights: Query<(&mut Visibility, &Parent), With<LightKind>>,
+    moved_lights: Query<(), (With<LightKind>, Changed<Parent>)>,
+    levels: Query<(), With<LevelProperties>>,
     physical_light_toggle: Res<PhysicalLightToggle>,
+    current_level: Res<CurrentLevel>,
 ) {
-    if physical_light_toggle.is_changed() {
-        for mut v in &mut physical_lights {
-            v.is_visible = physical_light_toggle.0;
-        }
+    if !physical_light_toggle.is_changed() && !current_level.is_changed() && moved_lights.is_empty()
+    {
+        return;
+    }
+
+    for (mut v, parent) in &mut physical_lights {
+        let level = parent.get();
+        let on_current_level = !levels.contains(level) || Some(level) == **current_level;
+        v.is_visible = physical_light_toggle.0 && on_current_level;
     }
 }
 
@@ -142,29 +171,47 @@ pub struct ExportLights(pub std::path::PathBuf);
 
 pub fn export_lights(
     mut exports: EventReader<ExportLights>,
-    lights: Query<(&Pose, &LightKind, &Parent)>,
+    lights: Query<(&Pose, &LightKind, &LightGroup, &Parent)>,
     levels: Query<&LevelProperties>,
 ) {
     for export in exports.iter() {
         let mut lights_per_level: BTreeMap<String, Vec<Light>> = BTreeMap::new();
-        for (pose, kind, parent) in &lights {
+        let mut shared_lights = Vec::new();
+        for (pose, kind, group, parent) in &lights {
+            let light = Light {
+                pose: pose.clone(),
+                kind: kind.clone(),
+                group: group.clone(),
+            };
             if let Ok(level) = levels.get(parent.get()) {
                 lights_per_level
                     .entry(level.name.clone())
                     .or_default()
-                    .push(Light {
-                        pose: pose.clone(),
-                        kind: kind.clone(),
-                    })
+                    .push(light);
+            } else {
+                shared_lights.push(light);
+            }
+        }
+
+        // Every level inherits the shared lights
+        if !shared_lights.is_empty() {
+            for level in &levels {
+                lights_per_level
+                    .entry(level.name.clone())
+                    .or_default()
+                    .extend(shared_lights.iter().cloned());
             }
         }
 
         let out_file = match std::fs::File::create(export.0.clone()) {
             Ok(out_file) => out_file,
             Err(err) => {
-                println!(
-                    "Failed to create file {:?} for exporting lights: {}",
-                    export.0, err,
+                notify_error(
+                    None,
+                    format!(
+                        "Failed to create file {:?} for exporting lights: {}",
+                        export.0, err,
+                    ),
                 );
                 continue;
             }
@@ -177,8 +224,9 @@ pub fn export_lights(
             root.insert(level, lights_map);
         }
 
-        if let Err(err) = serde_yaml::to_writer(out_file, &root) {
-            println!("Error while exporting lights: {err}");
+        match serde_yaml::to_writer(out_file, &root) {
+            Ok(()) => notify_info(None, format!("Lights exported to {}", export.0.display())),
+            Err(err) => notify_error(None, format!("Error while exporting lights: {err}")),
         }
     }
 }
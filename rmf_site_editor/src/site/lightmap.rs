@@ -0,0 +1,434 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::console::{notify_error, notify_info, notify_warning};
+use crate::site::{
+    illuminance_on, Anchor, Edge, FloorMarker, LevelProperties, LightKind, Path, SiteID,
+    WallMarker, DEFAULT_LEVEL_HEIGHT, DEFAULT_WALL_THICKNESS,
+};
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
+use serde::Serialize;
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// The illuminance in lux that is stored as full brightness in a lightmap.
+/// Texels are stored linearly, so a texel value of 0.5 means 500 lux.
+pub const LIGHTMAP_FULL_SCALE_LUX: f32 = 1000.0;
+/// Lightmaps will not be made wider or taller than this many texels
+pub const LIGHTMAP_MAX_SIZE: u32 = 2048;
+/// How far away directional lights are placed when checking if a wall blocks
+/// them
+const DIRECTIONAL_LIGHT_DISTANCE: f32 = 1000.0;
+/// Lift the floor samples slightly so the floor does not block itself
+const FLOOR_SAMPLE_OFFSET: f32 = 0.001;
+
+/// Bake lightmaps for every floor and wall of a site into a folder, along
+/// with a lightmaps.yaml file that describes where each lightmap belongs.
+pub struct BakeLightmaps {
+    pub site: Entity,
+    pub folder: PathBuf,
+    pub texels_per_meter: f32,
+}
+
+/// The lightmap bake that is currently running, if any
+#[derive(Resource, Default)]
+pub struct LightmapBake {
+    pub task: Option<Task<Result<usize, String>>>,
+}
+
+impl LightmapBake {
+    pub fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct FloorLightmap {
+    image: String,
+    /// Corner of the lightmap with the lowest x and y coordinates
+    min: [f32; 2],
+    /// Corner of the lightmap with the highest x and y coordinates
+    max: [f32; 2],
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct WallLightmap {
+    /// Lightmap of the side of the wall that is to the left when looking
+    /// from the start to the end
+    left_image: String,
+    right_image: String,
+    start: [f32; 2],
+    end: [f32; 2],
+    height: f32,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct LevelLightmaps {
+    floors: BTreeMap<String, FloorLightmap>,
+    walls: BTreeMap<String, WallLightmap>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct LightmapManifest {
+    full_scale_lux: f32,
+    texels_per_meter: f32,
+    levels: BTreeMap<String, LevelLightmaps>,
+}
+
+struct BakeLight {
+    kind: LightKind,
+    tf: Transform,
+}
+
+impl BakeLight {
+    /// The point that light travels from to reach p
+    fn source(&self, p: Vec3) -> Vec3 {
+        match self.kind {
+            LightKind::Directional(_) => p - DIRECTIONAL_LIGHT_DISTANCE * self.tf.forward(),
+            _ => self.tf.translation,
+        }
+    }
+
+    fn color(&self) -> Vec3 {
+        let [r, g, b, _] = self.kind.color();
+        Vec3::new(r, g, b)
+    }
+}
+
+struct BakeWall {
+    name: String,
+    start: Vec3,
+    end: Vec3,
+}
+
+impl BakeWall {
+    /// Check if this wall is between p and a light source
+    fn blocks(&self, p: Vec3, source: Vec3) -> bool {
+        let d = (source - p).truncate();
+        let e = (self.end - self.start).truncate();
+        let denom = d.perp_dot(e);
+        if denom.abs() < 1e-6 {
+            return false;
+        }
+        let f = (self.start - p).truncate();
+        let t = f.perp_dot(e) / denom;
+        let u = f.perp_dot(d) / denom;
+        if t <= 1e-4 || t >= 1.0 || !(0.0..=1.0).contains(&u) {
+            return false;
+        }
+        let z = p.z + t * (source.z - p.z);
+        self.start.z <= z && z <= self.start.z + DEFAULT_LEVEL_HEIGHT
+    }
+}
+
+struct BakeFloor {
+    name: String,
+    min: Vec3,
+    max: Vec3,
+}
+
+struct LevelBake {
+    name: String,
+    lights: Vec<BakeLight>,
+    floors: Vec<BakeFloor>,
+    walls: Vec<BakeWall>,
+}
+
+impl LevelBake {
+    fn texel(&self, p: Vec3, normal: Vec3) -> image::Rgb<u8> {
+        let lux: Vec3 = self
+            .lights
+            .iter()
+            .filter(|light| {
+                let source = light.source(p);
+                !self.walls.iter().any(|wall| wall.blocks(p, source))
+            })
+            .map(|light| light.color() * illuminance_on(p, normal, &light.kind, &light.tf))
+            .sum();
+        let value =
+            |lux: f32| ((lux / LIGHTMAP_FULL_SCALE_LUX).clamp(0.0, 1.0) * 255.0).round() as u8;
+        image::Rgb([value(lux.x), value(lux.y), value(lux.z)])
+    }
+}
+
+fn lightmap_size(length: f32, texels_per_meter: f32) -> u32 {
+    ((length * texels_per_meter).ceil() as u32).clamp(1, LIGHTMAP_MAX_SIZE)
+}
+
+fn file_name_part(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn element_name(e: Entity, site_id: Option<&SiteID>) -> String {
+    match site_id {
+        Some(id) => id.0.to_string(),
+        None => format!("e{}", e.index()),
+    }
+}
+
+fn bake(levels: Vec<LevelBake>, folder: PathBuf, texels_per_meter: f32) -> Result<usize, String> {
+    std::fs::create_dir_all(&folder)
+        .map_err(|err| format!("Unable to create folder {folder:?}: {err}"))?;
+
+    let save = |image: image::RgbImage, file: &str| {
+        image
+            .save(folder.join(file))
+            .map_err(|err| format!("Unable to save lightmap {file}: {err}"))
+    };
+
+    let mut count = 0;
+    let mut manifest = LightmapManifest {
+        full_scale_lux: LIGHTMAP_FULL_SCALE_LUX,
+        texels_per_meter,
+        levels: BTreeMap::new(),
+    };
+    for level in &levels {
+        let prefix = file_name_part(&level.name);
+        let mut level_lightmaps = LevelLightmaps::default();
+        for floor in &level.floors {
+            let size = floor.max - floor.min;
+            let (w, h) = (
+                lightmap_size(size.x, texels_per_meter),
+                lightmap_size(size.y, texels_per_meter),
+            );
+            // The first row of the image is the side of the floor with the
+            // highest y coordinate
+            let image = image::RgbImage::from_fn(w, h, |x, y| {
+                let p = Vec3::new(
+                    floor.min.x + (x as f32 + 0.5) / w as f32 * size.x,
+                    floor.max.y - (y as f32 + 0.5) / h as f32 * size.y,
+                    floor.min.z + FLOOR_SAMPLE_OFFSET,
+                );
+                level.texel(p, Vec3::Z)
+            });
+            let file = format!("{prefix}_floor_{}.png", floor.name);
+            save(image, &file)?;
+            count += 1;
+            level_lightmaps.floors.insert(
+                floor.name.clone(),
+                FloorLightmap {
+                    image: file,
+                    min: [floor.min.x, floor.min.y],
+                    max: [floor.max.x, floor.max.y],
+                },
+            );
+        }
+
+        for wall in &level.walls {
+            let span = wall.end - wall.start;
+            let length = span.truncate().length();
+            if length < 1e-6 {
+                continue;
+            }
+            let dir = span / length;
+            let left = Vec3::new(-dir.y, dir.x, 0.0);
+            let (w, h) = (
+                lightmap_size(length, texels_per_meter),
+                lightmap_size(DEFAULT_LEVEL_HEIGHT, texels_per_meter),
+            );
+            let mut images = Vec::new();
+            for (side, normal) in [("left", left), ("right", -left)] {
+                // The first row of the image is the top of the wall
+                let image = image::RgbImage::from_fn(w, h, |x, y| {
+                    let p = wall.start
+                        + (x as f32 + 0.5) / w as f32 * span
+                        + (1.0 - (y as f32 + 0.5) / h as f32) * DEFAULT_LEVEL_HEIGHT * Vec3::Z
+                        + DEFAULT_WALL_THICKNESS / 2.0 * normal;
+                    level.texel(p, normal)
+                });
+                let file = format!("{prefix}_wall_{}_{side}.png", wall.name);
+                save(image, &file)?;
+                count += 1;
+                images.push(file);
+            }
+            let right_image = images.pop().unwrap();
+            let left_image = images.pop().unwrap();
+            level_lightmaps.walls.insert(
+                wall.name.clone(),
+                WallLightmap {
+                    left_image,
+                    right_image,
+                    start: [wall.start.x, wall.start.y],
+                    end: [wall.end.x, wall.end.y],
+                    height: DEFAULT_LEVEL_HEIGHT,
+                },
+            );
+        }
+        manifest.levels.insert(level.name.clone(), level_lightmaps);
+    }
+
+    let manifest_file = folder.join("lightmaps.yaml");
+    let out_file = std::fs::File::create(&manifest_file)
+        .map_err(|err| format!("Unable to create file {manifest_file:?}: {err}"))?;
+    serde_yaml::to_writer(out_file, &manifest)
+        .map_err(|err| format!("Unable to write {manifest_file:?}: {err}"))?;
+    Ok(count)
+}
+
+pub fn bake_lightmaps(
+    mut requests: EventReader<BakeLightmaps>,
+    mut bake_task: ResMut<LightmapBake>,
+    levels: Query<(Entity, &LevelProperties, &Parent)>,
+    lights: Query<(&LightKind, &GlobalTransform, &Parent)>,
+    floors: Query<(Entity, &Path<Entity>, &Parent, Option<&SiteID>), With<FloorMarker>>,
+    walls: Query<(Entity, &Edge<Entity>, &Parent, Option<&SiteID>), With<WallMarker>>,
+    anchors: Query<&GlobalTransform, With<Anchor>>,
+) {
+    for request in requests.iter() {
+        if bake_task.is_running() {
+            notify_warning(None, "Lightmaps are already being baked");
+            continue;
+        }
+
+        let mut level_bakes = Vec::new();
+        for (level, properties, parent) in &levels {
+            if parent.get() != request.site {
+                continue;
+            }
+
+            // Include the lights that are shared by every level
+            let level_lights = lights
+                .iter()
+                .filter(|(_, _, parent)| parent.get() == level || parent.get() == request.site)
+                .map(|(kind, tf, _)| BakeLight {
+                    kind: kind.clone(),
+                    tf: tf.compute_transform(),
+                })
+                .collect();
+
+            let mut level_floors = Vec::new();
+            for (e, path, parent, site_id) in &floors {
+                if parent.get() != level {
+                    continue;
+                }
+                let points: Vec<Vec3> = path
+                    .0
+                    .iter()
+                    .filter_map(|a| anchors.get(*a).ok())
+                    .map(|tf| tf.translation())
+                    .collect();
+                let Some(first) = points.first() else {
+                    continue;
+                };
+                let (min, max) = points
+                    .iter()
+                    .fold((*first, *first), |(min, max), p| (min.min(*p), max.max(*p)));
+                level_floors.push(BakeFloor {
+                    name: element_name(e, site_id),
+                    min,
+                    max,
+                });
+            }
+
+            let mut level_walls = Vec::new();
+            for (e, edge, parent, site_id) in &walls {
+                if parent.get() != level {
+                    continue;
+                }
+                let (Ok(start), Ok(end)) = (anchors.get(edge.start()), anchors.get(edge.end()))
+                else {
+                    continue;
+                };
+                level_walls.push(BakeWall {
+                    name: element_name(e, site_id),
+                    start: start.translation(),
+                    end: end.translation(),
+                });
+            }
+
+            level_bakes.push(LevelBake {
+                name: properties.name.clone(),
+                lights: level_lights,
+                floors: level_floors,
+                walls: level_walls,
+            });
+        }
+
+        if level_bakes.is_empty() {
+            notify_error(None, "Unable to bake lightmaps: the site has no levels");
+            continue;
+        }
+
+        let folder = request.folder.clone();
+        let texels_per_meter = request.texels_per_meter.max(0.1);
+        notify_info(None, format!("Baking lightmaps into {folder:?}"));
+        bake_task.task = Some(
+            AsyncComputeTaskPool::get()
+                .spawn(async move { bake(level_bakes, folder, texels_per_meter) }),
+        );
+    }
+}
+
+pub fn finish_lightmap_bake(mut bake_task: ResMut<LightmapBake>) {
+    let Some(task) = &mut bake_task.task else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    bake_task.task = None;
+
+    match result {
+        Ok(count) => notify_info(None, format!("Finished baking {count} lightmaps")),
+        Err(err) => notify_error(None, format!("Unable to bake lightmaps: {err}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A wall that runs along x = 1 on a level at the given elevation
+    fn wall(elevation: f32) -> BakeWall {
+        BakeWall {
+            name: "wall".to_owned(),
+            start: Vec3::new(1.0, -1.0, elevation),
+            end: Vec3::new(1.0, 1.0, elevation),
+        }
+    }
+
+    #[test]
+    fn wall_blocks_light_that_crosses_it() {
+        assert!(wall(0.0).blocks(Vec3::ZERO, Vec3::new(2.0, 0.0, 1.0)));
+        assert!(wall(0.0).blocks(Vec3::ZERO, Vec3::new(2.0, 1.5, 1.0)));
+    }
+
+    #[test]
+    fn wall_does_not_block_light_that_misses_it() {
+        let wall = wall(0.0);
+        // The light is in front of the wall
+        assert!(!wall.blocks(Vec3::ZERO, Vec3::new(0.5, 0.0, 1.0)));
+        // The light passes beside the end of the wall
+        assert!(!wall.blocks(Vec3::ZERO, Vec3::new(2.0, 5.0, 1.0)));
+        // The light is parallel to the wall
+        assert!(!wall.blocks(Vec3::ZERO, Vec3::new(0.0, 2.0, 1.0)));
+        // The light passes over the top of the wall
+        assert!(!wall.blocks(Vec3::ZERO, Vec3::new(2.0, 0.0, 4.0 * DEFAULT_LEVEL_HEIGHT)));
+    }
+
+    #[test]
+    fn wall_does_not_shadow_its_own_surface_or_other_levels() {
+        assert!(!wall(0.0).blocks(Vec3::new(1.0, 0.0, 0.5), Vec3::new(2.0, 0.0, 1.0)));
+        let upper = wall(2.0 * DEFAULT_LEVEL_HEIGHT);
+        assert!(!upper.blocks(Vec3::ZERO, Vec3::new(2.0, 0.0, 1.0)));
+    }
+}
@@ -15,7 +15,7 @@
  *
 */
 
-use crate::{recency::RecencyRanking, site::*, Autoload, CurrentWorkspace};
+use crate::{console::report_error, recency::RecencyRanking, site::*, Autoload, CurrentWorkspace};
 use bevy::{ecs::system::SystemParam, prelude::*, tasks::AsyncComputeTaskPool};
 use futures_lite::future;
 use rmf_site_format::legacy::building_map::BuildingMap;
@@ -55,6 +55,8 @@ fn generate_site_entities(commands: &mut Commands, site_data: &rmf_site_format::
     site_cmd
         .insert(Category::Site)
         .insert(site_data.properties.clone())
+        .insert(site_data.environment.clone())
+        .insert(site_data.navigation.guided.palette)
         .with_children(|site| {
             for (anchor_id, anchor) in &site_data.anchors {
                 let anchor_entity = site
@@ -126,10 +128,27 @@ fn generate_site_entities(commands: &mut Commands, site_data: &rmf_site_format::
                         }
 
                         for (model_id, model) in &level_data.models {
-                            level.spawn(model.clone()).insert(SiteID(*model_id));
+                            level
+                                .spawn(model.clone())
+                                .insert(SiteID(*model_id))
+                                .with_children(|model| {
+                                    for (sensor_id, sensor) in &level_data.sensors {
+                                        if sensor.parent != *model_id {
+                                            continue;
+                                        }
+                                        sensor.bundle.add_bevy_components(model.spawn((
+                                            NameInSite(sensor.bundle.name.clone()),
+                                            SiteID(*sensor_id),
+                                        )));
+                                    }
+                                });
                             consider_id(*model_id);
                         }
 
+                        for sensor_id in level_data.sensors.keys() {
+                            consider_id(*sensor_id);
+                        }
+
                         for (physical_camera_id, physical_camera) in &level_data.physical_cameras {
                             level
                                 .spawn(physical_camera.clone())
@@ -137,6 +156,11 @@ fn generate_site_entities(commands: &mut Commands, site_data: &rmf_site_format::
                             consider_id(*physical_camera_id);
                         }
 
+                        for (shape_id, shape) in &level_data.primitive_shapes {
+                            level.spawn(shape.clone()).insert(SiteID(*shape_id));
+                            consider_id(*shape_id);
+                        }
+
                         for (wall_id, wall) in &level_data.walls {
                             level
                                 .spawn(wall.to_ecs(&id_to_entity))
@@ -166,6 +190,11 @@ fn generate_site_entities(commands: &mut Commands, site_data: &rmf_site_format::
                 consider_id(*level_id);
             }
 
+            for (light_id, light) in &site_data.lights {
+                site.spawn(light.clone()).insert(SiteID(*light_id));
+                consider_id(*light_id);
+            }
+
             for (lift_id, lift_data) in &site_data.lifts {
                 let lift = site
                     .spawn(SiteID(*lift_id))
@@ -511,7 +540,10 @@ pub fn import_nav_graph(
 ) {
     for r in import_requests.iter() {
         if let Err(err) = generate_imported_nav_graphs(&mut params, r.into_site, &r.from_site) {
-            println!("Failed to import nav graph: {err}");
+            report_error(
+                Some(r.into_site),
+                format!("Failed to import nav graph: {err}"),
+            );
         }
     }
 
@@ -571,7 +603,10 @@ pub fn import_nav_graph(
 
             if let Err(err) = generate_imported_nav_graphs(&mut params, into_site, &from_site_data)
             {
-                println!("Failed to auto-import nav graph: {err}");
+                report_error(
+                    Some(into_site),
+                    format!("Failed to auto-import nav graph: {err}"),
+                );
             }
         }
     }
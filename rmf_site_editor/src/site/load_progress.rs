@@ -0,0 +1,166 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::site::{LoadingDrawings, PendingSpawning};
+use bevy::{prelude::*, utils::HashSet};
+use rmf_site_format::AssetSource;
+
+/// Keeps track of the assets that have started loading since the editor was
+/// last idle. Every model and drawing that waits on an asset during a batch
+/// of loads is remembered until the whole batch has finished, so the
+/// progress of loading a site can be reported even as new loads begin.
+#[derive(Resource, Default, Debug)]
+pub struct LoadProgress {
+    /// Models that have been waiting on their asset during this batch
+    pub models: HashSet<Entity>,
+    /// Drawings that have been waiting on their image during this batch
+    pub drawings: HashSet<Entity>,
+    pub pending_models: usize,
+    /// Pending models whose asset is being downloaded from a remote source
+    pub pending_downloads: usize,
+    pub pending_drawings: usize,
+}
+
+impl LoadProgress {
+    pub fn total(&self) -> usize {
+        self.models.len() + self.drawings.len()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending_models + self.pending_drawings
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.pending() > 0
+    }
+
+    /// How much of the current batch has finished loading, from 0 to 1
+    pub fn fraction(&self) -> f32 {
+        let total = self.total();
+        if total == 0 {
+            return 1.0;
+        }
+        (total - self.pending().min(total)) as f32 / total as f32
+    }
+}
+
+pub fn track_load_progress(
+    mut progress: ResMut<LoadProgress>,
+    models: Query<(Entity, &AssetSource), With<PendingSpawning>>,
+    loading_drawings: Res<LoadingDrawings>,
+) {
+    let pending_drawings = loading_drawings.0.len();
+    let pending_models = models.iter().count();
+    if pending_models + pending_drawings == 0 {
+        if progress.total() > 0 {
+            *progress = LoadProgress::default();
+        }
+        return;
+    }
+
+    let progress = progress.as_mut();
+    progress.pending_models = pending_models;
+    progress.pending_drawings = pending_drawings;
+    progress.pending_downloads = 0;
+    for (e, source) in &models {
+        progress.models.insert(e);
+        if matches!(source, AssetSource::Remote(_)) {
+            progress.pending_downloads += 1;
+        }
+    }
+    for (e, _, _) in loading_drawings.0.values() {
+        progress.drawings.insert(*e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::asset::HandleId;
+    use rmf_site_format::{PixelsPerMeter, Pose};
+
+    fn start_drawing(app: &mut App) -> Handle<Image> {
+        let e = app.world.spawn_empty().id();
+        let handle = Handle::weak(HandleId::random::<Image>());
+        app.world.resource_mut::<LoadingDrawings>().0.insert(
+            handle.clone(),
+            (e, Pose::default(), PixelsPerMeter::default()),
+        );
+        handle
+    }
+
+    fn finish_drawing(app: &mut App, handle: &Handle<Image>) {
+        app.world.resource_mut::<LoadingDrawings>().0.remove(handle);
+    }
+
+    #[test]
+    fn fraction_counts_finished_loads() {
+        let mut progress = LoadProgress::default();
+        assert_eq!(progress.fraction(), 1.0);
+
+        progress.models = (0..3).map(Entity::from_raw).collect();
+        progress.drawings = [Entity::from_raw(3)].into_iter().collect();
+        progress.pending_models = 2;
+        progress.pending_drawings = 1;
+        assert!(progress.is_loading());
+        assert_eq!(progress.total(), 4);
+        assert!((progress.fraction() - 0.25).abs() < 1e-6);
+
+        // More pending loads than are remembered never gives a negative
+        // fraction
+        progress.pending_models = 10;
+        assert_eq!(progress.fraction(), 0.0);
+    }
+
+    #[test]
+    fn progress_is_kept_for_the_whole_batch_and_then_reset() {
+        let mut app = App::new();
+        app.init_resource::<LoadProgress>()
+            .init_resource::<LoadingDrawings>()
+            .add_system(track_load_progress);
+
+        let first = start_drawing(&mut app);
+        let second = start_drawing(&mut app);
+        app.update();
+        let progress = app.world.resource::<LoadProgress>();
+        assert_eq!(progress.total(), 2);
+        assert_eq!(progress.pending(), 2);
+        assert_eq!(progress.fraction(), 0.0);
+
+        // A finished drawing is still counted while the batch is loading
+        finish_drawing(&mut app, &first);
+        app.update();
+        let progress = app.world.resource::<LoadProgress>();
+        assert_eq!(progress.total(), 2);
+        assert!((progress.fraction() - 0.5).abs() < 1e-6);
+
+        // Loads that begin during the batch join it
+        let third = start_drawing(&mut app);
+        app.update();
+        let progress = app.world.resource::<LoadProgress>();
+        assert_eq!(progress.total(), 3);
+        assert_eq!(progress.pending(), 2);
+
+        finish_drawing(&mut app, &second);
+        finish_drawing(&mut app, &third);
+        app.update();
+        let progress = app.world.resource::<LoadProgress>();
+        assert!(!progress.is_loading());
+        assert_eq!(progress.total(), 0);
+        assert_eq!(progress.fraction(), 1.0);
+    }
+}
@@ -0,0 +1,491 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::interaction::CameraControls;
+use bevy::{
+    asset::HandleId,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+    utils::{HashMap, HashSet},
+};
+use bevy_mod_outline::OutlineMeshExt;
+use rmf_site_format::{LodPolicy, ModelMarker};
+
+/// How finely the first generated level of detail divides a mesh. Each
+/// further level uses half as many divisions.
+pub const LOD_BASE_DIVISIONS: u32 = 32;
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct LodSettings {
+    pub enabled: bool,
+    /// Models that have more triangles than this will get generated levels
+    /// of detail, unless their policy says otherwise
+    pub triangle_budget: usize,
+    /// The camera distance at which each level of detail after the first
+    /// begins. These should be in increasing order.
+    pub distances: Vec<f32>,
+}
+
+impl Default for LodSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            triangle_budget: 50_000,
+            distances: vec![25.0, 60.0],
+        }
+    }
+}
+
+impl LodSettings {
+    /// Get the level of detail that should be shown at a distance from the
+    /// camera, where 0 is full detail
+    pub fn level_at(&self, distance: f32) -> usize {
+        self.distances
+            .iter()
+            .take_while(|d| distance >= **d)
+            .count()
+    }
+
+    /// Decide whether levels of detail should be generated for a model
+    pub fn generates(&self, policy: LodPolicy, triangles: usize, authored_levels: usize) -> bool {
+        self.enabled
+            && authored_levels == 0
+            && match policy {
+                LodPolicy::Automatic => triangles > self.triangle_budget,
+                LodPolicy::Always => true,
+                LodPolicy::Never => false,
+            }
+    }
+
+    /// Get which of the levels of detail that an asset provides should be
+    /// shown at a distance from the camera
+    pub fn authored_level_at(
+        &self,
+        policy: LodPolicy,
+        distance: f32,
+        authored_levels: usize,
+    ) -> usize {
+        if self.enabled && policy != LodPolicy::Never {
+            self.level_at(distance).min(authored_levels)
+        } else {
+            0
+        }
+    }
+}
+
+/// Generated levels of detail, keyed by the mesh they were generated from, so
+/// that every model using the same asset shares them
+#[derive(Resource, Default)]
+pub struct GeneratedLodMeshes(pub HashMap<HandleId, Vec<Handle<Mesh>>>);
+
+/// The levels of detail that were generated for a mesh of a model
+#[derive(Component, Debug, Clone)]
+pub struct GeneratedLods {
+    pub full: Handle<Mesh>,
+    /// One mesh for each of the distances in [`LodSettings`]
+    pub levels: Vec<Handle<Mesh>>,
+}
+
+/// A part of a model that the asset provides as one of its levels of detail
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AuthoredLod {
+    pub model: Entity,
+    pub level: usize,
+}
+
+/// How detailed a model is, as found by [`update_model_lods`]
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ModelDetail {
+    pub triangles: usize,
+    /// The highest level of detail that the asset provides, or 0 if it does
+    /// not provide any
+    pub authored_levels: usize,
+    pub generated: bool,
+}
+
+/// Assets mark their levels of detail by ending the names of their nodes
+/// with _LOD followed by the level
+pub fn authored_lod_level(name: &str) -> Option<usize> {
+    let (_, level) = name.rsplit_once("_LOD")?;
+    level.parse().ok()
+}
+
+pub fn triangle_count(mesh: &Mesh) -> usize {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return 0;
+    }
+    mesh.indices()
+        .map(|indices| indices.len())
+        .unwrap_or_else(|| mesh.count_vertices())
+        / 3
+}
+
+/// Make a simpler version of a mesh by merging all the vertices that fall in
+/// the same cell of a grid. The grid divides the longest side of the mesh's
+/// bounding box into the given number of cells. Returns None if the mesh
+/// cannot be simplified this way, or if doing so would not remove anything.
+pub fn simplify_mesh(mesh: &Mesh, divisions: u32) -> Option<Mesh> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    // Merging the vertices of skinned meshes would break their animations
+    if mesh.attribute(Mesh::ATTRIBUTE_JOINT_INDEX).is_some() {
+        return None;
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+    let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) => Some(normals),
+        _ => None,
+    };
+    let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) => Some(uvs),
+        _ => None,
+    };
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(indices) => indices.iter().collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    let (min, max) = positions.iter().fold(
+        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+        |(min, max), p| (min.min(Vec3::from(*p)), max.max(Vec3::from(*p))),
+    );
+    let cell = (max - min).max_element() / divisions as f32;
+    if !cell.is_finite() || cell <= 0.0 {
+        return None;
+    }
+
+    let mut clusters: HashMap<IVec3, u32> = HashMap::new();
+    let mut cluster_of_vertex = Vec::with_capacity(positions.len());
+    let mut new_positions: Vec<Vec3> = Vec::new();
+    let mut new_normals: Vec<Vec3> = Vec::new();
+    let mut new_uvs: Vec<[f32; 2]> = Vec::new();
+    let mut counts: Vec<f32> = Vec::new();
+    for (i, p) in positions.iter().enumerate() {
+        let p = Vec3::from(*p);
+        let key = ((p - min) / cell).floor().as_ivec3();
+        let cluster = *clusters.entry(key).or_insert_with(|| {
+            new_positions.push(Vec3::ZERO);
+            new_normals.push(Vec3::ZERO);
+            new_uvs.push(uvs.map(|uvs| uvs[i]).unwrap_or_default());
+            counts.push(0.0);
+            (new_positions.len() - 1) as u32
+        });
+        let c = cluster as usize;
+        new_positions[c] += p;
+        if let Some(normals) = normals {
+            new_normals[c] += Vec3::from(normals[i]);
+        }
+        counts[c] += 1.0;
+        cluster_of_vertex.push(cluster);
+    }
+
+    let mut new_indices = Vec::new();
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| cluster_of_vertex[triangle[k]]);
+        // Triangles that fit inside one cell collapse and are removed
+        if a == b || b == c || c == a {
+            continue;
+        }
+        new_indices.extend([a, b, c]);
+    }
+    if new_indices.is_empty() || new_indices.len() >= indices.len() {
+        return None;
+    }
+
+    let new_positions: Vec<[f32; 3]> = new_positions
+        .iter()
+        .zip(&counts)
+        .map(|(p, n)| (*p / *n).to_array())
+        .collect();
+    let mut simplified = Mesh::new(PrimitiveTopology::TriangleList);
+    simplified.insert_attribute(Mesh::ATTRIBUTE_POSITION, new_positions);
+    if normals.is_some() {
+        let new_normals: Vec<[f32; 3]> = new_normals
+            .iter()
+            .map(|n| n.try_normalize().unwrap_or(Vec3::Z).to_array())
+            .collect();
+        simplified.insert_attribute(Mesh::ATTRIBUTE_NORMAL, new_normals);
+    }
+    if uvs.is_some() {
+        simplified.insert_attribute(Mesh::ATTRIBUTE_UV_0, new_uvs);
+    }
+    simplified.set_indices(Some(Indices::U32(new_indices)));
+    Some(simplified)
+}
+
+/// Find how detailed models are and decide which of their meshes should have
+/// levels of detail. This runs whenever new meshes appear in a model, when
+/// the policy of a model changes, or when the settings change.
+pub fn update_model_lods(
+    mut commands: Commands,
+    settings: Res<LodSettings>,
+    mut cache: ResMut<GeneratedLodMeshes>,
+    changed_policies: Query<Entity, (Changed<LodPolicy>, With<ModelMarker>)>,
+    models: Query<(Entity, &LodPolicy), With<ModelMarker>>,
+    mut mesh_handles: ParamSet<(
+        Query<Entity, Added<Handle<Mesh>>>,
+        Query<(&mut Handle<Mesh>, Option<&GeneratedLods>)>,
+    )>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    names: Query<&Name>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+) {
+    // The meshes of SDF models are models of their own, so always use the
+    // outermost model
+    let outermost_model = |e: Entity| -> Option<Entity> {
+        std::iter::once(e)
+            .chain(AncestorIter::new(&parents, e))
+            .filter(|p| models.contains(*p))
+            .last()
+    };
+
+    let mut dirty = HashSet::new();
+    if settings.is_changed() {
+        cache.0.clear();
+        dirty.extend(models.iter().filter_map(|(e, _)| outermost_model(e)));
+    }
+    dirty.extend(changed_policies.iter().filter_map(outermost_model));
+    dirty.extend(mesh_handles.p0().iter().filter_map(outermost_model));
+
+    let mut mesh_handles = mesh_handles.p1();
+    for model in dirty {
+        let Ok((_, policy)) = models.get(model) else {
+            continue;
+        };
+        let descendants: Vec<Entity> = DescendantIter::new(&children, model).collect();
+
+        let mut authored_levels = 0;
+        for e in &descendants {
+            let Some(level) = names
+                .get(*e)
+                .ok()
+                .and_then(|n| authored_lod_level(n.as_str()))
+            else {
+                continue;
+            };
+            authored_levels = authored_levels.max(level);
+            commands.entity(*e).insert(AuthoredLod { model, level });
+        }
+
+        let triangles: usize = descendants
+            .iter()
+            .filter_map(|e| mesh_handles.get(*e).ok())
+            .filter_map(|(handle, generated)| {
+                meshes.get(generated.map(|g| &g.full).unwrap_or(handle))
+            })
+            .map(triangle_count)
+            .sum();
+
+        let generate = settings.generates(*policy, triangles, authored_levels);
+
+        for e in &descendants {
+            let Ok((mut handle, generated)) = mesh_handles.get_mut(*e) else {
+                continue;
+            };
+            let full = generated
+                .map(|g| g.full.clone())
+                .unwrap_or_else(|| handle.clone());
+            if !generate {
+                if generated.is_some() {
+                    *handle = full;
+                    commands.entity(*e).remove::<GeneratedLods>();
+                }
+                continue;
+            }
+
+            let levels = match cache.0.get(&full.id()) {
+                Some(levels) => levels.clone(),
+                None => {
+                    let simplified: Vec<Option<Mesh>> = match meshes.get(&full) {
+                        Some(mesh) => (0..settings.distances.len())
+                            .map(|level| {
+                                let divisions = (LOD_BASE_DIVISIONS >> level).max(2);
+                                let mut simplified = simplify_mesh(mesh, divisions)?;
+                                simplified.generate_outline_normals().ok()?;
+                                Some(simplified)
+                            })
+                            .collect(),
+                        None => continue,
+                    };
+                    // Levels that could not be simplified any further use
+                    // the level before them
+                    let mut levels = Vec::new();
+                    let mut previous = full.clone();
+                    for mesh in simplified {
+                        if let Some(mesh) = mesh {
+                            previous = meshes.add(mesh);
+                        }
+                        levels.push(previous.clone());
+                    }
+                    cache.0.insert(full.id(), levels.clone());
+                    levels
+                }
+            };
+            commands.entity(*e).insert(GeneratedLods { full, levels });
+        }
+
+        commands.entity(model).insert(ModelDetail {
+            triangles,
+            authored_levels,
+            generated: generate,
+        });
+    }
+}
+
+/// Switch each model to the level of detail that matches its distance from
+/// the camera
+pub fn update_lod_levels(
+    settings: Res<LodSettings>,
+    camera_controls: Res<CameraControls>,
+    transforms: Query<&GlobalTransform>,
+    mut generated: Query<(&GeneratedLods, &mut Handle<Mesh>, &GlobalTransform)>,
+    mut authored: Query<(&AuthoredLod, &mut Visibility, &GlobalTransform)>,
+    models: Query<(&LodPolicy, &ModelDetail)>,
+) {
+    let Ok(camera) = transforms.get(camera_controls.active_camera()) else {
+        return;
+    };
+    let camera = camera.translation();
+
+    for (lods, mut handle, tf) in &mut generated {
+        let level = settings.level_at(tf.translation().distance(camera));
+        let lod = match level {
+            0 => &lods.full,
+            level => lods.levels.get(level - 1).unwrap_or(&lods.full),
+        };
+        if *handle != *lod {
+            *handle = lod.clone();
+        }
+    }
+
+    for (lod, mut visibility, tf) in &mut authored {
+        let Ok((policy, detail)) = models.get(lod.model) else {
+            continue;
+        };
+        let level = settings.authored_level_at(
+            *policy,
+            tf.translation().distance(camera),
+            detail.authored_levels,
+        );
+        let is_visible = lod.level == level;
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A flat square made of a grid of n x n quads
+    fn make_grid_mesh(n: u32) -> Mesh {
+        let mut positions = Vec::new();
+        for j in 0..=n {
+            for i in 0..=n {
+                positions.push([i as f32 / n as f32, j as f32 / n as f32, 0.0]);
+            }
+        }
+        let mut indices = Vec::new();
+        for j in 0..n {
+            for i in 0..n {
+                let v = j * (n + 1) + i;
+                indices.extend([v, v + 1, v + n + 2, v, v + n + 2, v + n + 1]);
+            }
+        }
+        let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        mesh
+    }
+
+    #[test]
+    fn level_increases_with_distance() {
+        let settings = LodSettings::default();
+        assert_eq!(settings.level_at(0.0), 0);
+        assert_eq!(settings.level_at(24.9), 0);
+        assert_eq!(settings.level_at(25.0), 1);
+        assert_eq!(settings.level_at(59.9), 1);
+        assert_eq!(settings.level_at(60.0), 2);
+        assert_eq!(settings.level_at(1000.0), 2);
+    }
+
+    #[test]
+    fn generation_follows_policy_and_budget() {
+        let mut settings = LodSettings::default();
+        let budget = settings.triangle_budget;
+        assert!(!settings.generates(LodPolicy::Automatic, budget, 0));
+        assert!(settings.generates(LodPolicy::Automatic, budget + 1, 0));
+        assert!(settings.generates(LodPolicy::Always, 10, 0));
+        assert!(!settings.generates(LodPolicy::Never, budget + 1, 0));
+
+        // Levels provided by the asset are used instead of generated ones
+        assert!(!settings.generates(LodPolicy::Always, budget + 1, 2));
+
+        settings.enabled = false;
+        assert!(!settings.generates(LodPolicy::Always, budget + 1, 0));
+    }
+
+    #[test]
+    fn authored_level_is_limited_by_asset() {
+        let mut settings = LodSettings::default();
+        assert_eq!(settings.authored_level_at(LodPolicy::Automatic, 10.0, 1), 0);
+        assert_eq!(settings.authored_level_at(LodPolicy::Automatic, 30.0, 1), 1);
+        assert_eq!(
+            settings.authored_level_at(LodPolicy::Automatic, 100.0, 1),
+            1
+        );
+        assert_eq!(settings.authored_level_at(LodPolicy::Always, 100.0, 3), 2);
+        assert_eq!(settings.authored_level_at(LodPolicy::Never, 100.0, 3), 0);
+
+        settings.enabled = false;
+        assert_eq!(
+            settings.authored_level_at(LodPolicy::Automatic, 100.0, 3),
+            0
+        );
+    }
+
+    #[test]
+    fn authored_levels_are_read_from_names() {
+        assert_eq!(authored_lod_level("shelf_LOD0"), Some(0));
+        assert_eq!(authored_lod_level("shelf_LOD2"), Some(2));
+        assert_eq!(authored_lod_level("shelf"), None);
+        assert_eq!(authored_lod_level("shelf_LODhigh"), None);
+    }
+
+    #[test]
+    fn simplified_mesh_has_fewer_triangles() {
+        let mesh = make_grid_mesh(16);
+        assert_eq!(triangle_count(&mesh), 2 * 16 * 16);
+
+        let simplified = simplify_mesh(&mesh, 4).unwrap();
+        let triangles = triangle_count(&simplified);
+        assert!(triangles > 0);
+        assert!(triangles < triangle_count(&mesh));
+
+        // A mesh that is already coarser than the grid cannot be simplified
+        assert!(simplify_mesh(&make_grid_mesh(1), 4).is_none());
+    }
+}
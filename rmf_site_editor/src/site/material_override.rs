@@ -0,0 +1,161 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::site::{MissingAssetPlaceholder, ModelCollisionGeometry};
+use bevy::{prelude::*, utils::HashSet};
+use rmf_site_format::{
+    MaterialOverride, ModelMarker, PrimitiveShapeMarker, WorkcellCollisionMarker,
+};
+
+/// The material that a mesh of a model had before a [`MaterialOverride`] was
+/// applied to it, so it can be restored when the override is removed
+#[derive(Component, Debug, Clone)]
+pub struct OriginalMaterial(pub Handle<StandardMaterial>);
+
+/// Meshes that keep their own material when their model has an override
+type NotVisualMesh = Or<(
+    With<ModelCollisionGeometry>,
+    With<WorkcellCollisionMarker>,
+    With<MissingAssetPlaceholder>,
+)>;
+
+/// Only the visual meshes of a model take its override. Collision meshes and
+/// the placeholder of a missing asset keep their own materials so they can
+/// still be told apart.
+fn is_visual_mesh(
+    e: Entity,
+    parents: &Query<&Parent>,
+    not_visual: &Query<(), NotVisualMesh>,
+) -> bool {
+    !not_visual.contains(e) && !AncestorIter::new(parents, e).any(|p| not_visual.contains(p))
+}
+
+fn apply_material_override(
+    e: Entity,
+    material_override: &MaterialOverride,
+    handles: &mut Query<(&mut Handle<StandardMaterial>, Option<&OriginalMaterial>)>,
+    materials: &mut Assets<StandardMaterial>,
+    commands: &mut Commands,
+) {
+    let Ok((mut handle, original)) = handles.get_mut(e) else {
+        return;
+    };
+    if material_override.is_empty() {
+        if let Some(original) = original {
+            *handle = original.0.clone();
+            commands.entity(e).remove::<OriginalMaterial>();
+        }
+        return;
+    }
+
+    let original = match original {
+        Some(original) => original.0.clone(),
+        None => {
+            commands.entity(e).insert(OriginalMaterial(handle.clone()));
+            handle.clone()
+        }
+    };
+    let Some(mut material) = materials.get(&original).cloned() else {
+        return;
+    };
+    if let Some(color) = material_override.base_color {
+        material.base_color = color.into();
+        material.base_color_texture = None;
+        if color[3] < 1.0 {
+            material.alpha_mode = AlphaMode::Blend;
+        }
+    }
+    if let Some(metallic) = material_override.metallic {
+        material.metallic = metallic;
+        material.metallic_roughness_texture = None;
+    }
+    if let Some(roughness) = material_override.perceptual_roughness {
+        material.perceptual_roughness = roughness;
+        material.metallic_roughness_texture = None;
+    }
+    if let Some(emissive) = material_override.emissive {
+        let [r, g, b] = emissive.radiance();
+        material.emissive = Color::rgb_linear(r, g, b);
+        material.emissive_texture = None;
+    }
+    // Each model gets its own copy of the material because the original may
+    // be shared by every model that uses the same asset.
+    *handle = materials.add(material);
+}
+
+pub fn update_model_material_overrides(
+    mut commands: Commands,
+    changed_overrides: Query<(Entity, &MaterialOverride), Changed<MaterialOverride>>,
+    overrides: Query<&MaterialOverride, Or<(With<ModelMarker>, With<PrimitiveShapeMarker>)>>,
+    mut handles: ParamSet<(
+        Query<Entity, Added<Handle<StandardMaterial>>>,
+        Query<(&mut Handle<StandardMaterial>, Option<&OriginalMaterial>)>,
+    )>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+    not_visual: Query<(), NotVisualMesh>,
+) {
+    let new_materials: Vec<Entity> = handles.p0().iter().collect();
+    let mut handles = handles.p1();
+    let mut changed_models = HashSet::new();
+    for (e, material_override) in &changed_overrides {
+        // The meshes of SDF models are models of their own, but they take
+        // the override of the outermost model.
+        if AncestorIter::new(&parents, e).any(|p| overrides.contains(p)) {
+            continue;
+        }
+        changed_models.insert(e);
+        for descendant in DescendantIter::new(&children, e) {
+            if !is_visual_mesh(descendant, &parents, &not_visual) {
+                continue;
+            }
+            apply_material_override(
+                descendant,
+                material_override,
+                &mut handles,
+                &mut materials,
+                &mut commands,
+            );
+        }
+    }
+
+    // Scenes are spawned some time after their model, so their meshes need
+    // to receive the override as they appear.
+    for e in new_materials {
+        let Some(model) = AncestorIter::new(&parents, e)
+            .filter(|p| overrides.contains(*p))
+            .last()
+        else {
+            continue;
+        };
+        if changed_models.contains(&model) || !is_visual_mesh(e, &parents, &not_visual) {
+            continue;
+        }
+        let material_override = overrides.get(model).unwrap();
+        if material_override.is_empty() {
+            continue;
+        }
+        apply_material_override(
+            e,
+            material_override,
+            &mut handles,
+            &mut materials,
+            &mut commands,
+        );
+    }
+}
@@ -55,6 +55,14 @@ pub fn add_measurement_visuals(
     }
 }
 
+/// The distance between the anchors of a measurement as they currently are
+/// in the site
+pub fn measured_length(edge: &Edge<Entity>, anchors: &AnchorParams) -> Option<f32> {
+    let start = anchors.point(edge.start(), Category::Measurement).ok()?;
+    let end = anchors.point(edge.end(), Category::Measurement).ok()?;
+    Some(start.distance(end))
+}
+
 fn update_measurement_visual(
     entity: Entity,
     edge: &Edge<Entity>,
@@ -0,0 +1,76 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::console::report_warning;
+use crate::site::LoadingDrawings;
+use bevy::{asset::LoadState, prelude::*};
+use rmf_site_format::AssetSource;
+
+/// Marks an element whose [`AssetSource`] could not be loaded. The marker is
+/// removed when the source of the element is changed.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MissingAsset;
+
+/// Get the path or name that an asset source refers to, without the prefix
+/// that identifies the kind of source.
+pub fn asset_source_path(source: &AssetSource) -> &str {
+    match source {
+        AssetSource::Local(path)
+        | AssetSource::Remote(path)
+        | AssetSource::Search(path)
+        | AssetSource::Bundled(path)
+        | AssetSource::Package(path) => path,
+    }
+}
+
+/// Make a new asset source of the same kind that refers to a different path.
+pub fn with_asset_source_path(source: &AssetSource, path: String) -> AssetSource {
+    match source {
+        AssetSource::Local(_) => AssetSource::Local(path),
+        AssetSource::Remote(_) => AssetSource::Remote(path),
+        AssetSource::Search(_) => AssetSource::Search(path),
+        AssetSource::Bundled(_) => AssetSource::Bundled(path),
+        AssetSource::Package(_) => AssetSource::Package(path),
+    }
+}
+
+pub fn detect_missing_drawings(
+    mut commands: Commands,
+    mut loading_drawings: ResMut<LoadingDrawings>,
+    asset_server: Res<AssetServer>,
+) {
+    loading_drawings.0.retain(|handle, (e, _, _)| {
+        if asset_server.get_load_state(handle) == LoadState::Failed {
+            report_warning(
+                Some(*e),
+                format!("WARNING: Drawing image for {e:?} could not be loaded"),
+            );
+            commands.entity(*e).insert(MissingAsset);
+            return false;
+        }
+        true
+    });
+}
+
+pub fn clear_missing_asset_on_change(
+    mut commands: Commands,
+    changed: Query<Entity, (Changed<AssetSource>, With<MissingAsset>)>,
+) {
+    for e in &changed {
+        commands.entity(e).remove::<MissingAsset>();
+    }
+}
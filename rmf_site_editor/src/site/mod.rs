@@ -24,9 +24,15 @@ pub use assets::*;
 pub mod change_plugin;
 pub use change_plugin::*;
 
+pub mod collision;
+pub use collision::*;
+
 pub mod deletion;
 pub use deletion::*;
 
+pub mod diagnostics;
+pub use diagnostics::*;
+
 pub mod display_color;
 pub use display_color::*;
 
@@ -36,9 +42,15 @@ pub use door::*;
 pub mod drawing;
 pub use drawing::*;
 
+pub mod environment;
+pub use environment::*;
+
 pub mod floor;
 pub use floor::*;
 
+pub mod illuminance;
+pub use illuminance::*;
+
 pub mod lane;
 pub use lane::*;
 
@@ -51,27 +63,57 @@ pub use lift::*;
 pub mod light;
 pub use light::*;
 
+pub mod lightmap;
+pub use lightmap::*;
+
 pub mod load;
 pub use load::*;
 
+pub mod load_progress;
+pub use load_progress::*;
+
 pub mod location;
 pub use location::*;
 
 pub mod measurement;
 pub use measurement::*;
 
+pub mod missing_asset;
+pub use missing_asset::*;
+
+pub mod lod;
+pub use lod::*;
+
+pub mod material_override;
+pub use material_override::*;
+
 pub mod model;
 pub use model::*;
 
+pub mod model_animation;
+pub use model_animation::*;
+
+pub mod model_tags;
+pub use model_tags::*;
+
 pub mod nav_graph;
 pub use nav_graph::*;
 
+pub mod nav_graph_diff;
+pub use nav_graph_diff::*;
+
 pub mod path;
 pub use path::*;
 
+pub mod path_preview;
+pub use path_preview::*;
+
 pub mod physical_camera;
 pub use physical_camera::*;
 
+pub mod primitive_shape;
+pub use primitive_shape::*;
+
 pub mod pose;
 pub use pose::*;
 
@@ -84,16 +126,35 @@ pub use sdf::*;
 pub mod save;
 pub use save::*;
 
+pub mod save_cache;
+pub use save_cache::*;
+
+pub mod sensor;
+pub use sensor::*;
+
+pub mod shading;
+pub use shading::*;
+
 pub mod site;
 pub use site::*;
 
+pub mod traffic_preview;
+pub use traffic_preview::*;
+
+pub mod undo;
+pub use undo::*;
+
 pub mod util;
 pub use util::*;
 
 pub mod wall;
 pub use wall::*;
 
+pub mod workcell_instance;
+pub use workcell_instance::*;
+
 use crate::recency::{RecencyRank, RecencyRankingPlugin};
+use crate::AppState;
 pub use rmf_site_format::*;
 
 use bevy::{prelude::*, render::view::visibility::VisibilitySystems, transform::TransformSystem};
@@ -136,34 +197,72 @@ impl Plugin for SitePlugin {
             .add_state_to_stage(SiteUpdateStage::AssignOrphans, SiteState::Off)
             .add_state_to_stage(CoreStage::PostUpdate, SiteState::Off)
             .insert_resource(ClearColor(Color::rgb(0., 0., 0.)))
+            .add_plugin(UndoPlugin)
             .insert_resource(FloorVisibility::default())
             .init_resource::<SiteAssets>()
             .init_resource::<LoadingDrawings>()
+            .init_resource::<LoadProgress>()
+            .init_resource::<LevelSaveCache>()
+            .init_resource::<LightmapBake>()
             .init_resource::<CurrentLevel>()
             .init_resource::<PhysicalLightToggle>()
+            .init_resource::<IlluminancePreview>()
+            .init_resource::<IlluminanceMap>()
+            .init_resource::<SiteDiagnostics>()
+            .init_resource::<PathPreview>()
+            .init_resource::<TrafficPreview>()
+            .init_resource::<NavGraphDiff>()
+            .init_resource::<GlobalCollisionDisplay>()
+            .init_resource::<ShadingMode>()
+            .init_resource::<ModelMeshes>()
+            .init_resource::<RecentModels>()
+            .init_resource::<HiddenModelTags>()
+            .init_resource::<LodSettings>()
+            .init_resource::<GeneratedLodMeshes>()
             .add_event::<LoadSite>()
             .add_event::<ImportNavGraphs>()
             .add_event::<ChangeCurrentSite>()
             .add_event::<SaveSite>()
             .add_event::<SaveNavGraphs>()
+            .add_event::<AddSensor>()
+            .add_event::<ExportSupervisorConfig>()
             .add_event::<ToggleLiftDoorAvailability>()
+            .add_event::<GenerateLiftLobbies>()
+            .add_event::<ValidateSite>()
+            .add_event::<PreviewPath>()
+            .add_event::<PreviewTraffic>()
+            .add_event::<SplitLane>()
+            .add_event::<MergeCollinearLanes>()
+            .add_event::<CompareNavGraphs>()
+            .add_event::<ResolveGraphChange>()
             .add_event::<ExportLights>()
+            .add_event::<BakeLightmaps>()
             .add_event::<ConsiderAssociatedGraph>()
             .add_event::<ConsiderLocationTag>()
+            .add_event::<ChangeLevel>()
             .add_plugin(ChangePlugin::<AssociatedGraphs<Entity>>::default())
             .add_plugin(RecallPlugin::<RecallAssociatedGraphs<Entity>>::default())
             .add_plugin(ChangePlugin::<Motion>::default())
             .add_plugin(RecallPlugin::<RecallMotion>::default())
             .add_plugin(ChangePlugin::<ReverseLane>::default())
+            .add_plugin(ChangePlugin::<LaneCost>::default())
+            .add_plugin(ChangePlugin::<LaneCurve>::default())
+            .add_plugin(ChangePlugin::<SharedZone>::default())
             .add_plugin(RecallPlugin::<RecallReverseLane>::default())
             .add_plugin(ChangePlugin::<NameInSite>::default())
             .add_plugin(ChangePlugin::<NameInWorkcell>::default())
             .add_plugin(ChangePlugin::<Pose>::default())
             .add_plugin(ChangePlugin::<Scale>::default())
+            .add_plugin(ChangePlugin::<ModelTags>::default())
+            .add_plugin(ChangePlugin::<MaterialOverride>::default())
+            .add_plugin(ChangePlugin::<LodPolicy>::default())
             .add_plugin(ChangePlugin::<MeshConstraint<Entity>>::default())
+            .add_plugin(ChangePlugin::<JointProperties>::default())
+            .add_plugin(ChangePlugin::<Inertial>::default())
             .add_plugin(ChangePlugin::<Label>::default())
             .add_plugin(RecallPlugin::<RecallLabel>::default())
             .add_plugin(ChangePlugin::<DoorType>::default())
+            .add_plugin(ChangePlugin::<DoorOccupancy>::default())
             .add_plugin(RecallPlugin::<RecallDoorType>::default())
             .add_plugin(ChangePlugin::<LevelProperties>::default())
             .add_plugin(ChangePlugin::<LiftCabin<Entity>>::default())
@@ -174,10 +273,17 @@ impl Plugin for SitePlugin {
             .add_plugin(RecallPlugin::<RecallMeshPrimitive>::default())
             .add_plugin(ChangePlugin::<PixelsPerMeter>::default())
             .add_plugin(ChangePlugin::<PhysicalCameraProperties>::default())
+            .add_plugin(ChangePlugin::<SensorProperties>::default())
             .add_plugin(ChangePlugin::<LightKind>::default())
+            .add_plugin(ChangePlugin::<LightGroup>::default())
+            .add_plugin(ChangePlugin::<Environment>::default())
             .add_plugin(RecallPlugin::<RecallLightKind>::default())
             .add_plugin(ChangePlugin::<DisplayColor>::default())
+            .add_plugin(ChangePlugin::<ManualColor>::default())
+            .add_plugin(ChangePlugin::<NavGraphPalette>::default())
+            .add_plugin(ChangePlugin::<LaneDefaults>::default())
             .add_plugin(ChangePlugin::<LocationTags>::default())
+            .add_plugin(ChangePlugin::<LocationParameters>::default())
             .add_plugin(RecallPlugin::<RecallLocationTags>::default())
             .add_plugin(ChangePlugin::<Visibility>::default())
             .add_plugin(ChangePlugin::<FloorVisibility>::default())
@@ -186,20 +292,46 @@ impl Plugin for SitePlugin {
             .add_plugin(RecencyRankingPlugin::<DrawingMarker>::default())
             .add_plugin(DeletionPlugin)
             .add_system(load_site)
+            .add_system(track_load_progress)
             .add_system(import_nav_graph)
+            .add_system(handle_add_sensors)
+            .add_system_set(
+                SystemSet::on_update(AppState::SiteEditor)
+                    .with_system(update_environment)
+                    .with_system(follow_camera_with_sky.after(update_environment)),
+            )
+            .add_system_set(SystemSet::on_exit(AppState::SiteEditor).with_system(clear_environment))
+            .add_system(update_shading_mode)
             .add_system_set_to_stage(
                 CoreStage::PreUpdate,
                 SystemSet::on_update(SiteState::Display)
                     .after(SiteUpdateLabel::ProcessChanges)
                     .with_system(update_lift_cabin)
+                    .with_system(update_lift_shafts)
                     .with_system(update_lift_edge)
                     .with_system(update_model_tentative_formats)
+                    .with_system(
+                        spawn_missing_model_placeholders.after(update_model_tentative_formats),
+                    )
                     .with_system(update_material_for_display_color),
             )
             .add_system_set(
                 SystemSet::on_update(SiteState::Display)
                     .with_system(save_site)
+                    .with_system(handle_change_level)
                     .with_system(save_nav_graphs)
+                    .with_system(export_supervisor_config)
+                    .with_system(generate_lift_lobbies)
+                    .with_system(check_lifts_for_issues)
+                    .with_system(check_nav_graphs_for_issues)
+                    .with_system(apply_nav_graph_palette)
+                    .with_system(update_path_preview)
+                    .with_system(update_traffic_preview)
+                    .with_system(move_virtual_robots.after(update_traffic_preview))
+                    .with_system(split_lanes)
+                    .with_system(merge_collinear_lanes)
+                    .with_system(compare_nav_graphs)
+                    .with_system(resolve_nav_graph_changes.after(compare_nav_graphs))
                     .with_system(change_site.before(load_site)),
             )
             .add_system_set_to_stage(
@@ -214,6 +346,7 @@ impl Plugin for SitePlugin {
                     .with_system(assign_orphan_elements_to_level::<LightKind>)
                     .with_system(assign_orphan_elements_to_level::<ModelMarker>)
                     .with_system(assign_orphan_elements_to_level::<PhysicalCameraProperties>)
+                    .with_system(assign_orphan_elements_to_level::<PrimitiveShapeMarker>)
                     .with_system(assign_orphan_elements_to_level::<WallMarker>)
                     .with_system(add_tags_to_lift)
                     .with_system(add_material_for_display_colors)
@@ -233,6 +366,8 @@ impl Plugin for SitePlugin {
                     .with_system(update_floor_for_moved_anchors)
                     .with_system(update_floor_visibility)
                     .with_system(add_lane_visuals)
+                    .with_system(add_lane_arrows)
+                    .with_system(update_lane_arrows)
                     .with_system(add_location_visuals)
                     .with_system(update_level_visibility)
                     .with_system(update_changed_lane)
@@ -241,6 +376,14 @@ impl Plugin for SitePlugin {
                     .with_system(
                         update_visibility_for_lanes.after(remove_association_for_deleted_graphs),
                     )
+                    .with_system(
+                        update_lane_curves
+                            .after(update_changed_lane)
+                            .after(update_lane_for_moved_anchor)
+                            .after(update_visibility_for_lanes),
+                    )
+                    .with_system(add_shared_zone_overlays)
+                    .with_system(update_shared_zone_overlays.after(update_lane_curves))
                     .with_system(
                         update_visibility_for_locations
                             .after(remove_association_for_deleted_graphs),
@@ -251,27 +394,45 @@ impl Plugin for SitePlugin {
                     .with_system(handle_consider_location_tag)
                     .with_system(update_lift_for_moved_anchors)
                     .with_system(update_lift_door_availability)
+                    .with_system(update_path_preview_visibility)
+                    .with_system(update_virtual_robot_visibility)
                     .with_system(update_physical_lights)
-                    .with_system(toggle_physical_lights)
+                    .with_system(update_illuminance_map)
+                    .with_system(update_physical_light_visibility)
                     .with_system(add_measurement_visuals)
                     .with_system(update_changed_measurement)
                     .with_system(update_measurement_for_moved_anchors)
                     .with_system(update_model_scenes)
                     .with_system(handle_new_sdf_roots)
+                    .with_system(handle_new_workcell_roots)
                     .with_system(update_model_scales)
+                    .with_system(update_model_animations)
+                    .with_system(update_model_tag_visibility)
+                    .with_system(update_model_material_overrides)
+                    .with_system(update_model_lods)
+                    .with_system(update_lod_levels.after(update_model_lods))
                     .with_system(make_models_selectable)
+                    .with_system(forget_removed_model_meshes)
                     .with_system(handle_new_mesh_primitives)
+                    .with_system(update_changed_mesh_primitives)
+                    .with_system(update_collision_display)
                     .with_system(add_drawing_visuals)
                     .with_system(handle_loaded_drawing)
+                    .with_system(detect_missing_drawings)
+                    .with_system(clear_missing_asset_on_change)
                     .with_system(update_drawing_visuals)
                     .with_system(update_drawing_rank)
                     .with_system(update_drawing_pixels_per_meter)
                     .with_system(add_physical_camera_visuals)
+                    .with_system(add_sensor_visuals)
+                    .with_system(add_primitive_shape_visuals)
                     .with_system(add_wall_visual)
                     .with_system(update_wall_edge)
                     .with_system(update_wall_for_moved_anchors)
                     .with_system(update_transforms_for_changed_poses)
-                    .with_system(export_lights),
+                    .with_system(export_lights)
+                    .with_system(bake_lightmaps)
+                    .with_system(finish_lightmap_bake),
             );
     }
 }
@@ -16,13 +16,21 @@
 */
 
 use crate::{
+    console::{notify_warning, report_warning},
     interaction::{DragPlaneBundle, Selectable},
-    site::{Category, PreventDeletion, SiteAssets},
-    SdfRoot,
+    site::{Category, MissingAsset, ModelAnimation, PreventDeletion, SiteAssets},
+    SdfRoot, WorkcellRoot,
+};
+use bevy::{
+    asset::{HandleId, LoadState},
+    gltf::Gltf,
+    prelude::*,
+    utils::HashSet,
 };
-use bevy::{asset::LoadState, gltf::Gltf, prelude::*};
 use bevy_mod_outline::OutlineMeshExt;
-use rmf_site_format::{AssetSource, ModelMarker, Pending, Pose, Scale, UrdfRoot};
+use rmf_site_format::{
+    AssetSource, MeshPrimitive, ModelMarker, Pending, Pose, Scale, SensorProperties, UrdfRoot,
+};
 use smallvec::SmallVec;
 
 #[derive(Component, Debug, Clone)]
@@ -77,6 +85,67 @@ pub struct PendingSpawning(HandleUntyped);
 #[derive(Component, Debug, Clone, Copy)]
 pub struct ModelSceneRoot;
 
+/// Keeps track of the meshes that are shared by every instance of a model.
+/// Models that are loaded from the same asset source already share their
+/// mesh handles, so each mesh only needs to be prepared once no matter how
+/// many instances of the model are in the scene.
+#[derive(Resource, Default)]
+pub struct ModelMeshes {
+    /// Meshes whose outline normals have already been generated
+    outlined: HashSet<HandleId>,
+    /// Meshes that have been generated for primitive shapes
+    primitives: Vec<(MeshPrimitive, Handle<Mesh>)>,
+}
+
+impl ModelMeshes {
+    /// Get the mesh for a primitive shape, generating it if no model has
+    /// used the same shape yet
+    pub fn primitive(
+        &mut self,
+        primitive: &MeshPrimitive,
+        make_mesh: impl FnOnce() -> Mesh,
+        meshes: &mut Assets<Mesh>,
+    ) -> Handle<Mesh> {
+        if let Some((_, handle)) = self.primitives.iter().find(|(p, _)| p == primitive) {
+            return handle.clone();
+        }
+        let handle = meshes.add(make_mesh());
+        self.primitives.push((primitive.clone(), handle.clone()));
+        handle
+    }
+}
+
+/// How many models are remembered by [`RecentModels`]
+pub const RECENT_MODELS_LIMIT: usize = 8;
+
+/// The models that were most recently placed by the user, starting with the
+/// most recent one
+#[derive(Resource, Default, Debug, Clone)]
+pub struct RecentModels {
+    pub models: Vec<(AssetSource, Scale)>,
+}
+
+impl RecentModels {
+    pub fn push(&mut self, source: AssetSource, scale: Scale) {
+        self.models.retain(|(s, _)| *s != source);
+        self.models.insert(0, (source, scale));
+        self.models.truncate(RECENT_MODELS_LIMIT);
+    }
+}
+
+/// Forget about meshes that have been unloaded so they are prepared again if
+/// they get reloaded.
+pub fn forget_removed_model_meshes(
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mut model_meshes: ResMut<ModelMeshes>,
+) {
+    for event in mesh_events.iter() {
+        if let AssetEvent::Removed { handle } = event {
+            model_meshes.outlined.remove(&handle.id());
+        }
+    }
+}
+
 pub fn update_model_scenes(
     mut commands: Commands,
     changed_models: Query<
@@ -103,6 +172,7 @@ pub fn update_model_scenes(
     gltfs: Res<Assets<Gltf>>,
     urdfs: Res<Assets<UrdfRoot>>,
     sdfs: Res<Assets<SdfRoot>>,
+    workcells: Res<Assets<WorkcellRoot>>,
 ) {
     fn spawn_model(
         e: Entity,
@@ -157,6 +227,9 @@ pub fn update_model_scenes(
     for (e, tentative_format, h, scale) in loading_models.iter() {
         if asset_server.get_load_state(&h.0) == LoadState::Loaded {
             let model_id = if let Some(gltf) = gltfs.get(&h.typed_weak::<Gltf>()) {
+                if let Some(animation) = ModelAnimation::from_gltf(gltf) {
+                    commands.entity(e).insert(animation);
+                }
                 Some(commands.entity(e).add_children(|parent| {
                     // Get default scene if present, otherwise index 0
                     let scene = gltf
@@ -210,6 +283,13 @@ pub fn update_model_scenes(
                         .insert(sdf.clone())
                         .id()
                 }))
+            } else if let Some(workcell) = workcells.get(&h.typed_weak::<WorkcellRoot>()) {
+                Some(commands.entity(e).add_children(|parent| {
+                    parent
+                        .spawn(SpatialBundle::VISIBLE_IDENTITY)
+                        .insert(workcell.clone())
+                        .id()
+                }))
             } else {
                 None
             };
@@ -232,7 +312,10 @@ pub fn update_model_scenes(
                 if let Some(scene_entity) = current_scene.entity {
                     commands.entity(scene_entity).despawn_recursive();
                     commands.entity(e).remove_children(&[scene_entity]);
-                    commands.entity(e).remove::<ModelSceneRoot>();
+                    commands
+                        .entity(e)
+                        .remove::<ModelSceneRoot>()
+                        .remove::<ModelAnimation>();
                 }
                 // Updated model
                 spawn_model(
@@ -284,11 +367,14 @@ pub fn update_model_tentative_formats(
                     *tentative_format = fmt;
                     commands.entity(e).remove::<PendingSpawning>();
                 } else {
-                    println!(
-                        "WARNING: Model with source {} not found",
-                        String::from(source)
+                    notify_warning(
+                        Some(e),
+                        format!("Failed to load model {}", String::from(source)),
                     );
-                    commands.entity(e).remove::<TentativeModelFormat>();
+                    commands
+                        .entity(e)
+                        .remove::<TentativeModelFormat>()
+                        .insert(MissingAsset);
                 }
             }
             _ => {}
@@ -309,14 +395,60 @@ pub fn update_model_scales(
     }
 }
 
+/// Marks the box that is shown in place of a model whose asset is missing
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MissingAssetPlaceholder;
+
+/// Show a box in place of models whose asset could not be loaded, so they
+/// can still be seen, selected, and moved around while the asset is missing.
+/// The box is replaced by the real model once its source is changed to one
+/// that can be loaded.
+pub fn spawn_missing_model_placeholders(
+    mut commands: Commands,
+    missing_models: Query<(Entity, &Scale), (Added<MissingAsset>, With<ModelMarker>)>,
+    mut scenes: Query<&mut ModelScene>,
+    parents: Query<&Parent>,
+    models: Query<(), With<ModelMarker>>,
+    site_assets: Res<SiteAssets>,
+) {
+    for (e, scale) in &missing_models {
+        // The meshes of SDF models are models of their own, but the
+        // outermost model is the one that gets the placeholder.
+        if AncestorIter::new(&parents, e).any(|p| models.contains(p)) {
+            continue;
+        }
+        let Ok(mut scene) = scenes.get_mut(e) else {
+            continue;
+        };
+        let placeholder = commands
+            .spawn(PbrBundle {
+                mesh: site_assets.box_mesh.clone(),
+                material: site_assets.missing_asset_material.clone(),
+                transform: Transform::from_translation(0.5 * Vec3::Z).with_scale(**scale),
+                ..default()
+            })
+            .insert(Selectable::new(e))
+            .insert(DragPlaneBundle::new(e, Vec3::Z))
+            .insert(MissingAssetPlaceholder)
+            .id();
+        commands
+            .entity(e)
+            .add_child(placeholder)
+            .remove::<PendingSpawning>();
+        scene.entity = Some(placeholder);
+    }
+}
+
 pub fn make_models_selectable(
     mut commands: Commands,
     new_scene_roots: Query<Entity, (Added<ModelSceneRoot>, Without<Pending>)>,
     parents: Query<&Parent>,
     scene_roots: Query<&Selectable, With<ModelMarker>>,
     all_children: Query<&Children>,
+    sensors: Query<(), With<SensorProperties>>,
     mesh_handles: Query<&Handle<Mesh>>,
     mut mesh_assets: ResMut<Assets<Mesh>>,
+    mut model_meshes: ResMut<ModelMeshes>,
 ) {
     // We use adding of scene root as a marker of models being spawned, the component is added when
     // the scene fininshed loading and is spawned
@@ -341,18 +473,26 @@ pub fn make_models_selectable(
                 .insert(DragPlaneBundle::new(selectable.element, Vec3::Z));
 
             if let Ok(mesh_handle) = mesh_handles.get(e) {
-                if let Some(mesh) = mesh_assets.get_mut(mesh_handle) {
-                    if mesh.generate_outline_normals().is_err() {
-                        println!(
-                            "WARNING: Unable to generate outline normals for \
-                            a model mesh"
-                        );
+                // Only modify a mesh the first time it appears, otherwise every
+                // instance of a model would cause its meshes to be uploaded
+                // to the GPU again.
+                if !model_meshes.outlined.contains(&mesh_handle.id()) {
+                    if let Some(mesh) = mesh_assets.get_mut(mesh_handle) {
+                        model_meshes.outlined.insert(mesh_handle.id());
+                        if mesh.generate_outline_normals().is_err() {
+                            report_warning(
+                                None,
+                                "WARNING: Unable to generate outline normals for \
+                                a model mesh",
+                            );
+                        }
                     }
                 }
             }
 
             if let Ok(children) = all_children.get(e) {
-                for child in children {
+                // Sensors attached to the model are selected on their own
+                for child in children.iter().filter(|c| !sensors.contains(**c)) {
                     queue.push(*child);
                 }
             }
@@ -0,0 +1,129 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use bevy::{gltf::Gltf, prelude::*};
+
+/// The animations that came with the asset of a model, and how they should be
+/// played in the editor. This is only used for previewing and is not saved.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct ModelAnimation {
+    /// The name and clip of each animation in the asset
+    pub clips: Vec<(String, Handle<AnimationClip>)>,
+    /// Index of the clip that is chosen
+    pub clip: usize,
+    pub playing: bool,
+    pub looping: bool,
+}
+
+impl ModelAnimation {
+    pub fn new(clips: Vec<(String, Handle<AnimationClip>)>) -> Self {
+        Self {
+            clips,
+            clip: 0,
+            playing: false,
+            looping: true,
+        }
+    }
+
+    /// Get the animations of a glTF asset, if it has any
+    pub fn from_gltf(gltf: &Gltf) -> Option<Self> {
+        if gltf.animations.is_empty() {
+            return None;
+        }
+        let clips = gltf
+            .animations
+            .iter()
+            .enumerate()
+            .map(|(i, clip)| {
+                let name = gltf
+                    .named_animations
+                    .iter()
+                    .find(|(_, named)| *named == clip)
+                    .map(|(name, _)| name.clone())
+                    .unwrap_or_else(|| format!("Animation {i}"));
+                (name, clip.clone())
+            })
+            .collect();
+        Some(Self::new(clips))
+    }
+
+    fn apply(
+        &self,
+        e: Entity,
+        player: &mut AnimationPlayer,
+        started: Option<&StartedAnimationClip>,
+        commands: &mut Commands,
+    ) {
+        let Some((_, clip)) = self.clips.get(self.clip) else {
+            return;
+        };
+        if self.playing {
+            if started.filter(|started| started.0 == *clip).is_some() {
+                // Pick up where the clip was paused instead of restarting it
+                player.resume();
+            } else {
+                player.play(clip.clone());
+                commands
+                    .entity(e)
+                    .insert(StartedAnimationClip(clip.clone()));
+            }
+            if self.looping {
+                player.repeat();
+            } else {
+                player.stop_repeating();
+            }
+        } else {
+            player.pause();
+        }
+    }
+}
+
+/// The clip that an animation player was last started with
+#[derive(Component, Debug, Clone)]
+pub struct StartedAnimationClip(Handle<AnimationClip>);
+
+/// Drive the animation players inside of model scenes according to the
+/// [`ModelAnimation`] of their model.
+pub fn update_model_animations(
+    mut commands: Commands,
+    changed_animations: Query<(Entity, &ModelAnimation), Changed<ModelAnimation>>,
+    new_players: Query<Entity, Added<AnimationPlayer>>,
+    animations: Query<&ModelAnimation>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+    mut players: Query<(&mut AnimationPlayer, Option<&StartedAnimationClip>)>,
+) {
+    for (e, animation) in &changed_animations {
+        for child in children.iter_descendants(e) {
+            if let Ok((mut player, started)) = players.get_mut(child) {
+                animation.apply(child, &mut player, started, &mut commands);
+            }
+        }
+    }
+
+    // Players are added when the scene of a model gets spawned, which can
+    // happen after its animations are known.
+    for e in &new_players {
+        let Some(animation) = AncestorIter::new(&parents, e).find_map(|p| animations.get(p).ok())
+        else {
+            continue;
+        };
+        if let Ok((mut player, started)) = players.get_mut(e) {
+            animation.apply(e, &mut player, started, &mut commands);
+        }
+    }
+}
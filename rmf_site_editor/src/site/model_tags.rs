@@ -0,0 +1,56 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::site::ModelScene;
+use bevy::prelude::*;
+use rmf_site_format::{ModelMarker, ModelTags};
+use std::collections::BTreeSet;
+
+/// Models that have any of these tags will be hidden
+#[derive(Resource, Default, Debug, Clone)]
+pub struct HiddenModelTags(pub BTreeSet<String>);
+
+impl HiddenModelTags {
+    pub fn hides(&self, tags: &ModelTags) -> bool {
+        tags.0.iter().any(|tag| self.0.contains(tag))
+    }
+}
+
+pub fn update_model_tag_visibility(
+    hidden: Res<HiddenModelTags>,
+    mut models: Query<(&ModelTags, &mut Visibility), With<ModelMarker>>,
+    changed_models: Query<
+        (),
+        (
+            With<ModelMarker>,
+            Or<(Changed<ModelTags>, Added<ModelScene>)>,
+        ),
+    >,
+) {
+    // The visibility of a model is reset when its scene gets spawned, so new
+    // scenes need to be checked as well.
+    if !hidden.is_changed() && changed_models.is_empty() {
+        return;
+    }
+
+    for (tags, mut visibility) in &mut models {
+        let is_visible = !hidden.hides(tags);
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+    }
+}
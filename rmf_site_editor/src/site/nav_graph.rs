@@ -15,7 +15,7 @@
  *
 */
 
-use crate::site::*;
+use crate::{recency::RecencyRanking, site::*};
 use bevy::{ecs::system::SystemParam, prelude::*};
 
 #[derive(SystemParam)]
@@ -31,6 +31,7 @@ pub struct GraphSelect<'w, 's> {
         ),
         With<NavGraphMarker>,
     >,
+    lane_defaults: Query<'w, 's, &'static LaneDefaults, With<NavGraphMarker>>,
     assets: Res<'w, SiteAssets>,
 }
 
@@ -44,13 +45,40 @@ impl<'w, 's> GraphSelect<'w, 's> {
         &self,
         associated_graphs: &AssociatedGraphs<Entity>,
     ) -> (Handle<StandardMaterial>, f32) {
+        self.display_graph(associated_graphs)
+            .and_then(|e| self.graphs.get(e).ok())
+            .map(|(_, m, _, d)| {
+                (
+                    m.clone(),
+                    d.proportion() * (LANE_LAYER_LIMIT - LANE_LAYER_START) + LANE_LAYER_START,
+                )
+            })
+            .unwrap_or((
+                self.assets.unassigned_lane_material.clone(),
+                LANE_LAYER_LIMIT,
+            ))
+    }
+
+    /// Get the width that a lane should be drawn with, based on the lane
+    /// defaults of the graph that it is being displayed for.
+    pub fn lane_width(&self, associated_graphs: &AssociatedGraphs<Entity>) -> f32 {
+        self.display_graph(associated_graphs)
+            .and_then(|e| self.lane_defaults.get(e).ok())
+            .and_then(|defaults| defaults.lane_width)
+            .unwrap_or(LANE_WIDTH)
+    }
+
+    /// Get the highest ranked visible graph that the associated graphs
+    /// include. This is the graph whose style will be used to display an
+    /// element.
+    pub fn display_graph(&self, associated_graphs: &AssociatedGraphs<Entity>) -> Option<Entity> {
         match associated_graphs {
             AssociatedGraphs::All => self
                 .graphs
                 .iter()
                 .filter(|(_, _, v, _)| v.is_visible)
                 .max_by(|(_, _, _, a), (_, _, _, b)| a.cmp(b))
-                .map(|(_, m, _, d)| (m.clone(), *d)),
+                .map(|(e, _, _, _)| e),
             AssociatedGraphs::Only(set) => set
                 .iter()
                 .filter(|e| {
@@ -67,25 +95,14 @@ impl<'w, 's> GraphSelect<'w, 's> {
                         .3
                         .cmp(self.graphs.get(**b).unwrap().3)
                 })
-                .map(|e| self.graphs.get(*e).map(|(_, m, _, d)| (m.clone(), *d)).ok())
-                .flatten(),
+                .copied(),
             AssociatedGraphs::AllExcept(set) => self
                 .graphs
                 .iter()
                 .filter(|(e, _, v, _)| v.is_visible && !set.contains(e))
                 .max_by(|(_, _, _, a), (_, _, _, b)| a.cmp(b))
-                .map(|(_, m, _, d)| (m.clone(), *d)),
+                .map(|(e, _, _, _)| e),
         }
-        .map(|(m, d)| {
-            (
-                m,
-                d.proportion() * (LANE_LAYER_LIMIT - LANE_LAYER_START) + LANE_LAYER_START,
-            )
-        })
-        .unwrap_or((
-            self.assets.unassigned_lane_material.clone(),
-            LANE_LAYER_LIMIT,
-        ))
     }
 
     pub fn should_display(&self, associated_graphs: &AssociatedGraphs<Entity>) -> bool {
@@ -121,3 +138,31 @@ impl<'w, 's> GraphSelect<'w, 's> {
         }
     }
 }
+
+/// Recolor the graphs of a site when its palette changes. Graphs whose color
+/// was chosen by hand keep their color.
+pub fn apply_nav_graph_palette(
+    sites: Query<(
+        &NavGraphPalette,
+        ChangeTrackers<NavGraphPalette>,
+        &RecencyRanking<NavGraphMarker>,
+    )>,
+    mut graphs: Query<(&mut DisplayColor, &ManualColor), With<NavGraphMarker>>,
+) {
+    for (palette, tracker, ranking) in &sites {
+        // The graphs of a site that was just loaded already have their colors
+        if !tracker.is_changed() || tracker.is_added() {
+            continue;
+        }
+
+        for (index, e) in ranking.iter().enumerate() {
+            let Ok((mut color, manual_color)) = graphs.get_mut(*e) else {
+                continue;
+            };
+            let new_color = palette.color(index);
+            if !manual_color.0 && color.0 != new_color {
+                color.0 = new_color;
+            }
+        }
+    }
+}
@@ -0,0 +1,662 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::console::{notify_error, notify_info};
+use crate::site::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use std::collections::{BTreeMap, HashMap};
+
+/// Compare a graph of a site against the graph with the same ID (or else the
+/// same name) in another site file. Lanes and locations are matched up by
+/// their site IDs, so this works best when both files came from the same
+/// original site.
+pub struct CompareNavGraphs {
+    pub site: Entity,
+    pub graph: Entity,
+    pub other: rmf_site_format::Site,
+}
+
+/// Accept or reject one of the changes in the current [`NavGraphDiff`]. The
+/// change is removed from the diff either way.
+#[derive(Clone, Copy, Debug)]
+pub struct ResolveGraphChange {
+    pub index: usize,
+    pub accept: bool,
+}
+
+#[derive(Clone, Debug)]
+enum GraphChangeKind {
+    AddLane(Lane<u32>),
+    RemoveLane(Entity),
+    ModifyLane(Entity, Lane<u32>),
+    AddLocation(Location<u32>),
+    RemoveLocation(Entity),
+    ModifyLocation(Entity, Location<u32>),
+}
+
+/// A difference between the graph in the current site and the graph in the
+/// other site file.
+#[derive(Clone, Debug)]
+pub struct GraphChange {
+    pub description: String,
+    /// The element of the current site that the change applies to, if it
+    /// already exists.
+    pub entity: Option<Entity>,
+    kind: GraphChangeKind,
+}
+
+#[derive(Resource, Default)]
+pub struct NavGraphDiff {
+    pub site: Option<Entity>,
+    pub graph: Option<Entity>,
+    pub changes: Vec<GraphChange>,
+    other: Option<rmf_site_format::Site>,
+    /// Anchors of the other site that have been brought into the current site
+    /// while accepting changes
+    imported_anchors: HashMap<u32, Entity>,
+}
+
+/// Queries for finding the anchors of a site and bringing anchors over from
+/// the other site.
+#[derive(SystemParam)]
+pub struct DiffAnchorParams<'w, 's> {
+    commands: Commands<'w, 's>,
+    anchors: Query<'w, 's, (Entity, &'static SiteID), With<Anchor>>,
+    levels: Query<'w, 's, (Entity, &'static LevelProperties, &'static Parent)>,
+    lifts: Query<
+        'w,
+        's,
+        (
+            &'static NameInSite,
+            &'static ChildCabinAnchorGroup,
+            &'static Parent,
+        ),
+    >,
+    parents: Query<'w, 's, &'static Parent>,
+    transforms: Query<'w, 's, &'static GlobalTransform>,
+}
+
+impl<'w, 's> DiffAnchorParams<'w, 's> {
+    fn is_in_site(&self, mut e: Entity, site: Entity) -> bool {
+        while let Ok(parent) = self.parents.get(e) {
+            if parent.get() == site {
+                return true;
+            }
+            e = parent.get();
+        }
+        false
+    }
+
+    fn anchor_ids(&self, site: Entity) -> HashMap<Entity, u32> {
+        self.anchors
+            .iter()
+            .filter(|(e, _)| self.is_in_site(*e, site))
+            .map(|(e, id)| (e, id.0))
+            .collect()
+    }
+}
+
+/// Find the ID of the graph in the other site that corresponds to a graph of
+/// the current site.
+fn other_graph_id(
+    graph: Entity,
+    graphs: &Query<(&NameInSite, Option<&SiteID>), With<NavGraphMarker>>,
+    other: &rmf_site_format::Site,
+) -> Option<u32> {
+    let (name, site_id) = graphs.get(graph).ok()?;
+    let other_graphs = &other.navigation.guided.graphs;
+    if let Some(id) = site_id.filter(|id| other_graphs.contains_key(&id.0)) {
+        return Some(id.0);
+    }
+
+    other_graphs
+        .iter()
+        .find(|(_, g)| g.name == *name)
+        .map(|(id, _)| *id)
+}
+
+fn include_graph(graphs: &mut AssociatedGraphs<Entity>, graph: Entity) {
+    match graphs {
+        AssociatedGraphs::All => {}
+        AssociatedGraphs::Only(set) => {
+            set.insert(graph);
+        }
+        AssociatedGraphs::AllExcept(set) => {
+            set.remove(&graph);
+        }
+    }
+}
+
+fn exclude_graph(graphs: &mut AssociatedGraphs<Entity>, graph: Entity) {
+    match graphs {
+        AssociatedGraphs::All => {
+            *graphs = AssociatedGraphs::AllExcept([graph].into());
+        }
+        AssociatedGraphs::Only(set) => {
+            set.remove(&graph);
+        }
+        AssociatedGraphs::AllExcept(set) => {
+            set.insert(graph);
+        }
+    }
+}
+
+pub fn compare_nav_graphs(
+    mut requests: EventReader<CompareNavGraphs>,
+    mut diff: ResMut<NavGraphDiff>,
+    lanes: Query<
+        (
+            Entity,
+            &Edge<Entity>,
+            &Motion,
+            &ReverseLane,
+            &LaneCost,
+            &LaneCurve,
+            &SharedZone,
+            &AssociatedGraphs<Entity>,
+            Option<&SiteID>,
+            &Parent,
+        ),
+        (With<LaneMarker>, Without<Pending>),
+    >,
+    locations: Query<
+        (
+            Entity,
+            &Point<Entity>,
+            &NameInSite,
+            &LocationTags,
+            &LocationParameters,
+            &AssociatedGraphs<Entity>,
+            Option<&SiteID>,
+            &Parent,
+        ),
+        (With<LocationTags>, Without<Pending>),
+    >,
+    graphs: Query<(&NameInSite, Option<&SiteID>), With<NavGraphMarker>>,
+    anchors: DiffAnchorParams,
+) {
+    let Some(request) = requests.iter().last() else {
+        return;
+    };
+
+    *diff = NavGraphDiff::default();
+    let Some(other_graph) = other_graph_id(request.graph, &graphs, &request.other) else {
+        notify_error(None, "The chosen graph does not exist in the other site");
+        return;
+    };
+
+    let anchor_ids = anchors.anchor_ids(request.site);
+    let to_ids = |edge: &Edge<Entity>| -> Option<Edge<u32>> {
+        let start = anchor_ids.get(&edge.start())?;
+        let end = anchor_ids.get(&edge.end())?;
+        Some(Edge::new(*start, *end))
+    };
+
+    let other_nav = &request.other.navigation.guided;
+    let mut changes = Vec::new();
+
+    // Compare lanes
+    let mut current_lanes = BTreeMap::new();
+    for (e, edge, forward, reverse, cost, curve, shared_zone, graphs, site_id, parent) in &lanes {
+        if parent.get() != request.site {
+            continue;
+        }
+
+        if let Some(id) = site_id {
+            current_lanes.insert(id.0, e);
+        }
+
+        let in_other_graph = site_id
+            .and_then(|id| other_nav.lanes.get(&id.0))
+            .filter(|lane| lane.graphs.includes(other_graph));
+        if !graphs.includes(request.graph) {
+            continue;
+        }
+
+        let Some(other_lane) = in_other_graph else {
+            changes.push(GraphChange {
+                description: match site_id {
+                    Some(id) => format!("Lane #{} was removed", id.0),
+                    None => "An unsaved lane was removed".to_owned(),
+                },
+                entity: Some(e),
+                kind: GraphChangeKind::RemoveLane(e),
+            });
+            continue;
+        };
+
+        let mut fields = Vec::new();
+        if to_ids(edge)
+            .filter(|ids| *ids == other_lane.anchors)
+            .is_none()
+        {
+            fields.push("anchors");
+        }
+        if *forward != other_lane.forward {
+            fields.push("forward motion");
+        }
+        if *reverse != other_lane.reverse {
+            fields.push("reverse motion");
+        }
+        if *cost != other_lane.cost {
+            fields.push("cost");
+        }
+        if *curve != other_lane.curve {
+            fields.push("curve");
+        }
+        if *shared_zone != other_lane.shared_zone {
+            fields.push("shared zone");
+        }
+        if !fields.is_empty() {
+            changes.push(GraphChange {
+                description: format!(
+                    "Lane #{} has a different {}",
+                    site_id.unwrap().0,
+                    fields.join(", ")
+                ),
+                entity: Some(e),
+                kind: GraphChangeKind::ModifyLane(e, other_lane.clone()),
+            });
+        }
+    }
+
+    for (id, other_lane) in &other_nav.lanes {
+        if !other_lane.graphs.includes(other_graph) {
+            continue;
+        }
+
+        match current_lanes.get(id) {
+            Some(e) => {
+                let in_graph = lanes
+                    .get(*e)
+                    .ok()
+                    .filter(|(_, _, _, _, _, _, _, graphs, _, _)| graphs.includes(request.graph))
+                    .is_some();
+                if !in_graph {
+                    changes.push(GraphChange {
+                        description: format!("Lane #{id} was added to the graph"),
+                        entity: Some(*e),
+                        kind: GraphChangeKind::ModifyLane(*e, other_lane.clone()),
+                    });
+                }
+            }
+            None => {
+                changes.push(GraphChange {
+                    description: format!("Lane #{id} was added"),
+                    entity: None,
+                    kind: GraphChangeKind::AddLane(other_lane.clone()),
+                });
+            }
+        }
+    }
+
+    // Compare locations
+    let mut current_locations = BTreeMap::new();
+    for (e, point, name, tags, parameters, graphs, site_id, parent) in &locations {
+        if parent.get() != request.site {
+            continue;
+        }
+
+        if let Some(id) = site_id {
+            current_locations.insert(id.0, e);
+        }
+
+        let in_other_graph = site_id
+            .and_then(|id| other_nav.locations.get(&id.0))
+            .filter(|location| location.graphs.includes(other_graph));
+        if !graphs.includes(request.graph) {
+            continue;
+        }
+
+        let Some(other_location) = in_other_graph else {
+            changes.push(GraphChange {
+                description: format!("Location [{}] was removed", name.0),
+                entity: Some(e),
+                kind: GraphChangeKind::RemoveLocation(e),
+            });
+            continue;
+        };
+
+        let mut fields = Vec::new();
+        if anchor_ids.get(&point.0) != Some(&other_location.anchor.0) {
+            fields.push("anchor");
+        }
+        if *name != other_location.name {
+            fields.push("name");
+        }
+        if *tags != other_location.tags {
+            fields.push("tags");
+        }
+        if *parameters != other_location.parameters {
+            fields.push("parameters");
+        }
+        if !fields.is_empty() {
+            changes.push(GraphChange {
+                description: format!(
+                    "Location [{}] has a different {}",
+                    name.0,
+                    fields.join(", ")
+                ),
+                entity: Some(e),
+                kind: GraphChangeKind::ModifyLocation(e, other_location.clone()),
+            });
+        }
+    }
+
+    for (id, other_location) in &other_nav.locations {
+        if !other_location.graphs.includes(other_graph) {
+            continue;
+        }
+
+        match current_locations.get(id) {
+            Some(e) => {
+                let in_graph = locations
+                    .get(*e)
+                    .ok()
+                    .filter(|(_, _, _, _, graphs, _, _)| graphs.includes(request.graph))
+                    .is_some();
+                if !in_graph {
+                    changes.push(GraphChange {
+                        description: format!(
+                            "Location [{}] was added to the graph",
+                            other_location.name.0
+                        ),
+                        entity: Some(*e),
+                        kind: GraphChangeKind::ModifyLocation(*e, other_location.clone()),
+                    });
+                }
+            }
+            None => {
+                changes.push(GraphChange {
+                    description: format!("Location [{}] was added", other_location.name.0),
+                    entity: None,
+                    kind: GraphChangeKind::AddLocation(other_location.clone()),
+                });
+            }
+        }
+    }
+
+    notify_info(
+        None,
+        format!("Found {} difference(s) between the graphs", changes.len()),
+    );
+    diff.site = Some(request.site);
+    diff.graph = Some(request.graph);
+    diff.changes = changes;
+    diff.other = Some(request.other.clone());
+}
+
+/// Get the entity in the current site for an anchor of the other site,
+/// bringing the anchor over from the other site if it does not exist yet.
+fn resolve_anchor(
+    id: u32,
+    dependents: Dependents,
+    site: Entity,
+    diff: &mut NavGraphDiff,
+    params: &mut DiffAnchorParams,
+) -> Option<Entity> {
+    let existing = params
+        .anchors
+        .iter()
+        .find(|(e, site_id)| site_id.0 == id && params.is_in_site(*e, site))
+        .map(|(e, _)| e);
+    if let Some(e) = existing.or_else(|| diff.imported_anchors.get(&id).copied()) {
+        return Some(e);
+    }
+
+    let other = diff.other.as_ref()?;
+    let (parent, anchor) = if let Some(anchor) = other.anchors.get(&id) {
+        (site, anchor)
+    } else if let Some((level, anchor)) = other
+        .levels
+        .values()
+        .find_map(|level| level.anchors.get(&id).map(|a| (level, a)))
+    {
+        let parent = params
+            .levels
+            .iter()
+            .find(|(_, props, p)| p.get() == site && props.name == level.properties.name)
+            .map(|(e, _, _)| e);
+        let Some(parent) = parent else {
+            notify_error(
+                None,
+                format!(
+                    "Cannot find level [{}] to bring anchor #{id} into",
+                    level.properties.name
+                ),
+            );
+            return None;
+        };
+        (parent, anchor)
+    } else if let Some((lift, anchor)) = other
+        .lifts
+        .values()
+        .find_map(|lift| lift.cabin_anchors.get(&id).map(|a| (lift, a)))
+    {
+        let parent = params
+            .lifts
+            .iter()
+            .find(|(name, _, p)| p.get() == site && **name == lift.properties.name)
+            .map(|(_, group, _)| **group);
+        let Some(parent) = parent else {
+            notify_error(
+                None,
+                format!(
+                    "Cannot find lift [{}] to bring anchor #{id} into",
+                    lift.properties.name.0
+                ),
+            );
+            return None;
+        };
+        (parent, anchor)
+    } else {
+        notify_error(
+            None,
+            format!("Anchor #{id} does not exist in the other site"),
+        );
+        return None;
+    };
+
+    let mut bundle = AnchorBundle::new(anchor.clone()).dependents(dependents);
+    if let Ok(parent_tf) = params.transforms.get(parent) {
+        bundle = bundle.parent_transform(parent_tf);
+    }
+    let e = params.commands.spawn(bundle).id();
+    params.commands.entity(parent).add_child(e);
+    diff.imported_anchors.insert(id, e);
+    Some(e)
+}
+
+fn remove_from_graph(
+    e: Entity,
+    graphs: &AssociatedGraphs<Entity>,
+    graph: Entity,
+    commands: &mut Commands,
+    delete: &mut EventWriter<Delete>,
+) {
+    let mut graphs = graphs.clone();
+    exclude_graph(&mut graphs, graph);
+    if graphs.only().filter(|set| set.is_empty()).is_some() {
+        delete.send(Delete::new(e));
+    } else {
+        commands.entity(e).insert(graphs);
+    }
+}
+
+pub fn resolve_nav_graph_changes(
+    mut requests: EventReader<ResolveGraphChange>,
+    mut diff: ResMut<NavGraphDiff>,
+    mut params: DiffAnchorParams,
+    mut lanes: Query<(&mut Edge<Entity>, &AssociatedGraphs<Entity>), With<LaneMarker>>,
+    mut locations: Query<(&mut Point<Entity>, &AssociatedGraphs<Entity>), With<LocationTags>>,
+    mut dependents: Query<&mut Dependents>,
+    mut delete: EventWriter<Delete>,
+) {
+    for request in requests.iter() {
+        if request.index >= diff.changes.len() {
+            continue;
+        }
+
+        let change = diff.changes.remove(request.index);
+        if !request.accept {
+            continue;
+        }
+
+        let (Some(site), Some(graph)) = (diff.site, diff.graph) else {
+            continue;
+        };
+
+        let graphs_for = |other: &AssociatedGraphs<u32>| {
+            if other.all() {
+                AssociatedGraphs::All
+            } else {
+                AssociatedGraphs::Only([graph].into())
+            }
+        };
+
+        match change.kind {
+            GraphChangeKind::AddLane(lane) => {
+                let start = resolve_anchor(
+                    lane.anchors.start(),
+                    Dependents::default(),
+                    site,
+                    &mut diff,
+                    &mut params,
+                );
+                let end = resolve_anchor(
+                    lane.anchors.end(),
+                    Dependents::default(),
+                    site,
+                    &mut diff,
+                    &mut params,
+                );
+                let (Some(start), Some(end)) = (start, end) else {
+                    continue;
+                };
+                params.commands.spawn(Lane {
+                    anchors: Edge::new(start, end),
+                    forward: lane.forward,
+                    reverse: lane.reverse,
+                    graphs: graphs_for(&lane.graphs),
+                    cost: lane.cost,
+                    curve: lane.curve,
+                    shared_zone: lane.shared_zone,
+                    marker: LaneMarker,
+                });
+            }
+            GraphChangeKind::RemoveLane(e) => {
+                if let Ok((_, graphs)) = lanes.get(e) {
+                    remove_from_graph(e, graphs, graph, &mut params.commands, &mut delete);
+                }
+            }
+            GraphChangeKind::ModifyLane(e, lane) => {
+                let ends = lane.anchors.array().map(|id| {
+                    resolve_anchor(id, Dependents::single(e), site, &mut diff, &mut params)
+                });
+                let Ok((mut edge, graphs)) = lanes.get_mut(e) else {
+                    continue;
+                };
+                let mut graphs = graphs.clone();
+                include_graph(&mut graphs, graph);
+
+                if let [Some(start), Some(end)] = ends {
+                    let new_edge = Edge::new(start, end);
+                    if *edge != new_edge {
+                        for anchor in edge.array() {
+                            if let Ok(mut deps) = dependents.get_mut(anchor) {
+                                deps.remove(&e);
+                            }
+                        }
+                        for anchor in new_edge.array() {
+                            if let Ok(mut deps) = dependents.get_mut(anchor) {
+                                deps.insert(e);
+                            }
+                        }
+                        *edge = new_edge;
+                    }
+                }
+
+                params
+                    .commands
+                    .entity(e)
+                    .insert(lane.forward)
+                    .insert(lane.reverse)
+                    .insert(lane.cost)
+                    .insert(lane.curve)
+                    .insert(lane.shared_zone)
+                    .insert(graphs);
+            }
+            GraphChangeKind::AddLocation(location) => {
+                let Some(anchor) = resolve_anchor(
+                    location.anchor.0,
+                    Dependents::default(),
+                    site,
+                    &mut diff,
+                    &mut params,
+                ) else {
+                    continue;
+                };
+                params.commands.spawn(Location {
+                    anchor: Point(anchor),
+                    tags: location.tags,
+                    name: location.name,
+                    graphs: graphs_for(&location.graphs),
+                    parameters: location.parameters,
+                });
+            }
+            GraphChangeKind::RemoveLocation(e) => {
+                if let Ok((_, graphs)) = locations.get(e) {
+                    remove_from_graph(e, graphs, graph, &mut params.commands, &mut delete);
+                }
+            }
+            GraphChangeKind::ModifyLocation(e, location) => {
+                let anchor = resolve_anchor(
+                    location.anchor.0,
+                    Dependents::single(e),
+                    site,
+                    &mut diff,
+                    &mut params,
+                );
+                let Ok((mut point, graphs)) = locations.get_mut(e) else {
+                    continue;
+                };
+                let mut graphs = graphs.clone();
+                include_graph(&mut graphs, graph);
+
+                if let Some(anchor) = anchor {
+                    if point.0 != anchor {
+                        if let Ok(mut deps) = dependents.get_mut(point.0) {
+                            deps.remove(&e);
+                        }
+                        if let Ok(mut deps) = dependents.get_mut(anchor) {
+                            deps.insert(e);
+                        }
+                        point.0 = anchor;
+                    }
+                }
+
+                params
+                    .commands
+                    .entity(e)
+                    .insert(location.name)
+                    .insert(location.tags)
+                    .insert(location.parameters)
+                    .insert(graphs);
+            }
+        }
+    }
+}
@@ -0,0 +1,277 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::site::*;
+use bevy::prelude::*;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// Height of the path preview above the lanes so that it is drawn on top.
+pub const PATH_PREVIEW_HEIGHT: f32 = LANE_LAYER_LIMIT + 0.005;
+pub const PATH_PREVIEW_WIDTH: f32 = 0.3;
+
+/// Ask for the shortest path between two locations to be computed and
+/// highlighted, or clear the current preview.
+#[derive(Clone, Copy, Debug)]
+pub enum PreviewPath {
+    Between {
+        graph: Entity,
+        start: Entity,
+        goal: Entity,
+    },
+    Clear,
+}
+
+/// The shortest path that was found between two locations
+#[derive(Clone, Debug, Default)]
+pub struct PathSolution {
+    /// The anchors that are visited along the path, in order
+    pub anchors: Vec<Entity>,
+    /// Total length of the path in meters
+    pub length: f32,
+    /// Doors that the path passes through, in order
+    pub doors: Vec<Entity>,
+    /// Lifts that the path rides, in order
+    pub lifts: Vec<Entity>,
+}
+
+#[derive(Resource, Default)]
+pub struct PathPreview {
+    pub solution: Option<Result<PathSolution, String>>,
+    visuals: Vec<Entity>,
+}
+
+/// Marks the visual segments of a path preview. The segments have a global
+/// transform so they are given the level that they belong to in order to
+/// decide their visibility.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PathPreviewSegment {
+    pub level: Option<Entity>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Candidate {
+    cost: f32,
+    anchor: Entity,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so that the BinaryHeap pops the lowest cost first
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn segments_intersect(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> bool {
+    let cross = |o: Vec2, p: Vec2, q: Vec2| (p - o).perp_dot(q - o);
+    let d0 = cross(b0, b1, a0);
+    let d1 = cross(b0, b1, a1);
+    let d2 = cross(a0, a1, b0);
+    let d3 = cross(a0, a1, b1);
+    d0 * d1 < 0.0 && d2 * d3 < 0.0
+}
+
+pub fn update_path_preview(
+    mut commands: Commands,
+    mut requests: EventReader<PreviewPath>,
+    mut preview: ResMut<PathPreview>,
+    lanes: Query<
+        (&Edge<Entity>, &ReverseLane, &AssociatedGraphs<Entity>),
+        (With<LaneMarker>, Without<Pending>),
+    >,
+    locations: Query<&Point<Entity>, With<LocationTags>>,
+    anchors: Query<(&GlobalTransform, &Parent), With<Anchor>>,
+    doors: Query<(Entity, &Edge<Entity>, &Parent), (With<DoorMarker>, Without<Pending>)>,
+    cabin_groups: Query<&Parent, With<CabinAnchorGroup>>,
+    levels: Query<(), With<LevelProperties>>,
+    assets: Res<SiteAssets>,
+) {
+    let Some(request) = requests.iter().last() else {
+        return;
+    };
+
+    for e in preview.visuals.drain(..) {
+        commands.entity(e).despawn_recursive();
+    }
+    preview.solution = None;
+
+    let (graph, start, goal) = match request {
+        PreviewPath::Between { graph, start, goal } => (*graph, *start, *goal),
+        PreviewPath::Clear => return,
+    };
+
+    let (Ok(start), Ok(goal)) = (locations.get(start), locations.get(goal)) else {
+        preview.solution = Some(Err("The start or goal is not a location".to_owned()));
+        return;
+    };
+    let (start, goal) = (**start, **goal);
+
+    let position = |anchor: Entity| anchors.get(anchor).ok().map(|(tf, _)| tf.translation());
+    let mut adjacent: HashMap<Entity, Vec<(Entity, f32)>> = HashMap::new();
+    for (edge, reverse, associated) in &lanes {
+        if !associated.includes(graph) {
+            continue;
+        }
+
+        let (Some(p0), Some(p1)) = (position(edge.start()), position(edge.end())) else {
+            continue;
+        };
+        let length = (p1 - p0).length();
+        adjacent
+            .entry(edge.start())
+            .or_default()
+            .push((edge.end(), length));
+        if !matches!(reverse, ReverseLane::Disable) {
+            adjacent
+                .entry(edge.end())
+                .or_default()
+                .push((edge.start(), length));
+        }
+    }
+
+    let mut cost: HashMap<Entity, f32> = HashMap::new();
+    let mut came_from: HashMap<Entity, Entity> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    cost.insert(start, 0.0);
+    queue.push(Candidate {
+        cost: 0.0,
+        anchor: start,
+    });
+    while let Some(Candidate { cost: c, anchor }) = queue.pop() {
+        if anchor == goal {
+            break;
+        }
+
+        if cost.get(&anchor).filter(|best| **best < c).is_some() {
+            continue;
+        }
+
+        for (next, length) in adjacent.get(&anchor).into_iter().flatten() {
+            let next_cost = c + *length;
+            if cost.get(next).filter(|best| **best <= next_cost).is_none() {
+                cost.insert(*next, next_cost);
+                came_from.insert(*next, anchor);
+                queue.push(Candidate {
+                    cost: next_cost,
+                    anchor: *next,
+                });
+            }
+        }
+    }
+
+    let Some(length) = cost.get(&goal).copied() else {
+        preview.solution = Some(Err(
+            "No path exists between these locations on the selected graph".to_owned(),
+        ));
+        return;
+    };
+
+    let mut path = vec![goal];
+    while let Some(prev) = came_from.get(path.last().unwrap()) {
+        path.push(*prev);
+    }
+    path.reverse();
+
+    let level_of = |anchor: Entity| {
+        anchors
+            .get(anchor)
+            .ok()
+            .map(|(_, parent)| parent.get())
+            .filter(|parent| levels.contains(*parent))
+    };
+
+    let mut solution = PathSolution {
+        anchors: path.clone(),
+        length,
+        ..default()
+    };
+    for anchor in &path {
+        let lift = anchors
+            .get(*anchor)
+            .ok()
+            .and_then(|(_, parent)| cabin_groups.get(parent.get()).ok())
+            .map(|parent| parent.get());
+        if let Some(lift) = lift {
+            if solution.lifts.last() != Some(&lift) {
+                solution.lifts.push(lift);
+            }
+        }
+    }
+
+    for pair in path.windows(2) {
+        let (Some(p0), Some(p1)) = (position(pair[0]), position(pair[1])) else {
+            continue;
+        };
+        let level = level_of(pair[0]).or_else(|| level_of(pair[1]));
+        if let Some(level) = level {
+            for (door, edge, parent) in &doors {
+                if parent.get() != level || solution.doors.contains(&door) {
+                    continue;
+                }
+
+                let (Some(d0), Some(d1)) = (position(edge.start()), position(edge.end())) else {
+                    continue;
+                };
+                if segments_intersect(p0.truncate(), p1.truncate(), d0.truncate(), d1.truncate()) {
+                    solution.doors.push(door);
+                }
+            }
+        }
+
+        let mut tf = line_stroke_transform(&p0, &p1, PATH_PREVIEW_WIDTH);
+        tf.translation.z = PATH_PREVIEW_HEIGHT;
+        let segment = commands
+            .spawn(PbrBundle {
+                mesh: assets.lane_mid_mesh.clone(),
+                material: assets.path_preview_material.clone(),
+                transform: tf,
+                ..default()
+            })
+            .insert(PathPreviewSegment { level })
+            .id();
+        preview.visuals.push(segment);
+    }
+
+    preview.solution = Some(Ok(solution));
+}
+
+pub fn update_path_preview_visibility(
+    mut segments: Query<(&PathPreviewSegment, &mut Visibility)>,
+    current_level: Res<CurrentLevel>,
+    added: Query<(), Added<PathPreviewSegment>>,
+) {
+    if !current_level.is_changed() && added.is_empty() {
+        return;
+    }
+
+    for (segment, mut visibility) in &mut segments {
+        let is_visible = segment.level.is_none() || segment.level == **current_level;
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+    }
+}
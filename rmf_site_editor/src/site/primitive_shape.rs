@@ -0,0 +1,46 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::site::*;
+use bevy::prelude::*;
+use rmf_site_format::{MeshPrimitive, Pose, PrimitiveShapeMarker};
+
+pub fn add_primitive_shape_visuals(
+    mut commands: Commands,
+    shapes: Query<(Entity, &Pose), Added<PrimitiveShapeMarker>>,
+) {
+    for (e, pose) in &shapes {
+        // The mesh itself is added by handle_new_mesh_primitives
+        commands
+            .entity(e)
+            .insert(SpatialBundle::from_transform(pose.transform()))
+            .insert(Category::PrimitiveShape);
+    }
+}
+
+/// How far the bottom of a primitive is below its center, as it is rendered by
+/// [`make_primitive_mesh`]. New shapes are raised by this much so that they
+/// rest on the surface they are placed on.
+pub fn primitive_base_offset(primitive: &MeshPrimitive) -> f32 {
+    match primitive {
+        MeshPrimitive::Box { size } => size[2] / 2.0,
+        MeshPrimitive::Cylinder { length, .. } | MeshPrimitive::Cone { length, .. } => length / 2.0,
+        // Capsules and spheres are symmetric about the horizontal axis that
+        // the capsule is generated along
+        MeshPrimitive::Capsule { radius, .. } | MeshPrimitive::Sphere { radius } => *radius,
+    }
+}
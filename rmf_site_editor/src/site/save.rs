@@ -19,15 +19,62 @@ use bevy::{
     ecs::{event::Events, system::SystemState},
     prelude::*,
 };
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 use thiserror::Error as ThisError;
 
-use crate::{recency::RecencyRanking, site::*};
+use crate::{
+    console::{notify_error, notify_info, notify_warning},
+    recency::RecencyRanking,
+    record_finished_save,
+    site::*,
+};
 use rmf_site_format::*;
 
 pub struct SaveSite {
     pub site: Entity,
     pub to_file: PathBuf,
+    /// Whether this is an autosave, which should not interrupt the user
+    pub autosave: bool,
+    pub options: SiteSaveOptions,
+}
+
+/// Choices for how a site gets written to its file
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SiteSaveOptions {
+    /// The format to use when the name of the file does not decide it
+    pub format: SiteFileFormat,
+    /// Give the elements of the site consecutive IDs, removing the gaps that
+    /// deleted elements leave behind
+    pub renumber_ids: bool,
+    pub drawings: DrawingStorage,
+}
+
+/// Where the images of the drawings are kept when a site is saved
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DrawingStorage {
+    /// Keep referring to the images wherever they are now
+    #[default]
+    Reference,
+    /// Copy the images into a folder next to the site file and refer to the
+    /// copies, so the site can be moved along with its drawings. Images are
+    /// not embedded in the site file itself because asset sources can only
+    /// refer to images, not hold them.
+    CopyNextToFile,
+}
+
+impl DrawingStorage {
+    pub const ALL: [DrawingStorage; 2] =
+        [DrawingStorage::Reference, DrawingStorage::CopyNextToFile];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DrawingStorage::Reference => "Reference drawings where they are",
+            DrawingStorage::CopyNextToFile => "Copy drawings next to the file",
+        }
+    }
 }
 
 pub struct SaveNavGraphs {
@@ -35,6 +82,12 @@ pub struct SaveNavGraphs {
     pub to_file: PathBuf,
 }
 
+/// Export the configuration needed by the RMF door and lift supervisors
+pub struct ExportSupervisorConfig {
+    pub site: Entity,
+    pub to_file: PathBuf,
+}
+
 // TODO(MXG): Change all these errors to use u32 SiteIDs instead of entities
 #[derive(ThisError, Debug, Clone)]
 pub enum SiteGenerationError {
@@ -75,6 +128,7 @@ fn assign_site_ids(world: &mut World, site: Entity) -> Result<(), SiteGeneration
                     With<MeasurementMarker>,
                     With<ModelMarker>,
                     With<PhysicalCameraProperties>,
+                    With<PrimitiveShapeMarker>,
                     With<WallMarker>,
                 )>,
                 Without<Pending>,
@@ -89,13 +143,24 @@ fn assign_site_ids(world: &mut World, site: Entity) -> Result<(), SiteGeneration
         >,
         Query<Entity, (With<LevelProperties>, Without<Pending>)>,
         Query<Entity, (With<LiftCabin<Entity>>, Without<Pending>)>,
+        Query<Entity, (With<SensorProperties>, Without<Pending>)>,
+        Query<Entity, (With<LightKind>, Without<Pending>)>,
         Query<&NextSiteID>,
         Query<&SiteID>,
         Query<&Children>,
     )> = SystemState::new(world);
 
-    let (level_children, nav_graph_elements, levels, lifts, sites, site_ids, children) =
-        state.get_mut(world);
+    let (
+        level_children,
+        nav_graph_elements,
+        levels,
+        lifts,
+        sensors,
+        lights,
+        sites,
+        site_ids,
+        children,
+    ) = state.get_mut(world);
 
     let mut new_entities = Vec::new();
 
@@ -115,12 +180,21 @@ fn assign_site_ids(world: &mut World, site: Entity) -> Result<(), SiteGeneration
                 new_entities.push(level);
             }
 
-            if let Ok(children) = children.get(level) {
-                for child in children {
+            if let Ok(level_contents) = children.get(level) {
+                for child in level_contents {
                     if level_children.contains(*child) {
                         if !site_ids.contains(*child) {
                             new_entities.push(*child);
                         }
+
+                        // Sensors are attached to the models of the level
+                        if let Ok(model_children) = children.get(*child) {
+                            for sensor in sensors.iter_many(model_children) {
+                                if !site_ids.contains(sensor) {
+                                    new_entities.push(sensor);
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -132,6 +206,13 @@ fn assign_site_ids(world: &mut World, site: Entity) -> Result<(), SiteGeneration
             }
         }
 
+        // Lights that are shared by every level
+        if let Ok(e) = lights.get(*site_child) {
+            if !site_ids.contains(e) {
+                new_entities.push(e);
+            }
+        }
+
         if let Ok(lift) = lifts.get(*site_child) {
             if !site_ids.contains(lift) {
                 new_entities.push(lift);
@@ -166,6 +247,140 @@ fn assign_site_ids(world: &mut World, site: Entity) -> Result<(), SiteGeneration
     Ok(())
 }
 
+/// The SiteIDs that a site had before they were renumbered
+pub(crate) struct PreviousSiteIDs {
+    ids: Vec<(Entity, SiteID)>,
+    next: Option<NextSiteID>,
+}
+
+/// Give the elements of a site consecutive SiteIDs in the same order as their
+/// current SiteIDs. The site file is generated from the SiteID components, so
+/// they are renumbered in place and the previous IDs are returned to be put
+/// back by [`restore_site_ids`] once the file has been generated. Change
+/// detection is bypassed so the open site never sees the renumbered IDs, which
+/// means the [`LevelSaveCache`] cannot notice them and gets cleared instead.
+pub(crate) fn renumber_site_ids(world: &mut World, site: Entity) -> PreviousSiteIDs {
+    let mut state: SystemState<(Query<&Children>, Query<&SiteID>)> = SystemState::new(world);
+    let (children, site_ids) = state.get(world);
+
+    let mut elements = Vec::new();
+    let mut queue = vec![site];
+    while let Some(e) = queue.pop() {
+        let Ok(e_children) = children.get(e) else {
+            continue;
+        };
+        for child in e_children {
+            if let Ok(id) = site_ids.get(*child) {
+                elements.push((id.0, *child));
+            }
+            queue.push(*child);
+        }
+    }
+    elements.sort();
+
+    let next = world.get::<NextSiteID>(site).copied();
+    for (new_id, (_, e)) in elements.iter().enumerate() {
+        if let Some(mut id) = world.get_mut::<SiteID>(*e) {
+            *id.bypass_change_detection() = SiteID(new_id as u32);
+        }
+    }
+    if let Some(mut next_id) = world.get_mut::<NextSiteID>(site) {
+        *next_id.bypass_change_detection() = NextSiteID(elements.len() as u32);
+    }
+    if let Some(mut cache) = world.get_resource_mut::<LevelSaveCache>() {
+        cache.clear();
+    }
+
+    PreviousSiteIDs {
+        ids: elements
+            .into_iter()
+            .map(|(id, e)| (e, SiteID(id)))
+            .collect(),
+        next,
+    }
+}
+
+/// Put back the SiteIDs that were replaced by [`renumber_site_ids`], along
+/// with clearing the levels that were cached with the renumbered IDs
+pub(crate) fn restore_site_ids(world: &mut World, site: Entity, previous: PreviousSiteIDs) {
+    for (e, previous_id) in previous.ids {
+        if let Some(mut id) = world.get_mut::<SiteID>(e) {
+            *id.bypass_change_detection() = previous_id;
+        }
+    }
+    if let (Some(next), Some(mut next_id)) = (previous.next, world.get_mut::<NextSiteID>(site)) {
+        *next_id.bypass_change_detection() = next;
+    }
+    if let Some(mut cache) = world.get_resource_mut::<LevelSaveCache>() {
+        cache.clear();
+    }
+}
+
+/// Copy the local images of the drawings into a folder next to the site file
+/// and point the drawings at the copies
+fn copy_drawings_next_to_file(site: &mut rmf_site_format::Site, path: &Path) {
+    let stem = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split('.').next())
+        .unwrap_or("site");
+    let folder = path.with_file_name(format!("{stem}_drawings"));
+
+    let mut copied: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for level in site.levels.values_mut() {
+        for (id, drawing) in level.drawings.iter_mut() {
+            let AssetSource::Local(original) = &drawing.source else {
+                continue;
+            };
+            let original = PathBuf::from(original);
+            let Some(name) = original.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            // Drawings from different folders may share a file name
+            let name = match copied.get(name) {
+                Some(other) if *other != original => format!("{id}_{name}"),
+                _ => name.to_owned(),
+            };
+            let destination = folder.join(&name);
+            if let Err(err) = std::fs::create_dir_all(&folder)
+                .and_then(|_| std::fs::copy(&original, &destination))
+            {
+                notify_warning(
+                    None,
+                    format!("Unable to copy drawing {original:?} to {destination:?}: {err}"),
+                );
+                continue;
+            }
+            copied.insert(name, original);
+            drawing.source = AssetSource::Local(destination.to_string_lossy().into_owned());
+        }
+    }
+}
+
+fn collect_shared_lights(world: &mut World, site: Entity) -> BTreeMap<u32, Light> {
+    let mut state: SystemState<(
+        Query<&Children>,
+        Query<(&SiteID, &Pose, &LightKind, &LightGroup), Without<Pending>>,
+    )> = SystemState::new(world);
+
+    let mut lights = BTreeMap::new();
+    let (q_children, q_lights) = state.get(world);
+    if let Ok(children) = q_children.get(site) {
+        for (site_id, pose, kind, group) in q_lights.iter_many(children) {
+            lights.insert(
+                site_id.0,
+                Light {
+                    pose: pose.clone(),
+                    kind: kind.clone(),
+                    group: group.clone(),
+                },
+            );
+        }
+    }
+
+    lights
+}
+
 fn collect_site_anchors(world: &mut World, site: Entity) -> BTreeMap<u32, Anchor> {
     let mut state: SystemState<(
         Query<&Children>,
@@ -185,9 +400,32 @@ fn collect_site_anchors(world: &mut World, site: Entity) -> BTreeMap<u32, Anchor
     site_anchors
 }
 
-fn generate_levels(
+pub(crate) fn generate_levels(
     world: &mut World,
     site: Entity,
+) -> Result<BTreeMap<u32, Level>, SiteGenerationError> {
+    if !world.contains_resource::<LevelSaveCache>() {
+        return generate_level_contents(world, site, None);
+    }
+
+    world.resource_scope(|world, mut cache: Mut<LevelSaveCache>| {
+        cache.remove_changed(world);
+        generate_level_contents(world, site, Some(&mut *cache))
+    })
+}
+
+/// Generate the levels of a site. Levels that are found in the cache are
+/// reused as they are, and only the rest get generated from their elements.
+///
+/// The cache finds edited levels with the `Changed` filters of
+/// [`LevelSaveCache`], so every component that is read here must also be
+/// listed in `ChangedLevelElements` or `ChangedLevelStructure` of
+/// `save_cache.rs`. A component that is missing from those lists will have
+/// its edits silently dropped from later saves.
+fn generate_level_contents(
+    world: &mut World,
+    site: Entity,
+    mut cache: Option<&mut LevelSaveCache>,
 ) -> Result<BTreeMap<u32, Level>, SiteGenerationError> {
     let mut state: SystemState<(
         Query<(&Anchor, &SiteID, &Parent)>,
@@ -197,6 +435,7 @@ fn generate_levels(
                 Option<&Original<Edge<Entity>>>,
                 &NameInSite,
                 &DoorType,
+                Option<&DoorOccupancy>,
                 &SiteID,
                 &Parent,
             ),
@@ -226,7 +465,7 @@ fn generate_levels(
             ),
             (With<FloorMarker>, Without<Pending>),
         >,
-        Query<(&LightKind, &Pose, &SiteID, &Parent)>,
+        Query<(&LightKind, &Pose, &LightGroup, &SiteID, &Parent)>,
         Query<
             (
                 &Edge<Entity>,
@@ -246,6 +485,9 @@ fn generate_levels(
                 &IsStatic,
                 &ConstraintDependents,
                 &Scale,
+                Option<&ModelTags>,
+                Option<&MaterialOverride>,
+                Option<&LodPolicy>,
                 &SiteID,
                 &Parent,
             ),
@@ -261,6 +503,18 @@ fn generate_levels(
             ),
             Without<Pending>,
         >,
+        Query<
+            (
+                &NameInSite,
+                &MeshPrimitive,
+                &Pose,
+                Option<&MaterialOverride>,
+                &SiteID,
+                &Parent,
+            ),
+            (With<PrimitiveShapeMarker>, Without<Pending>),
+        >,
+        Query<(&NameInSite, &Pose, &SensorProperties, &SiteID, &Parent), Without<Pending>>,
         Query<
             (
                 &Edge<Entity>,
@@ -281,6 +535,7 @@ fn generate_levels(
             ),
             Without<Pending>,
         >,
+        Query<Entity, (With<LevelProperties>, Without<Pending>)>,
         Query<&SiteID>,
     )> = SystemState::new(world);
 
@@ -294,14 +549,29 @@ fn generate_levels(
         q_measurements,
         q_models,
         q_physical_cameras,
+        q_primitive_shapes,
+        q_sensors,
         q_walls,
         q_levels,
+        q_level_entities,
         q_site_ids,
     ) = state.get(world);
 
     let mut levels = BTreeMap::new();
-    for (properties, level_id, parent, floor_ranking, drawing_ranking) in &q_levels {
+    let mut cached_levels = BTreeMap::new();
+    let mut generated_levels = Vec::new();
+    for e in &q_level_entities {
+        let Ok((properties, level_id, parent, floor_ranking, drawing_ranking)) = q_levels.get(e)
+        else {
+            continue;
+        };
         if parent.get() == site {
+            if let Some(level) = cache.as_ref().and_then(|c| c.get(e, level_id.0)) {
+                cached_levels.insert(level_id.0, level.clone());
+                continue;
+            }
+
+            generated_levels.push((e, level_id.0));
             levels.insert(
                 level_id.0,
                 Level::new(
@@ -350,7 +620,7 @@ fn generate_levels(
         }
     }
 
-    for (edge, o_edge, name, kind, id, parent) in &q_doors {
+    for (edge, o_edge, name, kind, occupancy, id, parent) in &q_doors {
         let edge = o_edge.map(|x| &x.0).unwrap_or(edge);
         if let Ok((_, level_id, _, _, _)) = q_levels.get(parent.get()) {
             if let Some(level) = levels.get_mut(&level_id.0) {
@@ -361,6 +631,7 @@ fn generate_levels(
                         anchors,
                         name: name.clone(),
                         kind: kind.clone(),
+                        occupancy: occupancy.copied().unwrap_or_default(),
                         marker: DoorMarker,
                     },
                 );
@@ -418,7 +689,7 @@ fn generate_levels(
         }
     }
 
-    for (kind, pose, id, parent) in &q_lights {
+    for (kind, pose, group, id, parent) in &q_lights {
         if let Ok((_, level_id, _, _, _)) = q_levels.get(parent.get()) {
             if let Some(level) = levels.get_mut(&level_id.0) {
                 level.lights.insert(
@@ -426,6 +697,7 @@ fn generate_levels(
                     Light {
                         pose: pose.clone(),
                         kind: kind.clone(),
+                        group: group.clone(),
                     },
                 );
             }
@@ -450,7 +722,20 @@ fn generate_levels(
         }
     }
 
-    for (name, source, pose, is_static, constraint_dependents, scale, id, parent) in &q_models {
+    for (
+        name,
+        source,
+        pose,
+        is_static,
+        constraint_dependents,
+        scale,
+        tags,
+        material,
+        lod,
+        id,
+        parent,
+    ) in &q_models
+    {
         if let Ok((_, level_id, _, _, _)) = q_levels.get(parent.get()) {
             if let Some(level) = levels.get_mut(&level_id.0) {
                 level.models.insert(
@@ -462,6 +747,9 @@ fn generate_levels(
                         is_static: is_static.clone(),
                         constraints: constraint_dependents.clone(),
                         scale: scale.clone(),
+                        tags: tags.cloned().unwrap_or_default(),
+                        material: material.cloned().unwrap_or_default(),
+                        lod: lod.copied().unwrap_or_default(),
                         marker: ModelMarker,
                     },
                 );
@@ -485,6 +773,46 @@ fn generate_levels(
         }
     }
 
+    for (name, shape, pose, material, id, parent) in &q_primitive_shapes {
+        if let Ok((_, level_id, _, _, _)) = q_levels.get(parent.get()) {
+            if let Some(level) = levels.get_mut(&level_id.0) {
+                level.primitive_shapes.insert(
+                    id.0,
+                    PrimitiveShape {
+                        name: name.clone(),
+                        shape: shape.clone(),
+                        pose: pose.clone(),
+                        material: material.cloned().unwrap_or_default(),
+                        marker: PrimitiveShapeMarker,
+                    },
+                );
+            }
+        }
+    }
+
+    for (name, pose, properties, id, parent) in &q_sensors {
+        // Sensors in a site are attached to models, so they are saved in the
+        // level of their model
+        let Ok((.., model_id, model_parent)) = q_models.get(parent.get()) else {
+            continue;
+        };
+        if let Ok((_, level_id, _, _, _)) = q_levels.get(model_parent.get()) {
+            if let Some(level) = levels.get_mut(&level_id.0) {
+                level.sensors.insert(
+                    id.0,
+                    Parented {
+                        parent: model_id.0,
+                        bundle: Sensor {
+                            name: name.0.clone(),
+                            pose: pose.clone(),
+                            properties: properties.clone(),
+                        },
+                    },
+                );
+            }
+        }
+    }
+
     for (edge, o_edge, texture, id, parent) in &q_walls {
         let edge = o_edge.map(|x| &x.0).unwrap_or(edge);
         if let Ok((_, level_id, _, _, _)) = q_levels.get(parent.get()) {
@@ -502,6 +830,15 @@ fn generate_levels(
         }
     }
 
+    if let Some(cache) = &mut cache {
+        for (e, id) in generated_levels {
+            if let Some(level) = levels.get(&id) {
+                cache.store(world, e, id, level.clone());
+            }
+        }
+    }
+
+    levels.extend(cached_levels);
     return Ok(levels);
 }
 
@@ -695,7 +1032,14 @@ fn generate_nav_graphs(
 ) -> Result<BTreeMap<u32, NavGraph>, SiteGenerationError> {
     let mut state: SystemState<
         Query<
-            (&NameInSite, &DisplayColor, &SiteID, &Parent),
+            (
+                &NameInSite,
+                &DisplayColor,
+                &ManualColor,
+                &LaneDefaults,
+                &SiteID,
+                &Parent,
+            ),
             (With<NavGraphMarker>, Without<Pending>),
         >,
     > = SystemState::new(world);
@@ -703,7 +1047,7 @@ fn generate_nav_graphs(
     let q_nav_graphs = state.get(world);
 
     let mut nav_graphs = BTreeMap::new();
-    for (name, color, id, parent) in &q_nav_graphs {
+    for (name, color, manual_color, lane_defaults, id, parent) in &q_nav_graphs {
         if parent.get() != site {
             continue;
         }
@@ -713,6 +1057,8 @@ fn generate_nav_graphs(
             NavGraph {
                 name: name.clone(),
                 color: color.clone(),
+                manual_color: *manual_color,
+                lane_defaults: lane_defaults.clone(),
                 marker: Default::default(),
             },
         );
@@ -733,6 +1079,9 @@ fn generate_lanes(
                 &Motion,
                 &ReverseLane,
                 &AssociatedGraphs<Entity>,
+                &LaneCost,
+                &LaneCurve,
+                &SharedZone,
                 &SiteID,
                 &Parent,
             ),
@@ -758,7 +1107,9 @@ fn generate_lanes(
     };
 
     let mut lanes = BTreeMap::new();
-    for (edge, o_edge, forward, reverse, graphs, lane_id, parent) in &q_lanes {
+    for (edge, o_edge, forward, reverse, graphs, cost, curve, shared_zone, lane_id, parent) in
+        &q_lanes
+    {
         if parent.get() != site {
             continue;
         }
@@ -776,6 +1127,9 @@ fn generate_lanes(
                 forward: forward.clone(),
                 reverse: reverse.clone(),
                 graphs,
+                cost: *cost,
+                curve: curve.clone(),
+                shared_zone: shared_zone.clone(),
                 marker: LaneMarker,
             },
         );
@@ -794,6 +1148,7 @@ fn generate_locations(
                 &Point<Entity>,
                 Option<&Original<Point<Entity>>>,
                 &LocationTags,
+                &LocationParameters,
                 &NameInSite,
                 &AssociatedGraphs<Entity>,
                 &SiteID,
@@ -815,7 +1170,7 @@ fn generate_locations(
     };
 
     let mut locations = BTreeMap::new();
-    for (point, o_point, tags, name, graphs, location_id, parent) in &q_locations {
+    for (point, o_point, tags, parameters, name, graphs, location_id, parent) in &q_locations {
         if parent.get() != site {
             continue;
         }
@@ -833,6 +1188,7 @@ fn generate_locations(
                 tags: tags.clone(),
                 name: name.clone(),
                 graphs,
+                parameters: parameters.clone(),
             },
         );
     }
@@ -873,6 +1229,7 @@ pub fn generate_site(
     let anchors = collect_site_anchors(world, site);
     let levels = generate_levels(world, site)?;
     let lifts = generate_lifts(world, site)?;
+    let lights = collect_shared_lights(world, site);
     let nav_graphs = generate_nav_graphs(world, site)?;
     let lanes = generate_lanes(world, site)?;
     let locations = generate_locations(world, site)?;
@@ -891,16 +1248,22 @@ pub fn generate_site(
         properties: props.clone(),
         levels,
         lifts,
+        lights,
         navigation: Navigation {
             guided: Guided {
                 graphs: nav_graphs,
                 ranking: graph_ranking,
                 lanes,
                 locations,
+                palette: world
+                    .get::<NavGraphPalette>(site)
+                    .copied()
+                    .unwrap_or_default(),
             },
         },
         // TODO(MXG): Parse agent information once the spec is figured out
         agents: Default::default(),
+        environment: world.get::<Environment>(site).cloned().unwrap_or_default(),
     });
 }
 
@@ -912,28 +1275,56 @@ pub fn save_site(world: &mut World) {
             "Saving to {}",
             path.to_str().unwrap_or("<failed to render??>")
         );
-        let f = match std::fs::File::create(path) {
+        let f = match std::fs::File::create(&path) {
             Ok(f) => f,
             Err(err) => {
-                println!("Unable to save file: {err}");
+                notify_error(None, format!("Unable to save file: {err}"));
                 continue;
             }
         };
 
-        let site = match generate_site(world, save_event.site) {
+        let options = save_event.options;
+        if options.renumber_ids {
+            if let Err(err) = assign_site_ids(world, save_event.site) {
+                notify_error(None, format!("Unable to compile site: {err}"));
+                continue;
+            }
+        }
+        let previous_ids = options
+            .renumber_ids
+            .then(|| renumber_site_ids(world, save_event.site));
+        let generated = generate_site(world, save_event.site);
+        if let Some(previous_ids) = previous_ids {
+            restore_site_ids(world, save_event.site, previous_ids);
+        }
+
+        let mut site = match generated {
             Ok(site) => site,
             Err(err) => {
-                println!("Unable to compile site: {err}");
+                notify_error(None, format!("Unable to compile site: {err}"));
                 continue;
             }
         };
 
-        match site.to_writer(f) {
+        if options.drawings == DrawingStorage::CopyNextToFile {
+            copy_drawings_next_to_file(&mut site, &path);
+        }
+
+        // Assets inside the folder of the site file are stored relative to it
+        if let Some(root) = path.parent() {
+            site.relativize_asset_paths(root);
+        }
+
+        let format = SiteFileFormat::from_path(&path).unwrap_or(options.format);
+        match site.to_writer_as(format, f) {
             Ok(()) => {
-                println!("Save successful");
+                if !save_event.autosave {
+                    notify_info(None, format!("Site saved to {}", path.display()));
+                }
+                record_finished_save(world, save_event.site, &path, save_event.autosave);
             }
             Err(err) => {
-                println!("Save failed: {err}");
+                notify_error(None, format!("Save failed: {err}"));
             }
         }
     }
@@ -945,12 +1336,15 @@ pub fn save_nav_graphs(world: &mut World) {
         .drain()
         .collect();
     for save_event in save_events {
+        world
+            .resource_mut::<Events<ValidateSite>>()
+            .send(ValidateSite(save_event.site));
         let path = save_event.to_file;
 
         let mut site = match generate_site(world, save_event.site) {
             Ok(site) => site,
             Err(err) => {
-                println!("Unable to compile site: {err}");
+                notify_error(None, format!("Unable to compile site: {err}"));
                 continue;
             }
         };
@@ -965,12 +1359,12 @@ pub fn save_nav_graphs(world: &mut World) {
             let f = match std::fs::File::create(graph_file) {
                 Ok(f) => f,
                 Err(err) => {
-                    println!("Unable to save nav graph: {err}");
+                    notify_error(None, format!("Unable to save nav graph: {err}"));
                     continue;
                 }
             };
             if let Err(err) = serde_yaml::to_writer(f, &nav_graph) {
-                println!("Failed to save nav graph: {err}");
+                notify_error(None, format!("Failed to save nav graph: {err}"));
             }
         }
 
@@ -985,25 +1379,75 @@ pub fn save_nav_graphs(world: &mut World) {
             level.models.clear();
             level.walls.clear();
         }
+        site.lights.clear();
 
         println!(
             "Saving all site nav graphs to {}",
             path.to_str().unwrap_or("<failed to render??>")
         );
-        let f = match std::fs::File::create(path) {
+        let f = match std::fs::File::create(&path) {
             Ok(f) => f,
             Err(err) => {
-                println!("Unable to save file: {err}");
+                notify_error(None, format!("Unable to save file: {err}"));
                 continue;
             }
         };
 
         match site.to_writer(f) {
             Ok(()) => {
-                println!("Save successful");
+                notify_info(None, format!("Nav graphs exported to {}", path.display()));
+            }
+            Err(err) => {
+                notify_error(None, format!("Nav graph export failed: {err}"));
+            }
+        }
+    }
+}
+
+pub fn export_supervisor_config(world: &mut World) {
+    let export_events: Vec<_> = world
+        .resource_mut::<Events<ExportSupervisorConfig>>()
+        .drain()
+        .collect();
+    for export_event in export_events {
+        world
+            .resource_mut::<Events<ValidateSite>>()
+            .send(ValidateSite(export_event.site));
+        let site = match generate_site(world, export_event.site) {
+            Ok(site) => site,
+            Err(err) => {
+                notify_error(None, format!("Unable to compile site: {err}"));
+                continue;
+            }
+        };
+
+        let config = match legacy::supervisor::SupervisorConfig::from_site(&site) {
+            Ok(config) => config,
+            Err(err) => {
+                notify_error(None, format!("Unable to export supervisor config: {err}"));
+                continue;
+            }
+        };
+
+        let path = export_event.to_file;
+        println!(
+            "Exporting supervisor config to {}",
+            path.to_str().unwrap_or("<failed to render??>")
+        );
+        let f = match std::fs::File::create(path) {
+            Ok(f) => f,
+            Err(err) => {
+                notify_error(None, format!("Unable to export supervisor config: {err}"));
+                continue;
+            }
+        };
+
+        match serde_yaml::to_writer(f, &config) {
+            Ok(()) => {
+                notify_info(None, "Export successful");
             }
             Err(err) => {
-                println!("Save failed: {err}");
+                notify_error(None, format!("Failed to export supervisor config: {err}"));
             }
         }
     }
@@ -0,0 +1,410 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{recency::RecencyRanking, site::*};
+use bevy::{ecs::system::SystemState, prelude::*};
+use std::collections::{HashMap, HashSet};
+
+/// How many change ticks may pass between two checks of the cache before it
+/// stops being trusted. Change detection cannot compare ticks that are too far
+/// apart, so after that long every level gets generated again.
+const MAX_CACHE_AGE: u32 = u32::MAX / 4;
+
+// These must cover every component that generate_level_contents reads
+type ChangedLevelElements = Query<
+    'static,
+    'static,
+    Entity,
+    Or<(
+        Changed<Anchor>,
+        Changed<Edge<Entity>>,
+        Changed<Point<Entity>>,
+        Changed<Path<Entity>>,
+        Changed<NameInSite>,
+        Changed<DoorType>,
+        Changed<AssetSource>,
+        Changed<Pose>,
+        Changed<PixelsPerMeter>,
+        Changed<Label>,
+        Changed<Texture>,
+        Changed<LightKind>,
+        Changed<LightGroup>,
+        Changed<Distance>,
+        Changed<IsStatic>,
+    )>,
+>;
+
+type ChangedLevelStructure = Query<
+    'static,
+    'static,
+    Entity,
+    Or<(
+        Changed<ConstraintDependents>,
+        Changed<Scale>,
+        Changed<ModelTags>,
+        Changed<MaterialOverride>,
+        Changed<LodPolicy>,
+        Changed<PhysicalCameraProperties>,
+        Changed<MeshPrimitive>,
+        Changed<SensorProperties>,
+        Changed<LevelProperties>,
+        Changed<DoorOccupancy>,
+        Changed<RecencyRanking<FloorMarker>>,
+        Changed<RecencyRanking<DrawingMarker>>,
+        Changed<SiteID>,
+        Changed<Parent>,
+        Changed<Children>,
+    )>,
+>;
+
+struct CachedLevel {
+    id: u32,
+    level: Level,
+    /// The saved elements of the level when it was generated. Elements that
+    /// stop being pending do not trigger change detection, so this is compared
+    /// to find them.
+    members: HashSet<Entity>,
+}
+
+/// Levels that were generated by an earlier save and have not changed since
+/// then. Saving a large site only needs to generate the levels that were
+/// edited, while the rest are taken from here.
+#[derive(Resource)]
+pub struct LevelSaveCache {
+    levels: HashMap<Entity, CachedLevel>,
+    changes: SystemState<(ChangedLevelElements, ChangedLevelStructure)>,
+    checked_at: u32,
+}
+
+impl FromWorld for LevelSaveCache {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            levels: HashMap::new(),
+            changes: SystemState::new(world),
+            checked_at: world.read_change_tick(),
+        }
+    }
+}
+
+impl LevelSaveCache {
+    /// Get the cached contents of a level if nothing in it has changed
+    pub fn get(&self, level: Entity, id: u32) -> Option<&Level> {
+        self.levels
+            .get(&level)
+            .filter(|cached| cached.id == id)
+            .map(|cached| &cached.level)
+    }
+
+    /// Remember the contents of a level that was just generated. Levels with
+    /// elements that are in the middle of being edited are not kept because
+    /// they are saved with the original values of those elements.
+    pub fn store(&mut self, world: &World, level: Entity, id: u32, contents: Level) {
+        match level_members(world, level) {
+            Some(members) => {
+                self.levels.insert(
+                    level,
+                    CachedLevel {
+                        id,
+                        level: contents,
+                        members,
+                    },
+                );
+            }
+            None => {
+                self.levels.remove(&level);
+            }
+        }
+    }
+
+    /// Forget every cached level, e.g. when SiteIDs were changed without
+    /// triggering change detection
+    pub fn clear(&mut self) {
+        self.levels.clear();
+    }
+
+    /// Drop every level that had something change since the last time this
+    /// was called
+    pub fn remove_changed(&mut self, world: &World) {
+        let tick = world.read_change_tick();
+        if tick.wrapping_sub(self.checked_at) > MAX_CACHE_AGE {
+            self.levels.clear();
+        }
+        self.checked_at = tick;
+
+        let (changed_elements, changed_structure) = self.changes.get(world);
+        let changed: Vec<Entity> = changed_elements
+            .iter()
+            .chain(changed_structure.iter())
+            .collect();
+
+        let mut stale = HashSet::new();
+        for e in changed {
+            // Changes count against the level that the element is saved in,
+            // which may be its grandparent in the case of sensors
+            let mut next = Some(e);
+            while let Some(e) = next {
+                if self.levels.contains_key(&e) {
+                    stale.insert(e);
+                    break;
+                }
+                next = world.get::<Parent>(e).map(|p| p.get());
+            }
+        }
+
+        for (level, cached) in &self.levels {
+            if world.get_entity(*level).is_none()
+                || level_members(world, *level).as_ref() != Some(&cached.members)
+            {
+                stale.insert(*level);
+            }
+        }
+
+        for level in stale {
+            self.levels.remove(&level);
+        }
+    }
+}
+
+/// Find the elements of a level that get saved, or None if any of them are
+/// being edited
+fn level_members(world: &World, level: Entity) -> Option<HashSet<Entity>> {
+    let mut members = HashSet::new();
+    let Some(children) = world.get::<Children>(level) else {
+        return Some(members);
+    };
+
+    let is_saved = |e: Entity| -> Option<bool> {
+        if world.get::<Original<Edge<Entity>>>(e).is_some()
+            || world.get::<Original<Point<Entity>>>(e).is_some()
+            || world.get::<Original<Path<Entity>>>(e).is_some()
+        {
+            return None;
+        }
+        Some(world.get::<Pending>(e).is_none())
+    };
+
+    for child in children {
+        if !is_saved(*child)? {
+            continue;
+        }
+        members.insert(*child);
+
+        // Sensors are attached to the models of the level
+        if let Some(model_children) = world.get::<Children>(*child) {
+            for sensor in model_children {
+                if world.get::<SensorProperties>(*sensor).is_some() && is_saved(*sensor)? {
+                    members.insert(*sensor);
+                }
+            }
+        }
+    }
+
+    Some(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::site::save::{generate_levels, renumber_site_ids, restore_site_ids};
+    use std::collections::BTreeMap;
+
+    struct TestSite {
+        world: World,
+        site: Entity,
+        level_a: Entity,
+        level_b: Entity,
+        anchor_a: Entity,
+        anchor_b: Entity,
+    }
+
+    const LEVEL_A: u32 = 1;
+    const LEVEL_B: u32 = 2;
+    const ANCHOR_A: u32 = 3;
+    const ANCHOR_B: u32 = 4;
+
+    fn spawn_child(world: &mut World, parent: Entity, bundle: impl Bundle) -> Entity {
+        let child = world.spawn(bundle).id();
+        world.entity_mut(parent).push_children(&[child]);
+        child
+    }
+
+    fn spawn_level(world: &mut World, site: Entity, id: u32) -> Entity {
+        let properties = LevelProperties {
+            name: format!("L{id}"),
+            elevation: 0.0,
+        };
+        spawn_child(world, site, (properties, SiteID(id)))
+    }
+
+    fn spawn_drawing(world: &mut World, level: Entity, id: u32) -> Entity {
+        let drawing = (
+            AssetSource::default(),
+            Pose::default(),
+            PixelsPerMeter::default(),
+            DrawingMarker,
+            SiteID(id),
+        );
+        spawn_child(world, level, drawing)
+    }
+
+    fn test_site() -> TestSite {
+        let mut world = World::new();
+        world.init_resource::<LevelSaveCache>();
+        let site = world.spawn_empty().id();
+        let level_a = spawn_level(&mut world, site, LEVEL_A);
+        let level_b = spawn_level(&mut world, site, LEVEL_B);
+        let anchor_a = spawn_child(
+            &mut world,
+            level_a,
+            (Anchor::Translate2D([0.0, 0.0]), SiteID(ANCHOR_A)),
+        );
+        let anchor_b = spawn_child(
+            &mut world,
+            level_b,
+            (Anchor::Translate2D([0.0, 0.0]), SiteID(ANCHOR_B)),
+        );
+        TestSite {
+            world,
+            site,
+            level_a,
+            level_b,
+            anchor_a,
+            anchor_b,
+        }
+    }
+
+    impl TestSite {
+        fn save(&mut self) -> BTreeMap<u32, Level> {
+            generate_levels(&mut self.world, self.site).unwrap()
+        }
+
+        fn is_cached(&self, level: Entity, id: u32) -> bool {
+            self.world
+                .resource::<LevelSaveCache>()
+                .get(level, id)
+                .is_some()
+        }
+
+        /// Change an anchor without triggering change detection, so only a
+        /// regenerated level will show the new position
+        fn move_quietly(&mut self, anchor: Entity, position: [f32; 2]) {
+            let mut a = self.world.get_mut::<Anchor>(anchor).unwrap();
+            *a.bypass_change_detection() = Anchor::Translate2D(position);
+        }
+    }
+
+    fn anchor_position(levels: &BTreeMap<u32, Level>, level: u32, anchor: u32) -> [f32; 2] {
+        match &levels[&level].anchors[&anchor] {
+            Anchor::Translate2D(p) => *p,
+            other => panic!("unexpected anchor {other:?}"),
+        }
+    }
+
+    #[test]
+    fn only_edited_levels_are_regenerated() {
+        let mut site = test_site();
+        site.save();
+        assert!(site.is_cached(site.level_a, LEVEL_A));
+        assert!(site.is_cached(site.level_b, LEVEL_B));
+
+        site.move_quietly(site.anchor_b, [2.0, 2.0]);
+        *site.world.get_mut::<Anchor>(site.anchor_a).unwrap() = Anchor::Translate2D([1.0, 1.0]);
+        let levels = site.save();
+        assert_eq!(anchor_position(&levels, LEVEL_A, ANCHOR_A), [1.0, 1.0]);
+        // Level B was taken from the cache, so the quiet change is missing
+        assert_eq!(anchor_position(&levels, LEVEL_B, ANCHOR_B), [0.0, 0.0]);
+        assert!(site.is_cached(site.level_a, LEVEL_A));
+        assert!(site.is_cached(site.level_b, LEVEL_B));
+    }
+
+    #[test]
+    fn elements_that_stop_being_pending_are_saved() {
+        let mut site = test_site();
+        let drawing = spawn_drawing(&mut site.world, site.level_b, 5);
+        site.world.entity_mut(drawing).insert(Pending);
+        let levels = site.save();
+        assert!(levels[&LEVEL_B].drawings.is_empty());
+
+        // Removing a component does not trigger change detection
+        site.move_quietly(site.anchor_a, [1.0, 1.0]);
+        site.world.entity_mut(drawing).remove::<Pending>();
+        let levels = site.save();
+        assert!(levels[&LEVEL_B].drawings.contains_key(&5));
+        assert_eq!(anchor_position(&levels, LEVEL_A, ANCHOR_A), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn despawned_elements_and_levels_are_dropped() {
+        let mut site = test_site();
+        site.save();
+
+        site.move_quietly(site.anchor_a, [1.0, 1.0]);
+        site.world.entity_mut(site.anchor_b).despawn_recursive();
+        let levels = site.save();
+        assert!(levels[&LEVEL_B].anchors.is_empty());
+        assert_eq!(anchor_position(&levels, LEVEL_A, ANCHOR_A), [0.0, 0.0]);
+
+        site.world.entity_mut(site.level_b).despawn_recursive();
+        let levels = site.save();
+        assert!(!levels.contains_key(&LEVEL_B));
+        assert!(!site.is_cached(site.level_b, LEVEL_B));
+    }
+
+    #[test]
+    fn renumbered_saves_do_not_use_or_fill_the_cache() {
+        let mut site = test_site();
+        // Renumbering closes the gap before this anchor while the levels keep
+        // their IDs, so only the contents of level B get new IDs
+        spawn_child(&mut site.world, site.site, SiteID(0));
+        spawn_child(
+            &mut site.world,
+            site.level_b,
+            (Anchor::Translate2D([0.0, 0.0]), SiteID(10)),
+        );
+        let levels = site.save();
+        assert!(levels[&LEVEL_B].anchors.contains_key(&10));
+
+        let previous = renumber_site_ids(&mut site.world, site.site);
+        let levels = site.save();
+        restore_site_ids(&mut site.world, site.site, previous);
+        assert!(levels[&LEVEL_B].anchors.contains_key(&5));
+        assert!(!levels[&LEVEL_B].anchors.contains_key(&10));
+
+        let levels = site.save();
+        assert!(levels[&LEVEL_B].anchors.contains_key(&10));
+        assert!(!levels[&LEVEL_B].anchors.contains_key(&5));
+    }
+
+    #[test]
+    fn levels_with_elements_being_edited_are_not_cached() {
+        let mut site = test_site();
+        let pending = spawn_drawing(&mut site.world, site.level_a, 5);
+        site.world.entity_mut(pending).insert(Pending);
+        let point = Point(site.anchor_a);
+        spawn_child(&mut site.world, site.level_b, (point, Original(point)));
+
+        assert_eq!(
+            level_members(&site.world, site.level_a),
+            Some(HashSet::from([site.anchor_a])),
+        );
+        assert_eq!(level_members(&site.world, site.level_b), None);
+
+        site.save();
+        assert!(site.is_cached(site.level_a, LEVEL_A));
+        assert!(!site.is_cached(site.level_b, LEVEL_B));
+    }
+}
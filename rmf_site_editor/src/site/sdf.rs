@@ -15,34 +15,60 @@
  *
 */
 
+use crate::console::report_warning;
 use bevy::prelude::*;
 use bevy::render::mesh::shape::{Capsule, UVSphere};
 
-use crate::interaction::Selectable;
-use crate::shapes::make_cylinder;
-use crate::site::SiteAssets;
+use crate::interaction::{DragPlaneBundle, Selectable};
+use crate::shapes::{make_bottom_circle, make_cone, make_cylinder, Circle};
+use crate::site::{
+    model_primitive_material, ModelCollisionGeometry, ModelMeshes, ModelVisualGeometry, SiteAssets,
+};
 use crate::SdfRoot;
 use sdformat_rs::{SdfGeometry, SdfPose, Vector3d};
+use std::path::Path;
 
 use rmf_site_format::{
-    Angle, AssetSource, ConstraintDependents, Geometry, IsStatic, MeshPrimitive, Model,
-    ModelMarker, NameInSite, Pose, Rotation, Scale, WorkcellCollisionMarker, WorkcellVisualMarker,
+    Angle, AssetSource, ConstraintDependents, Geometry, IsStatic, LodPolicy, MaterialOverride,
+    MeshPrimitive, Model, ModelMarker, ModelTags, NameInSite, Pending, Pose, PrimitiveShapeMarker,
+    Rotation, Scale, WorkcellCollisionMarker, WorkcellVisualMarker,
 };
 
 // TODO(luca) reduce chances for panic and do proper error handling here
 fn compute_model_source(path: &str, uri: &str) -> AssetSource {
-    let binding = path.strip_prefix("search://").unwrap();
-    if let Some(stripped) = uri.strip_prefix("model://") {
-        // Get the org name from context, model name from this and combine
-        let org_name = binding.split("/").next().unwrap();
-        let path = org_name.to_owned() + "/" + stripped;
-        AssetSource::Remote(path)
-    } else if let Some(path_idx) = binding.rfind("/") {
-        // It's a path relative to this model, remove file and append uri
-        let (model_path, _model_name) = binding.split_at(path_idx);
-        AssetSource::Remote(model_path.to_owned() + "/" + uri)
-    } else {
-        AssetSource::Remote("".into())
+    match AssetSource::from(&path.to_owned()) {
+        AssetSource::Search(binding) | AssetSource::Remote(binding) => {
+            if let Some(stripped) = uri.strip_prefix("model://") {
+                // Get the org name from context, model name from this and combine
+                let org_name = binding.split("/").next().unwrap();
+                let path = org_name.to_owned() + "/" + stripped;
+                AssetSource::Remote(path)
+            } else if let Some(path_idx) = binding.rfind("/") {
+                // It's a path relative to this model, remove file and append uri
+                let (model_path, _model_name) = binding.split_at(path_idx);
+                AssetSource::Remote(model_path.to_owned() + "/" + uri)
+            } else {
+                AssetSource::Remote("".into())
+            }
+        }
+        AssetSource::Local(filename) => {
+            let model_dir = Path::new(&filename).parent().unwrap_or(Path::new(""));
+            let path = if let Some(stripped) = uri.strip_prefix("model://") {
+                // Other models are expected to be in the same directory as
+                // the folder of this model
+                model_dir.parent().unwrap_or(Path::new("")).join(stripped)
+            } else {
+                model_dir.join(uri)
+            };
+            AssetSource::Local(path.to_string_lossy().into_owned())
+        }
+        _ => {
+            report_warning(
+                None,
+                format!("Unable to find {uri} relative to the model {path}"),
+            );
+            AssetSource::Remote("".into())
+        }
     }
 }
 
@@ -73,6 +99,75 @@ fn parse_pose(pose: &Option<SdfPose>) -> Pose {
     }
 }
 
+fn spawn_sdf_geometry(
+    commands: &mut Commands,
+    sdf: &SdfRoot,
+    name: &str,
+    geometry: &SdfGeometry,
+    pose: Pose,
+) -> Option<Entity> {
+    match geometry {
+        SdfGeometry::Mesh(mesh) => Some(
+            commands
+                .spawn(Model {
+                    name: NameInSite(name.to_owned()),
+                    source: compute_model_source(&sdf.path, &mesh.uri),
+                    pose,
+                    is_static: IsStatic(sdf.model.r#static.unwrap_or(false)),
+                    constraints: ConstraintDependents::default(),
+                    scale: parse_scale(&mesh.scale),
+                    tags: ModelTags::default(),
+                    material: MaterialOverride::default(),
+                    lod: LodPolicy::default(),
+                    marker: ModelMarker,
+                })
+                .id(),
+        ),
+        SdfGeometry::Box(b) => {
+            let s = &b.size.0;
+            Some(
+                commands
+                    .spawn(MeshPrimitive::Box {
+                        size: [s.x as f32, s.y as f32, s.z as f32],
+                    })
+                    .insert(pose)
+                    .insert(SpatialBundle::VISIBLE_IDENTITY)
+                    .id(),
+            )
+        }
+        SdfGeometry::Capsule(c) => Some(
+            commands
+                .spawn(MeshPrimitive::Capsule {
+                    radius: c.radius as f32,
+                    length: c.length as f32,
+                })
+                .insert(pose)
+                .insert(SpatialBundle::VISIBLE_IDENTITY)
+                .id(),
+        ),
+        SdfGeometry::Cylinder(c) => Some(
+            commands
+                .spawn(MeshPrimitive::Cylinder {
+                    radius: c.radius as f32,
+                    length: c.length as f32,
+                })
+                .insert(pose)
+                .insert(SpatialBundle::VISIBLE_IDENTITY)
+                .id(),
+        ),
+        SdfGeometry::Sphere(s) => Some(
+            commands
+                .spawn(MeshPrimitive::Sphere {
+                    radius: s.radius as f32,
+                })
+                .insert(pose)
+                .insert(SpatialBundle::VISIBLE_IDENTITY)
+                .id(),
+        ),
+        _ => None,
+    }
+}
+
 // TODO(luca) reduce duplication between sdf -> MeshPrimitive and urdf -> MeshPrimitive
 pub fn handle_new_sdf_roots(mut commands: Commands, new_sdfs: Query<(Entity, &SdfRoot)>) {
     for (e, sdf) in new_sdfs.iter() {
@@ -82,78 +177,78 @@ pub fn handle_new_sdf_roots(mut commands: Commands, new_sdfs: Query<(Entity, &Sd
                 .spawn(SpatialBundle::from_transform(link_pose.transform()))
                 .id();
             commands.entity(e).add_child(link_id);
+            // Visuals and collisions are kept in separate groups so that
+            // either can be hidden, see CollisionDisplay.
+            let visuals_id = commands
+                .spawn((SpatialBundle::VISIBLE_IDENTITY, ModelVisualGeometry))
+                .id();
+            let collisions_id = commands
+                .spawn((SpatialBundle::INVISIBLE_IDENTITY, ModelCollisionGeometry))
+                .id();
+            commands
+                .entity(link_id)
+                .push_children(&[visuals_id, collisions_id]);
             for visual in &link.visual {
                 let pose = parse_pose(&visual.pose);
-                let id = match &visual.geometry {
-                    SdfGeometry::Mesh(mesh) => Some(
-                        commands
-                            .spawn(Model {
-                                name: NameInSite(visual.name.clone()),
-                                source: compute_model_source(&sdf.path, &mesh.uri),
-                                pose,
-                                is_static: IsStatic(sdf.model.r#static.unwrap_or(false)),
-                                constraints: ConstraintDependents::default(),
-                                scale: parse_scale(&mesh.scale),
-                                marker: ModelMarker,
-                            })
-                            .id(),
-                    ),
-                    SdfGeometry::Box(b) => {
-                        let s = &b.size.0;
-                        Some(
-                            commands
-                                .spawn(MeshPrimitive::Box {
-                                    size: [s.x as f32, s.y as f32, s.z as f32],
-                                })
-                                .insert(pose)
-                                .insert(SpatialBundle::VISIBLE_IDENTITY)
-                                .id(),
-                        )
+                match spawn_sdf_geometry(&mut commands, sdf, &visual.name, &visual.geometry, pose) {
+                    Some(id) => {
+                        commands.entity(visuals_id).add_child(id);
                     }
-                    SdfGeometry::Capsule(c) => Some(
-                        commands
-                            .spawn(MeshPrimitive::Capsule {
-                                radius: c.radius as f32,
-                                length: c.length as f32,
-                            })
-                            .insert(pose)
-                            .insert(SpatialBundle::VISIBLE_IDENTITY)
-                            .id(),
+                    None => report_warning(
+                        None,
+                        format!("Found unhandled geometry type {:?}", &visual.geometry),
                     ),
-                    SdfGeometry::Cylinder(c) => Some(
-                        commands
-                            .spawn(MeshPrimitive::Cylinder {
-                                radius: c.radius as f32,
-                                length: c.length as f32,
-                            })
-                            .insert(pose)
-                            .insert(SpatialBundle::VISIBLE_IDENTITY)
-                            .id(),
-                    ),
-                    SdfGeometry::Sphere(s) => Some(
-                        commands
-                            .spawn(MeshPrimitive::Sphere {
-                                radius: s.radius as f32,
-                            })
-                            .insert(pose)
-                            .insert(SpatialBundle::VISIBLE_IDENTITY)
-                            .id(),
-                    ),
-                    _ => None,
-                };
-                match id {
+                }
+            }
+            for collision in &link.collision {
+                let pose = parse_pose(&collision.pose);
+                match spawn_sdf_geometry(
+                    &mut commands,
+                    sdf,
+                    &collision.name,
+                    &collision.geometry,
+                    pose,
+                ) {
                     Some(id) => {
-                        commands.entity(link_id).add_child(id);
+                        commands.entity(collisions_id).add_child(id);
                     }
-                    None => println!("Found unhandled geometry type {:?}", &visual.geometry),
+                    None => report_warning(
+                        None,
+                        format!("Found unhandled geometry type {:?}", &collision.geometry),
+                    ),
                 }
             }
-            // TODO(luca) parse and display collisions
         }
         commands.entity(e).remove::<SdfRoot>();
     }
 }
 
+pub fn make_primitive_mesh(primitive: &MeshPrimitive) -> Mesh {
+    match primitive {
+        MeshPrimitive::Box { size } => Mesh::from(shape::Box::new(size[0], size[1], size[2])),
+        MeshPrimitive::Cylinder { radius, length } => Mesh::from(make_cylinder(*length, *radius)),
+        MeshPrimitive::Capsule { radius, length } => Mesh::from(Capsule {
+            radius: *radius,
+            depth: *length,
+            ..default()
+        }),
+        MeshPrimitive::Sphere { radius } => Mesh::from(UVSphere {
+            radius: *radius,
+            ..default()
+        }),
+        MeshPrimitive::Cone { radius, length } => {
+            let base = Circle {
+                radius: *radius,
+                height: -length / 2.0,
+            };
+            Mesh::from(
+                make_cone(base, [0.0, 0.0, length / 2.0], 32)
+                    .merge_with(make_bottom_circle(base, 32)),
+            )
+        }
+    }
+}
+
 pub fn handle_new_mesh_primitives(
     mut commands: Commands,
     primitives: Query<(Entity, &MeshPrimitive), Added<MeshPrimitive>>,
@@ -166,35 +261,30 @@ pub fn handle_new_mesh_primitives(
             With<WorkcellCollisionMarker>,
         )>,
     >,
+    collisions: Query<(), Or<(With<ModelCollisionGeometry>, With<WorkcellCollisionMarker>)>>,
+    shapes: Query<(), (With<PrimitiveShapeMarker>, Without<Pending>)>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut model_meshes: ResMut<ModelMeshes>,
     site_assets: Res<SiteAssets>,
 ) {
     for (e, primitive) in primitives.iter() {
-        let mesh = match primitive {
-            MeshPrimitive::Box { size } => Mesh::from(shape::Box::new(size[0], size[1], size[2])),
-            MeshPrimitive::Cylinder { radius, length } => {
-                Mesh::from(make_cylinder(*length, *radius))
-            }
-            MeshPrimitive::Capsule { radius, length } => Mesh::from(Capsule {
-                radius: *radius,
-                depth: *length,
-                ..default()
-            }),
-            MeshPrimitive::Sphere { radius } => Mesh::from(UVSphere {
-                radius: *radius,
-                ..default()
-            }),
-        };
+        let make_mesh = || make_primitive_mesh(primitive);
         // Parent is the first of ModelMarker and / or WorkcellVisualMarker or
         // WorkcelLCollisionMarker
         let child_id = commands
             .spawn(PbrBundle {
-                mesh: meshes.add(mesh),
-                material: site_assets.default_mesh_grey_material.clone(),
+                mesh: model_meshes.primitive(primitive, make_mesh, &mut meshes),
+                material: model_primitive_material(e, &parents, &collisions, &site_assets),
                 ..default()
             })
             .id();
-        if let Some(selectable) = AncestorIter::new(&parents, e)
+        if shapes.contains(e) {
+            // Primitive shapes of a site are elements of their own
+            commands
+                .entity(child_id)
+                .insert(Selectable::new(e))
+                .insert(DragPlaneBundle::new(e, Vec3::Z));
+        } else if let Some(selectable) = AncestorIter::new(&parents, e)
             .filter_map(|p| selectables.get(p).ok())
             .last()
         {
@@ -203,3 +293,20 @@ pub fn handle_new_mesh_primitives(
         commands.entity(e).push_children(&[child_id]);
     }
 }
+
+/// Rebuild the meshes of primitives whose dimensions have been edited
+pub fn update_changed_mesh_primitives(
+    primitives: Query<(&MeshPrimitive, &Children), Changed<MeshPrimitive>>,
+    mut mesh_handles: Query<&mut Handle<Mesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (primitive, children) in &primitives {
+        for child in children {
+            if let Ok(mut handle) = mesh_handles.get_mut(*child) {
+                // The edited mesh is not shared through ModelMeshes, otherwise
+                // every intermediate size would be kept alive while dragging.
+                *handle = meshes.add(make_primitive_mesh(primitive));
+            }
+        }
+    }
+}
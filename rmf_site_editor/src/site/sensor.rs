@@ -0,0 +1,101 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{interaction::Selectable, site::*, AppState};
+use bevy::prelude::*;
+use rmf_site_format::{NameInSite, NameInWorkcell, Pose, PreviewableMarker, SensorProperties};
+
+/// Attach a new sensor to a workcell frame or a site model
+pub struct AddSensor {
+    pub parent: Entity,
+    pub properties: SensorProperties,
+}
+
+pub fn handle_add_sensors(
+    mut commands: Commands,
+    mut requests: EventReader<AddSensor>,
+    mut dependents: Query<&mut Dependents>,
+    app_state: Res<State<AppState>>,
+) {
+    for request in requests.iter() {
+        let name = request.properties.label().to_lowercase().replace(' ', "_");
+        let mut sensor = commands.spawn((request.properties.clone(), Pose::default()));
+        let sensor = if *app_state.current() == AppState::WorkcellEditor {
+            sensor.insert(NameInWorkcell(name)).id()
+        } else {
+            sensor.insert(NameInSite(name)).id()
+        };
+        // Elements of a workcell are dependents of their parents
+        if let Ok(mut deps) = dependents.get_mut(request.parent) {
+            deps.insert(sensor);
+        } else if *app_state.current() == AppState::WorkcellEditor {
+            commands
+                .entity(request.parent)
+                .insert(Dependents::single(sensor));
+        }
+        commands.entity(request.parent).add_child(sensor);
+    }
+}
+
+/// Give sensors a visual so they can be seen and selected. Sensors that
+/// produce images also get a camera, so they can be previewed the same way
+/// as physical cameras.
+pub fn add_sensor_visuals(
+    mut commands: Commands,
+    sensors: Query<(Entity, &Pose, &SensorProperties), Added<SensorProperties>>,
+    assets: Res<SiteAssets>,
+) {
+    for (e, pose, properties) in &sensors {
+        commands
+            .entity(e)
+            .insert(PbrBundle {
+                mesh: assets.physical_camera_mesh.clone(),
+                material: assets.physical_camera_material.clone(),
+                transform: pose.transform(),
+                ..default()
+            })
+            .insert(Selectable::new(e))
+            .insert(Category::Sensor);
+
+        if properties.camera().is_none() {
+            continue;
+        }
+        // Same orientation as the camera of a physical camera
+        let camera_sensor_transform = Pose {
+            trans: [0., 0., 0.],
+            rot: Rotation::EulerExtrinsicXYZ([
+                Angle::Deg(90.0),
+                Angle::Deg(0.0),
+                Angle::Deg(-90.0),
+            ]),
+        };
+        let child = commands
+            .spawn(Camera3dBundle {
+                transform: camera_sensor_transform.transform(),
+                camera: Camera {
+                    is_active: false,
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+        commands
+            .entity(e)
+            .insert(PreviewableMarker)
+            .push_children(&[child]);
+    }
+}
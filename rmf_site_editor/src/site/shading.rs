@@ -0,0 +1,111 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use bevy::{pbr::wireframe::Wireframe, prelude::*};
+use rmf_site_format::{FloorMarker, ModelMarker, WallMarker};
+
+/// How the surfaces of models, floors, and walls are drawn in the viewport.
+/// Wireframes are useful for checking the meshes of imported assets and of
+/// generated floors.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShadingMode {
+    #[default]
+    Shaded,
+    ShadedWireframe,
+    Wireframe,
+}
+
+impl ShadingMode {
+    pub const ALL: [ShadingMode; 3] = [
+        ShadingMode::Shaded,
+        ShadingMode::ShadedWireframe,
+        ShadingMode::Wireframe,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Shaded => "Shaded",
+            Self::ShadedWireframe => "Shaded with Wireframe",
+            Self::Wireframe => "Wireframe",
+        }
+    }
+
+    pub fn shows_surfaces(&self) -> bool {
+        matches!(self, Self::Shaded | Self::ShadedWireframe)
+    }
+
+    pub fn shows_wireframe(&self) -> bool {
+        matches!(self, Self::ShadedWireframe | Self::Wireframe)
+    }
+}
+
+/// Holds the material of a mesh while its surface is hidden by the wireframe
+/// shading mode. A mesh without a material only gets drawn by the wireframe
+/// pipeline, and it can still be picked.
+#[derive(Component, Clone, Debug)]
+pub struct HiddenSurface(pub Handle<StandardMaterial>);
+
+pub fn update_shading_mode(
+    mut commands: Commands,
+    mode: Res<ShadingMode>,
+    all_meshes: Query<Entity, With<Handle<Mesh>>>,
+    new_meshes: Query<Entity, Added<Handle<Mesh>>>,
+    new_materials: Query<Entity, (With<Handle<Mesh>>, Changed<Handle<StandardMaterial>>)>,
+    materials: Query<&Handle<StandardMaterial>>,
+    hidden: Query<&HiddenSurface>,
+    parents: Query<&Parent>,
+    shaded: Query<(), Or<(With<ModelMarker>, With<FloorMarker>, With<WallMarker>)>>,
+) {
+    let entities: Vec<Entity> = if mode.is_changed() {
+        all_meshes.iter().collect()
+    } else if *mode != ShadingMode::Shaded {
+        // Meshes and materials may be replaced at any time, e.g. when a
+        // floor is reshaped, so keep new ones in line with the mode
+        new_meshes.iter().chain(new_materials.iter()).collect()
+    } else {
+        return;
+    };
+
+    for e in entities {
+        let affected = std::iter::once(e)
+            .chain(AncestorIter::new(&parents, e))
+            .any(|a| shaded.contains(a));
+        if !affected {
+            continue;
+        }
+
+        if mode.shows_wireframe() {
+            commands.entity(e).insert(Wireframe);
+        } else {
+            commands.entity(e).remove::<Wireframe>();
+        }
+
+        if mode.shows_surfaces() {
+            if let Ok(surface) = hidden.get(e) {
+                if !materials.contains(e) {
+                    commands.entity(e).insert(surface.0.clone());
+                }
+                commands.entity(e).remove::<HiddenSurface>();
+            }
+        } else if let Ok(material) = materials.get(e) {
+            commands
+                .entity(e)
+                .insert(HiddenSurface(material.clone()))
+                .remove::<Handle<StandardMaterial>>();
+        }
+    }
+}
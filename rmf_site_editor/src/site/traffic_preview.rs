@@ -0,0 +1,352 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::console::{notify_error, notify_warning};
+use crate::site::*;
+use bevy::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+
+pub const VIRTUAL_ROBOT_RADIUS: f32 = 0.3;
+pub const VIRTUAL_ROBOT_HEIGHT: f32 = 0.2;
+/// Speed used by virtual robots on lanes that do not have a speed limit
+pub const DEFAULT_VIRTUAL_ROBOT_SPEED: f32 = 0.5;
+/// How long a virtual robot can wait for an anchor to be freed before it is
+/// considered to be blocked
+pub const VIRTUAL_ROBOT_BLOCKED_TIME: f32 = 5.0;
+
+/// Start or stop a traffic preview where virtual robots drive around a graph
+#[derive(Clone, Copy, Debug)]
+pub enum PreviewTraffic {
+    Start { graph: Entity, robots: usize },
+    Stop,
+}
+
+/// A lane that a virtual robot can drive along, leaving from some anchor
+#[derive(Clone, Copy, Debug)]
+struct TrafficLane {
+    to: Entity,
+    speed: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct TrafficPreview {
+    /// The graph that is being previewed, if a preview is running
+    pub graph: Option<Entity>,
+    /// How many robots have been waiting longer than
+    /// [`VIRTUAL_ROBOT_BLOCKED_TIME`] to move forward
+    pub blocked: usize,
+    robots: Vec<Entity>,
+    lanes: HashMap<Entity, Vec<TrafficLane>>,
+    seed: u64,
+}
+
+impl TrafficPreview {
+    /// A small xorshift generator so the preview is repeatable and does not
+    /// need a dependency on rand.
+    fn next_random(&mut self) -> usize {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed as usize
+    }
+
+    /// Choose the next lane to drive along after arriving at an anchor,
+    /// avoiding turning around unless it is the only option.
+    fn choose_lane(&mut self, at: Entity, came_from: Option<Entity>) -> Option<TrafficLane> {
+        let options: Vec<TrafficLane> = self.lanes.get(&at)?.clone();
+        let forward: Vec<TrafficLane> = options
+            .iter()
+            .filter(|lane| Some(lane.to) != came_from)
+            .copied()
+            .collect();
+        let options = if forward.is_empty() { options } else { forward };
+        if options.is_empty() {
+            return None;
+        }
+        let choice = self.next_random() % options.len();
+        Some(options[choice])
+    }
+}
+
+/// A simple marker that drives along the lanes of a graph during a traffic
+/// preview. A robot reserves the anchor that it is driving towards, and will
+/// wait at its current anchor until its next anchor is free.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct VirtualRobot {
+    pub from: Entity,
+    pub to: Entity,
+    /// How far along the current lane the robot has driven, in meters
+    pub progress: f32,
+    pub speed: f32,
+    pub moving: bool,
+    /// How long the robot has been waiting to start moving, in seconds
+    pub waiting: f32,
+    pub level: Option<Entity>,
+}
+
+impl VirtualRobot {
+    /// The anchor that this robot is currently keeping other robots out of
+    fn reserved(&self) -> Entity {
+        if self.moving {
+            self.to
+        } else {
+            self.from
+        }
+    }
+}
+
+fn level_of(
+    anchor: Entity,
+    parents: &Query<&Parent, With<Anchor>>,
+    levels: &Query<(), With<LevelProperties>>,
+) -> Option<Entity> {
+    parents
+        .get(anchor)
+        .ok()
+        .map(|p| p.get())
+        .filter(|p| levels.contains(*p))
+}
+
+pub fn update_traffic_preview(
+    mut commands: Commands,
+    mut requests: EventReader<PreviewTraffic>,
+    mut preview: ResMut<TrafficPreview>,
+    lanes: Query<
+        (
+            &Edge<Entity>,
+            &Motion,
+            &ReverseLane,
+            &AssociatedGraphs<Entity>,
+        ),
+        (With<LaneMarker>, Without<Pending>),
+    >,
+    graphs: Query<&LaneDefaults, With<NavGraphMarker>>,
+    anchors: Query<&GlobalTransform, With<Anchor>>,
+    parents: Query<&Parent, With<Anchor>>,
+    levels: Query<(), With<LevelProperties>>,
+    assets: Res<SiteAssets>,
+) {
+    let Some(request) = requests.iter().last() else {
+        return;
+    };
+
+    for e in preview.robots.drain(..) {
+        commands.entity(e).despawn_recursive();
+    }
+    preview.graph = None;
+    preview.blocked = 0;
+    preview.lanes.clear();
+
+    let (graph, robot_count) = match request {
+        PreviewTraffic::Start { graph, robots } => (*graph, *robots),
+        PreviewTraffic::Stop => return,
+    };
+
+    let Ok(defaults) = graphs.get(graph) else {
+        notify_error(
+            Some(graph),
+            format!("Cannot preview traffic for {graph:?} because it is not a graph"),
+        );
+        return;
+    };
+
+    let speed_of = |motion: &Motion| {
+        defaults
+            .apply_to(motion)
+            .speed_limit
+            .filter(|s| *s > 0.0)
+            .unwrap_or(DEFAULT_VIRTUAL_ROBOT_SPEED)
+    };
+    for (edge, motion, reverse, associated) in &lanes {
+        if !associated.includes(graph) {
+            continue;
+        }
+
+        preview
+            .lanes
+            .entry(edge.start())
+            .or_default()
+            .push(TrafficLane {
+                to: edge.end(),
+                speed: speed_of(motion),
+            });
+        let reverse_speed = match reverse {
+            ReverseLane::Same => Some(speed_of(motion)),
+            ReverseLane::Different(motion) => Some(speed_of(motion)),
+            ReverseLane::Disable => None,
+        };
+        if let Some(speed) = reverse_speed {
+            preview
+                .lanes
+                .entry(edge.end())
+                .or_default()
+                .push(TrafficLane {
+                    to: edge.start(),
+                    speed,
+                });
+        }
+    }
+
+    // Spread the robots across distinct anchors so that they do not start
+    // out blocking each other.
+    let mut starts: Vec<Entity> = preview
+        .lanes
+        .keys()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if robot_count > starts.len() {
+        notify_warning(
+            Some(graph),
+            format!(
+                "Only {} robot(s) can fit on the graph for the traffic preview",
+                starts.len()
+            ),
+        );
+    }
+
+    preview.seed = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..robot_count.min(starts.len()) {
+        let from = starts.remove(preview.next_random() % starts.len());
+        let Some(lane) = preview.choose_lane(from, None) else {
+            continue;
+        };
+        let Ok(tf) = anchors.get(from) else {
+            continue;
+        };
+
+        let robot = commands
+            .spawn(PbrBundle {
+                mesh: assets.virtual_robot_mesh.clone(),
+                material: assets.virtual_robot_material.clone(),
+                transform: Transform::from_translation(tf.translation()),
+                ..default()
+            })
+            .insert(VirtualRobot {
+                from,
+                to: lane.to,
+                progress: 0.0,
+                speed: lane.speed,
+                moving: false,
+                waiting: 0.0,
+                level: level_of(from, &parents, &levels),
+            })
+            .id();
+        preview.robots.push(robot);
+    }
+
+    preview.graph = Some(graph);
+}
+
+pub fn move_virtual_robots(
+    mut robots: Query<(
+        Entity,
+        &mut VirtualRobot,
+        &mut Transform,
+        &mut Handle<StandardMaterial>,
+    )>,
+    mut preview: ResMut<TrafficPreview>,
+    anchors: Query<&GlobalTransform, With<Anchor>>,
+    parents: Query<&Parent, With<Anchor>>,
+    levels: Query<(), With<LevelProperties>>,
+    assets: Res<SiteAssets>,
+    time: Res<Time>,
+) {
+    if preview.graph.is_none() {
+        return;
+    }
+
+    let dt = time.delta_seconds();
+    let mut reserved: HashMap<Entity, Entity> = robots
+        .iter()
+        .map(|(e, robot, _, _)| (robot.reserved(), e))
+        .collect();
+
+    let mut blocked = 0;
+    for (e, mut robot, mut tf, mut material) in &mut robots {
+        let (Ok(p0), Ok(p1)) = (anchors.get(robot.from), anchors.get(robot.to)) else {
+            continue;
+        };
+        let (p0, p1) = (p0.translation(), p1.translation());
+        let length = (p1 - p0).length();
+
+        if robot.moving {
+            robot.progress += robot.speed * dt;
+            if robot.progress >= length {
+                // The robot has arrived, so decide where it should go next
+                let came_from = robot.from;
+                robot.from = robot.to;
+                robot.progress = 0.0;
+                robot.moving = false;
+                robot.level = level_of(robot.from, &parents, &levels).or(robot.level);
+                if let Some(lane) = preview.choose_lane(robot.from, Some(came_from)) {
+                    robot.to = lane.to;
+                    robot.speed = lane.speed;
+                }
+            }
+        } else if robot.to != robot.from {
+            match reserved.get(&robot.to) {
+                Some(other) if *other != e => {
+                    robot.waiting += dt;
+                }
+                _ => {
+                    reserved.remove(&robot.from);
+                    reserved.insert(robot.to, e);
+                    robot.moving = true;
+                    robot.waiting = 0.0;
+                }
+            }
+        }
+
+        let s = if length > 0.0 {
+            (robot.progress / length).min(1.0)
+        } else {
+            0.0
+        };
+        tf.translation = p0.lerp(p1, s);
+
+        let is_blocked = robot.waiting > VIRTUAL_ROBOT_BLOCKED_TIME;
+        if is_blocked {
+            blocked += 1;
+        }
+        let target = if is_blocked {
+            &assets.blocked_robot_material
+        } else {
+            &assets.virtual_robot_material
+        };
+        if *material != *target {
+            *material = target.clone();
+        }
+    }
+
+    if preview.blocked != blocked {
+        preview.blocked = blocked;
+    }
+}
+
+pub fn update_virtual_robot_visibility(
+    mut robots: Query<(&VirtualRobot, &mut Visibility)>,
+    current_level: Res<CurrentLevel>,
+) {
+    for (robot, mut visibility) in &mut robots {
+        let is_visible = robot.level.is_none() || robot.level == **current_level;
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+    }
+}
@@ -0,0 +1,349 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::UnsavedChanges;
+use bevy::{prelude::*, utils::HashMap};
+use std::{any::TypeId, sync::Arc};
+
+/// The most changes that will be kept in the undo history
+pub const MAX_UNDO_HISTORY: usize = 100;
+
+type ApplyChange = Arc<dyn Fn(&mut Commands) + Send + Sync>;
+
+/// A change to one component of some elements that can be undone and redone.
+/// Batch edits change the same component of several elements at once.
+#[derive(Clone)]
+pub struct UndoEntry {
+    pub description: String,
+    /// The elements that were changed, in the order they were changed
+    pub elements: Vec<Entity>,
+    component: TypeId,
+    /// Applied in reverse order to undo the change
+    undo: Vec<ApplyChange>,
+    redo: Vec<ApplyChange>,
+}
+
+impl UndoEntry {
+    pub fn new<T: Component + Clone>(element: Entity, from_value: T, to_value: T) -> Self {
+        let set = |value: T| -> ApplyChange {
+            Arc::new(move |commands: &mut Commands| {
+                // The element may have been deleted since the change was made
+                if let Some(mut entity) = commands.get_entity(element) {
+                    entity.insert(value.clone());
+                }
+            })
+        };
+        Self {
+            description: format!("Change {}", short_type_name::<T>()),
+            elements: vec![element],
+            component: TypeId::of::<T>(),
+            undo: vec![set(from_value)],
+            redo: vec![set(to_value)],
+        }
+    }
+
+    /// Combine changes to the same component that were applied together,
+    /// such as the changes of one batch edit, so they are undone in one step
+    pub fn combine(entries: impl IntoIterator<Item = UndoEntry>) -> Option<Self> {
+        let mut entries = entries.into_iter();
+        let mut combined = entries.next()?;
+        for entry in entries {
+            for e in entry.elements {
+                if !combined.elements.contains(&e) {
+                    combined.elements.push(e);
+                }
+            }
+            combined.undo.extend(entry.undo);
+            combined.redo.extend(entry.redo);
+        }
+        if combined.elements.len() > 1 {
+            combined.description = format!(
+                "{} of {} elements",
+                combined.description,
+                combined.elements.len()
+            );
+        }
+        Some(combined)
+    }
+
+    fn undo(&self, commands: &mut Commands) {
+        for undo in self.undo.iter().rev() {
+            undo(commands);
+        }
+    }
+
+    fn redo(&self, commands: &mut Commands) {
+        for redo in &self.redo {
+            redo(commands);
+        }
+    }
+}
+
+/// The name of a type without its module path or generic parameters
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Used as a resource to keep track of the changes that have been made to
+/// the components of elements, so they can be undone
+#[derive(Resource, Default)]
+pub struct UndoHistory {
+    pub entries: Vec<UndoEntry>,
+    /// How many of the entries are currently applied. The entries after this
+    /// have been undone and can be redone.
+    pub applied: usize,
+}
+
+impl UndoHistory {
+    /// Add a change to the history, discarding any changes that were undone.
+    /// Consecutive changes to the same component of the same elements are
+    /// merged, so dragging or typing into a field makes only one entry.
+    pub fn push(&mut self, entry: UndoEntry) {
+        self.entries.truncate(self.applied);
+        if let Some(last) = self.entries.last_mut() {
+            if last.elements == entry.elements && last.component == entry.component {
+                last.redo = entry.redo;
+                return;
+            }
+        }
+
+        self.entries.push(entry);
+        if self.entries.len() > MAX_UNDO_HISTORY {
+            self.entries.remove(0);
+        }
+        self.applied = self.entries.len();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.applied > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.applied < self.entries.len()
+    }
+}
+
+/// The undo histories of workspaces that are open in the background. The
+/// history of the current workspace is the [`UndoHistory`] resource.
+#[derive(Resource, Default)]
+pub struct BackgroundUndoHistories(pub HashMap<Entity, UndoHistory>);
+
+/// Used as an event to move through the undo history
+#[derive(Debug, Clone, Copy)]
+pub enum UndoRequest {
+    Undo,
+    Redo,
+    /// Undo or redo changes until this many entries of the history are
+    /// applied
+    JumpTo(usize),
+}
+
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UndoHistory>()
+            .init_resource::<BackgroundUndoHistories>()
+            .add_event::<UndoRequest>()
+            .add_system(handle_undo_requests);
+    }
+}
+
+fn handle_undo_requests(
+    mut commands: Commands,
+    mut requests: EventReader<UndoRequest>,
+    mut history: ResMut<UndoHistory>,
+    mut unsaved: ResMut<UnsavedChanges>,
+) {
+    for request in requests.iter() {
+        let target = match request {
+            UndoRequest::Undo => history.applied.saturating_sub(1),
+            UndoRequest::Redo => (history.applied + 1).min(history.entries.len()),
+            UndoRequest::JumpTo(target) => (*target).min(history.entries.len()),
+        };
+        if target == history.applied {
+            continue;
+        }
+
+        while history.applied > target {
+            history.applied -= 1;
+            history.entries[history.applied].undo(&mut commands);
+        }
+        while history.applied < target {
+            history.entries[history.applied].redo(&mut commands);
+            history.applied += 1;
+        }
+        unsaved.0 = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq)]
+    struct Value(u32);
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq)]
+    struct Other(u32);
+
+    #[test]
+    fn consecutive_changes_to_one_component_are_merged() {
+        let a = Entity::from_raw(0);
+        let b = Entity::from_raw(1);
+        let mut history = UndoHistory::default();
+        history.push(UndoEntry::new(a, Value(0), Value(1)));
+        history.push(UndoEntry::new(a, Value(1), Value(2)));
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.applied, 1);
+
+        history.push(UndoEntry::new(a, Other(0), Other(1)));
+        history.push(UndoEntry::new(b, Value(0), Value(1)));
+        history.push(UndoEntry::new(a, Value(2), Value(3)));
+        assert_eq!(history.entries.len(), 4);
+        assert_eq!(history.applied, 4);
+        assert_eq!(history.entries[0].description, "Change Value");
+    }
+
+    #[test]
+    fn push_discards_undone_changes() {
+        let a = Entity::from_raw(0);
+        let mut history = UndoHistory::default();
+        history.push(UndoEntry::new(a, Value(0), Value(1)));
+        history.push(UndoEntry::new(a, Other(0), Other(1)));
+        history.applied = 1;
+        assert!(history.can_redo());
+
+        history.push(UndoEntry::new(a, Other(0), Other(2)));
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(history.applied, 2);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = UndoHistory::default();
+        for i in 0..(MAX_UNDO_HISTORY as u32 + 5) {
+            history.push(UndoEntry::new(Entity::from_raw(i), Value(0), Value(1)));
+        }
+        assert_eq!(history.entries.len(), MAX_UNDO_HISTORY);
+        assert_eq!(history.applied, MAX_UNDO_HISTORY);
+        assert_eq!(history.entries[0].elements, vec![Entity::from_raw(5)]);
+    }
+
+    #[test]
+    fn jump_to_undoes_and_redoes_changes() {
+        let mut app = App::new();
+        app.init_resource::<UnsavedChanges>().add_plugin(UndoPlugin);
+        let e = app.world.spawn((Value(2), Other(1))).id();
+        {
+            let mut history = app.world.resource_mut::<UndoHistory>();
+            history.push(UndoEntry::new(e, Value(0), Value(1)));
+            history.push(UndoEntry::new(e, Other(0), Other(1)));
+            history.push(UndoEntry::new(e, Value(1), Value(2)));
+        }
+
+        app.world
+            .resource_mut::<Events<UndoRequest>>()
+            .send(UndoRequest::JumpTo(0));
+        app.update();
+        assert_eq!(app.world.resource::<UndoHistory>().applied, 0);
+        assert_eq!(*app.world.get::<Value>(e).unwrap(), Value(0));
+        assert_eq!(*app.world.get::<Other>(e).unwrap(), Other(0));
+        assert!(app.world.resource::<UnsavedChanges>().0);
+
+        app.world
+            .resource_mut::<Events<UndoRequest>>()
+            .send(UndoRequest::JumpTo(2));
+        app.update();
+        assert_eq!(app.world.resource::<UndoHistory>().applied, 2);
+        assert_eq!(*app.world.get::<Value>(e).unwrap(), Value(1));
+        assert_eq!(*app.world.get::<Other>(e).unwrap(), Other(1));
+
+        // Jumping past the end stops at the last change
+        app.world
+            .resource_mut::<Events<UndoRequest>>()
+            .send(UndoRequest::JumpTo(10));
+        app.update();
+        assert_eq!(app.world.resource::<UndoHistory>().applied, 3);
+        assert_eq!(*app.world.get::<Value>(e).unwrap(), Value(2));
+    }
+
+    #[test]
+    fn combined_changes_are_one_step() {
+        let mut app = App::new();
+        app.init_resource::<UnsavedChanges>().add_plugin(UndoPlugin);
+        let elements: Vec<Entity> = (0..40).map(|_| app.world.spawn(Value(1)).id()).collect();
+        {
+            let mut history = app.world.resource_mut::<UndoHistory>();
+            let batch = elements
+                .iter()
+                .map(|e| UndoEntry::new(*e, Value(0), Value(1)));
+            history.push(UndoEntry::combine(batch).unwrap());
+            assert_eq!(history.entries.len(), 1);
+            assert_eq!(
+                history.entries[0].description,
+                "Change Value of 40 elements"
+            );
+
+            // Dragging the batch further keeps extending the same step
+            let batch = elements
+                .iter()
+                .map(|e| UndoEntry::new(*e, Value(1), Value(2)));
+            history.push(UndoEntry::combine(batch).unwrap());
+            assert_eq!(history.entries.len(), 1);
+        }
+
+        app.world
+            .resource_mut::<Events<UndoRequest>>()
+            .send(UndoRequest::Undo);
+        app.update();
+        for e in &elements {
+            assert_eq!(*app.world.get::<Value>(*e).unwrap(), Value(0));
+        }
+
+        app.world
+            .resource_mut::<Events<UndoRequest>>()
+            .send(UndoRequest::Redo);
+        app.update();
+        for e in &elements {
+            assert_eq!(*app.world.get::<Value>(*e).unwrap(), Value(2));
+        }
+    }
+
+    #[test]
+    fn repeated_changes_in_one_step_undo_to_the_first_value() {
+        let mut app = App::new();
+        app.init_resource::<UnsavedChanges>().add_plugin(UndoPlugin);
+        let e = app.world.spawn(Value(2)).id();
+        let entry = UndoEntry::combine([
+            UndoEntry::new(e, Value(0), Value(1)),
+            UndoEntry::new(e, Value(1), Value(2)),
+        ])
+        .unwrap();
+        assert_eq!(entry.elements, vec![e]);
+        assert_eq!(entry.description, "Change Value");
+        app.world.resource_mut::<UndoHistory>().push(entry);
+
+        app.world
+            .resource_mut::<Events<UndoRequest>>()
+            .send(UndoRequest::Undo);
+        app.update();
+        assert_eq!(*app.world.get::<Value>(e).unwrap(), Value(0));
+    }
+}
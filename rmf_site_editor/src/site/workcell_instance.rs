@@ -0,0 +1,112 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::report_error,
+    site::{ModelCollisionGeometry, ModelVisualGeometry},
+    WorkcellRoot,
+};
+use bevy::prelude::*;
+use rmf_site_format::{
+    AssetSource, Geometry, Model, NameInSite, Parented, Scale, Workcell, WorkcellModel,
+};
+use std::collections::HashMap;
+
+fn spawn_workcell_geometry(commands: &mut Commands, model: &WorkcellModel) -> Entity {
+    match &model.geometry {
+        Geometry::Mesh { filename, scale } => commands
+            .spawn(Model {
+                name: NameInSite(model.name.clone()),
+                source: AssetSource::from(filename),
+                pose: model.pose.clone(),
+                scale: scale.map(Scale).unwrap_or_default(),
+                ..default()
+            })
+            .id(),
+        Geometry::Primitive(primitive) => commands
+            .spawn(primitive.clone())
+            .insert(model.pose.clone())
+            .insert(SpatialBundle::VISIBLE_IDENTITY)
+            .id(),
+    }
+}
+
+/// Spawn the frames and geometry of workcells that have been placed in a site.
+/// The workcell is shown as a single model, so its elements cannot be edited
+/// from the site.
+pub fn handle_new_workcell_roots(
+    mut commands: Commands,
+    new_workcells: Query<(Entity, &WorkcellRoot)>,
+) {
+    for (e, root) in &new_workcells {
+        let workcell: &Workcell = &root.workcell;
+        let mut id_to_entity = HashMap::from([(workcell.id, e)]);
+        let mut parents = Vec::new();
+
+        for (id, frame) in &workcell.frames {
+            let frame_id = commands
+                .spawn(SpatialBundle::from_transform(
+                    frame.bundle.pose().transform(),
+                ))
+                .id();
+            id_to_entity.insert(*id, frame_id);
+            parents.push((frame.parent, frame_id));
+        }
+
+        for (id, joint) in &workcell.joints {
+            let joint_id = commands.spawn(SpatialBundle::VISIBLE_IDENTITY).id();
+            id_to_entity.insert(*id, joint_id);
+            parents.push((joint.parent, joint_id));
+        }
+
+        // Visuals and collisions are kept in separate groups so that either
+        // can be hidden, see CollisionDisplay.
+        let mut spawn_group = |group: Entity, parented: &Parented<u32, WorkcellModel>| {
+            let geometry = spawn_workcell_geometry(&mut commands, &parented.bundle);
+            commands.entity(group).add_child(geometry);
+            parents.push((parented.parent, group));
+        };
+        for visual in workcell.visuals.values() {
+            let group = commands
+                .spawn((SpatialBundle::VISIBLE_IDENTITY, ModelVisualGeometry))
+                .id();
+            spawn_group(group, visual);
+        }
+        for collision in workcell.collisions.values() {
+            let group = commands
+                .spawn((SpatialBundle::INVISIBLE_IDENTITY, ModelCollisionGeometry))
+                .id();
+            spawn_group(group, collision);
+        }
+
+        for (parent, child) in parents {
+            match id_to_entity.get(&parent) {
+                Some(parent) => {
+                    commands.entity(*parent).add_child(child);
+                }
+                None => {
+                    report_error(
+                        None,
+                        format!("Workcell element refers to a missing parent {parent}"),
+                    );
+                    commands.entity(child).despawn_recursive();
+                }
+            }
+        }
+        commands.entity(e).remove::<WorkcellRoot>();
+    }
+}
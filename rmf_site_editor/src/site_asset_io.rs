@@ -1,7 +1,8 @@
+use crate::console::report_error;
 use bevy::{
     asset::{AssetIo, AssetIoError, AssetPlugin, FileType, Metadata},
     prelude::*,
-    utils::{BoxedFuture, HashMap},
+    utils::{BoxedFuture, HashMap, HashSet},
 };
 use dirs;
 use serde::Deserialize;
@@ -10,6 +11,7 @@ use std::fs;
 use std::io;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::urdf_loader::UrdfPlugin;
 use urdf_rs::utils::expand_package_path;
@@ -23,13 +25,246 @@ pub fn cache_path() -> PathBuf {
     return p;
 }
 
+/// Version of the layout of the remote asset cache. Increase this whenever
+/// the way assets are saved into the cache changes, so that stale files from
+/// older versions of the editor will not be loaded.
+pub const REMOTE_ASSET_CACHE_VERSION: u32 = 1;
+
+/// The directory that all versions of the remote asset cache are kept in
+pub fn remote_asset_cache_root() -> PathBuf {
+    let mut p = cache_path();
+    p.push("remote_assets");
+    return p;
+}
+
+/// The directory that remote assets are downloaded into
+pub fn remote_asset_cache_path() -> PathBuf {
+    let mut p = remote_asset_cache_root();
+    p.push(format!("v{REMOTE_ASSET_CACHE_VERSION}"));
+    return p;
+}
+
+/// Total size in bytes of the files in the remote asset cache, including
+/// any files left over from older cache versions
+pub fn remote_asset_cache_size() -> u64 {
+    fn dir_size(path: &Path) -> u64 {
+        let Ok(entries) = fs::read_dir(path) else {
+            return 0;
+        };
+        entries
+            .flatten()
+            .map(|entry| match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+                Ok(metadata) => metadata.len(),
+                Err(_) => 0,
+            })
+            .sum()
+    }
+
+    dir_size(&remote_asset_cache_root())
+}
+
+/// Delete every file in the remote asset cache. Assets that are already
+/// loaded will stay loaded, but they will need to be downloaded again the
+/// next time they are loaded.
+pub fn clear_remote_asset_cache() -> io::Result<()> {
+    let root = remote_asset_cache_root();
+    if !root.exists() {
+        return Ok(());
+    }
+    fs::remove_dir_all(root)
+}
+
+/// Delete the files in the remote asset cache that do not belong to any of
+/// the remote asset names in `in_use`, along with every older version of the
+/// cache. Returns the number of files that were deleted.
+pub fn evict_unused_remote_assets(in_use: &HashSet<String>) -> io::Result<usize> {
+    evict_unused_assets_from(
+        &remote_asset_cache_root(),
+        &remote_asset_cache_path(),
+        in_use,
+    )
+}
+
+/// The part of a remote asset name that every file of its model is kept
+/// under. Remote names have the form OrgName/ModelName/FileName.ext, and the
+/// files that a model pulls in, such as its meshes, textures, and materials,
+/// all live inside the same model folder.
+fn remote_model_root(name: &str) -> &str {
+    match name.match_indices('/').nth(1) {
+        Some((i, _)) => &name[..i],
+        None => name,
+    }
+}
+
+/// Check if a file in the cache may be loaded for any of the asset names in
+/// `in_use`. Models are fetched in several formats, so a model named
+/// Org/Model can be cached as Org/Model.glb or inside of Org/Model/.
+fn is_cached_file_in_use(file: &str, in_use: &HashSet<String>) -> bool {
+    in_use.iter().any(|name| {
+        let root = remote_model_root(name);
+        match file.strip_prefix(root) {
+            Some("") => true,
+            Some(rest) => rest.starts_with('/') || (rest.starts_with('.') && !rest.contains('/')),
+            None => false,
+        }
+    })
+}
+
+fn evict_unused_assets_from(
+    root: &Path,
+    current: &Path,
+    in_use: &HashSet<String>,
+) -> io::Result<usize> {
+    fn evict_dir(dir: &Path, cache: &Path, in_use: &HashSet<String>) -> io::Result<usize> {
+        let mut evicted = 0;
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                evicted += evict_dir(&path, cache, in_use)?;
+                if fs::read_dir(&path)?.next().is_none() {
+                    fs::remove_dir(&path)?;
+                }
+                continue;
+            }
+
+            let Ok(relative) = path.strip_prefix(cache) else {
+                continue;
+            };
+            let name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if !is_cached_file_in_use(&name, in_use) {
+                fs::remove_file(&path)?;
+                evicted += 1;
+            }
+        }
+        Ok(evicted)
+    }
+
+    if !root.exists() {
+        return Ok(0);
+    }
+
+    let mut evicted = 0;
+    for entry in fs::read_dir(root)? {
+        let path = entry?.path();
+        if path == current {
+            evicted += evict_dir(current, current, in_use)?;
+        } else if path.is_dir() {
+            evicted += remote_asset_file_count(&path);
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+            evicted += 1;
+        }
+    }
+    Ok(evicted)
+}
+
+fn remote_asset_file_count(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.path() {
+            path if path.is_dir() => remote_asset_file_count(&path),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// When set, assets will only be loaded from this computer and nothing will
+/// be fetched from remote servers
+static OFFLINE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Check whether remote fetches are currently blocked
+pub fn is_offline_mode() -> bool {
+    OFFLINE_MODE.load(Ordering::Relaxed)
+}
+
+/// Block or allow fetching assets from remote servers. Assets that were
+/// already downloaded into the cache can still be loaded while offline.
+pub fn set_offline_mode(offline: bool) {
+    OFFLINE_MODE.store(offline, Ordering::Relaxed);
+}
+
+/// Where an asset source can currently be loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetAvailability {
+    /// The asset is a file on this computer or is bundled with the editor
+    Local,
+    /// The asset is remote but a copy of it is in the remote asset cache
+    Cached,
+    /// The asset can only be loaded by fetching it from a remote server
+    RemoteOnly,
+    /// The asset could not be found anywhere
+    Missing,
+}
+
+impl AssetAvailability {
+    /// Whether the asset can be loaded when remote fetches are blocked
+    pub fn is_available_offline(&self) -> bool {
+        matches!(self, Self::Local | Self::Cached)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Local => "Local",
+            Self::Cached => "Cached",
+            Self::RemoteOnly => "Remote only",
+            Self::Missing => "Missing",
+        }
+    }
+}
+
+/// Check where an asset source would be loaded from, without loading it.
+/// Search sources are matched by their name alone, so a folder that contains
+/// the model is assumed to provide it.
+pub fn asset_availability(source: &AssetSource) -> AssetAvailability {
+    let in_cache = |name: &str| remote_asset_cache_path().join(name).exists();
+    match source {
+        AssetSource::Local(path) => match Path::new(path).exists() {
+            true => AssetAvailability::Local,
+            false => AssetAvailability::Missing,
+        },
+        AssetSource::Bundled(_) => AssetAvailability::Local,
+        AssetSource::Package(_) => {
+            let path = expand_package_path(&String::from(source), None);
+            match Path::new(&*path).exists() {
+                true => AssetAvailability::Local,
+                false => AssetAvailability::Missing,
+            }
+        }
+        AssetSource::Remote(name) => match in_cache(name) {
+            true => AssetAvailability::Cached,
+            false => AssetAvailability::RemoteOnly,
+        },
+        AssetSource::Search(name) => {
+            let in_env = env::var(MODEL_ENVIRONMENT_VARIABLE)
+                .map(|var| PathBuf::from(var).join(name).exists())
+                .unwrap_or(false);
+            if in_env {
+                AssetAvailability::Local
+            } else if in_cache(name) {
+                AssetAvailability::Cached
+            } else {
+                AssetAvailability::RemoteOnly
+            }
+        }
+    }
+}
+
 struct SiteAssetIo {
     pub default_io: Box<dyn AssetIo>,
     pub bundled_assets: HashMap<String, Vec<u8>>,
 }
 
-const FUEL_BASE_URI: &str = "https://fuel.gazebosim.org/1.0";
-const MODEL_ENVIRONMENT_VARIABLE: &str = "GZ_SIM_RESOURCE_PATH";
+pub const FUEL_BASE_URI: &str = "https://fuel.gazebosim.org/1.0";
+pub const MODEL_ENVIRONMENT_VARIABLE: &str = "GZ_SIM_RESOURCE_PATH";
 
 #[derive(Deserialize)]
 struct FuelErrorMsg {
@@ -61,6 +296,13 @@ impl SiteAssetIo {
         asset_name: String,
     ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
         Box::pin(async move {
+            if is_offline_mode() {
+                return Err(AssetIoError::Io(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Not fetching {remote_url} because offline mode is on"),
+                )));
+            }
+
             let bytes = surf::get(remote_url.clone())
                 .recv_bytes()
                 .await
@@ -104,11 +346,23 @@ impl SiteAssetIo {
     }
 
     fn save_to_cache(&self, name: &String, bytes: &[u8]) {
-        let mut asset_path = cache_path();
+        if bytes.is_empty() {
+            return;
+        }
+        let mut asset_path = remote_asset_cache_path();
         asset_path.push(PathBuf::from(&name));
-        fs::create_dir_all(asset_path.parent().unwrap()).unwrap();
-        if bytes.len() > 0 {
-            fs::write(asset_path, bytes).expect("unable to write to file");
+        if let Err(err) = fs::create_dir_all(asset_path.parent().unwrap()) {
+            report_error(
+                None,
+                format!("Unable to create cache directory for {name}: {err}"),
+            );
+            return;
+        }
+        if let Err(err) = fs::write(&asset_path, bytes) {
+            report_error(
+                None,
+                format!("Unable to save {name} to the asset cache at {asset_path:?}: {err}"),
+            );
         }
     }
 
@@ -219,7 +473,7 @@ impl AssetIo for SiteAssetIo {
                 // Try local cache first
                 #[cfg(not(target_arch = "wasm32"))]
                 {
-                    let mut asset_path = cache_path();
+                    let mut asset_path = remote_asset_cache_path();
                     asset_path.push(PathBuf::from(&asset_name));
                     if asset_path.exists() {
                         return Box::pin(async move { self.load_from_file(asset_path) });
@@ -278,7 +532,7 @@ impl AssetIo for SiteAssetIo {
                 // Try local cache
                 #[cfg(not(target_arch = "wasm32"))]
                 {
-                    let mut asset_path = cache_path();
+                    let mut asset_path = remote_asset_cache_path();
                     asset_path.push(PathBuf::from(&asset_name));
                     if asset_path.exists() {
                         return Box::pin(async move { self.load_from_file(asset_path) });
@@ -358,3 +612,81 @@ impl Plugin for SiteAssetIoPlugin {
             .add_plugin(UrdfPlugin);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_use(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn every_format_of_a_used_model_is_kept() {
+        let used = in_use(&["Owner/Model"]);
+        for file in [
+            "Owner/Model.glb",
+            "Owner/Model.stl",
+            "Owner/Model/Model.obj",
+            "Owner/Model/Model.mtl",
+            "Owner/Model/model.sdf",
+            "Owner/Model/materials/textures/wood.png",
+        ] {
+            assert!(is_cached_file_in_use(file, &used), "{file}");
+        }
+        for file in [
+            "Owner/Model2.glb",
+            "Owner/Model2/model.sdf",
+            "Owner/Other/model.sdf",
+            "Other/Model.glb",
+        ] {
+            assert!(!is_cached_file_in_use(file, &used), "{file}");
+        }
+    }
+
+    #[test]
+    fn files_of_a_used_model_file_are_kept() {
+        let used = in_use(&["Owner/Model/meshes/body.dae"]);
+        assert!(is_cached_file_in_use(
+            "Owner/Model/materials/textures/body.png",
+            &used
+        ));
+        assert!(!is_cached_file_in_use("Owner/Other/meshes/body.dae", &used));
+    }
+
+    #[test]
+    fn eviction_keeps_used_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let current = root.join("v1");
+        let write = |file: &Path| {
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, b"data").unwrap();
+        };
+        let kept = [
+            current.join("Owner/Model/model.sdf"),
+            current.join("Owner/Model/meshes/body.obj"),
+            current.join("Owner/Model/meshes/body.mtl"),
+            current.join("Owner/Model/materials/textures/body.png"),
+        ];
+        let evicted = [
+            current.join("Owner/Unused/model.sdf"),
+            current.join("Owner/Unused.glb"),
+            root.join("v0/Owner/Model/model.sdf"),
+        ];
+        for file in kept.iter().chain(evicted.iter()) {
+            write(file);
+        }
+
+        let count = evict_unused_assets_from(root, &current, &in_use(&["Owner/Model"])).unwrap();
+        assert_eq!(count, evicted.len());
+        for file in &kept {
+            assert!(file.exists(), "{file:?}");
+        }
+        for file in &evicted {
+            assert!(!file.exists(), "{file:?}");
+        }
+        assert!(!current.join("Owner/Unused").exists());
+        assert!(!root.join("v0").exists());
+    }
+}
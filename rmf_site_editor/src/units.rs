@@ -0,0 +1,179 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use bevy_egui::egui::{Context, Id, Ui};
+use serde::{Deserialize, Serialize};
+
+/// Meters in one foot
+pub const METERS_PER_FOOT: f32 = 0.3048;
+
+/// How lengths are shown in the editor. Sites always store lengths in
+/// meters; this only changes what the user sees and types.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthUnit {
+    #[default]
+    Meters,
+    FeetInches,
+}
+
+impl LengthUnit {
+    pub const ALL: [LengthUnit; 2] = [LengthUnit::Meters, LengthUnit::FeetInches];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LengthUnit::Meters => "Meters",
+            LengthUnit::FeetInches => "Feet and inches",
+        }
+    }
+
+    /// Convert meters into the number that widgets edit for this unit. Feet
+    /// and inches are edited as a number of feet.
+    pub fn from_meters(&self, meters: f32) -> f32 {
+        match self {
+            LengthUnit::Meters => meters,
+            LengthUnit::FeetInches => meters / METERS_PER_FOOT,
+        }
+    }
+
+    pub fn to_meters(&self, value: f32) -> f32 {
+        match self {
+            LengthUnit::Meters => value,
+            LengthUnit::FeetInches => value * METERS_PER_FOOT,
+        }
+    }
+
+    /// Text for a length, e.g. 1.250 m or 4' 1.2"
+    pub fn format(&self, meters: f32, decimals: usize) -> String {
+        match self {
+            LengthUnit::Meters => format!("{meters:.decimals$} m"),
+            LengthUnit::FeetInches => {
+                let sign = if meters < 0.0 { "-" } else { "" };
+                // Round to tenths of an inch before splitting off the feet so
+                // that we never show 12 inches
+                let tenths = (meters.abs() / METERS_PER_FOOT * 120.0).round();
+                let feet = (tenths / 120.0).floor();
+                let inches = (tenths - feet * 120.0) / 10.0;
+                format!("{sign}{feet}' {inches:.1}\"")
+            }
+        }
+    }
+}
+
+/// How angles are shown in the editor. Angles keep whichever unit they were
+/// saved with in the site file.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    pub const ALL: [AngleUnit; 2] = [AngleUnit::Degrees, AngleUnit::Radians];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "Degrees",
+            AngleUnit::Radians => "Radians",
+        }
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "°",
+            AngleUnit::Radians => " rad",
+        }
+    }
+
+    pub fn from_radians(&self, radians: f32) -> f32 {
+        match self {
+            AngleUnit::Degrees => radians.to_degrees(),
+            AngleUnit::Radians => radians,
+        }
+    }
+
+    pub fn to_radians(&self, value: f32) -> f32 {
+        match self {
+            AngleUnit::Degrees => value.to_radians(),
+            AngleUnit::Radians => value,
+        }
+    }
+
+    pub fn format(&self, radians: f32) -> String {
+        match self {
+            AngleUnit::Degrees => format!("{:.1}°", radians.to_degrees()),
+            AngleUnit::Radians => format!("{radians:.4} rad"),
+        }
+    }
+}
+
+/// The units that values are displayed in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct DisplayUnits {
+    pub length: LengthUnit,
+    pub angle: AngleUnit,
+}
+
+impl DisplayUnits {
+    fn id() -> Id {
+        Id::new("rmf_site_editor_display_units")
+    }
+
+    /// Keep the units in the egui memory so that any widget can find them
+    /// without the units being passed down through every inspector.
+    pub fn share(self, ctx: &Context) {
+        ctx.data().insert_temp(Self::id(), self);
+    }
+
+    /// The units that were shared with egui, or the default units if none
+    /// have been shared
+    pub fn of(ui: &Ui) -> Self {
+        ui.data().get_temp(Self::id()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meters_are_formatted_with_decimals() {
+        assert_eq!(LengthUnit::Meters.format(1.25, 3), "1.250 m");
+        assert_eq!(LengthUnit::Meters.format(-0.5, 1), "-0.5 m");
+        assert_eq!(LengthUnit::Meters.format(2.0, 0), "2 m");
+    }
+
+    #[test]
+    fn feet_and_inches_are_formatted() {
+        let feet = LengthUnit::FeetInches;
+        assert_eq!(feet.format(0.0, 0), "0' 0.0\"");
+        assert_eq!(feet.format(4.0 * METERS_PER_FOOT, 0), "4' 0.0\"");
+        assert_eq!(feet.format(5.25 * METERS_PER_FOOT, 0), "5' 3.0\"");
+        assert_eq!(feet.format(-0.0254, 0), "-0' 1.0\"");
+    }
+
+    #[test]
+    fn inches_never_round_up_to_twelve() {
+        let feet = LengthUnit::FeetInches;
+        // 11.98 inches rounds up to the next foot instead of 12.0 inches
+        assert_eq!(feet.format(0.3043, 0), "1' 0.0\"");
+        assert_eq!(feet.format(METERS_PER_FOOT + 0.3043, 0), "2' 0.0\"");
+    }
+}
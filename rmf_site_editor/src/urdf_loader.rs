@@ -18,6 +18,7 @@
 use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
 use bevy::prelude::*;
 use bevy::utils::BoxedFuture;
+use std::path::Path;
 
 use rmf_site_format::UrdfRoot;
 
@@ -41,11 +42,21 @@ impl AssetLoader for UrdfLoader {
         bytes: &'a [u8],
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
-        Box::pin(async move { Ok(load_urdf(bytes, load_context).await?) })
+        Box::pin(async move {
+            let is_xacro = load_context
+                .path()
+                .extension()
+                .map_or(false, |ext| ext == "xacro");
+            if is_xacro {
+                Ok(load_xacro(bytes, load_context).await?)
+            } else {
+                Ok(load_urdf(bytes, load_context).await?)
+            }
+        })
     }
 
     fn extensions(&self) -> &[&str] {
-        static EXTENSIONS: &[&str] = &["urdf"];
+        static EXTENSIONS: &[&str] = &["urdf", "xacro"];
         EXTENSIONS
     }
 }
@@ -54,6 +65,8 @@ impl AssetLoader for UrdfLoader {
 pub enum UrdfError {
     #[error("Failed to load Urdf")]
     ParsingError,
+    #[error("Failed to expand xacro: {0}")]
+    XacroError(String),
     //Io(#[from] std::io::Error),
 }
 
@@ -71,3 +84,78 @@ async fn load_urdf<'a, 'b>(
         return Err(UrdfError::ParsingError);
     }
 }
+
+async fn load_xacro<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+) -> Result<(), UrdfError> {
+    let urdf = expand_xacro(bytes, load_context.path())?;
+    load_urdf(urdf.as_bytes(), load_context).await
+}
+
+/// Turn a xacro file into urdf by running the xacro tool, which is installed
+/// with ROS and needs to be found in the PATH.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn expand_xacro(bytes: &[u8], path: &Path) -> Result<String, UrdfError> {
+    use rmf_site_format::AssetSource;
+    use std::{io::Write, path::PathBuf};
+    use urdf_rs::utils::expand_package_path;
+
+    // Includes are looked up relative to the xacro file, so run the tool on
+    // the original file when it can be found
+    let file = if path.exists() {
+        Some(path.to_path_buf())
+    } else {
+        match AssetSource::from(path) {
+            AssetSource::Local(filename) => Some(PathBuf::from(filename)),
+            source @ AssetSource::Package(_) => Some(PathBuf::from(
+                (*expand_package_path(&String::from(&source), None)).to_owned(),
+            )),
+            _ => None,
+        }
+        .filter(|file| file.exists())
+    };
+    // Otherwise write the bytes into a temporary file with a unique name, so
+    // that expansions running at the same time do not overwrite each other.
+    // The file is deleted once the temporary file is dropped.
+    let temp_file;
+    let file = match file {
+        Some(file) => file,
+        None => {
+            let to_err = |err: std::io::Error| UrdfError::XacroError(err.to_string());
+            let stem = path.file_stem().unwrap_or("robot".as_ref());
+            let mut file = tempfile::Builder::new()
+                .prefix(stem)
+                .suffix(".xacro")
+                .tempfile()
+                .map_err(to_err)?;
+            file.write_all(bytes).map_err(to_err)?;
+            file.flush().map_err(to_err)?;
+            temp_file = file;
+            temp_file.path().to_path_buf()
+        }
+    };
+
+    let output = std::process::Command::new("xacro")
+        .arg(&file)
+        .output()
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => UrdfError::XacroError(
+                "the xacro tool was not found, make sure that ROS has been sourced".to_owned(),
+            ),
+            _ => UrdfError::XacroError(err.to_string()),
+        })?;
+    if !output.status.success() {
+        return Err(UrdfError::XacroError(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|err| UrdfError::XacroError(err.to_string()))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn expand_xacro(_bytes: &[u8], _path: &Path) -> Result<String, UrdfError> {
+    Err(UrdfError::XacroError(
+        "xacro files cannot be expanded in the browser".to_owned(),
+    ))
+}
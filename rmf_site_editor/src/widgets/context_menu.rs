@@ -0,0 +1,241 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{
+    interaction::{ChangeMode, Hovering, InteractionMode, Select, SelectAnchor3D},
+    keyboard::{on_hover_shortcut, KeyAction},
+    localization::tr,
+    site::{
+        AssociatedGraphs, Category, Change, ChangeLevel, Delete, IsStatic, LevelProperties,
+        LightKind, LodPolicy, MaterialOverride, Model, ModelMarker, ModelTags, NameInSite,
+        NavGraphMarker, Pose, Scale,
+    },
+    widgets::AppEvents,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{
+    egui::{self, Key},
+    EguiContext,
+};
+use rmf_site_format::{AssetSource, NameInWorkcell};
+
+#[derive(Resource, Default)]
+pub struct ContextMenuDisplay {
+    /// The element that the menu was opened for
+    pub target: Option<Entity>,
+    /// Where the menu is shown in the window
+    pub position: egui::Pos2,
+}
+
+#[derive(SystemParam)]
+pub struct ContextMenuParams<'w, 's> {
+    pub display: ResMut<'w, ContextMenuDisplay>,
+    pub hovering: Res<'w, Hovering>,
+    pub mode: Res<'w, InteractionMode>,
+    pub categories: Query<'w, 's, &'static Category>,
+    pub site_names: Query<'w, 's, &'static NameInSite>,
+    pub workcell_names: Query<'w, 's, &'static NameInWorkcell>,
+    pub visibility: Query<'w, 's, &'static Visibility>,
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub levels: Query<'w, 's, (Entity, &'static LevelProperties, &'static Parent)>,
+    pub models: Query<
+        'w,
+        's,
+        (
+            &'static NameInSite,
+            &'static AssetSource,
+            &'static Pose,
+            &'static IsStatic,
+            &'static Scale,
+            Option<&'static ModelTags>,
+            Option<&'static MaterialOverride>,
+            Option<&'static LodPolicy>,
+        ),
+        With<ModelMarker>,
+    >,
+    pub lights: Query<'w, 's, (), With<LightKind>>,
+    pub graphs: Query<'w, 's, (Entity, &'static NameInSite, &'static Parent), With<NavGraphMarker>>,
+    pub associated_graphs: Query<'w, 's, &'static AssociatedGraphs<Entity>>,
+}
+
+/// Open a menu of common operations when an element in the viewport is
+/// right-clicked
+pub fn show_context_menu(
+    mut egui_context: ResMut<EguiContext>,
+    mut params: ContextMenuParams,
+    mut events: AppEvents,
+) {
+    let ctx = egui_context.ctx_mut();
+    let (secondary_clicked, primary_clicked, pointer, escape) = {
+        let input = ctx.input();
+        (
+            input.pointer.secondary_clicked(),
+            input.pointer.primary_clicked(),
+            input.pointer.interact_pos(),
+            input.key_pressed(Key::Escape),
+        )
+    };
+
+    if secondary_clicked && !ctx.is_pointer_over_area() {
+        params.display.target = None;
+        if params.mode.is_inspecting() {
+            if let (Some(target), Some(position)) = (params.hovering.0, pointer) {
+                params.display.target = Some(target);
+                params.display.position = position;
+            }
+        }
+    }
+
+    let Some(e) = params.display.target else {
+        return;
+    };
+    // The element may have been deleted while the menu was open
+    let Ok(category) = params.categories.get(e) else {
+        params.display.target = None;
+        return;
+    };
+    let title = params
+        .site_names
+        .get(e)
+        .map(|name| name.0.clone())
+        .or_else(|_| params.workcell_names.get(e).map(|name| name.0.clone()))
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| category.label().to_owned());
+    let site = events.request.current_workspace.root;
+
+    let mut close = escape;
+    let response = egui::Area::new("context_menu")
+        .fixed_pos(params.display.position)
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::menu(ui.style()).show(ui, |ui| {
+                ui.set_min_width(120.0);
+                ui.strong(title);
+                ui.separator();
+
+                if ui.button(tr(ui, "Inspect")).clicked() {
+                    events.request.select.send(Select(Some(e)));
+                    close = true;
+                }
+
+                if let Ok(model) = params.models.get(e) {
+                    if ui
+                        .button(tr(ui, "Duplicate"))
+                        .on_hover_text(tr(ui, "Place a copy of this model"))
+                        .clicked()
+                    {
+                        let (name, source, pose, is_static, scale, tags, material, lod) = model;
+                        let model = Model {
+                            name: name.clone(),
+                            source: source.clone(),
+                            pose: pose.clone(),
+                            is_static: is_static.clone(),
+                            scale: scale.clone(),
+                            tags: tags.cloned().unwrap_or_default(),
+                            material: material.cloned().unwrap_or_default(),
+                            lod: lod.cloned().unwrap_or_default(),
+                            ..default()
+                        };
+                        events.request.change_mode.send(ChangeMode::To(
+                            SelectAnchor3D::create_new_point().for_model(model).into(),
+                        ));
+                        close = true;
+                    }
+                }
+
+                if params.visibility.get(e).map_or(false, |v| v.is_visible) {
+                    if ui.button(tr(ui, "Hide")).clicked() {
+                        events
+                            .change
+                            .visibility
+                            .send(Change::new(Visibility { is_visible: false }, e));
+                        close = true;
+                    }
+                }
+
+                // Only elements that do not depend on anchors can be moved
+                // between levels
+                let movable = params.models.contains(e) || params.lights.contains(e);
+                let on_level = params
+                    .parents
+                    .get(e)
+                    .ok()
+                    .filter(|parent| params.levels.contains(parent.get()));
+                if let (true, Some(on_level)) = (movable, on_level) {
+                    let mut others: Vec<_> = params
+                        .levels
+                        .iter()
+                        .filter(|(level, _, parent)| {
+                            *level != on_level.get() && Some(parent.get()) == site
+                        })
+                        .collect();
+                    others.sort_by(|(_, a, _), (_, b, _)| a.elevation.total_cmp(&b.elevation));
+                    if !others.is_empty() {
+                        ui.separator();
+                        ui.label(tr(ui, "Move to Level"));
+                        for (level, props, _) in others {
+                            if ui.button(&props.name).clicked() {
+                                events
+                                    .site_change
+                                    .change_level
+                                    .send(ChangeLevel { element: e, level });
+                                close = true;
+                            }
+                        }
+                    }
+                }
+
+                if let Ok(associated) = params.associated_graphs.get(e) {
+                    let graphs: Vec<_> = params
+                        .graphs
+                        .iter()
+                        .filter(|(graph, _, parent)| {
+                            Some(parent.get()) == site && associated.includes(*graph)
+                        })
+                        .collect();
+                    if !graphs.is_empty() {
+                        ui.separator();
+                        ui.label(tr(ui, "Select Graph"));
+                        for (graph, name, _) in graphs {
+                            if ui.button(&name.0).clicked() {
+                                events.request.select.send(Select(Some(graph)));
+                                close = true;
+                            }
+                        }
+                    }
+                }
+
+                ui.separator();
+                let response = ui.button(tr(ui, "Delete"));
+                if on_hover_shortcut(response, KeyAction::DeleteSelection, "Delete this element")
+                    .clicked()
+                {
+                    events.request.delete.send(Delete::new(e));
+                    close = true;
+                }
+            });
+        })
+        .response;
+
+    if primary_clicked && !response.hovered() {
+        close = true;
+    }
+
+    if close {
+        params.display.target = None;
+    }
+}
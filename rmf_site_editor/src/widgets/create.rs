@@ -18,6 +18,8 @@
 use crate::{
     inspector::{InspectAssetSource, InspectScale},
     interaction::{ChangeMode, SelectAnchor, SelectAnchor3D},
+    keyboard::{on_hover_shortcut, KeyAction},
+    localization::tr,
     site::Change,
     AppEvents, AppState,
 };
@@ -25,7 +27,8 @@ use bevy::prelude::*;
 use bevy_egui::egui::{CollapsingHeader, Ui};
 
 use rmf_site_format::{
-    AssetSource, Geometry, Model, Pending, RecallAssetSource, Scale, WorkcellModel,
+    AssetSource, Geometry, MeshPrimitive, Model, Pending, PrimitiveShape, RecallAssetSource, Scale,
+    WorkcellModel,
 };
 
 pub struct CreateWidget<'a, 'w, 's> {
@@ -44,60 +47,196 @@ impl<'a, 'w, 's> CreateWidget<'a, 'w, 's> {
                     return;
                 }
                 AppState::SiteEditor => {
-                    if ui.button("Lane").clicked() {
+                    let recent_models = &self.events.display.recent_models.models;
+                    if !recent_models.is_empty() {
+                        ui.label(tr(ui, "Recent models"));
+                        let mut chosen = None;
+                        ui.horizontal_wrapped(|ui| {
+                            for (source, scale) in recent_models {
+                                let path = String::from(source);
+                                let name = path
+                                    .rsplit('/')
+                                    .find(|s| !s.is_empty() && *s != "model.sdf")
+                                    .unwrap_or(&path);
+                                if ui.button(name).on_hover_text(&path).clicked() {
+                                    chosen = Some((source.clone(), scale.clone()));
+                                }
+                            }
+                        });
+                        if let Some((source, scale)) = chosen {
+                            if let Ok((e, _, _)) = self.events.pending_asset_sources.get_single() {
+                                self.events
+                                    .change
+                                    .asset_source
+                                    .send(Change::new(source.clone(), e));
+                                self.events
+                                    .workcell_change
+                                    .scale
+                                    .send(Change::new(scale.clone(), e));
+                            }
+                            let model = Model {
+                                source,
+                                scale,
+                                ..default()
+                            };
+                            self.events.request.change_mode.send(ChangeMode::To(
+                                SelectAnchor3D::create_new_point().for_model(model).into(),
+                            ));
+                        }
+                        ui.separator();
+                    }
+
+                    let response = ui.button(tr(ui, "Lane"));
+                    if on_hover_shortcut(response, KeyAction::LaneTool, "Draw a chain of lanes")
+                        .clicked()
+                    {
                         self.events.request.change_mode.send(ChangeMode::To(
                             SelectAnchor::create_new_edge_sequence().for_lane().into(),
                         ));
                     }
 
-                    if ui.button("Location").clicked() {
+                    if ui
+                        .button(tr(ui, "Location"))
+                        .on_hover_text(tr(ui, "Place a location for robots to visit"))
+                        .clicked()
+                    {
                         self.events.request.change_mode.send(ChangeMode::To(
                             SelectAnchor::create_new_point().for_location().into(),
                         ));
                     }
 
-                    if ui.button("Wall").clicked() {
+                    let response = ui.button(tr(ui, "Wall"));
+                    if on_hover_shortcut(response, KeyAction::WallTool, "Draw a chain of walls")
+                        .clicked()
+                    {
                         self.events.request.change_mode.send(ChangeMode::To(
                             SelectAnchor::create_new_edge_sequence().for_wall().into(),
                         ));
                     }
 
-                    if ui.button("Door").clicked() {
+                    if ui
+                        .button(tr(ui, "Door"))
+                        .on_hover_text(tr(ui, "Draw a door between two anchors"))
+                        .clicked()
+                    {
                         self.events.request.change_mode.send(ChangeMode::To(
                             SelectAnchor::create_one_new_edge().for_door().into(),
                         ));
                     }
 
-                    if ui.button("Lift").clicked() {
+                    if ui
+                        .button(tr(ui, "Lift"))
+                        .on_hover_text(tr(ui, "Draw the cabin of a lift from one of its edges"))
+                        .clicked()
+                    {
                         self.events.request.change_mode.send(ChangeMode::To(
                             SelectAnchor::create_one_new_edge().for_lift().into(),
                         ));
                     }
 
-                    if ui.button("Floor").clicked() {
+                    if ui
+                        .button(tr(ui, "Floor"))
+                        .on_hover_text(tr(ui, "Draw the outline of a floor"))
+                        .clicked()
+                    {
                         self.events.request.change_mode.send(ChangeMode::To(
                             SelectAnchor::create_new_path().for_floor().into(),
                         ));
                     }
 
-                    if ui.button("Measurement").clicked() {
+                    let response = ui.button(tr(ui, "Measurement"));
+                    if on_hover_shortcut(
+                        response,
+                        KeyAction::MeasureTool,
+                        "Measure the distance between two anchors",
+                    )
+                    .clicked()
+                    {
                         self.events.request.change_mode.send(ChangeMode::To(
                             SelectAnchor::create_one_new_edge().for_measurement().into(),
                         ));
                     }
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr(ui, "Shape"));
+                        for primitive in [
+                            MeshPrimitive::Box {
+                                size: [1.0, 1.0, 1.0],
+                            },
+                            MeshPrimitive::Cylinder {
+                                radius: 0.5,
+                                length: 1.0,
+                            },
+                            MeshPrimitive::Cone {
+                                radius: 0.5,
+                                length: 1.0,
+                            },
+                        ] {
+                            if ui.button(primitive.label()).clicked() {
+                                self.events.request.change_mode.send(ChangeMode::To(
+                                    SelectAnchor3D::create_new_point()
+                                        .for_primitive_shape(PrimitiveShape::new(primitive))
+                                        .into(),
+                                ));
+                            }
+                        }
+                    });
                 }
                 AppState::WorkcellEditor => {
-                    if ui.button("Frame").clicked() {
+                    if ui
+                        .button(tr(ui, "Frame"))
+                        .on_hover_text(tr(ui, "Add a frame that other elements can be attached to"))
+                        .clicked()
+                    {
                         self.events.request.change_mode.send(ChangeMode::To(
                             SelectAnchor3D::create_new_point().for_anchor(None).into(),
                         ));
                     }
+
+                    let primitives = [
+                        MeshPrimitive::Box {
+                            size: [0.1, 0.1, 0.1],
+                        },
+                        MeshPrimitive::Cylinder {
+                            radius: 0.05,
+                            length: 0.1,
+                        },
+                        MeshPrimitive::Capsule {
+                            radius: 0.05,
+                            length: 0.1,
+                        },
+                        MeshPrimitive::Sphere { radius: 0.05 },
+                    ];
+                    for is_collision in [false, true] {
+                        ui.horizontal(|ui| {
+                            ui.label(tr(ui, if is_collision { "Collision" } else { "Visual" }));
+                            for primitive in &primitives {
+                                if ui.button(primitive.label()).clicked() {
+                                    let workcell_model = WorkcellModel {
+                                        name: primitive.label(),
+                                        geometry: Geometry::Primitive(primitive.clone()),
+                                        ..default()
+                                    };
+                                    let placement = SelectAnchor3D::create_new_point();
+                                    let placement = if is_collision {
+                                        placement.for_collision(workcell_model)
+                                    } else {
+                                        placement.for_visual(workcell_model)
+                                    };
+                                    self.events
+                                        .request
+                                        .change_mode
+                                        .send(ChangeMode::To(placement.into()));
+                                }
+                            }
+                        });
+                    }
                 }
             }
             if let Ok((e, source, scale)) = self.events.pending_asset_sources.get_single() {
                 // TODO(luca) actual recall
                 ui.add_space(10.0);
-                CollapsingHeader::new("New model")
+                CollapsingHeader::new(tr(ui, "New model"))
                     .default_open(false)
                     .show(ui, |ui| {
                         if let Some(new_asset_source) =
@@ -121,12 +260,13 @@ impl<'a, 'w, 's> CreateWidget<'a, 'w, 's> {
                                 unreachable!();
                             }
                             AppState::SiteEditor => {
-                                if let Ok((_e, source, _scale)) =
+                                if let Ok((_e, source, scale)) =
                                     self.events.pending_asset_sources.get_single()
                                 {
-                                    if ui.button("Spawn model").clicked() {
+                                    if ui.button(tr(ui, "Spawn model")).clicked() {
                                         let model = Model {
                                             source: source.clone(),
+                                            scale: scale.clone(),
                                             ..default()
                                         };
                                         self.events.request.change_mode.send(ChangeMode::To(
@@ -141,7 +281,7 @@ impl<'a, 'w, 's> CreateWidget<'a, 'w, 's> {
                                 if let Ok((_e, source, scale)) =
                                     self.events.pending_asset_sources.get_single()
                                 {
-                                    if ui.button("Spawn visual").clicked() {
+                                    if ui.button(tr(ui, "Spawn visual")).clicked() {
                                         let model = Model {
                                             source: source.clone(),
                                             ..default()
@@ -159,7 +299,7 @@ impl<'a, 'w, 's> CreateWidget<'a, 'w, 's> {
                                                 .into(),
                                         ));
                                     }
-                                    if ui.button("Spawn collision").clicked() {
+                                    if ui.button(tr(ui, "Spawn collision")).clicked() {
                                         let model = Model {
                                             source: source.clone(),
                                             ..default()
@@ -0,0 +1,348 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{console::report_warning, AngleUnit, LengthUnit};
+use bevy_egui::egui::{Key, Response, TextEdit, Ui};
+use std::{iter::Peekable, str::Chars};
+
+/// What kind of value an expression describes. This decides which unit
+/// suffixes are allowed and what unit a number without a suffix is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Plain,
+    /// Lengths are evaluated into meters. Numbers without a suffix are in the
+    /// length unit that is being displayed.
+    Length(LengthUnit),
+    /// Angles are evaluated into radians. Numbers without a suffix are in
+    /// the angle unit that is being displayed.
+    Angle(AngleUnit),
+}
+
+impl Quantity {
+    fn bare_scale(&self) -> f64 {
+        match self {
+            Quantity::Plain => 1.0,
+            Quantity::Length(unit) => unit.to_meters(1.0) as f64,
+            Quantity::Angle(unit) => unit.to_radians(1.0) as f64,
+        }
+    }
+
+    fn unit_scale(&self, suffix: &str) -> Result<f64, String> {
+        let scale = match (self, suffix.to_lowercase().as_str()) {
+            (Quantity::Length(_), "mm") => 0.001,
+            (Quantity::Length(_), "cm") => 0.01,
+            (Quantity::Length(_), "m") => 1.0,
+            (Quantity::Length(_), "km") => 1000.0,
+            (Quantity::Length(_), "in" | "inch" | "inches" | "\"") => 0.0254,
+            (Quantity::Length(_), "ft" | "foot" | "feet" | "'") => 0.3048,
+            (Quantity::Angle(_), "deg" | "degree" | "degrees" | "°") => {
+                std::f64::consts::PI / 180.0
+            }
+            (Quantity::Angle(_), "rad" | "radian" | "radians") => 1.0,
+            _ => return Err(format!("unit [{suffix}] cannot be used here")),
+        };
+        Ok(scale)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A number along with the unit that was written after it
+    Number(f64, Option<String>),
+    Op(char),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.peek().copied() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let number = take_while(&mut chars, |c| c.is_ascii_digit() || c == '.');
+            let value: f64 = number
+                .parse()
+                .map_err(|_| format!("[{number}] is not a number"))?;
+            while chars.peek() == Some(&' ') {
+                chars.next();
+            }
+            let unit = match chars.peek() {
+                Some('\'' | '"' | '°') => chars.next().map(String::from),
+                Some(c) if c.is_alphabetic() => Some(take_while(&mut chars, |c| c.is_alphabetic())),
+                _ => None,
+            };
+            tokens.push(Token::Number(value, unit));
+        } else if "+-*/".contains(c) {
+            tokens.push(Token::Op(c));
+            chars.next();
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            chars.next();
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            chars.next();
+        } else {
+            return Err(format!("unexpected [{c}]"));
+        }
+    }
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<Chars>, f: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(c) = chars.peek().copied() {
+        if !f(c) {
+            break;
+        }
+        taken.push(c);
+        chars.next();
+    }
+    taken
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    next: usize,
+    quantity: Quantity,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn sum(&mut self) -> Result<f64, String> {
+        let (mut value, mut has_unit) = self.product()?;
+        let mut sign = value.signum();
+        loop {
+            match self.peek() {
+                Some(Token::Op('+')) => {
+                    self.next += 1;
+                    let (term, unit) = self.product()?;
+                    value += term;
+                    (sign, has_unit) = (1.0, unit);
+                }
+                Some(Token::Op('-')) => {
+                    self.next += 1;
+                    let (term, unit) = self.product()?;
+                    value -= term;
+                    (sign, has_unit) = (-1.0, unit);
+                }
+                // A number with a unit followed by another number continues
+                // the same term, so lengths like -5' 3" can be typed. The
+                // number that continues the term needs its own unit, since
+                // it would be ambiguous otherwise.
+                Some(Token::Number(..)) if has_unit => {
+                    let (term, unit) = self.product()?;
+                    if !unit {
+                        return Err("a number that follows a unit needs a unit too".to_owned());
+                    }
+                    value += sign * term;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    /// Returns whether the last factor was written with a unit
+    fn product(&mut self) -> Result<(f64, bool), String> {
+        let (mut value, mut has_unit) = self.factor()?;
+        loop {
+            match self.peek() {
+                Some(Token::Op('*')) => {
+                    self.next += 1;
+                    let (factor, unit) = self.factor()?;
+                    value *= factor;
+                    has_unit = unit;
+                }
+                Some(Token::Op('/')) => {
+                    self.next += 1;
+                    let (factor, unit) = self.factor()?;
+                    if factor == 0.0 {
+                        return Err("division by zero".to_owned());
+                    }
+                    value /= factor;
+                    has_unit = unit;
+                }
+                _ => return Ok((value, has_unit)),
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<(f64, bool), String> {
+        let token = self.peek().cloned();
+        self.next += 1;
+        match token {
+            Some(Token::Op('-')) => self.factor().map(|(v, unit)| (-v, unit)),
+            Some(Token::Op('+')) => self.factor(),
+            Some(Token::Number(value, None)) => Ok((value * self.quantity.bare_scale(), false)),
+            Some(Token::Number(value, Some(unit))) => {
+                Ok((value * self.quantity.unit_scale(&unit)?, true))
+            }
+            Some(Token::Open) => {
+                let value = self.sum()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err("missing )".to_owned());
+                }
+                self.next += 1;
+                Ok((value, false))
+            }
+            Some(token) => Err(format!("unexpected {token:?}")),
+            None => Err("expression ended early".to_owned()),
+        }
+    }
+}
+
+/// Evaluate simple arithmetic like 1.2+0.35 or 10/3. Numbers may have a unit
+/// suffix like 450mm, which is converted into meters for lengths or radians
+/// for angles.
+pub fn evaluate(text: &str, quantity: Quantity) -> Result<f64, String> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        next: 0,
+        quantity,
+    };
+    let value = parser.sum()?;
+    if parser.next < tokens.len() {
+        return Err(format!("unexpected {:?}", tokens[parser.next]));
+    }
+    if !value.is_finite() {
+        return Err("the result is not a finite number".to_owned());
+    }
+    Ok(value)
+}
+
+/// Show a drag value that becomes a text field when it is clicked, so that
+/// an expression can be typed into it. The expression is evaluated when the
+/// text field loses focus and its value is returned. Pressing escape leaves
+/// the value unchanged.
+pub fn drag_or_expression(
+    ui: &mut Ui,
+    quantity: Quantity,
+    edit_text: impl FnOnce() -> String,
+    drag: impl FnOnce(&mut Ui) -> Response,
+) -> (Response, Option<f64>) {
+    let id = ui.next_auto_id().with("expression");
+    let editing = ui.data().get_temp::<String>(id);
+    let Some(mut text) = editing else {
+        let response = drag(ui);
+        if response.clicked() {
+            ui.data().insert_temp(id, edit_text());
+            ui.memory().request_focus(id);
+        }
+        return (response, None);
+    };
+
+    let mut response = ui.add(TextEdit::singleline(&mut text).id(id).desired_width(80.0));
+    if ui.input().key_pressed(Key::Escape) {
+        ui.data().remove::<String>(id);
+        return (response, None);
+    }
+
+    if response.lost_focus() {
+        ui.data().remove::<String>(id);
+        return match evaluate(&text, quantity) {
+            Ok(value) => {
+                response.mark_changed();
+                (response, Some(value))
+            }
+            Err(err) => {
+                report_warning(None, format!("Unable to evaluate [{text}]: {err}"));
+                (response, None)
+            }
+        };
+    }
+
+    ui.data().insert_temp(id, text);
+    (response, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::METERS_PER_FOOT;
+    use std::f64::consts::PI;
+
+    fn assert_evaluates(text: &str, quantity: Quantity, expected: f64) {
+        let value = evaluate(text, quantity).unwrap();
+        assert!(
+            (value - expected).abs() < 1e-6,
+            "[{text}] evaluated to {value} instead of {expected}"
+        );
+    }
+
+    #[test]
+    fn operators_follow_precedence() {
+        assert_evaluates("1+2*3", Quantity::Plain, 7.0);
+        assert_evaluates("(1+2)*3", Quantity::Plain, 9.0);
+        assert_evaluates("10/4-1", Quantity::Plain, 1.5);
+        assert_evaluates("8/2/2", Quantity::Plain, 2.0);
+        assert_evaluates("-2*3", Quantity::Plain, -6.0);
+        assert_evaluates("2*-3", Quantity::Plain, -6.0);
+        assert_evaluates("1-(2-3)", Quantity::Plain, 2.0);
+    }
+
+    #[test]
+    fn units_are_converted() {
+        let meters = Quantity::Length(LengthUnit::Meters);
+        assert_evaluates("450mm", meters, 0.45);
+        assert_evaluates("2cm + 1m", meters, 1.02);
+        assert_evaluates("1km", meters, 1000.0);
+        assert_evaluates("2ft", meters, 0.6096);
+        assert_evaluates("6in", meters, 0.1524);
+        assert_evaluates("5' 3\"", meters, 1.6002);
+        assert_evaluates("-5' 3\"", meters, -1.6002);
+        assert_evaluates("1.5", meters, 1.5);
+
+        // Numbers without a unit are in the displayed unit
+        let feet = Quantity::Length(LengthUnit::FeetInches);
+        assert_evaluates("2", feet, 2.0 * METERS_PER_FOOT as f64);
+        assert_evaluates("2 m", feet, 2.0);
+
+        let degrees = Quantity::Angle(AngleUnit::Degrees);
+        assert_evaluates("180", degrees, 180_f32.to_radians() as f64);
+        assert_evaluates("1 rad", degrees, 1.0);
+        assert_evaluates("90°", Quantity::Angle(AngleUnit::Radians), PI / 2.0);
+    }
+
+    #[test]
+    fn whitespace_is_ignored() {
+        assert_evaluates("  1 +\t2 ", Quantity::Plain, 3.0);
+        assert_evaluates("( 1 + 2 ) * 3", Quantity::Plain, 9.0);
+        assert_evaluates("450 mm", Quantity::Length(LengthUnit::Meters), 0.45);
+    }
+
+    #[test]
+    fn invalid_expressions_are_errors() {
+        let meters = Quantity::Length(LengthUnit::Meters);
+        for text in [
+            "", "1+", "(1+2", "1+2)", "1 2", "1m 2", "1..2", "1 $", "1/0", "1/(2-2)",
+        ] {
+            assert!(
+                evaluate(text, meters).is_err(),
+                "[{text}] should not evaluate"
+            );
+        }
+        // Units that do not belong to the quantity are rejected
+        assert!(evaluate("3m", Quantity::Angle(AngleUnit::Degrees)).is_err());
+        assert!(evaluate("30deg", meters).is_err());
+        assert!(evaluate("3m", Quantity::Plain).is_err());
+    }
+}
@@ -17,14 +17,18 @@
 
 use crate::{
     interaction::{ChangeMode, Hover, MoveTo, SelectAnchor3D},
+    localization::tr,
     site::{
         Anchor, AssociatedGraphs, Category, Change, Dependents, LocationTags, MeshConstraint,
         SiteID, Subordinate,
     },
-    widgets::{inspector::InspectPose, inspector::SelectionWidget, AppEvents, Icons},
+    widgets::{
+        inspector::{InspectLength, InspectPose, SelectionWidget},
+        AppEvents, Icons,
+    },
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
-use bevy_egui::egui::{DragValue, ImageButton, Ui};
+use bevy_egui::egui::{ImageButton, Ui};
 use std::collections::{BTreeMap, HashSet};
 
 #[derive(SystemParam)]
@@ -91,7 +95,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorWidget<'a, 'w1, 'w2, 's1, 's2> {
             }
 
             replace = assign_response.clicked();
-            assign_response.on_hover_text("Reassign");
+            assign_response.on_hover_text(tr(ui, "Reassign"));
         }
 
         if let Ok((anchor, tf, subordinate, parent, mesh_constraint)) =
@@ -100,10 +104,11 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorWidget<'a, 'w1, 'w2, 's1, 's2> {
             if let Some(subordinate) = subordinate {
                 ui.horizontal(|ui| {
                     if let Some(boss) = subordinate.0 {
-                        ui.label("Subordinate to ").on_hover_text(
+                        ui.label(tr(ui, "Subordinate to ")).on_hover_text(tr(
+                            ui,
                             "The position of a subordinate anchor is \
                                 managed by the properties of another entity.",
-                        );
+                        ));
                         SelectionWidget::new(
                             boss,
                             self.params.site_id.get(boss).ok().copied(),
@@ -112,7 +117,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorWidget<'a, 'w1, 'w2, 's1, 's2> {
                         )
                         .show(ui);
                     } else {
-                        ui.label("Anonymous subordinate");
+                        ui.label(tr(ui, "Anonymous subordinate"));
                     }
                 });
             } else {
@@ -122,14 +127,14 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorWidget<'a, 'w1, 'w2, 's1, 's2> {
                             ui.label("x");
                         }
                         let mut x = tf.translation.x;
-                        ui.add(DragValue::new(&mut x).speed(0.01));
+                        InspectLength::new(&mut x).speed(0.01).show(ui);
                         // TODO(MXG): Make the drag speed a user-defined setting
 
                         if !self.is_dependency {
                             ui.label("y");
                         }
                         let mut y = tf.translation.y;
-                        ui.add(DragValue::new(&mut y).speed(0.01));
+                        InspectLength::new(&mut y).speed(0.01).show(ui);
 
                         if x != tf.translation.x || y != tf.translation.y {
                             self.events.request.move_to.send(MoveTo {
@@ -163,7 +168,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorWidget<'a, 'w1, 'w2, 's1, 's2> {
                                             self.anchor,
                                         ));
                                 }
-                                ui.label("Mesh Parent");
+                                ui.label(tr(ui, "Mesh Parent"));
                                 SelectionWidget::new(
                                     c.entity,
                                     self.params.site_id.get(c.entity).ok().cloned(),
@@ -172,7 +177,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorWidget<'a, 'w1, 'w2, 's1, 's2> {
                                 )
                                 .show(ui);
 
-                                ui.label("Frame Parent");
+                                ui.label(tr(ui, "Frame Parent"));
                                 SelectionWidget::new(
                                     parent.get(),
                                     self.params.site_id.get(parent.get()).ok().cloned(),
@@ -191,7 +196,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorWidget<'a, 'w1, 'w2, 's1, 's2> {
                                 }
 
                                 // Parent reassigning widget
-                                ui.label("Parent");
+                                ui.label(tr(ui, "Parent"));
                                 SelectionWidget::new(
                                     parent.get(),
                                     self.params.site_id.get(parent.get()).ok().cloned(),
@@ -210,7 +215,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorWidget<'a, 'w1, 'w2, 's1, 's2> {
                                 }
 
                                 let parent_replace = assign_response.clicked();
-                                assign_response.on_hover_text("Reassign");
+                                assign_response.on_hover_text(tr(ui, "Reassign"));
 
                                 if parent_replace {
                                     let request =
@@ -269,7 +274,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorDependentsWidget<'a, 'w1, 'w2, 's1, 's
         events: &mut AppEvents<'w2, 's2>,
         ui: &mut Ui,
     ) {
-        ui.heading("Dependents");
+        ui.heading(tr(ui, "Dependents"));
         let mut category_map: BTreeMap<Category, BTreeMap<Entity, Option<u32>>> = BTreeMap::new();
         for e in dependents {
             if let Ok((category, site_id)) = params.info.get(*e) {
@@ -298,12 +303,15 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectAnchorDependentsWidget<'a, 'w1, 'w2, 's1, 's
         ui.vertical(|ui| {
             if let Ok(dependents) = self.params.dependents.get(self.anchor) {
                 if dependents.is_empty() {
-                    ui.label("No dependents");
+                    ui.label(tr(ui, "No dependents"));
                 } else {
                     Self::show_dependents(&dependents.0, &self.params, &mut self.events, ui);
                 }
             } else {
-                ui.label("ERROR: Unable to find dependents info for this anchor");
+                ui.label(tr(
+                    ui,
+                    "ERROR: Unable to find dependents info for this anchor",
+                ));
             }
         });
     }
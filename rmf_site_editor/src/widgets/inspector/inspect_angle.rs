@@ -15,10 +15,16 @@
  *
 */
 
+use crate::{
+    widgets::inspector::{drag_or_expression, Quantity},
+    AngleUnit, DisplayUnits,
+};
 use bevy_egui::egui::{DragValue, Ui};
 use rmf_site_format::Angle;
 use std::ops::RangeInclusive;
 
+/// Edit an angle in the angle unit that the user prefers. The angle keeps the
+/// unit that it is stored with.
 pub struct InspectAngle<'a> {
     angle: &'a mut Angle,
     range_degrees: RangeInclusive<f32>,
@@ -38,40 +44,42 @@ impl<'a> InspectAngle<'a> {
     }
 
     pub fn show(self, ui: &mut Ui) {
-        ui.horizontal(|ui| match self.angle {
-            Angle::Deg(deg) => {
-                ui.add(
-                    DragValue::new(deg)
-                        .min_decimals(0)
-                        .max_decimals(1)
-                        .speed(1.0)
-                        .clamp_range(self.range_degrees),
-                );
+        let unit = DisplayUnits::of(ui).angle;
+        let mut value = unit.from_radians(self.angle.radians());
+        let current = value;
+        let range = unit.from_radians(self.range_degrees.start().to_radians())
+            ..=unit.from_radians(self.range_degrees.end().to_radians());
+        let drag = match unit {
+            AngleUnit::Degrees => DragValue::new(&mut value)
+                .min_decimals(0)
+                .max_decimals(1)
+                .speed(1.0),
+            AngleUnit::Radians => DragValue::new(&mut value)
+                .min_decimals(2)
+                .max_decimals(4)
+                .speed(std::f32::consts::PI / 180.0),
+        };
 
-                let response = ui.button("deg").on_hover_text("Click to change to radians");
-
-                if response.clicked() {
-                    *self.angle = Angle::Rad(self.angle.radians());
-                }
+        let drag = drag.clamp_range(range).suffix(unit.suffix());
+        let (_, typed) = drag_or_expression(
+            ui,
+            Quantity::Angle(unit),
+            || format!("{current}"),
+            |ui| ui.add(drag),
+        );
+        let radians = match typed {
+            // Typed angles are evaluated in radians
+            Some(typed) => Some((typed as f32).clamp(
+                self.range_degrees.start().to_radians(),
+                self.range_degrees.end().to_radians(),
+            )),
+            None => (value != current).then(|| unit.to_radians(value)),
+        };
+        if let Some(radians) = radians {
+            match self.angle {
+                Angle::Deg(deg) => *deg = radians.to_degrees(),
+                Angle::Rad(rad) => *rad = radians,
             }
-            Angle::Rad(rad) => {
-                ui.add(
-                    DragValue::new(rad)
-                        .min_decimals(2)
-                        .max_decimals(4)
-                        .speed(std::f32::consts::PI / 180.0)
-                        .clamp_range(
-                            self.range_degrees.start().to_radians()
-                                ..=self.range_degrees.end().to_radians(),
-                        ),
-                );
-
-                let response = ui.button("rad").on_hover_text("Click to change to degrees");
-
-                if response.clicked() {
-                    *self.angle = Angle::Deg(self.angle.degrees());
-                }
-            }
-        });
+        }
     }
 }
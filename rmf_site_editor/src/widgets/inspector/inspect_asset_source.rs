@@ -15,6 +15,7 @@
  *
 */
 
+use crate::localization::tr;
 use bevy_egui::egui::{ComboBox, Ui};
 use rmf_site_format::{AssetSource, RecallAssetSource};
 
@@ -42,7 +43,7 @@ impl<'a> InspectAssetSource<'a> {
             AssetSource::Package(path) => path,
         };
         ui.horizontal(|ui| {
-            ui.label("Source");
+            ui.label(tr(ui, "Source"));
             ComboBox::from_id_source("Asset Source")
                 .selected_text(new_source.label())
                 .show_ui(ui, |ui| {
@@ -63,7 +64,7 @@ impl<'a> InspectAssetSource<'a> {
                 ui.horizontal(|ui| {
                     // Button to load from file, disabled for wasm since there are no local files
                     #[cfg(not(target_arch = "wasm32"))]
-                    if ui.button("Browse").clicked() {
+                    if ui.button(tr(ui, "Browse")).clicked() {
                         if let Some(file) = FileDialog::new().pick_file() {
                             if let Some(src) = file.to_str() {
                                 *name = String::from(src);
@@ -16,6 +16,7 @@
 */
 
 use crate::{
+    localization::tr,
     site::{
         AssociatedGraphs, Change, ConsiderAssociatedGraph, NameInSite, NavGraphMarker,
         RecallAssociatedGraphs,
@@ -68,7 +69,7 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectAssociatedGraphsWidget<'a, 'w1, 's1, 'w2, 's
 
         let mut new_associated = associated.clone();
         ui.horizontal(|ui| {
-            ui.label("Associated Graphs");
+            ui.label(tr(ui, "Associated Graphs"));
             ComboBox::from_id_source("Associated Graphs")
                 .selected_text(new_associated.label())
                 .show_ui(ui, |ui| {
@@ -108,7 +109,7 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectAssociatedGraphsWidget<'a, 'w1, 's1, 'w2, 's
 
                 if let Some((first, _)) = unused_graphs.iter().next() {
                     ui.horizontal(|ui| {
-                        let add_graph = ui.button("Add").clicked();
+                        let add_graph = ui.button(tr(ui, "Add")).clicked();
                         let mut choice = recall.consider.unwrap_or(*first);
                         let choice_text = unused_graphs
                             .get(&choice)
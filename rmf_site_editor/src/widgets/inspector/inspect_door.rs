@@ -15,6 +15,7 @@
  *
 */
 
+use crate::localization::tr;
 use crate::widgets::inspector::{InspectAngle, InspectSide};
 use bevy_egui::egui::{ComboBox, DragValue, Ui};
 use rmf_site_format::{DoorType, RecallDoorType, Swing};
@@ -32,7 +33,7 @@ impl<'a> InspectDoorType<'a> {
     pub fn show(self, ui: &mut Ui) -> Option<DoorType> {
         let mut new_kind = self.kind.clone();
         ui.horizontal(|ui| {
-            ui.label("Door Type:");
+            ui.label(tr(ui, "Door Type:"));
             ComboBox::from_id_source("Door Type")
                 .selected_text(self.kind.label())
                 .show_ui(ui, |ui| {
@@ -51,25 +52,25 @@ impl<'a> InspectDoorType<'a> {
         match &mut new_kind {
             DoorType::SingleSliding(door) => {
                 ui.horizontal(|ui| {
-                    ui.label("Direction:")
-                        .on_hover_text("The direction the door will slide towards");
+                    ui.label(tr(ui, "Direction:"))
+                        .on_hover_text(tr(ui, "The direction the door will slide towards"));
                     InspectSide::new(&mut door.towards).show(ui);
                 });
             }
             DoorType::DoubleSliding(door) => {
                 ui.horizontal(|ui| {
-                    ui.label("Left : Right");
+                    ui.label(tr(ui, "Left : Right"));
                     ui.add(
                         DragValue::new(&mut door.left_right_ratio)
                             .speed(0.01)
                             .clamp_range(0.0..=std::f32::INFINITY),
                     )
-                    .on_hover_text("(Left Door Length)/(Right Door Length)");
+                    .on_hover_text(tr(ui, "(Left Door Length)/(Right Door Length)"));
                 });
             }
             DoorType::SingleSwing(door) => {
                 ui.horizontal(|ui| {
-                    ui.label("Pivot Side: ");
+                    ui.label(tr(ui, "Pivot Side: "));
                     InspectSide::new(&mut door.pivot_on).show(ui);
                 });
                 ui.add_space(5.0);
@@ -79,7 +80,7 @@ impl<'a> InspectDoorType<'a> {
                 InspectSwing::new(&mut door.swing).show(ui);
             }
             DoorType::Model(_) => {
-                ui.label("Not yet supported");
+                ui.label(tr(ui, "Not yet supported"));
             }
         }
 
@@ -102,7 +103,7 @@ impl<'a> InspectSwing<'a> {
 
     pub fn show(self, ui: &mut Ui) {
         ui.horizontal(|ui| {
-            ui.label("Swing:");
+            ui.label(tr(ui, "Swing:"));
             ComboBox::from_id_source("Door Swing")
                 .selected_text(self.swing.label())
                 .show_ui(ui, |ui| {
@@ -119,25 +120,25 @@ impl<'a> InspectSwing<'a> {
         match self.swing {
             Swing::Forward(angle) => {
                 ui.horizontal(|ui| {
-                    ui.label("Limit:");
+                    ui.label(tr(ui, "Limit:"));
                     InspectAngle::new(angle).range_degrees(0.0..=180.0).show(ui);
                 });
             }
             Swing::Backward(angle) => {
                 ui.horizontal(|ui| {
-                    ui.label("Limit:");
+                    ui.label(tr(ui, "Limit:"));
                     InspectAngle::new(angle).range_degrees(0.0..=180.0).show(ui);
                 });
             }
             Swing::Both { forward, backward } => {
                 ui.horizontal(|ui| {
-                    ui.label("Forward Limit: ");
+                    ui.label(tr(ui, "Forward Limit: "));
                     InspectAngle::new(forward)
                         .range_degrees(0.0..=180.0)
                         .show(ui);
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Backward Limit: ");
+                    ui.label(tr(ui, "Backward Limit: "));
                     InspectAngle::new(backward)
                         .range_degrees(0.0..=180.0)
                         .show(ui);
@@ -17,6 +17,7 @@
 
 use crate::{
     interaction::{ChangeMode, SelectAnchor},
+    localization::tr,
     site::{Category, EdgeLabels, Original},
     widgets::{
         inspector::{InspectAnchorParams, InspectAnchorWidget},
@@ -83,7 +84,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectEdgeWidget<'a, 'w1, 'w2, 's1, 's2> {
 
         Grid::new("inspect_edge").show(ui, |ui| {
             ui.label("");
-            ui.label("ID");
+            ui.label(tr(ui, "ID"));
             ui.label("");
             ui.label("x");
             ui.label("y");
@@ -0,0 +1,117 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{localization::tr, site::Change, widgets::AppEvents};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{DragValue, Grid, Ui};
+use rmf_site_format::{Anchor, Inertial, Mass};
+
+#[derive(SystemParam)]
+pub struct InspectInertialParams<'w, 's> {
+    pub frames: Query<'w, 's, (&'static Anchor, Option<&'static Inertial>)>,
+}
+
+pub struct InspectInertialWidget<'a, 'w1, 'w2, 's1, 's2> {
+    pub frame: Entity,
+    pub params: &'a InspectInertialParams<'w1, 's1>,
+    pub events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 'w2, 's1, 's2> InspectInertialWidget<'a, 'w1, 'w2, 's1, 's2> {
+    pub fn new(
+        frame: Entity,
+        params: &'a InspectInertialParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self {
+            frame,
+            params,
+            events,
+        }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        // Only workcell frames become links with mass properties
+        let Ok((Anchor::Pose3D(_), inertial)) = self.params.frames.get(self.frame) else {
+            return;
+        };
+        let Some(inertial) = inertial else {
+            if ui
+                .button(tr(ui, "Add Inertial"))
+                .on_hover_text(tr(ui, "Give the link of this frame a mass for simulation"))
+                .clicked()
+            {
+                let inertial = Inertial {
+                    mass: Mass(1.0),
+                    ..default()
+                };
+                self.events
+                    .workcell_change
+                    .inertial
+                    .send(Change::new(inertial, self.frame).or_insert());
+            }
+            ui.add_space(10.0);
+            return;
+        };
+
+        ui.label(tr(ui, "Inertial"));
+        let mut new_inertial = inertial.clone();
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Mass"));
+            ui.add(
+                DragValue::new(&mut new_inertial.mass.0)
+                    .clamp_range(0_f32..=std::f32::INFINITY)
+                    .speed(0.01)
+                    .suffix(" kg"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Center of Mass"));
+            for value in &mut new_inertial.origin.trans {
+                ui.add(DragValue::new(value).speed(0.01));
+            }
+        });
+        ui.label(tr(ui, "Inertia (kg·m²)"));
+        let inertia = &mut new_inertial.inertia;
+        Grid::new("inspect_inertia").show(ui, |ui| {
+            ui.add(DragValue::new(&mut inertia.ixx).speed(0.001).prefix("xx "));
+            ui.add(DragValue::new(&mut inertia.ixy).speed(0.001).prefix("xy "));
+            ui.add(DragValue::new(&mut inertia.ixz).speed(0.001).prefix("xz "));
+            ui.end_row();
+
+            ui.label("");
+            ui.add(DragValue::new(&mut inertia.iyy).speed(0.001).prefix("yy "));
+            ui.add(DragValue::new(&mut inertia.iyz).speed(0.001).prefix("yz "));
+            ui.end_row();
+
+            ui.label("");
+            ui.label("");
+            ui.add(DragValue::new(&mut inertia.izz).speed(0.001).prefix("zz "));
+            ui.end_row();
+        });
+
+        if ui.button(tr(ui, "Remove Inertial")).clicked() {
+            self.events.commands.entity(self.frame).remove::<Inertial>();
+        } else if new_inertial != *inertial {
+            self.events
+                .workcell_change
+                .inertial
+                .send(Change::new(new_inertial, self.frame));
+        }
+        ui.add_space(10.0);
+    }
+}
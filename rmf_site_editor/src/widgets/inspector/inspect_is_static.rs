@@ -15,6 +15,7 @@
  *
 */
 
+use crate::localization::tr;
 use bevy_egui::egui::Ui;
 use rmf_site_format::IsStatic;
 
@@ -33,8 +34,11 @@ impl InspectIsStatic {
     #[allow(dead_code)]
     pub fn show(self, ui: &mut Ui) -> Option<IsStatic> {
         let mut new_is_static = self.is_static;
-        ui.checkbox(&mut new_is_static.0, "Static")
-            .on_hover_text("Static means the object cannot move in a simulation");
+        ui.checkbox(&mut new_is_static.0, tr(ui, "Static"))
+            .on_hover_text(tr(
+                ui,
+                "Static means the object cannot move in a simulation",
+            ));
 
         if new_is_static != self.is_static {
             return Some(new_is_static);
@@ -0,0 +1,188 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{localization::tr, site::Change, widgets::AppEvents, workcell::CreateJoint};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{ComboBox, DragValue, Grid, Ui};
+use rmf_site_format::{Anchor, JointLimits, JointProperties, SingleDofJoint};
+
+#[derive(SystemParam)]
+pub struct InspectJointParams<'w, 's> {
+    pub joints: Query<'w, 's, &'static JointProperties>,
+    pub frames: Query<'w, 's, (&'static Anchor, &'static Parent), Without<JointProperties>>,
+}
+
+pub struct InspectJointWidget<'a, 'w1, 'w2, 's1, 's2> {
+    pub entity: Entity,
+    pub params: &'a InspectJointParams<'w1, 's1>,
+    pub events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 'w2, 's1, 's2> InspectJointWidget<'a, 'w1, 'w2, 's1, 's2> {
+    pub fn new(
+        entity: Entity,
+        params: &'a InspectJointParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self {
+            entity,
+            params,
+            events,
+        }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        if let Ok(properties) = self.params.joints.get(self.entity) {
+            if let Some(new_properties) = InspectJointProperties::new(properties).show(ui) {
+                self.events
+                    .workcell_change
+                    .joint_properties
+                    .send(Change::new(new_properties, self.entity));
+            }
+            ui.add_space(10.0);
+            return;
+        }
+
+        // Frames that are not already moved by a joint can be given one
+        let Ok((anchor, parent)) = self.params.frames.get(self.entity) else {
+            return;
+        };
+        if !anchor.is_3D() || self.params.joints.contains(parent.get()) {
+            return;
+        }
+        if ui
+            .button(tr(ui, "Add Joint"))
+            .on_hover_text(tr(ui, "Let this frame move relative to its parent"))
+            .clicked()
+        {
+            self.events
+                .workcell_change
+                .create_joint
+                .send(CreateJoint { frame: self.entity });
+        }
+        ui.add_space(10.0);
+    }
+}
+
+pub struct InspectJointProperties<'a> {
+    pub properties: &'a JointProperties,
+}
+
+impl<'a> InspectJointProperties<'a> {
+    pub fn new(properties: &'a JointProperties) -> Self {
+        Self { properties }
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Option<JointProperties> {
+        let mut new_properties = self.properties.clone();
+        // Keep the axis and limits when switching between the kinds of joints
+        // that have them
+        let dof = self.properties.single_dof().cloned().unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Joint"));
+            ComboBox::from_id_source("Joint Properties")
+                .selected_text(new_properties.label())
+                .show_ui(ui, |ui| {
+                    for variant in [
+                        JointProperties::Fixed,
+                        JointProperties::Revolute(dof.clone()),
+                        JointProperties::Prismatic(dof.clone()),
+                    ] {
+                        let label = variant.label();
+                        ui.selectable_value(&mut new_properties, variant, label);
+                    }
+                });
+        });
+
+        let is_revolute = matches!(new_properties, JointProperties::Revolute(_));
+        if let Some(dof) = new_properties.single_dof_mut() {
+            show_single_dof(dof, is_revolute, ui);
+        }
+
+        if new_properties != *self.properties {
+            Some(new_properties)
+        } else {
+            None
+        }
+    }
+}
+
+fn show_single_dof(dof: &mut SingleDofJoint, is_revolute: bool, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        ui.label(tr(ui, "Axis"));
+        for value in &mut dof.axis {
+            ui.add(DragValue::new(value).speed(0.01));
+        }
+    });
+
+    let mut limited = dof.limits.is_some();
+    let hint = if is_revolute {
+        "Revolute joints without limits can rotate continuously"
+    } else {
+        "Prismatic joints should always have limits"
+    };
+    ui.checkbox(&mut limited, tr(ui, "Limits"))
+        .on_hover_text(hint);
+    if !limited {
+        dof.limits = None;
+        return;
+    }
+
+    let limits = dof.limits.get_or_insert_with(JointLimits::default);
+    let (position_unit, velocity_unit, effort_unit) = if is_revolute {
+        (" rad", " rad/s", " N·m")
+    } else {
+        (" m", " m/s", " N")
+    };
+    Grid::new("joint_limits").show(ui, |ui| {
+        ui.label(tr(ui, "Lower"));
+        ui.add(
+            DragValue::new(&mut limits.lower)
+                .speed(0.01)
+                .clamp_range(std::f32::NEG_INFINITY..=limits.upper)
+                .suffix(position_unit),
+        );
+        ui.end_row();
+
+        ui.label(tr(ui, "Upper"));
+        ui.add(
+            DragValue::new(&mut limits.upper)
+                .speed(0.01)
+                .clamp_range(limits.lower..=std::f32::INFINITY)
+                .suffix(position_unit),
+        );
+        ui.end_row();
+
+        ui.label(tr(ui, "Velocity"));
+        ui.add(
+            DragValue::new(&mut limits.velocity)
+                .speed(0.01)
+                .clamp_range(0_f32..=std::f32::INFINITY)
+                .suffix(velocity_unit),
+        );
+        ui.end_row();
+
+        ui.label(tr(ui, "Effort"));
+        ui.add(
+            DragValue::new(&mut limits.effort)
+                .speed(0.1)
+                .clamp_range(0_f32..=std::f32::INFINITY)
+                .suffix(effort_unit),
+        );
+        ui.end_row();
+    });
+}
@@ -0,0 +1,87 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::localization::tr;
+use crate::widgets::{inspector::InspectLength, Icons};
+use bevy_egui::egui::{DragValue, ImageButton, RichText, Ui};
+use rmf_site_format::LaneCurve;
+
+pub struct InspectLaneCurve<'a> {
+    curve: &'a LaneCurve,
+    icons: &'a Icons,
+}
+
+impl<'a> InspectLaneCurve<'a> {
+    pub fn new(curve: &'a LaneCurve, icons: &'a Icons) -> Self {
+        Self { curve, icons }
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Option<LaneCurve> {
+        let mut new_curve = self.curve.clone();
+        ui.label(RichText::new(tr(ui, "Curve")).size(18.0));
+        let mut deleted = None;
+        for (i, [along, across]) in new_curve.0.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .add(ImageButton::new(self.icons.trash.egui(), [18., 18.]))
+                    .on_hover_text(tr(ui, "Remove this control point"))
+                    .clicked()
+                {
+                    deleted = Some(i);
+                }
+                ui.label(tr(ui, "Along"));
+                ui.add(
+                    DragValue::new(along)
+                        .clamp_range(0.0..=1.0)
+                        .speed(0.01)
+                        .max_decimals(2),
+                )
+                .on_hover_text(tr(
+                    ui,
+                    "Fraction of the way from the start anchor to the end anchor",
+                ));
+                ui.label(tr(ui, "Across"));
+                InspectLength::new(across)
+                    .speed(0.05)
+                    .show(ui)
+                    .on_hover_text(tr(
+                        ui,
+                        "Distance to the left of the straight line between the anchors",
+                    ));
+            });
+        }
+
+        if let Some(i) = deleted {
+            new_curve.0.remove(i);
+        }
+
+        if ui
+            .button(tr(ui, "Add Control Point"))
+            .on_hover_text(tr(ui, "Bend the lane through another point"))
+            .clicked()
+        {
+            let along = new_curve.0.last().map(|[along, _]| *along).unwrap_or(0.0);
+            new_curve.0.push([(along + 1.0) / 2.0, 0.0]);
+        }
+
+        if new_curve != *self.curve {
+            Some(new_curve)
+        } else {
+            None
+        }
+    }
+}
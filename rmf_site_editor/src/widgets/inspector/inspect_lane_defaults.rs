@@ -0,0 +1,87 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{localization::tr, site::LANE_WIDTH, widgets::inspector::InspectOptionF32};
+use bevy::prelude::*;
+use bevy_egui::egui::{ComboBox, Ui};
+use rmf_site_format::{LaneDefaults, OrientationConstraint};
+
+pub struct InspectLaneDefaults<'a> {
+    graph: Entity,
+    defaults: &'a LaneDefaults,
+}
+
+impl<'a> InspectLaneDefaults<'a> {
+    pub fn new(graph: Entity, defaults: &'a LaneDefaults) -> Self {
+        Self { graph, defaults }
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Option<LaneDefaults> {
+        let mut new_defaults = self.defaults.clone();
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Orientation"));
+            ComboBox::from_id_source(("Lane Default Orientation", self.graph))
+                .selected_text(new_defaults.orientation_constraint.label())
+                .show_ui(ui, |ui| {
+                    for variant in &[
+                        OrientationConstraint::None,
+                        OrientationConstraint::Forwards,
+                        OrientationConstraint::Backwards,
+                    ] {
+                        ui.selectable_value(
+                            &mut new_defaults.orientation_constraint,
+                            *variant,
+                            variant.label(),
+                        );
+                    }
+                });
+        });
+
+        if let Some(speed_limit) =
+            InspectOptionF32::new("Speed Limit".to_string(), self.defaults.speed_limit, 1.0)
+                .clamp_range(0.0..=100.0)
+                .min_decimals(2)
+                .max_decimals(2)
+                .speed(0.01)
+                .suffix(" m/s".to_string())
+                .show(ui)
+        {
+            new_defaults.speed_limit = speed_limit;
+        }
+
+        if let Some(lane_width) = InspectOptionF32::new(
+            "Lane Width".to_string(),
+            self.defaults.lane_width,
+            LANE_WIDTH,
+        )
+        .clamp_range(0.01..=100.0)
+        .min_decimals(2)
+        .max_decimals(2)
+        .speed(0.01)
+        .length()
+        .show(ui)
+        {
+            new_defaults.lane_width = lane_width;
+        }
+
+        if new_defaults != *self.defaults {
+            return Some(new_defaults);
+        }
+
+        None
+    }
+}
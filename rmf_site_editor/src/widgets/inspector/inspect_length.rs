@@ -0,0 +1,117 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    widgets::inspector::{drag_or_expression, evaluate, Quantity},
+    DisplayUnits, LengthUnit, METERS_PER_FOOT,
+};
+use bevy_egui::egui::{DragValue, Response, Ui};
+use std::ops::RangeInclusive;
+
+/// Edit a length that is stored in meters, showing it in the length unit
+/// that the user prefers
+pub struct InspectLength<'a> {
+    meters: &'a mut f32,
+    range: RangeInclusive<f32>,
+    speed: f32,
+}
+
+impl<'a> InspectLength<'a> {
+    pub fn new(meters: &'a mut f32) -> Self {
+        Self {
+            meters,
+            range: std::f32::NEG_INFINITY..=std::f32::INFINITY,
+            speed: 1.0,
+        }
+    }
+
+    /// The range of the length in meters
+    pub fn clamp_range(mut self, range: RangeInclusive<f32>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// How many meters the length changes for each pixel that it is dragged
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Response {
+        let unit = DisplayUnits::of(ui).length;
+        let mut value = unit.from_meters(*self.meters);
+        let current = value;
+        let range = unit.from_meters(*self.range.start())..=unit.from_meters(*self.range.end());
+        let drag = DragValue::new(&mut value)
+            .clamp_range(range)
+            .speed(unit.from_meters(self.speed));
+        let drag = match unit {
+            LengthUnit::Meters => drag.suffix(" m"),
+            LengthUnit::FeetInches => drag
+                .custom_formatter(|feet, _| {
+                    LengthUnit::FeetInches.format(feet as f32 * METERS_PER_FOOT, 0)
+                })
+                .custom_parser(parse_feet),
+        };
+
+        let meters = *self.meters;
+        let edit_text = || match unit {
+            LengthUnit::Meters => format!("{meters}"),
+            LengthUnit::FeetInches => unit.format(meters, 3),
+        };
+        let (response, typed) =
+            drag_or_expression(ui, Quantity::Length(unit), edit_text, |ui| ui.add(drag));
+        if let Some(typed) = typed {
+            // Typed lengths are evaluated in meters
+            *self.meters = (typed as f32).clamp(*self.range.start(), *self.range.end());
+        } else if value != current {
+            // Only convert back after an edit so that showing the value never
+            // changes it by a rounding error
+            *self.meters = unit.to_meters(value);
+        }
+        response
+    }
+}
+
+/// Parse the text of a drag value that shows feet and inches, such as 5' 3",
+/// into a number of feet
+fn parse_feet(text: &str) -> Option<f64> {
+    evaluate(text, Quantity::Length(LengthUnit::FeetInches))
+        .ok()
+        .map(|meters| meters / METERS_PER_FOOT as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_feet(text: &str, feet: f64) {
+        let parsed = parse_feet(text).unwrap();
+        assert!((parsed - feet).abs() < 1e-6, "{text} gave {parsed} feet");
+    }
+
+    #[test]
+    fn feet_and_inches_are_parsed() {
+        assert_feet("5' 3\"", 5.25);
+        assert_feet("5'3\"", 5.25);
+        assert_feet("-2' 6\"", -2.5);
+        assert_feet("7", 7.0);
+        assert_feet("6\"", 0.5);
+        assert_feet("1.524 m", 5.0);
+        assert_eq!(parse_feet("five feet"), None);
+    }
+}
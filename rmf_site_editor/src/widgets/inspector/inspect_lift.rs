@@ -16,14 +16,15 @@
 */
 
 use crate::{
-    site::{CabinDoorId, LevelProperties, SiteID, ToggleLiftDoorAvailability},
+    localization::tr,
+    site::{CabinDoorId, GenerateLiftLobbies, LevelProperties, SiteID, ToggleLiftDoorAvailability},
     widgets::{
-        inspector::{InspectOptionF32, SelectionWidget},
+        inspector::{InspectLength, InspectOptionF32, SelectionWidget},
         AppEvents, Icons,
     },
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
-use bevy_egui::egui::{CollapsingHeader, DragValue, Ui};
+use bevy_egui::egui::{CollapsingHeader, Ui};
 use rmf_site_format::lift::*;
 
 #[derive(SystemParam)]
@@ -63,25 +64,19 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
         match &mut new_cabin {
             LiftCabin::Rect(params) => {
                 ui.horizontal(|ui| {
-                    ui.label("width");
-                    ui.add(
-                        DragValue::new(&mut params.width)
-                            .suffix("m")
-                            .clamp_range(0.01..=std::f32::INFINITY)
-                            .fixed_decimals(2)
-                            .speed(0.01),
-                    );
+                    ui.label(tr(ui, "width"));
+                    InspectLength::new(&mut params.width)
+                        .clamp_range(0.01..=std::f32::INFINITY)
+                        .speed(0.01)
+                        .show(ui);
                 });
 
                 ui.horizontal(|ui| {
-                    ui.label("depth");
-                    ui.add(
-                        DragValue::new(&mut params.depth)
-                            .suffix("m")
-                            .clamp_range(0.01..=std::f32::INFINITY)
-                            .fixed_decimals(2)
-                            .speed(0.01),
-                    );
+                    ui.label(tr(ui, "depth"));
+                    InspectLength::new(&mut params.depth)
+                        .clamp_range(0.01..=std::f32::INFINITY)
+                        .speed(0.01)
+                        .show(ui);
                 });
 
                 if let Some(new_t) = InspectOptionF32::new(
@@ -92,10 +87,8 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
                         .unwrap_or(DEFAULT_CABIN_WALL_THICKNESS),
                 )
                 .clamp_range(0.001..=std::f32::INFINITY)
-                .suffix("m".to_string())
-                .min_decimals(2)
-                .max_decimals(4)
                 .speed(0.001)
+                .length()
                 .show(ui)
                 {
                     params.wall_thickness = new_t;
@@ -107,10 +100,8 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
                     recall.gap.unwrap_or(DEFAULT_CABIN_GAP),
                 )
                 .clamp_range(0.001..=std::f32::INFINITY)
-                .suffix("m".to_string())
-                .min_decimals(2)
-                .max_decimals(4)
                 .speed(0.001)
+                .length()
                 .show(ui)
                 {
                     params.gap = new_gap;
@@ -121,10 +112,8 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
                     params.shift,
                     recall.shift.unwrap_or(0.0),
                 )
-                .suffix("m".to_string())
-                .min_decimals(2)
-                .max_decimals(4)
                 .speed(0.001)
+                .length()
                 .show(ui)
                 {
                     params.shift = new_shift;
@@ -146,15 +135,11 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
                                 .show(ui);
 
                                 ui.horizontal(|ui| {
-                                    ui.label("width");
-                                    ui.add(
-                                        DragValue::new(&mut placement.width)
-                                            .suffix("m")
-                                            .clamp_range(0.001..=cabin_width - 0.001)
-                                            .min_decimals(2)
-                                            .max_decimals(4)
-                                            .speed(0.005),
-                                    );
+                                    ui.label(tr(ui, "width"));
+                                    InspectLength::new(&mut placement.width)
+                                        .clamp_range(0.001..=cabin_width - 0.001)
+                                        .speed(0.005)
+                                        .show(ui);
                                 });
 
                                 if let Some(new_shift) = InspectOptionF32::new(
@@ -162,10 +147,8 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
                                     placement.shifted,
                                     0.0,
                                 )
-                                .suffix("m".to_string())
-                                .min_decimals(2)
-                                .max_decimals(4)
                                 .speed(0.005)
+                                .length()
                                 .show(ui)
                                 {
                                     placement.shifted = new_shift;
@@ -176,11 +159,9 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
                                     placement.custom_gap,
                                     cabin_gap,
                                 )
-                                .suffix("m".to_string())
                                 .clamp_range(0.0..=std::f32::INFINITY)
-                                .min_decimals(2)
-                                .max_decimals(4)
                                 .speed(0.001)
+                                .length()
                                 .show(ui)
                                 {
                                     placement.custom_gap = new_gap;
@@ -191,11 +172,9 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
                                     placement.thickness,
                                     DEFAULT_CABIN_DOOR_THICKNESS,
                                 )
-                                .suffix("m".to_string())
                                 .clamp_range(0.001..=std::f32::INFINITY)
-                                .min_decimals(2)
-                                .max_decimals(4)
                                 .speed(0.001)
+                                .length()
                                 .show(ui)
                                 {
                                     placement.thickness = new_t;
@@ -247,6 +226,23 @@ impl<'a, 'w1, 's1, 'w2, 's2> InspectLiftCabin<'a, 'w1, 's1, 'w2, 's2> {
             }
         }
 
+        if ui
+            .button(tr(ui, "Generate Lobby Waypoints"))
+            .on_hover_text(tr(
+                ui,
+                "Create a waypoint in front of each cabin door on every level \
+                that it visits and connect it to the cabin waypoint",
+            ))
+            .clicked()
+        {
+            self.events
+                .site_request
+                .generate_lift_lobbies
+                .send(GenerateLiftLobbies {
+                    for_lift: self.lift,
+                });
+        }
+
         if new_cabin != *cabin {
             let (LiftCabin::Rect(new_params), LiftCabin::Rect(old_params)) =
                 (&mut new_cabin, cabin);
@@ -15,7 +15,11 @@
  *
 */
 
-use crate::site::{LightKind, RecallLightKind};
+use crate::{
+    localization::tr,
+    site::{LightKind, RecallLightKind},
+    widgets::inspector::InspectLength,
+};
 use bevy_egui::egui::{
     color_picker::{color_edit_button_rgba, Alpha},
     ComboBox, DragValue, Rgba, Ui,
@@ -34,7 +38,7 @@ impl<'a> InspectLightKind<'a> {
     pub fn show(self, ui: &mut Ui) -> Option<LightKind> {
         let mut new_kind = self.kind.clone();
         ui.horizontal(|ui| {
-            ui.label("Light Kind:");
+            ui.label(tr(ui, "Light Kind:"));
             ComboBox::from_id_source("Inspect Light Kind ComboBox")
                 .selected_text(self.kind.label())
                 .show_ui(ui, |ui| {
@@ -51,11 +55,11 @@ impl<'a> InspectLightKind<'a> {
         match &mut new_kind {
             LightKind::Point(point) => {
                 ui.horizontal(|ui| {
-                    ui.label("Color");
+                    ui.label(tr(ui, "Color"));
                     color_edit(ui, &mut point.color);
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Intensity");
+                    ui.label(tr(ui, "Intensity"));
                     ui.add(
                         DragValue::new(&mut point.intensity)
                             .clamp_range(0_f32..=std::f32::INFINITY)
@@ -63,28 +67,27 @@ impl<'a> InspectLightKind<'a> {
                     );
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Range");
-                    ui.add(
-                        DragValue::new(&mut point.range).clamp_range(0_f32..=std::f32::INFINITY),
-                    );
+                    ui.label(tr(ui, "Range"));
+                    InspectLength::new(&mut point.range)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .show(ui);
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Radius");
-                    ui.add(
-                        DragValue::new(&mut point.radius)
-                            .clamp_range(0_f32..=std::f32::INFINITY)
-                            .speed(0.1),
-                    );
+                    ui.label(tr(ui, "Radius"));
+                    InspectLength::new(&mut point.radius)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .speed(0.1)
+                        .show(ui);
                 });
-                ui.checkbox(&mut point.enable_shadows, "Enable Shadows");
+                ui.checkbox(&mut point.enable_shadows, tr(ui, "Enable Shadows"));
             }
             LightKind::Spot(spot) => {
                 ui.horizontal(|ui| {
-                    ui.label("Color");
+                    ui.label(tr(ui, "Color"));
                     color_edit(ui, &mut spot.color);
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Intensity");
+                    ui.label(tr(ui, "Intensity"));
                     ui.add(
                         DragValue::new(&mut spot.intensity)
                             .clamp_range(0_f32..=std::f32::INFINITY)
@@ -92,33 +95,34 @@ impl<'a> InspectLightKind<'a> {
                     );
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Range");
-                    ui.add(DragValue::new(&mut spot.range).clamp_range(0_f32..=std::f32::INFINITY));
+                    ui.label(tr(ui, "Range"));
+                    InspectLength::new(&mut spot.range)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .show(ui);
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Radius");
-                    ui.add(
-                        DragValue::new(&mut spot.radius)
-                            .clamp_range(0_f32..=std::f32::INFINITY)
-                            .speed(0.1),
-                    );
+                    ui.label(tr(ui, "Radius"));
+                    InspectLength::new(&mut spot.radius)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .speed(0.1)
+                        .show(ui);
                 });
-                ui.checkbox(&mut spot.enable_shadows, "Enable Shadows");
+                ui.checkbox(&mut spot.enable_shadows, tr(ui, "Enable Shadows"));
             }
             LightKind::Directional(dir) => {
                 ui.horizontal(|ui| {
-                    ui.label("Color");
+                    ui.label(tr(ui, "Color"));
                     color_edit(ui, &mut dir.color);
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Illuminance");
+                    ui.label(tr(ui, "Illuminance"));
                     ui.add(
                         DragValue::new(&mut dir.illuminance)
                             .clamp_range(0_f32..=std::f32::INFINITY)
                             .speed(1000),
                     );
                 });
-                ui.checkbox(&mut dir.enable_shadows, "Enable Shadows");
+                ui.checkbox(&mut dir.enable_shadows, tr(ui, "Enable Shadows"));
             }
         }
 
@@ -16,17 +16,18 @@
 */
 
 use crate::{
+    localization::tr,
     site::{
-        ConsiderLocationTag, LocationTag, LocationTags, Model, RecallAssetSource,
-        RecallLocationTags,
+        ConsiderLocationTag, LocationParameters, LocationTag, LocationTags, Model,
+        RecallAssetSource, RecallLocationTags,
     },
     widgets::{
-        inspector::{InspectAssetSource, InspectName},
+        inspector::{InspectAssetSource, InspectName, InspectOptionString},
         AppEvents, Icons,
     },
 };
 use bevy::prelude::*;
-use bevy_egui::egui::{ComboBox, ImageButton, RichText, Ui};
+use bevy_egui::egui::{ComboBox, DragValue, ImageButton, RichText, Ui};
 use smallvec::SmallVec;
 
 pub struct InspectLocationWidget<'a, 'w1, 'w2, 's2> {
@@ -55,7 +56,7 @@ impl<'a, 'w1, 'w2, 's2> InspectLocationWidget<'a, 'w1, 'w2, 's2> {
     }
 
     pub fn show(self, ui: &mut Ui) -> Option<LocationTags> {
-        ui.label(RichText::new("Location Tags").size(18.0));
+        ui.label(RichText::new(tr(ui, "Location Tags")).size(18.0));
         let mut deleted_tag = None;
         let mut changed_tag = None;
         for (i, tag) in self.tags.0.iter().enumerate() {
@@ -95,10 +96,10 @@ impl<'a, 'w1, 'w2, 's2> InspectLocationWidget<'a, 'w1, 'w2, 's2> {
         }
 
         let added_tag = ui
-            .collapsing("Add...", |ui| {
+            .collapsing(tr(ui, "Add..."), |ui| {
                 let (add, mut consider) = ui
                     .horizontal(|ui| {
-                        let add = ui.button("Confirm").clicked();
+                        let add = ui.button(tr(ui, "Confirm")).clicked();
                         let mut consider = self.recall.assume_tag(self.tags);
                         let mut variants: SmallVec<[LocationTag; 5]> = SmallVec::new();
                         if self.tags.iter().find(|t| t.is_charger()).is_none() {
@@ -220,3 +221,62 @@ impl<'a, 'w1, 'w2, 's2> InspectLocationWidget<'a, 'w1, 'w2, 's2> {
         }
     }
 }
+
+pub struct InspectLocationParameters<'a> {
+    pub tags: &'a LocationTags,
+    pub parameters: &'a LocationParameters,
+}
+
+impl<'a> InspectLocationParameters<'a> {
+    pub fn new(tags: &'a LocationTags, parameters: &'a LocationParameters) -> Self {
+        Self { tags, parameters }
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Option<LocationParameters> {
+        let mut new_parameters = self.parameters.clone();
+        let is_charger = self.tags.iter().any(|t| t.is_charger());
+        let is_parking_spot = self.tags.iter().any(|t| t.is_parking_spot());
+
+        ui.label(RichText::new(tr(ui, "Location Parameters")).size(18.0));
+        if let Some(dock_name) =
+            InspectOptionString::new("Dock Name", &self.parameters.dock_name, &None).show(ui)
+        {
+            new_parameters.dock_name = dock_name;
+        }
+
+        // Only offer the charger type for chargers, but keep showing it if it
+        // was already set so it can still be cleared.
+        if is_charger || self.parameters.charger_type.is_some() {
+            if let Some(charger_type) =
+                InspectOptionString::new("Charger Type", &self.parameters.charger_type, &None)
+                    .show(ui)
+            {
+                new_parameters.charger_type = charger_type;
+            }
+        }
+
+        if is_charger || is_parking_spot || self.parameters.capacity.is_some() {
+            ui.horizontal(|ui| {
+                let mut has_capacity = self.parameters.capacity.is_some();
+                ui.checkbox(&mut has_capacity, tr(ui, "Capacity"))
+                    .on_hover_text(tr(
+                        ui,
+                        "How many robots can use this location at the same time",
+                    ));
+                if has_capacity {
+                    let mut capacity = self.parameters.capacity.unwrap_or(1);
+                    ui.add(DragValue::new(&mut capacity).clamp_range(1..=u32::MAX));
+                    new_parameters.capacity = Some(capacity);
+                } else {
+                    new_parameters.capacity = None;
+                }
+            });
+        }
+
+        if new_parameters != *self.parameters {
+            Some(new_parameters)
+        } else {
+            None
+        }
+    }
+}
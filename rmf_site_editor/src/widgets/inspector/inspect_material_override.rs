@@ -0,0 +1,98 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::localization::tr;
+use crate::widgets::inspector::color_edit;
+use bevy_egui::egui::{DragValue, Grid, Ui};
+use rmf_site_format::{Emissive, MaterialOverride};
+
+pub struct InspectMaterialOverride<'a> {
+    pub material: &'a MaterialOverride,
+}
+
+impl<'a> InspectMaterialOverride<'a> {
+    pub fn new(material: &'a MaterialOverride) -> Self {
+        Self { material }
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Option<MaterialOverride> {
+        let mut new_material = self.material.clone();
+        ui.label(tr(ui, "Material Override")).on_hover_text(tr(
+            ui,
+            "Replace the material of this element without changing its asset",
+        ));
+        Grid::new("inspect_material_override").show(ui, |ui| {
+            let mut enabled = new_material.base_color.is_some();
+            ui.checkbox(&mut enabled, tr(ui, "Color"));
+            if enabled {
+                let color = new_material.base_color.get_or_insert([0.7, 0.7, 0.7, 1.0]);
+                color_edit(ui, color);
+            } else {
+                new_material.base_color = None;
+            }
+            ui.end_row();
+
+            let mut enabled = new_material.metallic.is_some();
+            ui.checkbox(&mut enabled, tr(ui, "Metallic"));
+            if enabled {
+                let metallic = new_material.metallic.get_or_insert(0.0);
+                ui.add(DragValue::new(metallic).clamp_range(0.0..=1.0).speed(0.01));
+            } else {
+                new_material.metallic = None;
+            }
+            ui.end_row();
+
+            let mut enabled = new_material.perceptual_roughness.is_some();
+            ui.checkbox(&mut enabled, tr(ui, "Roughness"));
+            if enabled {
+                let roughness = new_material.perceptual_roughness.get_or_insert(0.5);
+                ui.add(DragValue::new(roughness).clamp_range(0.0..=1.0).speed(0.01));
+            } else {
+                new_material.perceptual_roughness = None;
+            }
+            ui.end_row();
+
+            let mut enabled = new_material.emissive.is_some();
+            ui.checkbox(&mut enabled, tr(ui, "Emissive"))
+                .on_hover_text(tr(
+                    ui,
+                    "Make the material glow, for signs, beacons and light fixtures",
+                ));
+            if enabled {
+                let emissive = new_material.emissive.get_or_insert(Emissive::default());
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_rgb(&mut emissive.color);
+                    ui.add(
+                        DragValue::new(&mut emissive.intensity)
+                            .clamp_range(0.0..=std::f32::INFINITY)
+                            .speed(0.05),
+                    )
+                    .on_hover_text(tr(ui, "Intensity"));
+                });
+            } else {
+                new_material.emissive = None;
+            }
+            ui.end_row();
+        });
+
+        if new_material != *self.material {
+            Some(new_material)
+        } else {
+            None
+        }
+    }
+}
@@ -20,6 +20,7 @@ use rmf_site_format::*;
 use std::collections::{BTreeMap, HashSet};
 
 use crate::{
+    localization::tr,
     site::{Category, SiteID},
     widgets::{inspector::SelectionWidget, AppEvents, Icons},
 };
@@ -57,7 +58,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectModelDependentsWidget<'a, 'w1, 'w2, 's1, 's2
         events: &mut AppEvents<'w2, 's2>,
         ui: &mut Ui,
     ) {
-        ui.heading("Constraint Dependents");
+        ui.heading(tr(ui, "Constraint Dependents"));
         let mut category_map: BTreeMap<Category, BTreeMap<Entity, Option<u32>>> = BTreeMap::new();
         for e in dependents {
             if let Ok((category, site_id)) = params.info.get(*e) {
@@ -86,12 +87,15 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectModelDependentsWidget<'a, 'w1, 'w2, 's1, 's2
         ui.vertical(|ui| {
             if let Ok(dependents) = self.params.dependents.get(self.model) {
                 if dependents.0.is_empty() {
-                    ui.label("No dependents");
+                    ui.label(tr(ui, "No dependents"));
                 } else {
                     Self::show_dependents(&dependents.0, &self.params, &mut self.events, ui);
                 }
             } else {
-                ui.label("ERROR: Unable to find dependents info for this model");
+                ui.label(tr(
+                    ui,
+                    "ERROR: Unable to find dependents info for this model",
+                ));
             }
         });
     }
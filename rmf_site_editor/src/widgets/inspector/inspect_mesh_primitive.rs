@@ -15,7 +15,9 @@
  *
 */
 
-use bevy_egui::egui::{ComboBox, DragValue, Ui};
+use crate::localization::tr;
+use crate::widgets::inspector::InspectLength;
+use bevy_egui::egui::{ComboBox, Ui};
 use rmf_site_format::{MeshPrimitive, RecallMeshPrimitive};
 
 pub struct InspectMeshPrimitive<'a> {
@@ -32,7 +34,7 @@ impl<'a> InspectMeshPrimitive<'a> {
         let mut new_primitive = self.primitive.clone();
         // TODO(luca) implement recall plugin
         ui.horizontal(|ui| {
-            ui.label("Primitive");
+            ui.label(tr(ui, "Primitive"));
             ComboBox::from_id_source("Mesh Primitive")
                 .selected_text(new_primitive.label())
                 .show_ui(ui, |ui| {
@@ -41,6 +43,7 @@ impl<'a> InspectMeshPrimitive<'a> {
                         self.recall.assume_cylinder(self.primitive),
                         self.recall.assume_capsule(self.primitive),
                         self.recall.assume_sphere(self.primitive),
+                        self.recall.assume_cone(self.primitive),
                     ] {
                         ui.selectable_value(&mut new_primitive, variant.clone(), variant.label());
                     }
@@ -49,13 +52,43 @@ impl<'a> InspectMeshPrimitive<'a> {
         });
         match &mut new_primitive {
             MeshPrimitive::Box { size } => {
-                ui.add(DragValue::new(&mut size[0]).clamp_range(0_f32..=std::f32::INFINITY));
-                ui.add(DragValue::new(&mut size[1]).clamp_range(0_f32..=std::f32::INFINITY));
-                ui.add(DragValue::new(&mut size[2]).clamp_range(0_f32..=std::f32::INFINITY));
+                InspectLength::new(&mut size[0])
+                    .clamp_range(0_f32..=std::f32::INFINITY)
+                    .show(ui);
+                InspectLength::new(&mut size[1])
+                    .clamp_range(0_f32..=std::f32::INFINITY)
+                    .show(ui);
+                InspectLength::new(&mut size[2])
+                    .clamp_range(0_f32..=std::f32::INFINITY)
+                    .show(ui);
+            }
+            MeshPrimitive::Cylinder { radius, length }
+            | MeshPrimitive::Capsule { radius, length }
+            | MeshPrimitive::Cone { radius, length } => {
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Radius"));
+                    InspectLength::new(radius)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .speed(0.01)
+                        .show(ui);
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Length"));
+                    InspectLength::new(length)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .speed(0.01)
+                        .show(ui);
+                });
+            }
+            MeshPrimitive::Sphere { radius } => {
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Radius"));
+                    InspectLength::new(radius)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .speed(0.01)
+                        .show(ui);
+                });
             }
-            MeshPrimitive::Cylinder { radius, length } => {}
-            MeshPrimitive::Capsule { radius, length } => {}
-            MeshPrimitive::Sphere { radius } => {}
         }
         if &new_primitive != self.primitive {
             Some(new_primitive)
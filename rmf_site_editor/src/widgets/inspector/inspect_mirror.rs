@@ -0,0 +1,78 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    localization::tr,
+    widgets::{inspector::InspectorWorkcellParams, AppEvents},
+    workcell::{MirrorElement, MirrorPlane},
+    AppState,
+};
+use bevy::prelude::*;
+use bevy_egui::egui::Ui;
+
+pub struct InspectMirrorWidget<'a, 'w1, 'w2, 's1, 's2> {
+    pub entity: Entity,
+    pub params: &'a InspectorWorkcellParams<'w1, 's1>,
+    pub events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 'w2, 's1, 's2> InspectMirrorWidget<'a, 'w1, 'w2, 's1, 's2> {
+    pub fn new(
+        entity: Entity,
+        params: &'a InspectorWorkcellParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self {
+            entity,
+            params,
+            events,
+        }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let is_frame = matches!(
+            self.params.joints.frames.get(self.entity),
+            Ok((anchor, _)) if anchor.is_3D()
+        );
+        // Sensors can also be attached to site models, which cannot be mirrored
+        let is_workcell_sensor = *self.events.app_state.current() == AppState::WorkcellEditor
+            && self.params.sensors.sensors.contains(self.entity);
+        if !is_frame && !self.params.geometry.contains(self.entity) && !is_workcell_sensor {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Mirror about"));
+            for plane in MirrorPlane::ALL {
+                if ui
+                    .button(plane.label())
+                    .on_hover_text(format!(
+                        "Add a copy mirrored about the {} plane of the workcell",
+                        plane.label()
+                    ))
+                    .clicked()
+                {
+                    self.events.workcell_change.mirror.send(MirrorElement {
+                        element: self.entity,
+                        plane,
+                    });
+                }
+            }
+        });
+        ui.add_space(10.0);
+    }
+}
@@ -0,0 +1,67 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::localization::tr;
+use bevy_egui::egui::{Key, Ui};
+use rmf_site_format::ModelTags;
+
+pub struct InspectModelTags<'a> {
+    pub tags: &'a ModelTags,
+    /// Text of the tag that the user is typing in
+    pub new_tag: &'a mut String,
+}
+
+impl<'a> InspectModelTags<'a> {
+    pub fn new(tags: &'a ModelTags, new_tag: &'a mut String) -> Self {
+        Self { tags, new_tag }
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Option<ModelTags> {
+        let mut new_tags = self.tags.clone();
+        ui.label(tr(ui, "Tags"));
+        for tag in &self.tags.0 {
+            ui.horizontal(|ui| {
+                if ui
+                    .button("❌")
+                    .on_hover_text(tr(ui, "Remove this tag"))
+                    .clicked()
+                {
+                    new_tags.0.remove(tag);
+                }
+                ui.label(tag);
+            });
+        }
+
+        ui.horizontal(|ui| {
+            let response = ui.text_edit_singleline(self.new_tag);
+            let submitted = response.lost_focus() && ui.input().key_pressed(Key::Enter);
+            if ui.button(tr(ui, "Add")).clicked() || submitted {
+                let tag = self.new_tag.trim();
+                if !tag.is_empty() {
+                    new_tags.0.insert(tag.to_owned());
+                }
+                self.new_tag.clear();
+            }
+        });
+
+        if new_tags != *self.tags {
+            Some(new_tags)
+        } else {
+            None
+        }
+    }
+}
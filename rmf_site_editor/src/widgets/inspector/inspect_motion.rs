@@ -15,6 +15,7 @@
  *
 */
 
+use crate::localization::tr;
 use crate::widgets::inspector::{InspectAngle, InspectOptionF32};
 use bevy_egui::egui::{ComboBox, RichText, Ui};
 use rmf_site_format::{
@@ -46,7 +47,7 @@ impl<'a> InspectMotionWidget<'a> {
                     .absolute_yaw()
                     .unwrap_or(self.recall.absolute_yaw.unwrap_or(Angle::Deg(0.0)));
 
-                ui.label("Orientation Constraint");
+                ui.label(tr(ui, "Orientation Constraint"));
                 let mut orientation = self.motion.orientation_constraint.clone();
                 ComboBox::from_id_source("Orientation Constraint")
                     .selected_text(orientation.label())
@@ -97,7 +98,7 @@ impl<'a> InspectMotionWidget<'a> {
 
         ui.add_space(10.0);
         let mut has_dock = self.motion.dock.is_some();
-        ui.checkbox(&mut has_dock, "Dock");
+        ui.checkbox(&mut has_dock, tr(ui, "Dock"));
         let new_dock = if has_dock {
             let mut dock =
                 self.motion
@@ -115,7 +116,7 @@ impl<'a> InspectMotionWidget<'a> {
                     }));
 
             ui.horizontal(|ui| {
-                ui.label("name");
+                ui.label(tr(ui, "name"));
                 ui.text_edit_singleline(&mut dock.name);
             });
 
@@ -191,7 +192,7 @@ impl<'a> InspectReverseWidget<'a> {
             .unwrap_or(self.recall.motion.clone().unwrap_or(Motion::default()));
 
         let mut new_reverse = self.reverse.clone();
-        ui.label(RichText::new("Reverse Motion").size(18.0));
+        ui.label(RichText::new(tr(ui, "Reverse Motion")).size(18.0));
         ComboBox::from_id_source("Reverse Lane")
             .selected_text(new_reverse.label())
             .show_ui(ui, |ui| {
@@ -15,6 +15,7 @@
  *
 */
 
+use crate::localization::tr;
 use bevy_egui::egui::Ui;
 use rmf_site_format::{NameInSite, NameInWorkcell};
 
@@ -29,7 +30,7 @@ impl<'a> InspectName<'a> {
 
     pub fn show(self, ui: &mut Ui) -> Option<NameInSite> {
         ui.horizontal(|ui| {
-            ui.label("Name");
+            ui.label(tr(ui, "Name"));
             let mut new_name = self.name.clone();
             ui.text_edit_singleline(&mut new_name.0);
             if new_name != *self.name {
@@ -53,7 +54,7 @@ impl<'a> InspectNameInWorkcell<'a> {
 
     pub fn show(self, ui: &mut Ui) -> Option<NameInWorkcell> {
         ui.horizontal(|ui| {
-            ui.label("Name");
+            ui.label(tr(ui, "Name"));
             let mut new_name = self.name.clone();
             ui.text_edit_singleline(&mut new_name.0);
             if new_name != *self.name {
@@ -15,6 +15,7 @@
  *
 */
 
+use crate::widgets::inspector::{drag_or_expression, InspectLength, Quantity};
 use bevy_egui::egui::{DragValue, Ui};
 use std::ops::RangeInclusive;
 
@@ -28,6 +29,7 @@ pub struct InspectOptionF32 {
     speed: f64,
     suffix: String,
     tooltip: Option<String>,
+    is_length: bool,
 }
 
 impl InspectOptionF32 {
@@ -42,6 +44,7 @@ impl InspectOptionF32 {
             speed: 1.0,
             suffix: Default::default(),
             tooltip: Default::default(),
+            is_length: false,
         }
     }
 
@@ -75,20 +78,44 @@ impl InspectOptionF32 {
         self
     }
 
+    /// The value is a length in meters, so show it in the length unit that
+    /// the user prefers. The suffix and decimals are not used for lengths.
+    pub fn length(mut self) -> Self {
+        self.is_length = true;
+        self
+    }
+
     pub fn show(self, ui: &mut Ui) -> Option<Option<f32>> {
         ui.horizontal(|ui| {
             let mut has_value = self.current_value.is_some();
             let mut assumed_value = self.current_value.unwrap_or(self.assumed_value);
             ui.checkbox(&mut has_value, self.title);
             if has_value {
-                let response = ui.add(
-                    DragValue::new(&mut assumed_value)
+                let response = if self.is_length {
+                    InspectLength::new(&mut assumed_value)
                         .clamp_range(self.range)
+                        .speed(self.speed as f32)
+                        .show(ui)
+                } else {
+                    let current = assumed_value;
+                    let drag = DragValue::new(&mut assumed_value)
+                        .clamp_range(self.range.clone())
                         .min_decimals(self.min_decimals)
                         .max_decimals_opt(self.max_decimals)
                         .speed(self.speed)
-                        .suffix(self.suffix),
-                );
+                        .suffix(self.suffix);
+                    let (response, typed) = drag_or_expression(
+                        ui,
+                        Quantity::Plain,
+                        || format!("{current}"),
+                        |ui| ui.add(drag),
+                    );
+                    if let Some(typed) = typed {
+                        assumed_value =
+                            (typed as f32).clamp(*self.range.start(), *self.range.end());
+                    }
+                    response
+                };
 
                 if let Some(tooltip) = self.tooltip {
                     response.on_hover_text(tooltip);
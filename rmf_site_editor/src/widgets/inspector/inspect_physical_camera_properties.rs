@@ -16,6 +16,7 @@
 */
 
 use crate::inspector::{InspectAngle, InspectValue};
+use crate::localization::tr;
 use crate::widgets::egui::RichText;
 use bevy_egui::egui::{Grid, Ui};
 use rmf_site_format::PhysicalCameraProperties;
@@ -31,7 +32,7 @@ impl<'a> InspectPhysicalCameraProperties<'a> {
 
     pub fn show(self, ui: &mut Ui) -> Option<PhysicalCameraProperties> {
         let mut new_properties = self.properties.clone();
-        ui.label(RichText::new("Camera Properties").size(18.0));
+        ui.label(RichText::new(tr(ui, "Camera Properties")).size(18.0));
         Grid::new("physical_camera_properties").show(ui, |ui| {
             if let Some(new_width) =
                 InspectValue::<u32>::new(String::from("Width"), new_properties.width)
@@ -63,7 +64,7 @@ impl<'a> InspectPhysicalCameraProperties<'a> {
         });
         // Outside of main grid to avoid left padding
         ui.horizontal(|ui| {
-            ui.label("Horizontal fov");
+            ui.label(tr(ui, "Horizontal fov"));
             InspectAngle::new(&mut new_properties.horizontal_fov)
                 .range_degrees(0.0..=180.0)
                 .show(ui);
@@ -15,7 +15,8 @@
  *
 */
 
-use crate::widgets::inspector::InspectAngle;
+use crate::localization::tr;
+use crate::widgets::inspector::{InspectAngle, InspectLength};
 use bevy::math::Quat;
 use bevy_egui::egui::{ComboBox, DragValue, Grid, Ui};
 use rmf_site_format::{Pose, Rotation};
@@ -47,16 +48,22 @@ impl<'a> InspectPose<'a> {
                 ui.label("z");
                 ui.end_row();
 
-                ui.add(DragValue::new(&mut new_pose.trans[0]).speed(0.01));
-                ui.add(DragValue::new(&mut new_pose.trans[1]).speed(0.01));
-                ui.add(DragValue::new(&mut new_pose.trans[2]).speed(0.01));
+                InspectLength::new(&mut new_pose.trans[0])
+                    .speed(0.01)
+                    .show(ui);
+                InspectLength::new(&mut new_pose.trans[1])
+                    .speed(0.01)
+                    .show(ui);
+                InspectLength::new(&mut new_pose.trans[2])
+                    .speed(0.01)
+                    .show(ui);
                 ui.end_row();
             });
             ui.add_space(5.0);
         }
 
         ui.horizontal(|ui| {
-            ui.label("Rotation");
+            ui.label(tr(ui, "Rotation"));
             ComboBox::from_id_source("pose_rotation")
                 .selected_text(new_pose.rot.label())
                 .show_ui(ui, |ui| {
@@ -76,9 +83,9 @@ impl<'a> InspectPose<'a> {
             }
             Rotation::EulerExtrinsicXYZ([roll, pitch, yaw]) => {
                 Grid::new("inspect_rotation_euler_xyz").show(ui, |ui| {
-                    ui.label("roll");
-                    ui.label("pitch");
-                    ui.label("yaw");
+                    ui.label(tr(ui, "roll"));
+                    ui.label(tr(ui, "pitch"));
+                    ui.label(tr(ui, "yaw"));
                     ui.end_row();
 
                     InspectAngle::new(roll).show(ui);
@@ -101,7 +108,7 @@ impl<'a> InspectPose<'a> {
                     ui.end_row();
                 });
 
-                if ui.button("normalize").clicked() {
+                if ui.button(tr(ui, "normalize")).clicked() {
                     let normalized = Quat::from_array([*x, *y, *z, *w]).normalize();
                     [*x, *y, *z, *w] = normalized.to_array();
                 }
@@ -15,9 +15,15 @@
  *
 */
 
+use crate::localization::tr;
+use bevy::prelude::Vec3;
 use bevy_egui::egui::{DragValue, Grid, Ui};
 use rmf_site_format::Scale;
 
+/// Units that meshes are commonly made in, with the scale that converts them
+/// into meters
+pub const MESH_UNITS: [(&str, f32); 4] = [("m", 1.0), ("cm", 0.01), ("mm", 0.001), ("in", 0.0254)];
+
 pub struct InspectScale<'a> {
     pub scale: &'a Scale,
 }
@@ -29,7 +35,7 @@ impl<'a> InspectScale<'a> {
 
     pub fn show(self, ui: &mut Ui) -> Option<Scale> {
         let mut new_scale = self.scale.clone();
-        ui.label("Scale");
+        ui.label(tr(ui, "Scale"));
         Grid::new("inspect_scale").show(ui, |ui| {
             ui.label("x");
             ui.label("y");
@@ -53,6 +59,17 @@ impl<'a> InspectScale<'a> {
             );
             ui.end_row();
         });
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Mesh units")).on_hover_text(tr(
+                ui,
+                "Set a uniform scale that converts a mesh made in these units into meters",
+            ));
+            for (units, factor) in MESH_UNITS {
+                if ui.button(units).clicked() {
+                    new_scale = Scale(Vec3::splat(factor));
+                }
+            }
+        });
         ui.add_space(5.0);
 
         if new_scale != *self.scale {
@@ -0,0 +1,231 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    inspector::{InspectAngle, InspectValue},
+    localization::tr,
+    site::{AddSensor, Change},
+    widgets::{inspector::InspectorWorkcellParams, AppEvents},
+    AppState,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{Grid, RichText, Ui};
+use rmf_site_format::{
+    CameraSensor, DepthCameraSensor, LidarSensor, ModelMarker, Pending, SensorProperties,
+};
+
+#[derive(SystemParam)]
+pub struct InspectSensorParams<'w, 's> {
+    pub sensors: Query<'w, 's, &'static SensorProperties>,
+    pub models: Query<'w, 's, (), (With<ModelMarker>, Without<Pending>)>,
+}
+
+pub struct InspectSensorWidget<'a, 'w1, 'w2, 's1, 's2> {
+    pub entity: Entity,
+    pub params: &'a InspectorWorkcellParams<'w1, 's1>,
+    pub events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 'w2, 's1, 's2> InspectSensorWidget<'a, 'w1, 'w2, 's1, 's2> {
+    pub fn new(
+        entity: Entity,
+        params: &'a InspectorWorkcellParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self {
+            entity,
+            params,
+            events,
+        }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        if let Ok(properties) = self.params.sensors.sensors.get(self.entity) {
+            if let Some(new_properties) = InspectSensorProperties::new(properties).show(ui) {
+                self.events
+                    .site_change
+                    .sensor_properties
+                    .send(Change::new(new_properties, self.entity));
+            }
+            ui.add_space(10.0);
+            return;
+        }
+
+        // Sensors are attached to frames in workcells and to models in sites
+        let attachable = if *self.events.app_state.current() == AppState::WorkcellEditor {
+            matches!(
+                self.params.joints.frames.get(self.entity),
+                Ok((anchor, _)) if anchor.is_3D()
+            )
+        } else {
+            self.params.sensors.models.contains(self.entity)
+        };
+        if !attachable {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Add Sensor"));
+            for properties in [
+                SensorProperties::Camera(CameraSensor::default()),
+                SensorProperties::DepthCamera(DepthCameraSensor::default()),
+                SensorProperties::Lidar(LidarSensor::default()),
+            ] {
+                if ui.button(properties.label()).clicked() {
+                    self.events.site_change.add_sensor.send(AddSensor {
+                        parent: self.entity,
+                        properties,
+                    });
+                }
+            }
+        });
+        ui.add_space(10.0);
+    }
+}
+
+pub struct InspectSensorProperties<'a> {
+    pub properties: &'a SensorProperties,
+}
+
+impl<'a> InspectSensorProperties<'a> {
+    pub fn new(properties: &'a SensorProperties) -> Self {
+        Self { properties }
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Option<SensorProperties> {
+        let mut new_properties = self.properties.clone();
+        ui.label(RichText::new(format!("{} Properties", self.properties.label())).size(18.0));
+        match &mut new_properties {
+            SensorProperties::Camera(camera) => show_camera(camera, ui),
+            SensorProperties::DepthCamera(depth) => {
+                show_camera(&mut depth.camera, ui);
+                Grid::new("depth_camera_sensor").show(ui, |ui| {
+                    show_range(&mut depth.near, &mut depth.far, ui);
+                });
+            }
+            SensorProperties::Lidar(lidar) => show_lidar(lidar, ui),
+        }
+
+        if new_properties != *self.properties {
+            Some(new_properties)
+        } else {
+            None
+        }
+    }
+}
+
+fn show_camera(camera: &mut CameraSensor, ui: &mut Ui) {
+    Grid::new("camera_sensor").show(ui, |ui| {
+        if let Some(new_width) = InspectValue::<u32>::new(String::from("Width"), camera.width)
+            .clamp_range(1..=std::u32::MAX)
+            .tooltip("Image width in pixels".to_string())
+            .show(ui)
+        {
+            camera.width = new_width;
+        }
+        ui.end_row();
+        if let Some(new_height) = InspectValue::<u32>::new(String::from("Height"), camera.height)
+            .clamp_range(1..=std::u32::MAX)
+            .tooltip("Image height in pixels".to_string())
+            .show(ui)
+        {
+            camera.height = new_height;
+        }
+        ui.end_row();
+        if let Some(new_rate) =
+            InspectValue::<f32>::new(String::from("Update rate"), camera.update_rate)
+                .clamp_range(0.0..=std::f32::MAX)
+                .tooltip("Images per second".to_string())
+                .show(ui)
+        {
+            camera.update_rate = new_rate;
+        }
+        ui.end_row();
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr(ui, "Horizontal fov"));
+        InspectAngle::new(&mut camera.horizontal_fov)
+            .range_degrees(0.0..=180.0)
+            .show(ui);
+    });
+}
+
+fn show_lidar(lidar: &mut LidarSensor, ui: &mut Ui) {
+    Grid::new("lidar_sensor").show(ui, |ui| {
+        if let Some(new_samples) =
+            InspectValue::<u32>::new(String::from("Horizontal samples"), lidar.horizontal_samples)
+                .clamp_range(1..=std::u32::MAX)
+                .tooltip("Number of rays in each horizontal sweep".to_string())
+                .show(ui)
+        {
+            lidar.horizontal_samples = new_samples;
+        }
+        ui.end_row();
+        if let Some(new_samples) =
+            InspectValue::<u32>::new(String::from("Vertical samples"), lidar.vertical_samples)
+                .clamp_range(1..=std::u32::MAX)
+                .tooltip("Number of horizontal sweeps, 1 for a planar lidar".to_string())
+                .show(ui)
+        {
+            lidar.vertical_samples = new_samples;
+        }
+        ui.end_row();
+        show_range(&mut lidar.min_range, &mut lidar.max_range, ui);
+        if let Some(new_rate) =
+            InspectValue::<f32>::new(String::from("Update rate"), lidar.update_rate)
+                .clamp_range(0.0..=std::f32::MAX)
+                .tooltip("Scans per second".to_string())
+                .show(ui)
+        {
+            lidar.update_rate = new_rate;
+        }
+        ui.end_row();
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr(ui, "Horizontal fov"));
+        InspectAngle::new(&mut lidar.horizontal_fov)
+            .range_degrees(0.0..=360.0)
+            .show(ui);
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr(ui, "Vertical fov"));
+        InspectAngle::new(&mut lidar.vertical_fov)
+            .range_degrees(0.0..=180.0)
+            .show(ui);
+    });
+}
+
+/// Show the closest and farthest distance that a sensor can measure as rows
+/// of a grid
+fn show_range(min: &mut f32, max: &mut f32, ui: &mut Ui) {
+    if let Some(new_min) = InspectValue::<f32>::new(String::from("Min range"), *min)
+        .clamp_range(0.0..=*max)
+        .tooltip("Closest distance that can be measured, in meters".to_string())
+        .show(ui)
+    {
+        *min = new_min;
+    }
+    ui.end_row();
+    if let Some(new_max) = InspectValue::<f32>::new(String::from("Max range"), *max)
+        .clamp_range(*min..=std::f32::MAX)
+        .tooltip("Farthest distance that can be measured, in meters".to_string())
+        .show(ui)
+    {
+        *max = new_max;
+    }
+    ui.end_row();
+}
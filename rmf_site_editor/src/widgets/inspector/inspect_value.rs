@@ -15,6 +15,7 @@
  *
 */
 
+use crate::widgets::inspector::{drag_or_expression, Quantity};
 use bevy_egui::egui::emath::Numeric;
 use bevy_egui::egui::{DragValue, Ui};
 use std::ops::RangeInclusive;
@@ -82,14 +83,23 @@ impl<T: Numeric> InspectValue<T> {
         ui.horizontal(|ui| {
             let mut new_value = self.current_value;
             ui.label(self.title);
-            let response = ui.add(
-                DragValue::new(&mut new_value)
-                    .clamp_range(self.range)
-                    .min_decimals(self.min_decimals)
-                    .max_decimals_opt(self.max_decimals)
-                    .speed(self.speed)
-                    .suffix(self.suffix),
+            let current = self.current_value.to_f64();
+            let drag = DragValue::new(&mut new_value)
+                .clamp_range(self.range.clone())
+                .min_decimals(self.min_decimals)
+                .max_decimals_opt(self.max_decimals)
+                .speed(self.speed)
+                .suffix(self.suffix);
+            let (response, typed) = drag_or_expression(
+                ui,
+                Quantity::Plain,
+                || format!("{current}"),
+                |ui| ui.add(drag),
             );
+            if let Some(typed) = typed {
+                let (min, max) = (self.range.start().to_f64(), self.range.end().to_f64());
+                new_value = T::from_f64(typed.clamp(min, max));
+            }
 
             if let Some(tooltip) = self.tooltip {
                 response.on_hover_text(tooltip);
@@ -33,15 +33,36 @@ pub use inspect_door::*;
 pub mod inspect_edge;
 pub use inspect_edge::*;
 
+pub mod inspect_inertial;
+pub use inspect_inertial::*;
+
 pub mod inspect_is_static;
 pub use inspect_is_static::*;
 
+pub mod inspect_joint;
+pub use inspect_joint::*;
+
+pub mod inspect_mirror;
+pub use inspect_mirror::*;
+
 pub mod inspect_option_string;
 pub use inspect_option_string::*;
 
+pub mod inspect_lane_curve;
+pub use inspect_lane_curve::*;
+
+pub mod inspect_lane_defaults;
+pub use inspect_lane_defaults::*;
+
 pub mod inspect_layer;
 pub use inspect_layer::*;
 
+pub mod expression;
+pub use expression::*;
+
+pub mod inspect_length;
+pub use inspect_length::*;
+
 pub mod inspect_lift;
 pub use inspect_lift::*;
 
@@ -51,12 +72,18 @@ pub use inspect_light::*;
 pub mod inspect_location;
 pub use inspect_location::*;
 
+pub mod inspect_material_override;
+pub use inspect_material_override::*;
+
 pub mod inspect_mesh_constraint;
 pub use inspect_mesh_constraint::*;
 
 pub mod inspect_mesh_primitive;
 pub use inspect_mesh_primitive::*;
 
+pub mod inspect_model_tags;
+pub use inspect_model_tags::*;
+
 pub mod inspect_motion;
 pub use inspect_motion::*;
 
@@ -75,22 +102,34 @@ pub use inspect_pose::*;
 pub mod inspect_scale;
 pub use inspect_scale::*;
 
+pub mod inspect_sensor;
+pub use inspect_sensor::*;
+
 pub mod inspect_side;
 pub use inspect_side::*;
 
 pub mod inspect_value;
 pub use inspect_value::*;
 
+pub mod multi_edit;
+pub use multi_edit::*;
+
 pub mod selection_widget;
 pub use selection_widget::*;
 
 use crate::{
-    interaction::{Selection, SpawnPreview},
-    site::{Category, Change, EdgeLabels, FloorVisibility, Original, SiteID},
+    interaction::{ChangeMode, InteractionMode, MultiSelection, Selection, SpawnPreview},
+    localization::tr,
+    occupancy::{CheckReachability, SnapLaneToCenterline},
+    site::{
+        Category, Change, CollisionDisplay, EdgeLabels, FloorVisibility, ModelAnimation,
+        ModelDetail, Original, SiteID,
+    },
     widgets::AppEvents,
+    workcell::CopyGeometry,
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
-use bevy_egui::egui::{RichText, Ui};
+use bevy_egui::egui::{ComboBox, RichText, Ui};
 use rmf_site_format::*;
 
 // Bevy seems to have a limit of 16 fields in a SystemParam struct, so we split
@@ -98,7 +137,7 @@ use rmf_site_format::*;
 #[derive(SystemParam)]
 pub struct InspectorParams<'w, 's> {
     pub selection: Res<'w, Selection>,
-    pub heading: Query<'w, 's, (Option<&'static Category>, Option<&'static SiteID>)>,
+    pub heading: InspectorHeadingParams<'w, 's>,
     pub anchor_params: InspectAnchorParams<'w, 's>,
     pub anchor_dependents_params: InspectAnchorDependentsParams<'w, 's>,
     pub constraint_dependents_params: InspectModelDependentsParams<'w, 's>,
@@ -107,7 +146,41 @@ pub struct InspectorParams<'w, 's> {
     pub mesh_primitives: Query<'w, 's, (&'static MeshPrimitive, &'static RecallMeshPrimitive)>,
     pub names_in_workcell: Query<'w, 's, &'static NameInWorkcell>,
     pub scales: Query<'w, 's, &'static Scale>,
+    pub lane_costs: Query<'w, 's, &'static LaneCost>,
+    pub lane_curves: Query<'w, 's, &'static LaneCurve>,
+    pub shared_zones: Query<'w, 's, &'static SharedZone>,
+    pub location_parameters: Query<'w, 's, (&'static LocationTags, &'static LocationParameters)>,
     pub layer: InspectorLayerParams<'w, 's>,
+    pub models: InspectorModelParams<'w, 's>,
+    pub workcell: InspectorWorkcellParams<'w, 's>,
+}
+
+#[derive(SystemParam)]
+pub struct InspectorHeadingParams<'w, 's> {
+    pub categories: Query<'w, 's, (Option<&'static Category>, Option<&'static SiteID>)>,
+    pub multi_selection: Res<'w, MultiSelection>,
+}
+
+#[derive(SystemParam)]
+pub struct InspectorWorkcellParams<'w, 's> {
+    pub joints: InspectJointParams<'w, 's>,
+    pub inertials: InspectInertialParams<'w, 's>,
+    pub geometry: Query<
+        'w,
+        's,
+        Option<&'static WorkcellVisualMarker>,
+        Or<(With<WorkcellVisualMarker>, With<WorkcellCollisionMarker>)>,
+    >,
+    pub sensors: InspectSensorParams<'w, 's>,
+}
+
+#[derive(SystemParam)]
+pub struct InspectorModelParams<'w, 's> {
+    pub collision_displays: Query<'w, 's, Option<&'static CollisionDisplay>, With<ModelMarker>>,
+    pub animations: Query<'w, 's, &'static ModelAnimation>,
+    pub tags: Query<'w, 's, &'static ModelTags>,
+    pub material_overrides: Query<'w, 's, &'static MaterialOverride>,
+    pub lod: Query<'w, 's, (&'static LodPolicy, Option<&'static ModelDetail>)>,
 }
 
 // NOTE: We may need to split this struct into multiple structs if we ever need
@@ -130,7 +203,15 @@ pub struct InspectorComponentParams<'w, 's> {
     pub reverse_motions: Query<'w, 's, (&'static ReverseLane, &'static RecallReverseLane)>,
     pub names: Query<'w, 's, &'static NameInSite>,
     pub labels: Query<'w, 's, (&'static Label, &'static RecallLabel)>,
-    pub doors: Query<'w, 's, (&'static DoorType, &'static RecallDoorType)>,
+    pub doors: Query<
+        'w,
+        's,
+        (
+            &'static DoorType,
+            &'static RecallDoorType,
+            Option<&'static DoorOccupancy>,
+        ),
+    >,
     pub lifts: InspectLiftParams<'w, 's>,
     pub poses: Query<'w, 's, &'static Pose>,
     pub asset_sources:
@@ -158,26 +239,69 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectorWidget<'a, 'w1, 'w2, 's1, 's2> {
         Self { params, events }
     }
 
-    fn heading(&self, selection: Entity, ui: &mut Ui) {
-        let (label, site_id) = if let Ok((category, site_id)) = self.params.heading.get(selection) {
-            (
-                category.map(|x| x.label()).unwrap_or("<Unknown Type>"),
-                site_id,
-            )
-        } else {
-            ("<Unknown Type>", None)
-        };
+    fn heading(&self, selection: Entity, others: &[Entity], ui: &mut Ui) {
+        let (label, site_id) =
+            if let Ok((category, site_id)) = self.params.heading.categories.get(selection) {
+                (
+                    category.map(|x| x.label()).unwrap_or("<Unknown Type>"),
+                    site_id,
+                )
+            } else {
+                ("<Unknown Type>", None)
+            };
 
         if let Some(site_id) = site_id {
             ui.heading(format!("{} #{}", label, site_id.0));
         } else {
             ui.heading(format!("{} (unsaved)", label));
         }
+
+        let multi_selection = &self.params.heading.multi_selection;
+        if !others.is_empty() {
+            ui.label(format!(
+                "Edits apply to all {} selected {} elements",
+                others.len() + 1,
+                label.to_lowercase(),
+            ));
+        }
+        let unlike = multi_selection
+            .iter()
+            .filter(|e| **e != selection && !others.contains(e))
+            .count();
+        if unlike > 0 {
+            ui.label(format!(
+                "{unlike} other selected elements are a different kind and will not be edited"
+            ));
+        }
+    }
+
+    /// The other selected elements that are the same kind as the inspected
+    /// element
+    fn others_like(&self, selection: Entity) -> Vec<Entity> {
+        let category_of = |e: Entity| {
+            self.params
+                .heading
+                .categories
+                .get(e)
+                .ok()
+                .and_then(|(category, _)| category.copied())
+        };
+        let Some(category) = category_of(selection) else {
+            return Vec::new();
+        };
+        self.params
+            .heading
+            .multi_selection
+            .iter()
+            .copied()
+            .filter(|e| *e != selection && category_of(*e) == Some(category))
+            .collect()
     }
 
     pub fn show(mut self, ui: &mut Ui) {
         if let Some(selection) = self.params.selection.0 {
-            self.heading(selection, ui);
+            let others = self.others_like(selection);
+            self.heading(selection, &others, ui);
             if self.params.anchor_params.anchors.contains(selection) {
                 ui.horizontal(|ui| {
                     InspectAnchorWidget::new(selection, &self.params.anchor_params, self.events)
@@ -261,20 +385,67 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectorWidget<'a, 'w1, 'w2, 's1, 's2> {
                 )
                 .show(ui)
                 {
+                    let changes = batch_changes(selection, &others, tags, new_tags, |e| {
+                        self.params
+                            .component
+                            .location_tags
+                            .get(e)
+                            .ok()
+                            .map(|(t, _)| t.clone())
+                    });
+                    self.events.change.location_tags.send_batch(changes);
+                }
+            }
+
+            if let Ok((tags, parameters)) = self.params.location_parameters.get(selection) {
+                if let Some(new_parameters) =
+                    InspectLocationParameters::new(tags, parameters).show(ui)
+                {
+                    let changes =
+                        batch_changes(selection, &others, parameters, new_parameters, |e| {
+                            self.params
+                                .location_parameters
+                                .get(e)
+                                .ok()
+                                .map(|(_, p)| p.clone())
+                        });
                     self.events
-                        .change
-                        .location_tags
-                        .send(Change::new(new_tags, selection));
+                        .site_change
+                        .location_parameters
+                        .send_batch(changes);
+                }
+                if ui
+                    .button(tr(ui, "Check Reachability"))
+                    .on_hover_text(tr(
+                        ui,
+                        "Color the free space of the occupancy grid that a robot \
+                        can reach from this location",
+                    ))
+                    .clicked()
+                {
+                    self.events
+                        .site_request
+                        .check_reachability
+                        .send(CheckReachability::Start {
+                            location: selection,
+                            robot_radius: self.events.display.occupancy.robot_radius,
+                        });
                 }
+                ui.add_space(10.0);
             }
 
             if let Ok((motion, recall)) = self.params.component.motions.get(selection) {
-                ui.label(RichText::new("Forward Motion").size(18.0));
+                ui.label(RichText::new(tr(ui, "Forward Motion")).size(18.0));
                 if let Some(new_motion) = InspectMotionWidget::new(motion, recall).show(ui) {
-                    self.events
-                        .change
-                        .lane_motion
-                        .send(Change::new(new_motion, selection));
+                    let changes = batch_changes(selection, &others, motion, new_motion, |e| {
+                        self.params
+                            .component
+                            .motions
+                            .get(e)
+                            .ok()
+                            .map(|(m, _)| m.clone())
+                    });
+                    self.events.change.lane_motion.send_batch(changes);
                 }
                 ui.add_space(10.0);
             }
@@ -283,32 +454,122 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectorWidget<'a, 'w1, 'w2, 's1, 's2> {
                 ui.separator();
                 ui.push_id("Reverse Motion", |ui| {
                     if let Some(new_reverse) = InspectReverseWidget::new(reverse, recall).show(ui) {
-                        self.events
-                            .change
-                            .lane_reverse
-                            .send(Change::new(new_reverse, selection));
+                        let changes =
+                            batch_changes(selection, &others, reverse, new_reverse, |e| {
+                                self.params
+                                    .component
+                                    .reverse_motions
+                                    .get(e)
+                                    .ok()
+                                    .map(|(r, _)| r.clone())
+                            });
+                        self.events.change.lane_reverse.send_batch(changes);
                     }
                 });
                 ui.add_space(10.0);
             }
 
+            if let Ok(cost) = self.params.lane_costs.get(selection) {
+                if let Some(new_cost) =
+                    InspectValue::<f32>::new(String::from("Cost Multiplier"), cost.0)
+                        .clamp_range(0.0..=std::f32::INFINITY)
+                        .min_decimals(2)
+                        .max_decimals(2)
+                        .speed(0.01)
+                        .tooltip(
+                            "Planners will prefer to avoid lanes with a cost multiplier \
+                            greater than 1"
+                                .to_string(),
+                        )
+                        .show(ui)
+                {
+                    let changes =
+                        batch_changes(selection, &others, cost, LaneCost(new_cost), |e| {
+                            self.params.lane_costs.get(e).ok().cloned()
+                        });
+                    self.events.site_change.lane_cost.send_batch(changes);
+                }
+                ui.add_space(10.0);
+            }
+
+            if let Ok(zone) = self.params.shared_zones.get(selection) {
+                if let Some(new_zone) =
+                    InspectOptionString::new("Shared Zone", &zone.0, &None).show(ui)
+                {
+                    let changes =
+                        batch_changes(selection, &others, zone, SharedZone(new_zone), |e| {
+                            self.params.shared_zones.get(e).ok().cloned()
+                        });
+                    self.events.site_change.shared_zone.send_batch(changes);
+                }
+                ui.add_space(10.0);
+            }
+
+            if let Ok(curve) = self.params.lane_curves.get(selection) {
+                if let Some(new_curve) =
+                    InspectLaneCurve::new(curve, &self.params.anchor_params.icons).show(ui)
+                {
+                    self.events
+                        .site_change
+                        .lane_curve
+                        .send(Change::new(new_curve, selection));
+                }
+                ui.add_space(10.0);
+                if curve.is_straight() {
+                    if ui
+                        .button(tr(ui, "Split Lane"))
+                        .on_hover_text(tr(
+                            ui,
+                            "Click a point along the lane to split it into two lanes",
+                        ))
+                        .clicked()
+                    {
+                        self.events
+                            .request
+                            .change_mode
+                            .send(ChangeMode::To(InteractionMode::SplitLane(selection)));
+                    }
+                    ui.add_space(10.0);
+                }
+                if ui
+                    .button(tr(ui, "Snap to Centerline"))
+                    .on_hover_text(tr(
+                        ui,
+                        "Move the anchors of this lane to the middle of the free space \
+                        in the occupancy grid",
+                    ))
+                    .clicked()
+                {
+                    self.events
+                        .site_request
+                        .snap_to_centerline
+                        .send(SnapLaneToCenterline { lane: selection });
+                }
+                ui.add_space(10.0);
+            }
+
             if let Ok((label, recall)) = self.params.component.labels.get(selection) {
                 if let Some(new_label) =
                     InspectOptionString::new("Label", &label.0, &recall.value).show(ui)
                 {
-                    self.events
-                        .change
-                        .label
-                        .send(Change::new(Label(new_label), selection));
+                    let changes = batch_changes(selection, &others, label, Label(new_label), |e| {
+                        self.params
+                            .component
+                            .labels
+                            .get(e)
+                            .ok()
+                            .map(|(l, _)| l.clone())
+                    });
+                    self.events.change.label.send_batch(changes);
                 }
             }
 
             if let Ok(pose) = self.params.component.poses.get(selection) {
                 if let Some(new_pose) = InspectPose::new(pose).show(ui) {
-                    self.events
-                        .change
-                        .pose
-                        .send(Change::new(new_pose, selection));
+                    let changes = batch_changes(selection, &others, pose, new_pose, |e| {
+                        self.params.component.poses.get(e).ok().cloned()
+                    });
+                    self.events.change.pose.send_batch(changes);
                 }
                 ui.add_space(10.0);
             }
@@ -325,30 +586,193 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectorWidget<'a, 'w1, 'w2, 's1, 's2> {
 
             if let Ok((light, recall)) = self.params.component.lights.get(selection) {
                 if let Some(new_light) = InspectLightKind::new(light, recall).show(ui) {
-                    self.events
-                        .change
-                        .light
-                        .send(Change::new(new_light, selection));
+                    let changes = batch_changes(selection, &others, light, new_light, |e| {
+                        self.params
+                            .component
+                            .lights
+                            .get(e)
+                            .ok()
+                            .map(|(l, _)| l.clone())
+                    });
+                    self.events.change.light.send_batch(changes);
                 }
                 ui.add_space(10.0);
             }
 
-            if let Ok((door, recall)) = self.params.component.doors.get(selection) {
+            if let Ok((door, recall, occupancy)) = self.params.component.doors.get(selection) {
                 if let Some(new_door) = InspectDoorType::new(door, recall).show(ui) {
+                    let changes = batch_changes(selection, &others, door, new_door, |e| {
+                        self.params
+                            .component
+                            .doors
+                            .get(e)
+                            .ok()
+                            .map(|(d, _, _)| d.clone())
+                    });
+                    self.events.change.door.send_batch(changes);
+                }
+
+                let occupancy = occupancy.copied().unwrap_or_default();
+                let mut new_occupancy = occupancy;
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Occupancy"));
+                    ComboBox::from_id_source("door_occupancy")
+                        .selected_text(new_occupancy.label())
+                        .show_ui(ui, |ui| {
+                            for option in DoorOccupancy::ALL {
+                                ui.selectable_value(&mut new_occupancy, option, option.label());
+                            }
+                        });
+                })
+                .response
+                .on_hover_text(tr(ui, "Whether the door blocks the occupancy grid"));
+                if new_occupancy != occupancy {
                     self.events
-                        .change
-                        .door
-                        .send(Change::new(new_door, selection));
+                        .site_change
+                        .door_occupancy
+                        .send(Change::new(new_occupancy, selection));
                 }
                 ui.add_space(10.0);
             }
 
             if let Ok((source, recall)) = self.params.component.asset_sources.get(selection) {
                 if let Some(new_asset_source) = InspectAssetSource::new(source, recall).show(ui) {
+                    let changes =
+                        batch_changes(selection, &others, source, new_asset_source, |e| {
+                            self.params
+                                .component
+                                .asset_sources
+                                .get(e)
+                                .ok()
+                                .map(|(s, _)| s.clone())
+                        });
+                    self.events.change.asset_source.send_batch(changes);
+                }
+                ui.add_space(10.0);
+            }
+
+            if let Ok(display) = self.params.models.collision_displays.get(selection) {
+                let mut new_display = display.copied();
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Show"));
+                    ComboBox::from_id_source("model_collision_display")
+                        .selected_text(new_display.map(|d| d.label()).unwrap_or("Default"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut new_display, None, tr(ui, "Default"));
+                            for option in CollisionDisplay::ALL {
+                                ui.selectable_value(&mut new_display, Some(option), option.label());
+                            }
+                        });
+                })
+                .response
+                .on_hover_text(tr(
+                    ui,
+                    "Which geometry to render for models that describe collisions",
+                ));
+                if new_display != display.copied() {
+                    match new_display {
+                        Some(new_display) => {
+                            self.events.commands.entity(selection).insert(new_display);
+                        }
+                        None => {
+                            self.events
+                                .commands
+                                .entity(selection)
+                                .remove::<CollisionDisplay>();
+                        }
+                    }
+                }
+                ui.add_space(10.0);
+            }
+
+            if let Ok(tags) = self.params.models.tags.get(selection) {
+                let new_tag = &mut self.events.display.model_tags.new_tag;
+                if let Some(new_tags) = InspectModelTags::new(tags, new_tag).show(ui) {
+                    let changes = batch_changes(selection, &others, tags, new_tags, |e| {
+                        self.params.models.tags.get(e).ok().cloned()
+                    });
+                    self.events.site_change.model_tags.send_batch(changes);
+                }
+                ui.add_space(10.0);
+            }
+
+            if let Ok(material) = self.params.models.material_overrides.get(selection) {
+                if let Some(new_material) = InspectMaterialOverride::new(material).show(ui) {
+                    let changes = batch_changes(selection, &others, material, new_material, |e| {
+                        self.params.models.material_overrides.get(e).ok().cloned()
+                    });
                     self.events
-                        .change
-                        .asset_source
-                        .send(Change::new(new_asset_source, selection));
+                        .site_change
+                        .material_override
+                        .send_batch(changes);
+                }
+                ui.add_space(10.0);
+            }
+
+            if let Ok((policy, detail)) = self.params.models.lod.get(selection) {
+                let mut new_policy = *policy;
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Level of Detail"));
+                    ComboBox::from_id_source("model_lod_policy")
+                        .selected_text(new_policy.label())
+                        .show_ui(ui, |ui| {
+                            for option in LodPolicy::ALL {
+                                ui.selectable_value(&mut new_policy, option, option.label());
+                            }
+                        });
+                })
+                .response
+                .on_hover_text(tr(
+                    ui,
+                    "Whether simpler meshes are shown when the model is far away",
+                ));
+                if let Some(detail) = detail {
+                    let source = if detail.authored_levels > 0 {
+                        format!("{} levels from the asset", detail.authored_levels + 1)
+                    } else if detail.generated {
+                        "generated levels".to_owned()
+                    } else {
+                        "full detail".to_owned()
+                    };
+                    ui.label(format!("{} triangles, {source}", detail.triangles));
+                }
+                if new_policy != *policy {
+                    let changes = batch_changes(selection, &others, policy, new_policy, |e| {
+                        self.params.models.lod.get(e).ok().map(|(p, _)| *p)
+                    });
+                    self.events.site_change.lod_policy.send_batch(changes);
+                }
+                ui.add_space(10.0);
+            }
+
+            if let Ok(animation) = self.params.models.animations.get(selection) {
+                let mut new_animation = animation.clone();
+                ui.label(tr(ui, "Animation"));
+                ui.horizontal(|ui| {
+                    let selected = new_animation
+                        .clips
+                        .get(new_animation.clip)
+                        .map(|(name, _)| name.clone())
+                        .unwrap_or_default();
+                    ComboBox::from_id_source("model_animation_clip")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for (i, (name, _)) in animation.clips.iter().enumerate() {
+                                ui.selectable_value(&mut new_animation.clip, i, name);
+                            }
+                        });
+                    let play_text = if new_animation.playing {
+                        "Pause"
+                    } else {
+                        "Play"
+                    };
+                    if ui.button(play_text).clicked() {
+                        new_animation.playing = !new_animation.playing;
+                    }
+                    ui.checkbox(&mut new_animation.looping, tr(ui, "Loop"));
+                });
+                if new_animation != *animation {
+                    self.events.commands.entity(selection).insert(new_animation);
                 }
                 ui.add_space(10.0);
             }
@@ -364,6 +788,33 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectorWidget<'a, 'w1, 'w2, 's1, 's2> {
                 ui.add_space(10.0);
             }
 
+            if let Ok(visual) = self.params.workcell.geometry.get(selection) {
+                let (text, hover) = if visual.is_some() {
+                    (
+                        "Add Matching Collision",
+                        "Collide with the same geometry as this visual",
+                    )
+                } else {
+                    (
+                        "Add Matching Visual",
+                        "Show the same geometry as this collision",
+                    )
+                };
+                if ui.button(text).on_hover_text(hover).clicked() {
+                    self.events
+                        .workcell_change
+                        .copy_geometry
+                        .send(CopyGeometry { model: selection });
+                }
+                ui.add_space(10.0);
+            }
+
+            InspectSensorWidget::new(selection, &self.params.workcell, self.events).show(ui);
+            InspectMirrorWidget::new(selection, &self.params.workcell, self.events).show(ui);
+            InspectJointWidget::new(selection, &self.params.workcell.joints, self.events).show(ui);
+            InspectInertialWidget::new(selection, &self.params.workcell.inertials, self.events)
+                .show(ui);
+
             if self
                 .params
                 .component
@@ -404,10 +855,24 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectorWidget<'a, 'w1, 'w2, 's1, 's2> {
                 if let Some(new_camera_properties) =
                     InspectPhysicalCameraProperties::new(camera_properties).show(ui)
                 {
+                    let changes = batch_changes(
+                        selection,
+                        &others,
+                        camera_properties,
+                        new_camera_properties,
+                        |e| {
+                            self.params
+                                .component
+                                .physical_camera_properties
+                                .get(e)
+                                .ok()
+                                .cloned()
+                        },
+                    );
                     self.events
                         .change
                         .physical_camera_properties
-                        .send(Change::new(new_camera_properties, selection));
+                        .send_batch(changes);
                 }
                 ui.add_space(10.0);
             }
@@ -424,7 +889,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectorWidget<'a, 'w1, 'w2, 's1, 's2> {
             }
 
             if let Ok(_previewable) = self.params.component.previewable.get(selection) {
-                if ui.button("Preview").clicked() {
+                if ui.button(tr(ui, "Preview")).clicked() {
                     self.events
                         .request
                         .spawn_preview
@@ -433,7 +898,7 @@ impl<'a, 'w1, 'w2, 's1, 's2> InspectorWidget<'a, 'w1, 'w2, 's1, 's2> {
                 ui.add_space(10.0);
             }
         } else {
-            ui.label("Nothing selected");
+            ui.label(tr(ui, "Nothing selected"));
         }
     }
 }
@@ -0,0 +1,303 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{console::notify_warning, site::Change};
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{fmt::Debug, mem::discriminant};
+
+/// Turn an edit of the inspected element into a batch of changes for every
+/// element in the selection. Only the fields that the edit touched are
+/// changed in the other elements, so setting the speed limit of many lanes at
+/// once leaves the rest of their motion the way it was.
+///
+/// Elements that the edit cannot be applied to, such as elements whose value
+/// is a different variant of an enum than the inspected one, are left alone
+/// and the user is warned about them.
+pub fn batch_changes<T>(
+    inspected: Entity,
+    others: &[Entity],
+    old: &T,
+    new: T,
+    get: impl Fn(Entity) -> Option<T>,
+) -> Vec<Change<T>>
+where
+    T: Component + Clone + Debug + Serialize + DeserializeOwned,
+{
+    let mut changes = Vec::new();
+    let mut skipped = Vec::new();
+    if !others.is_empty() {
+        if let (Ok(old_value), Ok(new_value)) =
+            (serde_json::to_value(old), serde_json::to_value(&new))
+        {
+            for e in others {
+                let Some(Ok(mut value)) = get(*e).map(|current| serde_json::to_value(current))
+                else {
+                    continue;
+                };
+                if !patch_value(&old_value, &new_value, &mut value) {
+                    skipped.push(*e);
+                    continue;
+                }
+                match serde_json::from_value(value) {
+                    Ok(value) => changes.push(Change::new(value, *e)),
+                    Err(_) => skipped.push(*e),
+                }
+            }
+        }
+    }
+    if let Some(first) = skipped.first() {
+        notify_warning(
+            Some(*first),
+            format!(
+                "The edit was not applied to {} of the selected elements because \
+                their values are a different kind than the inspected one",
+                skipped.len(),
+            ),
+        );
+    }
+    changes.push(Change::new(new, inspected));
+    changes
+}
+
+/// Apply the difference between old and new onto target. Returns false if
+/// target holds a different variant of an enum than old and new, since the
+/// edit cannot be applied to it then.
+fn patch_value(old: &Value, new: &Value, target: &mut Value) -> bool {
+    if old == new {
+        return true;
+    }
+
+    match (old, new, target) {
+        (Value::Object(old), Value::Object(new), Value::Object(target)) => {
+            // Enum variants with fields are objects with a single key that
+            // names the variant
+            match (variant(old), variant(new), variant(target)) {
+                (Some(old_variant), Some(new_variant), _) if old_variant != new_variant => {
+                    // The edit switched to another variant
+                    *target = new.clone();
+                    return true;
+                }
+                (Some(old_variant), _, Some(target_variant)) if old_variant != target_variant => {
+                    return false;
+                }
+                _ => {}
+            }
+
+            let mut applied = true;
+            for (key, new_field) in new {
+                match (old.get(key), target.get_mut(key)) {
+                    (Some(old_field), Some(target_field)) => {
+                        applied &= patch_value(old_field, new_field, target_field);
+                    }
+                    (Some(old_field), None) if old_field == new_field => {}
+                    _ => {
+                        target.insert(key.clone(), new_field.clone());
+                    }
+                }
+            }
+            for key in old.keys() {
+                if !new.contains_key(key) {
+                    target.remove(key);
+                }
+            }
+            applied
+        }
+        (Value::Array(old), Value::Array(new), Value::Array(target)) => {
+            let is_vector = |values: &Vec<Value>| values.iter().all(Value::is_number);
+            if is_vector(old) && old.len() == new.len() && new.len() == target.len() {
+                // Coordinates and colors are changed one component at a time
+                let mut applied = true;
+                for ((old, new), target) in old.iter().zip(new).zip(target) {
+                    applied &= patch_value(old, new, target);
+                }
+                applied
+            } else {
+                // Anything else is treated like a set of tags, where the
+                // edit added and removed some entries
+                target.retain(|value| !old.contains(value) || new.contains(value));
+                for value in new {
+                    if !old.contains(value) && !target.contains(value) {
+                        target.push(value.clone());
+                    }
+                }
+                true
+            }
+        }
+        (old, new, target) => {
+            // An edit inside of an object or list cannot be applied to a
+            // target that holds a different kind of value, unless the target
+            // is unset
+            let same_kind = |a: &Value, b: &Value| discriminant(a) == discriminant(b);
+            if (old.is_object() || old.is_array())
+                && same_kind(old, new)
+                && !same_kind(old, target)
+                && !target.is_null()
+            {
+                return false;
+            }
+            *target = new.clone();
+            true
+        }
+    }
+}
+
+fn variant(object: &Map<String, Value>) -> Option<&String> {
+    let mut keys = object.keys();
+    match (keys.next(), keys.next()) {
+        (Some(key), None) => Some(key),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Component, Clone, Debug, Serialize, Deserialize, PartialEq)]
+    enum Limit {
+        Speed { max: f32, ramp: f32 },
+        Stop,
+    }
+
+    #[derive(Component, Clone, Debug, Serialize, Deserialize, PartialEq)]
+    struct Motion {
+        limit: Limit,
+        tags: Vec<String>,
+        color: [f32; 3],
+    }
+
+    fn motion(max: f32, ramp: f32, tags: &[&str]) -> Motion {
+        Motion {
+            limit: Limit::Speed { max, ramp },
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            color: [0.0, 0.5, 1.0],
+        }
+    }
+
+    fn patched(old: Value, new: Value, mut target: Value) -> Value {
+        assert!(patch_value(&old, &new, &mut target));
+        target
+    }
+
+    #[test]
+    fn patch_only_changes_edited_fields() {
+        assert_eq!(
+            patched(
+                json!({"max": 1.0, "ramp": 2.0}),
+                json!({"max": 3.0, "ramp": 2.0}),
+                json!({"max": 5.0, "ramp": 7.0}),
+            ),
+            json!({"max": 3.0, "ramp": 7.0}),
+        );
+        // Fields that the edit added or removed are added or removed
+        assert_eq!(
+            patched(
+                json!({"a": 1, "b": 2}),
+                json!({"a": 1, "c": 3}),
+                json!({"a": 4, "b": 5}),
+            ),
+            json!({"a": 4, "c": 3}),
+        );
+    }
+
+    #[test]
+    fn patch_vectors_by_component_and_lists_as_sets() {
+        assert_eq!(
+            patched(
+                json!([0.0, 0.5, 1.0]),
+                json!([0.0, 0.25, 1.0]),
+                json!([1.0, 1.0, 1.0])
+            ),
+            json!([1.0, 0.25, 1.0]),
+        );
+        assert_eq!(
+            patched(json!(["a", "b"]), json!(["b", "c"]), json!(["a", "d"])),
+            json!(["d", "c"]),
+        );
+        // Values of different kinds are replaced
+        assert_eq!(patched(json!(1), json!("one"), json!(2)), json!("one"));
+    }
+
+    #[test]
+    fn patch_rejects_a_different_variant() {
+        let old = json!({"Speed": {"max": 1.0}});
+        let new = json!({"Speed": {"max": 2.0}});
+        assert!(!patch_value(&old, &new, &mut json!({"Slow": {"max": 3.0}})));
+        assert!(!patch_value(&old, &new, &mut json!("Stop")));
+        // Switching variants replaces the variant of the target
+        assert_eq!(
+            patched(
+                old,
+                json!({"Slow": {"max": 2.0}}),
+                json!({"Speed": {"max": 5.0}})
+            ),
+            json!({"Slow": {"max": 2.0}}),
+        );
+        // Unset values are filled in
+        assert_eq!(
+            patched(json!({"max": 1.0}), json!({"max": 2.0}), Value::Null),
+            json!({"max": 2.0}),
+        );
+    }
+
+    #[test]
+    fn batch_copies_the_edit_to_every_selected_element() {
+        let mut world = World::new();
+        let inspected = world.spawn_empty().id();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        let values = [
+            (a, motion(4.0, 0.5, &["a"])),
+            (b, motion(6.0, 0.25, &["b", "fast"])),
+        ];
+        let old = motion(1.0, 1.0, &["fast"]);
+        let new = motion(2.0, 1.0, &[]);
+        let changes = batch_changes(inspected, &[a, b], &old, new.clone(), |e| {
+            values.iter().find(|(v, _)| *v == e).map(|(_, m)| m.clone())
+        });
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].for_element, a);
+        assert_eq!(changes[0].to_value, motion(2.0, 0.5, &["a"]));
+        assert_eq!(changes[1].for_element, b);
+        assert_eq!(changes[1].to_value, motion(2.0, 0.25, &["b"]));
+        assert_eq!(changes[2].for_element, inspected);
+        assert_eq!(changes[2].to_value, new);
+    }
+
+    #[test]
+    fn batch_skips_elements_with_a_different_variant() {
+        let mut world = World::new();
+        let inspected = world.spawn_empty().id();
+        let stopped = world.spawn_empty().id();
+        let mut stop = motion(0.0, 0.0, &[]);
+        stop.limit = Limit::Stop;
+        let changes = batch_changes(
+            inspected,
+            &[stopped],
+            &motion(1.0, 1.0, &[]),
+            motion(2.0, 1.0, &[]),
+            |_| Some(stop.clone()),
+        );
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].for_element, inspected);
+    }
+}
@@ -17,6 +17,7 @@
 
 use crate::{
     interaction::{Hover, Select},
+    localization::tr,
     site::SiteID,
     widgets::{AppEvents, Icons},
 };
@@ -63,6 +64,6 @@ impl<'a, 'w, 's> SelectionWidget<'a, 'w, 's> {
             self.events.request.hover.send(Hover(Some(self.entity)));
         }
 
-        response.on_hover_text("Select");
+        response.on_hover_text(tr(ui, "Select"));
     }
 }
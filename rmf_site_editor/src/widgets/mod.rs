@@ -16,21 +16,32 @@
 */
 
 use crate::{
+    action_button,
     interaction::{
         ChangeMode, HeadlightToggle, Hover, MoveTo, PickingBlockers, Select, SpawnPreview,
+        SurfaceSnapping, XRaySelection,
+    },
+    localization::tr,
+    occupancy::{
+        CalculateGrid, CheckReachability, CompareGrids, DisplayedGrid, GenerateNavGraph,
+        LiveOccupancy, PlacementWarnings, SnapLaneToCenterline,
     },
-    occupancy::CalculateGrid,
     recency::ChangeRank,
     site::{
-        AssociatedGraphs, Change, ConsiderAssociatedGraph, ConsiderLocationTag, CurrentLevel,
-        Delete, ExportLights, FloorVisibility, PhysicalLightToggle, SaveNavGraphs, SiteState,
-        ToggleLiftDoorAvailability,
+        AddSensor, AssociatedGraphs, Change, ChangeLevel, CollisionDisplay,
+        ConsiderAssociatedGraph, ConsiderLocationTag, CurrentLevel, Delete, ExportLights,
+        FloorVisibility, GenerateLiftLobbies, GlobalCollisionDisplay, HiddenModelTags,
+        IlluminancePreview, LodSettings, MergeCollinearLanes, PhysicalLightToggle, PreviewPath,
+        PreviewTraffic, RecentModels, ResolveGraphChange, SaveNavGraphs, ShadingMode, SiteState,
+        ToggleLiftDoorAvailability, ValidateSite,
     },
-    AppState, CreateNewWorkspace, CurrentWorkspace, LoadWorkspace, SaveWorkspace,
+    workcell::{ChangeParent, CopyGeometry, CreateJoint, MirrorElement},
+    AppState, CreateNewWorkspace, CurrentWorkspace, KeyAction, KeyCapture, Keymap, LoadWorkspace,
+    Preferences, RenderSettings, SaveWorkspace, UiTheme,
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_egui::{
-    egui::{self, Button, CollapsingHeader, Sense},
+    egui::{self, Sense, Ui},
     EguiContext,
 };
 use rmf_site_format::*;
@@ -38,21 +49,123 @@ use rmf_site_format::*;
 pub mod create;
 use create::CreateWidget;
 
+pub mod name_filter;
+pub use name_filter::*;
+
+pub mod context_menu;
+use context_menu::*;
+
+pub mod toolbar;
+use toolbar::*;
+
+pub mod toasts;
+use toasts::*;
+
+pub mod status_bar;
+use status_bar::*;
+
+pub mod workspace_tabs;
+use workspace_tabs::*;
+
+pub mod save_as;
+pub use save_as::*;
+
+pub mod view_console;
+pub use view_console::*;
+
+pub mod view_undo_history;
+pub use view_undo_history::*;
+
+pub mod view_diagnostics;
+use view_diagnostics::*;
+
 pub mod view_layers;
 use view_layers::*;
 
+pub mod view_outliner;
+pub use view_outliner::*;
+
 pub mod view_levels;
 use view_levels::{LevelDisplay, LevelParams, ViewLevels};
 
 pub mod view_lights;
 use view_lights::*;
 
+pub mod view_environment;
+use view_environment::*;
+
 pub mod view_nav_graphs;
 use view_nav_graphs::*;
 
+pub mod view_path_preview;
+use view_path_preview::*;
+
+pub mod view_measurements;
+pub use view_measurements::*;
+
+pub mod view_nav_graph_diff;
+use view_nav_graph_diff::*;
+
+pub mod view_traffic_preview;
+use view_traffic_preview::*;
+
 pub mod view_occupancy;
 use view_occupancy::*;
 
+pub mod view_fuel_models;
+use view_fuel_models::*;
+
+pub mod view_asset_library;
+use view_asset_library::*;
+
+pub mod view_model_search;
+use view_model_search::*;
+
+pub mod view_asset_cache;
+use view_asset_cache::*;
+
+pub mod view_missing_assets;
+use view_missing_assets::*;
+
+pub mod view_model_tags;
+use view_model_tags::*;
+
+pub mod view_asset_availability;
+use view_asset_availability::*;
+
+pub mod view_asset_paths;
+use view_asset_paths::*;
+
+pub mod view_level_of_detail;
+use view_level_of_detail::*;
+
+pub mod view_render_settings;
+use view_render_settings::*;
+
+pub mod view_keymap;
+use view_keymap::*;
+
+pub mod view_preferences;
+use view_preferences::*;
+
+pub mod view_frame_hierarchy;
+use view_frame_hierarchy::*;
+
+pub mod view_joint_sliders;
+use view_joint_sliders::*;
+
+pub mod view_frame_measurement;
+use view_frame_measurement::*;
+
+pub mod view_workcell_templates;
+use view_workcell_templates::*;
+
+pub mod view_duplicate_names;
+use view_duplicate_names::*;
+
+pub mod view_workcell_diff;
+use view_workcell_diff::*;
+
 pub mod icons;
 pub use icons::*;
 
@@ -62,6 +175,9 @@ use inspector::{InspectorParams, InspectorWidget};
 pub mod move_layer;
 pub use move_layer::*;
 
+pub mod panel_layout;
+pub use panel_layout::*;
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum UiUpdateLabel {
     DrawUi,
@@ -76,21 +192,82 @@ impl Plugin for StandardUiLayout {
             .init_resource::<LevelDisplay>()
             .init_resource::<NavGraphDisplay>()
             .init_resource::<LightDisplay>()
+            .init_resource::<OutlinerDisplay>()
+            .init_resource::<ConsoleDisplay>()
+            .init_resource::<EnvironmentDisplay>()
             .init_resource::<OccupancyDisplay>()
-            .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(init_ui_style))
+            .init_resource::<PathPreviewDisplay>()
+            .init_resource::<TrafficPreviewDisplay>()
+            .init_resource::<NavGraphDiffDisplay>()
+            .init_resource::<FuelModelsDisplay>()
+            .init_resource::<AssetLibraryDisplay>()
+            .init_resource::<ModelSearchDisplay>()
+            .init_resource::<AssetCacheDisplay>()
+            .init_resource::<MissingAssetsDisplay>()
+            .init_resource::<ModelTagsDisplay>()
+            .init_resource::<AssetAvailabilityDisplay>()
+            .init_resource::<FrameHierarchyDisplay>()
+            .init_resource::<FrameMeasurementDisplay>()
+            .init_resource::<WorkcellTemplateDisplay>()
+            .init_resource::<DuplicateNamesDisplay>()
+            .init_resource::<WorkcellDiffDisplay>()
+            .init_resource::<PreferencesDisplay>()
+            .init_resource::<ContextMenuDisplay>()
+            .init_resource::<WorkspaceTabsDisplay>()
+            .init_resource::<SaveAsDisplay>()
+            .insert_resource(PanelLayout::load().unwrap_or_default())
+            .add_system(save_panel_layout)
+            .add_system(resolve_preferences_asset_directory)
+            .add_system(add_preferred_asset_directories)
+            .add_system(apply_ui_theme)
+            .add_system(share_display_units)
+            .add_system(handle_tool_shortcuts)
+            .add_system(handle_undo_shortcuts)
+            .add_system(show_toasts.after(UiUpdateLabel::DrawUi))
             .add_system_set(
                 SystemSet::on_update(AppState::SiteEditor)
-                    .with_system(site_ui_layout.label(UiUpdateLabel::DrawUi)),
+                    .with_system(show_save_as_window.after(UiUpdateLabel::DrawUi))
+                    .with_system(show_status_bar.before(UiUpdateLabel::DrawUi))
+                    .with_system(show_context_menu.before(UiUpdateLabel::DrawUi))
+                    .with_system(site_ui_layout.label(UiUpdateLabel::DrawUi))
+                    .with_system(
+                        show_workspace_tabs
+                            .after(UiUpdateLabel::DrawUi)
+                            .before(show_toolbar),
+                    )
+                    .with_system(show_toolbar.after(UiUpdateLabel::DrawUi)),
             )
             .add_system_set(
                 SystemSet::on_update(AppState::WorkcellEditor)
-                    .with_system(workcell_ui_layout.label(UiUpdateLabel::DrawUi)),
+                    .with_system(show_status_bar.before(UiUpdateLabel::DrawUi))
+                    .with_system(show_context_menu.before(UiUpdateLabel::DrawUi))
+                    .with_system(workcell_ui_layout.label(UiUpdateLabel::DrawUi))
+                    .with_system(
+                        show_workspace_tabs
+                            .after(UiUpdateLabel::DrawUi)
+                            .before(show_toolbar),
+                    )
+                    .with_system(show_toolbar.after(UiUpdateLabel::DrawUi)),
             )
             .add_system_set_to_stage(
                 CoreStage::PostUpdate,
                 SystemSet::on_update(SiteState::Display)
                     .with_system(resolve_light_export_file)
-                    .with_system(resolve_nav_graph_import_export_files),
+                    .with_system(resolve_lightmap_folder)
+                    .with_system(resolve_environment_hdri_file)
+                    .with_system(resolve_nav_graph_import_export_files)
+                    .with_system(resolve_nav_graph_diff_file)
+                    .with_system(resolve_fuel_model_search)
+                    .with_system(load_fuel_model_thumbnails.after(resolve_fuel_model_search))
+                    .with_system(resolve_asset_library_directory)
+                    .with_system(
+                        load_asset_library_thumbnails.after(resolve_asset_library_directory),
+                    ),
+            )
+            .add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                SystemSet::on_update(AppState::WorkcellEditor)
+                    .with_system(resolve_workcell_diff_file),
             );
     }
 }
@@ -115,12 +292,40 @@ pub struct ChangeEvents<'w, 's> {
     pub location_tags: EventWriter<'w, 's, Change<LocationTags>>,
 }
 
+/// Change events for site elements. These are kept separate from ChangeEvents
+/// because a SystemParam struct is limited to 16 fields.
+#[derive(SystemParam)]
+pub struct SiteChangeEvents<'w, 's> {
+    pub lane_defaults: EventWriter<'w, 's, Change<LaneDefaults>>,
+    pub lane_cost: EventWriter<'w, 's, Change<LaneCost>>,
+    pub lane_curve: EventWriter<'w, 's, Change<LaneCurve>>,
+    pub shared_zone: EventWriter<'w, 's, Change<SharedZone>>,
+    pub location_parameters: EventWriter<'w, 's, Change<LocationParameters>>,
+    pub model_tags: EventWriter<'w, 's, Change<ModelTags>>,
+    pub material_override: EventWriter<'w, 's, Change<MaterialOverride>>,
+    pub lod_policy: EventWriter<'w, 's, Change<LodPolicy>>,
+    pub sensor_properties: EventWriter<'w, 's, Change<SensorProperties>>,
+    pub add_sensor: EventWriter<'w, 's, AddSensor>,
+    pub environment: EventWriter<'w, 's, Change<Environment>>,
+    pub light_group: EventWriter<'w, 's, Change<LightGroup>>,
+    pub nav_graph_palette: EventWriter<'w, 's, Change<NavGraphPalette>>,
+    pub manual_color: EventWriter<'w, 's, Change<ManualColor>>,
+    pub door_occupancy: EventWriter<'w, 's, Change<DoorOccupancy>>,
+    pub change_level: EventWriter<'w, 's, ChangeLevel>,
+}
+
 #[derive(SystemParam)]
 pub struct WorkcellChangeEvents<'w, 's> {
     pub mesh_constraints: EventWriter<'w, 's, Change<MeshConstraint<Entity>>>,
     pub mesh_primitives: EventWriter<'w, 's, Change<MeshPrimitive>>,
     pub name_in_workcell: EventWriter<'w, 's, Change<NameInWorkcell>>,
     pub scale: EventWriter<'w, 's, Change<Scale>>,
+    pub change_parent: EventWriter<'w, 's, ChangeParent>,
+    pub joint_properties: EventWriter<'w, 's, Change<JointProperties>>,
+    pub create_joint: EventWriter<'w, 's, CreateJoint>,
+    pub inertial: EventWriter<'w, 's, Change<Inertial>>,
+    pub copy_geometry: EventWriter<'w, 's, CopyGeometry>,
+    pub mirror: EventWriter<'w, 's, MirrorElement>,
 }
 
 #[derive(SystemParam)]
@@ -135,10 +340,79 @@ pub struct PanelResources<'w, 's> {
     pub level: ResMut<'w, LevelDisplay>,
     pub nav_graph: ResMut<'w, NavGraphDisplay>,
     pub light: ResMut<'w, LightDisplay>,
+    pub environment: ResMut<'w, EnvironmentDisplay>,
+    pub illuminance: ResMut<'w, IlluminancePreview>,
     pub occupancy: ResMut<'w, OccupancyDisplay>,
+    pub path_preview: ResMut<'w, PathPreviewDisplay>,
+    pub traffic_preview: ResMut<'w, TrafficPreviewDisplay>,
+    pub nav_graph_diff: ResMut<'w, NavGraphDiffDisplay>,
+    pub assets: AssetPanelResources<'w, 's>,
+    pub view: ViewMenuResources<'w, 's>,
+    pub recent_models: Res<'w, RecentModels>,
+    pub model_tags: ResMut<'w, ModelTagsDisplay>,
+    pub duplicate_names: ResMut<'w, DuplicateNamesDisplay>,
+    pub render_settings: ResMut<'w, RenderSettings>,
+    _ignore: Query<'w, 's, ()>,
+}
+
+/// Display options that are chosen from the View menu, along with the
+/// preferences that change how the editor is laid out and controlled
+#[derive(SystemParam)]
+pub struct ViewMenuResources<'w, 's> {
+    pub collision_display: ResMut<'w, GlobalCollisionDisplay>,
+    pub shading: ResMut<'w, ShadingMode>,
+    pub xray_selection: ResMut<'w, XRaySelection>,
+    pub panel_layout: ResMut<'w, PanelLayout>,
+    pub keymap: ResMut<'w, Keymap>,
+    pub key_capture: ResMut<'w, KeyCapture>,
+    pub preferences: ResMut<'w, Preferences>,
+    pub preferences_display: ResMut<'w, PreferencesDisplay>,
+    _ignore: Query<'w, 's, ()>,
+}
+
+/// The state of the panels that find, load, and keep track of assets
+#[derive(SystemParam)]
+pub struct AssetPanelResources<'w, 's> {
+    pub fuel_models: ResMut<'w, FuelModelsDisplay>,
+    pub asset_library: ResMut<'w, AssetLibraryDisplay>,
+    pub model_search: ResMut<'w, ModelSearchDisplay>,
+    pub asset_cache: ResMut<'w, AssetCacheDisplay>,
+    pub missing_assets: ResMut<'w, MissingAssetsDisplay>,
+    pub availability: ResMut<'w, AssetAvailabilityDisplay>,
     _ignore: Query<'w, 's, ()>,
 }
 
+#[derive(SystemParam)]
+pub struct AssetParams<'w, 's> {
+    pub missing: MissingAssetParams<'w, 's>,
+    pub availability: AssetAvailabilityParams<'w, 's>,
+    pub paths: AssetPathParams<'w, 's>,
+    pub cache: AssetCacheParams<'w, 's>,
+}
+
+/// Parameters of the panels that show and compare navigation graphs
+#[derive(SystemParam)]
+pub struct NavGraphPanelParams<'w, 's> {
+    pub graphs: NavGraphParams<'w, 's>,
+    pub path_preview: PathPreviewParams<'w, 's>,
+    pub traffic_preview: TrafficPreviewParams<'w, 's>,
+    pub graph_diff: NavGraphDiffParams<'w, 's>,
+}
+
+/// Parameters of the panels that light up the site
+#[derive(SystemParam)]
+pub struct LightingParams<'w, 's> {
+    pub lights: LightParams<'w, 's>,
+    pub environment: EnvironmentParams<'w, 's>,
+}
+
+/// Parameters of the panels that arrange and calibrate drawings
+#[derive(SystemParam)]
+pub struct DrawingPanelParams<'w, 's> {
+    pub layers: LayersParams<'w, 's>,
+    pub measurements: MeasurementsParams<'w, 's>,
+}
+
 #[derive(SystemParam)]
 pub struct Requests<'w, 's> {
     pub hover: ResMut<'w, Events<Hover>>,
@@ -159,6 +433,28 @@ pub struct Requests<'w, 's> {
     pub consider_graph: EventWriter<'w, 's, ConsiderAssociatedGraph>,
 }
 
+/// Requests that operate on the site as a whole. These are kept separate from
+/// Requests because a SystemParam struct is limited to 16 fields.
+#[derive(SystemParam)]
+pub struct SiteRequests<'w, 's> {
+    pub generate_lift_lobbies: EventWriter<'w, 's, GenerateLiftLobbies>,
+    pub generate_nav_graph: EventWriter<'w, 's, GenerateNavGraph>,
+    pub validate_site: EventWriter<'w, 's, ValidateSite>,
+    pub preview_path: EventWriter<'w, 's, PreviewPath>,
+    pub preview_traffic: EventWriter<'w, 's, PreviewTraffic>,
+    pub resolve_graph_change: EventWriter<'w, 's, ResolveGraphChange>,
+    pub merge_collinear_lanes: EventWriter<'w, 's, MergeCollinearLanes>,
+    pub snap_to_centerline: EventWriter<'w, 's, SnapLaneToCenterline>,
+    pub displayed_grid: ResMut<'w, DisplayedGrid>,
+    pub compare_grids: EventWriter<'w, 's, CompareGrids>,
+    pub live_occupancy: ResMut<'w, LiveOccupancy>,
+    pub check_reachability: EventWriter<'w, 's, CheckReachability>,
+    pub placement_warnings: ResMut<'w, PlacementWarnings>,
+    pub hidden_model_tags: ResMut<'w, HiddenModelTags>,
+    pub surface_snapping: ResMut<'w, SurfaceSnapping>,
+    pub lod_settings: ResMut<'w, LodSettings>,
+}
+
 #[derive(SystemParam)]
 pub struct LayerEvents<'w, 's> {
     pub floors: EventWriter<'w, 's, ChangeRank<FloorMarker>>,
@@ -176,9 +472,11 @@ pub struct LayerEvents<'w, 's> {
 pub struct AppEvents<'w, 's> {
     pub commands: Commands<'w, 's>,
     pub change: ChangeEvents<'w, 's>,
+    pub site_change: SiteChangeEvents<'w, 's>,
     pub workcell_change: WorkcellChangeEvents<'w, 's>,
     pub display: PanelResources<'w, 's>,
     pub request: Requests<'w, 's>,
+    pub site_request: SiteRequests<'w, 's>,
     pub file_events: FileEvents<'w, 's>,
     pub layers: LayerEvents<'w, 's>,
     pub app_state: Res<'w, State<AppState>>,
@@ -186,110 +484,311 @@ pub struct AppEvents<'w, 's> {
         Query<'w, 's, (Entity, &'static AssetSource, &'static Scale), With<Pending>>,
 }
 
+/// Panels of the site editor, in the order they are stacked within a dock
+const SITE_PANELS: &[DockablePanel] = &[
+    DockablePanel::new("Levels", true, PanelDock::Right),
+    DockablePanel::new("Outliner", false, PanelDock::Right),
+    DockablePanel::new("Navigation Graphs", true, PanelDock::Right),
+    DockablePanel::new("Path Preview", false, PanelDock::Right),
+    DockablePanel::new("Compare Graphs", false, PanelDock::Right),
+    DockablePanel::new("Traffic Preview", false, PanelDock::Right),
+    DockablePanel::new("Layers", false, PanelDock::Right),
+    DockablePanel::new("Measurements", false, PanelDock::Right),
+    DockablePanel::new("Inspect", true, PanelDock::Right),
+    DockablePanel::new("Create", false, PanelDock::Right),
+    DockablePanel::new("Lights", false, PanelDock::Right),
+    DockablePanel::new("Environment", false, PanelDock::Right),
+    DockablePanel::new("Occupancy", false, PanelDock::Right),
+    DockablePanel::new("Model Search", false, PanelDock::Right),
+    DockablePanel::new("Model Tags", false, PanelDock::Right),
+    DockablePanel::new("Fuel Models", false, PanelDock::Right),
+    DockablePanel::new("Asset Library", false, PanelDock::Right),
+    DockablePanel::new("Asset Cache", false, PanelDock::Right),
+    DockablePanel::new("Asset Availability", false, PanelDock::Right),
+    DockablePanel::new("Asset Paths", false, PanelDock::Right),
+    DockablePanel::new("Level of Detail", false, PanelDock::Right),
+    DockablePanel::new("Render Settings", false, PanelDock::Right),
+    DockablePanel::new("Keyboard Shortcuts", false, PanelDock::Right),
+    DockablePanel::new("Undo History", false, PanelDock::Right),
+    DockablePanel::new("Diagnostics", false, PanelDock::Right),
+    DockablePanel::new("Console", true, PanelDock::Bottom),
+];
+
+/// Panels of the workcell editor, in the order they are stacked within a dock
+const WORKCELL_PANELS: &[DockablePanel] = &[
+    DockablePanel::new("Frames", true, PanelDock::Right),
+    DockablePanel::new("Joints", false, PanelDock::Right),
+    DockablePanel::new("Measure", false, PanelDock::Right),
+    DockablePanel::new("Templates", false, PanelDock::Right),
+    DockablePanel::new("Compare", false, PanelDock::Right),
+    DockablePanel::new("Inspect", true, PanelDock::Right),
+    DockablePanel::new("Create", true, PanelDock::Right),
+    DockablePanel::new("Render Settings", false, PanelDock::Right),
+    DockablePanel::new("Keyboard Shortcuts", false, PanelDock::Right),
+    DockablePanel::new("Undo History", false, PanelDock::Right),
+    DockablePanel::new("Console", true, PanelDock::Bottom),
+];
+
 fn site_ui_layout(
     mut egui_context: ResMut<EguiContext>,
     mut picking_blocker: Option<ResMut<PickingBlockers>>,
     open_sites: Query<Entity, With<SiteProperties>>,
     inspector_params: InspectorParams,
     levels: LevelParams,
-    lights: LightParams,
-    nav_graphs: NavGraphParams,
-    layers: LayersParams,
+    lighting: LightingParams,
+    nav_graphs: NavGraphPanelParams,
+    mut outliner: OutlinerParams,
+    mut drawings: DrawingPanelParams,
+    diagnostics: DiagnosticParams,
+    mut console: ConsoleParams,
+    mut undo_history: UndoHistoryParams,
+    occupancy: OccupancyParams,
+    mut model_tags: ModelTagsParams,
+    assets: AssetParams,
     mut events: AppEvents,
 ) {
-    egui::SidePanel::right("right_panel")
-        .resizable(true)
-        .show(egui_context.ctx_mut(), |ui| {
-            egui::ScrollArea::both()
-                .auto_shrink([false, false])
-                .show(ui, |ui| {
-                    ui.vertical(|ui| {
-                        CollapsingHeader::new("Levels")
-                            .default_open(true)
-                            .show(ui, |ui| {
-                                ViewLevels::new(&levels, &mut events).show(ui);
-                            });
-                        ui.separator();
-                        CollapsingHeader::new("Navigation Graphs")
-                            .default_open(true)
-                            .show(ui, |ui| {
-                                ViewNavGraphs::new(&nav_graphs, &mut events).show(ui, &open_sites);
-                            });
-                        ui.separator();
-                        // TODO(MXG): Consider combining Nav Graphs and Layers
-                        CollapsingHeader::new("Layers")
-                            .default_open(false)
-                            .show(ui, |ui| {
-                                ViewLayers::new(&layers, &mut events).show(ui);
-                            });
-                        ui.separator();
-                        CollapsingHeader::new("Inspect")
-                            .default_open(true)
-                            .show(ui, |ui| {
-                                InspectorWidget::new(&inspector_params, &mut events).show(ui);
-                            });
-                        ui.separator();
-                        CollapsingHeader::new("Create")
-                            .default_open(false)
-                            .show(ui, |ui| {
-                                CreateWidget::new(&mut events).show(ui);
-                            });
-                        ui.separator();
-                        CollapsingHeader::new("Lights")
-                            .default_open(false)
-                            .show(ui, |ui| {
-                                ViewLights::new(&lights, &mut events).show(ui);
-                            });
-                        ui.separator();
-                        CollapsingHeader::new("Occupancy")
-                            .default_open(false)
-                            .show(ui, |ui| {
-                                ViewOccupancy::new(&mut events).show(ui);
-                            });
-                    });
-                });
-        });
+    let layout = events.display.view.panel_layout.clone();
+    show_docked_panels(
+        egui_context.ctx_mut(),
+        &layout,
+        SITE_PANELS,
+        |panel, ui| match panel.name {
+            "Levels" => {
+                ViewLevels::new(&levels, &mut events).show(ui);
+            }
+            "Outliner" => {
+                ViewOutliner::new(&mut outliner, &mut events).show(ui);
+            }
+            "Navigation Graphs" => {
+                ViewNavGraphs::new(&nav_graphs.graphs, &mut events).show(ui, &open_sites);
+            }
+            "Path Preview" => {
+                ViewPathPreview::new(&nav_graphs.path_preview, &mut events).show(ui);
+            }
+            "Compare Graphs" => {
+                ViewNavGraphDiff::new(&nav_graphs.graph_diff, &mut events).show(ui);
+            }
+            "Traffic Preview" => {
+                ViewTrafficPreview::new(&nav_graphs.traffic_preview, &mut events).show(ui);
+            }
+            // TODO(MXG): Consider combining Nav Graphs and Layers
+            "Layers" => {
+                ViewLayers::new(&drawings.layers, &mut events).show(ui);
+            }
+            "Measurements" => {
+                ViewMeasurements::new(&mut drawings.measurements, &mut events).show(ui);
+            }
+            "Inspect" => {
+                InspectorWidget::new(&inspector_params, &mut events).show(ui);
+            }
+            "Create" => {
+                CreateWidget::new(&mut events).show(ui);
+            }
+            "Lights" => {
+                ViewLights::new(&lighting.lights, &mut events).show(ui);
+            }
+            "Environment" => {
+                ViewEnvironment::new(&lighting.environment, &mut events).show(ui);
+            }
+            "Occupancy" => {
+                ViewOccupancy::new(&occupancy, &mut events).show(ui);
+            }
+            "Model Search" => {
+                ViewModelSearch::new(&mut events).show(ui);
+            }
+            "Model Tags" => {
+                ViewModelTags::new(&mut model_tags, &mut events).show(ui);
+            }
+            "Fuel Models" => {
+                ViewFuelModels::new(&mut events).show(ui);
+            }
+            "Asset Library" => {
+                ViewAssetLibrary::new(&mut events).show(ui);
+            }
+            "Asset Cache" => {
+                ViewAssetCache::new(&assets.cache, &mut events).show(ui);
+            }
+            "Asset Availability" => {
+                ViewAssetAvailability::new(&assets.availability, &mut events).show(ui);
+            }
+            "Asset Paths" => {
+                ViewAssetPaths::new(&assets.paths, &mut events).show(ui);
+            }
+            "Level of Detail" => {
+                ViewLevelOfDetail::new(&mut events).show(ui);
+            }
+            "Render Settings" => {
+                ViewRenderSettings::new(&mut events).show(ui);
+            }
+            "Keyboard Shortcuts" => {
+                ViewKeymap::new(&mut events).show(ui);
+            }
+            "Diagnostics" => {
+                ViewDiagnostics::new(&diagnostics, &mut events).show(ui);
+            }
+            "Console" => {
+                ViewConsole::new(&mut console, &mut events).show(ui);
+            }
+            "Undo History" => {
+                ViewUndoHistory::new(&mut undo_history, &mut events).show(ui);
+            }
+            _ => {}
+        },
+    );
 
     egui::TopBottomPanel::top("top_panel").show(egui_context.ctx_mut(), |ui| {
         egui::menu::bar(ui, |ui| {
-            ui.menu_button("File", |ui| {
-                if ui.add(Button::new("New").shortcut_text("Ctrl+N")).clicked() {
+            ui.menu_button(tr(ui, "File"), |ui| {
+                if action_button(
+                    ui,
+                    KeyAction::NewWorkspace,
+                    "New",
+                    "Start a new empty workspace",
+                )
+                .clicked()
+                {
                     events.file_events.new_workspace.send(CreateNewWorkspace);
                 }
                 #[cfg(not(target_arch = "wasm32"))]
                 {
-                    if ui
-                        .add(Button::new("Save").shortcut_text("Ctrl+S"))
-                        .clicked()
+                    if action_button(
+                        ui,
+                        KeyAction::Save,
+                        "Save",
+                        "Save the workspace to its file",
+                    )
+                    .clicked()
                     {
                         events
                             .file_events
                             .save
                             .send(SaveWorkspace::new().to_default_file());
                     }
-                    if ui
-                        .add(Button::new("Save As").shortcut_text("Ctrl+Shift+S"))
-                        .clicked()
+                    if action_button(
+                        ui,
+                        KeyAction::SaveAs,
+                        "Save As",
+                        "Choose a file to save the workspace to",
+                    )
+                    .clicked()
                     {
                         events
                             .file_events
                             .save
                             .send(SaveWorkspace::new().to_dialog());
                     }
+                    if ui.button(tr(ui, "Export Supervisor Config")).clicked() {
+                        events
+                            .file_events
+                            .save
+                            .send(SaveWorkspace::new().to_dialog().to_supervisor_config());
+                    }
                 }
-                if ui
-                    .add(Button::new("Open").shortcut_text("Ctrl+O"))
-                    .clicked()
+                if action_button(
+                    ui,
+                    KeyAction::OpenWorkspace,
+                    "Open",
+                    "Open a site, building, workcell, or urdf file",
+                )
+                .clicked()
                 {
                     events
                         .file_events
                         .load_workspace
                         .send(LoadWorkspace::Dialog);
                 }
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.menu_button(tr(ui, "Open Recent"), |ui| {
+                        let preferences = &mut events.display.view.preferences;
+                        let action = show_recent_files(ui, preferences);
+                        if let Some(file) = action.and_then(|a| a.apply(preferences)) {
+                            events
+                                .file_events
+                                .load_workspace
+                                .send(LoadWorkspace::Path(file));
+                            ui.close_menu();
+                        }
+                    });
+                }
+                ui.separator();
+                if ui.button(tr(ui, "Preferences...")).clicked() {
+                    events.display.view.preferences_display.open = true;
+                    ui.close_menu();
+                }
+            });
+            ui.menu_button(tr(ui, "Edit"), |ui| {
+                ui.checkbox(
+                    &mut events.site_request.surface_snapping.enabled,
+                    tr(ui, "Snap models to surfaces"),
+                )
+                .on_hover_text(tr(
+                    ui,
+                    "Rest models on the floor or on the top of the model beneath \
+                    the cursor while placing or dragging them",
+                ));
+            });
+            ui.menu_button(tr(ui, "View"), |ui| {
+                ui.label(tr(ui, "Model geometry"));
+                let display = &mut events.display.view.collision_display;
+                for option in CollisionDisplay::ALL {
+                    if ui
+                        .radio(display.0 == option, tr(ui, option.label()))
+                        .clicked()
+                    {
+                        display.0 = option;
+                    }
+                }
+                ui.separator();
+                show_shading_modes(ui, &mut events.display.view.shading);
+                ui.separator();
+                // Only assign when toggled, otherwise every frame would look
+                // like a change of mode
+                let mut xray = events.display.view.xray_selection.0;
+                if ui
+                    .checkbox(&mut xray, tr(ui, "X-Ray Selection"))
+                    .on_hover_text(tr(
+                        ui,
+                        "Draw everything except the selected and hovered elements \
+                        transparent so hidden lanes and anchors can be selected",
+                    ))
+                    .changed()
+                {
+                    events.display.view.xray_selection.0 = xray;
+                }
+                ui.separator();
+                let mut grid = events.display.view.preferences.grid;
+                ui.checkbox(&mut grid.visible, tr(ui, "Reference Grid"))
+                    .on_hover_text(tr(
+                        ui,
+                        "Draw a grid on the floor of the current level. Its \
+                        spacing and color are chosen in the preferences.",
+                    ));
+                ui.add_enabled(
+                    grid.visible,
+                    egui::Checkbox::new(&mut grid.snap, tr(ui, "Snap to Grid")),
+                )
+                .on_hover_text(tr(
+                    ui,
+                    "Place new anchors and dragged elements on the nearest crossing of the grid lines",
+                ));
+                // Only assign when toggled so the preferences are not saved
+                // every frame
+                if grid != events.display.view.preferences.grid {
+                    events.display.view.preferences.grid = grid;
+                }
+                ui.separator();
+                show_themes(ui, &mut events.display.view.preferences);
+            });
+            ui.menu_button(tr(ui, "Panels"), |ui| {
+                show_panel_layout_menu(ui, &mut events.display.view.panel_layout, SITE_PANELS);
             });
         });
     });
 
+    ViewMissingAssets::new(&assets.missing, &mut events).show(egui_context.ctx_mut());
+    ViewPreferences::new(&mut events).show(egui_context.ctx_mut());
+
     let egui_context = egui_context.ctx_mut();
     let ui_has_focus = egui_context.wants_pointer_input()
         || egui_context.wants_keyboard_input()
@@ -312,80 +811,182 @@ fn workcell_ui_layout(
     mut egui_context: ResMut<EguiContext>,
     mut picking_blocker: Option<ResMut<PickingBlockers>>,
     inspector_params: InspectorParams,
+    mut frame_hierarchy: FrameHierarchyParams,
+    mut joint_sliders: JointSliderParams,
+    mut frame_measurement: FrameMeasurementParams,
+    mut templates: WorkcellTemplateParams,
+    duplicate_names: DuplicateNameParams,
+    mut workcell_diff: WorkcellDiffParams,
+    mut console: ConsoleParams,
+    mut undo_history: UndoHistoryParams,
     mut events: AppEvents,
 ) {
-    egui::SidePanel::right("right_panel")
-        .resizable(true)
-        .show(egui_context.ctx_mut(), |ui| {
-            egui::ScrollArea::both()
-                .auto_shrink([false, false])
-                .show(ui, |ui| {
-                    ui.vertical(|ui| {
-                        CollapsingHeader::new("Inspect")
-                            .default_open(true)
-                            .show(ui, |ui| {
-                                InspectorWidget::new(&inspector_params, &mut events).show(ui);
-                            });
-                        ui.separator();
-                        CollapsingHeader::new("Create")
-                            .default_open(true)
-                            .show(ui, |ui| {
-                                CreateWidget::new(&mut events).show(ui);
-                            });
-                        ui.separator();
-                    });
-                });
-        });
+    let layout = events.display.view.panel_layout.clone();
+    show_docked_panels(
+        egui_context.ctx_mut(),
+        &layout,
+        WORKCELL_PANELS,
+        |panel, ui| match panel.name {
+            "Frames" => {
+                ViewFrameHierarchy::new(&mut frame_hierarchy, &mut events).show(ui);
+            }
+            "Joints" => {
+                ViewJointSliders::new(&mut joint_sliders, &mut events).show(ui);
+            }
+            "Measure" => {
+                ViewFrameMeasurement::new(&mut frame_measurement).show(ui);
+            }
+            "Templates" => {
+                ViewWorkcellTemplates::new(&mut templates).show(ui);
+            }
+            "Compare" => {
+                ViewWorkcellDiff::new(&mut workcell_diff, &mut events).show(ui);
+            }
+            "Inspect" => {
+                InspectorWidget::new(&inspector_params, &mut events).show(ui);
+            }
+            "Create" => {
+                CreateWidget::new(&mut events).show(ui);
+            }
+            "Render Settings" => {
+                ViewRenderSettings::new(&mut events).show(ui);
+            }
+            "Keyboard Shortcuts" => {
+                ViewKeymap::new(&mut events).show(ui);
+            }
+            "Console" => {
+                ViewConsole::new(&mut console, &mut events).show(ui);
+            }
+            "Undo History" => {
+                ViewUndoHistory::new(&mut undo_history, &mut events).show(ui);
+            }
+            _ => {}
+        },
+    );
 
     egui::TopBottomPanel::top("top_panel").show(egui_context.ctx_mut(), |ui| {
         egui::menu::bar(ui, |ui| {
-            ui.menu_button("File", |ui| {
-                if ui.add(Button::new("New").shortcut_text("Ctrl+N")).clicked() {
+            ui.menu_button(tr(ui, "File"), |ui| {
+                if action_button(
+                    ui,
+                    KeyAction::NewWorkspace,
+                    "New",
+                    "Start a new empty workspace",
+                )
+                .clicked()
+                {
                     events.file_events.new_workspace.send(CreateNewWorkspace);
                 }
                 #[cfg(not(target_arch = "wasm32"))]
                 {
-                    if ui
-                        .add(Button::new("Save").shortcut_text("Ctrl+S"))
-                        .clicked()
+                    if action_button(
+                        ui,
+                        KeyAction::Save,
+                        "Save",
+                        "Save the workspace to its file",
+                    )
+                    .clicked()
                     {
                         events
                             .file_events
                             .save
                             .send(SaveWorkspace::new().to_default_file());
                     }
-                    if ui
-                        .add(Button::new("Save As").shortcut_text("Ctrl+Shift+S"))
-                        .clicked()
+                    if action_button(
+                        ui,
+                        KeyAction::SaveAs,
+                        "Save As",
+                        "Choose a file to save the workspace to",
+                    )
+                    .clicked()
                     {
                         events
                             .file_events
                             .save
                             .send(SaveWorkspace::new().to_dialog());
                     }
+                    if action_button(
+                        ui,
+                        KeyAction::ExportUrdf,
+                        "Export urdf",
+                        "Save the workcell as a urdf file",
+                    )
+                    .clicked()
+                    {
+                        events
+                            .file_events
+                            .save
+                            .send(SaveWorkspace::new().to_dialog().to_urdf());
+                    }
                     if ui
-                        .add(Button::new("Export urdf").shortcut_text("Ctrl+E"))
+                        .button(tr(ui, "Export sdf"))
+                        .on_hover_text(tr(ui, "Save the workcell and its sensors as an sdf model"))
                         .clicked()
                     {
                         events
                             .file_events
                             .save
-                            .send(SaveWorkspace::new().to_dialog().to_urdf());
+                            .send(SaveWorkspace::new().to_dialog().to_sdf());
                     }
                 }
-                if ui
-                    .add(Button::new("Open").shortcut_text("Ctrl+O"))
-                    .clicked()
+                if action_button(
+                    ui,
+                    KeyAction::OpenWorkspace,
+                    "Open",
+                    "Open a site, building, workcell, or urdf file",
+                )
+                .clicked()
                 {
                     events
                         .file_events
                         .load_workspace
                         .send(LoadWorkspace::Dialog);
                 }
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.menu_button(tr(ui, "Open Recent"), |ui| {
+                        let preferences = &mut events.display.view.preferences;
+                        let action = show_recent_files(ui, preferences);
+                        if let Some(file) = action.and_then(|a| a.apply(preferences)) {
+                            events
+                                .file_events
+                                .load_workspace
+                                .send(LoadWorkspace::Path(file));
+                            ui.close_menu();
+                        }
+                    });
+                }
+                ui.separator();
+                if ui.button(tr(ui, "Preferences...")).clicked() {
+                    events.display.view.preferences_display.open = true;
+                    ui.close_menu();
+                }
+            });
+            ui.menu_button(tr(ui, "View"), |ui| {
+                ui.label(tr(ui, "Geometry"));
+                let display = &mut events.display.view.collision_display;
+                for option in CollisionDisplay::ALL {
+                    if ui
+                        .radio(display.0 == option, tr(ui, option.label()))
+                        .clicked()
+                    {
+                        display.0 = option;
+                    }
+                }
+                ui.separator();
+                show_shading_modes(ui, &mut events.display.view.shading);
+                ui.separator();
+                show_themes(ui, &mut events.display.view.preferences);
+            });
+            ui.menu_button(tr(ui, "Panels"), |ui| {
+                show_panel_layout_menu(ui, &mut events.display.view.panel_layout, WORKCELL_PANELS);
             });
         });
     });
 
+    ViewDuplicateNames::new(&duplicate_names, &mut events).show(egui_context.ctx_mut());
+    ViewPreferences::new(&mut events).show(egui_context.ctx_mut());
+
     let egui_context = egui_context.ctx_mut();
     let ui_has_focus = egui_context.wants_pointer_input()
         || egui_context.wants_keyboard_input()
@@ -404,10 +1005,51 @@ fn workcell_ui_layout(
     }
 }
 
-fn init_ui_style(mut egui_context: ResMut<EguiContext>) {
-    // I think the default egui dark mode text color is too dim, so this changes
-    // it to a brighter white.
-    let mut visuals = egui::Visuals::dark();
-    visuals.override_text_color = Some(egui::Color32::from_rgb(250, 250, 250));
+fn show_themes(ui: &mut Ui, preferences: &mut ResMut<Preferences>) {
+    ui.label(tr(ui, "Theme"));
+    for theme in UiTheme::ALL {
+        if ui
+            .radio(preferences.theme == theme, tr(ui, theme.label()))
+            .clicked()
+            && preferences.theme != theme
+        {
+            preferences.theme = theme;
+        }
+    }
+}
+
+fn show_shading_modes(ui: &mut Ui, shading: &mut ResMut<ShadingMode>) {
+    ui.label(tr(ui, "Shading"));
+    for mode in ShadingMode::ALL {
+        if ui.radio(**shading == mode, tr(ui, mode.label())).clicked() {
+            **shading = mode;
+        }
+    }
+}
+
+fn share_display_units(mut egui_context: ResMut<EguiContext>, preferences: Res<Preferences>) {
+    if preferences.is_changed() {
+        preferences.units.share(egui_context.ctx_mut());
+    }
+}
+
+fn apply_ui_theme(mut egui_context: ResMut<EguiContext>, preferences: Res<Preferences>) {
+    if !preferences.is_changed() {
+        return;
+    }
+
+    let visuals = if preferences.theme.is_dark() {
+        // I think the default egui dark mode text color is too dim, so this changes
+        // it to a brighter white.
+        let mut visuals = egui::Visuals::dark();
+        visuals.override_text_color = Some(egui::Color32::from_rgb(250, 250, 250));
+        visuals
+    } else {
+        // Use darker text than the egui default so the panels stay readable
+        // on washed out displays like projectors.
+        let mut visuals = egui::Visuals::light();
+        visuals.override_text_color = Some(egui::Color32::from_rgb(10, 10, 10));
+        visuals
+    };
     egui_context.ctx_mut().set_visuals(visuals);
 }
@@ -0,0 +1,80 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::localization::tr;
+use bevy_egui::egui::{
+    text::{LayoutJob, TextFormat},
+    Response, TextEdit, TextStyle, Ui,
+};
+
+/// Show a text field for filtering a list by name, with a button to clear it
+pub fn edit_name_filter(ui: &mut Ui, filter: &mut String) {
+    ui.horizontal(|ui| {
+        ui.label(tr(ui, "Filter"));
+        ui.text_edit_singleline(filter)
+            .on_hover_text(tr(ui, "Only show items whose names contain this text"));
+        if !filter.is_empty()
+            && ui
+                .button("❌")
+                .on_hover_text(tr(ui, "Clear filter"))
+                .clicked()
+        {
+            filter.clear();
+        }
+    });
+}
+
+/// Check if a name contains the filter, ignoring case. Every name matches an
+/// empty filter.
+pub fn matches_name_filter(name: &str, filter: &str) -> bool {
+    filter.is_empty() || name.to_lowercase().contains(&filter.to_lowercase())
+}
+
+/// Edit a name, highlighting the parts of it that match the filter
+pub fn edit_filtered_name(ui: &mut Ui, name: &mut String, filter: &str) -> Response {
+    if filter.is_empty() {
+        return ui.text_edit_singleline(name);
+    }
+
+    let filter = filter.to_lowercase();
+    let mut layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+        let font_id = TextStyle::Body.resolve(ui.style());
+        let plain = TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+        let highlight = TextFormat {
+            background: ui.visuals().selection.bg_fill,
+            color: ui.visuals().selection.stroke.color,
+            ..TextFormat::simple(font_id, ui.visuals().text_color())
+        };
+
+        let mut job = LayoutJob::default();
+        let lower = text.to_lowercase();
+        let mut start = 0;
+        // Lowercasing can change the byte length of some characters, so only
+        // highlight when the byte positions still line up with the text
+        if lower.len() == text.len() {
+            for (found, _) in lower.match_indices(&filter) {
+                job.append(&text[start..found], 0.0, plain.clone());
+                job.append(&text[found..found + filter.len()], 0.0, highlight.clone());
+                start = found + filter.len();
+            }
+        }
+        job.append(&text[start..], 0.0, plain);
+        job.wrap.max_width = wrap_width;
+        ui.fonts().layout_job(job)
+    };
+
+    ui.add(TextEdit::singleline(name).layouter(&mut layouter))
+}
@@ -0,0 +1,217 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    localization::{tr, Translations},
+    settings::{config_file, load_config, save_config},
+};
+use bevy::prelude::*;
+use bevy_egui::egui::{self, CollapsingHeader, Context, Ui};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Where a panel is shown in the editor window
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelDock {
+    Left,
+    Right,
+    Bottom,
+    /// The panel is shown in its own window that can be moved anywhere
+    Floating,
+}
+
+impl PanelDock {
+    pub const ALL: [PanelDock; 4] = [Self::Left, Self::Right, Self::Bottom, Self::Floating];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Left => "Left",
+            Self::Right => "Right",
+            Self::Bottom => "Bottom",
+            Self::Floating => "Floating",
+        }
+    }
+}
+
+/// A panel of the editor that can be moved between docks
+#[derive(Debug, Clone, Copy)]
+pub struct DockablePanel {
+    pub name: &'static str,
+    pub default_open: bool,
+    pub default_dock: PanelDock,
+}
+
+impl DockablePanel {
+    pub const fn new(name: &'static str, default_open: bool, default_dock: PanelDock) -> Self {
+        Self {
+            name,
+            default_open,
+            default_dock,
+        }
+    }
+}
+
+/// The docks that panels have been moved into. Panels that were never moved
+/// stay in their default dock. This is saved on this computer so the layout
+/// persists across sessions.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct PanelLayout {
+    pub docks: BTreeMap<String, PanelDock>,
+}
+
+impl PanelLayout {
+    pub fn dock_of(&self, panel: &DockablePanel) -> PanelDock {
+        self.docks
+            .get(panel.name)
+            .copied()
+            .unwrap_or(panel.default_dock)
+    }
+
+    pub fn set_dock(&mut self, panel: &DockablePanel, dock: PanelDock) {
+        if dock == panel.default_dock {
+            self.docks.remove(panel.name);
+        } else {
+            self.docks.insert(panel.name.to_owned(), dock);
+        }
+    }
+
+    /// The file that the panel layout is saved in
+    pub fn file() -> Option<PathBuf> {
+        config_file("panel_layout.json")
+    }
+
+    /// Load the layout from the last session, if it was saved
+    pub fn load() -> Option<Self> {
+        load_config("panel_layout.json", "panel layout")
+    }
+
+    pub fn save(&self) {
+        save_config("panel_layout.json", "panel layout", self);
+    }
+}
+
+pub fn save_panel_layout(layout: Res<PanelLayout>) {
+    if layout.is_changed() && !layout.is_added() {
+        layout.save();
+    }
+}
+
+/// Show each panel in the dock that the layout puts it in. Panels that share
+/// a dock are stacked in collapsible sections.
+pub fn show_docked_panels(
+    ctx: &Context,
+    layout: &PanelLayout,
+    panels: &[DockablePanel],
+    mut show: impl FnMut(&DockablePanel, &mut Ui),
+) {
+    for dock in [PanelDock::Left, PanelDock::Right, PanelDock::Bottom] {
+        let docked: Vec<&DockablePanel> = panels
+            .iter()
+            .filter(|panel| layout.dock_of(panel) == dock)
+            .collect();
+        if docked.is_empty() {
+            continue;
+        }
+
+        let contents = |ui: &mut Ui| {
+            egui::ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        for (i, panel) in docked.iter().enumerate() {
+                            if i > 0 {
+                                ui.separator();
+                            }
+                            CollapsingHeader::new(tr(ui, panel.name))
+                                .id_source(panel.name)
+                                .default_open(panel.default_open)
+                                .show(ui, |ui| {
+                                    show(panel, ui);
+                                });
+                        }
+                    });
+                });
+        };
+
+        match dock {
+            PanelDock::Left => {
+                egui::SidePanel::left("left_panel")
+                    .resizable(true)
+                    .show(ctx, contents);
+            }
+            PanelDock::Right => {
+                egui::SidePanel::right("right_panel")
+                    .resizable(true)
+                    .show(ctx, contents);
+            }
+            PanelDock::Bottom => {
+                egui::TopBottomPanel::bottom("bottom_panel")
+                    .resizable(true)
+                    .default_height(200.0)
+                    .show(ctx, contents);
+            }
+            PanelDock::Floating => {}
+        }
+    }
+
+    for panel in panels {
+        if layout.dock_of(panel) != PanelDock::Floating {
+            continue;
+        }
+        egui::Window::new(Translations::of(ctx).get(panel.name))
+            .id(egui::Id::new(panel.name))
+            .default_open(panel.default_open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                egui::ScrollArea::both().show(ui, |ui| {
+                    show(panel, ui);
+                });
+            });
+    }
+}
+
+/// Menu entries for moving each panel to a different dock
+pub fn show_panel_layout_menu(
+    ui: &mut Ui,
+    layout: &mut ResMut<PanelLayout>,
+    panels: &[DockablePanel],
+) {
+    for panel in panels {
+        let current = layout.dock_of(panel);
+        ui.menu_button(tr(ui, panel.name), |ui| {
+            for dock in PanelDock::ALL {
+                if ui.radio(current == dock, tr(ui, dock.label())).clicked() && current != dock {
+                    layout.set_dock(panel, dock);
+                }
+            }
+        });
+    }
+    ui.separator();
+    let moved = panels
+        .iter()
+        .any(|panel| layout.docks.contains_key(panel.name));
+    if ui
+        .add_enabled(moved, egui::Button::new(tr(ui, "Reset Layout")))
+        .clicked()
+    {
+        for panel in panels {
+            layout.docks.remove(panel.name);
+        }
+    }
+}
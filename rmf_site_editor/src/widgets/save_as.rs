@@ -0,0 +1,100 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    localization::{tr, Translations},
+    site::{DrawingStorage, SiteSaveOptions},
+    SaveWorkspace,
+};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+use rmf_site_format::SiteFileFormat;
+
+#[derive(Resource, Default)]
+pub struct SaveAsDisplay {
+    pub open: bool,
+    /// The choices are remembered for the next time the window is opened
+    pub options: SiteSaveOptions,
+}
+
+/// Let the user choose how a site should be saved before choosing the file to
+/// save it to
+pub fn show_save_as_window(
+    mut egui_context: ResMut<EguiContext>,
+    mut display: ResMut<SaveAsDisplay>,
+    mut save_workspace: EventWriter<SaveWorkspace>,
+) {
+    if !display.open {
+        return;
+    }
+
+    let ctx = egui_context.ctx_mut();
+    let title = Translations::of(ctx).get("Save As");
+    let mut open = true;
+    let mut save = false;
+    let mut options = display.options.clone();
+    egui::Window::new(title)
+        .id(egui::Id::new("save_as_window"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0., 0.))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Format"));
+                egui::ComboBox::from_id_source("save_as_format")
+                    .selected_text(options.format.label())
+                    .show_ui(ui, |ui| {
+                        for format in SiteFileFormat::ALL {
+                            ui.selectable_value(&mut options.format, format, format.label());
+                        }
+                    });
+            });
+            ui.checkbox(&mut options.renumber_ids, tr(ui, "Renumber IDs"))
+                .on_hover_text(tr(
+                    ui,
+                    "Give the elements of the site consecutive IDs, removing the gaps \
+                    left by deleted elements. Files that refer to the old IDs will no \
+                    longer match.",
+                ));
+            ui.separator();
+            ui.label(tr(ui, "Drawings"));
+            for storage in DrawingStorage::ALL {
+                ui.radio_value(&mut options.drawings, storage, tr(ui, storage.label()));
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(tr(ui, "Choose File...")).clicked() {
+                    save = true;
+                }
+                if ui.button(tr(ui, "Cancel")).clicked() {
+                    display.open = false;
+                }
+            });
+        });
+
+    if options != display.options {
+        display.options = options.clone();
+    }
+    if !open {
+        display.open = false;
+    }
+    if save {
+        display.open = false;
+        save_workspace.send(SaveWorkspace::new().to_dialog().with_site_options(options));
+    }
+}
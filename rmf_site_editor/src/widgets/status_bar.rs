@@ -0,0 +1,187 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{
+    interaction::{Cursor, InteractionMode, MultiSelection, Selection},
+    localization::{tr, tr_args},
+    site::{
+        measured_length, AnchorParams, CurrentLevel, Edge, LevelProperties, LoadProgress,
+        MeasurementMarker, Pending,
+    },
+    AutosaveRecord, AutosaveStatus, CurrentWorkspace, DisplayUnits, Preferences, UnsavedChanges,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{egui, EguiContext};
+
+#[derive(SystemParam)]
+pub struct StatusBarParams<'w, 's> {
+    pub mode: Res<'w, InteractionMode>,
+    pub cursor: Res<'w, Cursor>,
+    pub transforms: Query<'w, 's, &'static GlobalTransform>,
+    pub current_level: Option<Res<'w, CurrentLevel>>,
+    pub levels: Query<'w, 's, &'static LevelProperties>,
+    pub selection: Res<'w, Selection>,
+    pub multi_selection: Res<'w, MultiSelection>,
+    pub unsaved: Res<'w, UnsavedChanges>,
+    /// Measurements that are still being drawn
+    pub pending_measurements:
+        Query<'w, 's, &'static Edge<Entity>, (With<MeasurementMarker>, With<Pending>)>,
+    pub anchors: AnchorParams<'w, 's>,
+    pub load_progress: Res<'w, LoadProgress>,
+    pub autosave: Res<'w, AutosaveStatus>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub preferences: Res<'w, Preferences>,
+    pub time: Res<'w, Time>,
+}
+
+/// Show a thin bar along the bottom of the window that summarizes the state
+/// of the editor
+pub fn show_status_bar(mut egui_context: ResMut<EguiContext>, params: StatusBarParams) {
+    egui::TopBottomPanel::bottom("status_bar").show(egui_context.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr_args(
+                ui,
+                "Mode: {mode}",
+                &[("mode", tr(ui, params.mode.label()))],
+            ));
+
+            if let Ok(tf) = params.transforms.get(params.cursor.frame) {
+                let units = DisplayUnits::of(ui).length;
+                let p = tf.translation();
+                ui.separator();
+                ui.label(tr_args(
+                    ui,
+                    "Cursor: {x}, {y}, {z}",
+                    &[
+                        ("x", units.format(p.x, 2)),
+                        ("y", units.format(p.y, 2)),
+                        ("z", units.format(p.z, 2)),
+                    ],
+                ));
+            }
+
+            for edge in &params.pending_measurements {
+                if let Some(length) = measured_length(edge, &params.anchors) {
+                    let units = DisplayUnits::of(ui).length;
+                    ui.separator();
+                    ui.label(tr_args(
+                        ui,
+                        "Measuring: {length}",
+                        &[("length", units.format(length, 3))],
+                    ));
+                }
+            }
+
+            let level = params
+                .current_level
+                .as_ref()
+                .and_then(|level| level.0)
+                .and_then(|e| params.levels.get(e).ok());
+            if let Some(level) = level {
+                ui.separator();
+                ui.label(tr_args(
+                    ui,
+                    "Level: {level}",
+                    &[("level", level.name.clone())],
+                ));
+            }
+
+            let selected =
+                params.selection.0.map(|_| 1).unwrap_or(0) + params.multi_selection.len();
+            ui.separator();
+            ui.label(tr_args(
+                ui,
+                "Selected: {count}",
+                &[("count", selected.to_string())],
+            ));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if params.unsaved.0 {
+                    let text = format!("● {}", tr(ui, "Unsaved changes"));
+                    ui.colored_label(ui.visuals().warn_fg_color, text)
+                        .on_hover_text(tr(ui, "The workspace has changed since it was last saved"));
+                } else {
+                    ui.weak(tr(ui, "Saved"));
+                }
+
+                if params.preferences.autosave_minutes > 0 {
+                    ui.separator();
+                    let autosave = params
+                        .current_workspace
+                        .root
+                        .and_then(|root| params.autosave.0.get(&root));
+                    let text = match autosave {
+                        Some(autosave) => {
+                            let minutes =
+                                (params.time.elapsed_seconds_f64() - autosave.time) / 60.0;
+                            tr_args(
+                                ui,
+                                "Autosaved {minutes} min ago",
+                                &[("minutes", format!("{}", minutes.floor() as u64))],
+                            )
+                        }
+                        None => tr(ui, "Autosave on"),
+                    };
+                    let hover = match autosave {
+                        Some(AutosaveRecord { file, .. }) => tr_args(
+                            ui,
+                            "Autosaving every {interval} min to {file}",
+                            &[
+                                ("interval", params.preferences.autosave_minutes.to_string()),
+                                ("file", file.display().to_string()),
+                            ],
+                        ),
+                        None => tr_args(
+                            ui,
+                            "Autosaving every {interval} min",
+                            &[("interval", params.preferences.autosave_minutes.to_string())],
+                        ),
+                    };
+                    ui.weak(text).on_hover_text(hover);
+                }
+
+                let progress = &params.load_progress;
+                if progress.is_loading() {
+                    ui.separator();
+                    let done = progress.total() - progress.pending().min(progress.total());
+                    let text = tr_args(
+                        ui,
+                        "Loading {done} of {total}",
+                        &[
+                            ("done", done.to_string()),
+                            ("total", progress.total().to_string()),
+                        ],
+                    );
+                    let details = tr_args(
+                        ui,
+                        "Models: {models}\nDownloads: {downloads}\nDrawings: {drawings}",
+                        &[
+                            ("models", progress.pending_models.to_string()),
+                            ("downloads", progress.pending_downloads.to_string()),
+                            ("drawings", progress.pending_drawings.to_string()),
+                        ],
+                    );
+                    ui.add(
+                        egui::ProgressBar::new(progress.fraction())
+                            .desired_width(180.0)
+                            .text(text),
+                    )
+                    .on_hover_text(details);
+                }
+            });
+        });
+    });
+}
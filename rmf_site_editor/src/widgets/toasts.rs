@@ -0,0 +1,76 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::console::{Severity, Toasts, TOAST_DURATION};
+use crate::localization::tr;
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, RichText},
+    EguiContext,
+};
+
+/// Show toast notifications in the bottom right corner of the window until
+/// they expire or are dismissed
+pub fn show_toasts(
+    mut egui_context: ResMut<EguiContext>,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds_f64();
+    // Only touch the toasts when one expires so they are not marked as
+    // changed every frame
+    if toasts
+        .toasts
+        .iter()
+        .any(|toast| now - toast.shown_at >= TOAST_DURATION)
+    {
+        toasts.expire(now);
+    }
+    if toasts.toasts.is_empty() {
+        return;
+    }
+
+    let mut dismissed = None;
+    egui::Area::new("toasts")
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -36.0])
+        .order(egui::Order::Foreground)
+        .show(egui_context.ctx_mut(), |ui| {
+            for (i, toast) in toasts.toasts.iter().enumerate() {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(320.0);
+                    ui.horizontal(|ui| {
+                        let color = match toast.entry.severity {
+                            Severity::Info => ui.visuals().text_color(),
+                            Severity::Warning => ui.visuals().warn_fg_color,
+                            Severity::Error => ui.visuals().error_fg_color,
+                        };
+                        ui.label(RichText::new(&toast.entry.message).color(color));
+                        if ui
+                            .small_button("❌")
+                            .on_hover_text(tr(ui, "Dismiss"))
+                            .clicked()
+                        {
+                            dismissed = Some(i);
+                        }
+                    });
+                });
+            }
+        });
+
+    if let Some(i) = dismissed {
+        toasts.toasts.remove(i);
+    }
+}
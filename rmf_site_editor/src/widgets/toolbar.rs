@@ -0,0 +1,92 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{
+    interaction::{ChangeMode, InteractionMode, Tool},
+    keyboard::{KeyCapture, Keymap},
+    localization::tr,
+    AppState,
+};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+fn tools_for(state: &AppState) -> &'static [Tool] {
+    match state {
+        AppState::MainMenu => &[],
+        AppState::SiteEditor => &Tool::SITE,
+        AppState::WorkcellEditor => &Tool::WORKCELL,
+    }
+}
+
+fn choose_tool(choice: Tool, tool: &mut ResMut<Tool>, change_mode: &mut EventWriter<ChangeMode>) {
+    if **tool != choice {
+        **tool = choice;
+    }
+    change_mode.send(ChangeMode::To(choice.mode()));
+}
+
+/// Show a bar of the tools that can be used in the current editor, below the
+/// menu bar
+pub fn show_toolbar(
+    mut egui_context: ResMut<EguiContext>,
+    app_state: Res<State<AppState>>,
+    keymap: Res<Keymap>,
+    mode: Res<InteractionMode>,
+    mut tool: ResMut<Tool>,
+    mut change_mode: EventWriter<ChangeMode>,
+) {
+    egui::TopBottomPanel::top("toolbar").show(egui_context.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            for choice in tools_for(app_state.current()).iter().copied() {
+                let selected = *tool == choice && choice.is_active_in(&mode);
+                let label = tr(ui, choice.label());
+                let hover = keymap.hover_text(choice.key_action(), &label);
+                if ui
+                    .selectable_label(selected, format!("{} {label}", choice.icon()))
+                    .on_hover_text(hover)
+                    .clicked()
+                {
+                    choose_tool(choice, &mut tool, &mut change_mode);
+                }
+            }
+        });
+    });
+}
+
+pub fn handle_tool_shortcuts(
+    keyboard_input: Res<Input<KeyCode>>,
+    keymap: Res<Keymap>,
+    capture: Res<KeyCapture>,
+    app_state: Res<State<AppState>>,
+    mut egui_context: ResMut<EguiContext>,
+    mut tool: ResMut<Tool>,
+    mut change_mode: EventWriter<ChangeMode>,
+) {
+    let egui_context = egui_context.ctx_mut();
+    let ui_has_focus = egui_context.wants_pointer_input()
+        || egui_context.wants_keyboard_input()
+        || egui_context.is_pointer_over_area();
+
+    if ui_has_focus || capture.0.is_some() {
+        return;
+    }
+
+    for choice in tools_for(app_state.current()).iter().copied() {
+        if keymap.just_pressed(choice.key_action(), &keyboard_input) {
+            choose_tool(choice, &mut tool, &mut change_mode);
+        }
+    }
+}
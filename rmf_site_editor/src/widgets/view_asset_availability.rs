@@ -0,0 +1,183 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    localization::tr,
+    site::{NameInSite, SiteID},
+    site_asset_io::{asset_availability, is_offline_mode, set_offline_mode, AssetAvailability},
+    widgets::{inspector::SelectionWidget, AppEvents, Icons},
+    CurrentWorkspace,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{Grid, Ui};
+use rmf_site_format::{AssetSource, DrawingMarker, ModelMarker, Pending};
+
+/// An element of the site whose asset cannot be loaded while offline
+#[derive(Debug, Clone)]
+pub struct UnavailableAsset {
+    pub entity: Entity,
+    pub name: String,
+    pub source: AssetSource,
+    pub availability: AssetAvailability,
+}
+
+#[derive(Resource, Default)]
+pub struct AssetAvailabilityDisplay {
+    /// The elements that were found to depend on unreachable assets the last
+    /// time the assets were checked, or None if they have not been checked
+    pub report: Option<Vec<UnavailableAsset>>,
+}
+
+#[derive(SystemParam)]
+pub struct AssetAvailabilityParams<'w, 's> {
+    pub sources: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static AssetSource,
+            Option<&'static NameInSite>,
+            Option<&'static DrawingMarker>,
+        ),
+        Without<Pending>,
+    >,
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub models: Query<'w, 's, (), With<ModelMarker>>,
+    pub site_id: Query<'w, 's, &'static SiteID>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub icons: Res<'w, Icons>,
+}
+
+impl<'w, 's> AssetAvailabilityParams<'w, 's> {
+    /// Find every element of the current site whose asset is not on this
+    /// computer. This looks at the file system, so it should only be done
+    /// when the user asks for it.
+    pub fn check(&self) -> Vec<UnavailableAsset> {
+        let mut unavailable: Vec<_> = self
+            .sources
+            .iter()
+            .filter(|(e, ..)| {
+                AncestorIter::new(&self.parents, *e).any(|p| Some(p) == self.current_workspace.root)
+            })
+            // The meshes of SDF models share the availability of the model
+            .filter(|(e, ..)| {
+                !AncestorIter::new(&self.parents, *e).any(|p| self.models.contains(p))
+            })
+            .filter_map(|(e, source, name, drawing)| {
+                let availability = asset_availability(source);
+                if availability.is_available_offline() {
+                    return None;
+                }
+                let name = match (name, drawing) {
+                    (Some(name), _) => name.0.clone(),
+                    (None, Some(_)) => "Drawing".to_owned(),
+                    (None, None) => format!("{e:?}"),
+                };
+                Some(UnavailableAsset {
+                    entity: e,
+                    name,
+                    source: source.clone(),
+                    availability,
+                })
+            })
+            .collect();
+        unavailable.sort_by_key(|u| u.entity);
+        unavailable
+    }
+}
+
+/// Write a report of unavailable assets as plain text, one element per line
+pub fn asset_availability_report(unavailable: &[UnavailableAsset]) -> String {
+    unavailable
+        .iter()
+        .map(|u| {
+            format!(
+                "{}\t{}\t{}\n",
+                u.name,
+                u.availability.label(),
+                String::from(&u.source)
+            )
+        })
+        .collect()
+}
+
+pub struct ViewAssetAvailability<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a AssetAvailabilityParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewAssetAvailability<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a AssetAvailabilityParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let mut offline = is_offline_mode();
+        if ui
+            .checkbox(&mut offline, tr(ui, "Offline mode"))
+            .on_hover_text(tr(
+                ui,
+                "Only load assets that are on this computer or in the asset cache",
+            ))
+            .changed()
+        {
+            set_offline_mode(offline);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button(tr(ui, "Check Assets")).clicked() {
+                self.events.display.assets.availability.report = Some(self.params.check());
+            }
+            if let Some(report) = &self.events.display.assets.availability.report {
+                if !report.is_empty() && ui.button(tr(ui, "Copy Report")).clicked() {
+                    ui.output().copied_text = asset_availability_report(report);
+                }
+            }
+        });
+
+        let Some(report) = self.events.display.assets.availability.report.clone() else {
+            return;
+        };
+        // Elements may have been deleted since the assets were checked
+        let report: Vec<_> = report
+            .into_iter()
+            .filter(|u| self.params.sources.contains(u.entity))
+            .collect();
+        if report.is_empty() {
+            ui.label(tr(ui, "Every asset is available offline"));
+            return;
+        }
+
+        Grid::new("asset_availability").show(ui, |ui| {
+            for u in &report {
+                SelectionWidget::new(
+                    u.entity,
+                    self.params.site_id.get(u.entity).ok().copied(),
+                    self.params.icons.as_ref(),
+                    self.events,
+                )
+                .show(ui);
+                ui.label(&u.name).on_hover_text(String::from(&u.source));
+                ui.label(u.availability.label());
+                ui.end_row();
+            }
+        });
+    }
+}
@@ -0,0 +1,132 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{localization::tr, site::Pending, widgets::AppEvents};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::Ui;
+use rmf_site_format::AssetSource;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    console::{notify_error, notify_info},
+    site_asset_io::{
+        clear_remote_asset_cache, evict_unused_remote_assets, remote_asset_cache_path,
+        remote_asset_cache_size,
+    },
+};
+
+#[derive(Resource, Default)]
+pub struct AssetCacheDisplay {
+    /// Size of the cache in bytes, if it has been measured
+    pub size: Option<u64>,
+}
+
+/// Format a number of bytes for display
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[derive(SystemParam)]
+pub struct AssetCacheParams<'w, 's> {
+    pub sources: Query<'w, 's, &'static AssetSource, Without<Pending>>,
+}
+
+pub struct ViewAssetCache<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a AssetCacheParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewAssetCache<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a AssetCacheParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn show(self, ui: &mut Ui) {
+        ui.label(tr(ui, "Remote assets are not cached in the browser"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn show(self, ui: &mut Ui) {
+        let display = &mut self.events.display.assets.asset_cache;
+        let size = *display.size.get_or_insert_with(remote_asset_cache_size);
+        ui.label(format!("Size: {}", format_bytes(size)))
+            .on_hover_text(remote_asset_cache_path().to_string_lossy());
+        ui.horizontal(|ui| {
+            if ui.button(tr(ui, "Refresh")).clicked() {
+                display.size = None;
+            }
+            if ui
+                .button(tr(ui, "Clear"))
+                .on_hover_text(tr(ui, "Delete every downloaded asset"))
+                .clicked()
+            {
+                if let Err(err) = clear_remote_asset_cache() {
+                    notify_error(None, format!("Unable to clear the asset cache: {err}"));
+                }
+                display.size = None;
+            }
+            if ui
+                .button(tr(ui, "Evict Unused"))
+                .on_hover_text(tr(
+                    ui,
+                    "Delete every downloaded asset that is not used by an open \
+                    workspace, along with files left over from older versions \
+                    of the cache",
+                ))
+                .clicked()
+            {
+                let in_use = self
+                    .params
+                    .sources
+                    .iter()
+                    .filter_map(|source| match source {
+                        // Search sources fall back to the cache when they are
+                        // not found on this computer
+                        AssetSource::Remote(name) | AssetSource::Search(name) => Some(name.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                match evict_unused_remote_assets(&in_use) {
+                    Ok(count) => notify_info(
+                        None,
+                        format!("Evicted {count} unused files from the asset cache"),
+                    ),
+                    Err(err) => notify_error(
+                        None,
+                        format!("Unable to evict unused files from the asset cache: {err}"),
+                    ),
+                }
+                display.size = None;
+            }
+        });
+    }
+}
@@ -0,0 +1,333 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{notify_error, notify_warning},
+    interaction::{ChangeMode, SelectAnchor3D},
+    localization::tr,
+    site::Change,
+    site_asset_io::MODEL_ENVIRONMENT_VARIABLE,
+    widgets::AppEvents,
+};
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use bevy_egui::{
+    egui::{self, TextureId, Ui},
+    EguiContext,
+};
+use futures_lite::future;
+use rmf_site_format::{AssetSource, Model};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::AsyncFileDialog;
+
+/// File extensions of the meshes that can be loaded as models
+pub const MODEL_FILE_EXTENSIONS: [&str; 4] = ["glb", "gltf", "obj", "stl"];
+/// Size of the model thumbnails in the library
+pub const LIBRARY_THUMBNAIL_SIZE: f32 = 64.0;
+
+/// A model that was found in one of the directories of the asset library
+#[derive(Debug, Clone)]
+pub struct LocalModel {
+    pub name: String,
+    pub path: PathBuf,
+    /// Names of the folders between the library directory and the model,
+    /// which are used to find the model in searches
+    pub tags: Vec<String>,
+    /// An image of the model, if one was found next to it. Gazebo models
+    /// keep their thumbnails in a thumbnails folder inside the model folder.
+    pub thumbnail: Option<PathBuf>,
+}
+
+impl LocalModel {
+    pub fn source(&self) -> AssetSource {
+        AssetSource::Local(self.path.to_string_lossy().into_owned())
+    }
+
+    /// Check if every term of a search appears in the name or tags of this
+    /// model, ignoring case
+    pub fn matches(&self, search: &str) -> bool {
+        let name = self.name.to_lowercase();
+        let tags: Vec<String> = self.tags.iter().map(|t| t.to_lowercase()).collect();
+        search.split_whitespace().all(|term| {
+            let term = term.to_lowercase();
+            name.contains(&term) || tags.iter().any(|tag| tag.contains(&term))
+        })
+    }
+}
+
+#[derive(Resource)]
+pub struct AssetLibraryDisplay {
+    pub directories: Vec<PathBuf>,
+    pub models: Vec<LocalModel>,
+    pub choosing_directory: Option<Task<Option<PathBuf>>>,
+    /// Thumbnails that have been registered with egui, keyed by the path of
+    /// the model
+    pub thumbnails: HashMap<PathBuf, (Handle<Image>, TextureId)>,
+}
+
+impl Default for AssetLibraryDisplay {
+    fn default() -> Self {
+        // Start with the directories that Gazebo would search for models
+        let directories: Vec<PathBuf> = std::env::var(MODEL_ENVIRONMENT_VARIABLE)
+            .map(|paths| std::env::split_paths(&paths).collect())
+            .unwrap_or_default();
+        let mut display = Self {
+            directories,
+            models: Vec::new(),
+            choosing_directory: None,
+            thumbnails: HashMap::new(),
+        };
+        display.scan();
+        display
+    }
+}
+
+impl AssetLibraryDisplay {
+    /// Look through the library directories for models
+    pub fn scan(&mut self) {
+        self.models.clear();
+        for dir in &self.directories {
+            scan_directory(dir, &mut Vec::new(), &mut self.models);
+        }
+        self.models.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+/// How many folders deep the library will look for models
+pub const LIBRARY_SCAN_DEPTH: usize = 4;
+
+fn scan_directory(dir: &Path, tags: &mut Vec<String>, models: &mut Vec<LocalModel>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            notify_error(
+                None,
+                format!("Unable to read asset directory {dir:?}: {err}"),
+            );
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if path.is_dir() {
+            let sdf = path.join("model.sdf");
+            if sdf.exists() {
+                let thumbnail = path.join("thumbnails").join("1.png");
+                models.push(LocalModel {
+                    name,
+                    path: sdf,
+                    tags: tags.clone(),
+                    thumbnail: thumbnail.exists().then_some(thumbnail),
+                });
+            } else if tags.len() < LIBRARY_SCAN_DEPTH {
+                tags.push(name);
+                scan_directory(&path, tags, models);
+                tags.pop();
+            }
+            continue;
+        }
+
+        // Workcells can be placed in a site as a single model
+        if name.ends_with(".workcell.json") {
+            models.push(LocalModel {
+                name: name.trim_end_matches(".workcell.json").to_owned(),
+                path,
+                tags: tags.clone(),
+                thumbnail: None,
+            });
+            continue;
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase());
+        let Some(extension) = extension else {
+            continue;
+        };
+        if MODEL_FILE_EXTENSIONS.contains(&extension.as_str()) {
+            models.push(LocalModel {
+                name,
+                path,
+                tags: tags.clone(),
+                thumbnail: None,
+            });
+        }
+    }
+}
+
+pub struct ViewAssetLibrary<'a, 'w, 's> {
+    events: &'a mut AppEvents<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewAssetLibrary<'a, 'w, 's> {
+    pub fn new(events: &'a mut AppEvents<'w, 's>) -> Self {
+        Self { events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let display = &mut self.events.display.assets.asset_library;
+        ui.horizontal(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                if ui.button(tr(ui, "Add Directory...")).clicked() {
+                    if display.choosing_directory.is_some() {
+                        notify_warning(None, "A directory is already being chosen!");
+                    } else {
+                        let future = AsyncComputeTaskPool::get().spawn(async move {
+                            let dir = AsyncFileDialog::new().pick_folder().await?;
+                            Some(dir.path().to_path_buf())
+                        });
+                        display.choosing_directory = Some(future);
+                    }
+                }
+            }
+            if ui.button(tr(ui, "Rescan")).clicked() {
+                display.scan();
+            }
+        });
+
+        let mut removed_dir = None;
+        for (i, dir) in display.directories.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .button("❌")
+                    .on_hover_text(tr(ui, "Remove directory"))
+                    .clicked()
+                {
+                    removed_dir = Some(i);
+                }
+                ui.label(dir.to_string_lossy());
+            });
+        }
+        if let Some(i) = removed_dir {
+            display.directories.remove(i);
+            display.scan();
+        }
+
+        if display.models.is_empty() {
+            ui.label(tr(ui, "No models found"));
+            return;
+        }
+
+        ui.separator();
+        let mut chosen = None;
+        for model in &display.models {
+            ui.horizontal(|ui| {
+                if let Some((_, texture)) = display.thumbnails.get(&model.path) {
+                    ui.add(egui::Image::new(
+                        *texture,
+                        [LIBRARY_THUMBNAIL_SIZE, LIBRARY_THUMBNAIL_SIZE],
+                    ));
+                }
+                ui.vertical(|ui| {
+                    ui.label(&model.name);
+                    if ui
+                        .button(tr(ui, "Insert"))
+                        .on_hover_text(model.path.to_string_lossy())
+                        .clicked()
+                    {
+                        chosen = Some(model.source());
+                    }
+                });
+            });
+        }
+
+        if let Some(source) = chosen {
+            place_model(self.events, source);
+        }
+    }
+}
+
+/// Make a model the pending asset source, which is previewed by the Create
+/// panel
+pub fn preview_model(events: &mut AppEvents, source: AssetSource) {
+    if let Ok((e, _, _)) = events.pending_asset_sources.get_single() {
+        events.change.asset_source.send(Change::new(source, e));
+    }
+}
+
+/// Preview a model and start placing it in the site, using the scale that
+/// has been chosen for new models
+pub fn place_model(events: &mut AppEvents, source: AssetSource) {
+    preview_model(events, source.clone());
+    let scale = events
+        .pending_asset_sources
+        .get_single()
+        .map(|(_, _, scale)| scale.clone())
+        .unwrap_or_default();
+    let model = Model {
+        source,
+        scale,
+        ..default()
+    };
+    events.request.change_mode.send(ChangeMode::To(
+        SelectAnchor3D::create_new_point().for_model(model).into(),
+    ));
+}
+
+pub fn resolve_asset_library_directory(mut display: ResMut<AssetLibraryDisplay>) {
+    let Some(task) = &mut display.choosing_directory else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    display.choosing_directory = None;
+
+    if let Some(dir) = result {
+        if !display.directories.contains(&dir) {
+            display.directories.push(dir);
+            display.scan();
+        }
+    }
+}
+
+pub fn load_asset_library_thumbnails(
+    mut display: ResMut<AssetLibraryDisplay>,
+    mut egui_context: ResMut<EguiContext>,
+    asset_server: Res<AssetServer>,
+) {
+    if !display.is_changed() {
+        return;
+    }
+
+    let display = display.as_mut();
+    for model in &display.models {
+        if display.thumbnails.contains_key(&model.path) {
+            continue;
+        }
+        let Some(thumbnail) = &model.thumbnail else {
+            continue;
+        };
+        let source = AssetSource::Local(thumbnail.to_string_lossy().into_owned());
+        let handle: Handle<Image> = asset_server.load(&String::from(&source));
+        let texture = egui_context.add_image(handle.clone());
+        display
+            .thumbnails
+            .insert(model.path.clone(), (handle, texture));
+    }
+}
@@ -0,0 +1,263 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{notify_error, notify_info},
+    localization::{tr, tr_args},
+    site::{Change, DefaultFile, NameInSite, SiteID},
+    widgets::{inspector::SelectionWidget, AppEvents, Icons},
+    CurrentWorkspace,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{Grid, Ui};
+use rmf_site_format::{AssetSource, DrawingMarker, ModelMarker, Pending};
+use std::path::{Path, PathBuf};
+
+/// The folder inside the project root that assets are copied into
+pub const PROJECT_ASSET_FOLDER: &str = "assets";
+
+/// An element of the site that refers to a local file outside of the project
+/// root. Its path will be saved as an absolute path, which is unlikely to
+/// exist on other computers.
+#[derive(Debug, Clone)]
+pub struct AbsoluteAssetPath {
+    pub entity: Entity,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(SystemParam)]
+pub struct AssetPathParams<'w, 's> {
+    pub sources: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static AssetSource,
+            Option<&'static NameInSite>,
+            Option<&'static DrawingMarker>,
+        ),
+        Without<Pending>,
+    >,
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub models: Query<'w, 's, (), With<ModelMarker>>,
+    pub default_files: Query<'w, 's, &'static DefaultFile>,
+    pub site_id: Query<'w, 's, &'static SiteID>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub icons: Res<'w, Icons>,
+}
+
+impl<'w, 's> AssetPathParams<'w, 's> {
+    /// The folder of the file that the current site is saved in
+    pub fn project_root(&self) -> Option<PathBuf> {
+        let root = self.current_workspace.root?;
+        let file = self.default_files.get(root).ok()?;
+        file.0.parent().map(Path::to_path_buf)
+    }
+
+    /// Find the elements of the current site whose local files are outside
+    /// of the project root
+    pub fn absolute_paths(&self, project_root: &Path) -> Vec<AbsoluteAssetPath> {
+        let mut paths: Vec<_> = self
+            .sources
+            .iter()
+            .filter(|(e, ..)| {
+                AncestorIter::new(&self.parents, *e).any(|p| Some(p) == self.current_workspace.root)
+            })
+            // The meshes of SDF models are found through the model file
+            .filter(|(e, ..)| {
+                !AncestorIter::new(&self.parents, *e).any(|p| self.models.contains(p))
+            })
+            .filter_map(|(e, source, name, drawing)| {
+                let AssetSource::Local(path) = source else {
+                    return None;
+                };
+                let path = PathBuf::from(path);
+                if !path.is_absolute() || path.starts_with(project_root) {
+                    return None;
+                }
+                let name = match (name, drawing) {
+                    (Some(name), _) => name.0.clone(),
+                    (None, Some(_)) => "Drawing".to_owned(),
+                    (None, None) => format!("{e:?}"),
+                };
+                Some(AbsoluteAssetPath {
+                    entity: e,
+                    name,
+                    path,
+                })
+            })
+            .collect();
+        paths.sort_by_key(|p| p.entity);
+        paths
+    }
+}
+
+/// Find a name in a folder that is not taken yet, starting with the name of
+/// the original file
+fn unused_destination(folder: &Path, name: &str) -> PathBuf {
+    let destination = folder.join(name);
+    if !destination.exists() {
+        return destination;
+    }
+    let (stem, extension) = match name.split_once('.') {
+        Some((stem, extension)) => (stem, format!(".{extension}")),
+        None => (name, String::new()),
+    };
+    (2..)
+        .map(|n| folder.join(format!("{stem}_{n}{extension}")))
+        .find(|destination| !destination.exists())
+        .unwrap_or(destination)
+}
+
+fn copy_folder(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let destination = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_folder(&entry.path(), &destination)?;
+        } else {
+            std::fs::copy(entry.path(), destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy an asset into the asset folder of the project and return the path of
+/// the copy. SDF models refer to their meshes relative to the model file, so
+/// the whole folder of an SDF model is copied.
+pub fn copy_into_project(path: &Path, project_root: &Path) -> std::io::Result<PathBuf> {
+    let folder = project_root.join(PROJECT_ASSET_FOLDER);
+    std::fs::create_dir_all(&folder)?;
+    let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid file name");
+
+    let is_sdf = path.extension().map_or(false, |ext| ext == "sdf");
+    if is_sdf {
+        let model_folder = path.parent().ok_or_else(invalid)?;
+        let folder_name = model_folder.file_name().ok_or_else(invalid)?;
+        let destination = unused_destination(&folder, &folder_name.to_string_lossy());
+        copy_folder(model_folder, &destination)?;
+        return Ok(destination.join(path.file_name().ok_or_else(invalid)?));
+    }
+
+    let name = path.file_name().ok_or_else(invalid)?;
+    let destination = unused_destination(&folder, &name.to_string_lossy());
+    std::fs::copy(path, &destination)?;
+    Ok(destination)
+}
+
+pub struct ViewAssetPaths<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a AssetPathParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewAssetPaths<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(params: &'a AssetPathParams<'w1, 's1>, events: &'a mut AppEvents<'w2, 's2>) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let Some(project_root) = self.params.project_root() else {
+            ui.label(tr(
+                ui,
+                "Save the site to a file to give it a project root. Assets inside the \
+                folder of the site file are saved relative to it.",
+            ));
+            return;
+        };
+        ui.label(tr_args(
+            ui,
+            "Project root: {root}",
+            &[("root", project_root.display().to_string())],
+        ));
+
+        let paths = self.params.absolute_paths(&project_root);
+        if paths.is_empty() {
+            ui.label(tr(ui, "Every local asset is inside the project root"));
+            return;
+        }
+        ui.label(tr(
+            ui,
+            "These assets are outside of the project root, so they will be saved \
+            with absolute paths that may not exist on other computers.",
+        ));
+
+        let mut copy = Vec::new();
+        if ui
+            .button(tr(ui, "Copy All Into Project"))
+            .on_hover_text(tr_args(
+                ui,
+                "Copy every asset into the {folder} folder of the project",
+                &[("folder", PROJECT_ASSET_FOLDER.to_owned())],
+            ))
+            .clicked()
+        {
+            copy = paths.clone();
+        }
+
+        Grid::new("asset_paths").show(ui, |ui| {
+            for p in &paths {
+                SelectionWidget::new(
+                    p.entity,
+                    self.params.site_id.get(p.entity).ok().copied(),
+                    self.params.icons.as_ref(),
+                    self.events,
+                )
+                .show(ui);
+                ui.label(&p.name).on_hover_text(p.path.to_string_lossy());
+                if ui
+                    .button(tr(ui, "Copy Into Project"))
+                    .on_hover_text(tr_args(
+                        ui,
+                        "Copy the asset into the {folder} folder of the project",
+                        &[("folder", PROJECT_ASSET_FOLDER.to_owned())],
+                    ))
+                    .clicked()
+                {
+                    copy.push(p.clone());
+                }
+                ui.end_row();
+            }
+        });
+
+        for p in copy {
+            match copy_into_project(&p.path, &project_root) {
+                Ok(copied) => {
+                    notify_info(
+                        Some(p.entity),
+                        format!("Copied {} to {}", p.path.display(), copied.display()),
+                    );
+                    let source = AssetSource::Local(copied.to_string_lossy().into_owned());
+                    self.events
+                        .change
+                        .asset_source
+                        .send(Change::new(source, p.entity));
+                }
+                Err(err) => {
+                    notify_error(
+                        Some(p.entity),
+                        format!(
+                            "Unable to copy {} into the project: {err}",
+                            p.path.display()
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{ConsoleLog, Severity},
+    icons::Icons,
+    localization::tr,
+    site::SiteID,
+    widgets::{inspector::SelectionWidget, AppEvents},
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{RichText, ScrollArea, Ui};
+
+#[derive(Resource)]
+pub struct ConsoleDisplay {
+    pub show_info: bool,
+    pub show_warnings: bool,
+    pub show_errors: bool,
+}
+
+impl Default for ConsoleDisplay {
+    fn default() -> Self {
+        Self {
+            show_info: true,
+            show_warnings: true,
+            show_errors: true,
+        }
+    }
+}
+
+impl ConsoleDisplay {
+    pub fn shows(&self, severity: Severity) -> bool {
+        match severity {
+            Severity::Info => self.show_info,
+            Severity::Warning => self.show_warnings,
+            Severity::Error => self.show_errors,
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct ConsoleParams<'w, 's> {
+    pub log: ResMut<'w, ConsoleLog>,
+    pub display: ResMut<'w, ConsoleDisplay>,
+    pub site_id: Query<'w, 's, &'static SiteID>,
+    pub icons: Res<'w, Icons>,
+}
+
+pub struct ViewConsole<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a mut ConsoleParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewConsole<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a mut ConsoleParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            // Only assign when toggled so the display is not marked as
+            // changed every frame
+            for severity in Severity::ALL {
+                let mut shown = self.params.display.shows(severity);
+                let text = format!("{} ({})", severity.label(), self.params.log.count(severity));
+                if ui.checkbox(&mut shown, text).changed() {
+                    let display = &mut self.params.display;
+                    match severity {
+                        Severity::Info => display.show_info = shown,
+                        Severity::Warning => display.show_warnings = shown,
+                        Severity::Error => display.show_errors = shown,
+                    }
+                }
+            }
+            if ui.button(tr(ui, "Clear")).clicked() {
+                self.params.log.entries.clear();
+            }
+        });
+        ui.separator();
+
+        ScrollArea::vertical()
+            .auto_shrink([false, true])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for entry in &self.params.log.entries {
+                    if !self.params.display.shows(entry.severity) {
+                        continue;
+                    }
+                    ui.horizontal(|ui| {
+                        if let Some(e) = entry.entity {
+                            SelectionWidget::new(
+                                e,
+                                self.params.site_id.get(e).ok().copied(),
+                                self.params.icons.as_ref(),
+                                self.events,
+                            )
+                            .show(ui);
+                        }
+                        let color = match entry.severity {
+                            Severity::Info => ui.visuals().weak_text_color(),
+                            Severity::Warning => ui.visuals().warn_fg_color,
+                            Severity::Error => ui.visuals().error_fg_color,
+                        };
+                        let mut text = entry.message.clone();
+                        if entry.repeats > 0 {
+                            text = format!("{text} (x{})", entry.repeats + 1);
+                        }
+                        ui.label(RichText::new(text).color(color));
+                    });
+                }
+            });
+    }
+}
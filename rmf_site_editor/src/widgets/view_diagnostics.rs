@@ -0,0 +1,89 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    icons::Icons,
+    localization::tr,
+    site::{SiteDiagnostics, SiteID, ValidateSite},
+    widgets::{inspector::SelectionWidget, AppEvents},
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::Ui;
+
+#[derive(SystemParam)]
+pub struct DiagnosticParams<'w, 's> {
+    pub diagnostics: Res<'w, SiteDiagnostics>,
+    pub site_id: Query<'w, 's, &'static SiteID>,
+    pub icons: Res<'w, Icons>,
+}
+
+pub struct ViewDiagnostics<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a DiagnosticParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewDiagnostics<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a DiagnosticParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        if let Some(site) = self.events.request.current_workspace.root {
+            if ui
+                .button(tr(ui, "Validate"))
+                .on_hover_text(tr(ui, "Check the site for problems that would affect RMF"))
+                .clicked()
+            {
+                self.events
+                    .site_request
+                    .validate_site
+                    .send(ValidateSite(site));
+            }
+        }
+
+        if self.params.diagnostics.count() == 0 {
+            ui.label(tr(ui, "No issues found"));
+            return;
+        }
+
+        for (topic, issues) in &self.params.diagnostics.issues {
+            if issues.is_empty() {
+                continue;
+            }
+
+            ui.separator();
+            ui.heading(*topic);
+            for issue in issues {
+                ui.horizontal(|ui| {
+                    if let Some(e) = issue.entity {
+                        SelectionWidget::new(
+                            e,
+                            self.params.site_id.get(e).ok().copied(),
+                            self.params.icons.as_ref(),
+                            self.events,
+                        )
+                        .show(ui);
+                    }
+                    ui.label(&issue.message);
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{localization::tr, site::Change, widgets::AppEvents, CurrentWorkspace};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_egui::egui::{self, Grid};
+use rmf_site_format::{Anchor, JointProperties, NameInWorkcell};
+
+#[derive(Resource, Default)]
+pub struct DuplicateNamesDisplay {
+    /// Whether the user closed the dialog. It will open again if more names
+    /// are duplicated.
+    pub dismissed: bool,
+    /// How many duplicated names there were the last time the dialog was shown
+    pub count: usize,
+    /// New names that the user has typed in for each element
+    pub names: HashMap<Entity, String>,
+}
+
+#[derive(SystemParam)]
+pub struct DuplicateNameParams<'w, 's> {
+    pub frames: Query<'w, 's, (Entity, &'static NameInWorkcell), With<Anchor>>,
+    pub joints: Query<'w, 's, (Entity, &'static NameInWorkcell), With<JointProperties>>,
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+}
+
+/// Find the elements whose names are already used by another element, along
+/// with a unique name that each of them could be renamed to. The first
+/// element with each name keeps it.
+pub fn find_duplicate_names<'a>(
+    elements: impl Iterator<Item = (Entity, &'a NameInWorkcell)>,
+) -> Vec<(Entity, String, String)> {
+    let mut elements: Vec<_> = elements.collect();
+    elements.sort_by_key(|(e, _)| *e);
+    let mut used: HashSet<String> = elements.iter().map(|(_, name)| name.0.clone()).collect();
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    for (e, name) in elements {
+        if seen.insert(name.0.clone()) {
+            continue;
+        }
+        let suggestion = (1..)
+            .map(|n| format!("{}_{n}", name.0))
+            .find(|candidate| !used.contains(candidate))
+            .unwrap();
+        used.insert(suggestion.clone());
+        duplicates.push((e, name.0.clone(), suggestion));
+    }
+    duplicates
+}
+
+pub struct ViewDuplicateNames<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a DuplicateNameParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewDuplicateNames<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a DuplicateNameParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ctx: &egui::Context) {
+        let Some(root) = self.params.current_workspace.root else {
+            return;
+        };
+        let in_workcell =
+            |e: &Entity| AncestorIter::new(&self.params.parents, *e).any(|p| p == root);
+        // Links and joints are named separately in urdf, so a frame may share
+        // its name with a joint
+        let mut duplicates =
+            find_duplicate_names(self.params.frames.iter().filter(|(e, _)| in_workcell(e)));
+        duplicates.extend(find_duplicate_names(
+            self.params.joints.iter().filter(|(e, _)| in_workcell(e)),
+        ));
+
+        let display = &mut self.events.display.duplicate_names;
+        if duplicates.len() > display.count {
+            display.dismissed = false;
+        }
+        display.count = duplicates.len();
+        if duplicates.is_empty() || display.dismissed {
+            return;
+        }
+
+        let mut changes = Vec::new();
+        let mut open = true;
+        egui::Window::new("Duplicate Names")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(tr(
+                    ui,
+                    "These names are already used in the workcell, which will break urdf exports.",
+                ));
+                ui.separator();
+                Grid::new("duplicate_names").show(ui, |ui| {
+                    for (e, name, suggestion) in &duplicates {
+                        ui.label(name);
+                        let new_name = display
+                            .names
+                            .entry(*e)
+                            .or_insert_with(|| suggestion.clone());
+                        ui.text_edit_singleline(new_name);
+                        if ui.button(tr(ui, "Rename")).clicked() {
+                            changes.push((*e, new_name.clone()));
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.separator();
+                if ui.button(tr(ui, "Rename All")).clicked() {
+                    for (e, _, suggestion) in &duplicates {
+                        let new_name = display.names.get(e).unwrap_or(suggestion);
+                        changes.push((*e, new_name.clone()));
+                    }
+                }
+            });
+
+        if !open {
+            display.dismissed = true;
+        }
+        for (e, _) in &changes {
+            display.names.remove(e);
+        }
+        for (e, name) in changes {
+            self.events
+                .workcell_change
+                .name_in_workcell
+                .send(Change::new(NameInWorkcell(name), e));
+        }
+    }
+}
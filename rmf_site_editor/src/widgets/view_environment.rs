@@ -0,0 +1,206 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{
+    console::notify_warning,
+    localization::tr,
+    site::{AssetSource, Change, Environment, SiteProperties, Sky, Sun},
+    widgets::{inspector::InspectAngle, AppEvents},
+    CurrentWorkspace,
+};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use bevy_egui::egui::{ComboBox, DragValue, Ui};
+use futures_lite::future;
+
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::AsyncFileDialog;
+
+#[derive(Resource, Default)]
+pub struct EnvironmentDisplay {
+    pub choosing_hdri: Option<Task<Option<String>>>,
+}
+
+#[derive(SystemParam)]
+pub struct EnvironmentParams<'w, 's> {
+    pub environments: Query<'w, 's, &'static Environment, With<SiteProperties>>,
+}
+
+pub struct ViewEnvironment<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a EnvironmentParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewEnvironment<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a EnvironmentParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let Some(site) = self.events.request.current_workspace.root else {
+            return;
+        };
+        let environment = self
+            .params
+            .environments
+            .get(site)
+            .cloned()
+            .unwrap_or_default();
+        let mut new_environment = environment.clone();
+
+        let mut has_sun = new_environment.sun.is_some();
+        if ui.checkbox(&mut has_sun, tr(ui, "Sun")).changed() {
+            new_environment.sun = has_sun.then(Sun::default);
+        }
+        if let Some(sun) = &mut new_environment.sun {
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Azimuth"));
+                InspectAngle::new(&mut sun.azimuth).show(ui);
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Elevation"));
+                InspectAngle::new(&mut sun.elevation)
+                    .range_degrees(0.0..=90.0)
+                    .show(ui);
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Illuminance"));
+                ui.add(
+                    DragValue::new(&mut sun.illuminance)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .speed(100.0)
+                        .suffix(" lux"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Color"));
+                ui.color_edit_button_rgb(&mut sun.color);
+            });
+            ui.checkbox(&mut sun.shadows, tr(ui, "Cast shadows"));
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Sky"));
+            ComboBox::from_id_source("environment_sky")
+                .selected_text(new_environment.sky.label())
+                .show_ui(ui, |ui| {
+                    for variant in [Sky::default(), Sky::procedural(), Sky::hdri()] {
+                        let selected = variant.label() == new_environment.sky.label();
+                        if ui.selectable_label(selected, variant.label()).clicked() && !selected {
+                            new_environment.sky = variant;
+                        }
+                    }
+                });
+        });
+
+        match &mut new_environment.sky {
+            Sky::Solid { color } => {
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Color"));
+                    ui.color_edit_button_rgb(color);
+                });
+            }
+            Sky::Procedural {
+                zenith,
+                horizon,
+                ground,
+            } => {
+                for (label, color) in [("Zenith", zenith), ("Horizon", horizon), ("Ground", ground)]
+                {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        ui.color_edit_button_rgb(color);
+                    });
+                }
+            }
+            Sky::Hdri { source } => {
+                match source {
+                    AssetSource::Local(path) => {
+                        ui.horizontal(|ui| {
+                            ui.label(tr(ui, "File"));
+                            ui.text_edit_singleline(path);
+                        });
+                    }
+                    other => {
+                        ui.label(String::from(&*other));
+                    }
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    if ui
+                        .button(tr(ui, "Browse..."))
+                        .on_hover_text(tr(ui, "Choose an equirectangular panorama image"))
+                        .clicked()
+                    {
+                        let display = &mut self.events.display.environment;
+                        if display.choosing_hdri.is_some() {
+                            notify_warning(None, "A file is already being chosen!");
+                        } else {
+                            let future = AsyncComputeTaskPool::get().spawn(async move {
+                                let file = AsyncFileDialog::new()
+                                    .add_filter("Images", &["hdr", "png", "jpg", "jpeg"])
+                                    .pick_file()
+                                    .await?;
+                                Some(file.path().to_string_lossy().into_owned())
+                            });
+                            display.choosing_hdri = Some(future);
+                        }
+                    }
+                }
+            }
+        }
+
+        if new_environment != environment {
+            self.events
+                .site_change
+                .environment
+                .send(Change::new(new_environment, site).or_insert());
+        }
+    }
+}
+
+pub fn resolve_environment_hdri_file(
+    mut display: ResMut<EnvironmentDisplay>,
+    mut change: EventWriter<Change<Environment>>,
+    current_workspace: Res<CurrentWorkspace>,
+    open_sites: Query<Entity, With<SiteProperties>>,
+    environments: Query<&Environment>,
+) {
+    let Some(task) = &mut display.choosing_hdri else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    display.choosing_hdri = None;
+
+    let (Some(path), Some(site)) = (result, current_workspace.to_site(&open_sites)) else {
+        return;
+    };
+    let mut environment = environments.get(site).cloned().unwrap_or_default();
+    environment.sky = Sky::Hdri {
+        source: AssetSource::Local(path),
+    };
+    change.send(Change::new(environment, site).or_insert());
+}
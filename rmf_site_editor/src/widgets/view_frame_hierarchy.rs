@@ -0,0 +1,227 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::{Hover, Select, Selection},
+    localization::tr,
+    site::Change,
+    widgets::AppEvents,
+    workcell::ChangeParent,
+    CurrentWorkspace,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{collapsing_header::CollapsingState, Key, SelectableLabel, Sense, Ui};
+use rmf_site_format::{
+    Anchor, JointProperties, NameInWorkcell, SensorProperties, WorkcellCollisionMarker,
+    WorkcellProperties, WorkcellVisualMarker,
+};
+
+#[derive(Resource, Default)]
+pub struct FrameHierarchyDisplay {
+    /// The element that is being dragged onto a new parent
+    pub dragging: Option<Entity>,
+    /// The element that is being renamed and the name that is being typed
+    pub renaming: Option<(Entity, String)>,
+    /// Whether the name field still needs to take focus
+    pub focus_rename: bool,
+    /// The selection that the tree was last opened up to show
+    pub revealed: Option<Entity>,
+}
+
+#[derive(SystemParam)]
+pub struct FrameHierarchyParams<'w, 's> {
+    pub elements: Query<
+        'w,
+        's,
+        (
+            Option<&'static NameInWorkcell>,
+            Option<&'static Children>,
+            Option<&'static Anchor>,
+            Option<&'static WorkcellVisualMarker>,
+            Option<&'static WorkcellCollisionMarker>,
+            Option<&'static JointProperties>,
+        ),
+    >,
+    pub workcells: Query<'w, 's, (), With<WorkcellProperties>>,
+    pub sensors: Query<'w, 's, (), With<SensorProperties>>,
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub selection: Res<'w, Selection>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub display: ResMut<'w, FrameHierarchyDisplay>,
+}
+
+impl<'w, 's> FrameHierarchyParams<'w, 's> {
+    /// Whether an entity should be shown in the tree. This leaves out the
+    /// meshes that are only there to visualize the workcell.
+    fn is_element(&self, e: Entity) -> bool {
+        if self.workcells.contains(e) || self.sensors.contains(e) {
+            return true;
+        }
+        match self.elements.get(e) {
+            Ok((_, _, anchor, visual, collision, joint)) => {
+                anchor.is_some() || visual.is_some() || collision.is_some() || joint.is_some()
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+pub struct ViewFrameHierarchy<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a mut FrameHierarchyParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewFrameHierarchy<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a mut FrameHierarchyParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(mut self, ui: &mut Ui) {
+        let Some(root) = self
+            .params
+            .current_workspace
+            .root
+            .filter(|root| self.params.workcells.contains(*root))
+        else {
+            ui.label(tr(ui, "No workcell is open"));
+            return;
+        };
+
+        // Open up the tree to show an element that was selected in the 3D view
+        let selected = self.params.selection.0;
+        let reveal = if selected != self.params.display.revealed {
+            self.params.display.revealed = selected;
+            selected
+        } else {
+            None
+        };
+
+        self.show_element(root, true, reveal, ui);
+
+        if self.params.display.dragging.is_some() {
+            ui.label(tr(ui, "Drop onto a frame to attach to it"));
+            if ui.input().pointer.any_released() {
+                self.params.display.dragging = None;
+            }
+        }
+    }
+
+    fn show_element(&mut self, e: Entity, is_root: bool, reveal: Option<Entity>, ui: &mut Ui) {
+        let Ok((name, children, anchor, visual, collision, joint)) = self.params.elements.get(e)
+        else {
+            return;
+        };
+        let label = match name {
+            Some(name) => name.0.clone(),
+            None if anchor.is_some() => format!("Frame {e:?}"),
+            None if visual.is_some() => format!("Visual {e:?}"),
+            None if collision.is_some() => format!("Collision {e:?}"),
+            None if joint.is_some() => format!("Joint {e:?}"),
+            None if self.params.sensors.contains(e) => format!("Sensor {e:?}"),
+            None => format!("{e:?}"),
+        };
+        let children: Vec<Entity> = children
+            .map(|c| {
+                c.iter()
+                    .copied()
+                    .filter(|c| self.params.is_element(*c))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if children.is_empty() {
+            self.show_row(e, label, is_root, ui);
+            return;
+        }
+
+        let id = ui.make_persistent_id(("frame_hierarchy", e));
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        if let Some(reveal) = reveal {
+            if AncestorIter::new(&self.params.parents, reveal).any(|p| p == e) {
+                state.set_open(true);
+            }
+        }
+        state
+            .show_header(ui, |ui| self.show_row(e, label, is_root, ui))
+            .body(|ui| {
+                for child in children {
+                    self.show_element(child, false, reveal, ui);
+                }
+            });
+    }
+
+    fn show_row(&mut self, e: Entity, label: String, is_root: bool, ui: &mut Ui) {
+        let display = self.params.display.as_mut();
+        if let Some((_, new_name)) = display.renaming.as_mut().filter(|(r, _)| *r == e) {
+            let response = ui.text_edit_singleline(new_name);
+            if display.focus_rename {
+                response.request_focus();
+                display.focus_rename = false;
+            }
+            if response.lost_focus() {
+                if !ui.input().key_pressed(Key::Escape) && !new_name.is_empty() {
+                    self.events
+                        .workcell_change
+                        .name_in_workcell
+                        .send(Change::new(NameInWorkcell(new_name.clone()), e).or_insert());
+                }
+                display.renaming = None;
+            }
+            return;
+        }
+
+        let selected = self.params.selection.0 == Some(e);
+        let response = ui
+            .add(SelectableLabel::new(selected, &label))
+            .interact(Sense::drag());
+        if response.clicked() {
+            self.events.request.select.send(Select(Some(e)));
+        } else if response.hovered() {
+            self.events.request.hover.send(Hover(Some(e)));
+        }
+
+        // The workcell itself cannot be renamed or moved
+        if !is_root {
+            if response.double_clicked() {
+                display.renaming = Some((e, label));
+                display.focus_rename = true;
+            }
+            if response.drag_started() {
+                display.dragging = Some(e);
+            }
+        }
+
+        if let Some(dragging) = display.dragging.filter(|d| *d != e) {
+            if ui.rect_contains_pointer(response.rect) {
+                ui.painter()
+                    .rect_stroke(response.rect, 2.0, ui.visuals().selection.stroke);
+                if ui.input().pointer.any_released() {
+                    self.events
+                        .workcell_change
+                        .change_parent
+                        .send(ChangeParent {
+                            child: dragging,
+                            parent: e,
+                        });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::Selection, localization::tr, site::SiteID, CurrentWorkspace, DisplayUnits,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{Button, ComboBox, Grid, Ui};
+use rmf_site_format::{Anchor, NameInWorkcell};
+
+#[derive(Resource, Default)]
+pub struct FrameMeasurementDisplay {
+    /// The frame that the measurement is expressed in
+    pub from: Option<Entity>,
+    /// The frame that is being measured
+    pub to: Option<Entity>,
+}
+
+#[derive(SystemParam)]
+pub struct FrameMeasurementParams<'w, 's> {
+    pub frames: Query<
+        'w,
+        's,
+        (
+            Entity,
+            Option<&'static NameInWorkcell>,
+            Option<&'static SiteID>,
+            &'static GlobalTransform,
+        ),
+        With<Anchor>,
+    >,
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub selection: Res<'w, Selection>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub display: ResMut<'w, FrameMeasurementDisplay>,
+}
+
+impl<'w, 's> FrameMeasurementParams<'w, 's> {
+    fn frame_label(&self, e: Entity) -> String {
+        match self.frames.get(e) {
+            Ok((_, Some(name), ..)) => name.0.clone(),
+            Ok((_, None, Some(site_id), _)) => format!("Frame #{}", site_id.0),
+            _ => "<none>".to_owned(),
+        }
+    }
+}
+
+pub struct ViewFrameMeasurement<'a, 'w, 's> {
+    params: &'a mut FrameMeasurementParams<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewFrameMeasurement<'a, 'w, 's> {
+    pub fn new(params: &'a mut FrameMeasurementParams<'w, 's>) -> Self {
+        Self { params }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let Some(root) = self.params.current_workspace.root else {
+            return;
+        };
+        let mut frames: Vec<(Entity, String)> = self
+            .params
+            .frames
+            .iter()
+            .filter(|(e, ..)| AncestorIter::new(&self.params.parents, *e).any(|p| p == root))
+            .map(|(e, ..)| (e, self.params.frame_label(e)))
+            .collect();
+        if frames.len() < 2 {
+            ui.label(tr(ui, "At least two frames are needed for a measurement"));
+            return;
+        }
+        frames.sort_by(|a, b| a.1.cmp(&b.1));
+
+        // Forget frames that have been deleted
+        let display = &self.params.display;
+        let from = display.from.filter(|e| self.params.frames.contains(*e));
+        let to = display.to.filter(|e| self.params.frames.contains(*e));
+        let selected_frame = self
+            .params
+            .selection
+            .0
+            .filter(|e| self.params.frames.contains(*e));
+
+        let mut new_from = from;
+        let mut new_to = to;
+        Grid::new("frame_measurement_frames").show(ui, |ui| {
+            for (label, choice) in [("From", &mut new_from), ("To", &mut new_to)] {
+                ui.label(label);
+                let selected_text = choice
+                    .map(|e| self.params.frame_label(e))
+                    .unwrap_or_else(|| "<none>".to_owned());
+                ComboBox::from_id_source(("frame_measurement", label))
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for (e, name) in &frames {
+                            ui.selectable_value(choice, Some(*e), name);
+                        }
+                    });
+                if ui
+                    .add_enabled(
+                        selected_frame.is_some(),
+                        Button::new(tr(ui, "Use Selected")),
+                    )
+                    .clicked()
+                {
+                    *choice = selected_frame;
+                }
+                ui.end_row();
+            }
+        });
+
+        if new_from != display.from || new_to != display.to {
+            self.params.display.from = new_from;
+            self.params.display.to = new_to;
+        }
+
+        let (Some(from), Some(to)) = (new_from, new_to) else {
+            ui.label(tr(ui, "Choose two frames to measure between"));
+            return;
+        };
+        let (Ok((.., from_tf)), Ok((.., to_tf))) =
+            (self.params.frames.get(from), self.params.frames.get(to))
+        else {
+            return;
+        };
+
+        // The pose of the second frame expressed in the first frame. This is
+        // recalculated every time the panel is drawn, so it follows either
+        // frame as it moves.
+        let relative = from_tf.affine().inverse() * to_tf.affine();
+        let (_, rotation, translation) = relative.to_scale_rotation_translation();
+        let (yaw, pitch, roll) = rotation.to_euler(EulerRot::ZYX);
+
+        let units = DisplayUnits::of(ui);
+        ui.separator();
+        Grid::new("frame_measurement_result").show(ui, |ui| {
+            ui.label("");
+            ui.label("x");
+            ui.label("y");
+            ui.label("z");
+            ui.end_row();
+
+            ui.label(tr(ui, "Translation"));
+            for value in translation.to_array() {
+                ui.label(units.length.format(value, 4));
+            }
+            ui.end_row();
+
+            ui.label(tr(ui, "Rotation"));
+            for value in [roll, pitch, yaw] {
+                ui.label(units.angle.format(value));
+            }
+            ui.end_row();
+        });
+        ui.label(format!(
+            "Distance: {}",
+            units.length.format(translation.length(), 4)
+        ));
+        ui.label(format!(
+            "Angle: {}",
+            units.angle.format(rotation.to_axis_angle().1)
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{notify_error, notify_warning},
+    localization::tr,
+    site_asset_io::{is_offline_mode, FUEL_BASE_URI},
+    widgets::{view_asset_library::place_model, AppEvents},
+};
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+};
+use bevy_egui::{
+    egui::{self, Button, Key, TextureId, Ui},
+    EguiContext,
+};
+use futures_lite::future;
+use rmf_site_format::AssetSource;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// How many models are requested from Fuel for each page of results
+pub const FUEL_MODELS_PER_PAGE: usize = 20;
+/// Size of the model thumbnails in the browser
+pub const FUEL_THUMBNAIL_SIZE: f32 = 64.0;
+
+/// A model description as reported by the Fuel REST API
+#[derive(Deserialize, Debug, Clone)]
+pub struct FuelModel {
+    pub name: String,
+    pub owner: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+}
+
+impl FuelModel {
+    /// The path of this model on Fuel, in the form of OwnerName/ModelName
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+
+    /// The asset source that will load this model from Fuel. Every model on
+    /// Fuel is described by a model.sdf file at the root of its files.
+    pub fn source(&self) -> AssetSource {
+        AssetSource::Remote(format!("{}/model.sdf", self.path()))
+    }
+
+    /// The asset source of the thumbnail of this model, if it has one
+    pub fn thumbnail(&self) -> Option<AssetSource> {
+        let url = self.thumbnail_url.as_ref()?;
+        let (_, file) = url.split_once("/files/")?;
+        Some(AssetSource::Remote(format!("{}/{}", self.path(), file)))
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct FuelModelsDisplay {
+    pub search: String,
+    /// The page of search results being shown, starting from 1
+    pub page: usize,
+    pub models: Vec<FuelModel>,
+    pub fetching: Option<Task<Result<Vec<FuelModel>, String>>>,
+    /// Thumbnails that have been registered with egui, keyed by the path of
+    /// the model
+    pub thumbnails: HashMap<String, (Handle<Image>, TextureId)>,
+}
+
+impl FuelModelsDisplay {
+    /// Request a page of the models on Fuel that match the search
+    pub fn fetch(&mut self, page: usize) {
+        if is_offline_mode() {
+            notify_warning(None, "Unable to search Fuel while offline mode is on");
+            return;
+        }
+        let search = self.search.clone();
+        self.page = page;
+        let future = IoTaskPool::get().spawn(async move {
+            let url = surf::Url::parse_with_params(
+                &format!("{FUEL_BASE_URI}/models"),
+                &[
+                    ("q", search),
+                    ("page", page.to_string()),
+                    ("per_page", FUEL_MODELS_PER_PAGE.to_string()),
+                ],
+            )
+            .map_err(|err| err.to_string())?;
+            let bytes = surf::get(url)
+                .recv_bytes()
+                .await
+                .map_err(|err| err.to_string())?;
+            serde_json::from_slice::<Vec<FuelModel>>(&bytes).map_err(|err| err.to_string())
+        });
+        self.fetching = Some(future);
+    }
+}
+
+pub struct ViewFuelModels<'a, 'w, 's> {
+    events: &'a mut AppEvents<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewFuelModels<'a, 'w, 's> {
+    pub fn new(events: &'a mut AppEvents<'w, 's>) -> Self {
+        Self { events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let display = &mut self.events.display.assets.fuel_models;
+        ui.horizontal(|ui| {
+            let response = ui.text_edit_singleline(&mut display.search);
+            let submitted = response.lost_focus() && ui.input().key_pressed(Key::Enter);
+            if ui.button(tr(ui, "Search")).clicked() || submitted {
+                display.fetch(1);
+            }
+        });
+
+        if display.fetching.is_some() {
+            ui.label(tr(ui, "Searching Fuel..."));
+            return;
+        }
+
+        let page = display.page;
+        if page > 0 {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(page > 1, Button::new("<")).clicked() {
+                    display.fetch(page - 1);
+                }
+                ui.label(format!("Page {}", page));
+                if ui
+                    .add_enabled(
+                        display.models.len() == FUEL_MODELS_PER_PAGE,
+                        Button::new(">"),
+                    )
+                    .clicked()
+                {
+                    display.fetch(page + 1);
+                }
+            });
+        }
+
+        let mut chosen = None;
+        for model in &display.models {
+            ui.horizontal(|ui| {
+                if let Some((_, texture)) = display.thumbnails.get(&model.path()) {
+                    ui.add(egui::Image::new(
+                        *texture,
+                        [FUEL_THUMBNAIL_SIZE, FUEL_THUMBNAIL_SIZE],
+                    ));
+                }
+                ui.vertical(|ui| {
+                    ui.label(format!("{}/{}", model.owner, model.name));
+                    if !model.tags.is_empty() {
+                        ui.small(model.tags.join(", "));
+                    }
+                    if ui
+                        .button(tr(ui, "Insert"))
+                        .on_hover_text(
+                            model
+                                .description
+                                .clone()
+                                .unwrap_or_else(|| "Place this model in the site".to_owned()),
+                        )
+                        .clicked()
+                    {
+                        chosen = Some(model.source());
+                    }
+                });
+            });
+        }
+
+        if let Some(source) = chosen {
+            place_model(self.events, source);
+        }
+    }
+}
+
+pub fn resolve_fuel_model_search(mut display: ResMut<FuelModelsDisplay>) {
+    let Some(task) = &mut display.fetching else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    display.fetching = None;
+
+    match result {
+        Ok(models) => {
+            display.models = models;
+        }
+        Err(err) => {
+            notify_error(None, format!("Unable to search for models on Fuel: {err}"));
+            display.models.clear();
+        }
+    }
+}
+
+pub fn load_fuel_model_thumbnails(
+    mut display: ResMut<FuelModelsDisplay>,
+    mut egui_context: ResMut<EguiContext>,
+    asset_server: Res<AssetServer>,
+) {
+    if !display.is_changed() {
+        return;
+    }
+
+    let display = display.as_mut();
+    for model in &display.models {
+        let path = model.path();
+        if display.thumbnails.contains_key(&path) {
+            continue;
+        }
+        let Some(thumbnail) = model.thumbnail() else {
+            continue;
+        };
+        let handle: Handle<Image> = asset_server.load(&String::from(&thumbnail));
+        let texture = egui_context.add_image(handle.clone());
+        display.thumbnails.insert(path, (handle, texture));
+    }
+}
@@ -0,0 +1,134 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::{Select, Selection},
+    localization::tr,
+    widgets::AppEvents,
+    workcell::{joint_range, JointPosition, ReachEnvelopeRequest},
+    CurrentWorkspace,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{Button, SelectableLabel, Slider, Ui};
+use rmf_site_format::{Anchor, JointProperties, NameInWorkcell};
+
+#[derive(SystemParam)]
+pub struct JointSliderParams<'w, 's> {
+    pub joints: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static NameInWorkcell,
+            &'static JointProperties,
+            Option<&'static JointPosition>,
+        ),
+    >,
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub selection: Res<'w, Selection>,
+    pub anchors: Query<'w, 's, &'static Anchor>,
+    pub reach: EventWriter<'w, 's, ReachEnvelopeRequest>,
+}
+
+pub struct ViewJointSliders<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a mut JointSliderParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewJointSliders<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a mut JointSliderParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let Some(root) = self.params.current_workspace.root else {
+            return;
+        };
+        let mut joints: Vec<_> = self
+            .params
+            .joints
+            .iter()
+            .filter(|(e, ..)| AncestorIter::new(&self.params.parents, *e).any(|p| p == root))
+            .filter_map(|(e, name, properties, position)| {
+                let range = joint_range(properties)?;
+                Some((e, name, range, position.copied().unwrap_or_default()))
+            })
+            .collect();
+        if joints.is_empty() {
+            ui.label(tr(ui, "There are no movable joints"));
+            return;
+        }
+        joints.sort_by(|a, b| a.1 .0.cmp(&b.1 .0));
+
+        for (e, name, range, position) in &joints {
+            ui.horizontal(|ui| {
+                let selected = self.params.selection.0 == Some(*e);
+                if ui.add(SelectableLabel::new(selected, &name.0)).clicked() {
+                    self.events.request.select.send(Select(Some(*e)));
+                }
+                let mut new_position = position.0.clamp(*range.start(), *range.end());
+                ui.add(Slider::new(&mut new_position, range.clone()));
+                if new_position != position.0 {
+                    self.events
+                        .commands
+                        .entity(*e)
+                        .insert(JointPosition(new_position));
+                }
+            });
+        }
+
+        if ui
+            .button(tr(ui, "Reset"))
+            .on_hover_text(tr(ui, "Move every joint back to zero"))
+            .clicked()
+        {
+            for (e, ..) in &joints {
+                self.events.commands.entity(*e).insert(JointPosition(0.0));
+            }
+        }
+
+        ui.separator();
+        let selected_frame = self
+            .params
+            .selection
+            .0
+            .filter(|e| matches!(self.params.anchors.get(*e), Ok(Anchor::Pose3D(_))));
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(selected_frame.is_some(), Button::new(tr(ui, "Show Reach")))
+                .on_hover_text(tr(
+                    ui,
+                    "Show the positions that the selected frame can be moved to",
+                ))
+                .clicked()
+            {
+                if let Some(frame) = selected_frame {
+                    self.params
+                        .reach
+                        .send(ReachEnvelopeRequest::Compute { frame });
+                }
+            }
+            if ui.button(tr(ui, "Hide Reach")).clicked() {
+                self.params.reach.send(ReachEnvelopeRequest::Clear);
+            }
+        });
+    }
+}
@@ -0,0 +1,119 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{localization::tr, widgets::AppEvents, KeyAction, KeyCapture, Keymap};
+use bevy_egui::egui::{Grid, Ui};
+
+pub struct ViewKeymap<'a, 'w, 's> {
+    events: &'a mut AppEvents<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewKeymap<'a, 'w, 's> {
+    pub fn new(events: &'a mut AppEvents<'w, 's>) -> Self {
+        Self { events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let view = &mut self.events.display.view;
+        let mut new_keymap = view.keymap.clone();
+        let mut new_capture = view.key_capture.0;
+
+        if new_capture.is_some() {
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Press the new shortcut"));
+                if ui.button(tr(ui, "Cancel")).clicked() {
+                    new_capture = None;
+                }
+            });
+        } else {
+            ui.small(tr(ui, "Click a shortcut to change it"));
+        }
+
+        Grid::new("keymap_grid").striped(true).show(ui, |ui| {
+            for action in KeyAction::ALL {
+                ui.label(action.label());
+                ui.horizontal_wrapped(|ui| {
+                    let bindings = new_keymap.bindings_of(action).to_vec();
+                    let mut removed = None;
+                    for (index, binding) in bindings.iter().enumerate() {
+                        let capturing = new_capture == Some((action, index));
+                        let text = if capturing {
+                            "...".to_owned()
+                        } else {
+                            binding.label()
+                        };
+                        if ui
+                            .selectable_label(capturing, text)
+                            .on_hover_text(tr(ui, "Change this shortcut"))
+                            .clicked()
+                        {
+                            new_capture = Some((action, index));
+                        }
+                        if ui
+                            .small_button("❌")
+                            .on_hover_text(tr(ui, "Remove"))
+                            .clicked()
+                        {
+                            removed = Some(index);
+                        }
+                        if let Some(other) = new_keymap.conflict(action, binding) {
+                            ui.colored_label(ui.visuals().warn_fg_color, "⚠")
+                                .on_hover_text(format!("Also used by {}", other.label()));
+                        }
+                    }
+                    let adding = new_capture == Some((action, bindings.len()));
+                    if ui
+                        .selectable_label(adding, if adding { "..." } else { "+" })
+                        .on_hover_text(tr(ui, "Add a shortcut"))
+                        .clicked()
+                    {
+                        new_capture = Some((action, bindings.len()));
+                    }
+                    if bindings != action.default_bindings()
+                        && ui
+                            .small_button("↺")
+                            .on_hover_text(tr(ui, "Reset"))
+                            .clicked()
+                    {
+                        new_keymap.reset(action);
+                        new_capture = None;
+                    }
+                    if let Some(index) = removed {
+                        if let Some(bindings) = new_keymap.bindings.get_mut(&action) {
+                            bindings.remove(index);
+                        }
+                        new_capture = None;
+                    }
+                });
+                ui.end_row();
+            }
+        });
+
+        ui.separator();
+        if ui.button(tr(ui, "Reset All")).clicked() {
+            new_keymap = Keymap::default();
+            new_capture = None;
+        }
+
+        if new_keymap != *view.keymap {
+            *view.keymap = new_keymap;
+        }
+        if new_capture != view.key_capture.0 {
+            *view.key_capture = KeyCapture(new_capture);
+        }
+    }
+}
@@ -17,6 +17,7 @@
 
 use crate::{
     interaction::Selection,
+    localization::tr,
     recency::RecencyRanking,
     site::*,
     widgets::{inspector::InspectLayer, AppEvents, Icons},
@@ -51,7 +52,7 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLayers<'a, 'w1, 's1, 'w2, 's2> {
         };
 
         if let Ok(ranking) = self.params.floors.get(current_level) {
-            CollapsingHeader::new("Floors")
+            CollapsingHeader::new(tr(ui, "Floors"))
                 .default_open(true)
                 .show(ui, |ui| {
                     ui.horizontal(|ui| {
@@ -69,7 +70,7 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLayers<'a, 'w1, 's1, 'w2, 's2> {
         }
 
         if let Ok(ranking) = self.params.drawings.get(current_level) {
-            CollapsingHeader::new("Drawings")
+            CollapsingHeader::new(tr(ui, "Drawings"))
                 .default_open(true)
                 .show(ui, |ui| {
                     self.show_rankings(ranking, false, ui);
@@ -91,7 +92,7 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLayers<'a, 'w1, 's1, 'w2, 's2> {
                     layer.show(ui);
 
                     if Some(*e) == self.params.selection.0 {
-                        ui.label("Selected");
+                        ui.label(tr(ui, "Selected"));
                     }
                 });
             }
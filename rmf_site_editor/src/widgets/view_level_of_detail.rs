@@ -0,0 +1,86 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::localization::tr;
+use crate::widgets::AppEvents;
+use bevy_egui::egui::{Button, DragValue, Grid, Ui};
+
+pub struct ViewLevelOfDetail<'a, 'w, 's> {
+    events: &'a mut AppEvents<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewLevelOfDetail<'a, 'w, 's> {
+    pub fn new(events: &'a mut AppEvents<'w, 's>) -> Self {
+        Self { events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        // Edit a copy so that the models are only processed again when a
+        // setting actually changes
+        let mut settings = self.events.site_request.lod_settings.clone();
+        ui.checkbox(&mut settings.enabled, tr(ui, "Simplify distant models"));
+        Grid::new("level_of_detail_settings").show(ui, |ui| {
+            ui.label(tr(ui, "Triangle budget")).on_hover_text(tr(
+                ui,
+                "Models with more triangles than this get simpler meshes when they are far away",
+            ));
+            ui.add(DragValue::new(&mut settings.triangle_budget).speed(100));
+            ui.end_row();
+
+            let mut removed = None;
+            for (i, distance) in settings.distances.iter_mut().enumerate() {
+                ui.label(format!("Level {} from", i + 1));
+                ui.horizontal(|ui| {
+                    ui.add(
+                        DragValue::new(distance)
+                            .clamp_range(0.0..=f32::INFINITY)
+                            .suffix(" m"),
+                    );
+                    if ui
+                        .button("❌")
+                        .on_hover_text(tr(ui, "Remove this level"))
+                        .clicked()
+                    {
+                        removed = Some(i);
+                    }
+                });
+                ui.end_row();
+            }
+            if let Some(i) = removed {
+                settings.distances.remove(i);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button(tr(ui, "Add Level")).clicked() {
+                let next = settings.distances.last().map(|d| 2.0 * d).unwrap_or(25.0);
+                settings.distances.push(next);
+            }
+            let sorted = settings.distances.windows(2).all(|w| w[0] <= w[1]);
+            if ui
+                .add_enabled(!sorted, Button::new(tr(ui, "Sort")))
+                .clicked()
+            {
+                settings.distances.sort_by(|a, b| a.total_cmp(b));
+            }
+        });
+
+        if settings != *self.events.site_request.lod_settings {
+            *self.events.site_request.lod_settings = settings;
+        }
+    }
+}
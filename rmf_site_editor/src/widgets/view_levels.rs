@@ -16,11 +16,15 @@
 */
 
 use crate::{
+    localization::tr,
     site::{Category, Change, Delete, LevelProperties},
-    widgets::{AppEvents, Icons},
+    widgets::{
+        edit_filtered_name, edit_name_filter, inspector::InspectLength, matches_name_filter,
+        AppEvents, Icons,
+    },
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
-use bevy_egui::egui::{DragValue, ImageButton, Ui};
+use bevy_egui::egui::{ImageButton, Ui};
 use std::cmp::{Ordering, Reverse};
 
 #[derive(Resource)]
@@ -30,6 +34,8 @@ pub struct LevelDisplay {
     pub order: Vec<Entity>,
     pub freeze: bool,
     pub removing: bool,
+    /// Only levels whose names contain this text are listed
+    pub filter: String,
 }
 
 impl Default for LevelDisplay {
@@ -40,6 +46,7 @@ impl Default for LevelDisplay {
             order: Vec::new(),
             freeze: false,
             removing: false,
+            filter: String::new(),
         }
     }
 }
@@ -63,14 +70,15 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLevels<'a, 'w1, 's1, 'w2, 's2> {
 
     pub fn show(self, ui: &mut Ui) {
         ui.horizontal(|ui| {
-            let make_new_level = ui.button("Add").clicked();
+            let make_new_level = ui.button(tr(ui, "Add")).clicked();
             let mut show_elevation = self.events.display.level.new_elevation;
-            ui.add(DragValue::new(&mut show_elevation).suffix("m"))
-                .on_hover_text("Elevation for the new level");
+            InspectLength::new(&mut show_elevation)
+                .show(ui)
+                .on_hover_text(tr(ui, "Elevation for the new level"));
 
             let mut show_name = self.events.display.level.new_name.clone();
             ui.text_edit_singleline(&mut show_name)
-                .on_hover_text("Name for the new level");
+                .on_hover_text(tr(ui, "Name for the new level"));
 
             if make_new_level {
                 let new_level = self
@@ -119,30 +127,36 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLevels<'a, 'w1, 's1, 'w2, 's2> {
 
         if self.events.display.level.removing {
             ui.horizontal(|ui| {
-                if ui.button("Select").clicked() {
+                if ui.button(tr(ui, "Select")).clicked() {
                     self.events.display.level.removing = false;
                 }
-                ui.label("Remove");
+                ui.label(tr(ui, "Remove"));
             });
         } else {
             ui.horizontal(|ui| {
-                ui.label("Select");
-                if ui.button("Remove").clicked() {
+                ui.label(tr(ui, "Select"));
+                if ui.button(tr(ui, "Remove")).clicked() {
                     self.events.display.level.removing = true;
                 }
             });
         }
 
+        edit_name_filter(ui, &mut self.events.display.level.filter);
+        let filter = self.events.display.level.filter.clone();
+
         let mut any_dragging = false;
         let mut any_deleted = false;
         for e in self.events.display.level.order.iter().copied() {
             if let Ok((_, props)) = self.params.levels.get(e) {
+                if !matches_name_filter(&props.name, &filter) {
+                    continue;
+                }
                 let mut shown_props = props.clone();
                 ui.horizontal(|ui| {
                     if self.events.display.level.removing {
                         if ui
                             .add(ImageButton::new(self.params.icons.trash.egui(), [18., 18.]))
-                            .on_hover_text("Remove this level")
+                            .on_hover_text(tr(ui, "Remove this level"))
                             .clicked()
                         {
                             self.events
@@ -160,15 +174,15 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLevels<'a, 'w1, 's1, 'w2, 's2> {
                         }
                     }
 
-                    let r = ui
-                        .add(DragValue::new(&mut shown_props.elevation).suffix("m"))
-                        .on_hover_text("Elevation of the level");
+                    let r = InspectLength::new(&mut shown_props.elevation)
+                        .show(ui)
+                        .on_hover_text(tr(ui, "Elevation of the level"));
                     if r.dragged() || r.has_focus() {
                         any_dragging = true;
                     }
 
-                    ui.text_edit_singleline(&mut shown_props.name)
-                        .on_hover_text("Name of the level");
+                    edit_filtered_name(ui, &mut shown_props.name, &filter)
+                        .on_hover_text(tr(ui, "Name of the level"));
                 });
 
                 if shown_props != *props {
@@ -16,23 +16,27 @@
 */
 
 use crate::{
+    console::notify_warning,
     icons::Icons,
-    interaction::Select,
+    interaction::{Select, Selection},
+    localization::tr,
     site::{
-        Angle, Category, ExportLights, Light, LightKind, Pose, Recall, RecallLightKind, Rotation,
-        SiteID,
+        Angle, BakeLightmaps, Category, Change, ExportLights, IlluminanceMap, LevelProperties,
+        Light, LightGroup, LightKind, Pose, Recall, RecallLightKind, Rotation, SiteID,
+        ILLUMINANCE_BANDS,
     },
     widgets::{
-        inspector::{InspectLightKind, InspectPose, SelectionWidget},
+        inspector::{color_edit, InspectLightKind, InspectPose, SelectionWidget},
         AppEvents,
     },
+    CurrentWorkspace,
 };
 use bevy::{
     ecs::system::SystemParam,
     prelude::*,
     tasks::{AsyncComputeTaskPool, Task},
 };
-use bevy_egui::egui::Ui;
+use bevy_egui::egui::{Button, CollapsingHeader, Color32, DragValue, RichText, Ui};
 use futures_lite::future;
 #[cfg(not(target_arch = "wasm32"))]
 use rfd::AsyncFileDialog;
@@ -44,8 +48,18 @@ pub struct LightDisplay {
     pub pose: Pose,
     pub kind: LightKind,
     pub recall: RecallLightKind,
+    /// Create new lights in the set that is shared by every level instead of
+    /// the current level
+    pub shared: bool,
+    /// The group that new lights are put into, if it is not empty
+    pub group: String,
+    /// The group that the selected light will be moved into
+    pub assign_group: String,
     pub choosing_file_for_export: Option<Task<Option<std::path::PathBuf>>>,
     pub export_file: Option<std::path::PathBuf>,
+    pub choosing_lightmap_folder: Option<Task<Option<std::path::PathBuf>>>,
+    /// Resolution of baked lightmaps
+    pub lightmap_texels_per_meter: f32,
 }
 
 impl Default for LightDisplay {
@@ -61,16 +75,34 @@ impl Default for LightDisplay {
             },
             kind: Default::default(),
             recall: Default::default(),
+            shared: false,
+            group: String::new(),
+            assign_group: String::new(),
             choosing_file_for_export: None,
             export_file: None,
+            choosing_lightmap_folder: None,
+            lightmap_texels_per_meter: 10.0,
         }
     }
 }
 
 #[derive(SystemParam)]
 pub struct LightParams<'w, 's> {
-    pub lights: Query<'w, 's, (Entity, &'static LightKind, Option<&'static SiteID>)>,
+    pub lights: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static LightKind,
+            Option<&'static SiteID>,
+            Option<&'static Parent>,
+            Option<&'static LightGroup>,
+        ),
+    >,
+    pub selection: Res<'w, Selection>,
+    pub levels: Query<'w, 's, &'static LevelProperties>,
     pub icons: Res<'w, Icons>,
+    pub illuminance: Res<'w, IlluminanceMap>,
 }
 
 pub struct ViewLights<'a, 'w1, 's1, 'w2, 's2> {
@@ -83,15 +115,15 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLights<'a, 'w1, 's1, 'w2, 's2> {
         Self { params, events }
     }
 
-    pub fn show(self, ui: &mut Ui) {
+    pub fn show(mut self, ui: &mut Ui) {
         let mut use_headlight = self.events.request.toggle_headlights.0;
-        ui.checkbox(&mut use_headlight, "Use Headlight");
+        ui.checkbox(&mut use_headlight, tr(ui, "Use Headlight"));
         if use_headlight != self.events.request.toggle_headlights.0 {
             self.events.request.toggle_headlights.0 = use_headlight;
         }
 
         let mut use_physical_lights = self.events.request.toggle_physical_lights.0;
-        ui.checkbox(&mut use_physical_lights, "Use Physical Lights");
+        ui.checkbox(&mut use_physical_lights, tr(ui, "Use Physical Lights"));
         if use_physical_lights != self.events.request.toggle_physical_lights.0 {
             self.events.request.toggle_physical_lights.0 = use_physical_lights;
         }
@@ -102,17 +134,17 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLights<'a, 'w1, 's1, 'w2, 's2> {
         {
             ui.horizontal(|ui| {
                 if let Some(export_file) = &self.events.display.light.export_file {
-                    if ui.button("Export").clicked() {
+                    if ui.button(tr(ui, "Export")).clicked() {
                         self.events
                             .request
                             .export_lights
                             .send(ExportLights(export_file.clone()));
                     }
                 }
-                if ui.button("Export Lights As...").clicked() {
+                if ui.button(tr(ui, "Export Lights As...")).clicked() {
                     match &self.events.display.light.choosing_file_for_export {
                         Some(_) => {
-                            println!("A file is already being chosen!");
+                            notify_warning(None, "A file is already being chosen!");
                         }
                         None => {
                             let future = AsyncComputeTaskPool::get().spawn(async move {
@@ -134,17 +166,46 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLights<'a, 'w1, 's1, 'w2, 's2> {
                         ui.label(s);
                     }
                     None => {
-                        ui.label("unable to render path");
+                        ui.label(tr(ui, "unable to render path"));
                     }
                 },
                 None => {
-                    ui.label("<no file chosen>");
+                    ui.label(tr(ui, "<no file chosen>"));
                 }
             }
             ui.separator();
+
+            ui.horizontal(|ui| {
+                let display = &mut self.events.display.light;
+                if ui
+                    .button(tr(ui, "Bake Lightmaps..."))
+                    .on_hover_text(tr(
+                        ui,
+                        "Save the light that reaches each floor and wall as images in a folder",
+                    ))
+                    .clicked()
+                {
+                    if display.choosing_lightmap_folder.is_some() {
+                        notify_warning(None, "A folder is already being chosen!");
+                    } else {
+                        let future = AsyncComputeTaskPool::get().spawn(async move {
+                            let folder = AsyncFileDialog::new().pick_folder().await?;
+                            Some(folder.path().to_path_buf())
+                        });
+                        display.choosing_lightmap_folder = Some(future);
+                    }
+                }
+                ui.add(
+                    DragValue::new(&mut display.lightmap_texels_per_meter)
+                        .clamp_range(1.0..=100.0)
+                        .speed(0.5)
+                        .suffix(" px/m"),
+                );
+            });
+            ui.separator();
         }
 
-        ui.heading("Create new light");
+        ui.heading(tr(ui, "Create new light"));
         if let Some(new_pose) = InspectPose::new(&self.events.display.light.pose).show(ui) {
             self.events.display.light.pose = new_pose;
         }
@@ -161,50 +222,335 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewLights<'a, 'w1, 's1, 'w2, 's2> {
             }
         });
 
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Group"));
+            ui.text_edit_singleline(&mut self.events.display.light.group);
+        });
+        ui.checkbox(
+            &mut self.events.display.light.shared,
+            tr(ui, "Share with every level"),
+        );
+
         // TODO(MXG): Add a + icon to this button to make it more visible
-        if ui.button("Add").clicked() {
+        if ui.button(tr(ui, "Add")).clicked() {
             let new_light = self
                 .events
                 .commands
                 .spawn(Light {
                     pose: self.events.display.light.pose,
                     kind: self.events.display.light.kind,
+                    group: group_name(&self.events.display.light.group),
                 })
                 .insert(Category::Light)
                 .id();
+            if self.events.display.light.shared {
+                if let Some(site) = self.events.request.current_workspace.root {
+                    self.events.commands.entity(site).add_child(new_light);
+                }
+            }
             self.events.request.select.send(Select(Some(new_light)));
         }
 
         ui.separator();
 
-        let mut unsaved_lights = BTreeMap::new();
-        let mut saved_lights = BTreeMap::new();
-        for (e, kind, site_id) in &self.params.lights {
-            if let Some(site_id) = site_id {
-                saved_lights.insert(Reverse(site_id.0), (e, kind.label()));
+        // Lights that do not belong to a level belong to the site, which
+        // shares them with every level
+        let mut shared_lights = LightList::default();
+        let mut level_lights: BTreeMap<Entity, LightList> = BTreeMap::new();
+        for (e, kind, site_id, parent, _) in &self.params.lights {
+            let level = parent
+                .map(|p| p.get())
+                .filter(|p| self.params.levels.contains(*p));
+            let list = match level {
+                Some(level) => level_lights.entry(level).or_default(),
+                None => &mut shared_lights,
+            };
+            list.insert(e, kind.label(), site_id);
+        }
+
+        let current_level = *self.events.request.current_level;
+        ui.label(tr(ui, "Shared"));
+        self.show_light_list(
+            ui,
+            shared_lights,
+            current_level.map(|l| ("⬇", "Move this light to the current level", l)),
+        );
+
+        let mut level_lights: Vec<_> = level_lights
+            .into_iter()
+            .filter_map(|(level, list)| {
+                let name = self.params.levels.get(level).ok()?.name.clone();
+                Some((name, level, list))
+            })
+            .collect();
+        level_lights.sort_by(|a, b| a.0.cmp(&b.0));
+        let site = self.events.request.current_workspace.root;
+        for (name, level, list) in level_lights {
+            ui.separator();
+            if Some(level) == *current_level {
+                ui.label(format!("{name} (current)"));
+                self.show_light_list(
+                    ui,
+                    list,
+                    site.map(|s| ("⬆", "Share this light with every level", s)),
+                );
             } else {
-                unsaved_lights.insert(Reverse(e), kind.label());
+                ui.label(name);
+                self.show_light_list(ui, list, None);
             }
         }
 
-        for (e, label) in unsaved_lights {
+        ui.separator();
+        self.show_light_groups(ui);
+
+        ui.separator();
+        self.show_illuminance_preview(ui);
+    }
+
+    /// Show a list of lights. Each light can be moved into another set with
+    /// a button that has the given icon and hover text.
+    fn show_light_list(
+        &mut self,
+        ui: &mut Ui,
+        list: LightList,
+        move_to: Option<(&str, &str, Entity)>,
+    ) {
+        let LightList { unsaved, saved } = list;
+        let lights = unsaved
+            .into_iter()
+            .map(|(e, label)| (e.0, None, label))
+            .chain(
+                saved
+                    .into_iter()
+                    .map(|(site_id, (e, label))| (e, Some(SiteID(site_id.0)), label)),
+            );
+        for (e, site_id, label) in lights {
             ui.horizontal(|ui| {
-                SelectionWidget::new(e.0, None, self.params.icons.as_ref(), self.events).show(ui);
+                SelectionWidget::new(e, site_id, self.params.icons.as_ref(), self.events).show(ui);
                 ui.label(label);
+                if let Some((icon, hover, target)) = move_to {
+                    if ui.button(icon).on_hover_text(hover).clicked() {
+                        self.events.commands.entity(target).add_child(e);
+                    }
+                }
             });
         }
+    }
+
+    /// Edit every light of a group at once
+    fn show_light_groups(&mut self, ui: &mut Ui) {
+        ui.heading(tr(ui, "Light Groups"));
+        let selected_light = self
+            .params
+            .selection
+            .0
+            .filter(|e| self.params.lights.contains(*e));
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.events.display.light.assign_group);
+        });
+        ui.horizontal(|ui| {
+            let group = group_name(&self.events.display.light.assign_group);
+            if ui
+                .add_enabled(
+                    selected_light.is_some() && group.is_some(),
+                    Button::new(tr(ui, "Add Selected")),
+                )
+                .on_hover_text(tr(ui, "Put the selected light into this group"))
+                .clicked()
+            {
+                if let Some(e) = selected_light {
+                    self.events
+                        .site_change
+                        .light_group
+                        .send(Change::new(group, e).or_insert());
+                }
+            }
+            if ui
+                .add_enabled(
+                    selected_light.is_some(),
+                    Button::new(tr(ui, "Ungroup Selected")),
+                )
+                .clicked()
+            {
+                if let Some(e) = selected_light {
+                    self.events
+                        .site_change
+                        .light_group
+                        .send(Change::new(LightGroup(None), e).or_insert());
+                }
+            }
+        });
+
+        let mut groups: BTreeMap<&String, Vec<(Entity, &LightKind)>> = BTreeMap::new();
+        for (e, kind, _, _, group) in &self.params.lights {
+            if let Some(name) = group.and_then(|g| g.0.as_ref()) {
+                groups.entry(name).or_default().push((e, kind));
+            }
+        }
+
+        for (name, members) in groups {
+            let Some((_, first)) = members.first() else {
+                continue;
+            };
+            let mut color = first.color();
+            let mut intensity = members.iter().find_map(|(_, kind)| kind.intensity());
+            let mut shadows = first.enable_shadows();
+
+            CollapsingHeader::new(format!("{name} ({})", members.len()))
+                .id_source(("light_group", name))
+                .show(ui, |ui| {
+                    let mut new_color = None;
+                    let mut new_intensity = None;
+                    let mut new_shadows = None;
+                    ui.horizontal(|ui| {
+                        ui.label(tr(ui, "Color"));
+                        let before = color;
+                        color_edit(ui, &mut color);
+                        if color != before {
+                            new_color = Some(color);
+                        }
+                    });
+                    if let Some(intensity) = &mut intensity {
+                        ui.horizontal(|ui| {
+                            ui.label(tr(ui, "Intensity"));
+                            if ui
+                                .add(
+                                    DragValue::new(intensity)
+                                        .clamp_range(0_f32..=std::f32::INFINITY)
+                                        .speed(1.0),
+                                )
+                                .on_hover_text(tr(
+                                    ui,
+                                    "Applies to the point and spot lights of the group",
+                                ))
+                                .changed()
+                            {
+                                new_intensity = Some(*intensity);
+                            }
+                        });
+                    }
+                    if ui
+                        .checkbox(&mut shadows, tr(ui, "Enable shadows"))
+                        .changed()
+                    {
+                        new_shadows = Some(shadows);
+                    }
+
+                    if new_color.is_none() && new_intensity.is_none() && new_shadows.is_none() {
+                        return;
+                    }
+                    for (e, kind) in &members {
+                        let mut kind = **kind;
+                        if let Some(color) = new_color {
+                            kind.set_color(color);
+                        }
+                        if let Some(intensity) = new_intensity {
+                            kind.set_intensity(intensity);
+                        }
+                        if let Some(shadows) = new_shadows {
+                            kind.set_enable_shadows(shadows);
+                        }
+                        self.events.change.light.send(Change::new(kind, *e));
+                    }
+                });
+        }
+    }
 
-        for (site_id, (e, label)) in saved_lights {
+    fn show_illuminance_preview(&mut self, ui: &mut Ui) {
+        ui.heading(tr(ui, "Illuminance Preview"));
+        // Edit a copy so the heatmap is only recalculated when a setting
+        // actually changes
+        let mut preview = self.events.display.illuminance.clone();
+        ui.checkbox(&mut preview.show, tr(ui, "Show heatmap"))
+            .on_hover_text(tr(
+                ui,
+                "Estimate the illuminance on the current level from its lights",
+            ));
+        if preview.show {
             ui.horizontal(|ui| {
-                SelectionWidget::new(
-                    e,
-                    Some(SiteID(site_id.0)),
-                    self.params.icons.as_ref(),
-                    self.events,
+                ui.label(tr(ui, "Cell size"));
+                ui.add(
+                    DragValue::new(&mut preview.cell_size)
+                        .clamp_range(0.05_f32..=10.0)
+                        .speed(0.01)
+                        .suffix(" m"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Height"));
+                ui.add(
+                    DragValue::new(&mut preview.height)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .speed(0.01)
+                        .suffix(" m"),
                 )
-                .show(ui);
-                ui.label(label);
+                .on_hover_text(tr(
+                    ui,
+                    "Height above the floor where the illuminance is measured",
+                ));
             });
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Required"));
+                ui.add(
+                    DragValue::new(&mut preview.required)
+                        .clamp_range(0_f32..=std::f32::INFINITY)
+                        .speed(1.0)
+                        .suffix(" lux"),
+                );
+            });
+        }
+        let show = preview.show;
+        if preview != *self.events.display.illuminance {
+            *self.events.display.illuminance = preview;
+        }
+        if !show {
+            return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            for (lower, [r, g, b]) in ILLUMINANCE_BANDS {
+                let color =
+                    Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
+                ui.label(RichText::new("■").color(color));
+                ui.label(format!("{lower}+"));
+            }
+        });
+
+        let map = &self.params.illuminance;
+        if map.cells == 0 {
+            ui.label(tr(ui, "No cells to show"));
+            return;
+        }
+        ui.label(format!(
+            "min {:.0} / mean {:.0} / max {:.0} lux",
+            map.min, map.mean, map.max,
+        ));
+        ui.label(format!(
+            "{} of {} cells ({:.0}%) meet the requirement",
+            map.meeting_requirement,
+            map.cells,
+            100.0 * map.meeting_requirement as f32 / map.cells as f32,
+        ));
+    }
+}
+
+fn group_name(name: &str) -> LightGroup {
+    let name = name.trim();
+    LightGroup((!name.is_empty()).then(|| name.to_owned()))
+}
+
+#[derive(Default)]
+struct LightList {
+    unsaved: BTreeMap<Reverse<Entity>, &'static str>,
+    saved: BTreeMap<Reverse<u32>, (Entity, &'static str)>,
+}
+
+impl LightList {
+    fn insert(&mut self, e: Entity, label: &'static str, site_id: Option<&SiteID>) {
+        if let Some(site_id) = site_id {
+            self.saved.insert(Reverse(site_id.0), (e, label));
+        } else {
+            self.unsaved.insert(Reverse(e), label);
         }
     }
 }
@@ -229,3 +575,27 @@ pub fn resolve_light_export_file(
         light_display.choosing_file_for_export = None;
     }
 }
+
+pub fn resolve_lightmap_folder(
+    mut light_display: ResMut<LightDisplay>,
+    mut bake_lightmaps: EventWriter<BakeLightmaps>,
+    current_workspace: Res<CurrentWorkspace>,
+    open_sites: Query<Entity, With<rmf_site_format::SiteProperties>>,
+) {
+    let Some(task) = &mut light_display.choosing_lightmap_folder else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    light_display.choosing_lightmap_folder = None;
+
+    let (Some(folder), Some(site)) = (result, current_workspace.to_site(&open_sites)) else {
+        return;
+    };
+    bake_lightmaps.send(BakeLightmaps {
+        site,
+        folder,
+        texels_per_meter: light_display.lightmap_texels_per_meter,
+    });
+}
@@ -0,0 +1,207 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::FocusCamera,
+    localization::tr,
+    site::{
+        AnchorParams, AssetSource, Category, Distance, DrawingMarker, DrawingSegments, Edge, Label,
+        LevelProperties, MeasurementMarker, Pending, SiteID,
+    },
+    widgets::{inspector::SelectionWidget, AppEvents, Icons},
+    DisplayUnits,
+};
+use bevy::{ecs::system::SystemParam, prelude::*, render::primitives::Aabb};
+use bevy_egui::egui::{Grid, Ui};
+use std::path::Path;
+
+#[derive(SystemParam)]
+pub struct MeasurementsParams<'w, 's> {
+    pub measurements: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Edge<Entity>,
+            &'static Distance,
+            &'static Label,
+            &'static Parent,
+        ),
+        (With<MeasurementMarker>, Without<Pending>),
+    >,
+    pub drawings: Query<
+        'w,
+        's,
+        (
+            &'static AssetSource,
+            &'static DrawingSegments,
+            &'static Parent,
+        ),
+        With<DrawingMarker>,
+    >,
+    pub bounds: Query<'w, 's, (&'static GlobalTransform, &'static Aabb)>,
+    pub levels: Query<'w, 's, (&'static LevelProperties, &'static Parent)>,
+    pub anchors: AnchorParams<'w, 's>,
+    pub site_id: Query<'w, 's, &'static SiteID>,
+    pub icons: Res<'w, Icons>,
+    pub focus: EventWriter<'w, 's, FocusCamera>,
+}
+
+impl<'w, 's> MeasurementsParams<'w, 's> {
+    /// The name of the drawing on the level that lies beneath a point
+    fn drawing_under(&self, point: Vec3, level: Entity) -> Option<String> {
+        for (source, segments, parent) in &self.drawings {
+            if parent.get() != level {
+                continue;
+            }
+            let Ok((tf, aabb)) = self.bounds.get(segments.leaf) else {
+                continue;
+            };
+            let p = tf.affine().inverse().transform_point3(point);
+            let (min, max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+            if min.x <= p.x && p.x <= max.x && min.y <= p.y && p.y <= max.y {
+                let source = String::from(source);
+                return Some(
+                    Path::new(&source)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or(source),
+                );
+            }
+        }
+        None
+    }
+}
+
+struct MeasurementRow {
+    entity: Entity,
+    label: Option<String>,
+    level: String,
+    /// The drawing that the middle of the measurement lies on
+    drawing: Option<String>,
+    length: f32,
+    /// The distance that the measurement is supposed to have, used to
+    /// calibrate drawings
+    expected: Option<f32>,
+    midpoint: Vec3,
+}
+
+pub struct ViewMeasurements<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a mut MeasurementsParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewMeasurements<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a mut MeasurementsParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let Some(site) = self.events.request.current_workspace.root else {
+            return;
+        };
+
+        let mut rows = Vec::new();
+        for (e, edge, distance, label, parent) in &self.params.measurements {
+            let level = parent.get();
+            let Ok((level_props, level_parent)) = self.params.levels.get(level) else {
+                continue;
+            };
+            if level_parent.get() != site {
+                continue;
+            }
+            let anchors = &self.params.anchors;
+            let (Ok(start), Ok(end)) = (
+                anchors.point(edge.start(), Category::Measurement),
+                anchors.point(edge.end(), Category::Measurement),
+            ) else {
+                continue;
+            };
+            let midpoint = (start + end) / 2.0;
+            rows.push(MeasurementRow {
+                entity: e,
+                label: label.0.clone(),
+                level: level_props.name.clone(),
+                drawing: self.params.drawing_under(midpoint, level),
+                length: start.distance(end),
+                expected: distance.0,
+                midpoint,
+            });
+        }
+
+        if rows.is_empty() {
+            ui.label(tr(ui, "No measurements in this site"));
+            return;
+        }
+        rows.sort_by(|a, b| (&a.level, &a.drawing).cmp(&(&b.level, &b.drawing)));
+
+        let units = DisplayUnits::of(ui).length;
+        let mut focus = None;
+        Grid::new("measurements_list").striped(true).show(ui, |ui| {
+            ui.strong("");
+            ui.strong(tr(ui, "Level"));
+            ui.strong(tr(ui, "Drawing"));
+            ui.strong(tr(ui, "Measured"));
+            ui.strong(tr(ui, "Expected"));
+            ui.strong("");
+            ui.end_row();
+
+            for row in rows {
+                ui.horizontal(|ui| {
+                    SelectionWidget::new(
+                        row.entity,
+                        self.params.site_id.get(row.entity).ok().copied(),
+                        self.params.icons.as_ref(),
+                        self.events,
+                    )
+                    .show(ui);
+                    if let Some(label) = row.label {
+                        ui.label(label);
+                    }
+                });
+                ui.label(row.level);
+                match row.drawing {
+                    Some(drawing) => ui.label(drawing),
+                    None => ui.weak(tr(ui, "<none>")),
+                };
+                ui.label(units.format(row.length, 3));
+                match row.expected {
+                    Some(expected) => ui.label(units.format(expected, 3)).on_hover_text(format!(
+                        "Off by {}",
+                        units.format(row.length - expected, 3)
+                    )),
+                    None => ui.weak(tr(ui, "<none>")),
+                };
+                if ui
+                    .button(tr(ui, "Focus"))
+                    .on_hover_text(tr(ui, "Center the camera on this measurement"))
+                    .clicked()
+                {
+                    focus = Some(row.midpoint);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(midpoint) = focus {
+            self.params.focus.send(FocusCamera(midpoint));
+        }
+    }
+}
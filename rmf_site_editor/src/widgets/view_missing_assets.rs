@@ -0,0 +1,169 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::notify_warning,
+    localization::tr,
+    site::{asset_source_path, with_asset_source_path, Change, MissingAsset},
+    widgets::AppEvents,
+};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_egui::egui::{self, Grid};
+use rmf_site_format::{AssetSource, DrawingMarker, ModelMarker, NameInSite};
+use std::path::Path;
+
+#[derive(Resource, Default)]
+pub struct MissingAssetsDisplay {
+    /// Whether the user closed the dialog. It will open again if more assets
+    /// go missing.
+    pub dismissed: bool,
+    /// How many missing assets there were the last time the dialog was shown
+    pub count: usize,
+    /// New paths that the user has typed in for each missing asset
+    pub paths: HashMap<Entity, String>,
+    pub from_prefix: String,
+    pub to_prefix: String,
+}
+
+#[derive(SystemParam)]
+pub struct MissingAssetParams<'w, 's> {
+    pub missing: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static AssetSource,
+            Option<&'static NameInSite>,
+            Option<&'static DrawingMarker>,
+        ),
+        With<MissingAsset>,
+    >,
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub models: Query<'w, 's, (), With<ModelMarker>>,
+}
+
+pub struct ViewMissingAssets<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a MissingAssetParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewMissingAssets<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a MissingAssetParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ctx: &egui::Context) {
+        // The meshes of SDF models are spawned as models of their own, but
+        // only the outermost model can be relinked.
+        let mut missing: Vec<_> = self
+            .params
+            .missing
+            .iter()
+            .filter(|(e, ..)| {
+                !AncestorIter::new(&self.params.parents, *e).any(|p| self.params.models.contains(p))
+            })
+            .collect();
+        missing.sort_by_key(|(e, ..)| *e);
+
+        let display = &mut self.events.display.assets.missing_assets;
+        if missing.len() > display.count {
+            display.dismissed = false;
+        }
+        display.count = missing.len();
+        if missing.is_empty() || display.dismissed {
+            return;
+        }
+
+        if display.from_prefix.is_empty() {
+            // Suggest the directory of the first missing asset
+            if let Some(dir) = Path::new(asset_source_path(missing[0].1)).parent() {
+                display.from_prefix = dir.to_string_lossy().into_owned();
+            }
+        }
+
+        let mut changes = Vec::new();
+        let mut open = true;
+        egui::Window::new("Missing Assets")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(tr(
+                    ui,
+                    "These assets could not be found. Point them to a new location.",
+                ));
+                ui.separator();
+                Grid::new("missing_assets").show(ui, |ui| {
+                    for (e, source, name, drawing) in &missing {
+                        let label = match (name, drawing) {
+                            (Some(name), _) => name.0.clone(),
+                            (None, Some(_)) => "Drawing".to_owned(),
+                            (None, None) => format!("{e:?}"),
+                        };
+                        ui.label(label);
+                        let path = display
+                            .paths
+                            .entry(*e)
+                            .or_insert_with(|| asset_source_path(source).to_owned());
+                        ui.text_edit_singleline(path);
+                        if ui.button(tr(ui, "Apply")).clicked() {
+                            changes.push((*e, with_asset_source_path(source, path.clone())));
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                ui.label(tr(ui, "Replace a directory for every missing asset"));
+                Grid::new("missing_assets_prefix").show(ui, |ui| {
+                    ui.label(tr(ui, "From"));
+                    ui.text_edit_singleline(&mut display.from_prefix);
+                    ui.end_row();
+                    ui.label(tr(ui, "To"));
+                    ui.text_edit_singleline(&mut display.to_prefix);
+                    ui.end_row();
+                });
+                if ui.button(tr(ui, "Apply to All")).clicked() && !display.from_prefix.is_empty() {
+                    for (e, source, ..) in &missing {
+                        let path = asset_source_path(source);
+                        if let Some(rest) = path.strip_prefix(display.from_prefix.as_str()) {
+                            let new_path = display.to_prefix.clone() + rest;
+                            changes.push((*e, with_asset_source_path(source, new_path)));
+                        }
+                    }
+                    if changes.is_empty() {
+                        notify_warning(
+                            None,
+                            format!("No missing assets start with {}", display.from_prefix),
+                        );
+                    }
+                }
+            });
+
+        if !open {
+            display.dismissed = true;
+        }
+        for (e, _) in &changes {
+            display.paths.remove(e);
+        }
+        for (e, source) in changes {
+            self.events.change.asset_source.send(Change::new(source, e));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::localization::tr;
+use crate::widgets::{
+    view_asset_library::{place_model, preview_model, LIBRARY_THUMBNAIL_SIZE},
+    AppEvents,
+};
+use bevy::prelude::*;
+use bevy_egui::egui::{Image, Key, TextureId, Ui};
+use rmf_site_format::AssetSource;
+
+/// The most local models that will be listed for one search
+pub const MAX_LOCAL_SEARCH_RESULTS: usize = 50;
+
+#[derive(Resource, Default)]
+pub struct ModelSearchDisplay {
+    pub search: String,
+    /// The search that the current results were found for
+    pub results_for: Option<String>,
+}
+
+pub struct ViewModelSearch<'a, 'w, 's> {
+    events: &'a mut AppEvents<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewModelSearch<'a, 'w, 's> {
+    pub fn new(events: &'a mut AppEvents<'w, 's>) -> Self {
+        Self { events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let display = &mut self.events.display.assets;
+        ui.horizontal(|ui| {
+            let response = ui.text_edit_singleline(&mut display.model_search.search);
+            let submitted = response.lost_focus() && ui.input().key_pressed(Key::Enter);
+            if ui.button(tr(ui, "Search")).clicked() || submitted {
+                let search = display.model_search.search.trim().to_owned();
+                // Fuel matches the search against the names and tags of its
+                // models, so the remote results are filtered by the server.
+                display.fuel_models.search = search.clone();
+                display.fuel_models.fetch(1);
+                display.model_search.results_for = Some(search);
+            }
+        });
+
+        let Some(search) = display.model_search.results_for.clone() else {
+            return;
+        };
+
+        let mut action = None;
+        let mut show_result = |ui: &mut Ui,
+                               name: &str,
+                               details: String,
+                               thumbnail: Option<TextureId>,
+                               source: AssetSource| {
+            ui.horizontal(|ui| {
+                if let Some(texture) = thumbnail {
+                    ui.add(Image::new(
+                        texture,
+                        [LIBRARY_THUMBNAIL_SIZE, LIBRARY_THUMBNAIL_SIZE],
+                    ));
+                }
+                ui.vertical(|ui| {
+                    ui.label(name).on_hover_text(details);
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(tr(ui, "Preview"))
+                            .on_hover_text(tr(ui, "Show this model in the Create panel"))
+                            .clicked()
+                        {
+                            action = Some((source.clone(), false));
+                        }
+                        if ui.button(tr(ui, "Insert")).clicked() {
+                            action = Some((source, true));
+                        }
+                    });
+                });
+            });
+        };
+
+        ui.label(tr(ui, "Local"));
+        let library = &display.asset_library;
+        let mut found_local = false;
+        for model in library
+            .models
+            .iter()
+            .filter(|model| model.matches(&search))
+            .take(MAX_LOCAL_SEARCH_RESULTS)
+        {
+            found_local = true;
+            show_result(
+                ui,
+                &model.name,
+                model.path.to_string_lossy().into_owned(),
+                library.thumbnails.get(&model.path).map(|(_, t)| *t),
+                model.source(),
+            );
+        }
+        if !found_local {
+            ui.label(tr(ui, "No local models found"));
+        }
+
+        ui.separator();
+        ui.label(tr(ui, "Fuel"));
+        let fuel = &display.fuel_models;
+        if fuel.fetching.is_some() {
+            ui.label(tr(ui, "Searching Fuel..."));
+        } else if fuel.models.is_empty() {
+            ui.label(tr(ui, "No models found on Fuel"));
+        } else {
+            for model in &fuel.models {
+                show_result(
+                    ui,
+                    &model.path(),
+                    model.tags.join(", "),
+                    fuel.thumbnails.get(&model.path()).map(|(_, t)| *t),
+                    model.source(),
+                );
+            }
+        }
+
+        match action {
+            Some((source, true)) => place_model(self.events, source),
+            Some((source, false)) => preview_model(self.events, source),
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,144 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::{Select, ToggleSelect},
+    localization::tr,
+    site::{NameInSite, SiteID},
+    widgets::{inspector::SelectionWidget, AppEvents, Icons},
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{CollapsingHeader, Ui};
+use rmf_site_format::{ModelMarker, ModelTags, Pending};
+use std::collections::BTreeMap;
+
+#[derive(Resource, Default)]
+pub struct ModelTagsDisplay {
+    /// Text of the tag being added to the selected model
+    pub new_tag: String,
+}
+
+#[derive(SystemParam)]
+pub struct ModelTagsParams<'w, 's> {
+    pub models: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static NameInSite,
+            &'static ModelTags,
+            Option<&'static SiteID>,
+        ),
+        (With<ModelMarker>, Without<Pending>),
+    >,
+    pub icons: Res<'w, Icons>,
+    pub toggle_select: EventWriter<'w, 's, ToggleSelect>,
+}
+
+pub struct ViewModelTags<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a mut ModelTagsParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewModelTags<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a mut ModelTagsParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let mut tagged: BTreeMap<&String, Vec<(Entity, &NameInSite, Option<&SiteID>)>> =
+            BTreeMap::new();
+        let mut untagged = 0;
+        for (e, name, tags, site_id) in &self.params.models {
+            if tags.0.is_empty() {
+                untagged += 1;
+            }
+            for tag in &tags.0 {
+                tagged.entry(tag).or_default().push((e, name, site_id));
+            }
+        }
+
+        if tagged.is_empty() {
+            ui.label(tr(ui, "No models have been tagged"));
+            return;
+        }
+
+        let mut select_all = None;
+        for (tag, models) in &tagged {
+            let hidden = &mut self.events.site_request.hidden_model_tags;
+            let mut visible = !hidden.0.contains(*tag);
+            let previously_visible = visible;
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut visible, "")
+                    .on_hover_text(tr(ui, "Show models with this tag"));
+                if ui
+                    .button(tr(ui, "Select All"))
+                    .on_hover_text(tr(ui, "Select every model with this tag"))
+                    .clicked()
+                {
+                    select_all = Some(models.iter().map(|(e, _, _)| *e).collect::<Vec<_>>());
+                }
+                CollapsingHeader::new(format!("{tag} ({})", models.len()))
+                    .id_source(("model_tag", *tag))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        // List the models to let the user jump between them
+                        for (e, name, site_id) in models {
+                            ui.horizontal(|ui| {
+                                SelectionWidget::new(
+                                    *e,
+                                    site_id.copied(),
+                                    self.params.icons.as_ref(),
+                                    self.events,
+                                )
+                                .show(ui);
+                                ui.label(&name.0);
+                            });
+                        }
+                    });
+            });
+
+            if visible != previously_visible {
+                let hidden = &mut self.events.site_request.hidden_model_tags;
+                if visible {
+                    hidden.0.remove(*tag);
+                } else {
+                    hidden.0.insert((*tag).clone());
+                }
+            }
+        }
+
+        if untagged > 0 {
+            ui.label(format!("{untagged} models have no tags"));
+        }
+
+        // Selecting the first model drops the rest of the selection, and the
+        // others are added to the multi-selection after it
+        if let Some(models) = select_all {
+            let mut models = models.into_iter();
+            if let Some(first) = models.next() {
+                self.events.request.select.send(Select(Some(first)));
+            }
+            for e in models {
+                self.params.toggle_select.send(ToggleSelect(e));
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{notify_error, notify_warning},
+    icons::Icons,
+    localization::tr,
+    site::{
+        CompareNavGraphs, NameInSite, NavGraphDiff, NavGraphMarker, ResolveGraphChange, SiteID,
+    },
+    widgets::{inspector::SelectionWidget, AppEvents},
+    CurrentWorkspace,
+};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use bevy_egui::egui::{ComboBox, Ui};
+use futures_lite::future;
+
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::AsyncFileDialog;
+
+#[derive(Resource, Default)]
+pub struct NavGraphDiffDisplay {
+    pub graph: Option<Entity>,
+    pub choosing_file: Option<Task<Option<rmf_site_format::Site>>>,
+}
+
+#[derive(SystemParam)]
+pub struct NavGraphDiffParams<'w, 's> {
+    pub graphs: Query<'w, 's, (Entity, &'static NameInSite), With<NavGraphMarker>>,
+    pub diff: Res<'w, NavGraphDiff>,
+    pub site_id: Query<'w, 's, &'static SiteID>,
+    pub icons: Res<'w, Icons>,
+}
+
+pub struct ViewNavGraphDiff<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a NavGraphDiffParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphDiff<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a NavGraphDiffParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let display = &mut self.events.display.nav_graph_diff;
+        let selected_name = display
+            .graph
+            .and_then(|e| self.params.graphs.get(e).ok())
+            .map(|(_, name)| name.0.clone())
+            .unwrap_or_else(|| "<none>".to_owned());
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Graph"));
+            ComboBox::from_id_source("nav_graph_diff_graph")
+                .selected_text(selected_name)
+                .show_ui(ui, |ui| {
+                    for (e, name) in &self.params.graphs {
+                        ui.selectable_value(&mut display.graph, Some(e), &name.0);
+                    }
+                });
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if ui
+                .button(tr(ui, "Compare With File..."))
+                .on_hover_text(tr(ui, "Show how the graph differs in another site file"))
+                .clicked()
+            {
+                if display.graph.is_none() {
+                    notify_warning(None, "Choose a graph to compare first");
+                } else if display.choosing_file.is_some() {
+                    notify_warning(None, "A file is already being chosen!");
+                } else {
+                    let future = AsyncComputeTaskPool::get().spawn(async move {
+                        let file = AsyncFileDialog::new().pick_file().await?;
+                        match rmf_site_format::Site::from_bytes(&file.read().await) {
+                            Ok(site) => Some(site),
+                            Err(err) => {
+                                notify_error(None, format!("Unable to parse file:\n{err}"));
+                                None
+                            }
+                        }
+                    });
+                    display.choosing_file = Some(future);
+                }
+            }
+        }
+
+        let diff = &self.params.diff;
+        if diff.graph.is_none() {
+            return;
+        }
+
+        if diff.changes.is_empty() {
+            ui.label(tr(ui, "No differences remaining"));
+            return;
+        }
+
+        let mut resolve = None;
+        for (index, change) in diff.changes.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if let Some(e) = change.entity {
+                    SelectionWidget::new(
+                        e,
+                        self.params.site_id.get(e).ok().copied(),
+                        self.params.icons.as_ref(),
+                        self.events,
+                    )
+                    .show(ui);
+                }
+                ui.label(&change.description);
+                if ui.button(tr(ui, "Accept")).clicked() {
+                    resolve = Some(ResolveGraphChange {
+                        index,
+                        accept: true,
+                    });
+                }
+                if ui.button(tr(ui, "Reject")).clicked() {
+                    resolve = Some(ResolveGraphChange {
+                        index,
+                        accept: false,
+                    });
+                }
+            });
+        }
+
+        if let Some(resolve) = resolve {
+            self.events.site_request.resolve_graph_change.send(resolve);
+        }
+    }
+}
+
+pub fn resolve_nav_graph_diff_file(
+    mut display: ResMut<NavGraphDiffDisplay>,
+    mut compare: EventWriter<CompareNavGraphs>,
+    current_workspace: Res<CurrentWorkspace>,
+    open_sites: Query<Entity, With<rmf_site_format::SiteProperties>>,
+) {
+    let Some(task) = &mut display.choosing_file else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    display.choosing_file = None;
+
+    let (Some(other), Some(graph), Some(site)) = (
+        result,
+        display.graph,
+        current_workspace.to_site(&open_sites),
+    ) else {
+        return;
+    };
+    compare.send(CompareNavGraphs { site, graph, other });
+}
@@ -16,12 +16,19 @@
 */
 
 use crate::{
+    console::{notify_error, notify_warning, report_error},
+    localization::tr,
     recency::RecencyRanking,
     site::{
-        Change, Delete, DisplayColor, ImportNavGraphs, NameInSite, NavGraph, NavGraphMarker,
-        SaveNavGraphs, SiteProperties, DEFAULT_NAV_GRAPH_COLORS,
+        Change, Delete, DisplayColor, ImportNavGraphs, LaneDefaults, ManualColor,
+        MergeCollinearLanes, NameInSite, NavGraph, NavGraphMarker, NavGraphPalette, SaveNavGraphs,
+        SiteProperties,
+    },
+    widgets::{
+        edit_filtered_name, edit_name_filter,
+        inspector::{color_edit, InspectLaneDefaults},
+        matches_name_filter, AppEvents, Icons, MoveLayer,
     },
-    widgets::{inspector::color_edit, AppEvents, Icons, MoveLayer},
     Autoload, CurrentWorkspace,
 };
 use bevy::{
@@ -29,7 +36,7 @@ use bevy::{
     prelude::*,
     tasks::{AsyncComputeTaskPool, Task},
 };
-use bevy_egui::egui::{ImageButton, Ui};
+use bevy_egui::egui::{CollapsingHeader, ComboBox, ImageButton, Ui};
 use futures_lite::future;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -40,9 +47,12 @@ pub struct NavGraphDisplay {
     pub color: Option<[f32; 4]>,
     pub name: String,
     pub removing: bool,
+    /// Only graphs whose names contain this text are listed
+    pub filter: String,
     pub choosing_file_for_export: Option<Task<Option<std::path::PathBuf>>>,
     pub export_file: Option<std::path::PathBuf>,
     pub choosing_file_to_import: Option<Task<Option<(std::path::PathBuf, ImportNavGraphs)>>>,
+    pub choosing_legacy_file_to_import: Option<Task<Option<ImportNavGraphs>>>,
 }
 
 impl FromWorld for NavGraphDisplay {
@@ -55,9 +65,11 @@ impl FromWorld for NavGraphDisplay {
             color: None,
             name: "<Unnamed>".to_string(),
             removing: false,
+            filter: String::new(),
             choosing_file_for_export: None,
             export_file,
             choosing_file_to_import: None,
+            choosing_legacy_file_to_import: None,
         }
     }
 }
@@ -71,10 +83,13 @@ pub struct NavGraphParams<'w, 's> {
         (
             &'static NameInSite,
             &'static DisplayColor,
+            &'static ManualColor,
+            &'static LaneDefaults,
             &'static Visibility,
         ),
         With<NavGraphMarker>,
     >,
+    pub palettes: Query<'w, 's, &'static NavGraphPalette>,
     pub icons: Res<'w, Icons>,
 }
 
@@ -89,30 +104,50 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphs<'a, 'w1, 's1, 'w2, 's2> {
     }
 
     pub fn show(self, ui: &mut Ui, open_sites: &Query<Entity, With<SiteProperties>>) {
-        let ranking = match self.events.request.current_workspace.root {
-            Some(c) => match self.params.ranking.get(c) {
-                Ok(r) => r,
-                Err(_) => return,
-            },
-            None => return,
+        let Some(site) = self.events.request.current_workspace.root else {
+            return;
+        };
+        let Ok(ranking) = self.params.ranking.get(site) else {
+            return;
         };
         let graph_count = ranking.len();
+        let palette = self.params.palettes.get(site).copied().unwrap_or_default();
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Palette"));
+            let mut new_palette = palette;
+            ComboBox::from_id_source("nav_graph_palette")
+                .selected_text(palette.label())
+                .show_ui(ui, |ui| {
+                    for option in NavGraphPalette::ALL {
+                        ui.selectable_value(&mut new_palette, option, option.label());
+                    }
+                });
+            if new_palette != palette {
+                self.events
+                    .site_change
+                    .nav_graph_palette
+                    .send(Change::new(new_palette, site).or_insert());
+                // Let the next graph pick up a color from the new palette
+                self.events.display.nav_graph.color = None;
+            }
+        });
 
         ui.horizontal(|ui| {
             if self.events.display.nav_graph.removing {
                 if ui
-                    .button("View")
-                    .on_hover_text("Toggle visibility of graphs")
+                    .button(tr(ui, "View"))
+                    .on_hover_text(tr(ui, "Toggle visibility of graphs"))
                     .clicked()
                 {
                     self.events.display.nav_graph.removing = false;
                 }
-                ui.label("Remove");
+                ui.label(tr(ui, "Remove"));
             } else {
-                ui.label("View");
+                ui.label(tr(ui, "View"));
                 if ui
-                    .button("Remove")
-                    .on_hover_text("Choose a graph to remove")
+                    .button(tr(ui, "Remove"))
+                    .on_hover_text(tr(ui, "Choose a graph to remove"))
                     .clicked()
                 {
                     self.events.display.nav_graph.removing = true;
@@ -121,11 +156,9 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphs<'a, 'w1, 's1, 'w2, 's2> {
         });
 
         ui.horizontal(|ui| {
-            let add = ui.button("Add").clicked();
+            let add = ui.button(tr(ui, "Add")).clicked();
             if self.events.display.nav_graph.color.is_none() {
-                let next_color_index = graph_count % DEFAULT_NAV_GRAPH_COLORS.len();
-                self.events.display.nav_graph.color =
-                    Some(DEFAULT_NAV_GRAPH_COLORS[next_color_index]);
+                self.events.display.nav_graph.color = Some(palette.color(graph_count));
             }
             if let Some(color) = &mut self.events.display.nav_graph.color {
                 color_edit(ui, color);
@@ -138,18 +171,26 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphs<'a, 'w1, 's1, 'w2, 's2> {
                     .insert(NavGraph {
                         name: NameInSite(self.events.display.nav_graph.name.clone()),
                         color: DisplayColor(self.events.display.nav_graph.color.unwrap().clone()),
+                        manual_color: Default::default(),
+                        lane_defaults: Default::default(),
                         marker: Default::default(),
                     });
                 self.events.display.nav_graph.color = None;
             }
         });
 
-        for e in ranking.iter().rev() {
+        edit_name_filter(ui, &mut self.events.display.nav_graph.filter);
+        let filter = self.events.display.nav_graph.filter.clone();
+
+        for (index, e) in ranking.iter().enumerate().rev() {
             let e = *e;
-            let (name, color, vis) = match self.params.graphs.get(e) {
+            let (name, color, manual_color, lane_defaults, vis) = match self.params.graphs.get(e) {
                 Ok(g) => g,
                 Err(_) => continue,
             };
+            if !matches_name_filter(&name.0, &filter) {
+                continue;
+            }
             ui.horizontal(|ui| {
                 if self.events.display.nav_graph.removing {
                     if ui
@@ -194,27 +235,78 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphs<'a, 'w1, 's1, 'w2, 's2> {
                         .change
                         .color
                         .send(Change::new(DisplayColor(new_color), e));
+                    if !manual_color.0 {
+                        self.events
+                            .site_change
+                            .manual_color
+                            .send(Change::new(ManualColor(true), e));
+                    }
+                }
+                if manual_color.0
+                    && ui
+                        .button("↺")
+                        .on_hover_text(tr(ui, "Use the color from the palette"))
+                        .clicked()
+                {
+                    self.events
+                        .change
+                        .color
+                        .send(Change::new(DisplayColor(palette.color(index)), e));
+                    self.events
+                        .site_change
+                        .manual_color
+                        .send(Change::new(ManualColor(false), e));
                 }
 
                 let mut new_name = name.0.clone();
-                if ui.text_edit_singleline(&mut new_name).changed() {
+                if edit_filtered_name(ui, &mut new_name, &filter).changed() {
                     self.events
                         .change
                         .name
                         .send(Change::new(NameInSite(new_name), e));
                 }
             });
+
+            CollapsingHeader::new(tr(ui, "Lane Defaults"))
+                .id_source(("Lane Defaults", e))
+                .default_open(false)
+                .show(ui, |ui| {
+                    if let Some(new_defaults) = InspectLaneDefaults::new(e, lane_defaults).show(ui)
+                    {
+                        self.events
+                            .site_change
+                            .lane_defaults
+                            .send(Change::new(new_defaults, e));
+                    }
+                });
+        }
+
+        ui.separator();
+        if ui
+            .button(tr(ui, "Merge Collinear Lanes"))
+            .on_hover_text(tr(
+                ui,
+                "Combine chains of collinear lanes with matching properties",
+            ))
+            .clicked()
+        {
+            if let Some(site) = self.events.request.current_workspace.to_site(open_sites) {
+                self.events
+                    .site_request
+                    .merge_collinear_lanes
+                    .send(MergeCollinearLanes { site });
+            }
         }
 
         #[cfg(not(target_arch = "wasm32"))]
         {
             ui.separator();
-            if ui.button("Import Graphs...").clicked() {
+            if ui.button(tr(ui, "Import Graphs...")).clicked() {
                 match self.events.request.current_workspace.to_site(open_sites) {
                     Some(into_site) => {
                         match &self.events.display.nav_graph.choosing_file_to_import {
                             Some(_) => {
-                                println!("A file is already being chosen!");
+                                notify_warning(None, "A file is already being chosen!");
                             }
                             None => {
                                 let future = AsyncComputeTaskPool::get().spawn(async move {
@@ -232,7 +324,10 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphs<'a, 'w1, 's1, 'w2, 's2> {
                                             },
                                         )),
                                         Err(err) => {
-                                            println!("Unable to parse file:\n{err}");
+                                            notify_error(
+                                                None,
+                                                format!("Unable to parse file:\n{err}"),
+                                            );
                                             None
                                         }
                                     }
@@ -243,14 +338,76 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphs<'a, 'w1, 's1, 'w2, 's2> {
                         }
                     }
                     None => {
-                        println!("DEV ERROR: No current site??");
+                        report_error(None, "DEV ERROR: No current site??");
+                    }
+                }
+            }
+            if ui
+                .button(tr(ui, "Import Graph YAML..."))
+                .on_hover_text(tr(
+                    ui,
+                    "Import a nav graph that was generated for a deployment",
+                ))
+                .clicked()
+            {
+                match self.events.request.current_workspace.to_site(open_sites) {
+                    Some(into_site) => {
+                        match &self.events.display.nav_graph.choosing_legacy_file_to_import {
+                            Some(_) => {
+                                notify_warning(None, "A file is already being chosen!");
+                            }
+                            None => {
+                                let future = AsyncComputeTaskPool::get().spawn(async move {
+                                    let file = match AsyncFileDialog::new()
+                                        .add_filter("nav graph", &["yaml", "yml"])
+                                        .pick_file()
+                                        .await
+                                    {
+                                        Some(file) => file,
+                                        None => return None,
+                                    };
+
+                                    let graph = match rmf_site_format::legacy::nav_graph::NavGraph::from_bytes(
+                                        &file.read().await,
+                                    ) {
+                                        Ok(graph) => graph,
+                                        Err(err) => {
+                                            notify_error(None, format!("Unable to parse nav graph file:\n{err}"));
+                                            return None;
+                                        }
+                                    };
+
+                                    let graph_name = file
+                                        .path()
+                                        .file_stem()
+                                        .and_then(|stem| stem.to_str())
+                                        .unwrap_or("<Unnamed>")
+                                        .to_owned();
+                                    match graph.to_site(&graph_name) {
+                                        Ok(from_site) => Some(ImportNavGraphs {
+                                            into_site,
+                                            from_site,
+                                        }),
+                                        Err(err) => {
+                                            notify_error(None, format!("Unable to convert nav graph file:\n{err}"));
+                                            None
+                                        }
+                                    }
+                                });
+                                self.events.display.nav_graph.choosing_legacy_file_to_import =
+                                    Some(future);
+                            }
+                        }
+                    }
+                    None => {
+                        report_error(None, "DEV ERROR: No current site??");
                     }
                 }
             }
             ui.separator();
             ui.horizontal(|ui| {
                 if let Some(export_file) = &self.events.display.nav_graph.export_file {
-                    if ui.button("Export").clicked() {
+                    if ui.button(tr(ui, "Export")).clicked() {
                         if let Some(current_site) =
                             self.events.request.current_workspace.to_site(open_sites)
                         {
@@ -263,10 +420,10 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphs<'a, 'w1, 's1, 'w2, 's2> {
                         }
                     }
                 }
-                if ui.button("Export Graphs As...").clicked() {
+                if ui.button(tr(ui, "Export Graphs As...")).clicked() {
                     match &self.events.display.nav_graph.choosing_file_for_export {
                         Some(_) => {
-                            println!("A file is already being chosen!");
+                            notify_warning(None, "A file is already being chosen!");
                         }
                         None => {
                             let future = AsyncComputeTaskPool::get().spawn(async move {
@@ -284,7 +441,7 @@ impl<'a, 'w1, 's1, 'w2, 's2> ViewNavGraphs<'a, 'w1, 's1, 'w2, 's2> {
             if let Some(export_file) = &self.events.display.nav_graph.export_file {
                 if let Some(export_file) = export_file.as_os_str().to_str() {
                     ui.horizontal(|ui| {
-                        ui.label("Chosen file:");
+                        ui.label(tr(ui, "Chosen file:"));
                         ui.label(export_file);
                     });
                 }
@@ -336,4 +493,21 @@ pub fn resolve_nav_graph_import_export_files(
     } {
         nav_graph_display.choosing_file_to_import = None;
     }
+
+    // A legacy nav graph file cannot be exported back into, so this does not
+    // change the export file.
+    if 'resolved: {
+        if let Some(task) = &mut nav_graph_display.choosing_legacy_file_to_import {
+            if let Some(result) = future::block_on(future::poll_once(task)) {
+                if let Some(request) = result {
+                    import_nav_graphs.send(request);
+                }
+
+                break 'resolved true;
+            }
+        }
+        false
+    } {
+        nav_graph_display.choosing_legacy_file_to_import = None;
+    }
 }
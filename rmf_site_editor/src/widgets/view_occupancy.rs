@@ -15,38 +15,96 @@
  *
 */
 
-use crate::{occupancy::CalculateGrid, widgets::AppEvents};
-use bevy::prelude::Resource;
-use bevy_egui::egui::{DragValue, Ui};
+use crate::{
+    localization::tr,
+    occupancy::{
+        CalculateGrid, CheckReachability, CompareGrids, GenerateNavGraph, Grid, GridComparison,
+        Reachability,
+    },
+    site::NameInSite,
+    widgets::AppEvents,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{ComboBox, DragValue, Ui};
+use std::collections::BTreeMap;
 
 #[derive(Resource)]
 pub struct OccupancyDisplay {
+    /// Name of the resolution that will be calculated next
+    pub name: String,
     pub cell_size: f32,
+    /// Ignore obstacles below this height
+    pub floor: f32,
+    /// Ignore obstacles above this height
+    pub ceiling: f32,
+    /// Calculate the occupancy in layers between the floor and ceiling
+    pub voxels: bool,
+    /// Leave doors out of the occupancy unless they are set to be closed
+    pub open_doors: bool,
+    /// Robot radius used to inflate the obstacles of the occupancy grid
+    pub inflation: f32,
+    pub robot_radius: f32,
+    /// The pair of grids chosen for a comparison
+    pub compare: [Option<Entity>; 2],
 }
 
 impl Default for OccupancyDisplay {
     fn default() -> Self {
-        Self { cell_size: 0.5 }
+        Self {
+            name: "default".to_owned(),
+            cell_size: 0.5,
+            floor: 0.01,
+            ceiling: 1.5,
+            voxels: false,
+            open_doors: false,
+            inflation: 0.0,
+            robot_radius: 0.3,
+            compare: [None, None],
+        }
     }
 }
 
-pub struct ViewOccupancy<'a, 'w2, 's2> {
+impl OccupancyDisplay {
+    fn calculate_grid(&self) -> CalculateGrid {
+        CalculateGrid {
+            name: self.name.clone(),
+            cell_size: self.cell_size,
+            floor: self.floor,
+            ceiling: self.ceiling,
+            inflation: self.inflation,
+            voxels: self.voxels,
+            open_doors: self.open_doors,
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct OccupancyParams<'w, 's> {
+    pub grids: Query<'w, 's, (Entity, &'static Grid, &'static Parent)>,
+    pub level_names: Query<'w, 's, &'static NameInSite>,
+    pub comparison: Res<'w, GridComparison>,
+    pub reachability: Res<'w, Reachability>,
+}
+
+pub struct ViewOccupancy<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a OccupancyParams<'w1, 's1>,
     events: &'a mut AppEvents<'w2, 's2>,
 }
 
-impl<'a, 'w2, 's2> ViewOccupancy<'a, 'w2, 's2> {
-    pub fn new(events: &'a mut AppEvents<'w2, 's2>) -> Self {
-        Self { events }
+impl<'a, 'w1, 's1, 'w2, 's2> ViewOccupancy<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(params: &'a OccupancyParams<'w1, 's1>, events: &'a mut AppEvents<'w2, 's2>) -> Self {
+        Self { params, events }
     }
 
-    pub fn show(self, ui: &mut Ui) {
+    pub fn show(mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Resolution"));
+            ui.text_edit_singleline(&mut self.events.display.occupancy.name);
+        });
         ui.horizontal(|ui| {
-            if ui.button("Calculate Occupancy").clicked() {
-                self.events.request.calculate_grid.send(CalculateGrid {
-                    cell_size: self.events.display.occupancy.cell_size,
-                    floor: 0.01,
-                    ceiling: 1.5,
-                });
+            if ui.button(tr(ui, "Calculate Occupancy")).clicked() {
+                let request = self.events.display.occupancy.calculate_grid();
+                self.events.request.calculate_grid.send(request);
             }
             if ui
                 .add(
@@ -57,13 +115,234 @@ impl<'a, 'w2, 's2> ViewOccupancy<'a, 'w2, 's2> {
                 .changed()
             {
                 if self.events.display.occupancy.cell_size > 0.1 {
-                    self.events.request.calculate_grid.send(CalculateGrid {
-                        cell_size: self.events.display.occupancy.cell_size,
-                        floor: 0.01,
-                        ceiling: 1.5,
+                    let request = self.events.display.occupancy.calculate_grid();
+                    self.events.request.calculate_grid.send(request);
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let display = &mut self.events.display.occupancy;
+            let (mut floor, mut ceiling) = (display.floor, display.ceiling);
+            ui.label(tr(ui, "Floor"));
+            ui.add(
+                DragValue::new(&mut floor)
+                    .clamp_range(f32::NEG_INFINITY..=ceiling)
+                    .speed(0.01),
+            );
+            ui.label(tr(ui, "Ceiling"));
+            ui.add(
+                DragValue::new(&mut ceiling)
+                    .clamp_range(floor..=f32::INFINITY)
+                    .speed(0.01),
+            );
+            if floor != display.floor {
+                display.floor = floor;
+            }
+            if ceiling != display.ceiling {
+                display.ceiling = ceiling;
+            }
+        });
+        ui.checkbox(&mut self.events.display.occupancy.voxels, tr(ui, "Voxels"))
+            .on_hover_text(tr(
+                ui,
+                "Record the height of obstacles between the floor and ceiling",
+            ));
+        ui.checkbox(
+            &mut self.events.display.occupancy.open_doors,
+            tr(ui, "Open doors"),
+        )
+        .on_hover_text(tr(
+            ui,
+            "Treat doors as open unless they are set to be closed in the inspector",
+        ));
+        let mut live = self.events.site_request.live_occupancy.0;
+        if ui
+            .checkbox(&mut live, tr(ui, "Update while editing"))
+            .on_hover_text(tr(
+                ui,
+                "Recalculate the cells around anything that moves or changes",
+            ))
+            .changed()
+        {
+            self.events.site_request.live_occupancy.0 = live;
+        }
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Min corridor width"));
+            let mut width = self
+                .events
+                .site_request
+                .placement_warnings
+                .min_corridor_width;
+            if ui
+                .add(
+                    DragValue::new(&mut width)
+                        .clamp_range(0.0..=f32::INFINITY)
+                        .speed(0.01),
+                )
+                .on_hover_text(tr(
+                    ui,
+                    "Warn about lanes in corridors that are narrower than this",
+                ))
+                .changed()
+            {
+                self.events
+                    .site_request
+                    .placement_warnings
+                    .min_corridor_width = width;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Inflation radius"));
+            if ui
+                .add(
+                    DragValue::new(&mut self.events.display.occupancy.inflation)
+                        .clamp_range(0.0..=f32::INFINITY)
+                        .speed(0.01),
+                )
+                .on_hover_text(tr(
+                    ui,
+                    "Show the cells that a robot of this radius cannot reach",
+                ))
+                .changed()
+            {
+                let request = self.events.display.occupancy.calculate_grid();
+                self.events.request.calculate_grid.send(request);
+            }
+        });
+
+        let resolutions: BTreeMap<&String, f32> = self
+            .params
+            .grids
+            .iter()
+            .map(|(_, grid, _)| (&grid.name, grid.cell_size))
+            .collect();
+        if !resolutions.is_empty() {
+            let displayed = &mut self.events.site_request.displayed_grid;
+            let selected_text = displayed.0.clone().unwrap_or_else(|| "<none>".to_owned());
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Display"));
+                let mut selected = displayed.0.clone();
+                ComboBox::from_id_source("occupancy_displayed_grid")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for (name, cell_size) in &resolutions {
+                            ui.selectable_value(
+                                &mut selected,
+                                Some((*name).clone()),
+                                format!("{name} ({cell_size} m)"),
+                            );
+                        }
+                    });
+                if selected != displayed.0 {
+                    displayed.0 = selected;
+                }
+            });
+
+            self.show_comparison(ui);
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(tr(ui, "Generate Nav Graph"))
+                .on_hover_text(tr(
+                    ui,
+                    "Create a new nav graph from the free space of the occupancy grid",
+                ))
+                .clicked()
+            {
+                self.events
+                    .site_request
+                    .generate_nav_graph
+                    .send(GenerateNavGraph {
+                        robot_radius: self.events.display.occupancy.robot_radius,
+                    });
+            }
+            ui.label(tr(ui, "Robot radius"));
+            ui.add(
+                DragValue::new(&mut self.events.display.occupancy.robot_radius)
+                    .clamp_range(0.0..=f32::INFINITY)
+                    .speed(0.01),
+            );
+        });
+
+        let reachability = &self.params.reachability;
+        if reachability.location.is_some() {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{} cell(s) reachable, {} free cell(s) unreachable",
+                    reachability.reachable, reachability.unreachable,
+                ));
+                if ui.button(tr(ui, "Clear")).clicked() {
+                    self.events
+                        .site_request
+                        .check_reachability
+                        .send(CheckReachability::Clear);
+                }
+            });
+        }
+    }
+
+    fn show_comparison(&mut self, ui: &mut Ui) {
+        let grid_label = |e: Entity| -> String {
+            let Ok((_, grid, level)) = self.params.grids.get(e) else {
+                return "<none>".to_owned();
+            };
+            let level = self
+                .params
+                .level_names
+                .get(level.get())
+                .map(|name| name.0.as_str())
+                .unwrap_or("<unnamed level>");
+            format!("{} on {}", grid.name, level)
+        };
+
+        let display = &mut self.events.display.occupancy;
+        for (i, label) in ["Compare", "With"].into_iter().enumerate() {
+            let selected_text = display.compare[i]
+                .map(&grid_label)
+                .unwrap_or_else(|| "<none>".to_owned());
+            ui.horizontal(|ui| {
+                ui.label(label);
+                ComboBox::from_id_source(("occupancy_compare_grid", i))
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for (e, _, _) in &self.params.grids {
+                            ui.selectable_value(&mut display.compare[i], Some(e), grid_label(e));
+                        }
                     });
+            });
+        }
+
+        let mut request = None;
+        ui.horizontal(|ui| {
+            if ui
+                .button(tr(ui, "Show Differences"))
+                .on_hover_text(tr(
+                    ui,
+                    "Highlight the cells that are only occupied in one of the grids",
+                ))
+                .clicked()
+            {
+                if let [Some(base), Some(other)] = display.compare {
+                    request = Some(CompareGrids::Start { base, other });
                 }
             }
+            if ui.button(tr(ui, "Clear")).clicked() {
+                request = Some(CompareGrids::Clear);
+            }
         });
+
+        let comparison = &self.params.comparison;
+        if comparison.grids.is_some() {
+            ui.label(format!(
+                "{} cell(s) only in the first grid, {} cell(s) only in the second",
+                comparison.only_base, comparison.only_other,
+            ));
+        }
+
+        if let Some(request) = request {
+            self.events.site_request.compare_grids.send(request);
+        }
     }
 }
@@ -0,0 +1,209 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::{Hover, MultiSelection, Select, Selection, ToggleSelect},
+    localization::tr,
+    site::{Category, Change, LevelProperties, NameInSite, Pending, SiteID},
+    widgets::{AppEvents, Icons},
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{CollapsingHeader, ImageButton, Ui};
+use std::collections::BTreeMap;
+
+#[derive(Resource, Default)]
+pub struct OutlinerDisplay {
+    pub search: String,
+}
+
+#[derive(SystemParam)]
+pub struct OutlinerParams<'w, 's> {
+    pub elements: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Category,
+            &'static Parent,
+            Option<&'static NameInSite>,
+            Option<&'static SiteID>,
+            Option<&'static Visibility>,
+        ),
+        Without<Pending>,
+    >,
+    pub levels: Query<'w, 's, (Entity, &'static LevelProperties, &'static Parent)>,
+    pub selection: Res<'w, Selection>,
+    pub multi_selection: Res<'w, MultiSelection>,
+    pub toggle_select: EventWriter<'w, 's, ToggleSelect>,
+    pub icons: Res<'w, Icons>,
+    pub display: ResMut<'w, OutlinerDisplay>,
+}
+
+struct OutlinerRow {
+    entity: Entity,
+    name: String,
+    visible: Option<bool>,
+}
+
+pub struct ViewOutliner<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a mut OutlinerParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewOutliner<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a mut OutlinerParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let Some(site) = self.events.request.current_workspace.root else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Search"));
+            ui.text_edit_singleline(&mut self.params.display.search);
+        });
+        let search = self.params.display.search.to_lowercase();
+
+        // Site elements are listed first, followed by each level in order of
+        // elevation
+        let mut groups: Vec<(Entity, String)> = vec![(site, "Site".to_owned())];
+        let mut levels: Vec<_> = self
+            .params
+            .levels
+            .iter()
+            .filter(|(_, _, parent)| parent.get() == site)
+            .collect();
+        levels.sort_by(|(_, a, _), (_, b, _)| a.elevation.total_cmp(&b.elevation));
+        groups.extend(
+            levels
+                .into_iter()
+                .map(|(e, props, _)| (e, props.name.clone())),
+        );
+
+        let mut rows: BTreeMap<Entity, BTreeMap<Category, Vec<OutlinerRow>>> = BTreeMap::new();
+        for (e, category, parent, name, site_id, visibility) in &self.params.elements {
+            if matches!(category, Category::Site | Category::Level) {
+                continue;
+            }
+            let name = match (name, site_id) {
+                (Some(name), _) if !name.0.is_empty() => name.0.clone(),
+                (_, Some(id)) => format!("{} #{}", category.label(), id.0),
+                _ => format!("{} *", category.label()),
+            };
+            if !search.is_empty() && !name.to_lowercase().contains(&search) {
+                continue;
+            }
+            rows.entry(parent.get())
+                .or_default()
+                .entry(*category)
+                .or_default()
+                .push(OutlinerRow {
+                    entity: e,
+                    name,
+                    visible: visibility.map(|v| v.is_visible),
+                });
+        }
+
+        // Open the groups that hold a newly selected element so it can be
+        // seen in the list
+        let selected = self.params.selection.0;
+        let reveal = self.params.selection.is_changed() || !search.is_empty();
+        let mut picked = None;
+        let mut toggled_selection = None;
+        let mut hovered = None;
+        let mut toggled = None;
+        for (group, group_name) in groups {
+            let Some(categories) = rows.get_mut(&group) else {
+                continue;
+            };
+            let holds_selected = |rows: &Vec<OutlinerRow>| {
+                selected.map_or(false, |s| rows.iter().any(|row| row.entity == s))
+            };
+            let open = reveal && categories.values().any(|rows| holds_selected(rows));
+            CollapsingHeader::new(group_name)
+                .id_source(("outliner", group))
+                .open(open.then_some(true))
+                .show(ui, |ui| {
+                    for (category, elements) in categories.iter_mut() {
+                        elements.sort_by(|a, b| a.name.cmp(&b.name));
+                        let open = reveal && holds_selected(elements);
+                        CollapsingHeader::new(format!("{} ({})", category.label(), elements.len()))
+                            .id_source(("outliner", group, *category))
+                            .open(open.then_some(true))
+                            .show(ui, |ui| {
+                                for row in elements.iter() {
+                                    ui.horizontal(|ui| {
+                                        if let Some(visible) = row.visible {
+                                            let icon = if visible {
+                                                self.params.icons.opaque.egui()
+                                            } else {
+                                                self.params.icons.hidden.egui()
+                                            };
+                                            if ui
+                                                .add(ImageButton::new(icon, [18., 18.]))
+                                                .on_hover_text(if visible {
+                                                    "Hide"
+                                                } else {
+                                                    "Show"
+                                                })
+                                                .clicked()
+                                            {
+                                                toggled = Some((row.entity, !visible));
+                                            }
+                                        }
+                                        let is_selected = selected == Some(row.entity)
+                                            || self.params.multi_selection.contains(&row.entity);
+                                        let response = ui.selectable_label(is_selected, &row.name);
+                                        if selected == Some(row.entity)
+                                            && self.params.selection.is_changed()
+                                        {
+                                            response.scroll_to_me(None);
+                                        }
+                                        if response.clicked() && ui.input().modifiers.shift {
+                                            toggled_selection = Some(row.entity);
+                                        } else if response.clicked() {
+                                            picked = Some(row.entity);
+                                        } else if response.hovered() {
+                                            hovered = Some(row.entity);
+                                        }
+                                    });
+                                }
+                            });
+                    }
+                });
+        }
+
+        if let Some(e) = toggled_selection {
+            self.params.toggle_select.send(ToggleSelect(e));
+        } else if let Some(e) = picked {
+            self.events.request.select.send(Select(Some(e)));
+        } else if let Some(e) = hovered {
+            self.events.request.hover.send(Hover(Some(e)));
+        }
+        if let Some((e, is_visible)) = toggled {
+            self.events
+                .change
+                .visibility
+                .send(Change::new(Visibility { is_visible }, e));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    localization::tr,
+    site::{LocationTags, NameInSite, NavGraphMarker, PathPreview, Pending, PreviewPath},
+    widgets::AppEvents,
+    DisplayUnits,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{ComboBox, Ui};
+
+#[derive(Resource, Default)]
+pub struct PathPreviewDisplay {
+    pub graph: Option<Entity>,
+    pub start: Option<Entity>,
+    pub goal: Option<Entity>,
+}
+
+#[derive(SystemParam)]
+pub struct PathPreviewParams<'w, 's> {
+    pub locations:
+        Query<'w, 's, (Entity, &'static NameInSite), (With<LocationTags>, Without<Pending>)>,
+    pub graphs: Query<'w, 's, (Entity, &'static NameInSite), With<NavGraphMarker>>,
+    pub names: Query<'w, 's, &'static NameInSite>,
+    pub preview: Res<'w, PathPreview>,
+}
+
+pub struct ViewPathPreview<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a PathPreviewParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewPathPreview<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a PathPreviewParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    fn name_of(&self, e: Option<Entity>) -> String {
+        e.and_then(|e| self.params.names.get(e).ok())
+            .map(|name| name.0.clone())
+            .unwrap_or_else(|| "<none>".to_owned())
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let display = &self.events.display.path_preview;
+        let mut graph = display.graph;
+        let mut start = display.start;
+        let mut goal = display.goal;
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Graph"));
+            ComboBox::from_id_source("path_preview_graph")
+                .selected_text(self.name_of(graph))
+                .show_ui(ui, |ui| {
+                    for (e, name) in &self.params.graphs {
+                        ui.selectable_value(&mut graph, Some(e), &name.0);
+                    }
+                });
+        });
+
+        for (label, choice) in [("Start", &mut start), ("Goal", &mut goal)] {
+            ui.horizontal(|ui| {
+                ui.label(label);
+                ComboBox::from_id_source(("path_preview", label))
+                    .selected_text(self.name_of(*choice))
+                    .show_ui(ui, |ui| {
+                        for (e, name) in &self.params.locations {
+                            ui.selectable_value(choice, Some(e), &name.0);
+                        }
+                    });
+            });
+        }
+
+        let changed = graph != display.graph || start != display.start || goal != display.goal;
+        let mut request = None;
+        ui.horizontal(|ui| {
+            if ui.button(tr(ui, "Find Path")).clicked() || changed {
+                if let (Some(graph), Some(start), Some(goal)) = (graph, start, goal) {
+                    request = Some(PreviewPath::Between { graph, start, goal });
+                }
+            }
+            if ui.button(tr(ui, "Clear")).clicked() {
+                request = Some(PreviewPath::Clear);
+            }
+        });
+
+        match &self.params.preview.solution {
+            Some(Ok(solution)) => {
+                let length = DisplayUnits::of(ui).length.format(solution.length, 2);
+                ui.label(format!("Length: {length}"));
+                if !solution.doors.is_empty() {
+                    let doors: Vec<String> = solution
+                        .doors
+                        .iter()
+                        .map(|e| self.name_of(Some(*e)))
+                        .collect();
+                    ui.label(format!("Doors: {}", doors.join(", ")));
+                }
+                if !solution.lifts.is_empty() {
+                    let lifts: Vec<String> = solution
+                        .lifts
+                        .iter()
+                        .map(|e| self.name_of(Some(*e)))
+                        .collect();
+                    ui.label(format!("Lifts: {}", lifts.join(", ")));
+                }
+            }
+            Some(Err(msg)) => {
+                ui.label(msg);
+            }
+            None => {}
+        }
+
+        if changed {
+            let display = &mut self.events.display.path_preview;
+            display.graph = graph;
+            display.start = start;
+            display.goal = goal;
+        }
+
+        if let Some(request) = request {
+            self.events.site_request.preview_path.send(request);
+        }
+    }
+}
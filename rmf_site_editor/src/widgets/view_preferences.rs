@@ -0,0 +1,398 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{notify_error, notify_info, notify_warning},
+    localization::{available_languages, tr, tr_args, Translations},
+    widgets::{inspector::InspectLength, view_asset_library::AssetLibraryDisplay, AppEvents},
+    AngleUnit, LengthUnit, Preferences, UiTheme, MAX_RECENT_FILES,
+};
+use bevy::{prelude::*, tasks::Task};
+use bevy_egui::egui::{self, DragValue, Ui};
+use futures_lite::future;
+use std::path::{Path, PathBuf};
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::tasks::AsyncComputeTaskPool;
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::AsyncFileDialog;
+
+#[derive(Resource, Default)]
+pub struct PreferencesDisplay {
+    pub open: bool,
+    pub choosing_directory: Option<Task<Option<PathBuf>>>,
+}
+
+pub struct ViewPreferences<'a, 'w, 's> {
+    events: &'a mut AppEvents<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewPreferences<'a, 'w, 's> {
+    pub fn new(events: &'a mut AppEvents<'w, 's>) -> Self {
+        Self { events }
+    }
+
+    pub fn show(self, ctx: &egui::Context) {
+        let view = &mut self.events.display.view;
+        let mut open = view.preferences_display.open;
+        if !open {
+            return;
+        }
+
+        let mut new_preferences = view.preferences.clone();
+        let title = Translations::of(ctx).get("Preferences");
+        egui::Window::new(title)
+            .id(egui::Id::new("preferences_window"))
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Theme"));
+                    for theme in UiTheme::ALL {
+                        let label = tr(ui, theme.label());
+                        ui.radio_value(&mut new_preferences.theme, theme, label);
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Language"));
+                    egui::ComboBox::from_id_source("preferences_language")
+                        .selected_text(
+                            new_preferences
+                                .language
+                                .clone()
+                                .unwrap_or_else(|| "English".to_owned()),
+                        )
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut new_preferences.language, None, "English");
+                            for language in available_languages() {
+                                let label = language.clone();
+                                ui.selectable_value(
+                                    &mut new_preferences.language,
+                                    Some(language),
+                                    label,
+                                );
+                            }
+                        });
+                    if new_preferences.language.is_some()
+                        && ui
+                            .button(tr(ui, "Export Untranslated Text"))
+                            .on_hover_text(tr(
+                                ui,
+                                "Save the text that has been shown without a \
+                                translation so that it can be translated",
+                            ))
+                            .clicked()
+                    {
+                        match Translations::of(ui.ctx()).export_missing() {
+                            Ok(path) => {
+                                notify_info(None, format!("Saved untranslated text to {path:?}"));
+                            }
+                            Err(err) => {
+                                notify_error(
+                                    None,
+                                    format!("Unable to save untranslated text: {err}"),
+                                );
+                            }
+                        }
+                    }
+                });
+
+                ui.separator();
+                ui.heading(tr(ui, "Units"));
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Length"));
+                    for unit in LengthUnit::ALL {
+                        let label = tr(ui, unit.label());
+                        ui.radio_value(&mut new_preferences.units.length, unit, label);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Angle"));
+                    for unit in AngleUnit::ALL {
+                        let label = tr(ui, unit.label());
+                        ui.radio_value(&mut new_preferences.units.angle, unit, label);
+                    }
+                });
+                ui.small(tr(ui, "Sites are always saved in meters"));
+
+                ui.separator();
+                ui.heading(tr(ui, "Camera"));
+                let camera = &mut new_preferences.camera;
+                ui.checkbox(
+                    &mut camera.start_orthographic,
+                    tr(ui, "Start in orthographic view"),
+                );
+                for (label, speed) in [
+                    ("Zoom speed", &mut camera.zoom_speed),
+                    ("Pan speed", &mut camera.pan_speed),
+                    ("Orbit speed", &mut camera.orbit_speed),
+                ] {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(ui, label));
+                        ui.add(DragValue::new(speed).clamp_range(0.1_f32..=3.0).speed(0.01));
+                    });
+                }
+
+                ui.separator();
+                ui.heading(tr(ui, "Reference Grid"));
+                let grid = &mut new_preferences.grid;
+                ui.checkbox(&mut grid.visible, tr(ui, "Show the grid"));
+                ui.checkbox(&mut grid.snap, tr(ui, "Snap new anchors and dragged elements to the grid"));
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Spacing"));
+                    InspectLength::new(&mut grid.spacing)
+                        .clamp_range(0.01..=100.0)
+                        .speed(0.01)
+                        .show(ui);
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Subdivisions"));
+                    ui.add(DragValue::new(&mut grid.subdivisions).clamp_range(1..=20));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Extent"));
+                    ui.add(DragValue::new(&mut grid.extent).clamp_range(1..=500))
+                        .on_hover_text(tr(
+                            ui,
+                            "How many major cells the grid reaches from the origin",
+                        ));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Color"));
+                    ui.color_edit_button_rgba_unmultiplied(&mut grid.color);
+                });
+
+                ui.separator();
+                ui.heading(tr(ui, "Autosave"));
+                ui.horizontal(|ui| {
+                    ui.label(tr(ui, "Save every"));
+                    ui.add(
+                        DragValue::new(&mut new_preferences.autosave_minutes)
+                            .clamp_range(0..=120)
+                            .suffix(tr(ui, " min")),
+                    );
+                });
+                ui.small(tr(
+                    ui,
+                    if new_preferences.autosave_minutes == 0 {
+                        "Autosave is off"
+                    } else {
+                        "Changes are saved to a hidden file next to the workspace file. Only files that have been saved before are autosaved"
+                    },
+                ));
+
+                ui.separator();
+                ui.heading(tr(ui, "Asset Directories"));
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let display = &mut view.preferences_display;
+                    if ui.button(tr(ui, "Add Directory...")).clicked() {
+                        if display.choosing_directory.is_some() {
+                            notify_warning(None, "A directory is already being chosen!");
+                        } else {
+                            let future = AsyncComputeTaskPool::get().spawn(async move {
+                                let dir = AsyncFileDialog::new().pick_folder().await?;
+                                Some(dir.path().to_path_buf())
+                            });
+                            display.choosing_directory = Some(future);
+                        }
+                    }
+                }
+                let mut removed_dir = None;
+                for (i, dir) in new_preferences.asset_directories.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button("❌")
+                            .on_hover_text(tr(ui, "Remove directory"))
+                            .clicked()
+                        {
+                            removed_dir = Some(i);
+                        }
+                        ui.label(dir.to_string_lossy());
+                    });
+                }
+                if let Some(i) = removed_dir {
+                    new_preferences.asset_directories.remove(i);
+                }
+
+                ui.separator();
+                ui.heading(tr(ui, "Recent Files"));
+                ui.label(tr_args(
+                    ui,
+                    "{count} of {max} remembered, {pinned} pinned",
+                    &[
+                        ("count", new_preferences.recent_files.len().to_string()),
+                        ("max", MAX_RECENT_FILES.to_string()),
+                        ("pinned", new_preferences.pinned_files.len().to_string()),
+                    ],
+                ));
+                if ui
+                    .add_enabled(
+                        !new_preferences.recent_files.is_empty(),
+                        egui::Button::new(tr(ui, "Clear Recent Files")),
+                    )
+                    .clicked()
+                {
+                    new_preferences.recent_files.clear();
+                }
+                if ui
+                    .add_enabled(
+                        !new_preferences.pinned_files.is_empty(),
+                        egui::Button::new(tr(ui, "Unpin All")),
+                    )
+                    .clicked()
+                {
+                    new_preferences.pinned_files.clear();
+                }
+            });
+
+        if new_preferences != *view.preferences {
+            *view.preferences = new_preferences;
+        }
+        if !open {
+            view.preferences_display.open = false;
+        }
+    }
+}
+
+/// Something the user did with an entry of the recent files
+pub enum RecentFileAction {
+    Open(PathBuf),
+    /// Pin or unpin the file
+    Pin(PathBuf, bool),
+}
+
+impl RecentFileAction {
+    /// Pin or unpin the file if that was requested, and return the file if it
+    /// should be opened
+    pub fn apply(self, preferences: &mut Preferences) -> Option<PathBuf> {
+        match self {
+            RecentFileAction::Open(file) => Some(file),
+            RecentFileAction::Pin(file, pinned) => {
+                preferences.pin_file(&file, pinned);
+                None
+            }
+        }
+    }
+}
+
+/// Menu entries for the pinned files followed by the recent files
+pub fn show_recent_files(ui: &mut Ui, preferences: &Preferences) -> Option<RecentFileAction> {
+    let recent: Vec<&PathBuf> = preferences
+        .recent_files
+        .iter()
+        .filter(|file| !preferences.is_pinned(file))
+        .collect();
+    if preferences.pinned_files.is_empty() && recent.is_empty() {
+        ui.label(tr(ui, "No recent files"));
+        return None;
+    }
+
+    let mut action = None;
+    for file in &preferences.pinned_files {
+        if let Some(a) = show_recent_file(ui, file, true) {
+            action = Some(a);
+        }
+    }
+    if !preferences.pinned_files.is_empty() && !recent.is_empty() {
+        ui.separator();
+    }
+    for file in recent {
+        if let Some(a) = show_recent_file(ui, file, false) {
+            action = Some(a);
+        }
+    }
+    action
+}
+
+fn show_recent_file(ui: &mut Ui, file: &Path, pinned: bool) -> Option<RecentFileAction> {
+    let mut action = None;
+    ui.horizontal(|ui| {
+        let name = file
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.to_string_lossy().into_owned());
+        let exists = file.exists();
+        let hover = if exists {
+            file.to_string_lossy().into_owned()
+        } else {
+            tr_args(
+                ui,
+                "{file} can no longer be found",
+                &[("file", file.to_string_lossy().into_owned())],
+            )
+        };
+        if ui
+            .selectable_label(pinned, "📌")
+            .on_hover_text(tr(ui, if pinned { "Unpin" } else { "Pin" }))
+            .clicked()
+        {
+            action = Some(RecentFileAction::Pin(file.to_path_buf(), !pinned));
+        }
+        if ui
+            .add_enabled(exists, egui::Button::new(name))
+            .on_hover_text(hover.clone())
+            .on_disabled_hover_text(hover)
+            .clicked()
+        {
+            action = Some(RecentFileAction::Open(file.to_path_buf()));
+        }
+    });
+    action
+}
+
+pub fn resolve_preferences_asset_directory(
+    mut display: ResMut<PreferencesDisplay>,
+    mut preferences: ResMut<Preferences>,
+) {
+    let Some(task) = &mut display.choosing_directory else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    display.choosing_directory = None;
+
+    if let Some(dir) = result {
+        if !preferences.asset_directories.contains(&dir) {
+            preferences.asset_directories.push(dir);
+        }
+    }
+}
+
+/// Make sure every asset directory in the preferences is part of the asset
+/// library
+pub fn add_preferred_asset_directories(
+    preferences: Res<Preferences>,
+    mut library: ResMut<AssetLibraryDisplay>,
+) {
+    if !preferences.is_changed() {
+        return;
+    }
+
+    let mut added = false;
+    for dir in &preferences.asset_directories {
+        if !library.directories.contains(dir) {
+            library.directories.push(dir.clone());
+            added = true;
+        }
+    }
+    if added {
+        library.scan();
+    }
+}
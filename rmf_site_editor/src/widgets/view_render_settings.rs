@@ -0,0 +1,156 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{
+    localization::tr, widgets::AppEvents, GraphicsPreset, RenderSettings, MSAA_SAMPLES,
+    SHADOW_MAP_SIZES, TEXTURE_SIZE_LIMITS,
+};
+use bevy_egui::egui::{ComboBox, DragValue, Ui};
+
+pub struct ViewRenderSettings<'a, 'w, 's> {
+    events: &'a mut AppEvents<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewRenderSettings<'a, 'w, 's> {
+    pub fn new(events: &'a mut AppEvents<'w, 's>) -> Self {
+        Self { events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let settings = &mut self.events.display.render_settings;
+        let mut new_settings = settings.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Preset"));
+            let current = new_settings.current_preset();
+            for preset in GraphicsPreset::ALL {
+                if ui
+                    .selectable_label(current == Some(preset), preset.label())
+                    .on_hover_text(preset.description())
+                    .clicked()
+                {
+                    new_settings = RenderSettings::preset(preset);
+                }
+            }
+        });
+        if new_settings.current_preset().is_none() {
+            ui.small(tr(ui, "Custom settings"));
+        }
+
+        ui.separator();
+        ui.checkbox(&mut new_settings.shadows, tr(ui, "Shadows"));
+        ui.add_enabled_ui(new_settings.shadows, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Shadow map"));
+                ComboBox::from_id_source("render_settings_shadow_map")
+                    .selected_text(format!("{}", new_settings.shadow_map_size))
+                    .show_ui(ui, |ui| {
+                        for size in SHADOW_MAP_SIZES {
+                            ui.selectable_value(
+                                &mut new_settings.shadow_map_size,
+                                size,
+                                format!("{size}"),
+                            );
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(ui, "Shadow distance"));
+                ui.add(
+                    DragValue::new(&mut new_settings.shadow_distance)
+                        .clamp_range(1_f32..=1000.0)
+                        .speed(1.0)
+                        .suffix(" m"),
+                );
+            });
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Anti-aliasing"));
+            for samples in MSAA_SAMPLES {
+                let label = if samples == 1 {
+                    "Off".to_owned()
+                } else {
+                    format!("{samples}x")
+                };
+                ui.selectable_value(&mut new_settings.msaa_samples, samples, label);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Texture limit"));
+            let text = match new_settings.max_texture_size {
+                Some(size) => format!("{size}"),
+                None => "None".to_owned(),
+            };
+            ComboBox::from_id_source("render_settings_texture_limit")
+                .selected_text(text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut new_settings.max_texture_size, None, tr(ui, "None"));
+                    for size in TEXTURE_SIZE_LIMITS {
+                        ui.selectable_value(
+                            &mut new_settings.max_texture_size,
+                            Some(size),
+                            format!("{size}"),
+                        );
+                    }
+                });
+        })
+        .response
+        .on_hover_text(tr(
+            ui,
+            "Applies to textures that are loaded after it changes",
+        ));
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Model triangles"));
+            ui.add(
+                DragValue::new(&mut new_settings.model_triangle_budget)
+                    .clamp_range(1000..=10_000_000)
+                    .speed(1000.0),
+            );
+        })
+        .response
+        .on_hover_text(tr(
+            ui,
+            "Models with more triangles than this are simplified with distance",
+        ));
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut new_settings.bloom, tr(ui, "Bloom"))
+                .on_hover_text(tr(
+                    ui,
+                    "Make bright surfaces glow. This renders the viewport in HDR.",
+                ));
+            if new_settings.bloom {
+                ui.add(
+                    DragValue::new(&mut new_settings.bloom_intensity)
+                        .clamp_range(0_f32..=1.0)
+                        .speed(0.01),
+                );
+            }
+        });
+        ui.checkbox(&mut new_settings.tonemapping, tr(ui, "Tone mapping"))
+            .on_hover_text(tr(
+                ui,
+                "Keep bright lights from washing out the colors of surfaces",
+            ));
+
+        // Only assign when something changed so the settings are not saved
+        // every frame
+        if new_settings != **settings {
+            **settings = new_settings;
+        }
+    }
+}
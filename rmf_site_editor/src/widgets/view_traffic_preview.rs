@@ -0,0 +1,110 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    localization::tr,
+    site::{NameInSite, NavGraphMarker, PreviewTraffic, TrafficPreview},
+    widgets::AppEvents,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{ComboBox, DragValue, Ui};
+
+#[derive(Resource)]
+pub struct TrafficPreviewDisplay {
+    pub graph: Option<Entity>,
+    pub robots: usize,
+}
+
+impl Default for TrafficPreviewDisplay {
+    fn default() -> Self {
+        Self {
+            graph: None,
+            robots: 5,
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct TrafficPreviewParams<'w, 's> {
+    pub graphs: Query<'w, 's, (Entity, &'static NameInSite), With<NavGraphMarker>>,
+    pub preview: Res<'w, TrafficPreview>,
+}
+
+pub struct ViewTrafficPreview<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a TrafficPreviewParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewTrafficPreview<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a TrafficPreviewParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let display = &mut self.events.display.traffic_preview;
+        let selected_name = display
+            .graph
+            .and_then(|e| self.params.graphs.get(e).ok())
+            .map(|(_, name)| name.0.clone())
+            .unwrap_or_else(|| "<none>".to_owned());
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Graph"));
+            ComboBox::from_id_source("traffic_preview_graph")
+                .selected_text(selected_name)
+                .show_ui(ui, |ui| {
+                    for (e, name) in &self.params.graphs {
+                        ui.selectable_value(&mut display.graph, Some(e), &name.0);
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Robots"));
+            ui.add(DragValue::new(&mut display.robots).clamp_range(1..=100));
+        });
+
+        let mut request = None;
+        ui.horizontal(|ui| {
+            if ui.button(tr(ui, "Start")).clicked() {
+                if let Some(graph) = display.graph {
+                    request = Some(PreviewTraffic::Start {
+                        graph,
+                        robots: display.robots,
+                    });
+                }
+            }
+            if ui.button(tr(ui, "Stop")).clicked() {
+                request = Some(PreviewTraffic::Stop);
+            }
+        });
+
+        if self.params.preview.graph.is_some() && self.params.preview.blocked > 0 {
+            ui.label(format!(
+                "{} robot(s) are blocked, which may indicate a deadlock",
+                self.params.preview.blocked
+            ));
+        }
+
+        if let Some(request) = request {
+            self.events.site_request.preview_traffic.send(request);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::{
+    icons::Icons,
+    keyboard::{on_hover_shortcut, KeyAction, KeyCapture, Keymap},
+    localization::tr,
+    site::{SiteID, UndoHistory, UndoRequest},
+    widgets::{inspector::SelectionWidget, AppEvents},
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{
+    egui::{Button, RichText, Ui},
+    EguiContext,
+};
+
+#[derive(SystemParam)]
+pub struct UndoHistoryParams<'w, 's> {
+    pub history: Res<'w, UndoHistory>,
+    pub requests: EventWriter<'w, 's, UndoRequest>,
+    pub site_id: Query<'w, 's, &'static SiteID>,
+    pub exists: Query<'w, 's, ()>,
+    pub icons: Res<'w, Icons>,
+}
+
+pub struct ViewUndoHistory<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a mut UndoHistoryParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewUndoHistory<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a mut UndoHistoryParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let history = &self.params.history;
+        let keymap = &self.events.display.view.keymap;
+        let mut request = None;
+        ui.horizontal(|ui| {
+            let undo =
+                Button::new(tr(ui, "Undo")).shortcut_text(keymap.shortcut_text(KeyAction::Undo));
+            let response = ui.add_enabled(history.can_undo(), undo);
+            if on_hover_shortcut(response, KeyAction::Undo, "Undo the last change").clicked() {
+                request = Some(UndoRequest::Undo);
+            }
+            let redo =
+                Button::new(tr(ui, "Redo")).shortcut_text(keymap.shortcut_text(KeyAction::Redo));
+            let response = ui.add_enabled(history.can_redo(), redo);
+            if on_hover_shortcut(response, KeyAction::Redo, "Redo the last undone change").clicked()
+            {
+                request = Some(UndoRequest::Redo);
+            }
+        });
+        ui.separator();
+
+        if ui
+            .selectable_label(history.applied == 0, tr(ui, "Start"))
+            .on_hover_text(tr(ui, "Undo every change in the history"))
+            .clicked()
+        {
+            request = Some(UndoRequest::JumpTo(0));
+        }
+
+        for (i, entry) in history.entries.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let mut text = RichText::new(&entry.description);
+                if i >= history.applied {
+                    // Changes that have been undone can still be redone
+                    text = text.weak();
+                }
+                if ui
+                    .selectable_label(history.applied == i + 1, text)
+                    .on_hover_text(tr(ui, "Undo or redo changes until this one"))
+                    .clicked()
+                {
+                    request = Some(UndoRequest::JumpTo(i + 1));
+                }
+                let element = entry.elements.first().copied();
+                if let Some(element) = element.filter(|e| self.params.exists.contains(*e)) {
+                    SelectionWidget::new(
+                        element,
+                        self.params.site_id.get(element).ok().copied(),
+                        self.params.icons.as_ref(),
+                        self.events,
+                    )
+                    .show(ui);
+                }
+            });
+        }
+
+        if let Some(request) = request {
+            self.params.requests.send(request);
+        }
+    }
+}
+
+pub fn handle_undo_shortcuts(
+    keyboard_input: Res<Input<KeyCode>>,
+    keymap: Res<Keymap>,
+    capture: Res<KeyCapture>,
+    mut egui_context: ResMut<EguiContext>,
+    mut requests: EventWriter<UndoRequest>,
+) {
+    let egui_context = egui_context.ctx_mut();
+    // Text fields have their own undo, so only undo changes to the workspace
+    // when no text is being edited
+    if egui_context.wants_keyboard_input() || capture.0.is_some() {
+        return;
+    }
+
+    if keymap.just_pressed(KeyAction::Undo, &keyboard_input) {
+        requests.send(UndoRequest::Undo);
+    }
+
+    if keymap.just_pressed(KeyAction::Redo, &keyboard_input) {
+        requests.send(UndoRequest::Redo);
+    }
+}
@@ -0,0 +1,144 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{notify_error, notify_warning},
+    icons::Icons,
+    localization::tr,
+    site::SiteID,
+    widgets::{inspector::SelectionWidget, AppEvents},
+    workcell::{CompareWorkcells, WorkcellChangeKind, WorkcellDiff},
+    CurrentWorkspace,
+};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use bevy_egui::egui::{Color32, RichText, Ui};
+use futures_lite::future;
+use rmf_site_format::Workcell;
+
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::AsyncFileDialog;
+
+#[derive(Resource, Default)]
+pub struct WorkcellDiffDisplay {
+    pub choosing_file: Option<Task<Option<Workcell>>>,
+}
+
+#[derive(SystemParam)]
+pub struct WorkcellDiffParams<'w, 's> {
+    pub diff: Res<'w, WorkcellDiff>,
+    pub display: ResMut<'w, WorkcellDiffDisplay>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub site_id: Query<'w, 's, &'static SiteID>,
+    pub icons: Res<'w, Icons>,
+}
+
+pub struct ViewWorkcellDiff<'a, 'w1, 's1, 'w2, 's2> {
+    params: &'a mut WorkcellDiffParams<'w1, 's1>,
+    events: &'a mut AppEvents<'w2, 's2>,
+}
+
+impl<'a, 'w1, 's1, 'w2, 's2> ViewWorkcellDiff<'a, 'w1, 's1, 'w2, 's2> {
+    pub fn new(
+        params: &'a mut WorkcellDiffParams<'w1, 's1>,
+        events: &'a mut AppEvents<'w2, 's2>,
+    ) -> Self {
+        Self { params, events }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if ui
+                .button(tr(ui, "Compare With File..."))
+                .on_hover_text(tr(
+                    ui,
+                    "Show how another workcell file differs from this workcell",
+                ))
+                .clicked()
+            {
+                if self.params.display.choosing_file.is_some() {
+                    notify_warning(None, "A file is already being chosen");
+                } else {
+                    let future = AsyncComputeTaskPool::get().spawn(async move {
+                        let file = AsyncFileDialog::new().pick_file().await?;
+                        match Workcell::from_bytes(&file.read().await) {
+                            Ok(workcell) => Some(workcell),
+                            Err(err) => {
+                                notify_error(None, format!("Unable to parse file:\n{err}"));
+                                None
+                            }
+                        }
+                    });
+                    self.params.display.choosing_file = Some(future);
+                }
+            }
+        }
+
+        let diff = &self.params.diff;
+        if diff.root.is_none() || diff.root != self.params.current_workspace.root {
+            return;
+        }
+        if diff.changes.is_empty() {
+            ui.label(tr(ui, "The workcells are the same"));
+            return;
+        }
+
+        for change in &diff.changes {
+            ui.horizontal(|ui| {
+                let (symbol, color) = match change.kind {
+                    WorkcellChangeKind::Added => ("+", Color32::GREEN),
+                    WorkcellChangeKind::Removed => ("-", ui.visuals().error_fg_color),
+                    WorkcellChangeKind::Modified => ("~", ui.visuals().warn_fg_color),
+                };
+                ui.label(RichText::new(symbol).color(color));
+                if let Some(e) = change.entity {
+                    SelectionWidget::new(
+                        e,
+                        self.params.site_id.get(e).ok().copied(),
+                        self.params.icons.as_ref(),
+                        self.events,
+                    )
+                    .show(ui);
+                }
+                ui.label(&change.description);
+            });
+        }
+    }
+}
+
+pub fn resolve_workcell_diff_file(
+    mut display: ResMut<WorkcellDiffDisplay>,
+    mut compare: EventWriter<CompareWorkcells>,
+    current_workspace: Res<CurrentWorkspace>,
+) {
+    let Some(task) = &mut display.choosing_file else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    display.choosing_file = None;
+
+    let (Some(other), Some(root)) = (result, current_workspace.root) else {
+        return;
+    };
+    compare.send(CompareWorkcells { root, other });
+}
@@ -0,0 +1,132 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    interaction::Selection,
+    localization::tr,
+    workcell::{
+        is_valid_template_name, InsertWorkcellTemplate, SaveWorkcellTemplate,
+        WorkcellTemplateLibrary,
+    },
+    CurrentWorkspace,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui::{Button, Ui};
+use rmf_site_format::Anchor;
+
+#[derive(Resource, Default)]
+pub struct WorkcellTemplateDisplay {
+    /// Name to save the next template with
+    pub name: String,
+    /// Prefix for the names of the elements of inserted templates
+    pub prefix: String,
+}
+
+#[derive(SystemParam)]
+pub struct WorkcellTemplateParams<'w, 's> {
+    pub library: ResMut<'w, WorkcellTemplateLibrary>,
+    pub display: ResMut<'w, WorkcellTemplateDisplay>,
+    pub anchors: Query<'w, 's, &'static Anchor>,
+    pub selection: Res<'w, Selection>,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub save: EventWriter<'w, 's, SaveWorkcellTemplate>,
+    pub insert: EventWriter<'w, 's, InsertWorkcellTemplate>,
+}
+
+pub struct ViewWorkcellTemplates<'a, 'w, 's> {
+    params: &'a mut WorkcellTemplateParams<'w, 's>,
+}
+
+impl<'a, 'w, 's> ViewWorkcellTemplates<'a, 'w, 's> {
+    pub fn new(params: &'a mut WorkcellTemplateParams<'w, 's>) -> Self {
+        Self { params }
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let Some(root) = self.params.current_workspace.root else {
+            return;
+        };
+        // Templates are saved from and inserted beneath the selected frame
+        let selected_frame = self
+            .params
+            .selection
+            .0
+            .filter(|e| matches!(self.params.anchors.get(*e), Ok(Anchor::Pose3D(_))));
+
+        ui.label(tr(ui, "Save selected frame"));
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.params.display.name);
+            let valid_name = is_valid_template_name(&self.params.display.name);
+            let can_save = selected_frame.is_some() && valid_name;
+            if ui
+                .add_enabled(can_save, Button::new(tr(ui, "Save")))
+                .on_disabled_hover_text(if valid_name {
+                    "Select a frame to save"
+                } else {
+                    "Enter a name that can be used as a file name"
+                })
+                .on_hover_text(tr(
+                    ui,
+                    "Save the frame and everything attached to it as a template",
+                ))
+                .clicked()
+            {
+                if let Some(frame) = selected_frame {
+                    self.params.save.send(SaveWorkcellTemplate {
+                        frame,
+                        name: self.params.display.name.clone(),
+                    });
+                }
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr(ui, "Name prefix"));
+            ui.text_edit_singleline(&mut self.params.display.prefix);
+        });
+        if ui.button(tr(ui, "Rescan")).clicked() {
+            self.params.library.scan();
+        }
+        if self.params.library.templates.is_empty() {
+            ui.label(tr(ui, "No templates have been saved"));
+            return;
+        }
+
+        let parent = selected_frame.unwrap_or(root);
+        let mut chosen = None;
+        for (name, path) in &self.params.library.templates {
+            ui.horizontal(|ui| {
+                if ui
+                    .button(tr(ui, "Insert"))
+                    .on_hover_text(path.to_string_lossy())
+                    .clicked()
+                {
+                    chosen = Some(path.clone());
+                }
+                ui.label(name);
+            });
+        }
+        if let Some(template) = chosen {
+            self.params.insert.send(InsertWorkcellTemplate {
+                template,
+                parent,
+                prefix: self.params.display.prefix.clone(),
+            });
+        }
+    }
+}
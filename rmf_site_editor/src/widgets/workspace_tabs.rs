@@ -0,0 +1,188 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    localization::{tr, tr_args, Translations},
+    site::DefaultFile,
+    BackgroundUnsavedChanges, ChangeCurrentWorkspace, CloseWorkspace, CurrentWorkspace,
+    LoadWorkspace, UnsavedChanges,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{egui, EguiContext};
+use rmf_site_format::{SiteProperties, WorkcellProperties};
+
+#[derive(Resource, Default)]
+pub struct WorkspaceTabsDisplay {
+    /// A workspace with unsaved changes that the user has asked to close
+    pub confirm_close: Option<Entity>,
+}
+
+#[derive(SystemParam)]
+pub struct WorkspaceTabsParams<'w, 's> {
+    pub sites: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static SiteProperties,
+            Option<&'static DefaultFile>,
+        ),
+    >,
+    pub workcells: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static WorkcellProperties,
+            Option<&'static DefaultFile>,
+        ),
+    >,
+    pub current_workspace: Res<'w, CurrentWorkspace>,
+    pub unsaved: Res<'w, UnsavedChanges>,
+    pub background_unsaved: Res<'w, BackgroundUnsavedChanges>,
+    pub display: ResMut<'w, WorkspaceTabsDisplay>,
+    pub change_workspace: EventWriter<'w, 's, ChangeCurrentWorkspace>,
+    pub close_workspace: EventWriter<'w, 's, CloseWorkspace>,
+    pub load_workspace: EventWriter<'w, 's, LoadWorkspace>,
+}
+
+struct WorkspaceTab {
+    root: Entity,
+    name: String,
+    file: Option<String>,
+    is_site: bool,
+    unsaved: bool,
+}
+
+impl WorkspaceTabsParams<'_, '_> {
+    fn tabs(&self) -> Vec<WorkspaceTab> {
+        let is_unsaved = |root| {
+            if self.current_workspace.root == Some(root) {
+                self.unsaved.0
+            } else {
+                self.background_unsaved.0.contains(&root)
+            }
+        };
+        let file = |file: Option<&DefaultFile>| file.map(|f| f.0.to_string_lossy().into_owned());
+
+        let mut tabs: Vec<WorkspaceTab> = self
+            .sites
+            .iter()
+            .map(|(root, properties, default_file)| WorkspaceTab {
+                root,
+                name: properties.name.clone(),
+                file: file(default_file),
+                is_site: true,
+                unsaved: is_unsaved(root),
+            })
+            .chain(
+                self.workcells
+                    .iter()
+                    .map(|(root, properties, default_file)| WorkspaceTab {
+                        root,
+                        name: properties.name.clone(),
+                        file: file(default_file),
+                        is_site: false,
+                        unsaved: is_unsaved(root),
+                    }),
+            )
+            .collect();
+        // Keep the tabs in the order that the workspaces were opened
+        tabs.sort_by_key(|tab| tab.root);
+        tabs
+    }
+}
+
+/// Show a tab for each open site and workcell, below the menu bar
+pub fn show_workspace_tabs(mut egui_context: ResMut<EguiContext>, mut params: WorkspaceTabsParams) {
+    let tabs = params.tabs();
+    let ctx = egui_context.ctx_mut();
+    egui::TopBottomPanel::top("workspace_tabs").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            for tab in &tabs {
+                let current = params.current_workspace.root == Some(tab.root);
+                let icon = if tab.is_site { "🏢" } else { "🔧" };
+                let marker = if tab.unsaved { " ●" } else { "" };
+                let hover = tab
+                    .file
+                    .clone()
+                    .unwrap_or_else(|| tr(ui, "Not saved to a file yet"));
+                if ui
+                    .selectable_label(current, format!("{icon} {}{marker}", tab.name))
+                    .on_hover_text(hover)
+                    .clicked()
+                    && !current
+                {
+                    params
+                        .change_workspace
+                        .send(ChangeCurrentWorkspace { root: tab.root });
+                }
+                if ui
+                    .small_button("❌")
+                    .on_hover_text(tr(ui, "Close"))
+                    .clicked()
+                {
+                    if tab.unsaved {
+                        params.display.confirm_close = Some(tab.root);
+                    } else {
+                        params
+                            .close_workspace
+                            .send(CloseWorkspace { root: tab.root });
+                    }
+                }
+                ui.separator();
+            }
+            if ui
+                .button("➕")
+                .on_hover_text(tr(ui, "Open another file in a new tab"))
+                .clicked()
+            {
+                params.load_workspace.send(LoadWorkspace::Dialog);
+            }
+        });
+    });
+
+    let Some(root) = params.display.confirm_close else {
+        return;
+    };
+    let Some(tab) = tabs.iter().find(|tab| tab.root == root) else {
+        params.display.confirm_close = None;
+        return;
+    };
+    let title = Translations::of(ctx).get("Unsaved Changes");
+    egui::Window::new(title)
+        .id(egui::Id::new("confirm_close_workspace"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0., 0.))
+        .show(ctx, |ui| {
+            ui.label(tr_args(
+                ui,
+                "{name} has changes that have not been saved. Close it anyway?",
+                &[("name", tab.name.clone())],
+            ));
+            ui.horizontal(|ui| {
+                if ui.button(tr(ui, "Close Without Saving")).clicked() {
+                    params.close_workspace.send(CloseWorkspace { root });
+                    params.display.confirm_close = None;
+                }
+                if ui.button(tr(ui, "Cancel")).clicked() {
+                    params.display.confirm_close = None;
+                }
+            });
+        });
+}
@@ -0,0 +1,463 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{console::notify_error, site::SiteID, workcell::generate_workcell};
+use bevy::{ecs::system::SystemState, prelude::*};
+use rmf_site_format::{Anchor, Pose, Workcell};
+use std::collections::{BTreeMap, HashMap};
+
+/// Compare a workcell against another workcell file. Since site IDs are not
+/// kept between saves, frames and joints are matched up by their names, and
+/// visuals, collisions and sensors by their names along with the name of their
+/// parent. Elements without a name can only be matched up by their SiteID.
+pub struct CompareWorkcells {
+    pub root: Entity,
+    pub other: Workcell,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkcellChangeKind {
+    /// The element is only in the other file
+    Added,
+    /// The element is only in the current workcell
+    Removed,
+    Modified,
+}
+
+/// A difference between the current workcell and the other workcell file
+#[derive(Clone, Debug)]
+pub struct WorkcellChange {
+    pub kind: WorkcellChangeKind,
+    pub description: String,
+    /// The element of the current workcell that the change applies to, if it
+    /// exists in the current workcell
+    pub entity: Option<Entity>,
+}
+
+#[derive(Resource, Default)]
+pub struct WorkcellDiff {
+    pub root: Option<Entity>,
+    pub changes: Vec<WorkcellChange>,
+}
+
+/// The name that an element is matched up by
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ElementName {
+    Root,
+    Named(String),
+    /// Elements without a name are matched up by their SiteID
+    Unnamed(u32),
+}
+
+impl std::fmt::Display for ElementName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Root => write!(f, "<root>"),
+            Self::Named(name) => write!(f, "{name}"),
+            Self::Unnamed(id) => write!(f, "#{id}"),
+        }
+    }
+}
+
+/// Identifies an element across workcells. The names of geometry and sensors
+/// only need to be unique within their parent, so their parent is included.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ElementKey {
+    category: &'static str,
+    parent: Option<ElementName>,
+    name: ElementName,
+}
+
+impl std::fmt::Display for ElementKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", self.category)?;
+        if let Some(parent) = &self.parent {
+            write!(f, "{parent}/")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// The parts of an element that are compared between workcells
+#[derive(Clone, Debug)]
+struct ElementSummary {
+    id: u32,
+    parent: ElementName,
+    pose: Option<Pose>,
+    /// Everything else about the element, which is only checked for equality
+    details: String,
+}
+
+/// Summarize the elements of a workcell, keyed by how they are matched up
+fn summarize(workcell: &Workcell) -> BTreeMap<ElementKey, ElementSummary> {
+    let frame_name = |id: u32| -> ElementName {
+        if id == workcell.id {
+            return ElementName::Root;
+        }
+        let name = match (workcell.frames.get(&id), workcell.joints.get(&id)) {
+            (Some(frame), _) => frame.bundle.name.as_ref().map(|name| name.0.as_str()),
+            (None, Some(joint)) => Some(joint.bundle.name.0.as_str()),
+            (None, None) => None,
+        };
+        match name {
+            Some(name) if !name.is_empty() => ElementName::Named(name.to_owned()),
+            _ => ElementName::Unnamed(id),
+        }
+    };
+    let model_name = |id: u32, name: &str| -> ElementName {
+        if name.is_empty() {
+            ElementName::Unnamed(id)
+        } else {
+            ElementName::Named(name.to_owned())
+        }
+    };
+
+    let mut elements = BTreeMap::new();
+    for (id, frame) in &workcell.frames {
+        let pose = match &frame.bundle.anchor {
+            Anchor::Pose3D(pose) => Some(*pose),
+            _ => None,
+        };
+        elements.insert(
+            ElementKey {
+                category: "Frame",
+                parent: None,
+                name: frame_name(*id),
+            },
+            ElementSummary {
+                id: *id,
+                parent: frame_name(frame.parent),
+                pose,
+                details: format!("{:?}", frame.bundle.inertial),
+            },
+        );
+    }
+    for (id, joint) in &workcell.joints {
+        elements.insert(
+            ElementKey {
+                category: "Joint",
+                parent: None,
+                name: frame_name(*id),
+            },
+            ElementSummary {
+                id: *id,
+                parent: frame_name(joint.parent),
+                pose: None,
+                details: format!("{:?}", joint.bundle.properties),
+            },
+        );
+    }
+    for (category, models) in [
+        ("Visual", &workcell.visuals),
+        ("Collision", &workcell.collisions),
+    ] {
+        for (id, model) in models {
+            let parent = frame_name(model.parent);
+            elements.insert(
+                ElementKey {
+                    category,
+                    parent: Some(parent.clone()),
+                    name: model_name(*id, &model.bundle.name),
+                },
+                ElementSummary {
+                    id: *id,
+                    parent,
+                    pose: Some(model.bundle.pose),
+                    details: format!("{:?}", model.bundle.geometry),
+                },
+            );
+        }
+    }
+    for (id, sensor) in &workcell.sensors {
+        let parent = frame_name(sensor.parent);
+        elements.insert(
+            ElementKey {
+                category: "Sensor",
+                parent: Some(parent.clone()),
+                name: model_name(*id, &sensor.bundle.name),
+            },
+            ElementSummary {
+                id: *id,
+                parent,
+                pose: Some(sensor.bundle.pose),
+                details: format!("{:?}", sensor.bundle.properties),
+            },
+        );
+    }
+    elements
+}
+
+/// Describe how far an element moved between two poses
+fn pose_delta(from: &Pose, to: &Pose) -> Option<String> {
+    let translation = Vec3::from_array(to.trans) - Vec3::from_array(from.trans);
+    let rotation = from.rot.as_bevy_quat().angle_between(to.rot.as_bevy_quat());
+    if translation.length() < 1e-6 && rotation < 1e-6 {
+        return None;
+    }
+    Some(format!(
+        "moved by [{:.4}, {:.4}, {:.4}] m, rotated by {:.3} deg",
+        translation.x,
+        translation.y,
+        translation.z,
+        rotation.to_degrees()
+    ))
+}
+
+/// A difference between two workcells, before it is matched up with the
+/// entities of the current workcell
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkcellElementChange {
+    pub kind: WorkcellChangeKind,
+    pub description: String,
+    /// The id of the element in the current workcell, if it is there
+    pub id: Option<u32>,
+}
+
+/// Find the elements that were added, removed, or modified in the other
+/// workcell compared to the current one
+pub fn diff_workcells(current: &Workcell, other: &Workcell) -> Vec<WorkcellElementChange> {
+    let current_elements = summarize(current);
+    let other_elements = summarize(other);
+    let mut changes = Vec::new();
+    for (key, element) in &current_elements {
+        let Some(other_element) = other_elements.get(key) else {
+            changes.push(WorkcellElementChange {
+                kind: WorkcellChangeKind::Removed,
+                description: format!("{key} was removed"),
+                id: Some(element.id),
+            });
+            continue;
+        };
+
+        let mut differences = Vec::new();
+        if element.parent != other_element.parent {
+            differences.push(format!(
+                "moved from {} to {}",
+                element.parent, other_element.parent
+            ));
+        }
+        if let (Some(pose), Some(other_pose)) = (&element.pose, &other_element.pose) {
+            differences.extend(pose_delta(pose, other_pose));
+        }
+        if element.details != other_element.details {
+            differences.push("properties changed".to_owned());
+        }
+        if !differences.is_empty() {
+            changes.push(WorkcellElementChange {
+                kind: WorkcellChangeKind::Modified,
+                description: format!("{key} {}", differences.join(", ")),
+                id: Some(element.id),
+            });
+        }
+    }
+    for key in other_elements.keys() {
+        if !current_elements.contains_key(key) {
+            changes.push(WorkcellElementChange {
+                kind: WorkcellChangeKind::Added,
+                description: format!("{key} was added"),
+                id: None,
+            });
+        }
+    }
+    changes
+}
+
+pub fn compare_workcells(world: &mut World) {
+    let requests: Vec<_> = world
+        .resource_mut::<Events<CompareWorkcells>>()
+        .drain()
+        .collect();
+    for CompareWorkcells { root, other } in requests {
+        let current = match generate_workcell(world, root) {
+            Ok(workcell) => workcell,
+            Err(err) => {
+                notify_error(Some(root), format!("Unable to compile workcell: {err}"));
+                continue;
+            }
+        };
+        // Site IDs are assigned while the workcell is generated
+        let mut state: SystemState<(Query<(Entity, &SiteID)>, Query<&Parent>)> =
+            SystemState::new(world);
+        let (site_ids, parents) = state.get(world);
+        let id_to_entity: HashMap<u32, Entity> = site_ids
+            .iter()
+            .filter(|(e, _)| AncestorIter::new(&parents, *e).any(|p| p == root))
+            .map(|(e, id)| (id.0, e))
+            .collect();
+
+        let changes = diff_workcells(&current, &other)
+            .into_iter()
+            .map(|change| WorkcellChange {
+                kind: change.kind,
+                description: change.description,
+                entity: change.id.and_then(|id| id_to_entity.get(&id).copied()),
+            })
+            .collect();
+
+        let mut diff = world.resource_mut::<WorkcellDiff>();
+        diff.root = Some(root);
+        diff.changes = changes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmf_site_format::{
+        Frame, FrameMarker, Geometry, MeshPrimitive, NameInWorkcell, Parented, WorkcellModel,
+    };
+
+    fn frame(name: Option<&str>, trans: [f32; 3]) -> Frame {
+        Frame {
+            anchor: Anchor::Pose3D(Pose {
+                trans,
+                ..Default::default()
+            }),
+            name: name.map(|name| NameInWorkcell(name.to_owned())),
+            mesh_constraint: None,
+            inertial: None,
+            marker: FrameMarker,
+        }
+    }
+
+    fn make_workcell() -> Workcell {
+        let mut workcell = Workcell::default();
+        workcell.frames.insert(
+            1,
+            Parented {
+                parent: 0,
+                bundle: frame(Some("table"), [1.0, 0.0, 0.0]),
+            },
+        );
+        workcell.frames.insert(
+            2,
+            Parented {
+                parent: 1,
+                bundle: frame(None, [0.0, 0.0, 1.0]),
+            },
+        );
+        workcell.visuals.insert(
+            3,
+            Parented {
+                parent: 1,
+                bundle: WorkcellModel {
+                    name: "top".to_owned(),
+                    geometry: Geometry::Primitive(MeshPrimitive::Box {
+                        size: [1.0, 1.0, 0.1],
+                    }),
+                    pose: Pose::default(),
+                },
+            },
+        );
+        workcell
+    }
+
+    #[test]
+    fn identical_workcells_have_no_changes() {
+        let workcell = make_workcell();
+        assert!(diff_workcells(&workcell, &workcell.clone()).is_empty());
+    }
+
+    #[test]
+    fn changes_are_found() {
+        let current = make_workcell();
+        let mut other = make_workcell();
+        // Move the table, remove its visual, and add a new frame
+        if let Anchor::Pose3D(pose) = &mut other.frames.get_mut(&1).unwrap().bundle.anchor {
+            pose.trans = [2.0, 0.0, 0.0];
+        }
+        other.visuals.clear();
+        other.frames.insert(
+            4,
+            Parented {
+                parent: 0,
+                bundle: frame(Some("shelf"), [0.0, 3.0, 0.0]),
+            },
+        );
+
+        let changes = diff_workcells(&current, &other);
+        let find = |kind: WorkcellChangeKind| {
+            changes
+                .iter()
+                .filter(|c| c.kind == kind)
+                .collect::<Vec<_>>()
+        };
+        let modified = find(WorkcellChangeKind::Modified);
+        assert_eq!(modified.len(), 1);
+        assert_eq!(modified[0].id, Some(1));
+        assert!(modified[0]
+            .description
+            .starts_with("Frame table moved by [1.0000"));
+
+        let removed = find(WorkcellChangeKind::Removed);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id, Some(3));
+        assert_eq!(removed[0].description, "Visual table/top was removed");
+
+        let added = find(WorkcellChangeKind::Added);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].id, None);
+        assert_eq!(added[0].description, "Frame shelf was added");
+    }
+
+    #[test]
+    fn unnamed_frames_are_matched_by_site_id() {
+        let current = make_workcell();
+        // An unnamed frame that has a different SiteID is a different frame,
+        // even if a frame is named after the id of the other one
+        let mut other = make_workcell();
+        let unnamed = other.frames.remove(&2).unwrap();
+        other.frames.insert(5, unnamed);
+        other.frames.insert(
+            2,
+            Parented {
+                parent: 1,
+                bundle: frame(Some("#2"), [0.0, 0.0, 1.0]),
+            },
+        );
+
+        let changes = diff_workcells(&current, &other);
+        let removed: Vec<_> = changes
+            .iter()
+            .filter(|c| c.kind == WorkcellChangeKind::Removed)
+            .collect();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id, Some(2));
+        assert_eq!(
+            changes
+                .iter()
+                .filter(|c| c.kind == WorkcellChangeKind::Added)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn changed_parent_is_reported() {
+        let current = make_workcell();
+        let mut other = make_workcell();
+        other.visuals.get_mut(&3).unwrap().bundle.pose.trans = [0.0, 0.0, 0.5];
+        other.frames.get_mut(&2).unwrap().parent = 0;
+
+        let changes = diff_workcells(&current, &other);
+        let frame = changes.iter().find(|c| c.id == Some(2)).unwrap();
+        assert_eq!(frame.kind, WorkcellChangeKind::Modified);
+        assert!(frame.description.contains("moved from table to <root>"));
+        let visual = changes.iter().find(|c| c.id == Some(3)).unwrap();
+        assert_eq!(visual.kind, WorkcellChangeKind::Modified);
+    }
+}
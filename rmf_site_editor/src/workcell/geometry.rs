@@ -0,0 +1,113 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::console::notify_error;
+use crate::site::{Dependents, GlobalCollisionDisplay};
+use bevy::prelude::*;
+use rmf_site_format::{
+    AssetSource, Geometry, MeshPrimitive, NameInWorkcell, Pose, Scale, WorkcellCollisionMarker,
+    WorkcellModel, WorkcellVisualMarker,
+};
+
+/// Used as an event to give a visual a collision with the same geometry, or to
+/// give a collision a matching visual
+#[derive(Clone, Copy, Debug)]
+pub struct CopyGeometry {
+    pub model: Entity,
+}
+
+pub fn handle_copy_geometry(
+    mut commands: Commands,
+    mut requests: EventReader<CopyGeometry>,
+    mut dependents: Query<&mut Dependents>,
+    models: Query<(
+        Option<&WorkcellVisualMarker>,
+        &NameInWorkcell,
+        Option<&AssetSource>,
+        Option<&MeshPrimitive>,
+        &Pose,
+        Option<&Scale>,
+        &Parent,
+    )>,
+) {
+    for CopyGeometry { model } in requests.iter().copied() {
+        let Ok((visual, name, source, primitive, pose, scale, parent)) = models.get(model) else {
+            continue;
+        };
+        let geometry = if let Some(source) = source {
+            Geometry::Mesh {
+                filename: source.into(),
+                scale: scale.map(|s| **s),
+            }
+        } else if let Some(primitive) = primitive {
+            Geometry::Primitive(primitive.clone())
+        } else {
+            notify_error(
+                Some(model),
+                format!("Unable to copy the geometry of {model:?} because it has none"),
+            );
+            continue;
+        };
+        let copy = WorkcellModel {
+            name: name.0.clone(),
+            geometry,
+            pose: pose.clone(),
+        };
+
+        let mut cmd = commands.spawn(SpatialBundle::VISIBLE_IDENTITY);
+        if visual.is_some() {
+            cmd.insert(WorkcellCollisionMarker);
+        } else {
+            cmd.insert(WorkcellVisualMarker);
+        }
+        let id = cmd.id();
+        copy.add_bevy_components(cmd);
+
+        let parent = parent.get();
+        commands.entity(parent).add_child(id);
+        if let Ok(mut deps) = dependents.get_mut(parent) {
+            deps.insert(id);
+        }
+    }
+}
+
+/// Show the visuals and collisions of workcells according to the geometry
+/// display that has been chosen
+pub fn update_workcell_collision_display(
+    display: Res<GlobalCollisionDisplay>,
+    new_models: Query<(), Or<(Added<WorkcellVisualMarker>, Added<WorkcellCollisionMarker>)>>,
+    mut models: Query<
+        (
+            &mut Visibility,
+            Option<&WorkcellVisualMarker>,
+            Option<&WorkcellCollisionMarker>,
+        ),
+        Or<(With<WorkcellVisualMarker>, With<WorkcellCollisionMarker>)>,
+    >,
+) {
+    if !display.is_changed() && new_models.is_empty() {
+        return;
+    }
+
+    for (mut visibility, visual, collision) in &mut models {
+        let is_visible = (visual.is_some() && display.shows_visuals())
+            || (collision.is_some() && display.shows_collisions());
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+    }
+}
@@ -0,0 +1,110 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::console::notify_error;
+use crate::site::Dependents;
+use bevy::prelude::*;
+use rmf_site_format::{Anchor, JointProperties, MeshConstraint, Pose, WorkcellProperties};
+
+/// Used as an event to move an element of a workcell, along with everything
+/// attached to it, underneath a different frame
+#[derive(Clone, Copy, Debug)]
+pub struct ChangeParent {
+    pub child: Entity,
+    pub parent: Entity,
+}
+
+pub fn handle_change_parent(
+    mut commands: Commands,
+    mut requests: EventReader<ChangeParent>,
+    mut anchors: Query<&mut Anchor>,
+    mut poses: Query<&mut Pose>,
+    mut dependents: Query<&mut Dependents>,
+    constraints: Query<(), With<MeshConstraint<Entity>>>,
+    workcells: Query<(), With<WorkcellProperties>>,
+    joints: Query<Option<&Children>, With<JointProperties>>,
+    parents: Query<&Parent>,
+    transforms: Query<&GlobalTransform>,
+) {
+    for ChangeParent { child, parent } in requests.iter().copied() {
+        if child == parent || AncestorIter::new(&parents, parent).any(|p| p == child) {
+            notify_error(Some(child), format!("Unable to attach {child:?} to {parent:?} because it is one of its own descendants"));
+            continue;
+        }
+        let parent_is_frame = matches!(anchors.get(parent), Ok(Anchor::Pose3D(_)));
+        if let Ok(joint_children) = joints.get(parent) {
+            // A joint moves exactly one frame
+            let child_is_frame = matches!(anchors.get(child), Ok(Anchor::Pose3D(_)));
+            let has_frame = joint_children
+                .map(|c| c.iter().any(|c| anchors.contains(*c)))
+                .unwrap_or(false);
+            if !child_is_frame || has_frame {
+                notify_error(
+                    Some(child),
+                    format!("Only one frame can be attached to the joint {parent:?}"),
+                );
+                continue;
+            }
+        } else if !parent_is_frame && !workcells.contains(parent) {
+            notify_error(
+                Some(child),
+                format!("Unable to attach {child:?} to {parent:?} because it is not a frame"),
+            );
+            continue;
+        }
+        if constraints.contains(child) {
+            notify_error(
+                Some(child),
+                format!(
+                    "Unable to change the parent of {child:?} because it is constrained to a mesh"
+                ),
+            );
+            continue;
+        }
+        let Ok(old_parent) = parents.get(child).map(|p| p.get()) else {
+            continue;
+        };
+        if old_parent == parent {
+            continue;
+        }
+
+        // Keep the element where it is instead of moving it along with its
+        // new parent
+        if let (Ok(child_tf), Ok(parent_tf)) = (transforms.get(child), transforms.get(parent)) {
+            let tf =
+                Transform::from_matrix((parent_tf.affine().inverse() * child_tf.affine()).into());
+            if let Ok(mut anchor) = anchors.get_mut(child) {
+                if let Anchor::Pose3D(pose) = anchor.as_mut() {
+                    pose.align_with(&tf);
+                }
+            } else if let Ok(mut pose) = poses.get_mut(child) {
+                pose.align_with(&tf);
+            }
+        }
+
+        if let Ok(mut deps) = dependents.get_mut(old_parent) {
+            deps.remove(&child);
+        }
+        if let Ok(mut deps) = dependents.get_mut(parent) {
+            deps.insert(child);
+        } else {
+            commands.entity(parent).insert(Dependents::single(child));
+        }
+        commands.entity(old_parent).remove_children(&[child]);
+        commands.entity(parent).push_children(&[child]);
+    }
+}
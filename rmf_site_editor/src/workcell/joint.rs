@@ -0,0 +1,166 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::console::notify_error;
+use crate::site::Dependents;
+use bevy::prelude::*;
+use rmf_site_format::{
+    Anchor, Category, Joint, JointProperties, NameInWorkcell, SingleDofJoint, WorkcellProperties,
+};
+use std::ops::RangeInclusive;
+
+/// How far prismatic joints without limits can be moved in the preview
+pub const UNLIMITED_PRISMATIC_RANGE: f32 = 1.0;
+
+/// The position of a joint that is being previewed. This moves the frame that
+/// is attached to the joint but is not saved with the workcell.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Deref, DerefMut)]
+pub struct JointPosition(pub f32);
+
+/// Used as an event to put a new joint between a frame and its parent, so the
+/// frame can move relative to the parent
+#[derive(Clone, Copy, Debug)]
+pub struct CreateJoint {
+    pub frame: Entity,
+}
+
+pub fn handle_create_joint(
+    mut commands: Commands,
+    mut requests: EventReader<CreateJoint>,
+    mut dependents: Query<&mut Dependents>,
+    anchors: Query<&Anchor>,
+    names: Query<&NameInWorkcell>,
+    joints: Query<(), With<JointProperties>>,
+    workcells: Query<(), With<WorkcellProperties>>,
+    parents: Query<&Parent>,
+) {
+    for CreateJoint { frame } in requests.iter().copied() {
+        if !matches!(anchors.get(frame), Ok(Anchor::Pose3D(_))) {
+            notify_error(
+                Some(frame),
+                format!("Unable to add a joint to {frame:?} because it is not a frame"),
+            );
+            continue;
+        }
+        let Ok(parent) = parents.get(frame).map(|p| p.get()) else {
+            continue;
+        };
+        if joints.contains(parent) {
+            notify_error(
+                Some(frame),
+                format!("Frame {frame:?} is already attached to a joint"),
+            );
+            continue;
+        }
+        if !workcells.contains(parent) && !matches!(anchors.get(parent), Ok(Anchor::Pose3D(_))) {
+            notify_error(
+                Some(frame),
+                format!("Unable to add a joint to {frame:?} because its parent is not a frame"),
+            );
+            continue;
+        }
+
+        let name = match names.get(frame) {
+            Ok(name) => format!("{}_joint", name.0),
+            Err(_) => "joint".to_owned(),
+        };
+        let joint = commands
+            .spawn(SpatialBundle::VISIBLE_IDENTITY)
+            .insert(Joint {
+                name: NameInWorkcell(name),
+                properties: JointProperties::Revolute(SingleDofJoint::default()),
+            })
+            .insert(Category::Joint)
+            .insert(Dependents::single(frame))
+            .id();
+
+        if let Ok(mut deps) = dependents.get_mut(parent) {
+            deps.remove(&frame);
+            deps.insert(joint);
+        } else {
+            commands.entity(parent).insert(Dependents::single(joint));
+        }
+        commands.entity(parent).remove_children(&[frame]);
+        commands.entity(parent).push_children(&[joint]);
+        commands.entity(joint).push_children(&[frame]);
+    }
+}
+
+/// Move the frame attached to each joint according to the previewed position
+/// of the joint. The axis of the joint is expressed in the frame that it
+/// moves, so the motion is applied in that frame.
+pub fn update_joint_transforms(
+    mut joints: Query<(&JointProperties, &JointPosition, &Children, &mut Transform)>,
+    anchors: Query<&Anchor>,
+) {
+    for (properties, position, children, mut tf) in &mut joints {
+        let Some(motion) = joint_motion(properties, **position) else {
+            if *tf != Transform::IDENTITY {
+                *tf = Transform::IDENTITY;
+            }
+            continue;
+        };
+
+        let origin = children.iter().find_map(|c| match anchors.get(*c) {
+            Ok(Anchor::Pose3D(pose)) => Some(pose.transform()),
+            _ => None,
+        });
+        let Some(origin) = origin else {
+            continue;
+        };
+        let new_tf = joint_transform(origin, motion);
+        if *tf != new_tf {
+            *tf = new_tf;
+        }
+    }
+}
+
+/// The motion of a joint at a position, expressed in the frame that it moves,
+/// or None if the joint cannot move
+pub fn joint_motion(properties: &JointProperties, position: f32) -> Option<Transform> {
+    match properties {
+        JointProperties::Fixed => None,
+        JointProperties::Revolute(dof) => {
+            let axis = Vec3::from_array(dof.axis).normalize_or_zero();
+            (axis != Vec3::ZERO)
+                .then(|| Transform::from_rotation(Quat::from_axis_angle(axis, position)))
+        }
+        JointProperties::Prismatic(dof) => {
+            let axis = Vec3::from_array(dof.axis).normalize_or_zero();
+            Some(Transform::from_translation(axis * position))
+        }
+    }
+}
+
+/// The transform of a joint entity that applies a motion in the frame of the
+/// child, where origin is the pose of the child relative to the joint
+pub fn joint_transform(origin: Transform, motion: Transform) -> Transform {
+    let origin = origin.compute_matrix();
+    Transform::from_matrix(origin * motion.compute_matrix() * origin.inverse())
+}
+
+/// The positions that a joint can be moved to, or None if it cannot move
+pub fn joint_range(properties: &JointProperties) -> Option<RangeInclusive<f32>> {
+    let dof = properties.single_dof()?;
+    if let Some(limits) = dof.limits {
+        return Some(limits.lower..=limits.upper);
+    }
+    match properties {
+        JointProperties::Revolute(_) => Some(-std::f32::consts::PI..=std::f32::consts::PI),
+        _ => Some(-UNLIMITED_PRISMATIC_RANGE..=UNLIMITED_PRISMATIC_RANGE),
+    }
+}
@@ -18,42 +18,53 @@
 use bevy::prelude::*;
 use bevy_egui::EguiContext;
 
-use rmf_site_format::{WorkcellCollisionMarker, WorkcellVisualMarker};
+use crate::{
+    keyboard::{KeyAction, KeyCapture, Keymap},
+    site::{CollisionDisplay, GlobalCollisionDisplay},
+    SaveWorkspace,
+};
 
 pub fn handle_workcell_keyboard_input(
     keyboard_input: Res<Input<KeyCode>>,
+    keymap: Res<Keymap>,
+    capture: Res<KeyCapture>,
     mut egui_context: ResMut<EguiContext>,
-    mut visuals: Query<
-        &mut Visibility,
-        (With<WorkcellVisualMarker>, Without<WorkcellCollisionMarker>),
-    >,
-    mut collisions: Query<
-        &mut Visibility,
-        (With<WorkcellCollisionMarker>, Without<WorkcellVisualMarker>),
-    >,
+    mut display: ResMut<GlobalCollisionDisplay>,
+    mut save_workspace: EventWriter<SaveWorkspace>,
 ) {
     let egui_context = egui_context.ctx_mut();
     let ui_has_focus = egui_context.wants_pointer_input()
         || egui_context.wants_keyboard_input()
         || egui_context.is_pointer_over_area();
 
-    if ui_has_focus {
+    if ui_has_focus || capture.0.is_some() {
         return;
     }
 
-    if keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]) {
-        if keyboard_input.just_pressed(KeyCode::V) {
-            println!("Toggling visuals");
-            for mut v in visuals.iter_mut() {
-                v.is_visible = !v.is_visible;
-            }
-        }
-
-        if keyboard_input.just_pressed(KeyCode::C) {
-            println!("Toggling collisions");
-            for mut c in collisions.iter_mut() {
-                c.is_visible = !c.is_visible;
-            }
-        }
+    if keymap.just_pressed(KeyAction::ExportUrdf, &keyboard_input) {
+        save_workspace.send(SaveWorkspace::new().to_dialog().to_urdf());
+    }
+
+    let mut visuals = display.shows_visuals();
+    let mut collisions = display.shows_collisions();
+    if keymap.just_pressed(KeyAction::ToggleVisuals, &keyboard_input) {
+        println!("Toggling visuals");
+        visuals = !visuals;
+    }
+
+    if keymap.just_pressed(KeyAction::ToggleCollisions, &keyboard_input) {
+        println!("Toggling collisions");
+        collisions = !collisions;
+    }
+
+    // At least one kind of geometry always stays visible
+    let new_display = match (visuals, collisions) {
+        (true, true) => CollisionDisplay::Both,
+        (false, true) => CollisionDisplay::Collisions,
+        (true, false) => CollisionDisplay::Visuals,
+        (false, false) => return,
+    };
+    if display.0 != new_display {
+        display.0 = new_display;
     }
 }
@@ -18,7 +18,8 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use crate::site::{AnchorBundle, DefaultFile, Dependents, PreventDeletion, SiteState};
+use crate::console::report_error;
+use crate::site::{AnchorBundle, DefaultFile, Dependents, NextSiteID, PreventDeletion, SiteState};
 use crate::workcell::ChangeCurrentWorkcell;
 use bevy::prelude::*;
 use std::collections::HashSet;
@@ -41,24 +42,38 @@ fn generate_workcell_entities(
     commands: &mut Commands,
     workcell: &rmf_site_format::Workcell,
 ) -> Entity {
-    // Create hashmap of ids to entity to correctly generate hierarchy
-    let mut id_to_entity = HashMap::new();
-    // Hashmap of parent id to list of its children entities
-    let mut parent_to_child_entities = HashMap::new();
-    // Hashmap of parent model entity to constraint dependent entity
-    let mut model_to_constraint_dependent_entities = HashMap::new();
-
     let root = commands
         .spawn(SpatialBundle::VISIBLE_IDENTITY)
         .insert(workcell.properties.clone())
         .insert(NameInWorkcell(workcell.properties.name.clone()))
         .insert(SiteID(workcell.id))
+        .insert(NextSiteID(workcell.highest_id() + 1))
         .insert(Category::Workcell)
         .insert(PreventDeletion::because(
             "Workcell root cannot be deleted".to_string(),
         ))
         .id();
-    id_to_entity.insert(&workcell.id, root);
+    let children = generate_workcell_elements(commands, workcell);
+    commands
+        .entity(root)
+        .insert(Dependents(HashSet::from_iter(children.clone())))
+        .push_children(&children);
+    root
+}
+
+/// Spawn the frames, joints and geometry of a workcell. The entities that
+/// belong directly under the workcell root are returned, so the caller can
+/// attach them to a new root or to a frame of another workcell.
+pub fn generate_workcell_elements(
+    commands: &mut Commands,
+    workcell: &rmf_site_format::Workcell,
+) -> Vec<Entity> {
+    // Create hashmap of ids to entity to correctly generate hierarchy
+    let mut id_to_entity = HashMap::new();
+    // Hashmap of parent id to list of its children entities
+    let mut parent_to_child_entities = HashMap::new();
+    // Hashmap of parent model entity to constraint dependent entity
+    let mut model_to_constraint_dependent_entities = HashMap::new();
 
     for (id, parented_visual) in &workcell.visuals {
         let cmd = commands.spawn((SiteID(*id), WorkcellVisualMarker));
@@ -84,6 +99,34 @@ fn generate_workcell_entities(
         id_to_entity.insert(id, e);
     }
 
+    for (id, parented_joint) in &workcell.joints {
+        let e = commands
+            .spawn(SpatialBundle::VISIBLE_IDENTITY)
+            .insert(parented_joint.bundle.clone())
+            .insert(SiteID(*id))
+            .insert(Category::Joint)
+            .id();
+        let child_entities: &mut Vec<Entity> = parent_to_child_entities
+            .entry(parented_joint.parent)
+            .or_default();
+        child_entities.push(e);
+        id_to_entity.insert(id, e);
+    }
+
+    for (id, parented_sensor) in &workcell.sensors {
+        let cmd = commands.spawn((
+            SiteID(*id),
+            NameInWorkcell(parented_sensor.bundle.name.clone()),
+        ));
+        let e = cmd.id();
+        parented_sensor.bundle.add_bevy_components(cmd);
+        let child_entities: &mut Vec<Entity> = parent_to_child_entities
+            .entry(parented_sensor.parent)
+            .or_default();
+        child_entities.push(e);
+        id_to_entity.insert(id, e);
+    }
+
     for (id, parented_anchor) in &workcell.frames {
         let e = commands
             .spawn(AnchorBundle::new(parented_anchor.bundle.anchor.clone()).visible(true))
@@ -107,6 +150,9 @@ fn generate_workcell_entities(
         if let Some(name) = &parented_anchor.bundle.name {
             commands.entity(e).insert(name.clone());
         }
+        if let Some(inertial) = &parented_anchor.bundle.inertial {
+            commands.entity(e).insert(inertial.clone());
+        }
         let child_entities: &mut Vec<Entity> = parent_to_child_entities
             .entry(parented_anchor.parent)
             .or_default();
@@ -121,18 +167,24 @@ fn generate_workcell_entities(
             .insert(ConstraintDependents(dependents));
     }
 
+    let mut root_children = Vec::new();
     for (parent, children) in parent_to_child_entities {
-        if let Some(parent) = id_to_entity.get(&parent) {
+        if parent == workcell.id {
+            root_children = children;
+        } else if let Some(parent) = id_to_entity.get(&parent) {
             commands
                 .entity(*parent)
                 .insert(Dependents(HashSet::from_iter(children.clone())))
                 .push_children(&children);
         } else {
-            println!("DEV error, didn't find matching entity for id {}", parent);
+            report_error(
+                None,
+                format!("DEV ERROR: Didn't find matching entity for id {parent}"),
+            );
             continue;
         }
     }
-    root
+    root_children
 }
 
 pub fn load_workcell(
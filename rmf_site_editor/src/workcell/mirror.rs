@@ -0,0 +1,242 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::notify_error,
+    site::{Dependents, NextSiteID, SiteID},
+    workcell::{find_workcell_root, generate_workcell, insert_workcell_elements},
+};
+use bevy::{ecs::system::SystemState, prelude::*};
+use rmf_site_format::{Anchor, Geometry, JointProperties, MeshPrimitive, Pose, Workcell};
+use std::f32::consts::PI;
+
+/// Planes of the workcell root frame that elements can be mirrored about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MirrorPlane {
+    XY,
+    XZ,
+    YZ,
+}
+
+impl MirrorPlane {
+    pub const ALL: [MirrorPlane; 3] = [MirrorPlane::YZ, MirrorPlane::XZ, MirrorPlane::XY];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::XY => "XY",
+            Self::XZ => "XZ",
+            Self::YZ => "YZ",
+        }
+    }
+
+    /// The scale that reflects vectors about this plane
+    pub fn reflection(&self) -> Vec3 {
+        match self {
+            Self::XY => Vec3::new(1.0, 1.0, -1.0),
+            Self::XZ => Vec3::new(1.0, -1.0, 1.0),
+            Self::YZ => Vec3::new(-1.0, 1.0, 1.0),
+        }
+    }
+}
+
+/// Used as an event to add a mirrored copy of a frame, along with everything
+/// attached to it, or of a visual or collision. The copy is placed beneath the
+/// same parent as the original.
+#[derive(Clone, Copy, Debug)]
+pub struct MirrorElement {
+    pub element: Entity,
+    pub plane: MirrorPlane,
+}
+
+/// Put in front of the names of mirrored copies
+pub const MIRROR_NAME_PREFIX: &str = "mirrored_";
+
+pub fn handle_mirror_elements(world: &mut World) {
+    let requests: Vec<_> = world
+        .resource_mut::<Events<MirrorElement>>()
+        .drain()
+        .collect();
+    for MirrorElement { element, plane } in requests {
+        let Some(root) = find_workcell_root(world, element) else {
+            notify_error(
+                Some(element),
+                format!("Unable to mirror {element:?} because it is not in a workcell"),
+            );
+            continue;
+        };
+        let Some(parent) = world.get::<Parent>(element).map(|p| p.get()) else {
+            continue;
+        };
+        if world.get::<JointProperties>(parent).is_some() {
+            // A joint can only move one frame
+            notify_error(
+                Some(element),
+                format!(
+                    "Unable to mirror {element:?} because it is attached to a joint, \
+                    mirror the frame above the joint instead"
+                ),
+            );
+            continue;
+        }
+        let workcell = match generate_workcell(world, root) {
+            Ok(workcell) => workcell,
+            Err(err) => {
+                notify_error(Some(root), format!("Unable to compile workcell: {err}"));
+                continue;
+            }
+        };
+        // Site IDs are assigned while the workcell is generated
+        let Some(id) = world.get::<SiteID>(element).map(|id| id.0) else {
+            continue;
+        };
+        let Some(mut copy) = element_subtree(&workcell, id) else {
+            notify_error(
+                Some(element),
+                format!("Unable to mirror {element:?} because it is not a frame or geometry"),
+            );
+            continue;
+        };
+
+        // The pose of the parent in the workcell root frame
+        let (Some(root_tf), Some(parent_tf)) = (
+            world.get::<GlobalTransform>(root),
+            world.get::<GlobalTransform>(parent),
+        ) else {
+            continue;
+        };
+        let parent_tf = root_tf.compute_matrix().inverse() * parent_tf.compute_matrix();
+        mirror_workcell(&mut copy, plane, parent_tf);
+        copy.add_name_prefix(MIRROR_NAME_PREFIX);
+
+        // The copy still has the ids of the original elements, so move it to
+        // ids that are not in use yet
+        let Some(next_site_id) = world.get::<NextSiteID>(root).map(|n| n.0) else {
+            continue;
+        };
+        copy.offset_ids(next_site_id);
+        world
+            .entity_mut(root)
+            .insert(NextSiteID(copy.highest_id() + 1));
+
+        let mut state: SystemState<(Commands, Query<&mut Dependents>)> = SystemState::new(world);
+        let (mut commands, mut dependents) = state.get_mut(world);
+        insert_workcell_elements(&mut commands, &mut dependents, &copy, parent);
+        state.apply(world);
+    }
+}
+
+/// The element with this id and everything attached beneath it, with the
+/// element attached to the root of the returned workcell
+fn element_subtree(workcell: &Workcell, id: u32) -> Option<Workcell> {
+    if let Some(subtree) = workcell.subtree(id) {
+        return Some(subtree);
+    }
+    let mut subtree = Workcell {
+        properties: workcell.properties.clone(),
+        id: workcell.id,
+        ..Default::default()
+    };
+    if let Some(visual) = workcell.visuals.get(&id) {
+        let mut visual = visual.clone();
+        visual.parent = workcell.id;
+        subtree.visuals.insert(id, visual);
+    } else if let Some(collision) = workcell.collisions.get(&id) {
+        let mut collision = collision.clone();
+        collision.parent = workcell.id;
+        subtree.collisions.insert(id, collision);
+    } else if let Some(sensor) = workcell.sensors.get(&id) {
+        let mut sensor = sensor.clone();
+        sensor.parent = workcell.id;
+        subtree.sensors.insert(id, sensor);
+    } else {
+        return None;
+    }
+    Some(subtree)
+}
+
+/// Mirror every element of a workcell. Elements that are attached to the root
+/// are given poses relative to parent_tf, the transform of the entity they
+/// will be attached to, while the rest keep their place relative to their
+/// mirrored parents.
+///
+/// Poses are reflected on both sides so frames stay right handed, which means
+/// the geometry needs to be reflected within its own frame to become the
+/// mirror image of the original.
+fn mirror_workcell(workcell: &mut Workcell, plane: MirrorPlane, parent_tf: Mat4) {
+    let reflection = Mat4::from_scale(plane.reflection());
+    let mirror_pose = |pose: &mut Pose, attached_to_root: bool| {
+        let local = pose.transform().compute_matrix();
+        let mirrored = if attached_to_root {
+            parent_tf.inverse() * reflection * parent_tf * local * reflection
+        } else {
+            reflection * local * reflection
+        };
+        pose.align_with(&Transform::from_matrix(mirrored));
+    };
+
+    for frame in workcell.frames.values_mut() {
+        if let Anchor::Pose3D(pose) = &mut frame.bundle.anchor {
+            mirror_pose(pose, frame.parent == workcell.id);
+        }
+        // Mesh constraints would pull the mirrored frame back onto the
+        // original mesh
+        frame.bundle.mesh_constraint = None;
+    }
+
+    for model in workcell
+        .visuals
+        .values_mut()
+        .chain(workcell.collisions.values_mut())
+    {
+        mirror_pose(&mut model.bundle.pose, model.parent == workcell.id);
+        match &mut model.bundle.geometry {
+            Geometry::Mesh { scale, .. } => {
+                *scale = Some(scale.unwrap_or(Vec3::ONE) * plane.reflection());
+            }
+            // A cone points along its z axis, so reflecting it about the XY
+            // plane turns it upside down. Since it is symmetric about its
+            // other planes, that is the same as rotating it around x.
+            Geometry::Primitive(MeshPrimitive::Cone { .. }) if plane == MirrorPlane::XY => {
+                let flipped = model.bundle.pose.transform()
+                    * Transform::from_rotation(Quat::from_rotation_x(PI));
+                model.bundle.pose.align_with(&flipped);
+            }
+            // Boxes, cylinders, capsules and spheres are symmetric about
+            // every plane of their own frame
+            Geometry::Primitive(_) => {}
+        }
+    }
+
+    for sensor in workcell.sensors.values_mut() {
+        mirror_pose(&mut sensor.bundle.pose, sensor.parent == workcell.id);
+    }
+
+    for joint in workcell.joints.values_mut() {
+        // The axis is expressed in the frame that the joint moves. Reflecting
+        // a rotation reverses its direction, so the axis of a revolute joint
+        // is flipped to keep the limits the same.
+        match &mut joint.bundle.properties {
+            JointProperties::Fixed => {}
+            JointProperties::Revolute(dof) => {
+                dof.axis = (-Vec3::from_array(dof.axis) * plane.reflection()).to_array();
+            }
+            JointProperties::Prismatic(dof) => {
+                dof.axis = (Vec3::from_array(dof.axis) * plane.reflection()).to_array();
+            }
+        }
+    }
+}
@@ -18,22 +18,43 @@
 pub mod load;
 pub use load::*;
 
+pub mod diff;
+pub use diff::*;
+
+pub mod geometry;
+pub use geometry::*;
+
+pub mod hierarchy;
+pub use hierarchy::*;
+
+pub mod joint;
+pub use joint::*;
+
 pub mod keyboard;
 pub use keyboard::*;
 
 pub mod mesh_constraint;
 pub use mesh_constraint::*;
 
+pub mod mirror;
+pub use mirror::*;
+
+pub mod reach;
+pub use reach::*;
+
 pub mod save;
 pub use save::*;
 
+pub mod template;
+pub use template::*;
+
 pub mod workcell;
 pub use workcell::*;
 
 pub mod urdf;
 pub use urdf::*;
 
-use bevy::pbr::wireframe::{Wireframe, WireframePlugin};
+use bevy::pbr::wireframe::WireframePlugin;
 use bevy::render::{render_resource::WgpuFeatures, settings::WgpuSettings};
 use bevy::{prelude::*, render::view::visibility::VisibilitySystems, transform::TransformSystem};
 use bevy_infinite_grid::{InfiniteGrid, InfiniteGridBundle, InfiniteGridPlugin};
@@ -43,13 +64,13 @@ use crate::AppState;
 use crate::{
     shapes::make_infinite_grid,
     site::{
-        handle_new_mesh_primitives, make_models_selectable, update_anchor_transforms,
+        add_sensor_visuals, forget_removed_model_meshes, handle_new_mesh_primitives,
+        make_models_selectable, update_anchor_transforms, update_changed_mesh_primitives,
         update_model_scenes, update_model_tentative_formats, update_transforms_for_changed_poses,
+        ModelMeshes, ShadingMode,
     },
 };
 
-use rmf_site_format::ModelMarker;
-
 use bevy_rapier3d::prelude::*;
 
 #[derive(Default)]
@@ -65,20 +86,14 @@ fn delete_grid(mut commands: Commands, grids: Query<Entity, With<InfiniteGrid>>)
     }
 }
 
-fn add_wireframe_to_meshes(
-    mut commands: Commands,
-    new_meshes: Query<Entity, Added<Handle<Mesh>>>,
-    parents: Query<&Parent>,
-    models: Query<Entity, With<ModelMarker>>,
-) {
-    for e in new_meshes.iter() {
-        for ancestor in AncestorIter::new(&parents, e) {
-            if let Ok(_) = models.get(ancestor) {
-                println!("Adding wireframe to mesh {:?}", e);
-                commands.entity(e).insert(Wireframe);
-            }
-        }
-    }
+// Workcells are edited with wireframes on by default so the meshes of their
+// models can be inspected
+fn enable_wireframes(mut shading: ResMut<ShadingMode>) {
+    *shading = ShadingMode::ShadedWireframe;
+}
+
+fn disable_wireframes(mut shading: ResMut<ShadingMode>) {
+    *shading = ShadingMode::Shaded;
 }
 
 impl Plugin for WorkcellEditorPlugin {
@@ -91,25 +106,55 @@ impl Plugin for WorkcellEditorPlugin {
             .add_plugin(WireframePlugin)
             .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
             .add_plugin(RapierDebugRenderPlugin::default())
+            .init_resource::<ModelMeshes>()
+            .init_resource::<WorkcellTemplateLibrary>()
+            .init_resource::<WorkcellDiff>()
             .add_event::<SaveWorkcell>()
             .add_event::<LoadWorkcell>()
             .add_event::<ChangeCurrentWorkcell>()
-            .add_system_set(SystemSet::on_enter(AppState::WorkcellEditor).with_system(spawn_grid))
-            .add_system_set(SystemSet::on_exit(AppState::WorkcellEditor).with_system(delete_grid))
+            .add_event::<ChangeParent>()
+            .add_event::<CreateJoint>()
+            .add_event::<CopyGeometry>()
+            .add_event::<SaveWorkcellTemplate>()
+            .add_event::<InsertWorkcellTemplate>()
+            .add_event::<ReachEnvelopeRequest>()
+            .add_event::<MirrorElement>()
+            .add_event::<CompareWorkcells>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::WorkcellEditor)
+                    .with_system(spawn_grid)
+                    .with_system(enable_wireframes),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::WorkcellEditor)
+                    .with_system(delete_grid)
+                    .with_system(disable_wireframes),
+            )
             .add_system_set(
                 SystemSet::on_update(AppState::WorkcellEditor)
-                    .with_system(add_wireframe_to_meshes)
                     .with_system(update_constraint_dependents)
                     .with_system(update_model_scenes)
                     .with_system(update_model_tentative_formats)
                     .with_system(make_models_selectable)
+                    .with_system(forget_removed_model_meshes)
                     .with_system(handle_workcell_keyboard_input)
                     .with_system(handle_new_mesh_primitives)
+                    .with_system(update_changed_mesh_primitives)
                     .with_system(change_workcell.before(load_workcell))
-                    .with_system(handle_new_urdf_roots),
+                    .with_system(handle_new_urdf_roots)
+                    .with_system(handle_change_parent)
+                    .with_system(handle_create_joint)
+                    .with_system(handle_copy_geometry)
+                    .with_system(handle_insert_workcell_templates)
+                    .with_system(handle_reach_envelope_requests)
+                    .with_system(update_workcell_collision_display)
+                    .with_system(add_sensor_visuals),
             )
             .add_system(load_workcell)
             .add_system(save_workcell)
+            .add_system(save_workcell_templates)
+            .add_system(handle_mirror_elements)
+            .add_system(compare_workcells)
             .add_system(add_workcell_visualization)
             .add_system_set(
                 SystemSet::on_update(AppState::WorkcellEditor)
@@ -119,7 +164,8 @@ impl Plugin for WorkcellEditorPlugin {
                     .with_system(
                         add_anchors_for_new_mesh_constraints.before(update_anchor_transforms),
                     )
-                    .with_system(update_transforms_for_changed_poses),
+                    .with_system(update_transforms_for_changed_poses)
+                    .with_system(update_joint_transforms.after(update_anchor_transforms)),
             );
     }
 }
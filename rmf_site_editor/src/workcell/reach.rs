@@ -0,0 +1,165 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::notify_error,
+    shapes::{make_box, MeshBuffer},
+    workcell::{joint_motion, joint_range, joint_transform},
+};
+use bevy::{math::Affine3A, prelude::*};
+use rmf_site_format::{Anchor, JointProperties, WorkcellProperties};
+
+/// How many joint configurations are sampled to estimate the reach envelope
+pub const REACH_ENVELOPE_SAMPLES: usize = 2000;
+/// Size of the markers that show each sampled position
+pub const REACH_ENVELOPE_POINT_SIZE: f32 = 0.01;
+
+/// Used as an event to show where a frame can be moved to by the joints
+/// between it and the root of its workcell
+pub enum ReachEnvelopeRequest {
+    Compute { frame: Entity },
+    Clear,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ReachEnvelopeMarker;
+
+/// Sample the joints above a frame within their limits and show the positions
+/// that the frame reaches as a point cloud in the workcell root frame.
+pub fn handle_reach_envelope_requests(
+    mut commands: Commands,
+    mut requests: EventReader<ReachEnvelopeRequest>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    envelopes: Query<Entity, With<ReachEnvelopeMarker>>,
+    elements: Query<(&Transform, Option<&JointProperties>)>,
+    anchors: Query<&Anchor>,
+    workcells: Query<(), With<WorkcellProperties>>,
+    parents: Query<&Parent>,
+) {
+    for request in requests.iter() {
+        for e in &envelopes {
+            commands.entity(e).despawn_recursive();
+        }
+        let ReachEnvelopeRequest::Compute { frame } = request else {
+            continue;
+        };
+
+        // The chain of elements from the frame up to, but not including, the
+        // workcell root, ordered from the root downwards
+        let mut chain = vec![*frame];
+        let mut root = None;
+        for ancestor in AncestorIter::new(&parents, *frame) {
+            if workcells.contains(ancestor) {
+                root = Some(ancestor);
+                break;
+            }
+            chain.push(ancestor);
+        }
+        let Some(root) = root else {
+            notify_error(
+                Some(*frame),
+                format!("Unable to compute reach: {frame:?} is not in a workcell"),
+            );
+            continue;
+        };
+        chain.reverse();
+
+        let mut movable = Vec::new();
+        for (i, e) in chain.iter().enumerate() {
+            let Ok((_, Some(properties))) = elements.get(*e) else {
+                continue;
+            };
+            let Some(range) = joint_range(properties) else {
+                continue;
+            };
+            // The joint moves the frame beneath it in the chain
+            let origin = chain
+                .get(i + 1)
+                .and_then(|child| match anchors.get(*child) {
+                    Ok(Anchor::Pose3D(pose)) => Some(pose.transform()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            movable.push((i, properties, range, origin));
+        }
+        if movable.is_empty() {
+            notify_error(
+                Some(*frame),
+                format!("Unable to compute reach: there are no movable joints above {frame:?}"),
+            );
+            continue;
+        }
+
+        let mut local: Vec<Transform> = chain
+            .iter()
+            .map(|e| elements.get(*e).map(|(tf, _)| *tf).unwrap_or_default())
+            .collect();
+        let mut mesh = MeshBuffer::empty();
+        for sample in 0..REACH_ENVELOPE_SAMPLES {
+            for (dim, (i, properties, range, origin)) in movable.iter().enumerate() {
+                let t = halton(sample + 1, PRIMES[dim % PRIMES.len()]);
+                let position = range.start() + t * (range.end() - range.start());
+                local[*i] = joint_motion(properties, position)
+                    .map(|motion| joint_transform(*origin, motion))
+                    .unwrap_or_default();
+            }
+            let tf = local
+                .iter()
+                .fold(Affine3A::IDENTITY, |tf, local| tf * local.compute_affine());
+            mesh = mesh.merge_with(
+                make_box(
+                    REACH_ENVELOPE_POINT_SIZE,
+                    REACH_ENVELOPE_POINT_SIZE,
+                    REACH_ENVELOPE_POINT_SIZE,
+                )
+                .transform_by(Affine3A::from_translation(tf.translation.into())),
+            );
+        }
+
+        let envelope = commands
+            .spawn(PbrBundle {
+                mesh: meshes.add(mesh.into()),
+                material: materials.add(StandardMaterial {
+                    base_color: Color::rgb(0.9, 0.4, 0.1),
+                    unlit: true,
+                    ..default()
+                }),
+                ..default()
+            })
+            .insert(ReachEnvelopeMarker)
+            .id();
+        commands.entity(root).add_child(envelope);
+    }
+}
+
+/// Bases of the sequences used for each joint, which need to be different
+/// from each other so the samples spread out over every joint
+const PRIMES: [usize; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// A low discrepancy sequence in [0, 1) that covers the joint space more
+/// evenly than random sampling
+fn halton(mut index: usize, base: usize) -> f32 {
+    let mut f = 1.0;
+    let mut r = 0.0;
+    while index > 0 {
+        f /= base as f32;
+        r += f * (index % base) as f32;
+        index /= base;
+    }
+    r
+}
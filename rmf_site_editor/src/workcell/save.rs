@@ -19,8 +19,11 @@ use bevy::ecs::system::SystemState;
 use bevy::prelude::*;
 use std::path::PathBuf;
 
-use crate::site::Pending;
-use crate::ExportFormat;
+use crate::site::{NextSiteID, Pending};
+use crate::{
+    console::{notify_error, notify_info, notify_warning, report_error},
+    record_finished_save, ExportFormat,
+};
 
 use thiserror::Error as ThisError;
 
@@ -31,6 +34,8 @@ pub struct SaveWorkcell {
     pub root: Entity,
     pub to_file: PathBuf,
     pub format: ExportFormat,
+    /// Whether this is an autosave, which should not interrupt the user
+    pub autosave: bool,
 }
 
 #[derive(ThisError, Debug, Clone)]
@@ -57,8 +62,10 @@ fn assign_site_ids(world: &mut World, workcell: Entity) {
                 Or<(
                     With<Anchor>,
                     With<LinkMarker>,
+                    With<JointProperties>,
                     With<WorkcellVisualMarker>,
                     With<WorkcellCollisionMarker>,
+                    With<SensorProperties>,
                 )>,
                 Without<Pending>,
             ),
@@ -79,6 +86,9 @@ fn assign_site_ids(world: &mut World, workcell: Entity) {
             .entity_mut(*entity)
             .insert(SiteID(idx.try_into().unwrap()));
     }
+    world
+        .entity_mut(workcell)
+        .insert(NextSiteID(new_entities.len() as u32));
 }
 
 pub fn generate_workcell(
@@ -95,6 +105,7 @@ pub fn generate_workcell(
                 &SiteID,
                 &Parent,
                 Option<&MeshConstraint<Entity>>,
+                Option<&Inertial>,
             ),
             Without<Pending>,
         >,
@@ -107,21 +118,42 @@ pub fn generate_workcell(
                 &Pose,
                 &SiteID,
                 &Parent,
-                &Scale,
+                Option<&Scale>,
             ),
             (
                 Or<(With<WorkcellVisualMarker>, With<WorkcellCollisionMarker>)>,
                 Without<Pending>,
             ),
         >,
+        Query<(Entity, &NameInWorkcell, &JointProperties, &SiteID, &Parent), Without<Pending>>,
+        Query<
+            (
+                Entity,
+                &NameInWorkcell,
+                &Pose,
+                &SensorProperties,
+                &SiteID,
+                &Parent,
+            ),
+            Without<Pending>,
+        >,
         Query<&WorkcellVisualMarker>,
         Query<&WorkcellCollisionMarker>,
         Query<&SiteID>,
         Query<&WorkcellProperties>,
         Query<&Parent>,
     )> = SystemState::new(world);
-    let (q_anchors, q_models, q_visuals, q_collisions, q_site_id, q_properties, q_parents) =
-        state.get(world);
+    let (
+        q_anchors,
+        q_models,
+        q_joints,
+        q_sensors,
+        q_visuals,
+        q_collisions,
+        q_site_id,
+        q_properties,
+        q_parents,
+    ) = state.get(world);
 
     let mut workcell = Workcell::default();
     match q_properties.get(root) {
@@ -142,7 +174,10 @@ pub fn generate_workcell(
         let parent = match q_site_id.get(parent.get()) {
             Ok(parent) => parent.0,
             Err(_) => {
-                println!("DEV Error: Parent not found for visual {:?}", parent.get());
+                report_error(
+                    Some(e),
+                    format!("DEV ERROR: Parent not found for visual {:?}", parent.get()),
+                );
                 continue;
             }
         };
@@ -150,12 +185,12 @@ pub fn generate_workcell(
             // It's a model
             Geometry::Mesh {
                 filename: String::from(source),
-                scale: Some(**scale),
+                scale: scale.map(|s| **s),
             }
         } else if let Some(primitive) = primitive {
             Geometry::Primitive(primitive.clone())
         } else {
-            println!("DEV Error, visual without primitive or mesh");
+            report_error(Some(e), "DEV ERROR: Visual without primitive or mesh");
             continue;
         };
         if q_visuals.get(e).is_ok() {
@@ -187,14 +222,17 @@ pub fn generate_workcell(
     }
 
     // Anchors
-    for (e, anchor, name, id, parent, constraint) in &q_anchors {
+    for (e, anchor, name, id, parent, constraint, inertial) in &q_anchors {
         if !parent_in_workcell(&q_parents, e, root) {
             continue;
         }
         let parent = match q_site_id.get(parent.get()) {
             Ok(parent) => parent.0,
             Err(_) => {
-                println!("DEV Error: Parent not found for anchor {:?}", parent.get());
+                report_error(
+                    Some(e),
+                    format!("DEV ERROR: Parent not found for anchor {:?}", parent.get()),
+                );
                 continue;
             }
         };
@@ -218,12 +256,68 @@ pub fn generate_workcell(
                     anchor: anchor.clone(),
                     name: name.cloned(),
                     mesh_constraint: constraint,
+                    inertial: inertial.cloned(),
                     marker: FrameMarker,
                 },
             },
         );
     }
 
+    // Joints
+    for (e, name, properties, id, parent) in &q_joints {
+        if !parent_in_workcell(&q_parents, e, root) {
+            continue;
+        }
+        let parent = match q_site_id.get(parent.get()) {
+            Ok(parent) => parent.0,
+            Err(_) => {
+                report_error(
+                    Some(e),
+                    format!("DEV ERROR: Parent not found for joint {:?}", parent.get()),
+                );
+                continue;
+            }
+        };
+        workcell.joints.insert(
+            id.0,
+            Parented {
+                parent,
+                bundle: Joint {
+                    name: name.clone(),
+                    properties: properties.clone(),
+                },
+            },
+        );
+    }
+
+    // Sensors
+    for (e, name, pose, properties, id, parent) in &q_sensors {
+        if !parent_in_workcell(&q_parents, e, root) {
+            continue;
+        }
+        let parent = match q_site_id.get(parent.get()) {
+            Ok(parent) => parent.0,
+            Err(_) => {
+                report_error(
+                    Some(e),
+                    format!("DEV ERROR: Parent not found for sensor {:?}", parent.get()),
+                );
+                continue;
+            }
+        };
+        workcell.sensors.insert(
+            id.0,
+            Parented {
+                parent,
+                bundle: Sensor {
+                    name: name.0.clone(),
+                    pose: pose.clone(),
+                    properties: properties.clone(),
+                },
+            },
+        );
+    }
+
     Ok(workcell)
 }
 
@@ -238,10 +332,10 @@ pub fn save_workcell(world: &mut World) {
             "Saving to {}",
             path.to_str().unwrap_or("<failed to render??>")
         );
-        let f = match std::fs::File::create(path) {
+        let mut f = match std::fs::File::create(&path) {
             Ok(f) => f,
             Err(err) => {
-                println!("Unable to save file: {err}");
+                notify_error(None, format!("Unable to save file: {err}"));
                 continue;
             }
         };
@@ -249,7 +343,7 @@ pub fn save_workcell(world: &mut World) {
         let workcell = match generate_workcell(world, save_event.root) {
             Ok(root) => root,
             Err(err) => {
-                println!("Unable to compile workcell: {err}");
+                notify_error(None, format!("Unable to compile workcell: {err}"));
                 continue;
             }
         };
@@ -257,14 +351,64 @@ pub fn save_workcell(world: &mut World) {
         match save_event.format {
             ExportFormat::Default => match workcell.to_writer(f) {
                 Ok(()) => {
-                    println!("Save successful");
+                    if !save_event.autosave {
+                        notify_info(None, format!("Workcell saved to {}", path.display()));
+                    }
+                    record_finished_save(world, save_event.root, &path, save_event.autosave);
                 }
                 Err(err) => {
-                    println!("Save failed: {err}");
+                    notify_error(None, format!("Save failed: {err}"));
                 }
             },
             ExportFormat::Urdf => {
-                println!("Saving to urdf");
+                let robot = match workcell.to_urdf() {
+                    Ok(robot) => robot,
+                    Err(err) => {
+                        notify_error(None, format!("Unable to convert workcell to urdf: {err}"));
+                        continue;
+                    }
+                };
+                let urdf = match urdf_rs::write_to_string(&robot) {
+                    Ok(urdf) => urdf,
+                    Err(err) => {
+                        notify_error(None, format!("Unable to serialize urdf: {err}"));
+                        continue;
+                    }
+                };
+                if !workcell.sensors.is_empty() {
+                    notify_warning(
+                        None,
+                        "urdf cannot describe sensors, export to sdf to keep them",
+                    );
+                }
+                match std::io::Write::write_all(&mut f, urdf.as_bytes()) {
+                    Ok(()) => {
+                        notify_info(None, format!("Urdf exported to {}", path.display()));
+                    }
+                    Err(err) => {
+                        notify_error(None, format!("Urdf export failed: {err}"));
+                    }
+                }
+            }
+            ExportFormat::Sdf => {
+                let sdf = match workcell.to_sdf() {
+                    Ok(sdf) => sdf,
+                    Err(err) => {
+                        notify_error(None, format!("Unable to convert workcell to sdf: {err}"));
+                        continue;
+                    }
+                };
+                match std::io::Write::write_all(&mut f, sdf.as_bytes()) {
+                    Ok(()) => {
+                        notify_info(None, format!("Sdf exported to {}", path.display()));
+                    }
+                    Err(err) => {
+                        notify_error(None, format!("Sdf export failed: {err}"));
+                    }
+                }
+            }
+            ExportFormat::SupervisorConfig => {
+                notify_warning(None, "Workcells do not have a supervisor config to export");
             }
         }
     }
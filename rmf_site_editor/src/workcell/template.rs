@@ -0,0 +1,253 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::{
+    console::{notify_error, notify_info},
+    site::{Dependents, NextSiteID, SiteID},
+    workcell::{generate_workcell, generate_workcell_elements},
+};
+use bevy::prelude::*;
+use rmf_site_format::{Workcell, WorkcellProperties};
+use std::path::PathBuf;
+
+/// File suffix of saved templates. Templates are saved as workcells, so they
+/// can also be opened or placed in a site on their own.
+pub const WORKCELL_TEMPLATE_SUFFIX: &str = ".workcell.json";
+
+/// The folder that workcell templates are saved in
+pub fn workcell_template_dir() -> Option<PathBuf> {
+    let mut p = dirs::data_dir()?;
+    p.push("open-rmf");
+    p.push("rmf_site_editor");
+    p.push("workcell_templates");
+    Some(p)
+}
+
+/// Check that a template name can be used as the name of its file. Names
+/// that would put the file outside of the template folder are rejected.
+pub fn is_valid_template_name(name: &str) -> bool {
+    const RESERVED: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+    !name.trim().is_empty()
+        && !name.starts_with('.')
+        && !name.contains("..")
+        && !name.contains(RESERVED)
+        && !name.chars().any(char::is_control)
+}
+
+/// The templates that are available to be inserted into workcells
+#[derive(Resource)]
+pub struct WorkcellTemplateLibrary {
+    /// Names of the templates and the files that they were found in
+    pub templates: Vec<(String, PathBuf)>,
+}
+
+impl Default for WorkcellTemplateLibrary {
+    fn default() -> Self {
+        let mut library = Self {
+            templates: Vec::new(),
+        };
+        library.scan();
+        library
+    }
+}
+
+impl WorkcellTemplateLibrary {
+    /// Look through the template folder for templates
+    pub fn scan(&mut self) {
+        self.templates.clear();
+        let Some(dir) = workcell_template_dir() else {
+            return;
+        };
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if let Some(name) = name.strip_suffix(WORKCELL_TEMPLATE_SUFFIX) {
+                self.templates.push((name.to_owned(), entry.path()));
+            }
+        }
+        self.templates.sort();
+    }
+}
+
+/// Used as an event to save a frame and everything attached beneath it as a
+/// template
+pub struct SaveWorkcellTemplate {
+    pub frame: Entity,
+    pub name: String,
+}
+
+/// Used as an event to insert a copy of a template beneath a frame or the
+/// root of a workcell
+pub struct InsertWorkcellTemplate {
+    pub template: PathBuf,
+    pub parent: Entity,
+    /// Put in front of the names of every inserted element
+    pub prefix: String,
+}
+
+pub fn save_workcell_templates(world: &mut World) {
+    let save_events: Vec<_> = world
+        .resource_mut::<Events<SaveWorkcellTemplate>>()
+        .drain()
+        .collect();
+    for SaveWorkcellTemplate { frame, name } in save_events {
+        if !is_valid_template_name(&name) {
+            notify_error(
+                None,
+                format!("Unable to save template: [{name}] cannot be used as a file name"),
+            );
+            continue;
+        }
+        let Some(root) = find_workcell_root(world, frame) else {
+            notify_error(
+                Some(frame),
+                format!("Unable to save template: {frame:?} is not in a workcell"),
+            );
+            continue;
+        };
+        let workcell = match generate_workcell(world, root) {
+            Ok(workcell) => workcell,
+            Err(err) => {
+                notify_error(Some(root), format!("Unable to compile workcell: {err}"));
+                continue;
+            }
+        };
+        // Site IDs are assigned while the workcell is generated
+        let Some(frame_id) = world.get::<SiteID>(frame).map(|id| id.0) else {
+            continue;
+        };
+        let Some(mut template) = workcell.subtree(frame_id) else {
+            notify_error(
+                Some(frame),
+                format!("Unable to save template: {frame:?} is not a frame"),
+            );
+            continue;
+        };
+        template.properties.name = name.clone();
+
+        let Some(dir) = workcell_template_dir() else {
+            notify_error(None, "Unable to find a folder to save templates in");
+            continue;
+        };
+        if let Err(err) = std::fs::create_dir_all(&dir) {
+            notify_error(
+                None,
+                format!("Unable to create template folder {dir:?}: {err}"),
+            );
+            continue;
+        }
+        let path = dir.join(format!("{name}{WORKCELL_TEMPLATE_SUFFIX}"));
+        let f = match std::fs::File::create(&path) {
+            Ok(f) => f,
+            Err(err) => {
+                notify_error(None, format!("Unable to save template: {err}"));
+                continue;
+            }
+        };
+        match template.to_writer(f) {
+            Ok(()) => {
+                notify_info(None, format!("Saved template {name} to {path:?}"));
+            }
+            Err(err) => {
+                notify_error(None, format!("Unable to save template: {err}"));
+            }
+        }
+        world.resource_mut::<WorkcellTemplateLibrary>().scan();
+    }
+}
+
+/// The root of the workcell that an entity belongs to
+pub fn find_workcell_root(world: &World, e: Entity) -> Option<Entity> {
+    let mut current = e;
+    loop {
+        if world.get::<WorkcellProperties>(current).is_some() {
+            return Some(current);
+        }
+        current = world.get::<Parent>(current)?.get();
+    }
+}
+
+pub fn handle_insert_workcell_templates(
+    mut commands: Commands,
+    mut requests: EventReader<InsertWorkcellTemplate>,
+    mut dependents: Query<&mut Dependents>,
+    mut next_site_ids: Query<&mut NextSiteID>,
+    parents: Query<&Parent>,
+) {
+    for request in requests.iter() {
+        let bytes = match std::fs::read(&request.template) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                notify_error(
+                    None,
+                    format!("Unable to read template {:?}: {err}", request.template),
+                );
+                continue;
+            }
+        };
+        let mut template = match Workcell::from_bytes(&bytes) {
+            Ok(template) => template,
+            Err(err) => {
+                notify_error(
+                    None,
+                    format!("Unable to parse template {:?}: {err}", request.template),
+                );
+                continue;
+            }
+        };
+        // The ids of the template would collide with the SiteIDs of the
+        // workcell, so give its elements the next ids that are free.
+        let Some(mut next_site_id) = std::iter::once(request.parent)
+            .chain(AncestorIter::new(&parents, request.parent))
+            .find(|e| next_site_ids.contains(*e))
+            .and_then(|root| next_site_ids.get_mut(root).ok())
+        else {
+            notify_error(
+                Some(request.parent),
+                format!(
+                    "Unable to insert template: {:?} is not in a workcell",
+                    request.parent
+                ),
+            );
+            continue;
+        };
+        template.offset_ids(next_site_id.0);
+        next_site_id.0 = template.highest_id() + 1;
+        template.add_name_prefix(&request.prefix);
+        insert_workcell_elements(&mut commands, &mut dependents, &template, request.parent);
+    }
+}
+
+/// Spawn the elements of a workcell beneath an entity of another workcell
+pub fn insert_workcell_elements(
+    commands: &mut Commands,
+    dependents: &mut Query<&mut Dependents>,
+    workcell: &Workcell,
+    parent: Entity,
+) {
+    let children = generate_workcell_elements(commands, workcell);
+    if let Ok(mut deps) = dependents.get_mut(parent) {
+        deps.extend(children.iter().copied());
+    } else {
+        commands
+            .entity(parent)
+            .insert(Dependents(children.iter().copied().collect()));
+    }
+    commands.entity(parent).push_children(&children);
+}
@@ -15,114 +15,61 @@
  *
 */
 
-use bevy::prelude::*;
-use std::collections::{HashMap, HashSet};
-
-use rmf_site_format::{
-    Anchor, Angle, Category, Link, MeshPrimitive, Pose, Rotation, UrdfRoot,
-    WorkcellCollisionMarker, WorkcellModel, WorkcellVisualMarker,
+use crate::{
+    site::{Dependents, NextSiteID},
+    workcell::insert_workcell_elements,
 };
+use bevy::prelude::*;
+use rmf_site_format::{Anchor, Pose, UrdfRoot, Workcell};
 
-use urdf_rs::JointType;
-
-use bevy_rapier3d::na::geometry::Rotation as RapierRotation;
-use bevy_rapier3d::prelude::*;
-use bevy_rapier3d::rapier::math::Isometry;
+/// Turn urdf models that were placed in a workcell into frames, joints and
+/// geometry of the workcell, so the robot can be edited and exported again.
+/// The robot is placed where the model was and the model is removed.
+pub fn handle_new_urdf_roots(
+    mut commands: Commands,
+    new_urdfs: Query<(&UrdfRoot, &Parent)>,
+    models: Query<(&Pose, &Parent)>,
+    mut dependents: Query<&mut Dependents>,
+    mut next_site_ids: Query<&mut NextSiteID>,
+    parents: Query<&Parent>,
+) {
+    for (urdf, model) in &new_urdfs {
+        let model = model.get();
+        let Ok((model_pose, parent)) = models.get(model) else {
+            continue;
+        };
+        let parent = parent.get();
 
-pub fn handle_new_urdf_roots(mut commands: Commands, new_urdfs: Query<(Entity, &UrdfRoot)>) {
-    let mut link_name_to_entity = HashMap::new();
-    // Keep track of which links have a parent, add the ones that don't as a root child
-    let mut root_links = HashSet::new();
-    for (e, urdf) in new_urdfs.iter() {
-        commands.entity(e).insert(RigidBody::KinematicVelocityBased);
-        // Populate here
-        for link in &urdf.links {
-            // TODO*luca) link as child of anchor
-            let link_entity = commands
-                .spawn(SpatialBundle::VISIBLE_IDENTITY)
-                .insert(Link::from(link))
-                .insert(RigidBody::KinematicVelocityBased)
-                .insert(Category::Workcell)
-                .id();
-            println!("Found link {:?} - {}", link_entity, link.name);
-            link_name_to_entity.insert(link.name.clone(), link_entity);
-            root_links.insert(link_entity);
-            for visual in &link.visual {
-                let model = WorkcellModel::from(visual);
-                let cmd = commands.spawn((SpatialBundle::VISIBLE_IDENTITY, WorkcellVisualMarker));
-                let id = cmd.id();
-                model.add_bevy_components(cmd);
-                commands.entity(link_entity).add_child(id);
+        let mut workcell = Workcell::from_urdf(urdf);
+        let model_tf = model_pose.transform();
+        let root_id = workcell.id;
+        // The base link of the robot is placed where the model was. Its pose
+        // is not exported back to urdf, see Workcell::to_urdf.
+        for frame in workcell.frames.values_mut() {
+            if frame.parent != root_id {
+                continue;
             }
-            for collision in &link.collision {
-                let model = WorkcellModel::from(collision);
-                let cmd =
-                    commands.spawn((SpatialBundle::VISIBLE_IDENTITY, WorkcellCollisionMarker));
-                let id = cmd.id();
-                model.add_bevy_components(cmd);
-                commands.entity(link_entity).add_child(id);
+            if let Anchor::Pose3D(pose) = &mut frame.bundle.anchor {
+                pose.align_with(&(model_tf * pose.transform()));
             }
         }
-        for joint in &urdf.joints {
-            if let Some(parent) = link_name_to_entity.get(&joint.parent.link) {
-                if let Some(child) = link_name_to_entity.get(&joint.child.link) {
-                    let trans = Vec3::from_array(joint.origin.xyz.map(|t| t as f32));
-                    let rot = Vec3::from_array(joint.origin.rpy.map(|t| t as f32));
-                    let rot = RapierRotation::from_euler_angles(rot[0], rot[1], rot[2]);
-                    // TODO(luca) invert the above since it's in joint coordinates
-                    let frame = Isometry::<f32>::from_parts(trans.into(), rot.into());
-                    let joint_data = match joint.joint_type {
-                        JointType::Revolute => {
-                            let axis = Vec3::from_array(joint.axis.xyz.map(|t| t as f32));
-                            let joint = RevoluteJointBuilder::new(axis)
-                                //.local_anchor2(trans)
-                                .limits([joint.limit.lower as f32, joint.limit.upper as f32]);
-                            ImpulseJoint::new(*parent, joint)
-                        }
-                        JointType::Prismatic => {
-                            let axis = Vec3::from_array(joint.axis.xyz.map(|t| t as f32));
-                            let joint = PrismaticJointBuilder::new(axis)
-                                //.local_anchor2(trans)
-                                .local_axis2(axis)
-                                .limits([joint.limit.lower as f32, joint.limit.upper as f32]);
-                            ImpulseJoint::new(*parent, joint)
-                        }
-                        JointType::Fixed => {
-                            let joint = FixedJointBuilder::new()
-                                .local_anchor1(trans)
-                                //.local_anchor2(trans)
-                                //.local_basis2(rot.into())
-                                ;
-                            ImpulseJoint::new(*parent, joint)
-                        }
-                        _ => {
-                            todo!("Unimplemented joint type {:?}", joint.joint_type);
-                        }
-                    };
-                    let trans = joint.origin.xyz.map(|t| t as f32);
-                    let rot = Rotation::EulerExtrinsicXYZ(
-                        joint.origin.rpy.map(|angle| Angle::Rad(angle as f32)),
-                    );
-                    //commands.entity(*child).insert(AnchorBundle::new(Anchor::Pose3D(Pose {trans, rot})));
-                    commands.entity(*parent).add_child(*child);
-                    root_links.remove(child);
-                    println!(
-                        "Adding joint between {:?} - {} and {:?} - {}",
-                        *parent, &joint.parent.link, *child, &joint.child.link
-                    );
-                    commands.entity(*child).with_children(|children| {
-                        children
-                            .spawn(SpatialBundle::VISIBLE_IDENTITY)
-                            .insert(joint_data)
-                            .insert(Anchor::Pose3D(Pose { trans, rot }));
-                    });
-                }
-            }
-        }
-        for link in root_links.iter() {
-            println!("Found root entity {:?}", link);
-            commands.entity(e).add_child(*link);
+
+        // Move the elements of the robot to ids that the workcell does not
+        // use yet
+        let Some(mut next_site_id) = std::iter::once(parent)
+            .chain(AncestorIter::new(&parents, parent))
+            .find(|e| next_site_ids.contains(*e))
+            .and_then(|root| next_site_ids.get_mut(root).ok())
+        else {
+            continue;
+        };
+        workcell.offset_ids(next_site_id.0);
+        next_site_id.0 = workcell.highest_id() + 1;
+
+        insert_workcell_elements(&mut commands, &mut dependents, &workcell, parent);
+        if let Ok(mut deps) = dependents.get_mut(parent) {
+            deps.remove(&model);
         }
-        commands.entity(e).remove::<UrdfRoot>();
+        commands.entity(model).despawn_recursive();
     }
 }
@@ -0,0 +1,75 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+
+use rmf_site_format::Workcell;
+
+use thiserror::Error;
+
+/// Lets saved workcells be loaded as assets, so they can be placed in sites
+/// like models
+pub struct WorkcellAssetPlugin;
+
+impl Plugin for WorkcellAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset_loader::<WorkcellLoader>()
+            .add_asset::<WorkcellRoot>();
+    }
+}
+
+#[derive(Component, Default, Debug, TypeUuid, Clone)]
+#[uuid = "fe707f9e-c6f3-11ed-afa3-0242ac120002"]
+pub struct WorkcellRoot {
+    pub workcell: Workcell,
+}
+
+#[derive(Default)]
+struct WorkcellLoader;
+
+impl AssetLoader for WorkcellLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move { Ok(load_workcell(bytes, load_context).await?) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["workcell.json"];
+        EXTENSIONS
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum WorkcellLoadingError {
+    #[error("Failed to parse workcell: {0}")]
+    ParsingError(#[from] serde_json::Error),
+}
+
+async fn load_workcell<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+) -> Result<(), WorkcellLoadingError> {
+    let workcell = Workcell::from_bytes(bytes)?;
+    load_context.set_default_asset(LoadedAsset::new(WorkcellRoot { workcell }));
+    Ok(())
+}
@@ -15,16 +15,20 @@
  *
 */
 
-use bevy::{prelude::*, tasks::AsyncComputeTaskPool};
+use crate::console::{notify_error, report_error};
+use bevy::{prelude::*, tasks::AsyncComputeTaskPool, utils::HashSet};
 use rfd::AsyncFileDialog;
 use std::path::PathBuf;
 
 use crate::interaction::InteractionState;
-use crate::site::LoadSite;
-use crate::workcell::LoadWorkcell;
-use crate::AppState;
+use crate::site::{
+    BackgroundUndoHistories, ChangeCurrentSite, CurrentLevel, LoadSite, SiteState, UndoHistory,
+};
+use crate::urdf_loader::expand_xacro;
+use crate::workcell::{ChangeCurrentWorkcell, LoadWorkcell};
+use crate::{autosave_path, AppState, AutosaveStatus, WorkspaceFileWatch};
 use rmf_site_format::legacy::building_map::BuildingMap;
-use rmf_site_format::{Site, SiteProperties, Workcell};
+use rmf_site_format::{Site, SiteFileFormat, SiteProperties, Workcell, WorkcellProperties};
 
 use crossbeam_channel::{Receiver, Sender};
 
@@ -35,6 +39,13 @@ pub struct ChangeCurrentWorkspace {
     pub root: Entity,
 }
 
+/// Used as an event to command that a workspace should be closed. Changes to
+/// the workspace that have not been saved are lost.
+#[derive(Clone, Copy, Debug)]
+pub struct CloseWorkspace {
+    pub root: Entity,
+}
+
 /// Used as an event to command that a new workspace should be created, behavior will depend on
 /// what app mode the editor is currently in
 pub struct CreateNewWorkspace;
@@ -49,12 +60,16 @@ pub enum LoadWorkspace {
     Dialog,
     Path(PathBuf),
     Data(WorkspaceData),
+    /// Restore the autosave of a file. The workspace keeps the file as its
+    /// default file, so saving it will overwrite the file and not the autosave.
+    Autosave(PathBuf),
 }
 
 pub enum WorkspaceData {
     LegacyBuilding(Vec<u8>),
     Site(Vec<u8>),
     Workcell(Vec<u8>),
+    Urdf(Vec<u8>),
 }
 
 impl WorkspaceData {
@@ -62,12 +77,22 @@ impl WorkspaceData {
         let filename = path.file_name().and_then(|f| f.to_str())?;
         if filename.ends_with(".building.yaml") {
             Some(WorkspaceData::LegacyBuilding(data))
-        } else if filename.ends_with("site.ron") {
+        } else if SiteFileFormat::from_path(path).is_some() {
             Some(WorkspaceData::Site(data))
         } else if filename.ends_with("workcell.json") {
             Some(WorkspaceData::Workcell(data))
+        } else if filename.ends_with(".urdf") {
+            Some(WorkspaceData::Urdf(data))
+        } else if filename.ends_with(".xacro") {
+            match expand_xacro(&data, path) {
+                Ok(urdf) => Some(WorkspaceData::Urdf(urdf.into_bytes())),
+                Err(err) => {
+                    notify_error(None, format!("Unable to open {:?}: {err}", filename));
+                    None
+                }
+            }
         } else {
-            println!("Unrecognized file type {:?}", filename);
+            notify_error(None, format!("Unrecognized file type {:?}", filename));
             None
         }
     }
@@ -97,6 +122,21 @@ impl Default for LoadWorkspaceChannels {
     }
 }
 
+/// Used as a resource to keep track of whether the current workspace has been
+/// changed since it was last saved or opened
+#[derive(Clone, Copy, Debug, Default, Deref, DerefMut, Resource)]
+pub struct UnsavedChanges(pub bool);
+
+/// Workspaces that are open in the background and have changes which have not
+/// been saved. The current workspace is tracked by [`UnsavedChanges`].
+#[derive(Debug, Default, Resource)]
+pub struct BackgroundUnsavedChanges(pub HashSet<Entity>);
+
+/// Set while a workspace is being restored from an autosave, so that it
+/// starts out with unsaved changes once it becomes the current workspace
+#[derive(Debug, Default, Resource)]
+pub struct RestoringAutosave(pub bool);
+
 /// Used to keep track of visibility when switching workspace
 #[derive(Debug, Default, Resource)]
 pub struct RecallWorkspace(Option<Entity>);
@@ -113,12 +153,18 @@ pub struct WorkspacePlugin;
 impl Plugin for WorkspacePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ChangeCurrentWorkspace>()
+            .add_event::<CloseWorkspace>()
             .add_event::<CreateNewWorkspace>()
             .add_event::<LoadWorkspace>()
             .init_resource::<CurrentWorkspace>()
             .init_resource::<RecallWorkspace>()
+            .init_resource::<UnsavedChanges>()
+            .init_resource::<RestoringAutosave>()
+            .init_resource::<BackgroundUnsavedChanges>()
             .init_resource::<LoadWorkspaceChannels>()
             .add_system(dispatch_new_workspace_events)
+            .add_system(dispatch_change_workspace_events)
+            .add_system(close_workspaces)
             .add_system(workspace_file_load_complete)
             .add_system(sync_workspace_visibility)
             .add_system(dispatch_load_workspace_events);
@@ -134,7 +180,10 @@ pub fn dispatch_new_workspace_events(
     if let Some(_cmd) = new_workspace.iter().last() {
         match state.current() {
             AppState::MainMenu => {
-                println!("DEV ERROR: Sent generic change workspace while in main menu");
+                report_error(
+                    None,
+                    "DEV ERROR: Sent generic change workspace while in main menu",
+                );
             }
             AppState::SiteEditor => {
                 load_site.send(LoadSite {
@@ -154,6 +203,102 @@ pub fn dispatch_new_workspace_events(
     }
 }
 
+/// Switch to the editor that belongs to the chosen workspace and make the
+/// workspace the current one
+pub fn dispatch_change_workspace_events(
+    mut app_state: ResMut<State<AppState>>,
+    mut site_display_state: ResMut<State<SiteState>>,
+    mut change_workspace: EventReader<ChangeCurrentWorkspace>,
+    mut change_site: EventWriter<ChangeCurrentSite>,
+    mut change_workcell: EventWriter<ChangeCurrentWorkcell>,
+    open_sites: Query<(), With<SiteProperties>>,
+    open_workcells: Query<(), With<WorkcellProperties>>,
+) {
+    let Some(cmd) = change_workspace.iter().last() else {
+        return;
+    };
+
+    if open_sites.contains(cmd.root) {
+        if *app_state.current() != AppState::SiteEditor {
+            app_state.set(AppState::SiteEditor).ok();
+        }
+        if *site_display_state.current() == SiteState::Off {
+            site_display_state.set(SiteState::Display).ok();
+        }
+        change_site.send(ChangeCurrentSite {
+            site: cmd.root,
+            level: None,
+        });
+    } else if open_workcells.contains(cmd.root) {
+        if *app_state.current() != AppState::WorkcellEditor {
+            app_state.set(AppState::WorkcellEditor).ok();
+        }
+        if *site_display_state.current() == SiteState::Display {
+            site_display_state.set(SiteState::Off).ok();
+        }
+        change_workcell.send(ChangeCurrentWorkcell { root: cmd.root });
+    } else {
+        report_error(
+            None,
+            format!(
+                "Requested workspace change to an entity that is not an open workspace: {:?}",
+                cmd.root
+            ),
+        );
+    }
+}
+
+/// Despawn closed workspaces. When the current workspace is closed, another
+/// open workspace takes its place, or the editor goes back to the main menu
+/// if there are none left.
+pub fn close_workspaces(
+    mut commands: Commands,
+    mut close_workspace: EventReader<CloseWorkspace>,
+    mut change_workspace: EventWriter<ChangeCurrentWorkspace>,
+    mut current_workspace: ResMut<CurrentWorkspace>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut background_unsaved: ResMut<BackgroundUnsavedChanges>,
+    mut background_undo: ResMut<BackgroundUndoHistories>,
+    mut autosave_status: ResMut<AutosaveStatus>,
+    mut app_state: ResMut<State<AppState>>,
+    mut interaction_state: ResMut<State<InteractionState>>,
+    mut site_display_state: ResMut<State<SiteState>>,
+    open_workspaces: Query<Entity, Or<(With<SiteProperties>, With<WorkcellProperties>)>>,
+) {
+    let closed: HashSet<Entity> = close_workspace.iter().map(|cmd| cmd.root).collect();
+    if closed.is_empty() {
+        return;
+    }
+
+    for root in &closed {
+        if open_workspaces.contains(*root) {
+            commands.entity(*root).despawn_recursive();
+        }
+        background_unsaved.0.remove(root);
+        background_undo.0.remove(root);
+        autosave_status.0.remove(root);
+    }
+
+    let Some(current) = current_workspace.root else {
+        return;
+    };
+    if !closed.contains(&current) {
+        return;
+    }
+
+    if let Some(next) = open_workspaces.iter().find(|e| !closed.contains(e)) {
+        change_workspace.send(ChangeCurrentWorkspace { root: next });
+    } else {
+        current_workspace.root = None;
+        current_level.0 = None;
+        if *site_display_state.current() == SiteState::Display {
+            site_display_state.set(SiteState::Off).ok();
+        }
+        interaction_state.set(InteractionState::Disable).ok();
+        app_state.set(AppState::MainMenu).ok();
+    }
+}
+
 pub fn dispatch_load_workspace_events(
     mut commands: Commands,
     mut app_state: ResMut<State<AppState>>,
@@ -162,6 +307,8 @@ pub fn dispatch_load_workspace_events(
     mut load_site: EventWriter<LoadSite>,
     mut load_workcell: EventWriter<LoadWorkcell>,
     mut load_workspace: EventReader<LoadWorkspace>,
+    mut restoring: ResMut<RestoringAutosave>,
+    mut file_watch: ResMut<WorkspaceFileWatch>,
 ) {
     if let Some(cmd) = load_workspace.iter().last() {
         match cmd {
@@ -184,18 +331,22 @@ pub fn dispatch_load_workspace_events(
                     .detach();
             }
             LoadWorkspace::Path(path) => {
-                if let Some(data) = std::fs::read(&path)
+                let loading = std::fs::read(&path)
                     .ok()
                     .and_then(|d| WorkspaceData::new(&path, d))
-                {
-                    handle_workspace_data(
-                        Some(path.clone()),
-                        &data,
-                        &mut app_state,
-                        &mut interaction_state,
-                        &mut load_site,
-                        &mut load_workcell,
-                    );
+                    .map(|data| {
+                        handle_workspace_data(
+                            Some(path.clone()),
+                            &data,
+                            &mut app_state,
+                            &mut interaction_state,
+                            &mut load_site,
+                            &mut load_workcell,
+                        )
+                    })
+                    .unwrap_or(false);
+                if !loading {
+                    file_watch.load_failed(path);
                 }
             }
             LoadWorkspace::Data(data) => {
@@ -209,6 +360,36 @@ pub fn dispatch_load_workspace_events(
                     &mut load_workcell,
                 );
             }
+            LoadWorkspace::Autosave(path) => {
+                let autosave = autosave_path(path);
+                let data = match std::fs::read(&autosave) {
+                    Ok(data) => data,
+                    Err(err) => {
+                        notify_error(None, format!("Unable to read autosave {autosave:?}: {err}"));
+                        return;
+                    }
+                };
+                let restored = WorkspaceData::new(&autosave, data)
+                    .map(|data| {
+                        handle_workspace_data(
+                            Some(path.clone()),
+                            &data,
+                            &mut app_state,
+                            &mut interaction_state,
+                            &mut load_site,
+                            &mut load_workcell,
+                        )
+                    })
+                    .unwrap_or(false);
+                if restored {
+                    restoring.0 = true;
+                } else {
+                    notify_error(
+                        None,
+                        format!("Unable to restore the autosave of {}", path.display()),
+                    );
+                }
+            }
         }
     }
 }
@@ -220,14 +401,19 @@ fn handle_workspace_data(
     interaction_state: &mut ResMut<State<InteractionState>>,
     load_site: &mut EventWriter<LoadSite>,
     load_workcell: &mut EventWriter<LoadWorkcell>,
-) {
+) -> bool {
     match workspace_data {
         WorkspaceData::LegacyBuilding(data) => {
             println!("Opening legacy building map file");
             match BuildingMap::from_bytes(&data) {
                 Ok(building) => {
                     match building.to_site() {
-                        Ok(site) => {
+                        Ok(mut site) => {
+                            // Drawings of legacy buildings are relative to
+                            // the building file
+                            if let Some(root) = file.as_deref().and_then(|f| f.parent()) {
+                                site.resolve_asset_paths(root);
+                            }
                             // Switch state
                             app_state.set(AppState::SiteEditor).ok();
                             load_site.send(LoadSite {
@@ -236,21 +422,31 @@ fn handle_workspace_data(
                                 default_file: file,
                             });
                             interaction_state.set(InteractionState::Enable).ok();
+                            true
                         }
                         Err(err) => {
-                            println!("Failed converting to site {:?}", err);
+                            notify_error(None, format!("Failed converting to site: {err}"));
+                            false
                         }
                     }
                 }
                 Err(err) => {
-                    println!("Failed loading legacy building {:?}", err);
+                    notify_error(None, format!("Failed loading legacy building: {err}"));
+                    false
                 }
             }
         }
         WorkspaceData::Site(data) => {
             println!("Opening site file");
-            match Site::from_bytes(&data) {
-                Ok(site) => {
+            let format = file
+                .as_deref()
+                .and_then(SiteFileFormat::from_path)
+                .unwrap_or_default();
+            match Site::from_bytes_as(format, &data) {
+                Ok(mut site) => {
+                    if let Some(root) = file.as_deref().and_then(|f| f.parent()) {
+                        site.resolve_asset_paths(root);
+                    }
                     // Switch state
                     app_state.set(AppState::SiteEditor).ok();
                     load_site.send(LoadSite {
@@ -259,9 +455,11 @@ fn handle_workspace_data(
                         default_file: file,
                     });
                     interaction_state.set(InteractionState::Enable).ok();
+                    true
                 }
                 Err(err) => {
-                    println!("Failed loading site {:?}", err);
+                    notify_error(None, format!("Failed loading site: {err}"));
+                    false
                 }
             }
         }
@@ -277,9 +475,36 @@ fn handle_workspace_data(
                         default_file: file,
                     });
                     interaction_state.set(InteractionState::Enable).ok();
+                    true
                 }
                 Err(err) => {
-                    println!("Failed loading workcell {:?}", err);
+                    notify_error(None, format!("Failed loading workcell: {err}"));
+                    false
+                }
+            }
+        }
+        WorkspaceData::Urdf(data) => {
+            println!("Opening urdf file");
+            let robot = std::str::from_utf8(&data)
+                .map_err(|err| err.to_string())
+                .and_then(|s| urdf_rs::read_from_string(s).map_err(|err| err.to_string()));
+            match robot {
+                Ok(robot) => {
+                    // Switch state
+                    app_state.set(AppState::WorkcellEditor).ok();
+                    load_workcell.send(LoadWorkcell {
+                        workcell: Workcell::from_urdf(&robot),
+                        focus: true,
+                        // Saving should not overwrite the urdf with the
+                        // workcell format
+                        default_file: None,
+                    });
+                    interaction_state.set(InteractionState::Enable).ok();
+                    true
+                }
+                Err(err) => {
+                    notify_error(None, format!("Failed loading urdf {:?}", err));
+                    false
                 }
             }
         }
@@ -314,6 +539,11 @@ pub fn sync_workspace_visibility(
     current_workspace: Res<CurrentWorkspace>,
     mut recall: ResMut<RecallWorkspace>,
     mut visibility: Query<&mut Visibility>,
+    mut unsaved: ResMut<UnsavedChanges>,
+    mut restoring: ResMut<RestoringAutosave>,
+    mut background_unsaved: ResMut<BackgroundUnsavedChanges>,
+    mut undo_history: ResMut<UndoHistory>,
+    mut background_undo: ResMut<BackgroundUndoHistories>,
 ) {
     if !current_workspace.is_changed() {
         return;
@@ -332,6 +562,28 @@ pub fn sync_workspace_visibility(
                 v.is_visible = false;
             }
         }
+        // Remember whether the previous workspace still needs to be saved
+        if let Some(recall) = recall.0 {
+            if unsaved.0 {
+                background_unsaved.0.insert(recall);
+            }
+        }
+        // Each workspace keeps its own undo history. A workspace that was
+        // closed has already been despawned, so its history is dropped.
+        let previous = std::mem::take(&mut *undo_history);
+        if let Some(recall) = recall.0 {
+            if visibility.contains(recall) {
+                background_undo.0.insert(recall, previous);
+            }
+        }
+        if let Some(root) = current_workspace.root {
+            *undo_history = background_undo.0.remove(&root).unwrap_or_default();
+        }
         recall.0 = current_workspace.root;
+        let was_unsaved = current_workspace
+            .root
+            .map_or(false, |root| background_unsaved.0.remove(&root));
+        // A restored autosave has not been saved to its file yet
+        unsaved.0 = std::mem::take(&mut restoring.0) || was_unsaved;
     }
 }
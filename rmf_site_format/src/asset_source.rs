@@ -43,6 +43,41 @@ impl AssetSource {
     }
 }
 
+impl AssetSource {
+    /// Turn a local path that is relative to the project root into an
+    /// absolute path. The project root is the folder of the site file.
+    pub fn resolve_from(&mut self, root: &Path) {
+        if let AssetSource::Local(path) = self {
+            if !path.is_empty() && is_relative(path) {
+                *path = root.join(&path).to_string_lossy().into_owned();
+            }
+        }
+    }
+
+    /// Refer to a local file relative to the project root if the file is
+    /// inside of it. The path is written with forward slashes so that it can
+    /// be resolved on any platform.
+    pub fn make_relative_to(&mut self, root: &Path) {
+        if let AssetSource::Local(path) = self {
+            if let Ok(relative) = Path::new(path).strip_prefix(root) {
+                *path = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+            }
+        }
+    }
+}
+
+/// Paths that start with a Windows drive letter or network share are absolute
+/// even when a site that was saved on Windows gets opened on another platform
+fn is_relative(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    Path::new(path).is_relative() && !drive && !path.starts_with("\\\\")
+}
+
 impl Default for AssetSource {
     fn default() -> Self {
         AssetSource::Local(String::new()).into()
@@ -124,3 +159,109 @@ impl Recall for RecallAssetSource {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(path: &str) -> AssetSource {
+        AssetSource::Local(path.to_owned())
+    }
+
+    fn resolved(path: &str, root: &str) -> AssetSource {
+        let mut source = local(path);
+        source.resolve_from(Path::new(root));
+        source
+    }
+
+    fn relative(path: &str, root: &str) -> AssetSource {
+        let mut source = local(path);
+        source.make_relative_to(Path::new(root));
+        source
+    }
+
+    #[test]
+    fn relative_paths_are_resolved_from_the_root() {
+        let expected = Path::new("/maps").join("drawings/office.png");
+        assert_eq!(
+            resolved("drawings/office.png", "/maps"),
+            local(expected.to_str().unwrap()),
+        );
+    }
+
+    #[test]
+    fn absolute_and_empty_paths_are_not_resolved() {
+        assert_eq!(
+            resolved("/other/office.png", "/maps"),
+            local("/other/office.png")
+        );
+        assert_eq!(resolved("", "/maps"), local(""));
+        assert_eq!(
+            resolved("C:\\other\\office.png", "/maps"),
+            local("C:\\other\\office.png"),
+        );
+        assert_eq!(
+            resolved("\\\\server\\office.png", "/maps"),
+            local("\\\\server\\office.png"),
+        );
+
+        let mut remote = AssetSource::Remote("drawings/office.png".to_owned());
+        remote.resolve_from(Path::new("/maps"));
+        assert_eq!(
+            remote,
+            AssetSource::Remote("drawings/office.png".to_owned())
+        );
+    }
+
+    #[test]
+    fn paths_inside_the_root_become_relative() {
+        assert_eq!(
+            relative("/maps/drawings/office.png", "/maps"),
+            local("drawings/office.png"),
+        );
+        let mut source = resolved("drawings/office.png", "/maps");
+        source.make_relative_to(Path::new("/maps"));
+        assert_eq!(source, local("drawings/office.png"));
+    }
+
+    #[test]
+    fn paths_outside_the_root_stay_absolute() {
+        assert_eq!(
+            relative("/other/office.png", "/maps"),
+            local("/other/office.png"),
+        );
+        // Only whole folder names count as being inside of the root
+        assert_eq!(
+            relative("/maps2/office.png", "/maps"),
+            local("/maps2/office.png"),
+        );
+    }
+
+    #[test]
+    fn relative_paths_are_not_changed() {
+        assert_eq!(
+            relative("drawings/office.png", "/maps"),
+            local("drawings/office.png"),
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_roots() {
+        assert_eq!(
+            relative("C:\\maps\\drawings\\office.png", "C:\\maps"),
+            local("drawings/office.png"),
+        );
+        assert_eq!(
+            relative("D:\\maps\\office.png", "C:\\maps"),
+            local("D:\\maps\\office.png"),
+        );
+        assert_eq!(
+            resolved("drawings/office.png", "C:\\maps"),
+            local("C:\\maps\\drawings/office.png"),
+        );
+        let mut source = resolved("drawings/office.png", "C:\\maps");
+        source.make_relative_to(Path::new("C:\\maps"));
+        assert_eq!(source, local("drawings/office.png"));
+    }
+}
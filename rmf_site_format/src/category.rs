@@ -40,9 +40,12 @@ pub enum Category {
     Location,
     Measurement,
     Model,
+    PrimitiveShape,
     Camera,
     Drawing,
     Workcell,
+    Joint,
+    Sensor,
 }
 
 impl Category {
@@ -61,9 +64,12 @@ impl Category {
             Self::Location => "Location",
             Self::Measurement => "Measurement",
             Self::Model => "Model",
+            Self::PrimitiveShape => "Primitive Shape",
             Self::Camera => "Camera",
             Self::Drawing => "Drawing",
             Self::Workcell => "Workcell",
+            Self::Joint => "Joint",
+            Self::Sensor => "Sensor",
         }
     }
 
@@ -73,7 +79,12 @@ impl Category {
     // be assigned the VisualCue component.
     pub fn is_physical(&self) -> bool {
         match self {
-            Self::Door | Self::Wall | Self::Floor | Self::Lift | Self::Model => true,
+            Self::Door
+            | Self::Wall
+            | Self::Floor
+            | Self::Lift
+            | Self::Model
+            | Self::PrimitiveShape => true,
             // TODO(MXG): Consider whether Light and Camera should be considered physical
             _ => false,
         }
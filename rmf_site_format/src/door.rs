@@ -32,10 +32,46 @@ pub struct Door<T: RefTrait> {
     pub name: NameInSite,
     /// What kind of door is it.
     pub kind: DoorType,
+    /// Whether the door blocks occupancy grids
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub occupancy: DoorOccupancy,
     #[serde(skip)]
     pub marker: DoorMarker,
 }
 
+/// Decide whether a door should be treated as open or closed when calculating
+/// occupancy grids.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum DoorOccupancy {
+    /// Follow the setting of the occupancy calculation
+    #[default]
+    Default,
+    Open,
+    Closed,
+}
+
+impl DoorOccupancy {
+    pub const ALL: [DoorOccupancy; 3] = [Self::Default, Self::Open, Self::Closed];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::Open => "Open",
+            Self::Closed => "Closed",
+        }
+    }
+
+    /// Check whether the door is open, given how doors are treated by default
+    pub fn is_open(&self, open_by_default: bool) -> bool {
+        match self {
+            Self::Default => open_by_default,
+            Self::Open => true,
+            Self::Closed => false,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "bevy", derive(Component))]
 pub enum DoorType {
@@ -379,6 +415,7 @@ impl Door<Entity> {
             anchors,
             name: self.name.clone(),
             kind: self.kind.clone(),
+            occupancy: self.occupancy,
             marker: Default::default(),
         }
     }
@@ -391,6 +428,7 @@ impl Door<u32> {
             anchors: self.anchors.to_ecs(id_to_entity),
             name: self.name.clone(),
             kind: self.kind.clone(),
+            occupancy: self.occupancy,
             marker: Default::default(),
         }
     }
@@ -402,6 +440,7 @@ impl<T: RefTrait> From<Edge<T>> for Door<T> {
             anchors: edge,
             name: NameInSite("<Unnamed>".to_string()),
             kind: SingleSlidingDoor::default().into(),
+            occupancy: Default::default(),
             marker: Default::default(),
         }
     }
@@ -0,0 +1,125 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+use crate::*;
+#[cfg(feature = "bevy")]
+use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/// The outdoor surroundings of a site: the sun that lights it and the sky
+/// that is drawn behind it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct Environment {
+    /// The sun that lights the site, if it has one. Indoor sites usually
+    /// leave this empty and rely on their own lights.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sun: Option<Sun>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub sky: Sky,
+}
+
+impl Environment {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Sun {
+    /// Direction of the sun measured counter-clockwise from the x axis of
+    /// the site
+    pub azimuth: Angle,
+    /// Height of the sun above the horizon
+    pub elevation: Angle,
+    /// Illuminance of the sunlight in lux
+    pub illuminance: f32,
+    pub color: [f32; 3],
+    #[serde(default = "default_sun_shadows")]
+    pub shadows: bool,
+}
+
+fn default_sun_shadows() -> bool {
+    true
+}
+
+impl Default for Sun {
+    fn default() -> Self {
+        Self {
+            azimuth: Angle::Deg(135.0),
+            elevation: Angle::Deg(45.0),
+            illuminance: 20000.0,
+            color: [1.0, 0.96, 0.9],
+            shadows: true,
+        }
+    }
+}
+
+impl Sun {
+    /// Unit vector that points from the site towards the sun
+    pub fn direction(&self) -> [f32; 3] {
+        let (az, el) = (self.azimuth.radians(), self.elevation.radians());
+        [el.cos() * az.cos(), el.cos() * az.sin(), el.sin()]
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Sky {
+    /// Fill the background with a single color
+    Solid { color: [f32; 3] },
+    /// Blend between a horizon color and a zenith color, with the ground
+    /// color filling everything below the horizon
+    Procedural {
+        zenith: [f32; 3],
+        horizon: [f32; 3],
+        ground: [f32; 3],
+    },
+    /// Wrap an equirectangular panorama image around the site
+    Hdri { source: AssetSource },
+}
+
+impl Default for Sky {
+    fn default() -> Self {
+        Sky::Solid {
+            color: [0.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl Sky {
+    pub fn procedural() -> Self {
+        Sky::Procedural {
+            zenith: [0.25, 0.45, 0.8],
+            horizon: [0.75, 0.85, 0.95],
+            ground: [0.35, 0.33, 0.3],
+        }
+    }
+
+    pub fn hdri() -> Self {
+        Sky::Hdri {
+            source: AssetSource::Local(String::new()),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Solid { .. } => "Solid",
+            Self::Procedural { .. } => "Procedural",
+            Self::Hdri { .. } => "HDRI",
+        }
+    }
+}
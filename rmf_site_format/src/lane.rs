@@ -17,7 +17,8 @@
 
 use crate::*;
 #[cfg(feature = "bevy")]
-use bevy::prelude::{Bundle, Component, Entity};
+use bevy::prelude::{Bundle, Component, Deref, DerefMut, Entity};
+use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -33,6 +34,15 @@ pub struct Lane<T: RefTrait> {
     pub reverse: ReverseLane,
     /// What graphs this lane is associated with
     pub graphs: AssociatedGraphs<T>,
+    /// How strongly planners should avoid this lane
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub cost: LaneCost,
+    /// Control points that bend the lane into a smooth curve
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub curve: LaneCurve,
+    /// A zone that this lane belongs to which is shared between fleets
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub shared_zone: SharedZone,
     /// Marker that tells bevy the entity is a Lane-type
     #[serde(skip)]
     pub marker: LaneMarker,
@@ -42,6 +52,94 @@ pub struct Lane<T: RefTrait> {
 #[cfg_attr(feature = "bevy", derive(Component))]
 pub struct LaneMarker;
 
+/// A multiplier that planners apply to the cost of traversing a lane. Values
+/// greater than 1 discourage planners from using the lane without forbidding
+/// it entirely.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(transparent)]
+#[cfg_attr(feature = "bevy", derive(Component, Deref, DerefMut))]
+pub struct LaneCost(pub f32);
+
+impl Default for LaneCost {
+    fn default() -> Self {
+        LaneCost(1.0)
+    }
+}
+
+/// The name of a zone that a lane belongs to which is shared between the
+/// graphs of different fleets, such as a corridor that two fleets drive
+/// through. Traffic inside of a shared zone needs to be negotiated between the
+/// fleets instead of being planned by each fleet on its own.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+#[cfg_attr(feature = "bevy", derive(Component, Deref, DerefMut))]
+pub struct SharedZone(pub Option<String>);
+
+/// Intermediate control points that bend a lane into a smooth curve between
+/// its anchors. Each point is `[along, across]` where `along` is the fraction
+/// of the way from the start anchor to the end anchor and `across` is the
+/// distance in meters to the left of the straight line between the anchors.
+/// Keeping the points relative to the anchors lets the curve follow along
+/// when the anchors are moved.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+#[cfg_attr(feature = "bevy", derive(Component, Deref, DerefMut))]
+pub struct LaneCurve(pub Vec<[f32; 2]>);
+
+impl LaneCurve {
+    pub fn is_straight(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the control points of the curve for a lane that goes from `start`
+    /// to `end`.
+    pub fn control_points(&self, start: Vec2, end: Vec2) -> Vec<Vec2> {
+        let dp = end - start;
+        let left = dp.perp().normalize_or_zero();
+        self.0
+            .iter()
+            .map(|[along, across]| start + *along * dp + *across * left)
+            .collect()
+    }
+
+    /// Get a polyline that follows the lane from `start` to `end`. The curve
+    /// is a Catmull-Rom spline that passes through each control point, with
+    /// `resolution` straight pieces between each pair of points.
+    pub fn path(&self, start: Vec2, end: Vec2, resolution: usize) -> Vec<Vec2> {
+        let mut knots = vec![start];
+        knots.extend(self.control_points(start, end));
+        knots.push(end);
+        if knots.len() == 2 {
+            return knots;
+        }
+
+        let resolution = resolution.max(1);
+        let last = knots.len() - 1;
+        let mut path = vec![start];
+        for i in 0..last {
+            let p0 = knots[i.saturating_sub(1)];
+            let p1 = knots[i];
+            let p2 = knots[i + 1];
+            let p3 = knots[(i + 2).min(last)];
+            for k in 1..=resolution {
+                let t = k as f32 / resolution as f32;
+                path.push(catmull_rom(p0, p1, p2, p3, t));
+            }
+        }
+
+        path
+    }
+}
+
+fn catmull_rom(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 #[cfg_attr(feature = "bevy", derive(Component))]
 pub struct Motion {
@@ -200,6 +298,9 @@ impl Lane<u32> {
             forward: self.forward.clone(),
             reverse: self.reverse.clone(),
             graphs: self.graphs.to_ecs(id_to_entity),
+            cost: self.cost,
+            curve: self.curve.clone(),
+            shared_zone: self.shared_zone.clone(),
             marker: Default::default(),
         }
     }
@@ -212,6 +313,9 @@ impl<T: RefTrait> From<Edge<T>> for Lane<T> {
             forward: Default::default(),
             reverse: Default::default(),
             graphs: Default::default(),
+            cost: Default::default(),
+            curve: Default::default(),
+            shared_zone: Default::default(),
             marker: Default::default(),
         }
     }
@@ -280,6 +280,8 @@ impl BuildingMap {
                     measurements,
                     models,
                     physical_cameras,
+                    primitive_shapes: BTreeMap::new(),
+                    sensors: BTreeMap::new(),
                     walls,
                     rankings,
                 },
@@ -340,6 +342,9 @@ impl BuildingMap {
                     forward: motion,
                     reverse,
                     graphs: AssociatedGraphs::Only([*graph_id].into()),
+                    cost: Default::default(),
+                    curve: Default::default(),
+                    shared_zone: Default::default(),
                     marker: LaneMarker,
                 };
 
@@ -370,6 +375,8 @@ impl BuildingMap {
                 NavGraph {
                     name: NameInSite("unnamed_graph_#".to_string() + &i.to_string()),
                     color: DisplayColor(DEFAULT_NAV_GRAPH_COLORS[color_index]),
+                    manual_color: Default::default(),
+                    lane_defaults: Default::default(),
                     marker: Default::default(),
                 },
             );
@@ -389,9 +396,12 @@ impl BuildingMap {
                     ranking: Vec::new(),
                     lanes,
                     locations,
+                    palette: Default::default(),
                 },
             },
             agents: Default::default(),
+            environment: Default::default(),
+            lights: Default::default(),
         })
     }
 }
@@ -111,6 +111,7 @@ impl Door {
             anchors: [*left_anchor, *right_anchor].into(),
             name: NameInSite(self.2.name.1.clone()),
             kind,
+            occupancy: Default::default(),
             marker: Default::default(),
         })
     }
@@ -45,6 +45,14 @@ pub enum PortingError {
     DeprecatedType(String),
     #[error("the data contained an unknown/invalid type: {0}")]
     InvalidType(String),
+    #[error("door name [{door}] is used on level [{level}] and level [{other_level}]")]
+    DuplicateDoorName {
+        door: String,
+        level: String,
+        other_level: String,
+    },
+    #[error("lift name [{0}] is used by more than one lift")]
+    DuplicateLiftName(String),
 }
 
 pub type Result<T> = std::result::Result<T, PortingError>;
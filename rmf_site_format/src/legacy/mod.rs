@@ -30,6 +30,7 @@ pub mod nav_graph;
 pub(crate) mod optimization;
 pub mod physical_camera;
 pub mod rbmf;
+pub mod supervisor;
 pub mod vertex;
 pub mod wall;
 pub use error::{PortingError, Result};
@@ -1,6 +1,6 @@
 use crate::{
-    Angle, AssetSource, ConstraintDependents, IsStatic, Model as SiteModel, ModelMarker,
-    NameInSite, Pose, Rotation, Scale,
+    Angle, AssetSource, ConstraintDependents, IsStatic, LodPolicy, MaterialOverride,
+    Model as SiteModel, ModelMarker, ModelTags, NameInSite, Pose, Rotation, Scale,
 };
 use glam::DVec2;
 use serde::{Deserialize, Serialize};
@@ -35,6 +35,9 @@ impl Model {
             is_static: IsStatic(self.static_),
             constraints: ConstraintDependents::default(),
             scale: Scale::default(),
+            tags: ModelTags::default(),
+            material: MaterialOverride::default(),
+            lod: LodPolicy::default(),
             marker: ModelMarker,
         }
     }
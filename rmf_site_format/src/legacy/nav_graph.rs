@@ -1,9 +1,15 @@
+use super::{PortingError, Result};
 use crate::*;
-use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-#[derive(Serialize, Clone)]
+/// Curved lanes are exported as chains of straight lanes with this many lanes
+/// between each pair of control points.
+const CURVED_LANE_RESOLUTION: usize = 4;
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct NavGraph {
+    #[serde(default)]
     building_name: String,
     levels: HashMap<String, NavLevel>,
 }
@@ -69,17 +75,38 @@ impl NavGraph {
                         }
                     };
 
-                    let props = NavLaneProperties::from_motion(&lane.forward);
-                    lanes.push(NavLane(v0, v1, props.clone()));
-                    match &lane.reverse {
-                        ReverseLane::Same => {
-                            lanes.push(NavLane(v1, v0, props));
-                        }
-                        ReverseLane::Different(motion) => {
-                            lanes.push(NavLane(v1, v0, NavLaneProperties::from_motion(motion)));
+                    // Nav graphs only have straight lanes, so curved lanes
+                    // get broken up into a chain of vertices along the curve.
+                    let mut chain = vec![v0];
+                    if !lane.curve.is_straight() {
+                        let position = |v: usize| glam::Vec2::new(vertices[v].0, vertices[v].1);
+                        let path =
+                            lane.curve
+                                .path(position(v0), position(v1), CURVED_LANE_RESOLUTION);
+                        for p in &path[1..path.len() - 1] {
+                            chain.push(vertices.len());
+                            vertices.push(NavVertex(p.x, p.y, Default::default()));
                         }
-                        ReverseLane::Disable => {
-                            // Do nothing
+                    }
+                    chain.push(v1);
+
+                    let defaults = &graph.lane_defaults;
+                    let props = NavLaneProperties::from_motion(&defaults.apply_to(&lane.forward))
+                        .with_cost(&lane.cost)
+                        .with_shared_zone(&lane.shared_zone);
+                    let reverse_props = match &lane.reverse {
+                        ReverseLane::Same => Some(props.clone()),
+                        ReverseLane::Different(motion) => Some(
+                            NavLaneProperties::from_motion(&defaults.apply_to(motion))
+                                .with_cost(&lane.cost)
+                                .with_shared_zone(&lane.shared_zone),
+                        ),
+                        ReverseLane::Disable => None,
+                    };
+                    for pair in chain.windows(2) {
+                        lanes.push(NavLane(pair[0], pair[1], props.clone()));
+                        if let Some(reverse_props) = &reverse_props {
+                            lanes.push(NavLane(pair[1], pair[0], reverse_props.clone()));
                         }
                     }
                 }
@@ -100,36 +127,213 @@ impl NavGraph {
     }
 }
 
-#[derive(Serialize, Clone)]
+impl NavGraph {
+    pub fn from_bytes(data: &[u8]) -> serde_yaml::Result<NavGraph> {
+        serde_yaml::from_slice(data)
+    }
+
+    /// Reconstruct a site that contains only this nav graph. Each level of the
+    /// site only has the anchors that are needed by the graph, so the result
+    /// is meant to be imported into an existing site whose level names match.
+    pub fn to_site(&self, graph_name: &str) -> Result<Site> {
+        let mut site_id = 0_u32..;
+        let graph_id = site_id.next().unwrap();
+        let mut levels = BTreeMap::new();
+        let mut lanes = BTreeMap::new();
+        let mut locations = BTreeMap::new();
+
+        // Sort the levels so the IDs come out the same every time
+        let mut nav_levels: Vec<_> = self.levels.iter().collect();
+        nav_levels.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (level_name, nav_level) in nav_levels {
+            let mut level = Level::new(
+                LevelProperties {
+                    name: level_name.clone(),
+                    elevation: 0.0,
+                },
+                RankingsInLevel::default(),
+            );
+
+            let mut vertex_to_anchor = Vec::new();
+            for vertex in &nav_level.vertices {
+                let anchor_id = site_id.next().unwrap();
+                level.anchors.insert(anchor_id, [vertex.0, vertex.1].into());
+                vertex_to_anchor.push(anchor_id);
+
+                let tags = vertex.2.to_tags();
+                let parameters = vertex.2.to_parameters();
+                if !vertex.2.name.is_empty() || !tags.is_empty() || !parameters.is_empty() {
+                    locations.insert(
+                        site_id.next().unwrap(),
+                        Location {
+                            anchor: Point(anchor_id),
+                            tags,
+                            name: NameInSite(vertex.2.name.clone()),
+                            graphs: AssociatedGraphs::Only([graph_id].into()),
+                            parameters,
+                        },
+                    );
+                }
+            }
+
+            // The nav graph has one entry per direction of travel, so pair up
+            // the entries that go in opposite directions into a single lane.
+            let mut lane_between: HashMap<(usize, usize), u32> = HashMap::new();
+            for NavLane(v0, v1, props) in &nav_level.lanes {
+                let a0 = *vertex_to_anchor
+                    .get(*v0)
+                    .ok_or(PortingError::InvalidVertex(*v0))?;
+                let a1 = *vertex_to_anchor
+                    .get(*v1)
+                    .ok_or(PortingError::InvalidVertex(*v1))?;
+                let motion = props.to_motion();
+
+                if let Some(lane_id) = lane_between.get(&(*v1, *v0)) {
+                    let lane: &mut Lane<u32> = lanes.get_mut(lane_id).unwrap();
+                    if lane.reverse == ReverseLane::Disable {
+                        lane.reverse = if motion == lane.forward {
+                            ReverseLane::Same
+                        } else {
+                            ReverseLane::Different(motion)
+                        };
+                        continue;
+                    }
+                }
+
+                let lane_id = site_id.next().unwrap();
+                lane_between.insert((*v0, *v1), lane_id);
+                lanes.insert(
+                    lane_id,
+                    Lane {
+                        anchors: [a0, a1].into(),
+                        forward: motion,
+                        reverse: ReverseLane::Disable,
+                        graphs: AssociatedGraphs::Only([graph_id].into()),
+                        cost: props.to_cost(),
+                        curve: Default::default(),
+                        shared_zone: SharedZone(props.shared_zone.clone()),
+                        marker: Default::default(),
+                    },
+                );
+            }
+
+            levels.insert(site_id.next().unwrap(), level);
+        }
+
+        let mut graphs = BTreeMap::new();
+        graphs.insert(
+            graph_id,
+            crate::NavGraph {
+                name: NameInSite(graph_name.to_owned()),
+                color: DisplayColor(DEFAULT_NAV_GRAPH_COLORS[0]),
+                manual_color: Default::default(),
+                lane_defaults: Default::default(),
+                marker: Default::default(),
+            },
+        );
+
+        Ok(Site {
+            format_version: Default::default(),
+            anchors: Default::default(),
+            properties: SiteProperties {
+                name: self.building_name.clone(),
+            },
+            levels,
+            lifts: Default::default(),
+            navigation: Navigation {
+                guided: Guided {
+                    graphs,
+                    ranking: vec![graph_id],
+                    lanes,
+                    locations,
+                    palette: Default::default(),
+                },
+            },
+            agents: Default::default(),
+            environment: Default::default(),
+            lights: Default::default(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct NavLevel {
+    #[serde(default)]
     lanes: Vec<NavLane>,
+    #[serde(default)]
     vertices: Vec<NavVertex>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct NavLane(pub usize, pub usize, pub NavLaneProperties);
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct NavLaneProperties {
+    #[serde(default)]
     speed_limit: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     dock_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    orientation_constraint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_multiplier: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shared_zone: Option<String>,
     // TODO(MXG): Add other lane properties
     // door_name,
-    // orientation_constraint,
     // demo_mock_floor_name
 }
 
 impl NavLaneProperties {
     fn from_motion(motion: &Motion) -> Self {
+        let orientation_constraint = match motion.orientation_constraint {
+            OrientationConstraint::Forwards => Some("forward".to_owned()),
+            OrientationConstraint::Backwards => Some("backward".to_owned()),
+            _ => None,
+        };
         Self {
             speed_limit: motion.speed_limit.unwrap_or(0.0),
             dock_name: motion.dock.as_ref().map(|d| d.name.clone()),
+            orientation_constraint,
+            cost_multiplier: None,
+            shared_zone: None,
+        }
+    }
+
+    fn to_motion(&self) -> Motion {
+        let orientation_constraint = match self.orientation_constraint.as_deref() {
+            Some("forward") => OrientationConstraint::Forwards,
+            Some("backward") => OrientationConstraint::Backwards,
+            _ => OrientationConstraint::None,
+        };
+        Motion {
+            orientation_constraint,
+            speed_limit: Some(self.speed_limit).filter(|s| *s > 0.0),
+            dock: self.dock_name.as_ref().map(|name| Dock {
+                name: name.clone(),
+                duration: None,
+            }),
+        }
+    }
+
+    fn to_cost(&self) -> LaneCost {
+        self.cost_multiplier.map(LaneCost).unwrap_or_default()
+    }
+
+    fn with_cost(mut self, cost: &LaneCost) -> Self {
+        if *cost != LaneCost::default() {
+            self.cost_multiplier = Some(cost.0);
         }
+        self
+    }
+
+    fn with_shared_zone(mut self, zone: &SharedZone) -> Self {
+        self.shared_zone = zone.0.clone();
+        self
     }
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct NavVertex(pub f32, pub f32, pub NavVertexProperties);
 
 impl NavVertex {
@@ -139,16 +343,23 @@ impl NavVertex {
     }
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct NavVertexProperties {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     lift: Option<String>,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_default")]
     is_charger: bool,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_default")]
     is_holding_point: bool,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_default")]
     is_parking_spot: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dock_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    charger_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    capacity: Option<u32>,
+    #[serde(default)]
     name: String,
 }
 
@@ -159,6 +370,9 @@ impl Default for NavVertexProperties {
             is_charger: false,
             is_holding_point: false,
             is_parking_spot: false,
+            dock_name: None,
+            charger_type: None,
+            capacity: None,
             name: "".to_owned(),
         }
     }
@@ -179,11 +393,147 @@ impl NavVertexProperties {
             .find(|t| t.is_holding_point())
             .is_some();
         props.is_parking_spot = location.tags.iter().find(|t| t.is_parking_spot()).is_some();
+        props.dock_name = location.parameters.dock_name.clone();
+        props.charger_type = location.parameters.charger_type.clone();
+        props.capacity = location.parameters.capacity;
 
         props
     }
+
+    fn to_tags(&self) -> LocationTags {
+        let mut tags = Vec::new();
+        if self.is_charger {
+            tags.push(LocationTag::Charger);
+        }
+        if self.is_holding_point {
+            tags.push(LocationTag::HoldingPoint);
+        }
+        if self.is_parking_spot {
+            tags.push(LocationTag::ParkingSpot);
+        }
+        LocationTags(tags)
+    }
+
+    fn to_parameters(&self) -> LocationParameters {
+        LocationParameters {
+            dock_name: self.dock_name.clone(),
+            charger_type: self.charger_type.clone(),
+            capacity: self.capacity,
+        }
+    }
 }
 
-fn is_false(b: &bool) -> bool {
-    !b
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAPH: &str = r#"
+building_name: test_building
+levels:
+  L1:
+    vertices:
+    - [0.0, 0.0, {name: charger, is_charger: true, dock_name: dock_1}]
+    - [1.0, 0.0, {name: ""}]
+    - [2.0, 0.0, {}]
+    - [3.0, 0.0, {}]
+    lanes:
+    - [0, 1, {speed_limit: 0.0}]
+    - [1, 0, {speed_limit: 0.0}]
+    - [1, 2, {speed_limit: 0.5, orientation_constraint: forward}]
+    - [2, 3, {speed_limit: 1.0}]
+    - [3, 2, {speed_limit: 0.5}]
+"#;
+
+    #[test]
+    fn nav_graph_to_site() {
+        let graph = NavGraph::from_bytes(GRAPH.as_bytes()).unwrap();
+        let site = graph.to_site("main").unwrap();
+        assert_eq!(site.properties.name, "test_building");
+        assert_eq!(site.navigation.guided.graphs.len(), 1);
+        let (graph_id, nav_graph) = site.navigation.guided.graphs.iter().next().unwrap();
+        assert_eq!(nav_graph.name.0, "main");
+        assert_eq!(site.navigation.guided.ranking, vec![*graph_id]);
+
+        assert_eq!(site.levels.len(), 1);
+        let level = site.levels.values().next().unwrap();
+        assert_eq!(level.properties.name, "L1");
+        assert_eq!(level.anchors.len(), 4);
+
+        // Entries that go in opposite directions are paired into one lane
+        let lanes = &site.navigation.guided.lanes;
+        assert_eq!(lanes.len(), 3);
+        assert_eq!(
+            lanes
+                .values()
+                .filter(|l| l.reverse == ReverseLane::Same)
+                .count(),
+            1
+        );
+        let one_way = lanes
+            .values()
+            .find(|l| l.reverse == ReverseLane::Disable)
+            .unwrap();
+        assert_eq!(
+            one_way.forward.orientation_constraint,
+            OrientationConstraint::Forwards
+        );
+        assert_eq!(one_way.forward.speed_limit, Some(0.5));
+        let different = lanes
+            .values()
+            .find_map(|l| match &l.reverse {
+                ReverseLane::Different(motion) => Some((l, motion)),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(different.0.forward.speed_limit, Some(1.0));
+        assert_eq!(different.1.speed_limit, Some(0.5));
+        assert!(lanes
+            .values()
+            .all(|l| l.graphs == AssociatedGraphs::Only([*graph_id].into())));
+
+        // Only vertices with a name, tags, or parameters become locations
+        let locations = &site.navigation.guided.locations;
+        assert_eq!(locations.len(), 1);
+        let charger = locations.values().next().unwrap();
+        assert_eq!(charger.name.0, "charger");
+        assert!(charger.tags.iter().any(|t| t.is_charger()));
+        assert_eq!(charger.parameters.dock_name.as_deref(), Some("dock_1"));
+        let anchor = level.anchors.get(&charger.anchor.0).unwrap();
+        assert_eq!(
+            anchor.translation_for_category(Category::General),
+            &[0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn nav_graph_round_trip() {
+        let graph = NavGraph::from_bytes(GRAPH.as_bytes()).unwrap();
+        let site = graph.to_site("main").unwrap();
+        let exported = NavGraph::from_site(&site);
+        assert_eq!(exported.len(), 1);
+        let (name, exported) = &exported[0];
+        assert_eq!(name, "main");
+        let level = exported.levels.get("L1").unwrap();
+        assert_eq!(level.vertices.len(), 4);
+        assert_eq!(level.lanes.len(), 5);
+    }
+
+    #[test]
+    fn nav_graph_with_invalid_vertex() {
+        let graph = NavGraph::from_bytes(
+            br#"
+levels:
+  L1:
+    vertices:
+    - [0.0, 0.0, {}]
+    lanes:
+    - [0, 3, {speed_limit: 0.0}]
+"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            graph.to_site("main"),
+            Err(PortingError::InvalidVertex(3))
+        ));
+    }
 }
@@ -0,0 +1,149 @@
+use super::{PortingError, Result};
+use crate::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Configuration for the RMF door and lift supervisor nodes. This lists the
+/// names of the doors and lifts in the site along with the levels that they
+/// belong to or service.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct SupervisorConfig {
+    pub building_name: String,
+    pub doors: BTreeMap<String, SupervisorDoor>,
+    pub lifts: BTreeMap<String, SupervisorLift>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SupervisorDoor {
+    #[serde(rename = "type")]
+    pub door_type: String,
+    pub level: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SupervisorLift {
+    /// Names of the levels that the lift can visit, sorted by elevation
+    pub levels: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_level: Option<String>,
+    #[serde(skip_serializing_if = "is_default")]
+    pub is_static: bool,
+}
+
+impl SupervisorConfig {
+    /// The supervisors look up doors and lifts by name, so every door and
+    /// every lift needs a unique name.
+    pub fn from_site(site: &Site) -> Result<Self> {
+        let mut doors: BTreeMap<String, SupervisorDoor> = BTreeMap::new();
+        for (_, level) in &site.levels {
+            for (_, door) in &level.doors {
+                if let Some(other) = doors.get(&door.name.0) {
+                    return Err(PortingError::DuplicateDoorName {
+                        door: door.name.0.clone(),
+                        level: level.properties.name.clone(),
+                        other_level: other.level.clone(),
+                    });
+                }
+                doors.insert(
+                    door.name.0.clone(),
+                    SupervisorDoor {
+                        door_type: door_type_name(&door.kind).to_owned(),
+                        level: level.properties.name.clone(),
+                    },
+                );
+            }
+        }
+
+        let mut lifts = BTreeMap::new();
+        for (_, lift) in &site.lifts {
+            let visited: BTreeSet<u32> = lift
+                .cabin_doors
+                .values()
+                .flat_map(|door| door.visits.iter().copied())
+                .collect();
+
+            let mut levels: Vec<&Level> = visited
+                .iter()
+                .filter_map(|level_id| site.levels.get(level_id))
+                .collect();
+            levels.sort_by(|a, b| a.properties.elevation.total_cmp(&b.properties.elevation));
+
+            let initial_level = lift
+                .properties
+                .initial_level
+                .0
+                .and_then(|level_id| site.levels.get(&level_id))
+                .map(|level| level.properties.name.clone());
+
+            if lifts.contains_key(&lift.properties.name.0) {
+                return Err(PortingError::DuplicateLiftName(
+                    lift.properties.name.0.clone(),
+                ));
+            }
+            lifts.insert(
+                lift.properties.name.0.clone(),
+                SupervisorLift {
+                    levels: levels
+                        .into_iter()
+                        .map(|level| level.properties.name.clone())
+                        .collect(),
+                    initial_level,
+                    is_static: lift.properties.is_static.0,
+                },
+            );
+        }
+
+        Ok(Self {
+            building_name: site.properties.name.clone(),
+            doors,
+            lifts,
+        })
+    }
+}
+
+/// Get the name that RMF uses for each type of door.
+fn door_type_name(kind: &DoorType) -> &'static str {
+    match kind {
+        DoorType::SingleSliding(_) => "sliding",
+        DoorType::DoubleSliding(_) => "double_sliding",
+        DoorType::SingleSwing(_) => "hinged",
+        DoorType::DoubleSwing(_) => "double_hinged",
+        DoorType::Model(_) => "model",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::legacy::building_map::BuildingMap;
+
+    #[test]
+    fn office_supervisor_config() {
+        let data = std::fs::read("../assets/demo_maps/office.building.yaml").unwrap();
+        let site = BuildingMap::from_bytes(&data).unwrap().to_site().unwrap();
+        let config = SupervisorConfig::from_site(&site).unwrap();
+        assert_eq!(config.building_name, site.properties.name);
+        let door_count: usize = site.levels.values().map(|l| l.doors.len()).sum();
+        assert_eq!(config.doors.len(), door_count);
+        assert_eq!(config.lifts.len(), site.lifts.len());
+    }
+
+    #[test]
+    fn repeated_door_names_are_reported() {
+        let data = std::fs::read("../assets/demo_maps/office.building.yaml").unwrap();
+        let mut site = BuildingMap::from_bytes(&data).unwrap().to_site().unwrap();
+        let level = site.levels.values().next().unwrap().clone();
+        let door = level.doors.values().next().unwrap().clone();
+        let mut copy = level;
+        copy.properties.name = "copy".to_owned();
+        copy.doors = BTreeMap::from([(u32::MAX, door.clone())]);
+        site.levels.insert(u32::MAX, copy);
+
+        match SupervisorConfig::from_site(&site) {
+            Err(PortingError::DuplicateDoorName { door: name, .. }) => {
+                assert_eq!(name, door.name.0);
+            }
+            other => panic!("expected a duplicate door name, got {other:?}"),
+        }
+    }
+}
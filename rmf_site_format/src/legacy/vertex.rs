@@ -1,7 +1,8 @@
 use super::rbmf::*;
 use crate::{
     is_default, AssetSource, AssociatedGraphs, ConstraintDependents, IsStatic, Location,
-    LocationTag, LocationTags, Model, ModelMarker, NameInSite, Pose, Scale,
+    LocationParameters, LocationTag, LocationTags, LodPolicy, MaterialOverride, Model, ModelMarker,
+    ModelTags, NameInSite, Pose, Scale,
 };
 use glam::DVec2;
 use serde::{Deserialize, Serialize};
@@ -65,17 +66,25 @@ impl Vertex {
                 is_static: IsStatic(false),
                 constraints: ConstraintDependents::default(),
                 scale: Scale::default(),
+                tags: ModelTags::default(),
+                material: MaterialOverride::default(),
+                lod: LodPolicy::default(),
                 marker: ModelMarker,
             }))
         }
 
+        let parameters = LocationParameters {
+            dock_name: (!me.dock_name.is_empty()).then(|| me.dock_name.1.clone()),
+            ..Default::default()
+        };
+
         let name = if self.3.is_empty() {
             None
         } else {
             Some(self.3.clone())
         };
 
-        if tags.is_empty() && name.is_none() {
+        if tags.is_empty() && name.is_none() && parameters.is_empty() {
             return None;
         } else {
             return Some(Location {
@@ -83,6 +92,7 @@ impl Vertex {
                 tags: LocationTags(tags),
                 name: NameInSite(name.unwrap_or("<Unnamed>".to_string())),
                 graphs: AssociatedGraphs::All,
+                parameters,
             });
         }
     }
@@ -59,6 +59,12 @@ pub struct Level {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub physical_cameras: BTreeMap<u32, PhysicalCamera>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub primitive_shapes: BTreeMap<u32, PrimitiveShape>,
+    /// Sensors that are attached to the models of this level, keyed by the
+    /// site ID of the sensor and parented to the site ID of the model
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sensors: BTreeMap<u32, Parented<u32, Sensor>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub walls: BTreeMap<u32, Wall<u32>>,
     #[serde(default, skip_serializing_if = "RankingsInLevel::is_empty")]
     pub rankings: RankingsInLevel,
@@ -78,6 +84,8 @@ impl Level {
             measurements: Default::default(),
             models: Default::default(),
             physical_cameras: Default::default(),
+            primitive_shapes: Default::default(),
+            sensors: Default::default(),
             walls: Default::default(),
         }
     }
@@ -39,6 +39,9 @@ pub use drawing::*;
 pub mod edge;
 pub use edge::*;
 
+pub mod environment;
+pub use environment::*;
+
 pub mod fiducial;
 pub use fiducial::*;
 
@@ -84,12 +87,18 @@ pub use physical_camera::*;
 pub mod point;
 pub use point::*;
 
+pub mod primitive_shape;
+pub use primitive_shape::*;
+
 pub mod recall;
 pub use recall::*;
 
 pub mod semver;
 pub use semver::*;
 
+pub mod sensor;
+pub use sensor::*;
+
 pub mod site;
 pub use site::*;
 
@@ -18,8 +18,8 @@
 use crate::*;
 #[cfg(feature = "bevy")]
 use bevy::prelude::{
-    Bundle, Component, DirectionalLight as BevyDirectionalLight, PointLight as BevyPointLight,
-    SpotLight as BevySpotLight,
+    Bundle, Component, Deref, DerefMut, DirectionalLight as BevyDirectionalLight,
+    PointLight as BevyPointLight, SpotLight as BevySpotLight,
 };
 use serde::{Deserialize, Serialize};
 
@@ -28,8 +28,17 @@ use serde::{Deserialize, Serialize};
 pub struct Light {
     pub pose: Pose,
     pub kind: LightKind,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub group: LightGroup,
 }
 
+/// The name of a group of lights that can be edited together, such as the
+/// fixtures of one aisle
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+#[cfg_attr(feature = "bevy", derive(Component, Deref, DerefMut))]
+pub struct LightGroup(pub Option<String>);
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[cfg_attr(feature = "bevy", derive(Component))]
@@ -116,6 +125,32 @@ impl LightKind {
         }
     }
 
+    pub fn set_color(&mut self, color: [f32; 4]) {
+        match self {
+            Self::Point(l) => l.color = color,
+            Self::Spot(l) => l.color = color,
+            Self::Directional(l) => l.color = color,
+        }
+    }
+
+    /// Set the intensity of a point or spot light. Directional lights are
+    /// described by their illuminance instead, so they are left unchanged.
+    pub fn set_intensity(&mut self, intensity: f32) {
+        match self {
+            Self::Point(l) => l.intensity = intensity,
+            Self::Spot(l) => l.intensity = intensity,
+            Self::Directional(_) => {}
+        }
+    }
+
+    pub fn set_enable_shadows(&mut self, enable: bool) {
+        match self {
+            Self::Point(l) => l.enable_shadows = enable,
+            Self::Spot(l) => l.enable_shadows = enable,
+            Self::Directional(l) => l.enable_shadows = enable,
+        }
+    }
+
     pub fn label(&self) -> &'static str {
         match self {
             Self::Point(_) => "Point",
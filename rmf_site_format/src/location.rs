@@ -70,6 +70,8 @@ pub struct Location<T: RefTrait> {
     pub tags: LocationTags,
     pub name: NameInSite,
     pub graphs: AssociatedGraphs<T>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub parameters: LocationParameters,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -83,6 +85,29 @@ impl Default for LocationTags {
     }
 }
 
+/// Structured details about how robots should use a location that has been
+/// tagged as a charger or parking spot, or that robots need to dock into.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct LocationParameters {
+    /// Name of the docking maneuver that robots perform to reach this location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dock_name: Option<String>,
+    /// The type of charger at this location, used to match it with robots
+    /// that are able to use it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charger_type: Option<String>,
+    /// How many robots can charge or park at this location at the same time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<u32>,
+}
+
+impl LocationParameters {
+    pub fn is_empty(&self) -> bool {
+        self.dock_name.is_none() && self.charger_type.is_none() && self.capacity.is_none()
+    }
+}
+
 #[cfg(feature = "bevy")]
 impl Location<u32> {
     pub fn to_ecs(
@@ -94,6 +119,7 @@ impl Location<u32> {
             tags: self.tags.clone(),
             name: self.name.clone(),
             graphs: self.graphs.to_ecs(id_to_entity),
+            parameters: self.parameters.clone(),
         }
     }
 }
@@ -105,6 +131,7 @@ impl<T: RefTrait> From<Point<T>> for Location<T> {
             tags: Default::default(),
             name: NameInSite("<Unnamed>".to_string()),
             graphs: AssociatedGraphs::All,
+            parameters: Default::default(),
         }
     }
 }
@@ -19,6 +19,7 @@ use crate::*;
 #[cfg(feature = "bevy")]
 use bevy::prelude::{Bundle, Component};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "bevy", derive(Bundle))]
@@ -39,6 +40,15 @@ pub struct Model {
     /// Scale to be applied to the model
     #[serde(default, skip_serializing_if = "is_default")]
     pub scale: Scale,
+    /// Tags that the user has given this model to organize it
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub tags: ModelTags,
+    /// Material properties that replace those of the model's own meshes
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub material: MaterialOverride,
+    /// Whether simpler versions of this model are shown when it is far away
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub lod: LodPolicy,
     /// Only relevant for bevy
     #[serde(skip)]
     pub marker: ModelMarker,
@@ -48,6 +58,93 @@ pub struct Model {
 #[cfg_attr(feature = "bevy", derive(Component))]
 pub struct ModelMarker;
 
+/// Free-form labels, such as "racking" or "furniture", that can be used to
+/// find and filter groups of models
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct ModelTags(pub BTreeSet<String>);
+
+/// Material properties that are applied to every mesh of one model instance,
+/// for example to give a different color to models that share the same
+/// asset. Properties that are None keep the values from the asset.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct MaterialOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_color: Option<[f32; 4]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metallic: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub perceptual_roughness: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emissive: Option<Emissive>,
+}
+
+/// Light given off by a material, for things like exit signs, status beacons
+/// and light fixtures. Emissive materials glow but do not light up the things
+/// around them, use a light for that.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Emissive {
+    pub color: [f32; 3],
+    /// Multiplier on the color, values above 1 make the material appear
+    /// brighter than its color
+    pub intensity: f32,
+}
+
+impl Default for Emissive {
+    fn default() -> Self {
+        Self {
+            color: [1.0, 1.0, 1.0],
+            intensity: 1.0,
+        }
+    }
+}
+
+impl Emissive {
+    /// The color multiplied by the intensity
+    pub fn radiance(&self) -> [f32; 3] {
+        self.color.map(|c| c * self.intensity)
+    }
+}
+
+/// How the level of detail of a model changes with its distance from the
+/// camera. Levels of detail are either authored in the asset, as meshes whose
+/// names end in _LOD1, _LOD2, and so on, or generated by the site editor.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum LodPolicy {
+    /// Use the levels of detail of the asset if it has any, otherwise
+    /// generate them if the model has more triangles than the budget
+    #[default]
+    Automatic,
+    /// Use or generate levels of detail regardless of the triangle budget
+    Always,
+    /// Always show the model at full detail
+    Never,
+}
+
+impl LodPolicy {
+    pub const ALL: [LodPolicy; 3] = [Self::Automatic, Self::Always, Self::Never];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Automatic => "Automatic",
+            Self::Always => "Always",
+            Self::Never => "Never",
+        }
+    }
+}
+
+impl MaterialOverride {
+    pub fn is_empty(&self) -> bool {
+        self.base_color.is_none()
+            && self.metallic.is_none()
+            && self.perceptual_roughness.is_none()
+            && self.emissive.is_none()
+    }
+}
+
 impl Default for Model {
     fn default() -> Self {
         Self {
@@ -57,6 +154,9 @@ impl Default for Model {
             is_static: IsStatic(false),
             constraints: ConstraintDependents::default(),
             scale: Scale::default(),
+            tags: ModelTags::default(),
+            material: MaterialOverride::default(),
+            lod: LodPolicy::default(),
             marker: ModelMarker,
         }
     }
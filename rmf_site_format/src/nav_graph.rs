@@ -32,11 +32,81 @@ pub const DEFAULT_NAV_GRAPH_COLORS: [[f32; 4]; 8] = [
     [0.7, 0.5, 0.1, 1.0],
 ];
 
+/// The Okabe-Ito palette, which stays distinguishable for the common kinds of
+/// color vision deficiency. Grey takes the place of black so lanes remain
+/// visible against dark floors.
+pub const COLOR_BLIND_SAFE_NAV_GRAPH_COLORS: [[f32; 4]; 8] = [
+    [0.90, 0.62, 0.0, 1.0],
+    [0.34, 0.71, 0.91, 1.0],
+    [0.0, 0.62, 0.45, 1.0],
+    [0.94, 0.89, 0.26, 1.0],
+    [0.0, 0.45, 0.70, 1.0],
+    [0.84, 0.37, 0.0, 1.0],
+    [0.80, 0.47, 0.65, 1.0],
+    [0.6, 0.6, 0.6, 1.0],
+];
+
+/// Paul Tol's muted palette, which is also safe for color vision deficiency
+pub const MUTED_NAV_GRAPH_COLORS: [[f32; 4]; 9] = [
+    [0.80, 0.40, 0.47, 1.0],
+    [0.20, 0.13, 0.53, 1.0],
+    [0.87, 0.80, 0.47, 1.0],
+    [0.07, 0.47, 0.20, 1.0],
+    [0.53, 0.80, 0.93, 1.0],
+    [0.53, 0.13, 0.33, 1.0],
+    [0.27, 0.67, 0.60, 1.0],
+    [0.60, 0.60, 0.20, 1.0],
+    [0.67, 0.27, 0.60, 1.0],
+];
+
+/// The colors that are given to the graphs of a site, in the order of their
+/// ranking. Graphs with a [`ManualColor`] keep their own color.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum NavGraphPalette {
+    #[default]
+    Classic,
+    ColorBlindSafe,
+    Muted,
+}
+
+impl NavGraphPalette {
+    pub const ALL: [NavGraphPalette; 3] = [Self::Classic, Self::ColorBlindSafe, Self::Muted];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::ColorBlindSafe => "Color Blind Safe",
+            Self::Muted => "Muted",
+        }
+    }
+
+    pub fn colors(&self) -> &'static [[f32; 4]] {
+        match self {
+            Self::Classic => &DEFAULT_NAV_GRAPH_COLORS,
+            Self::ColorBlindSafe => &COLOR_BLIND_SAFE_NAV_GRAPH_COLORS,
+            Self::Muted => &MUTED_NAV_GRAPH_COLORS,
+        }
+    }
+
+    /// Get the color for the graph at this position in the ranking. The
+    /// palette repeats when there are more graphs than colors.
+    pub fn color(&self, index: usize) -> [f32; 4] {
+        let colors = self.colors();
+        colors[index % colors.len()]
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[cfg_attr(feature = "bevy", derive(Bundle))]
 pub struct NavGraph {
     pub name: NameInSite,
     pub color: DisplayColor,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub manual_color: ManualColor,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub lane_defaults: LaneDefaults,
     #[serde(skip)]
     pub marker: NavGraphMarker,
 }
@@ -50,16 +120,52 @@ impl Default for NavGraph {
         Self {
             name: NameInSite("<Unnamed>".to_string()),
             color: DisplayColor([1.0, 0.5, 0.3, 1.0]),
+            manual_color: Default::default(),
+            lane_defaults: Default::default(),
             marker: NavGraphMarker,
         }
     }
 }
 
+/// Properties that lanes of a graph will use when they do not specify their
+/// own value.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct LaneDefaults {
+    #[serde(default, skip_serializing_if = "OrientationConstraint::is_none")]
+    pub orientation_constraint: OrientationConstraint,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_limit: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lane_width: Option<f32>,
+}
+
+impl LaneDefaults {
+    /// Get the motion that a lane will have after inheriting these defaults
+    pub fn apply_to(&self, motion: &Motion) -> Motion {
+        let mut motion = motion.clone();
+        if motion.orientation_constraint.is_none() {
+            motion.orientation_constraint = self.orientation_constraint;
+        }
+        if motion.speed_limit.is_none() {
+            motion.speed_limit = self.speed_limit;
+        }
+        motion
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(transparent)]
 #[cfg_attr(feature = "bevy", derive(Component, Deref, DerefMut))]
 pub struct DisplayColor(pub [f32; 4]);
 
+/// The display color of a graph was chosen by hand, so it is kept when the
+/// palette of the site changes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(transparent)]
+#[cfg_attr(feature = "bevy", derive(Component, Deref, DerefMut))]
+pub struct ManualColor(pub bool);
+
 /// This component is used by graph elements such as [`Lane`] and [`Location`]
 /// to indicate what graphs they can be associated with.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
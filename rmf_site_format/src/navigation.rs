@@ -43,10 +43,16 @@ pub struct Guided {
     pub lanes: BTreeMap<u32, Lane<u32>>,
     /// Properties of each special location
     pub locations: BTreeMap<u32, Location<u32>>,
+    /// The colors that are given to the graphs
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub palette: NavGraphPalette,
 }
 
 impl Guided {
     pub fn is_empty(&self) -> bool {
-        self.graphs.is_empty() && self.lanes.is_empty() && self.locations.is_empty()
+        self.graphs.is_empty()
+            && self.lanes.is_empty()
+            && self.locations.is_empty()
+            && is_default(&self.palette)
     }
 }
@@ -0,0 +1,55 @@
+/*
+ * Copyright (C) 2023 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::*;
+#[cfg(feature = "bevy")]
+use bevy::prelude::{Bundle, Component};
+use serde::{Deserialize, Serialize};
+
+/// A simple shape that is described by its dimensions instead of a mesh file.
+/// These are useful for quickly blocking out volumes of equipment that has not
+/// been modeled.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Bundle))]
+pub struct PrimitiveShape {
+    pub name: NameInSite,
+    pub shape: MeshPrimitive,
+    pub pose: Pose,
+    /// Material properties of the shape, which is otherwise shown with the
+    /// default material
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub material: MaterialOverride,
+    /// Only relevant for bevy
+    #[serde(skip)]
+    pub marker: PrimitiveShapeMarker,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct PrimitiveShapeMarker;
+
+impl PrimitiveShape {
+    pub fn new(shape: MeshPrimitive) -> Self {
+        Self {
+            name: NameInSite(shape.label()),
+            shape,
+            pose: Pose::default(),
+            material: MaterialOverride::default(),
+            marker: PrimitiveShapeMarker,
+        }
+    }
+}
@@ -0,0 +1,258 @@
+/*
+ * Copyright (C) 2022 Open Source Robotics Foundation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+*/
+
+use crate::*;
+#[cfg(feature = "bevy")]
+use bevy::{ecs::system::EntityCommands, prelude::Component};
+use serde::{Deserialize, Serialize};
+
+/// A sensor that is attached to a workcell frame or a site model
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Sensor {
+    pub name: String,
+    /// The pose of the sensor relative to what it is attached to
+    pub pose: Pose,
+    pub properties: SensorProperties,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum SensorProperties {
+    Camera(CameraSensor),
+    DepthCamera(DepthCameraSensor),
+    Lidar(LidarSensor),
+}
+
+impl Default for SensorProperties {
+    fn default() -> Self {
+        SensorProperties::Camera(Default::default())
+    }
+}
+
+impl SensorProperties {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Camera(_) => "Camera",
+            Self::DepthCamera(_) => "Depth Camera",
+            Self::Lidar(_) => "Lidar",
+        }
+    }
+
+    /// The image that the sensor produces, if it has one that can be
+    /// previewed
+    pub fn camera(&self) -> Option<&CameraSensor> {
+        match self {
+            Self::Camera(camera) => Some(camera),
+            Self::DepthCamera(depth) => Some(&depth.camera),
+            Self::Lidar(_) => None,
+        }
+    }
+
+    /// The name of this type of sensor in SDF
+    pub fn sdf_type(&self) -> &'static str {
+        match self {
+            Self::Camera(_) => "camera",
+            Self::DepthCamera(_) => "depth_camera",
+            Self::Lidar(_) => "gpu_lidar",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CameraSensor {
+    pub width: u32,
+    pub height: u32,
+    pub horizontal_fov: Angle,
+    pub update_rate: f32,
+}
+
+impl Default for CameraSensor {
+    fn default() -> Self {
+        CameraSensor {
+            width: 1280,
+            height: 720,
+            horizontal_fov: Angle::Deg(90.0),
+            update_rate: 30.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DepthCameraSensor {
+    #[serde(flatten)]
+    pub camera: CameraSensor,
+    /// Closest distance that can be measured, in meters
+    pub near: f32,
+    /// Farthest distance that can be measured, in meters
+    pub far: f32,
+}
+
+impl Default for DepthCameraSensor {
+    fn default() -> Self {
+        DepthCameraSensor {
+            camera: CameraSensor {
+                width: 640,
+                height: 480,
+                ..Default::default()
+            },
+            near: 0.1,
+            far: 10.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LidarSensor {
+    pub horizontal_samples: u32,
+    pub horizontal_fov: Angle,
+    /// Set to 1 for a planar lidar
+    pub vertical_samples: u32,
+    pub vertical_fov: Angle,
+    /// Closest distance that can be measured, in meters
+    pub min_range: f32,
+    /// Farthest distance that can be measured, in meters
+    pub max_range: f32,
+    pub update_rate: f32,
+}
+
+impl Default for LidarSensor {
+    fn default() -> Self {
+        LidarSensor {
+            horizontal_samples: 640,
+            horizontal_fov: Angle::Deg(270.0),
+            vertical_samples: 1,
+            vertical_fov: Angle::Deg(0.0),
+            min_range: 0.1,
+            max_range: 30.0,
+            update_rate: 10.0,
+        }
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl Sensor {
+    /// Insert the pose and properties of the sensor. The name is left to the
+    /// caller since sensors can be named within a site or within a workcell.
+    pub fn add_bevy_components(&self, mut commands: EntityCommands) {
+        commands.insert((self.pose.clone(), self.properties.clone()));
+    }
+}
+
+impl Sensor {
+    /// Describe the sensor as an SDF sensor element
+    pub fn to_sdf(&self) -> String {
+        let pose = urdf_rs::Pose::from(&self.pose);
+        let [x, y, z] = pose.xyz.0;
+        let [roll, pitch, yaw] = pose.rpy.0;
+        let mut sdf = format!(
+            "<sensor name=\"{}\" type=\"{}\">\n  \
+            <pose>{x} {y} {z} {roll} {pitch} {yaw}</pose>\n  \
+            <always_on>true</always_on>\n",
+            escape_xml(&self.name),
+            self.properties.sdf_type(),
+        );
+        let camera_sdf = |camera: &CameraSensor, clip: Option<(f32, f32)>| -> String {
+            let clip = clip
+                .map(|(near, far)| {
+                    format!("    <clip>\n      <near>{near}</near>\n      <far>{far}</far>\n    </clip>\n")
+                })
+                .unwrap_or_default();
+            format!(
+                "  <update_rate>{}</update_rate>\n  <camera>\n    \
+                <horizontal_fov>{}</horizontal_fov>\n    \
+                <image>\n      <width>{}</width>\n      <height>{}</height>\n    </image>\n\
+                {clip}  </camera>\n",
+                camera.update_rate,
+                camera.horizontal_fov.radians(),
+                camera.width,
+                camera.height,
+            )
+        };
+        match &self.properties {
+            SensorProperties::Camera(camera) => sdf += &camera_sdf(camera, None),
+            SensorProperties::DepthCamera(depth) => {
+                sdf += &camera_sdf(&depth.camera, Some((depth.near, depth.far)))
+            }
+            SensorProperties::Lidar(lidar) => {
+                let h = lidar.horizontal_fov.radians() / 2.0;
+                let v = lidar.vertical_fov.radians() / 2.0;
+                sdf += &format!(
+                    "  <update_rate>{}</update_rate>\n  <lidar>\n    <scan>\n      \
+                    <horizontal>\n        <samples>{}</samples>\n        \
+                    <min_angle>{}</min_angle>\n        <max_angle>{}</max_angle>\n      \
+                    </horizontal>\n      <vertical>\n        <samples>{}</samples>\n        \
+                    <min_angle>{}</min_angle>\n        <max_angle>{}</max_angle>\n      \
+                    </vertical>\n    </scan>\n    <range>\n      <min>{}</min>\n      \
+                    <max>{}</max>\n    </range>\n  </lidar>\n",
+                    lidar.update_rate,
+                    lidar.horizontal_samples,
+                    -h,
+                    h,
+                    lidar.vertical_samples,
+                    -v,
+                    v,
+                    lidar.min_range,
+                    lidar.max_range,
+                );
+            }
+        }
+        sdf += "</sensor>\n";
+        sdf
+    }
+}
+
+/// Escape the characters that cannot appear as they are in XML text or
+/// attribute values
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&apos;",
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xml_characters_are_escaped() {
+        assert_eq!(escape_xml("camera"), "camera");
+        assert_eq!(
+            escape_xml("<a & \"b\" 'c'>"),
+            "&lt;a &amp; &quot;b&quot; &apos;c&apos;&gt;"
+        );
+    }
+
+    #[test]
+    fn sensor_name_is_escaped_in_sdf() {
+        let sensor = Sensor {
+            name: "front \"left\" <cam>".to_owned(),
+            ..Default::default()
+        };
+        let sdf = sensor.to_sdf();
+        assert!(sdf.starts_with("<sensor name=\"front &quot;left&quot; &lt;cam&gt;\" "));
+        assert!(sdf.ends_with("</sensor>\n"));
+    }
+}
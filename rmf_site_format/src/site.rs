@@ -19,7 +19,8 @@ use crate::*;
 #[cfg(feature = "bevy")]
 use bevy::prelude::{Component, Entity};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, io};
+use std::{collections::BTreeMap, io, path::Path};
+use thiserror::Error as ThisError;
 
 pub use ron::ser::PrettyConfig as Style;
 
@@ -54,12 +55,19 @@ pub struct Site {
     /// Properties of each lift
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub lifts: BTreeMap<u32, Lift<u32>>,
+    /// Lights that are shared by every level. Their poses are relative to the
+    /// site instead of a level.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lights: BTreeMap<u32, Light>,
     /// Data related to navigation
     #[serde(default, skip_serializing_if = "Navigation::is_empty")]
     pub navigation: Navigation,
     /// Properties that describe simulated agents in the site
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub agents: BTreeMap<u32, Agent>,
+    /// The sun and sky around the site
+    #[serde(default, skip_serializing_if = "Environment::is_empty")]
+    pub environment: Environment,
 }
 
 fn default_style_config() -> Style {
@@ -70,7 +78,131 @@ fn default_style_config() -> Style {
         .struct_names(false)
 }
 
+/// The formats that a site file can be written in. RON is the native format
+/// of site files, while JSON and YAML are easier for other tools to read.
+/// MessagePack is a compact binary format. It is written with the names of
+/// fields included, so fields that were skipped because they are empty can
+/// still be read back.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SiteFileFormat {
+    #[default]
+    Ron,
+    Json,
+    Yaml,
+    MessagePack,
+}
+
+impl SiteFileFormat {
+    pub const ALL: [SiteFileFormat; 4] = [
+        SiteFileFormat::Ron,
+        SiteFileFormat::Json,
+        SiteFileFormat::Yaml,
+        SiteFileFormat::MessagePack,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SiteFileFormat::Ron => "RON",
+            SiteFileFormat::Json => "JSON",
+            SiteFileFormat::Yaml => "YAML",
+            SiteFileFormat::MessagePack => "MessagePack",
+        }
+    }
+
+    /// The ending of the names of site files that use this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SiteFileFormat::Ron => "site.ron",
+            SiteFileFormat::Json => "site.json",
+            SiteFileFormat::Yaml => "site.yaml",
+            SiteFileFormat::MessagePack => "site.msgpack",
+        }
+    }
+
+    /// Find the format of a site file from the ending of its name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        Self::ALL
+            .into_iter()
+            .find(|format| name.ends_with(format.extension()))
+    }
+}
+
+#[derive(ThisError, Debug)]
+pub enum SiteFileError {
+    #[error("{0}")]
+    Ron(#[from] ron::Error),
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("{0}")]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    #[error("{0}")]
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
+}
+
 impl Site {
+    pub fn to_writer_as<W: io::Write>(
+        &self,
+        format: SiteFileFormat,
+        mut writer: W,
+    ) -> Result<(), SiteFileError> {
+        match format {
+            SiteFileFormat::Ron => self.to_writer(writer)?,
+            SiteFileFormat::Json => serde_json::to_writer_pretty(writer, self)?,
+            SiteFileFormat::Yaml => serde_yaml::to_writer(writer, self)?,
+            // Structs are written as maps so that skipped fields are allowed
+            SiteFileFormat::MessagePack => rmp_serde::encode::write_named(&mut writer, self)?,
+        }
+        Ok(())
+    }
+
+    pub fn from_bytes_as(format: SiteFileFormat, s: &[u8]) -> Result<Self, SiteFileError> {
+        Ok(match format {
+            SiteFileFormat::Ron => Self::from_bytes(s)?,
+            SiteFileFormat::Json => serde_json::from_slice(s)?,
+            SiteFileFormat::Yaml => serde_yaml::from_slice(s)?,
+            SiteFileFormat::MessagePack => rmp_serde::from_slice(s)?,
+        })
+    }
+
+    /// Every asset that the site refers to
+    pub fn asset_sources_mut(&mut self) -> Vec<&mut AssetSource> {
+        let mut sources = Vec::new();
+        for level in self.levels.values_mut() {
+            sources.extend(level.drawings.values_mut().map(|d| &mut d.source));
+            sources.extend(level.models.values_mut().map(|m| &mut m.source));
+        }
+        for location in self.navigation.guided.locations.values_mut() {
+            for tag in &mut location.tags.0 {
+                if let LocationTag::SpawnRobot(model) | LocationTag::Workcell(model) = tag {
+                    sources.push(&mut model.source);
+                }
+            }
+        }
+        if let Sky::Hdri { source } = &mut self.environment.sky {
+            sources.push(source);
+        }
+        sources
+    }
+
+    /// Turn local asset paths that are relative to the project root into
+    /// absolute paths, so they can be loaded no matter where the editor runs
+    pub fn resolve_asset_paths(&mut self, root: &Path) {
+        for source in self.asset_sources_mut() {
+            source.resolve_from(root);
+        }
+    }
+
+    /// Refer to the local assets that are inside the project root relative to
+    /// it, so the project can be moved to another folder or machine
+    pub fn relativize_asset_paths(&mut self, root: &Path) {
+        for source in self.asset_sources_mut() {
+            source.make_relative_to(root);
+        }
+    }
+
     pub fn to_writer<W: io::Write>(&self, writer: W) -> ron::Result<()> {
         ron::ser::to_writer_pretty(writer, self, default_style_config())
     }
@@ -108,3 +240,58 @@ impl RefTrait for u32 {}
 
 #[cfg(feature = "bevy")]
 impl RefTrait for Entity {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::legacy::building_map::BuildingMap;
+
+    fn office() -> Site {
+        let data = std::fs::read("../assets/demo_maps/office.building.yaml").unwrap();
+        BuildingMap::from_bytes(&data).unwrap().to_site().unwrap()
+    }
+
+    #[test]
+    fn sites_round_trip_through_every_format() {
+        let site = office();
+        let expected = site.to_string().unwrap();
+        for format in SiteFileFormat::ALL {
+            let mut bytes = Vec::new();
+            site.to_writer_as(format, &mut bytes).unwrap();
+            let loaded = Site::from_bytes_as(format, &bytes)
+                .unwrap_or_else(|err| panic!("{}: {err}", format.label()));
+            assert_eq!(loaded.to_string().unwrap(), expected, "{}", format.label());
+        }
+    }
+
+    #[test]
+    fn empty_sites_round_trip_through_every_format() {
+        // Every optional field of an empty site is skipped when it is written
+        let site = Site::default();
+        let expected = site.to_string().unwrap();
+        for format in SiteFileFormat::ALL {
+            let mut bytes = Vec::new();
+            site.to_writer_as(format, &mut bytes).unwrap();
+            let loaded = Site::from_bytes_as(format, &bytes)
+                .unwrap_or_else(|err| panic!("{}: {err}", format.label()));
+            assert_eq!(loaded.to_string().unwrap(), expected, "{}", format.label());
+        }
+    }
+
+    #[test]
+    fn formats_are_found_from_file_names() {
+        let format_of = |name: &str| SiteFileFormat::from_path(Path::new(name));
+        assert_eq!(format_of("office.site.ron"), Some(SiteFileFormat::Ron));
+        assert_eq!(
+            format_of("maps/office.site.json"),
+            Some(SiteFileFormat::Json)
+        );
+        assert_eq!(format_of("my.office.site.yaml"), Some(SiteFileFormat::Yaml));
+        assert_eq!(
+            format_of("office.site.msgpack"),
+            Some(SiteFileFormat::MessagePack)
+        );
+        assert_eq!(format_of("office.building.yaml"), None);
+        assert_eq!(format_of("office.json"), None);
+    }
+}
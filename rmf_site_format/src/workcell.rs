@@ -15,7 +15,7 @@
  *
 */
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io;
 
 use crate::*;
@@ -25,8 +25,9 @@ use bevy::ecs::system::EntityCommands;
 use bevy::prelude::{Bundle, Component, Deref, DerefMut, Entity};
 #[cfg(feature = "bevy")]
 use bevy::reflect::TypeUuid;
-use glam::Vec3;
+use glam::{EulerRot, Quat, Vec3};
 use serde::{Deserialize, Serialize};
+use thiserror::Error as ThisError;
 use urdf_rs::Robot;
 
 /// Helper structure to serialize / deserialize entities with parents
@@ -49,6 +50,9 @@ pub struct Frame {
     pub name: Option<NameInWorkcell>,
     #[serde(default, skip_serializing_if = "is_default")]
     pub mesh_constraint: Option<MeshConstraint<u32>>,
+    /// Mass properties of the link that this frame becomes when exported
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub inertial: Option<Inertial>,
     #[serde(skip)]
     pub marker: FrameMarker,
 }
@@ -83,22 +87,79 @@ pub struct WorkcellProperties {
 #[cfg_attr(feature = "bevy", derive(Component, Deref, DerefMut))]
 pub struct NameInWorkcell(pub String);
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "bevy", derive(Component, Deref, DerefMut))]
-pub struct Mass(f32);
+pub struct Mass(pub f32);
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+/// Moments of inertia about the center of mass, in kg·m²
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "bevy", derive(Component))]
-pub struct Inertia {}
+pub struct Inertia {
+    pub ixx: f32,
+    pub ixy: f32,
+    pub ixz: f32,
+    pub iyy: f32,
+    pub iyz: f32,
+    pub izz: f32,
+}
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
-#[cfg_attr(feature = "bevy", derive(Bundle))]
+impl From<&urdf_rs::Inertia> for Inertia {
+    fn from(inertia: &urdf_rs::Inertia) -> Self {
+        Self {
+            ixx: inertia.ixx as f32,
+            ixy: inertia.ixy as f32,
+            ixz: inertia.ixz as f32,
+            iyy: inertia.iyy as f32,
+            iyz: inertia.iyz as f32,
+            izz: inertia.izz as f32,
+        }
+    }
+}
+
+impl From<&Inertia> for urdf_rs::Inertia {
+    fn from(inertia: &Inertia) -> Self {
+        Self {
+            ixx: inertia.ixx as f64,
+            ixy: inertia.ixy as f64,
+            ixz: inertia.ixz as f64,
+            iyy: inertia.iyy as f64,
+            iyz: inertia.iyz as f64,
+            izz: inertia.izz as f64,
+        }
+    }
+}
+
+/// The origin is the center of mass, relative to the frame
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
 pub struct Inertial {
     pub origin: Pose,
     pub mass: Mass,
     pub inertia: Inertia,
 }
 
+impl From<&urdf_rs::Inertial> for Inertial {
+    fn from(inertial: &urdf_rs::Inertial) -> Self {
+        Self {
+            origin: (&inertial.origin).into(),
+            mass: Mass(inertial.mass.value as f32),
+            inertia: (&inertial.inertia).into(),
+        }
+    }
+}
+
+impl From<&Inertial> for urdf_rs::Inertial {
+    fn from(inertial: &Inertial) -> Self {
+        Self {
+            origin: (&inertial.origin).into(),
+            mass: urdf_rs::Mass {
+                value: inertial.mass.0 as f64,
+            },
+            inertia: (&inertial.inertia).into(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 #[cfg_attr(feature = "bevy", derive(Bundle))]
 pub struct Link {
@@ -112,13 +173,83 @@ pub struct Link {
 #[cfg_attr(feature = "bevy", derive(Component))]
 pub struct LinkMarker;
 
+/// A joint lets the frame that is attached to it move relative to the parent
+/// of the joint. Joints are parented to a frame (or the workcell root) and the
+/// moving frame is parented to the joint.
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 #[cfg_attr(feature = "bevy", derive(Bundle))]
 pub struct Joint {
     pub name: NameInWorkcell,
+    pub properties: JointProperties,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum JointProperties {
+    Fixed,
+    Revolute(SingleDofJoint),
+    Prismatic(SingleDofJoint),
+}
+
+impl Default for JointProperties {
+    fn default() -> Self {
+        JointProperties::Fixed
+    }
+}
+
+impl JointProperties {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Fixed => "Fixed",
+            Self::Revolute(_) => "Revolute",
+            Self::Prismatic(_) => "Prismatic",
+        }
+    }
+
+    pub fn single_dof(&self) -> Option<&SingleDofJoint> {
+        match self {
+            Self::Fixed => None,
+            Self::Revolute(dof) | Self::Prismatic(dof) => Some(dof),
+        }
+    }
+
+    pub fn single_dof_mut(&mut self) -> Option<&mut SingleDofJoint> {
+        match self {
+            Self::Fixed => None,
+            Self::Revolute(dof) | Self::Prismatic(dof) => Some(dof),
+        }
+    }
+}
+
+/// Properties of a joint that moves along or around a single axis
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SingleDofJoint {
+    /// Axis of the joint, expressed in the frame that the joint moves
+    pub axis: [f32; 3],
+    /// Revolute joints without limits can rotate continuously
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub limits: Option<JointLimits>,
+}
+
+impl Default for SingleDofJoint {
+    fn default() -> Self {
+        Self {
+            axis: [0.0, 0.0, 1.0],
+            limits: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct JointLimits {
+    pub lower: f32,
+    pub upper: f32,
+    pub effort: f32,
+    pub velocity: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Geometry {
     //#[serde(flatten)]
     Primitive(MeshPrimitive),
@@ -136,6 +267,7 @@ pub enum MeshPrimitive {
     Cylinder { radius: f32, length: f32 },
     Capsule { radius: f32, length: f32 },
     Sphere { radius: f32 },
+    Cone { radius: f32, length: f32 },
 }
 
 impl MeshPrimitive {
@@ -145,6 +277,7 @@ impl MeshPrimitive {
             MeshPrimitive::Cylinder { .. } => "Cylinder",
             MeshPrimitive::Capsule { .. } => "Capsule",
             MeshPrimitive::Sphere { .. } => "Sphere",
+            MeshPrimitive::Cone { .. } => "Cone",
         }
         .to_string()
     }
@@ -159,6 +292,8 @@ pub struct RecallMeshPrimitive {
     pub capsule_radius: Option<f32>,
     pub capsule_length: Option<f32>,
     pub sphere_radius: Option<f32>,
+    pub cone_radius: Option<f32>,
+    pub cone_length: Option<f32>,
 }
 
 impl Recall for RecallMeshPrimitive {
@@ -180,6 +315,10 @@ impl Recall for RecallMeshPrimitive {
             MeshPrimitive::Sphere { radius } => {
                 self.sphere_radius = Some(*radius);
             }
+            MeshPrimitive::Cone { radius, length } => {
+                self.cone_radius = Some(*radius);
+                self.cone_length = Some(*length);
+            }
         }
     }
 }
@@ -210,6 +349,13 @@ impl RecallMeshPrimitive {
             radius: self.sphere_radius.unwrap_or_default(),
         }
     }
+
+    pub fn assume_cone(&self, current: &MeshPrimitive) -> MeshPrimitive {
+        MeshPrimitive::Cone {
+            radius: self.cone_radius.unwrap_or_default(),
+            length: self.cone_length.unwrap_or_default(),
+        }
+    }
 }
 
 impl Default for Geometry {
@@ -277,7 +423,24 @@ pub struct Workcell {
     pub visuals: BTreeMap<u32, Parented<u32, WorkcellModel>>,
     /// Collisions, key is their id, used for hierarchy
     pub collisions: BTreeMap<u32, Parented<u32, WorkcellModel>>,
-    // TODO(luca) Joints
+    /// Joints, key is their id, used for hierarchy
+    #[serde(default)]
+    pub joints: BTreeMap<u32, Parented<u32, Joint>>,
+    /// Sensors, key is their id, used for hierarchy
+    #[serde(default)]
+    pub sensors: BTreeMap<u32, Parented<u32, Sensor>>,
+}
+
+#[derive(ThisError, Debug, Clone)]
+pub enum UrdfExportError {
+    #[error("the parent [{1}] of element [{0}] is not a frame or the workcell root")]
+    InvalidParent(u32, u32),
+    #[error("joint [{0}] is attached to more than one frame")]
+    MultipleJointChildren(u32),
+    #[error("the geometry of element [{0}] cannot be represented in urdf")]
+    UnsupportedGeometry(u32),
+    #[error("more than one link or joint is named [{0}]")]
+    DuplicateName(String),
 }
 
 impl Workcell {
@@ -300,6 +463,143 @@ impl Workcell {
     pub fn from_bytes<'a>(s: &'a [u8]) -> serde_json::Result<Self> {
         serde_json::from_slice(s)
     }
+
+    /// Make a new workcell out of a frame and everything attached beneath it.
+    /// The frame is attached to the root of the new workcell, and mesh
+    /// constraints on models outside of the subtree are dropped. Returns None
+    /// if the id does not refer to a frame.
+    pub fn subtree(&self, frame: u32) -> Option<Workcell> {
+        let root_frame = self.frames.get(&frame)?;
+        let mut parents: HashMap<u32, u32> = HashMap::new();
+        parents.extend(self.frames.iter().map(|(id, f)| (*id, f.parent)));
+        parents.extend(self.joints.iter().map(|(id, j)| (*id, j.parent)));
+        parents.extend(self.visuals.iter().map(|(id, v)| (*id, v.parent)));
+        parents.extend(self.collisions.iter().map(|(id, c)| (*id, c.parent)));
+        parents.extend(self.sensors.iter().map(|(id, s)| (*id, s.parent)));
+        let in_subtree = |mut id: u32| loop {
+            if id == frame {
+                return true;
+            }
+            match parents.get(&id) {
+                Some(parent) if *parent != id => id = *parent,
+                _ => return false,
+            }
+        };
+
+        let name = root_frame
+            .bundle
+            .name
+            .as_ref()
+            .map(|name| name.0.clone())
+            .unwrap_or_else(|| self.properties.name.clone());
+        let mut subtree = Workcell {
+            properties: WorkcellProperties { name },
+            id: self.id,
+            ..Default::default()
+        };
+        for (id, f) in &self.frames {
+            if !in_subtree(*id) {
+                continue;
+            }
+            let mut f = f.clone();
+            if *id == frame {
+                f.parent = self.id;
+            }
+            if let Some(c) = &f.bundle.mesh_constraint {
+                if !in_subtree(c.entity) {
+                    f.bundle.mesh_constraint = None;
+                }
+            }
+            subtree.frames.insert(*id, f);
+        }
+        subtree.joints.extend(
+            self.joints
+                .iter()
+                .filter(|(id, _)| in_subtree(**id))
+                .map(|(id, j)| (*id, j.clone())),
+        );
+        subtree.visuals.extend(
+            self.visuals
+                .iter()
+                .filter(|(id, _)| in_subtree(**id))
+                .map(|(id, v)| (*id, v.clone())),
+        );
+        subtree.collisions.extend(
+            self.collisions
+                .iter()
+                .filter(|(id, _)| in_subtree(**id))
+                .map(|(id, c)| (*id, c.clone())),
+        );
+        subtree.sensors.extend(
+            self.sensors
+                .iter()
+                .filter(|(id, _)| in_subtree(**id))
+                .map(|(id, s)| (*id, s.clone())),
+        );
+        Some(subtree)
+    }
+
+    /// Put a prefix in front of the names of every element of the workcell,
+    /// so it can be inserted next to other copies of itself
+    pub fn add_name_prefix(&mut self, prefix: &str) {
+        for f in self.frames.values_mut() {
+            if let Some(name) = &mut f.bundle.name {
+                name.0 = format!("{prefix}{}", name.0);
+            }
+        }
+        for j in self.joints.values_mut() {
+            j.bundle.name.0 = format!("{prefix}{}", j.bundle.name.0);
+        }
+        for m in self
+            .visuals
+            .values_mut()
+            .chain(self.collisions.values_mut())
+        {
+            m.bundle.name = format!("{prefix}{}", m.bundle.name);
+        }
+        for s in self.sensors.values_mut() {
+            s.bundle.name = format!("{prefix}{}", s.bundle.name);
+        }
+    }
+
+    /// The highest id that is used by the workcell root or any element
+    pub fn highest_id(&self) -> u32 {
+        std::iter::once(self.id)
+            .chain(self.frames.keys().copied())
+            .chain(self.joints.keys().copied())
+            .chain(self.visuals.keys().copied())
+            .chain(self.collisions.keys().copied())
+            .chain(self.sensors.keys().copied())
+            .max()
+            .unwrap_or(self.id)
+    }
+
+    /// Add an offset to the id of the root and every element, so the
+    /// elements can be inserted into another workcell without sharing ids
+    /// with the elements that are already there
+    pub fn offset_ids(&mut self, offset: u32) {
+        fn offset_map<T>(elements: &mut BTreeMap<u32, Parented<u32, T>>, offset: u32) {
+            *elements = std::mem::take(elements)
+                .into_iter()
+                .map(|(id, mut element)| {
+                    element.parent += offset;
+                    (id + offset, element)
+                })
+                .collect();
+        }
+
+        self.id += offset;
+        offset_map(&mut self.frames, offset);
+        offset_map(&mut self.joints, offset);
+        offset_map(&mut self.visuals, offset);
+        offset_map(&mut self.collisions, offset);
+        offset_map(&mut self.sensors, offset);
+        for f in self.frames.values_mut() {
+            if let Some(c) = &mut f.bundle.mesh_constraint {
+                c.entity += offset;
+            }
+        }
+    }
 }
 
 #[cfg_attr(
@@ -348,16 +648,7 @@ impl From<&urdf_rs::Link> for Link {
     fn from(link: &urdf_rs::Link) -> Self {
         Self {
             name: NameInWorkcell(link.name.clone()),
-            inertial: Inertial {
-                origin: Pose {
-                    trans: link.inertial.origin.xyz.0.map(|v| v as f32),
-                    rot: Rotation::EulerExtrinsicXYZ(
-                        link.inertial.origin.rpy.map(|v| Angle::Rad(v as f32)),
-                    ),
-                },
-                mass: Mass(link.inertial.mass.value as f32),
-                inertia: Inertia::default(),
-            },
+            inertial: (&link.inertial).into(),
             marker: LinkMarker,
         }
     }
@@ -369,12 +660,10 @@ impl WorkcellModel {
         name: &Option<String>,
         geometry: &urdf_rs::Geometry,
     ) -> Self {
-        let trans = pose.xyz.map(|t| t as f32);
-        let rot = Rotation::EulerExtrinsicXYZ(pose.rpy.map(|t| Angle::Rad(t as f32)));
         WorkcellModel {
             name: name.clone().unwrap_or_default(),
             geometry: geometry.into(),
-            pose: Pose { trans, rot },
+            pose: pose.into(),
         }
     }
 }
@@ -390,3 +679,919 @@ impl From<&urdf_rs::Collision> for WorkcellModel {
         WorkcellModel::from_urdf_data(&collision.origin, &collision.name, &collision.geometry)
     }
 }
+
+impl From<&urdf_rs::Pose> for Pose {
+    fn from(pose: &urdf_rs::Pose) -> Self {
+        Pose {
+            trans: pose.xyz.map(|t| t as f32),
+            rot: Rotation::EulerExtrinsicXYZ(pose.rpy.map(|t| Angle::Rad(t as f32))),
+        }
+    }
+}
+
+impl From<&Pose> for urdf_rs::Pose {
+    fn from(pose: &Pose) -> Self {
+        let rpy = match pose.rot {
+            Rotation::Yaw(yaw) => [0.0, 0.0, yaw.radians()],
+            Rotation::EulerExtrinsicXYZ([x, y, z]) => [x.radians(), y.radians(), z.radians()],
+            Rotation::Quat(quat) => {
+                let (z, y, x) = Quat::from_array(quat).to_euler(EulerRot::ZYX);
+                [x, y, z]
+            }
+        };
+        urdf_rs::Pose {
+            xyz: urdf_rs::Vec3(pose.trans.map(|t| t as f64)),
+            rpy: urdf_rs::Vec3(rpy.map(|r| r as f64)),
+        }
+    }
+}
+
+impl Geometry {
+    fn to_urdf(&self) -> Option<urdf_rs::Geometry> {
+        let geometry = match self {
+            Geometry::Primitive(MeshPrimitive::Box { size }) => urdf_rs::Geometry::Box {
+                size: urdf_rs::Vec3(size.map(|s| s as f64)),
+            },
+            Geometry::Primitive(MeshPrimitive::Cylinder { radius, length }) => {
+                urdf_rs::Geometry::Cylinder {
+                    radius: *radius as f64,
+                    length: *length as f64,
+                }
+            }
+            Geometry::Primitive(MeshPrimitive::Capsule { radius, length }) => {
+                urdf_rs::Geometry::Capsule {
+                    radius: *radius as f64,
+                    length: *length as f64,
+                }
+            }
+            Geometry::Primitive(MeshPrimitive::Sphere { radius }) => urdf_rs::Geometry::Sphere {
+                radius: *radius as f64,
+            },
+            Geometry::Primitive(MeshPrimitive::Cone { .. }) => return None,
+            Geometry::Mesh { filename, scale } => urdf_rs::Geometry::Mesh {
+                filename: filename.clone(),
+                scale: scale.map(|s| urdf_rs::Vec3(s.to_array().map(|v| v as f64))),
+            },
+        };
+        Some(geometry)
+    }
+}
+
+impl Frame {
+    /// The pose of the frame relative to its parent
+    pub fn pose(&self) -> Pose {
+        match &self.anchor {
+            Anchor::Pose3D(pose) => pose.clone(),
+            anchor => {
+                let [x, y] = *anchor.translation_for_category(Category::General);
+                Pose {
+                    trans: [x, y, 0.0],
+                    ..Default::default()
+                }
+            }
+        }
+    }
+}
+
+impl Workcell {
+    /// Convert the workcell into a urdf robot. The workcell root becomes the
+    /// base link and every frame becomes a link. A frame that is attached to a
+    /// joint is connected to the link of the joint's parent by that joint,
+    /// every other frame is connected to its parent by a fixed joint.
+    ///
+    /// If the root only holds a single frame, as it does for workcells loaded
+    /// from urdf, that frame becomes the base link instead so the robot can be
+    /// exported again without gaining an extra link. urdf does not say where
+    /// a robot is placed, so the pose of that frame is not exported.
+    pub fn to_urdf(&self) -> Result<Robot, UrdfExportError> {
+        let base_frame = {
+            let mut root_frames = self.frames.iter().filter(|(_, f)| f.parent == self.id);
+            let root_has_others = self.joints.values().any(|j| j.parent == self.id)
+                || self.visuals.values().any(|v| v.parent == self.id)
+                || self.collisions.values().any(|c| c.parent == self.id)
+                || self.sensors.values().any(|s| s.parent == self.id);
+            match (root_frames.next(), root_frames.next()) {
+                (Some((id, _)), None) if !root_has_others => Some(*id),
+                _ => None,
+            }
+        };
+
+        let link_name = |id: u32| self.urdf_link_name(id);
+
+        let mut links = BTreeMap::new();
+        let root_link = base_frame.is_none().then_some(self.id);
+        for id in root_link.into_iter().chain(self.frames.keys().copied()) {
+            let inertial = self
+                .frames
+                .get(&id)
+                .and_then(|frame| frame.bundle.inertial.as_ref())
+                .map(|inertial| inertial.into())
+                .unwrap_or_default();
+            links.insert(
+                id,
+                urdf_rs::Link {
+                    name: link_name(id),
+                    inertial,
+                    visual: Vec::new(),
+                    collision: Vec::new(),
+                },
+            );
+        }
+
+        let mut joints = Vec::new();
+        let mut joint_children = HashMap::new();
+        for (id, frame) in &self.frames {
+            if Some(*id) == base_frame {
+                continue;
+            }
+            let (name, parent, properties) = match self.joints.get(&frame.parent) {
+                Some(joint) => {
+                    if joint_children.insert(frame.parent, *id).is_some() {
+                        return Err(UrdfExportError::MultipleJointChildren(frame.parent));
+                    }
+                    let name = if joint.bundle.name.0.is_empty() {
+                        format!("joint_{}", frame.parent)
+                    } else {
+                        joint.bundle.name.0.clone()
+                    };
+                    (name, joint.parent, joint.bundle.properties.clone())
+                }
+                None => (
+                    format!("{}_joint", link_name(*id)),
+                    frame.parent,
+                    JointProperties::Fixed,
+                ),
+            };
+            if !links.contains_key(&parent) {
+                return Err(UrdfExportError::InvalidParent(*id, parent));
+            }
+
+            let (joint_type, dof) = match &properties {
+                JointProperties::Fixed => (urdf_rs::JointType::Fixed, None),
+                JointProperties::Revolute(dof) => match dof.limits {
+                    Some(_) => (urdf_rs::JointType::Revolute, Some(dof)),
+                    None => (urdf_rs::JointType::Continuous, Some(dof)),
+                },
+                JointProperties::Prismatic(dof) => (urdf_rs::JointType::Prismatic, Some(dof)),
+            };
+            let axis = dof.map(|dof| dof.axis).unwrap_or([1.0, 0.0, 0.0]);
+            let limits = dof.and_then(|dof| dof.limits).unwrap_or_default();
+            joints.push(urdf_rs::Joint {
+                name,
+                joint_type,
+                origin: (&frame.bundle.pose()).into(),
+                parent: urdf_rs::LinkName {
+                    link: link_name(parent),
+                },
+                child: urdf_rs::LinkName {
+                    link: link_name(*id),
+                },
+                axis: urdf_rs::Axis {
+                    xyz: urdf_rs::Vec3(axis.map(|a| a as f64)),
+                },
+                limit: urdf_rs::JointLimit {
+                    lower: limits.lower as f64,
+                    upper: limits.upper as f64,
+                    effort: limits.effort as f64,
+                    velocity: limits.velocity as f64,
+                },
+                calibration: None,
+                dynamics: None,
+                mimic: None,
+                safety_controller: None,
+            });
+        }
+
+        for (id, visual) in &self.visuals {
+            let link = links
+                .get_mut(&visual.parent)
+                .ok_or(UrdfExportError::InvalidParent(*id, visual.parent))?;
+            link.visual.push(urdf_rs::Visual {
+                name: Some(visual.bundle.name.clone()).filter(|name| !name.is_empty()),
+                origin: (&visual.bundle.pose).into(),
+                geometry: visual
+                    .bundle
+                    .geometry
+                    .to_urdf()
+                    .ok_or(UrdfExportError::UnsupportedGeometry(*id))?,
+                material: None,
+            });
+        }
+
+        for (id, collision) in &self.collisions {
+            let link = links
+                .get_mut(&collision.parent)
+                .ok_or(UrdfExportError::InvalidParent(*id, collision.parent))?;
+            link.collision.push(urdf_rs::Collision {
+                name: Some(collision.bundle.name.clone()).filter(|name| !name.is_empty()),
+                origin: (&collision.bundle.pose).into(),
+                geometry: collision
+                    .bundle
+                    .geometry
+                    .to_urdf()
+                    .ok_or(UrdfExportError::UnsupportedGeometry(*id))?,
+            });
+        }
+
+        // Links and joints are referred to by name in urdf, so their names
+        // need to be unique
+        let mut link_names = HashSet::new();
+        for link in links.values() {
+            if !link_names.insert(link.name.as_str()) {
+                return Err(UrdfExportError::DuplicateName(link.name.clone()));
+            }
+        }
+        let mut joint_names = HashSet::new();
+        for joint in &joints {
+            if !joint_names.insert(joint.name.as_str()) {
+                return Err(UrdfExportError::DuplicateName(joint.name.clone()));
+            }
+        }
+
+        // urdf has no element for sensors so they are only exported by to_sdf,
+        // but they still need to be attached to a link
+        for (id, sensor) in &self.sensors {
+            if !links.contains_key(&sensor.parent) {
+                return Err(UrdfExportError::InvalidParent(*id, sensor.parent));
+            }
+        }
+
+        Ok(Robot {
+            name: self.properties.name.clone(),
+            links: links.into_values().collect(),
+            joints,
+            materials: Vec::new(),
+        })
+    }
+
+    /// The name of the urdf link that an element with this id becomes
+    fn urdf_link_name(&self, id: u32) -> String {
+        if id == self.id {
+            return if self.properties.name.is_empty() {
+                "base_link".to_owned()
+            } else {
+                self.properties.name.clone()
+            };
+        }
+        self.frames
+            .get(&id)
+            .and_then(|frame| frame.bundle.name.as_ref())
+            .filter(|name| !name.0.is_empty())
+            .map(|name| name.0.clone())
+            .unwrap_or_else(|| format!("frame_{id}"))
+    }
+
+    /// Convert the workcell into an SDF model. The links, joints and geometry
+    /// are the same as those of to_urdf, and each sensor is placed inside of
+    /// the link that it is attached to.
+    pub fn to_sdf(&self) -> Result<String, UrdfExportError> {
+        let robot = self.to_urdf()?;
+        let mut link_sensors: HashMap<String, Vec<&Sensor>> = HashMap::new();
+        for sensor in self.sensors.values() {
+            link_sensors
+                .entry(self.urdf_link_name(sensor.parent))
+                .or_default()
+                .push(&sensor.bundle);
+        }
+        // SDF links are placed relative to the link of the joint that they
+        // are the child of
+        let parent_joints: HashMap<&str, &urdf_rs::Joint> = robot
+            .joints
+            .iter()
+            .map(|joint| (joint.child.link.as_str(), joint))
+            .collect();
+
+        let mut sdf = format!(
+            "<?xml version=\"1.0\"?>\n<sdf version=\"1.8\">\n<model name=\"{}\">\n",
+            escape_xml(&robot.name),
+        );
+        for link in &robot.links {
+            sdf += &format!("<link name=\"{}\">\n", escape_xml(&link.name));
+            if let Some(joint) = parent_joints.get(link.name.as_str()) {
+                sdf += &format!(
+                    "  <pose relative_to=\"{}\">{}</pose>\n",
+                    escape_xml(&joint.parent.link),
+                    sdf_pose(&joint.origin),
+                );
+            }
+            let inertial = &link.inertial;
+            if inertial.mass.value > 0.0 {
+                let i = &inertial.inertia;
+                sdf += &format!(
+                    "  <inertial>\n    <pose>{}</pose>\n    <mass>{}</mass>\n    <inertia>\n      \
+                    <ixx>{}</ixx>\n      <ixy>{}</ixy>\n      <ixz>{}</ixz>\n      \
+                    <iyy>{}</iyy>\n      <iyz>{}</iyz>\n      <izz>{}</izz>\n    \
+                    </inertia>\n  </inertial>\n",
+                    sdf_pose(&inertial.origin),
+                    inertial.mass.value,
+                    i.ixx,
+                    i.ixy,
+                    i.ixz,
+                    i.iyy,
+                    i.iyz,
+                    i.izz,
+                );
+            }
+            for (i, visual) in link.visual.iter().enumerate() {
+                let name = visual.name.clone().unwrap_or_else(|| format!("visual_{i}"));
+                sdf += &format!(
+                    "  <visual name=\"{}\">\n    <pose>{}</pose>\n{}  </visual>\n",
+                    escape_xml(&name),
+                    sdf_pose(&visual.origin),
+                    sdf_geometry(&visual.geometry),
+                );
+            }
+            for (i, collision) in link.collision.iter().enumerate() {
+                let name = collision
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("collision_{i}"));
+                sdf += &format!(
+                    "  <collision name=\"{}\">\n    <pose>{}</pose>\n{}  </collision>\n",
+                    escape_xml(&name),
+                    sdf_pose(&collision.origin),
+                    sdf_geometry(&collision.geometry),
+                );
+            }
+            for sensor in link_sensors.get(&link.name).into_iter().flatten() {
+                sdf += &sensor.to_sdf();
+            }
+            sdf += "</link>\n";
+        }
+
+        for joint in &robot.joints {
+            let joint_type = match joint.joint_type {
+                urdf_rs::JointType::Revolute => "revolute",
+                urdf_rs::JointType::Continuous => "continuous",
+                urdf_rs::JointType::Prismatic => "prismatic",
+                _ => "fixed",
+            };
+            sdf += &format!(
+                "<joint name=\"{}\" type=\"{joint_type}\">\n  <parent>{}</parent>\n  \
+                <child>{}</child>\n",
+                escape_xml(&joint.name),
+                escape_xml(&joint.parent.link),
+                escape_xml(&joint.child.link),
+            );
+            if joint_type != "fixed" {
+                let [x, y, z] = joint.axis.xyz.0;
+                sdf += &format!("  <axis>\n    <xyz>{x} {y} {z}</xyz>\n");
+                if joint_type != "continuous" {
+                    let limit = &joint.limit;
+                    sdf += &format!(
+                        "    <limit>\n      <lower>{}</lower>\n      <upper>{}</upper>\n      \
+                        <effort>{}</effort>\n      <velocity>{}</velocity>\n    </limit>\n",
+                        limit.lower, limit.upper, limit.effort, limit.velocity,
+                    );
+                }
+                sdf += "  </axis>\n";
+            }
+            sdf += "</joint>\n";
+        }
+        sdf += "</model>\n</sdf>\n";
+        Ok(sdf)
+    }
+
+    /// Create a workcell from a urdf robot. Every link becomes a frame, and
+    /// every joint is placed between the frame of its parent link and the
+    /// frame of its child link, keeping its name, origin, axis and limits so
+    /// the robot can be exported again. Joint types that workcells do not
+    /// support, such as floating and planar joints, are loaded as fixed.
+    pub fn from_urdf(robot: &Robot) -> Self {
+        let mut workcell = Workcell {
+            properties: WorkcellProperties {
+                name: robot.name.clone(),
+            },
+            id: 0,
+            ..Default::default()
+        };
+        let mut next_id = 1;
+        let mut link_ids = HashMap::new();
+        for link in &robot.links {
+            link_ids.insert(link.name.as_str(), next_id);
+            next_id += 1;
+        }
+
+        // The element that each link is attached to and the pose of the link
+        // relative to it
+        let mut link_parents = HashMap::new();
+        for joint in &robot.joints {
+            let Some(parent) = link_ids.get(joint.parent.link.as_str()).copied() else {
+                continue;
+            };
+            let dof = SingleDofJoint {
+                axis: joint.axis.xyz.map(|a| a as f32),
+                limits: Some(JointLimits {
+                    lower: joint.limit.lower as f32,
+                    upper: joint.limit.upper as f32,
+                    effort: joint.limit.effort as f32,
+                    velocity: joint.limit.velocity as f32,
+                }),
+            };
+            let properties = match joint.joint_type {
+                urdf_rs::JointType::Revolute => JointProperties::Revolute(dof),
+                urdf_rs::JointType::Continuous => JointProperties::Revolute(SingleDofJoint {
+                    limits: None,
+                    ..dof
+                }),
+                urdf_rs::JointType::Prismatic => JointProperties::Prismatic(dof),
+                _ => JointProperties::Fixed,
+            };
+            let origin: Pose = (&joint.origin).into();
+            workcell.joints.insert(
+                next_id,
+                Parented {
+                    parent,
+                    bundle: Joint {
+                        name: NameInWorkcell(joint.name.clone()),
+                        properties,
+                    },
+                },
+            );
+            link_parents.insert(joint.child.link.as_str(), (next_id, origin));
+            next_id += 1;
+        }
+
+        for link in &robot.links {
+            let id = link_ids[link.name.as_str()];
+            let (parent, pose) = link_parents
+                .get(link.name.as_str())
+                .cloned()
+                .unwrap_or((workcell.id, Pose::default()));
+            workcell.frames.insert(
+                id,
+                Parented {
+                    parent,
+                    bundle: Frame {
+                        anchor: Anchor::Pose3D(pose),
+                        name: Some(NameInWorkcell(link.name.clone())),
+                        mesh_constraint: None,
+                        inertial: Some(Inertial::from(&link.inertial)).filter(|inertial| {
+                            inertial.mass.0 != 0.0 || inertial.inertia != Inertia::default()
+                        }),
+                        marker: FrameMarker,
+                    },
+                },
+            );
+            for visual in &link.visual {
+                workcell.visuals.insert(
+                    next_id,
+                    Parented {
+                        parent: id,
+                        bundle: visual.into(),
+                    },
+                );
+                next_id += 1;
+            }
+            for collision in &link.collision {
+                workcell.collisions.insert(
+                    next_id,
+                    Parented {
+                        parent: id,
+                        bundle: collision.into(),
+                    },
+                );
+                next_id += 1;
+            }
+        }
+
+        workcell
+    }
+}
+
+fn sdf_pose(pose: &urdf_rs::Pose) -> String {
+    let [x, y, z] = pose.xyz.0;
+    let [roll, pitch, yaw] = pose.rpy.0;
+    format!("{x} {y} {z} {roll} {pitch} {yaw}")
+}
+
+fn sdf_geometry(geometry: &urdf_rs::Geometry) -> String {
+    let shape = match geometry {
+        urdf_rs::Geometry::Box { size } => {
+            let [x, y, z] = size.0;
+            format!("<box>\n        <size>{x} {y} {z}</size>\n      </box>")
+        }
+        urdf_rs::Geometry::Cylinder { radius, length } => format!(
+            "<cylinder>\n        <radius>{radius}</radius>\n        \
+            <length>{length}</length>\n      </cylinder>"
+        ),
+        urdf_rs::Geometry::Capsule { radius, length } => format!(
+            "<capsule>\n        <radius>{radius}</radius>\n        \
+            <length>{length}</length>\n      </capsule>"
+        ),
+        urdf_rs::Geometry::Sphere { radius } => {
+            format!("<sphere>\n        <radius>{radius}</radius>\n      </sphere>")
+        }
+        urdf_rs::Geometry::Mesh { filename, scale } => {
+            let [x, y, z] = scale.as_ref().map(|s| s.0).unwrap_or([1.0; 3]);
+            format!(
+                "<mesh>\n        <uri>{}</uri>\n        \
+                <scale>{x} {y} {z}</scale>\n      </mesh>",
+                escape_xml(filename),
+            )
+        }
+    };
+    format!("    <geometry>\n      {shape}\n    </geometry>\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(name: &str, trans: [f32; 3]) -> Frame {
+        Frame {
+            anchor: Anchor::Pose3D(Pose {
+                trans,
+                ..Default::default()
+            }),
+            name: Some(NameInWorkcell(name.to_owned())),
+            mesh_constraint: None,
+            inertial: None,
+            marker: FrameMarker,
+        }
+    }
+
+    /// A base frame with an arm attached to it by a revolute joint, and a
+    /// gripper attached to the arm by a prismatic joint
+    fn make_arm() -> Workcell {
+        let mut workcell = Workcell {
+            properties: WorkcellProperties {
+                name: "arm".to_owned(),
+            },
+            id: 0,
+            ..Default::default()
+        };
+        let parented = |parent, bundle| Parented { parent, bundle };
+        workcell
+            .frames
+            .insert(1, parented(0, frame("base", [1.0, 0.0, 0.0])));
+        workcell.joints.insert(
+            2,
+            parented(
+                1,
+                Joint {
+                    name: NameInWorkcell("shoulder".to_owned()),
+                    properties: JointProperties::Revolute(SingleDofJoint {
+                        axis: [0.0, 1.0, 0.0],
+                        limits: Some(JointLimits {
+                            lower: -1.5,
+                            upper: 1.5,
+                            effort: 10.0,
+                            velocity: 2.0,
+                        }),
+                    }),
+                },
+            ),
+        );
+        workcell
+            .frames
+            .insert(3, parented(2, frame("upper_arm", [0.0, 0.0, 0.5])));
+        workcell.joints.insert(
+            4,
+            parented(
+                3,
+                Joint {
+                    name: NameInWorkcell("slide".to_owned()),
+                    properties: JointProperties::Prismatic(SingleDofJoint::default()),
+                },
+            ),
+        );
+        workcell
+            .frames
+            .insert(5, parented(4, frame("gripper", [0.0, 0.0, 0.3])));
+        workcell.visuals.insert(
+            6,
+            parented(
+                3,
+                WorkcellModel {
+                    name: "upper_arm_visual".to_owned(),
+                    geometry: Geometry::Primitive(MeshPrimitive::Box {
+                        size: [0.1, 0.1, 0.5],
+                    }),
+                    pose: Pose::default(),
+                },
+            ),
+        );
+        workcell
+    }
+
+    #[test]
+    fn joints_round_trip_through_json() {
+        let workcell = make_arm();
+        let loaded = Workcell::from_str(&workcell.to_string().unwrap()).unwrap();
+        assert_eq!(loaded.joints.len(), 2);
+        for (id, joint) in &workcell.joints {
+            let loaded_joint = loaded.joints.get(id).unwrap();
+            assert_eq!(loaded_joint.parent, joint.parent);
+            assert_eq!(loaded_joint.bundle.name, joint.bundle.name);
+            assert_eq!(loaded_joint.bundle.properties, joint.bundle.properties);
+        }
+        for (id, frame) in &workcell.frames {
+            let loaded_frame = loaded.frames.get(id).unwrap();
+            assert_eq!(loaded_frame.parent, frame.parent);
+            assert_eq!(loaded_frame.bundle.pose(), frame.bundle.pose());
+        }
+
+        // Workcells saved before joints were added can still be loaded
+        let mut value: serde_json::Value =
+            serde_json::from_str(&workcell.to_string().unwrap()).unwrap();
+        value.as_object_mut().unwrap().remove("joints");
+        let loaded = Workcell::from_str(&value.to_string()).unwrap();
+        assert!(loaded.joints.is_empty());
+    }
+
+    #[test]
+    fn joints_export_to_urdf() {
+        // With a second frame on the root, the root becomes a link of its own
+        let mut workcell = make_arm();
+        workcell.frames.insert(
+            7,
+            Parented {
+                parent: 0,
+                bundle: frame("table", [0.0, 2.0, 0.0]),
+            },
+        );
+        let robot = workcell.to_urdf().unwrap();
+        let link_names: Vec<_> = robot.links.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(link_names, ["arm", "base", "upper_arm", "gripper", "table"]);
+
+        let joint = |name: &str| robot.joints.iter().find(|j| j.name == name).unwrap();
+        let base = joint("base_joint");
+        assert!(matches!(base.joint_type, urdf_rs::JointType::Fixed));
+        assert_eq!(base.parent.link, "arm");
+        assert_eq!(base.child.link, "base");
+
+        let shoulder = joint("shoulder");
+        assert!(matches!(shoulder.joint_type, urdf_rs::JointType::Revolute));
+        assert_eq!(shoulder.parent.link, "base");
+        assert_eq!(shoulder.child.link, "upper_arm");
+        assert_eq!(shoulder.axis.xyz.0, [0.0, 1.0, 0.0]);
+        assert_eq!(shoulder.limit.lower, -1.5);
+        assert_eq!(shoulder.limit.upper, 1.5);
+        assert_eq!(shoulder.origin.xyz.0, [0.0, 0.0, 0.5]);
+
+        let slide = joint("slide");
+        assert!(matches!(slide.joint_type, urdf_rs::JointType::Prismatic));
+        assert_eq!(slide.parent.link, "upper_arm");
+        assert_eq!(slide.child.link, "gripper");
+
+        let upper_arm = robot.links.iter().find(|l| l.name == "upper_arm").unwrap();
+        assert_eq!(upper_arm.visual.len(), 1);
+    }
+
+    #[test]
+    fn subtree_keeps_elements_beneath_frame() {
+        let mut workcell = make_arm();
+        workcell.frames.get_mut(&5).unwrap().bundle.mesh_constraint = Some(MeshConstraint {
+            entity: 6,
+            element: MeshElement::Vertex(0),
+            relative_pose: Pose::default(),
+        });
+
+        let subtree = workcell.subtree(3).unwrap();
+        assert_eq!(subtree.id, workcell.id);
+        assert_eq!(subtree.properties.name, "upper_arm");
+        assert_eq!(subtree.frames.keys().copied().collect::<Vec<_>>(), [3, 5]);
+        assert_eq!(subtree.joints.keys().copied().collect::<Vec<_>>(), [4]);
+        assert_eq!(subtree.visuals.keys().copied().collect::<Vec<_>>(), [6]);
+        // The frame becomes attached to the root of the subtree
+        assert_eq!(subtree.frames[&3].parent, subtree.id);
+        assert_eq!(subtree.frames[&5].parent, 4);
+        // Constraints on models inside of the subtree are kept
+        assert!(subtree.frames[&5].bundle.mesh_constraint.is_some());
+
+        // Constraints on models outside of the subtree are dropped
+        let subtree = workcell.subtree(5).unwrap();
+        assert_eq!(subtree.frames.keys().copied().collect::<Vec<_>>(), [5]);
+        assert!(subtree.frames[&5].bundle.mesh_constraint.is_none());
+        assert!(subtree.joints.is_empty());
+        assert!(subtree.visuals.is_empty());
+
+        // Only frames can be the top of a subtree
+        assert!(workcell.subtree(2).is_none());
+        assert!(workcell.subtree(100).is_none());
+    }
+
+    #[test]
+    fn name_prefix_applies_to_every_element() {
+        let mut workcell = make_arm();
+        workcell.frames.get_mut(&1).unwrap().bundle.name = None;
+        workcell.add_name_prefix("left_");
+        assert!(workcell.frames[&1].bundle.name.is_none());
+        assert_eq!(
+            workcell.frames[&3].bundle.name,
+            Some(NameInWorkcell("left_upper_arm".to_owned()))
+        );
+        assert_eq!(workcell.joints[&2].bundle.name.0, "left_shoulder");
+        assert_eq!(workcell.visuals[&6].bundle.name, "left_upper_arm_visual");
+        // The workcell itself keeps its name
+        assert_eq!(workcell.properties.name, "arm");
+    }
+
+    #[test]
+    fn offset_ids_keeps_hierarchy() {
+        let mut workcell = make_arm();
+        workcell.frames.get_mut(&5).unwrap().bundle.mesh_constraint = Some(MeshConstraint {
+            entity: 6,
+            element: MeshElement::Vertex(0),
+            relative_pose: Pose::default(),
+        });
+        assert_eq!(workcell.highest_id(), 6);
+
+        workcell.offset_ids(10);
+        assert_eq!(workcell.id, 10);
+        assert_eq!(workcell.highest_id(), 16);
+        assert_eq!(
+            workcell.frames.keys().copied().collect::<Vec<_>>(),
+            [11, 13, 15]
+        );
+        assert_eq!(workcell.frames[&11].parent, 10);
+        assert_eq!(workcell.joints[&12].parent, 11);
+        assert_eq!(workcell.frames[&13].parent, 12);
+        assert_eq!(workcell.visuals[&16].parent, 13);
+        assert_eq!(
+            workcell.frames[&15]
+                .bundle
+                .mesh_constraint
+                .as_ref()
+                .map(|c| c.entity),
+            Some(16)
+        );
+        // Exporting still works because every parent was offset too
+        assert!(workcell.to_urdf().is_ok());
+    }
+
+    const ROBOT: &str = r#"
+<robot name="test_robot">
+  <link name="base_link">
+    <visual>
+      <geometry><box size="1 1 0.2"/></geometry>
+    </visual>
+  </link>
+  <link name="arm_link">
+    <inertial>
+      <mass value="2.0"/>
+      <inertia ixx="0.1" ixy="0" ixz="0" iyy="0.1" iyz="0" izz="0.1"/>
+    </inertial>
+    <collision>
+      <origin xyz="0 0 0.25" rpy="0 0 0"/>
+      <geometry><cylinder radius="0.05" length="0.5"/></geometry>
+    </collision>
+  </link>
+  <link name="wheel_link"/>
+  <joint name="arm_joint" type="revolute">
+    <origin xyz="0 0 0.125" rpy="0 0 0"/>
+    <parent link="base_link"/>
+    <child link="arm_link"/>
+    <axis xyz="0 1 0"/>
+    <limit lower="-1" upper="1" effort="5" velocity="0.5"/>
+  </joint>
+  <joint name="wheel_joint" type="continuous">
+    <origin xyz="0.5 0 0" rpy="0 0 0"/>
+    <parent link="base_link"/>
+    <child link="wheel_link"/>
+    <axis xyz="1 0 0"/>
+  </joint>
+</robot>
+"#;
+
+    #[test]
+    fn urdf_round_trip() {
+        let robot = urdf_rs::read_from_string(ROBOT).unwrap();
+        let mut workcell = Workcell::from_urdf(&robot);
+        // Placing the robot in a workcell moves its base frame and ids
+        let (_, base) = workcell
+            .frames
+            .iter_mut()
+            .find(|(_, f)| f.parent == 0)
+            .unwrap();
+        base.bundle.anchor = Anchor::Pose3D(Pose {
+            trans: [3.0, 1.0, 0.0],
+            ..Default::default()
+        });
+        workcell.offset_ids(20);
+
+        let exported = workcell.to_urdf().unwrap();
+        assert_eq!(exported.name, robot.name);
+        let names = |links: &Vec<urdf_rs::Link>| {
+            let mut names: Vec<_> = links.iter().map(|l| l.name.clone()).collect();
+            names.sort();
+            names
+        };
+        // The base frame is exported as the base link without adding a link
+        // for the workcell root
+        assert_eq!(names(&exported.links), names(&robot.links));
+
+        for original in &robot.links {
+            let link = exported
+                .links
+                .iter()
+                .find(|l| l.name == original.name)
+                .unwrap();
+            assert_eq!(link.visual.len(), original.visual.len());
+            assert_eq!(link.collision.len(), original.collision.len());
+            assert_eq!(link.inertial.mass.value, original.inertial.mass.value);
+        }
+        let arm = exported
+            .links
+            .iter()
+            .find(|l| l.name == "arm_link")
+            .unwrap();
+        assert_eq!(arm.collision[0].origin.xyz.0, [0.0, 0.0, 0.25]);
+
+        assert_eq!(exported.joints.len(), robot.joints.len());
+        for original in &robot.joints {
+            let joint = exported
+                .joints
+                .iter()
+                .find(|j| j.name == original.name)
+                .unwrap();
+            assert_eq!(
+                format!("{:?}", joint.joint_type),
+                format!("{:?}", original.joint_type)
+            );
+            assert_eq!(joint.parent.link, original.parent.link);
+            assert_eq!(joint.child.link, original.child.link);
+            assert_eq!(joint.origin.xyz.0, original.origin.xyz.0);
+            assert_eq!(joint.axis.xyz.0, original.axis.xyz.0);
+        }
+        let arm_joint = exported
+            .joints
+            .iter()
+            .find(|j| j.name == "arm_joint")
+            .unwrap();
+        assert_eq!(arm_joint.limit.lower, -1.0);
+        assert_eq!(arm_joint.limit.upper, 1.0);
+    }
+
+    #[test]
+    fn joint_with_two_children_cannot_be_exported() {
+        let mut workcell = make_arm();
+        workcell.frames.insert(
+            7,
+            Parented {
+                parent: 2,
+                bundle: frame("forearm", [0.0, 0.0, 0.2]),
+            },
+        );
+        assert!(matches!(
+            workcell.to_urdf(),
+            Err(UrdfExportError::MultipleJointChildren(2))
+        ));
+    }
+
+    #[test]
+    fn sensors_are_placed_inside_their_sdf_link() {
+        let mut workcell = make_arm();
+        workcell.sensors.insert(
+            7,
+            Parented {
+                parent: 3,
+                bundle: Sensor {
+                    name: "cam & co".to_owned(),
+                    ..Default::default()
+                },
+            },
+        );
+        let sdf = workcell.to_sdf().unwrap();
+        assert!(sdf.starts_with("<?xml"));
+        assert!(sdf.contains("<model name=\"arm\">"));
+
+        let link_start = sdf.find("<link name=\"upper_arm\">").unwrap();
+        let link_end = link_start + sdf[link_start..].find("</link>").unwrap();
+        let link = &sdf[link_start..link_end];
+        assert!(link.contains("<pose relative_to=\"base\">0 0 0.5 0 0 0</pose>"));
+        assert!(link.contains("<sensor name=\"cam &amp; co\" type=\"camera\">"));
+        assert!(link.contains("<size>0.10000000149011612 0.10000000149011612 0.5</size>"));
+        assert_eq!(sdf.matches("<sensor ").count(), 1);
+    }
+
+    #[test]
+    fn joints_export_to_sdf() {
+        let sdf = make_arm().to_sdf().unwrap();
+        let joint_start = sdf
+            .find("<joint name=\"shoulder\" type=\"revolute\">")
+            .unwrap();
+        let joint_end = joint_start + sdf[joint_start..].find("</joint>").unwrap();
+        let joint = &sdf[joint_start..joint_end];
+        assert!(joint.contains("<parent>base</parent>"));
+        assert!(joint.contains("<child>upper_arm</child>"));
+        assert!(joint.contains("<xyz>0 1 0</xyz>"));
+        assert!(joint.contains("<lower>-1.5</lower>"));
+        assert!(joint.contains("<upper>1.5</upper>"));
+        assert!(sdf.contains("<joint name=\"slide\" type=\"prismatic\">"));
+
+        let mut workcell = make_arm();
+        if let JointProperties::Revolute(dof) =
+            &mut workcell.joints.get_mut(&2).unwrap().bundle.properties
+        {
+            dof.limits = None;
+        }
+        let sdf = workcell.to_sdf().unwrap();
+        let joint_start = sdf
+            .find("<joint name=\"shoulder\" type=\"continuous\">")
+            .unwrap();
+        let joint_end = joint_start + sdf[joint_start..].find("</joint>").unwrap();
+        assert!(!sdf[joint_start..joint_end].contains("<limit>"));
+    }
+}